@@ -5,20 +5,20 @@ use ark_dh_commitments::{
     pedersen::PedersenCommitment,
     DoublyHomomorphicCommitment,
 };
-use ark_ec::PairingEngine;
+use ark_ec::pairing::Pairing;
 use ark_ff::UniformRand;
 use ark_inner_products::{
     ExtensionFieldElement, InnerProduct, MultiexponentiationInnerProduct, PairingInnerProduct,
 };
 use ark_ip_proofs::gipa::GIPA;
 
-use blake2::Blake2b;
+use blake2::Blake2b512;
 use digest::Digest;
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand::{rngs::StdRng, CryptoRng, RngCore, SeedableRng};
 
-use std::{ops::MulAssign, time::Instant};
+use std::time::Instant;
 
-fn bench_gipa<IP, LMC, RMC, IPC, D, R: Rng>(rng: &mut R, len: usize)
+fn bench_gipa<IP, LMC, RMC, IPC, D, R: RngCore + CryptoRng>(rng: &mut R, len: usize)
 where
     D: Digest,
     IP: InnerProduct<
@@ -29,12 +29,6 @@ where
     LMC: DoublyHomomorphicCommitment,
     RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    RMC::Message: MulAssign<LMC::Scalar>,
-    IPC::Message: MulAssign<LMC::Scalar>,
-    RMC::Key: MulAssign<LMC::Scalar>,
-    IPC::Key: MulAssign<LMC::Scalar>,
-    RMC::Output: MulAssign<LMC::Scalar>,
-    IPC::Output: MulAssign<LMC::Scalar>,
     IP::LeftMessage: UniformRand,
     IP::RightMessage: UniformRand,
 {
@@ -49,7 +43,7 @@ where
     let com_l = LMC::commit(&ck_l, &l).unwrap();
     let com_r = RMC::commit(&ck_r, &r).unwrap();
     let t = vec![IP::inner_product(&l, &r).unwrap()];
-    let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+    let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
     let mut start = Instant::now();
     let proof = GIPA::<IP, LMC, RMC, IPC, D>::prove(
         (&l, &r, &t[0]),
@@ -70,7 +64,7 @@ fn main() {
     const LEN: usize = 16;
     type GC1 = AFGHOCommitmentG1<Bls12_381>;
     type GC2 = AFGHOCommitmentG2<Bls12_381>;
-    type SC1 = PedersenCommitment<<Bls12_381 as PairingEngine>::G1Projective>;
+    type SC1 = PedersenCommitment<<Bls12_381 as Pairing>::G1>;
     let mut rng = StdRng::seed_from_u64(0u64);
 
     println!("Benchmarking GIPA with vector length: {}", LEN);
@@ -80,21 +74,18 @@ fn main() {
         PairingInnerProduct<Bls12_381>,
         GC1,
         GC2,
-        IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>,
-        Blake2b,
+        IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as Pairing>::ScalarField>,
+        Blake2b512,
         StdRng,
     >(&mut rng, LEN);
 
     println!("2) Multiexponentiation G1 inner product...");
     bench_gipa::<
-        MultiexponentiationInnerProduct<<Bls12_381 as PairingEngine>::G1Projective>,
+        MultiexponentiationInnerProduct<<Bls12_381 as Pairing>::G1>,
         GC1,
         SC1,
-        IdentityCommitment<
-            <Bls12_381 as PairingEngine>::G1Projective,
-            <Bls12_381 as PairingEngine>::Fr,
-        >,
-        Blake2b,
+        IdentityCommitment<<Bls12_381 as Pairing>::G1, <Bls12_381 as Pairing>::ScalarField>,
+        Blake2b512,
         StdRng,
     >(&mut rng, LEN);
 }
@@ -0,0 +1,70 @@
+use ark_bls12_381::{g1, g2, Fr};
+use ark_dh_commitments::fold_generators;
+use ark_ec::{
+    short_weierstrass::{Projective, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::UniformRand;
+use ark_inner_products::{
+    glv::{Endomorphism, GLV_MIN_POINTS},
+    msm::{BatchAffineMsm, GlvMsm, PippengerMsm},
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use std::time::Instant;
+
+fn bench_fold<P: SWCurveConfig<ScalarField = Fr>>(rng: &mut StdRng, len: usize) {
+    let k = (0..len)
+        .map(|_| Projective::<P>::rand(rng))
+        .collect::<Vec<_>>();
+    let c = Fr::rand(rng);
+
+    // First search for the endomorphism, which later folds take from the cache
+    let start = Instant::now();
+    Endomorphism::<P>::find().unwrap();
+    println!("\t find endomorphism: {} us", start.elapsed().as_micros());
+    let start = Instant::now();
+    Endomorphism::<P>::find().unwrap();
+    println!("\t cached endomorphism: {} us", start.elapsed().as_micros());
+
+    // Folding without the endomorphism
+    let start = Instant::now();
+    let plain = fold_generators::<_, PippengerMsm>(&k, &c).unwrap();
+    println!("\t plain fold: {} ms", start.elapsed().as_millis());
+
+    let start = Instant::now();
+    let glv = fold_generators::<_, GlvMsm>(&k, &c).unwrap();
+    println!("\t GLV fold: {} ms", start.elapsed().as_millis());
+
+    let start = Instant::now();
+    let glv_affine = fold_generators::<_, BatchAffineMsm>(&k, &c).unwrap();
+    println!(
+        "\t GLV batch affine fold: {} ms",
+        start.elapsed().as_millis()
+    );
+
+    assert_eq!(plain, glv);
+    assert_eq!(plain, glv_affine);
+    assert_eq!(
+        plain,
+        k[..len / 2]
+            .iter()
+            .zip(Projective::normalize_batch(&k[len / 2..]))
+            .map(|(g_1, g_2)| *g_1 + g_2 * c)
+            .collect::<Vec<_>>()
+    );
+}
+
+fn main() {
+    const LEN: usize = 1 << 12;
+    const _: () = assert!(LEN / 2 >= GLV_MIN_POINTS);
+    let mut rng = StdRng::seed_from_u64(0u64);
+    println!("Benchmarking GLV key folding with key length: {}", LEN);
+
+    println!("1) G1 key folding...");
+    bench_fold::<g1::Config>(&mut rng, LEN);
+
+    println!("2) G2 key folding...");
+    bench_fold::<g2::Config>(&mut rng, LEN);
+}
@@ -1,7 +1,4 @@
-use ark_bls12_377::{
-    constraints::PairingVar as BLS12PairingVar, Bls12_377, Fr as BLS12Fr,
-    FrParameters as BLS12FrParameters,
-};
+use ark_bls12_377::{constraints::PairingVar as BLS12PairingVar, Bls12_377, Fr as BLS12Fr};
 use ark_bw6_761::{Fr as BW6Fr, BW6_761};
 use ark_crypto_primitives::{
     prf::{
@@ -11,10 +8,11 @@ use ark_crypto_primitives::{
     },
     snark::*,
 };
-use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
-use ark_ff::{
-    biginteger::BigInteger, FftParameters, One, PrimeField, ToConstraintField, UniformRand,
+use ark_ec::{
+    pairing::{MillerLoopOutput, Pairing},
+    AffineRepr, CurveGroup,
 };
+use ark_ff::{BigInteger, One, PrimeField, ToConstraintField, UniformRand};
 use ark_groth16::{constraints::*, Groth16, PreparedVerifyingKey, Proof, VerifyingKey};
 use ark_r1cs_std::prelude::*;
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
@@ -23,7 +21,7 @@ use ark_ip_proofs::applications::groth16_aggregation::{
     aggregate_proofs, setup_inner_product, verify_aggregate_proof,
 };
 
-use blake2::Blake2b;
+use blake2::Blake2b512;
 use csv::Writer;
 use rand::{rngs::StdRng, SeedableRng};
 
@@ -41,7 +39,7 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for SingleBlake2SCircuit {
         let input = UInt8::new_witness_vec(cs.clone(), &self.input)?;
         let hash = <Blake2sGadget as PRFGadget<_, F>>::OutputVar::new_variable(
             cs.clone(),
-            || Ok(self.output.clone()),
+            || Ok(self.output),
             AllocationMode::Input,
         )?;
         hash.enforce_equal(&<Blake2sGadget as PRFGadget<_, F>>::evaluate(
@@ -65,7 +63,7 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for ManyBlake2SCircuit {
             let input = UInt8::new_witness_vec(cs.clone(), hash_input)?;
             let hash = <Blake2sGadget as PRFGadget<_, F>>::OutputVar::new_variable(
                 cs.clone(),
-                || Ok(hash_output.clone()),
+                || Ok(*hash_output),
                 AllocationMode::Input,
             )?;
             hash.enforce_equal(&<Blake2sGadget as PRFGadget<_, F>>::evaluate(
@@ -97,7 +95,7 @@ impl ConstraintSynthesizer<BW6Fr> for AggregateBlake2SCircuitVerificationCircuit
                     .iter()
                     .map(|bls_fr| {
                         bls_fr
-                            .into_repr()
+                            .into_bigint()
                             .as_ref()
                             .iter()
                             .map(|bls_fr_int| bls_fr_int.to_le_bytes().to_vec())
@@ -117,7 +115,7 @@ impl ConstraintSynthesizer<BW6Fr> for AggregateBlake2SCircuitVerificationCircuit
             .iter()
             .map(|h_as_bls_fr_bytes| {
                 let bls_field_element_size_in_bytes =
-                    <BLS12FrParameters as FftParameters>::BigInt::NUM_LIMBS * 8;
+                    <BLS12Fr as PrimeField>::BigInt::NUM_LIMBS * 8;
                 h_as_bls_fr_bytes
                     .chunks(bls_field_element_size_in_bytes)
                     .map(|bls_field_element_chunk| bls_field_element_chunk.to_vec())
@@ -182,7 +180,7 @@ impl ToConstraintField<BW6Fr> for AggregateBlake2SCircuitVerificationCircuitInpu
                     .iter()
                     .map(|bls_fr| {
                         bls_fr
-                            .into_repr()
+                            .into_bigint()
                             .as_ref()
                             .iter()
                             .map(|bls_fr_int| bls_fr_int.to_le_bytes().to_vec())
@@ -228,10 +226,12 @@ fn main() {
             _ => panic!("<monolithic_proof> should be true/false"),
         };
         (
-            String::from(args[1].clone())
+            args[1]
+                .clone()
                 .parse()
                 .expect("<num_trials> should be integer"),
-            String::from(args[2].clone())
+            args[2]
+                .clone()
                 .parse()
                 .expect("<num_proofs> should be integer"),
             bench_recursion,
@@ -243,7 +243,7 @@ fn main() {
 
     let mut csv_writer = Writer::from_writer(stdout());
     csv_writer
-        .write_record(&["trial", "num_proofs", "scheme", "function", "time"])
+        .write_record(["trial", "num_proofs", "scheme", "function", "time"])
         .unwrap();
     csv_writer.flush().unwrap();
     let mut start;
@@ -285,8 +285,8 @@ fn main() {
                 Groth16::<Bls12_377>::prove(
                     &hash_circuit_parameters.0,
                     SingleBlake2SCircuit {
-                        input: hash_inputs[i].clone(),
-                        output: hash_outputs[i].clone(),
+                        input: hash_inputs[i],
+                        output: hash_outputs[i],
                     },
                     &mut rng,
                 )
@@ -322,7 +322,7 @@ fn main() {
                 &hash_outputs
                     .iter()
                     .map(|h| h.to_field_elements())
-                    .collect::<Option<Vec<Vec<<Bls12_377 as PairingEngine>::Fr>>>>()
+                    .collect::<Option<Vec<Vec<<Bls12_377 as Pairing>::ScalarField>>>>()
                     .unwrap(),
                 &proofs,
             )
@@ -344,7 +344,8 @@ fn main() {
         // Benchmark aggregation via IPA
         {
             start = Instant::now();
-            let srs = setup_inner_product::<Bls12_377, Blake2b, _>(&mut rng, num_proofs).unwrap();
+            let srs =
+                setup_inner_product::<Bls12_377, Blake2b512, _>(&mut rng, num_proofs).unwrap();
             time = start.elapsed().as_millis();
             csv_writer
                 .write_record(&[
@@ -360,8 +361,12 @@ fn main() {
 
             for i in 1..=num_trials {
                 start = Instant::now();
-                let aggregate_proof =
-                    aggregate_proofs::<Bls12_377, Blake2b>(&srs, &proofs).unwrap();
+                let aggregate_proof = aggregate_proofs::<Bls12_377, Blake2b512>(
+                    &srs,
+                    &hash_circuit_parameters.0.vk,
+                    &proofs,
+                )
+                .unwrap();
                 time = start.elapsed().as_millis();
                 csv_writer
                     .write_record(&[
@@ -381,7 +386,7 @@ fn main() {
                     &hash_outputs
                         .iter()
                         .map(|h| h.to_field_elements())
-                        .collect::<Option<Vec<Vec<<Bls12_377 as PairingEngine>::Fr>>>>()
+                        .collect::<Option<Vec<Vec<<Bls12_377 as Pairing>::ScalarField>>>>()
                         .unwrap(),
                     &aggregate_proof,
                 )
@@ -528,15 +533,15 @@ fn main() {
     }
 }
 
-pub fn batch_verify_proof<E: PairingEngine>(
+pub fn batch_verify_proof<E: Pairing>(
     pvk: &PreparedVerifyingKey<E>,
-    public_inputs: &[Vec<E::Fr>],
+    public_inputs: &[Vec<E::ScalarField>],
     proofs: &[Proof<E>],
 ) -> Result<bool, SynthesisError> {
     let mut rng = StdRng::seed_from_u64(0u64);
     let mut r_powers = Vec::with_capacity(proofs.len());
     for _ in 0..proofs.len() {
-        let challenge: E::Fr = u128::rand(&mut rng).into();
+        let challenge: E::ScalarField = u128::rand(&mut rng).into();
         r_powers.push(challenge);
     }
 
@@ -544,48 +549,47 @@ pub fn batch_verify_proof<E: PairingEngine>(
         .iter()
         .zip(&r_powers)
         .map(|(input, r)| {
-            let mut g_ic = pvk.vk.gamma_abc_g1[0].into_projective();
+            let mut g_ic = pvk.vk.gamma_abc_g1[0].into_group();
             for (i, b) in input.iter().zip(pvk.vk.gamma_abc_g1.iter().skip(1)) {
-                g_ic += &b.mul(i.into_repr());
+                g_ic += &b.mul_bigint(i.into_bigint());
             }
-            g_ic.mul(r.into_repr())
+            g_ic * r
         })
-        .sum::<E::G1Projective>()
+        .sum::<E::G1>()
         .into_affine();
 
     let combined_proof_a_s = proofs
         .iter()
         .zip(&r_powers)
-        .map(|(proof, r)| proof.a.mul(*r))
+        .map(|(proof, r)| proof.a * r)
         .collect::<Vec<_>>();
-    let combined_proof_a_s = E::G1Projective::batch_normalization_into_affine(&combined_proof_a_s);
-    let ml_inputs = proofs
-        .iter()
-        .zip(&combined_proof_a_s)
-        .map(|(proof, a)| ((*a).into(), proof.b.into()))
-        .collect::<Vec<_>>();
-    let a_r_times_b = E::miller_loop(ml_inputs.iter());
+    let combined_proof_a_s = E::G1::normalize_batch(&combined_proof_a_s);
+    let a_r_times_b = E::multi_miller_loop(combined_proof_a_s, proofs.iter().map(|proof| proof.b));
 
     let combined_c_s = proofs
         .iter()
         .zip(&r_powers)
-        .map(|(proof, r)| proof.c.mul(*r))
-        .sum::<E::G1Projective>()
+        .map(|(proof, r)| proof.c * r)
+        .sum::<E::G1>()
         .into_affine();
 
-    let sum_of_rs = (&r_powers).iter().copied().sum::<E::Fr>();
-    let combined_alpha = (-pvk.vk.alpha_g1.mul(sum_of_rs)).into_affine();
-    let qap = E::miller_loop(
+    let sum_of_rs = r_powers.iter().copied().sum::<E::ScalarField>();
+    let combined_alpha = (-(pvk.vk.alpha_g1 * sum_of_rs)).into_affine();
+    let qap = E::multi_miller_loop(
+        [
+            E::G1Prepared::from(combined_alpha),
+            combined_inputs.into(),
+            combined_c_s.into(),
+        ],
         [
-            (combined_alpha.into(), pvk.vk.beta_g2.into()),
-            (combined_inputs.into(), pvk.gamma_g2_neg_pc.clone()),
-            (combined_c_s.into(), pvk.delta_g2_neg_pc.clone()),
-        ]
-        .iter(),
+            pvk.vk.beta_g2.into(),
+            pvk.gamma_g2_neg_pc.clone(),
+            pvk.delta_g2_neg_pc.clone(),
+        ],
     );
 
-    let test =
-        E::final_exponentiation(&(qap * &a_r_times_b)).ok_or(SynthesisError::UnexpectedIdentity)?;
+    let test = E::final_exponentiation(MillerLoopOutput(qap.0 * a_r_times_b.0))
+        .ok_or(SynthesisError::UnexpectedIdentity)?;
 
-    Ok(test == E::Fqk::one())
+    Ok(test.0 == E::TargetField::one())
 }
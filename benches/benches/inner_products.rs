@@ -1,13 +1,17 @@
 use ark_bls12_381::Bls12_381;
-use ark_ec::PairingEngine;
+use ark_ec::pairing::Pairing;
+use ark_ec::CurveGroup;
 use ark_ff::UniformRand;
-use ark_inner_products::{InnerProduct, MultiexponentiationInnerProduct, PairingInnerProduct};
+use ark_inner_products::{
+    FixedBaseMultiexponentiationInnerProduct, FixedBaseTable, InnerProduct, InnerProductConfig,
+    MultiexponentiationInnerProduct, PairingInnerProduct,
+};
 
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand::{rngs::StdRng, CryptoRng, RngCore, SeedableRng};
 
 use std::time::Instant;
 
-fn bench_inner_product<IP: InnerProduct, R: Rng>(rng: &mut R, len: usize)
+fn bench_inner_product<IP: InnerProduct, R: RngCore + CryptoRng>(rng: &mut R, len: usize)
 where
     IP::LeftMessage: UniformRand,
     IP::RightMessage: UniformRand,
@@ -24,6 +28,102 @@ where
     println!("\t time: {} ms", bench);
 }
 
+// Multiexponentiations over a fixed left message, e.g. a commitment key: unprepared, over the
+// precomputed bases of `precompute_left`, and over fixed-base window tables of each base
+fn bench_prepared_msm<G: CurveGroup, R: RngCore + CryptoRng>(rng: &mut R, len: usize) {
+    type IP<G> = MultiexponentiationInnerProduct<G>;
+    let l = (0..len).map(|_| G::rand(rng)).collect::<Vec<_>>();
+    let r = (0..len)
+        .map(|_| G::ScalarField::rand(rng))
+        .collect::<Vec<_>>();
+
+    let start = Instant::now();
+    let expected = IP::<G>::inner_product(&l, &r).unwrap();
+    println!("\t unprepared: {} ms", start.elapsed().as_millis());
+
+    let start = Instant::now();
+    let prepared = IP::<G>::precompute_left(&l);
+    println!(
+        "\t precomputing bases (window {}): {} ms",
+        prepared.window(),
+        start.elapsed().as_millis()
+    );
+    let start = Instant::now();
+    assert_eq!(
+        IP::<G>::inner_product_prepared(&prepared, &r).unwrap(),
+        expected
+    );
+    println!("\t precomputed bases: {} ms", start.elapsed().as_millis());
+
+    let start = Instant::now();
+    let tables = l
+        .iter()
+        .map(|g| FixedBaseTable::new(g, 4))
+        .collect::<Vec<_>>();
+    println!("\t fixed-base tables: {} ms", start.elapsed().as_millis());
+    let start = Instant::now();
+    assert_eq!(
+        FixedBaseMultiexponentiationInnerProduct::inner_product(&tables, &r).unwrap(),
+        expected
+    );
+    println!(
+        "\t fixed-base multiplications: {} ms",
+        start.elapsed().as_millis()
+    );
+}
+
+// Average time in microseconds of `f` over enough runs to measure short inputs
+fn time_us<F: FnMut()>(mut f: F) -> f64 {
+    const RUNS: u32 = 20;
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        f();
+    }
+    start.elapsed().as_micros() as f64 / RUNS as f64
+}
+
+// Each switch of `InnerProductConfig` forced to either side, for the lengths around its default
+fn bench_strategies<P: Pairing, R: RngCore + CryptoRng>(rng: &mut R) {
+    let default = InnerProductConfig::default();
+    let config = |naive: bool, parallel: bool| InnerProductConfig {
+        naive_msm_max_len: if naive { usize::MAX } else { 0 },
+        naive_pairing_max_len: if naive { usize::MAX } else { 0 },
+        parallel_msm_min_len: if parallel { 0 } else { usize::MAX },
+        parallel_pairing_min_len: if parallel { 0 } else { usize::MAX },
+    };
+    println!("\t default: {:?}", default);
+    println!("\t length, naive MSM, Pippenger MSM, parallel MSM, separate Miller loops, multi-Miller loop, parallel multi-Miller loop (us)");
+    for log_len in 0..=9 {
+        let len = 1 << log_len;
+        let g1 = (0..len).map(|_| P::G1::rand(rng)).collect::<Vec<_>>();
+        let g2 = (0..len).map(|_| P::G2::rand(rng)).collect::<Vec<_>>();
+        let b = (0..len)
+            .map(|_| P::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let msm = |c: InnerProductConfig| {
+            time_us(|| {
+                MultiexponentiationInnerProduct::<P::G1>::inner_product_with_config(&g1, &b, &c)
+                    .unwrap();
+            })
+        };
+        let pairing = |c: InnerProductConfig| {
+            time_us(|| {
+                PairingInnerProduct::<P>::inner_product_with_config(&g1, &g2, &c).unwrap();
+            })
+        };
+        println!(
+            "\t {}, {:.0}, {:.0}, {:.0}, {:.0}, {:.0}, {:.0}",
+            len,
+            msm(config(true, false)),
+            msm(config(false, false)),
+            msm(config(false, true)),
+            pairing(config(true, false)),
+            pairing(config(false, false)),
+            pairing(config(false, true)),
+        );
+    }
+}
+
 fn main() {
     const LEN: usize = 16;
     let mut rng = StdRng::seed_from_u64(0u64);
@@ -33,14 +133,22 @@ fn main() {
     bench_inner_product::<PairingInnerProduct<Bls12_381>, StdRng>(&mut rng, LEN);
 
     println!("2) Multiexponentiation G1 inner product...");
-    bench_inner_product::<
-        MultiexponentiationInnerProduct<<Bls12_381 as PairingEngine>::G1Projective>,
-        StdRng,
-    >(&mut rng, LEN);
+    bench_inner_product::<MultiexponentiationInnerProduct<<Bls12_381 as Pairing>::G1>, StdRng>(
+        &mut rng, LEN,
+    );
 
     println!("3) Multiexponentiation G2 inner product...");
-    bench_inner_product::<
-        MultiexponentiationInnerProduct<<Bls12_381 as PairingEngine>::G2Projective>,
-        StdRng,
-    >(&mut rng, LEN);
+    bench_inner_product::<MultiexponentiationInnerProduct<<Bls12_381 as Pairing>::G2>, StdRng>(
+        &mut rng, LEN,
+    );
+
+    const PREPARED_LEN: usize = 1 << 12;
+    println!(
+        "4) Prepared multiexponentiation G1 inner product with vector length: {}",
+        PREPARED_LEN
+    );
+    bench_prepared_msm::<<Bls12_381 as Pairing>::G1, StdRng>(&mut rng, PREPARED_LEN);
+
+    println!("5) Inner product strategies over BLS12-381 by length...");
+    bench_strategies::<Bls12_381, StdRng>(&mut rng);
 }
@@ -1,18 +1,18 @@
 use ark_bls12_381::Bls12_381;
-use ark_ec::PairingEngine;
+use ark_ec::pairing::Pairing;
 use ark_ff::UniformRand;
 use ark_ip_proofs::applications::poly_commit::{
     transparent::UnivariatePolynomialCommitment as TransparentIPA,
     UnivariatePolynomialCommitment as IPA, KZG,
 };
 use ark_poly::polynomial::{
-    univariate::DensePolynomial as UnivariatePolynomial, Polynomial, UVPolynomial,
+    univariate::DensePolynomial as UnivariatePolynomial, DenseUVPolynomial, Polynomial,
 };
 
 use csv::Writer;
 use rand::{rngs::StdRng, SeedableRng};
 
-use blake2::Blake2b;
+use blake2::Blake2b512;
 use std::{
     io::stdout,
     time::{Duration, Instant},
@@ -31,10 +31,12 @@ fn main() {
         return;
     } else {
         (
-            String::from(args[1].clone())
+            args[1]
+                .clone()
                 .parse()
                 .expect("<num_trials> should be integer"),
-            String::from(args[2].clone())
+            args[2]
+                .clone()
                 .parse()
                 .expect("<num_data_points> should be integer"),
         )
@@ -42,7 +44,7 @@ fn main() {
 
     let mut csv_writer = Writer::from_writer(stdout());
     csv_writer
-        .write_record(&["trial", "scheme", "function", "degree", "time"])
+        .write_record(["trial", "scheme", "function", "degree", "time"])
         .unwrap();
     csv_writer.flush().unwrap();
     let mut start;
@@ -67,7 +69,7 @@ fn main() {
             csv_writer.flush().unwrap();
             for i in 1..num_trials + 1 {
                 let polynomial = UnivariatePolynomial::rand(degree, &mut rng);
-                let point = <Bls12_381 as PairingEngine>::Fr::rand(&mut rng);
+                let point = <Bls12_381 as Pairing>::ScalarField::rand(&mut rng);
                 let eval = polynomial.evaluate(&point);
 
                 // Commit
@@ -124,7 +126,7 @@ fn main() {
         {
             let mut rng = StdRng::seed_from_u64(0u64);
             start = Instant::now();
-            let srs = IPA::<Bls12_381, Blake2b>::setup(&mut rng, degree).unwrap();
+            let srs = IPA::<Bls12_381, Blake2b512>::setup(&mut rng, degree).unwrap();
             let v_srs = srs.0.get_verifier_key();
             time = start.elapsed().as_millis();
             csv_writer
@@ -139,13 +141,13 @@ fn main() {
             csv_writer.flush().unwrap();
             for i in 1..num_trials + 1 {
                 let polynomial = UnivariatePolynomial::rand(degree, &mut rng);
-                let point = <Bls12_381 as PairingEngine>::Fr::rand(&mut rng);
+                let point = <Bls12_381 as Pairing>::ScalarField::rand(&mut rng);
                 let eval = polynomial.evaluate(&point);
 
                 // Commit
                 start = Instant::now();
                 let (com, prover_aux) =
-                    IPA::<Bls12_381, Blake2b>::commit(&srs, &polynomial).unwrap();
+                    IPA::<Bls12_381, Blake2b512>::commit(&srs, &polynomial).unwrap();
                 time = start.elapsed().as_millis();
                 csv_writer
                     .write_record(&[
@@ -159,8 +161,9 @@ fn main() {
 
                 // Open
                 start = Instant::now();
-                let proof = IPA::<Bls12_381, Blake2b>::open(&srs, &polynomial, &prover_aux, &point)
-                    .unwrap();
+                let proof =
+                    IPA::<Bls12_381, Blake2b512>::open(&srs, &polynomial, &prover_aux, &point)
+                        .unwrap();
                 time = start.elapsed().as_millis();
                 csv_writer
                     .write_record(&[
@@ -176,7 +179,7 @@ fn main() {
                 std::thread::sleep(Duration::from_millis(5000));
                 start = Instant::now();
                 for _ in 0..50 {
-                    let is_valid = IPA::<Bls12_381, Blake2b>::verify(
+                    let is_valid = IPA::<Bls12_381, Blake2b512>::verify(
                         &v_srs, degree, &com, &point, &eval, &proof,
                     )
                     .unwrap();
@@ -199,7 +202,7 @@ fn main() {
             {
                 let mut rng = StdRng::seed_from_u64(0u64);
                 start = Instant::now();
-                let ck = TransparentIPA::<Bls12_381, Blake2b>::setup(&mut rng, degree).unwrap();
+                let ck = TransparentIPA::<Bls12_381, Blake2b512>::setup(&mut rng, degree).unwrap();
                 time = start.elapsed().as_millis();
                 csv_writer
                     .write_record(&[
@@ -213,13 +216,13 @@ fn main() {
                 csv_writer.flush().unwrap();
                 for i in 1..num_trials + 1 {
                     let polynomial = UnivariatePolynomial::rand(degree, &mut rng);
-                    let point = <Bls12_381 as PairingEngine>::Fr::rand(&mut rng);
+                    let point = <Bls12_381 as Pairing>::ScalarField::rand(&mut rng);
                     let eval = polynomial.evaluate(&point);
 
                     // Commit
                     start = Instant::now();
                     let (com, prover_aux) =
-                        TransparentIPA::<Bls12_381, Blake2b>::commit(&ck, &polynomial).unwrap();
+                        TransparentIPA::<Bls12_381, Blake2b512>::commit(&ck, &polynomial).unwrap();
                     time = start.elapsed().as_millis();
                     csv_writer
                         .write_record(&[
@@ -233,7 +236,7 @@ fn main() {
 
                     // Open
                     start = Instant::now();
-                    let proof = TransparentIPA::<Bls12_381, Blake2b>::open(
+                    let proof = TransparentIPA::<Bls12_381, Blake2b512>::open(
                         &ck,
                         &polynomial,
                         &prover_aux,
@@ -255,7 +258,7 @@ fn main() {
                     std::thread::sleep(Duration::from_millis(5000));
                     start = Instant::now();
                     for _ in 0..50 {
-                        let is_valid = TransparentIPA::<Bls12_381, Blake2b>::verify(
+                        let is_valid = TransparentIPA::<Bls12_381, Blake2b512>::verify(
                             &ck, &com, &point, &eval, &proof,
                         )
                         .unwrap();
@@ -0,0 +1,230 @@
+#![allow(clippy::upper_case_acronyms)]
+use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+use ark_crypto_primitives::snark::*;
+use ark_dh_commitments::{
+    afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+    identity::IdentityCommitment,
+    DoublyHomomorphicCommitment,
+};
+use ark_ff::UniformRand;
+use ark_groth16::Groth16;
+use ark_inner_products::{ExtensionFieldElement, InnerProduct, PairingInnerProduct};
+use ark_ip_proofs::{
+    applications::groth16_aggregation::{
+        aggregate_proofs, setup_inner_product, verify_aggregate_proof,
+    },
+    gipa::GIPA,
+    tipa::TIPA,
+};
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_serialize::CanonicalSerialize;
+
+use blake2::Blake2b512;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use csv::Writer;
+use rand::{rngs::StdRng, SeedableRng};
+
+use std::{env, fs::File, path::PathBuf};
+
+type IP = PairingInnerProduct<Bls12_381>;
+type GC1 = AFGHOCommitmentG1<Bls12_381>;
+type GC2 = AFGHOCommitmentG2<Bls12_381>;
+type IPC = IdentityCommitment<ExtensionFieldElement<Bls12_381>, Fr>;
+type PairingGIPA = GIPA<IP, GC1, GC2, IPC, Blake2b512>;
+type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b512>;
+
+// Logarithms of the vector lengths to benchmark, from RIPP_BENCH_LOG_SIZES as e.g. "8,12,16"
+fn log_sizes() -> Vec<usize> {
+    match env::var("RIPP_BENCH_LOG_SIZES") {
+        Ok(sizes) => sizes
+            .split(',')
+            .map(|s| {
+                s.trim()
+                    .parse()
+                    .expect("RIPP_BENCH_LOG_SIZES should be a comma separated list of integers")
+            })
+            .collect(),
+        Err(_) => (8..=20).step_by(4).collect(),
+    }
+}
+
+// Proof sizes in bytes, written as CSV next to the JSON estimates criterion writes for timings
+struct ProofSizes(Writer<File>);
+
+impl ProofSizes {
+    fn new() -> Self {
+        let dir = env::var("CARGO_TARGET_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../target"))
+            .join("criterion");
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut writer = Writer::from_path(dir.join("proof_sizes.csv")).unwrap();
+        writer
+            .write_record(["protocol", "log_size", "proof_bytes"])
+            .unwrap();
+        ProofSizes(writer)
+    }
+
+    fn record(&mut self, protocol: &str, log_size: usize, proof: &impl CanonicalSerialize) {
+        self.0
+            .write_record(&[
+                protocol.to_string(),
+                log_size.to_string(),
+                proof.compressed_size().to_string(),
+            ])
+            .unwrap();
+        self.0.flush().unwrap();
+    }
+}
+
+fn random_pairing_inputs(rng: &mut StdRng, len: usize) -> (Vec<G1Projective>, Vec<G2Projective>) {
+    (
+        (0..len).map(|_| G1Projective::rand(rng)).collect(),
+        (0..len).map(|_| G2Projective::rand(rng)).collect(),
+    )
+}
+
+fn bench_gipa(c: &mut Criterion, sizes: &mut ProofSizes) {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let mut group = c.benchmark_group("gipa");
+    for log_size in log_sizes() {
+        let len = 1 << log_size;
+        let (l, r) = random_pairing_inputs(&mut rng, len);
+        let (ck_l, ck_r, ck_t) = PairingGIPA::setup(&mut rng, len).unwrap();
+        let com_l = GC1::commit(&ck_l, &l).unwrap();
+        let com_r = GC2::commit(&ck_r, &r).unwrap();
+        let t = vec![IP::inner_product(&l, &r).unwrap()];
+        let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
+
+        let prove = || {
+            PairingGIPA::prove(
+                (&l, &r, &t[0]),
+                (&ck_l, &ck_r, &ck_t),
+                (&com_l, &com_r, &com_t),
+            )
+            .unwrap()
+        };
+        group.bench_function(BenchmarkId::new("prove", len), |b| b.iter(prove));
+        let proof = prove();
+        sizes.record("gipa", log_size, &proof);
+        group.bench_function(BenchmarkId::new("verify", len), |b| {
+            b.iter(|| {
+                PairingGIPA::verify((&ck_l, &ck_r, &ck_t), (&com_l, &com_r, &com_t), &proof)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_tipa(c: &mut Criterion, sizes: &mut ProofSizes) {
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let mut group = c.benchmark_group("tipa");
+    for log_size in log_sizes() {
+        let len = 1 << log_size;
+        let (l, r) = random_pairing_inputs(&mut rng, len);
+        let (srs, ck_t) = PairingTIPA::setup(&mut rng, len).unwrap();
+        let (ck_l, ck_r) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let com_l = GC1::commit(&ck_l, &l).unwrap();
+        let com_r = GC2::commit(&ck_r, &r).unwrap();
+        let t = vec![IP::inner_product(&l, &r).unwrap()];
+        let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
+
+        let prove = || PairingTIPA::prove(&srs, (&l, &r), (&ck_l, &ck_r, &ck_t)).unwrap();
+        group.bench_function(BenchmarkId::new("prove", len), |b| b.iter(prove));
+        let proof = prove();
+        sizes.record("tipa", log_size, &proof);
+        group.bench_function(BenchmarkId::new("verify", len), |b| {
+            b.iter(|| PairingTIPA::verify(&v_srs, &ck_t, (&com_l, &com_r, &com_t), &proof).unwrap())
+        });
+    }
+    group.finish();
+}
+
+#[derive(Clone)]
+struct TestCircuit {
+    public_inputs: Vec<Fr>,
+    witness_input: Fr,
+    public_sum: Fr,
+}
+
+impl ConstraintSynthesizer<Fr> for TestCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let input_variables =
+            Vec::<FpVar<Fr>>::new_input(cs.clone(), || Ok(self.public_inputs.clone()))?;
+        let sum = FpVar::new_input(cs.clone(), || Ok(&self.public_sum))?;
+        let witness = FpVar::new_witness(cs.clone(), || Ok(&self.witness_input))?;
+
+        let mut computed_sum = witness;
+        for x in &input_variables {
+            computed_sum += x;
+        }
+
+        sum.enforce_equal(&computed_sum)?;
+
+        Ok(())
+    }
+}
+
+fn bench_groth16_aggregation(c: &mut Criterion, sizes: &mut ProofSizes) {
+    const NUM_PUBLIC_INPUTS: usize = 4;
+    let mut rng = StdRng::seed_from_u64(0u64);
+    let mut group = c.benchmark_group("groth16_aggregation");
+
+    let test_circuit = TestCircuit {
+        public_inputs: vec![Default::default(); NUM_PUBLIC_INPUTS],
+        public_sum: Default::default(),
+        witness_input: Default::default(),
+    };
+    let parameters = Groth16::<Bls12_381>::setup(test_circuit, &mut rng).unwrap();
+    let vk = &parameters.0.vk;
+
+    // Aggregation does not depend on the proofs being distinct, so a single proof is repeated to
+    // reach large sizes without proving each statement
+    let public_inputs = (0..NUM_PUBLIC_INPUTS)
+        .map(|_| Fr::rand(&mut rng))
+        .collect::<Vec<_>>();
+    let witness_input = Fr::rand(&mut rng);
+    let public_sum = witness_input + public_inputs.iter().sum::<Fr>();
+    let mut statement = public_inputs.clone();
+    statement.push(public_sum);
+    let circuit = TestCircuit {
+        public_inputs,
+        public_sum,
+        witness_input,
+    };
+    let proof = Groth16::<Bls12_381>::prove(&parameters.0, circuit, &mut rng).unwrap();
+
+    for log_size in log_sizes() {
+        let len = 1 << log_size;
+        let proofs = vec![proof.clone(); len];
+        let statements = vec![statement.clone(); len];
+        let srs = setup_inner_product::<_, Blake2b512, _>(&mut rng, len).unwrap();
+        let v_srs = srs.get_verifier_key();
+
+        let aggregate = || aggregate_proofs::<Bls12_381, Blake2b512>(&srs, vk, &proofs).unwrap();
+        group.bench_function(BenchmarkId::new("aggregate", len), |b| b.iter(aggregate));
+        let aggregate_proof = aggregate();
+        sizes.record("groth16_aggregation", log_size, &aggregate_proof);
+        group.bench_function(BenchmarkId::new("verify", len), |b| {
+            b.iter(|| verify_aggregate_proof(&v_srs, vk, &statements, &aggregate_proof).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_protocols(c: &mut Criterion) {
+    let mut sizes = ProofSizes::new();
+    bench_gipa(c, &mut sizes);
+    bench_tipa(c, &mut sizes);
+    bench_groth16_aggregation(c, &mut sizes);
+}
+
+criterion_group! {
+    name = protocols;
+    config = Criterion::default().sample_size(10);
+    targets = bench_protocols
+}
+criterion_main!(protocols);
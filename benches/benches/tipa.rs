@@ -5,7 +5,7 @@ use ark_dh_commitments::{
     pedersen::PedersenCommitment,
     DoublyHomomorphicCommitment,
 };
-use ark_ec::{group::Group, PairingEngine};
+use ark_ec::{pairing::Pairing, Group};
 use ark_ff::{Field, UniformRand};
 use ark_inner_products::{
     ExtensionFieldElement, InnerProduct, MultiexponentiationInnerProduct, PairingInnerProduct,
@@ -15,33 +15,24 @@ use ark_ip_proofs::tipa::{
     TIPACompatibleSetup, TIPA,
 };
 
-use blake2::Blake2b;
+use blake2::Blake2b512;
 use digest::Digest;
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand::{rngs::StdRng, CryptoRng, RngCore, SeedableRng};
 
-use std::{ops::MulAssign, time::Instant};
+use std::time::Instant;
 
-fn bench_tipa<IP, LMC, RMC, IPC, P, D, R: Rng>(rng: &mut R, len: usize)
+fn bench_tipa<IP, LMC, RMC, IPC, P, D, R: RngCore + CryptoRng>(rng: &mut R, len: usize)
 where
     D: Digest,
-    P: PairingEngine,
+    P: Pairing,
     IP: InnerProduct<
         LeftMessage = LMC::Message,
         RightMessage = RMC::Message,
         Output = IPC::Message,
     >,
-    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective> + TIPACompatibleSetup,
-    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1Projective>
-        + TIPACompatibleSetup,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::ScalarField, Key = P::G2> + TIPACompatibleSetup,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1> + TIPACompatibleSetup,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    LMC::Message: MulAssign<P::Fr>,
-    RMC::Message: MulAssign<P::Fr>,
-    IPC::Message: MulAssign<P::Fr>,
-    IPC::Key: MulAssign<P::Fr>,
-    LMC::Output: MulAssign<P::Fr>,
-    RMC::Output: MulAssign<P::Fr>,
-    IPC::Output: MulAssign<P::Fr>,
-    IPC::Output: MulAssign<LMC::Scalar>,
     IP::LeftMessage: UniformRand,
     IP::RightMessage: UniformRand,
 {
@@ -58,7 +49,7 @@ where
     let com_l = LMC::commit(&ck_l, &l).unwrap();
     let com_r = RMC::commit(&ck_r, &r).unwrap();
     let t = vec![IP::inner_product(&l, &r).unwrap()];
-    let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+    let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
     let mut start = Instant::now();
     let proof =
         TIPA::<IP, LMC, RMC, IPC, P, D>::prove(&srs, (&l, &r), (&ck_l, &ck_r, &ck_t)).unwrap();
@@ -71,27 +62,20 @@ where
     println!("\t verification time: {} ms", bench);
 }
 
-fn bench_tipa_srs_shift<IP, LMC, RMC, IPC, P, D, R: Rng>(rng: &mut R, len: usize)
+fn bench_tipa_srs_shift<IP, LMC, RMC, IPC, P, D, R: RngCore + CryptoRng>(rng: &mut R, len: usize)
 where
     D: Digest,
-    P: PairingEngine,
+    P: Pairing,
     IP: InnerProduct<
         LeftMessage = LMC::Message,
         RightMessage = RMC::Message,
         Output = IPC::Message,
     >,
-    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective> + TIPACompatibleSetup,
-    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1Projective>
-        + TIPACompatibleSetup,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::ScalarField, Key = P::G2> + TIPACompatibleSetup,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1> + TIPACompatibleSetup,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    LMC::Message: MulAssign<P::Fr> + Group<ScalarField = P::Fr>,
-    RMC::Message: MulAssign<P::Fr> + Group,
-    IPC::Message: MulAssign<P::Fr>,
-    IPC::Key: MulAssign<P::Fr>,
-    LMC::Output: MulAssign<P::Fr>,
-    RMC::Output: MulAssign<P::Fr>,
-    IPC::Output: MulAssign<P::Fr>,
-    IPC::Output: MulAssign<LMC::Scalar>,
+    LMC::Message: Group<ScalarField = P::ScalarField>,
+    RMC::Message: Group,
     IP::LeftMessage: UniformRand + Group,
     IP::RightMessage: UniformRand + Group,
 {
@@ -107,21 +91,21 @@ where
     let v_srs = srs.get_verifier_key();
     let com_l = LMC::commit(&ck_l, &l).unwrap();
     let com_r = RMC::commit(&ck_r, &r).unwrap();
-    let a_scalar = <P::Fr>::rand(rng);
+    let a_scalar = <P::ScalarField>::rand(rng);
     let r_vec = structured_scalar_power(len, &a_scalar);
     let l_a = l
         .iter()
         .zip(&r_vec)
-        .map(|(a, r)| a.mul(r))
+        .map(|(a, r)| *a * r)
         .collect::<Vec<LMC::Message>>();
     let ck_l_a = ck_l
         .iter()
         .zip(&r_vec)
-        .map(|(ck, r)| ck.mul(&r.inverse().unwrap()))
+        .map(|(ck, r)| *ck * r.inverse().unwrap())
         .collect::<Vec<LMC::Key>>();
 
     let t = vec![IP::inner_product(&l_a, &r).unwrap()];
-    let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+    let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
     let mut start = Instant::now();
     let proof = TIPA::<IP, LMC, RMC, IPC, P, D>::prove_with_srs_shift(
         &srs,
@@ -145,19 +129,13 @@ where
     println!("\t verification time: {} ms", bench);
 }
 
-fn bench_tipa_ssm<IP, LMC, IPC, P, D, R: Rng>(rng: &mut R, len: usize)
+fn bench_tipa_ssm<IP, LMC, IPC, P, D, R: RngCore + CryptoRng>(rng: &mut R, len: usize)
 where
     D: Digest,
-    P: PairingEngine,
+    P: Pairing,
     IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = LMC::Scalar, Output = IPC::Message>,
-    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective> + TIPACompatibleSetup,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::ScalarField, Key = P::G2> + TIPACompatibleSetup,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    LMC::Message: MulAssign<P::Fr>,
-    IPC::Message: MulAssign<P::Fr>,
-    IPC::Key: MulAssign<P::Fr>,
-    LMC::Output: MulAssign<P::Fr>,
-    IPC::Output: MulAssign<P::Fr>,
-    IPC::Output: MulAssign<LMC::Scalar>,
     IP::LeftMessage: UniformRand,
     IP::RightMessage: UniformRand,
 {
@@ -165,7 +143,7 @@ where
     for _ in 0..len {
         l.push(<IP::LeftMessage>::rand(rng));
     }
-    let scalar = <P::Fr>::rand(rng);
+    let scalar = <P::ScalarField>::rand(rng);
     let r = structured_scalar_power(len, &scalar);
 
     let (srs, ck_t) = TIPAWithSSM::<IP, LMC, IPC, P, D>::setup(rng, len).unwrap();
@@ -173,7 +151,7 @@ where
     let v_srs = srs.get_verifier_key();
     let com_l = LMC::commit(&ck_l, &l).unwrap();
     let t = vec![IP::inner_product(&l, &r).unwrap()];
-    let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+    let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
     let mut start = Instant::now();
     let proof = TIPAWithSSM::<IP, LMC, IPC, P, D>::prove_with_structured_scalar_message(
         &srs,
@@ -200,7 +178,7 @@ fn main() {
     const LEN: usize = 16;
     type GC1 = AFGHOCommitmentG1<Bls12_381>;
     type GC2 = AFGHOCommitmentG2<Bls12_381>;
-    type SC1 = PedersenCommitment<<Bls12_381 as PairingEngine>::G1Projective>;
+    type SC1 = PedersenCommitment<<Bls12_381 as Pairing>::G1>;
     let mut rng = StdRng::seed_from_u64(0u64);
 
     println!("Benchmarking TIPA with vector length: {}", LEN);
@@ -210,23 +188,20 @@ fn main() {
         PairingInnerProduct<Bls12_381>,
         GC1,
         GC2,
-        IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>,
+        IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as Pairing>::ScalarField>,
         Bls12_381,
-        Blake2b,
+        Blake2b512,
         StdRng,
     >(&mut rng, LEN);
 
     println!("2) Multiexponentiation G1 inner product...");
     bench_tipa::<
-        MultiexponentiationInnerProduct<<Bls12_381 as PairingEngine>::G1Projective>,
+        MultiexponentiationInnerProduct<<Bls12_381 as Pairing>::G1>,
         GC1,
         SC1,
-        IdentityCommitment<
-            <Bls12_381 as PairingEngine>::G1Projective,
-            <Bls12_381 as PairingEngine>::Fr,
-        >,
+        IdentityCommitment<<Bls12_381 as Pairing>::G1, <Bls12_381 as Pairing>::ScalarField>,
         Bls12_381,
-        Blake2b,
+        Blake2b512,
         StdRng,
     >(&mut rng, LEN);
 
@@ -235,22 +210,19 @@ fn main() {
         PairingInnerProduct<Bls12_381>,
         GC1,
         GC2,
-        IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>,
+        IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as Pairing>::ScalarField>,
         Bls12_381,
-        Blake2b,
+        Blake2b512,
         StdRng,
     >(&mut rng, LEN);
 
     println!("4) Multiexponentiation G1 inner product with structured scalar message...");
     bench_tipa_ssm::<
-        MultiexponentiationInnerProduct<<Bls12_381 as PairingEngine>::G1Projective>,
+        MultiexponentiationInnerProduct<<Bls12_381 as Pairing>::G1>,
         GC1,
-        IdentityCommitment<
-            <Bls12_381 as PairingEngine>::G1Projective,
-            <Bls12_381 as PairingEngine>::Fr,
-        >,
+        IdentityCommitment<<Bls12_381 as Pairing>::G1, <Bls12_381 as Pairing>::ScalarField>,
         Bls12_381,
-        Blake2b,
+        Blake2b512,
         StdRng,
     >(&mut rng, LEN);
 }
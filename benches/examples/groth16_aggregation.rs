@@ -1,5 +1,8 @@
-use ark_ip_proofs::applications::groth16_aggregation::{
-    aggregate_proofs, setup_inner_product, verify_aggregate_proof,
+use ark_ip_proofs::{
+    applications::groth16_aggregation::{
+        aggregate_proofs, setup_inner_product, verify_aggregate_proof,
+    },
+    memory::PeakAllocator,
 };
 
 use std::time::Instant;
@@ -11,9 +14,12 @@ use ark_groth16::Groth16;
 use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
 
-use blake2::Blake2b;
+use blake2::Blake2b512;
 use rand::{rngs::StdRng, SeedableRng};
 
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator::new();
+
 #[derive(Clone)]
 struct TestCircuit {
     public_inputs: Vec<Fr>,
@@ -53,7 +59,7 @@ fn main() {
     let parameters = Groth16::<Bls12_381>::setup(test_circuit, &mut rng).unwrap();
 
     // Generate parameters for inner product aggregation
-    let srs = setup_inner_product::<_, Blake2b, _>(&mut rng, NUM_PROOFS_TO_AGGREGATE).unwrap();
+    let srs = setup_inner_product::<_, Blake2b512, _>(&mut rng, NUM_PROOFS_TO_AGGREGATE).unwrap();
 
     // Generate proofs
     println!("Generating {} Groth16 proofs...", NUM_PROOFS_TO_AGGREGATE);
@@ -66,13 +72,13 @@ fn main() {
         let mut statement = Vec::new();
         for i in 0..NUM_PUBLIC_INPUTS {
             public_inputs.push(Fr::rand(&mut rng));
-            statement.push(public_inputs[i].clone());
+            statement.push(public_inputs[i]);
         }
         let w = Fr::rand(&mut rng);
-        let sum: Fr = w.clone() + &public_inputs.iter().sum();
-        statement.push(sum.clone());
+        let sum: Fr = w + public_inputs.iter().sum::<Fr>();
+        statement.push(sum);
         let circuit = TestCircuit {
-            public_inputs: public_inputs,
+            public_inputs,
             public_sum: sum,
             witness_input: w,
         };
@@ -89,7 +95,9 @@ fn main() {
     // Aggregate proofs using inner product proofs
     start = Instant::now();
     println!("Aggregating {} Groth16 proofs...", NUM_PROOFS_TO_AGGREGATE);
-    let aggregate_proof = aggregate_proofs::<Bls12_381, Blake2b>(&srs, &proofs).unwrap();
+    let (aggregate_proof, memory) = ALLOCATOR
+        .measure(|| aggregate_proofs::<Bls12_381, Blake2b512>(&srs, &parameters.0.vk, &proofs));
+    let aggregate_proof = aggregate_proof.unwrap();
     let prover_time = start.elapsed().as_millis();
 
     println!("Verifying aggregated proof...");
@@ -107,4 +115,5 @@ fn main() {
     println!("Proof generation time: {} ms", generation_time);
     println!("Proof aggregation time: {} ms", prover_time);
     println!("Proof verification time: {} ms", verifier_time);
+    println!("Proof aggregation {}", memory);
 }
@@ -0,0 +1,190 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Proof, VerifyingKey};
+use ark_ip_proofs::{
+    applications::groth16_aggregation::{
+        aggregate_proofs, aggregate_proofs_with_checkpoints, resume_aggregate_proofs,
+        setup_inner_product, verify_aggregate_proof, AggregateProof, AggregationCheckpoint,
+    },
+    memory::PeakAllocator,
+    tipa::{VerifierSRS, SRS},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::{
+    error::Error as ErrorTrait,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+use blake2::Blake2b512;
+use rand::rngs::OsRng;
+use structopt::StructOpt;
+
+// File and argument errors alongside those of the library
+type Error = Box<dyn ErrorTrait>;
+
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator::new();
+
+// All files use the canonical compressed serialization of the corresponding arkworks types
+#[derive(StructOpt)]
+#[structopt(name = "ripp", about = "Aggregation of Groth16 proofs over BLS12-381")]
+enum Command {
+    /// Samples an SRS for aggregating up to `size` proofs. The trapdoor is discarded but not
+    /// generated by a ceremony, so only use this for testing
+    Setup {
+        #[structopt(long)]
+        size: usize,
+        #[structopt(long, parse(from_os_str))]
+        out: PathBuf,
+        #[structopt(long = "verifier-out", parse(from_os_str))]
+        verifier_out: PathBuf,
+    },
+    /// Aggregates the Groth16 proofs in a directory, taken in file name order
+    Aggregate {
+        #[structopt(long, parse(from_os_str))]
+        srs: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        vk: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        proofs: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        out: PathBuf,
+        /// Saves progress to this file after every round, and resumes from it if it exists. The
+        /// file is removed once the aggregate proof is written
+        #[structopt(long, parse(from_os_str))]
+        checkpoint: Option<PathBuf>,
+        /// Prints the peak heap memory of the aggregation, counting the loaded SRS and proofs
+        #[structopt(long = "report-memory")]
+        report_memory: bool,
+    },
+    /// Verifies an aggregate proof against the public inputs of every aggregated proof, given as
+    /// a serialized vector of vectors in the order the proofs were aggregated. Exits with status 1
+    /// if the aggregate proof is invalid
+    VerifyAggregate {
+        #[structopt(long, parse(from_os_str))]
+        srs: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        vk: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        inputs: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        proof: PathBuf,
+    },
+}
+
+fn main() {
+    if let Err(e) = run(Command::from_args()) {
+        eprintln!("error: {}", e);
+        process::exit(2);
+    }
+}
+
+fn run(command: Command) -> Result<(), Error> {
+    match command {
+        Command::Setup {
+            size,
+            out,
+            verifier_out,
+        } => {
+            if !size.is_power_of_two() {
+                return Err(format!("size {} is not a power of 2", size).into());
+            }
+            let srs = setup_inner_product::<Bls12_381, Blake2b512, _>(&mut OsRng, size)?;
+            write(&out, &srs)?;
+            write(&verifier_out, &srs.get_verifier_key())
+        }
+        Command::Aggregate {
+            srs,
+            vk,
+            proofs,
+            out,
+            checkpoint,
+            report_memory,
+        } => {
+            let srs: SRS<Bls12_381> = read(&srs)?;
+            let vk: VerifyingKey<Bls12_381> = read(&vk)?;
+            let mut proof_paths = fs::read_dir(&proofs)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<Result<Vec<PathBuf>, _>>()?;
+            proof_paths.retain(|path| path.is_file());
+            proof_paths.sort();
+            let proofs = proof_paths
+                .iter()
+                .map(|path| read(path))
+                .collect::<Result<Vec<Proof<Bls12_381>>, Error>>()?;
+
+            let (aggregate_proof, memory) = ALLOCATOR.measure(|| match &checkpoint {
+                Some(path) => aggregate_with_checkpoint(&srs, &vk, &proofs, path),
+                None => Ok(aggregate_proofs::<Bls12_381, Blake2b512>(
+                    &srs, &vk, &proofs,
+                )?),
+            });
+            write(&out, &aggregate_proof?)?;
+            if let Some(path) = &checkpoint {
+                fs::remove_file(path)?;
+            }
+            println!("aggregated {} proofs", proofs.len());
+            if report_memory {
+                println!("{}", memory);
+            }
+            Ok(())
+        }
+        Command::VerifyAggregate {
+            srs,
+            vk,
+            inputs,
+            proof,
+        } => {
+            let v_srs: VerifierSRS<Bls12_381> = read(&srs)?;
+            let vk: VerifyingKey<Bls12_381> = read(&vk)?;
+            let inputs: Vec<Vec<Fr>> = read(&inputs)?;
+            let proof: AggregateProof<Bls12_381, Blake2b512> = read(&proof)?;
+
+            if verify_aggregate_proof(&v_srs, &vk, &inputs, &proof)? {
+                println!("valid");
+                Ok(())
+            } else {
+                println!("invalid");
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn aggregate_with_checkpoint(
+    srs: &SRS<Bls12_381>,
+    vk: &VerifyingKey<Bls12_381>,
+    proofs: &[Proof<Bls12_381>],
+    path: &Path,
+) -> Result<AggregateProof<Bls12_381, Blake2b512>, Error> {
+    // Written to a temporary file first, so that a crash while saving keeps the previous checkpoint
+    let tmp_path = path.with_extension("tmp");
+    let save = |checkpoint: &AggregationCheckpoint<Bls12_381, Blake2b512>| -> Result<(), Error> {
+        write(&tmp_path, checkpoint)?;
+        Ok(fs::rename(&tmp_path, path)?)
+    };
+    if path.exists() {
+        let checkpoint: AggregationCheckpoint<Bls12_381, Blake2b512> = read(path)?;
+        eprintln!("resuming after {} rounds", checkpoint.rounds());
+        resume_aggregate_proofs(srs, vk, proofs, checkpoint, save)
+    } else {
+        aggregate_proofs_with_checkpoints(srs, vk, proofs, save)
+    }
+}
+
+fn read<T: CanonicalDeserialize>(path: &Path) -> Result<T, Error> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    T::deserialize_compressed(BufReader::new(file))
+        .map_err(|e| format!("{}: {:?}", path.display(), e).into())
+}
+
+fn write<T: CanonicalSerialize>(path: &Path, value: &T) -> Result<(), Error> {
+    let file = File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut writer = BufWriter::new(file);
+    value
+        .serialize_compressed(&mut writer)
+        .map_err(|e| format!("{}: {:?}", path.display(), e))?;
+    Ok(writer.flush()?)
+}
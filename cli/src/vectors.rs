@@ -0,0 +1,300 @@
+#![allow(clippy::upper_case_acronyms)]
+use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+use ark_dh_commitments::{
+    afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+    identity::{HomomorphicPlaceholderValue, IdentityCommitment, IdentityOutput},
+    DoublyHomomorphicCommitment,
+};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::{Field, UniformRand};
+use ark_groth16::{Proof, VerifyingKey};
+use ark_inner_products::{ExtensionFieldElement, InnerProduct, PairingInnerProduct};
+use ark_ip_proofs::{
+    applications::groth16_aggregation::{
+        aggregate_proofs, setup_inner_product, verify_aggregate_proof, AggregateProof,
+    },
+    gipa::{GIPAProof, GIPA},
+    tipa::{TIPAProof, VerifierSRS, TIPA},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::{
+    error::Error as ErrorTrait,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    process,
+};
+
+use blake2::Blake2b512;
+use rand::{CryptoRng, RngCore};
+use rand_chacha::ChaChaRng;
+use rand_core::SeedableRng;
+use structopt::StructOpt;
+
+type Error = Box<dyn ErrorTrait>;
+
+type IP = PairingInnerProduct<Bls12_381>;
+type GC1 = AFGHOCommitmentG1<Bls12_381>;
+type GC2 = AFGHOCommitmentG2<Bls12_381>;
+type IPC = IdentityCommitment<ExtensionFieldElement<Bls12_381>, Fr>;
+type PairingGIPA = GIPA<IP, GC1, GC2, IPC, Blake2b512>;
+type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b512>;
+
+const SEED: u64 = 0;
+const SIZE: usize = 8;
+const NUM_PUBLIC_INPUTS: usize = 2;
+
+// Test vectors are files of canonical compressed serializations, one directory per protocol
+#[derive(StructOpt)]
+#[structopt(
+    name = "ripp-vectors",
+    about = "Deterministic test vectors for the wire formats of GIPA, TIPA and Groth16 aggregation"
+)]
+enum Command {
+    /// Writes the test vectors to a directory
+    Generate {
+        #[structopt(long, parse(from_os_str))]
+        out: PathBuf,
+    },
+    /// Checks that the proofs in a directory of test vectors verify, and that generating the
+    /// vectors again gives the same bytes. Exits with status 1 if any check fails
+    Verify {
+        #[structopt(long, parse(from_os_str))]
+        dir: PathBuf,
+    },
+}
+
+fn main() {
+    let result = match Command::from_args() {
+        Command::Generate { out } => generate(&out),
+        Command::Verify { dir } => verify(&dir).map(|valid| {
+            if !valid {
+                process::exit(1);
+            }
+        }),
+    };
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(2);
+    }
+}
+
+fn generate(out: &Path) -> Result<(), Error> {
+    for (name, bytes) in vectors()? {
+        let path = out.join(name);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(&path, bytes).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+fn verify(dir: &Path) -> Result<bool, Error> {
+    let mut valid = true;
+    let mut check = |what: &str, ok: bool| {
+        println!("{}: {}", what, if ok { "ok" } else { "FAILED" });
+        valid &= ok;
+    };
+
+    // Stored proofs verify under the stored keys and commitments
+    let gipa_ck: GIPAKeys = read(&dir.join("gipa/ck.bin"))?;
+    let gipa_com = read(&dir.join("gipa/com.bin"))?;
+    let gipa_proof: GIPAProof<IP, GC1, GC2, IPC, Blake2b512> = read(&dir.join("gipa/proof.bin"))?;
+    check(
+        "gipa/proof.bin verifies",
+        verify_gipa(&gipa_ck, &gipa_com, &gipa_proof)?,
+    );
+
+    let tipa_v_srs: VerifierSRS<Bls12_381> = read(&dir.join("tipa/verifier_srs.bin"))?;
+    let tipa_ck_t: HomomorphicPlaceholderValue = read(&dir.join("tipa/ck_t.bin"))?;
+    let tipa_com = read(&dir.join("tipa/com.bin"))?;
+    let tipa_proof: TIPAProof<IP, GC1, GC2, IPC, Bls12_381, Blake2b512> =
+        read(&dir.join("tipa/proof.bin"))?;
+    check(
+        "tipa/proof.bin verifies",
+        verify_tipa(&tipa_v_srs, &tipa_ck_t, &tipa_com, &tipa_proof)?,
+    );
+
+    let agg_v_srs: VerifierSRS<Bls12_381> =
+        read(&dir.join("groth16_aggregation/verifier_srs.bin"))?;
+    let vk: VerifyingKey<Bls12_381> = read(&dir.join("groth16_aggregation/vk.bin"))?;
+    let inputs: Vec<Vec<Fr>> = read(&dir.join("groth16_aggregation/inputs.bin"))?;
+    let aggregate_proof: AggregateProof<Bls12_381, Blake2b512> =
+        read(&dir.join("groth16_aggregation/aggregate_proof.bin"))?;
+    check(
+        "groth16_aggregation/aggregate_proof.bin verifies",
+        verify_aggregate_proof(&agg_v_srs, &vk, &inputs, &aggregate_proof)?,
+    );
+
+    // Generation is reproducible byte for byte
+    for (name, bytes) in vectors()? {
+        let path = dir.join(name);
+        let stored = fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        check(&format!("{} matches", name), stored == bytes);
+    }
+    Ok(valid)
+}
+
+type GIPAKeys = (
+    Vec<G2Projective>,
+    Vec<G1Projective>,
+    HomomorphicPlaceholderValue,
+);
+type PairingCommitments = (
+    ExtensionFieldElement<Bls12_381>,
+    ExtensionFieldElement<Bls12_381>,
+    IdentityOutput<ExtensionFieldElement<Bls12_381>>,
+);
+
+fn verify_gipa(
+    ck: &GIPAKeys,
+    com: &PairingCommitments,
+    proof: &GIPAProof<IP, GC1, GC2, IPC, Blake2b512>,
+) -> Result<bool, Error> {
+    Ok(PairingGIPA::verify(
+        (&ck.0, &ck.1, &ck.2),
+        (&com.0, &com.1, &com.2),
+        proof,
+    )?)
+}
+
+fn verify_tipa(
+    v_srs: &VerifierSRS<Bls12_381>,
+    ck_t: &HomomorphicPlaceholderValue,
+    com: &PairingCommitments,
+    proof: &TIPAProof<IP, GC1, GC2, IPC, Bls12_381, Blake2b512>,
+) -> Result<bool, Error> {
+    Ok(PairingTIPA::verify(
+        v_srs,
+        ck_t,
+        (&com.0, &com.1, &com.2),
+        proof,
+    )?)
+}
+
+fn read<T: CanonicalDeserialize>(path: &Path) -> Result<T, Error> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    T::deserialize_compressed(BufReader::new(file))
+        .map_err(|e| format!("{}: {:?}", path.display(), e).into())
+}
+
+fn bytes<T: CanonicalSerialize>(t: &T) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    t.serialize_compressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+// Pairing inner product of random messages, committed under the given keys
+fn pairing_instance<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    ck: (
+        &[G2Projective],
+        &[G1Projective],
+        &HomomorphicPlaceholderValue,
+    ),
+) -> Result<(Vec<G1Projective>, Vec<G2Projective>, PairingCommitments), Error> {
+    let left = (0..SIZE)
+        .map(|_| G1Projective::rand(rng))
+        .collect::<Vec<_>>();
+    let right = (0..SIZE)
+        .map(|_| G2Projective::rand(rng))
+        .collect::<Vec<_>>();
+    let t = vec![IP::inner_product(&left, &right)?];
+    let com = (
+        GC1::commit(ck.0, &left)?,
+        GC2::commit(ck.1, &right)?,
+        IPC::commit(std::slice::from_ref(ck.2), &t)?,
+    );
+    Ok((left, right, com))
+}
+
+type Groth16Instance = (VerifyingKey<Bls12_381>, Vec<Proof<Bls12_381>>, Vec<Vec<Fr>>);
+
+// Groth16 proofs simulated with the trapdoor of the verifying key, which verify without a circuit:
+// c = (a b - alpha beta - s gamma) / delta where s is the linear combination of the public inputs
+fn simulated_groth16<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Groth16Instance, Error> {
+    let (alpha, beta, gamma, delta) = (Fr::rand(rng), Fr::rand(rng), Fr::rand(rng), Fr::rand(rng));
+    let gamma_abc = (0..=NUM_PUBLIC_INPUTS)
+        .map(|_| Fr::rand(rng))
+        .collect::<Vec<_>>();
+    let g = G1Projective::generator();
+    let h = G2Projective::generator();
+    let vk = VerifyingKey::<Bls12_381> {
+        alpha_g1: (g * alpha).into_affine(),
+        beta_g2: (h * beta).into_affine(),
+        gamma_g2: (h * gamma).into_affine(),
+        delta_g2: (h * delta).into_affine(),
+        gamma_abc_g1: gamma_abc.iter().map(|u| (g * u).into_affine()).collect(),
+    };
+    let delta_inverse = delta.inverse().ok_or("delta is zero")?;
+
+    let mut proofs = Vec::new();
+    let mut inputs = Vec::new();
+    for _ in 0..SIZE {
+        let input = (0..NUM_PUBLIC_INPUTS)
+            .map(|_| Fr::rand(rng))
+            .collect::<Vec<_>>();
+        let s = gamma_abc[0]
+            + input
+                .iter()
+                .zip(&gamma_abc[1..])
+                .map(|(x, u)| *x * u)
+                .sum::<Fr>();
+        let (a, b) = (Fr::rand(rng), Fr::rand(rng));
+        let c = (a * b - (alpha * beta) - (s * gamma)) * delta_inverse;
+        proofs.push(Proof {
+            a: (g * a).into_affine(),
+            b: (h * b).into_affine(),
+            c: (g * c).into_affine(),
+        });
+        inputs.push(input);
+    }
+    Ok((vk, proofs, inputs))
+}
+
+// All test vectors as (path, bytes). ChaCha20 is used rather than StdRng, whose algorithm may change
+// between versions of rand.
+fn vectors() -> Result<Vec<(&'static str, Vec<u8>)>, Error> {
+    let mut rng = ChaChaRng::seed_from_u64(SEED);
+    let mut vectors = Vec::new();
+
+    let (ck_l, ck_r, ck_t) = PairingGIPA::setup(&mut rng, SIZE)?;
+    let (left, right, com) = pairing_instance(&mut rng, (&ck_l, &ck_r, &ck_t))?;
+    let t = IP::inner_product(&left, &right)?;
+    let proof = PairingGIPA::prove(
+        (&left, &right, &t),
+        (&ck_l, &ck_r, &ck_t),
+        (&com.0, &com.1, &com.2),
+    )?;
+    vectors.push(("gipa/ck.bin", bytes(&(ck_l, ck_r, ck_t))?));
+    vectors.push(("gipa/messages.bin", bytes(&(left, right))?));
+    vectors.push(("gipa/com.bin", bytes(&com)?));
+    vectors.push(("gipa/proof.bin", bytes(&proof)?));
+
+    let (srs, ck_t) = PairingTIPA::setup(&mut rng, SIZE)?;
+    let (ck_l, ck_r) = srs.get_commitment_keys();
+    let (left, right, com) = pairing_instance(&mut rng, (&ck_l, &ck_r, &ck_t))?;
+    let proof = PairingTIPA::prove(&srs, (&left, &right), (&ck_l, &ck_r, &ck_t))?;
+    vectors.push(("tipa/srs.bin", bytes(&srs)?));
+    vectors.push(("tipa/verifier_srs.bin", bytes(&srs.get_verifier_key())?));
+    vectors.push(("tipa/ck_t.bin", bytes(&ck_t)?));
+    vectors.push(("tipa/messages.bin", bytes(&(left, right))?));
+    vectors.push(("tipa/com.bin", bytes(&com)?));
+    vectors.push(("tipa/proof.bin", bytes(&proof)?));
+
+    let srs = setup_inner_product::<Bls12_381, Blake2b512, _>(&mut rng, SIZE)?;
+    let (vk, proofs, inputs) = simulated_groth16(&mut rng)?;
+    let aggregate_proof = aggregate_proofs::<Bls12_381, Blake2b512>(&srs, &vk, &proofs)?;
+    vectors.push(("groth16_aggregation/srs.bin", bytes(&srs)?));
+    vectors.push((
+        "groth16_aggregation/verifier_srs.bin",
+        bytes(&srs.get_verifier_key())?,
+    ));
+    vectors.push(("groth16_aggregation/vk.bin", bytes(&vk)?));
+    vectors.push(("groth16_aggregation/proofs.bin", bytes(&proofs)?));
+    vectors.push(("groth16_aggregation/inputs.bin", bytes(&inputs)?));
+    vectors.push((
+        "groth16_aggregation/aggregate_proof.bin",
+        bytes(&aggregate_proof)?,
+    ));
+    Ok(vectors)
+}
@@ -1,57 +1,608 @@
-use ark_ec::PairingEngine;
-use rand::Rng;
-use std::marker::PhantomData;
+use ark_ec::{pairing::Pairing, scalar_mul::fixed_base::FixedBase, CurveGroup, Group};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::marker::PhantomData;
+use ark_std::{cfg_into_iter, cfg_iter, vec::Vec};
+use rand::{CryptoRng, RngCore};
 
-use crate::{random_generators, DoublyHomomorphicCommitment, Error};
+#[cfg(feature = "parallel")]
+use crate::parallel_chunk_size;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-use ark_inner_products::{ExtensionFieldElement, InnerProduct, PairingInnerProduct};
+use crate::{
+    check_message_length, check_split, fold_generators, fold_generators_msm, hash_to_generators,
+    random_generators, split_halves, validate_generators, AffineKey, CommitmentError,
+    DoublyHomomorphicCommitment, Error, HidingCommitment,
+};
 
+use ark_inner_products::{
+    msm::{MsmBackend, PippengerMsm},
+    pairing::PairingBackend,
+    ExtensionFieldElement, InnerProduct, InnerProductError, MultiexponentiationInnerProduct,
+    PairingInnerProduct, ScalarInnerProduct,
+};
+
+const AFGHO_G1_DOMAIN: &[u8] = b"ripp-afgho-g1";
+const AFGHO_G2_DOMAIN: &[u8] = b"ripp-afgho-g2";
+const BLINDED_KEY_WINDOW_SIZE: usize = 4;
+
+// Pairing commitments whose multi-Miller loops go through backend `B`, e.g. to route them to GPU
+// kernels. The commitment schemes below also fold their keys with it as an `MsmBackend`
 #[derive(Clone)]
-pub struct AFGHOCommitment<P: PairingEngine> {
-    _pair: PhantomData<P>,
+pub struct AFGHOCommitment<P: Pairing, B = PippengerMsm> {
+    _pair: PhantomData<(P, B)>,
+}
+
+// Commitments are computed with a single multi-Miller loop and final exponentiation. Unreduced
+// commitments, the Miller loop outputs, can be multiplied together before a single reduction.
+impl<P: Pairing, B: PairingBackend<P>> AFGHOCommitment<P, B> {
+    pub fn miller_loop(left: &[P::G1], right: &[P::G2]) -> Result<P::TargetField, Error> {
+        let (left, right) = Self::prepare_pairs(left, right)?;
+        Self::multi_miller_loop(&left, &right)
+    }
+
+    // Miller loops of the pairs before and after split, sharing the preparation of the points
+    pub fn miller_loop_split(
+        left: &[P::G1],
+        right: &[P::G2],
+        split: usize,
+    ) -> Result<(P::TargetField, P::TargetField), Error> {
+        check_split(left.len(), split)?;
+        let (left, right) = Self::prepare_pairs(left, right)?;
+        Ok((
+            Self::multi_miller_loop(&left[..split], &right[..split])?,
+            Self::multi_miller_loop(&left[split..], &right[split..])?,
+        ))
+    }
+
+    fn prepare_pairs(
+        left: &[P::G1],
+        right: &[P::G2],
+    ) -> Result<(Vec<P::G1Prepared>, Vec<P::G2Prepared>), Error> {
+        // Keys of G1 commitments are the G2 side
+        check_message_length(right.len(), left.len())?;
+        Ok((prepare(left), prepare(right)))
+    }
+
+    fn multi_miller_loop(
+        left: &[P::G1Prepared],
+        right: &[P::G2Prepared],
+    ) -> Result<P::TargetField, Error> {
+        report_metrics!(pairings, left.len());
+        #[cfg(feature = "parallel")]
+        let unreduced = {
+            let chunk_size = parallel_chunk_size(left.len());
+            left.par_chunks(chunk_size)
+                .zip(right.par_chunks(chunk_size))
+                .map(|(left, right)| B::multi_miller_loop(left, right))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .product()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let unreduced = B::multi_miller_loop(left, right)?;
+        Ok(unreduced)
+    }
+
+    // Reduced commitment from prepared points
+    fn pairing_product(
+        left: &[P::G1Prepared],
+        right: &[P::G2Prepared],
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        AFGHOCommitment::<P>::final_exponentiation(&Self::multi_miller_loop(left, right)?)
+    }
+
+    pub fn final_exponentiation(
+        unreduced: &P::TargetField,
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        Ok(PairingInnerProduct::<P>::final_exponentiation(unreduced)?)
+    }
+}
+
+// Points in affine form prepared for pairings
+fn prepare<G: CurveGroup, Prepared: From<G::Affine> + Send>(points: &[G]) -> Vec<Prepared> {
+    cfg_into_iter!(G::normalize_batch(points))
+        .map(Prepared::from)
+        .collect()
 }
 
 #[derive(Clone)]
-pub struct AFGHOCommitmentG1<P: PairingEngine>(AFGHOCommitment<P>);
+pub struct AFGHOCommitmentG1<P: Pairing, B = PippengerMsm>(AFGHOCommitment<P, B>);
+
+impl<P: Pairing, B: PairingBackend<P>> AFGHOCommitmentG1<P, B> {
+    pub fn commit_unreduced(k: &[P::G2], m: &[P::G1]) -> Result<P::TargetField, Error> {
+        check_message_length(k.len(), m.len())?;
+        AFGHOCommitment::<P, B>::miller_loop(m, k)
+    }
+
+    pub fn commit_affine(
+        k: &AffineKey<P::G2>,
+        m: &[P::G1],
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        check_message_length(k.len(), m.len())?;
+        let k = cfg_iter!(k.generators())
+            .map(|g| P::G2Prepared::from(*g))
+            .collect::<Vec<_>>();
+        AFGHOCommitment::<P, B>::pairing_product(&prepare(m), &k)
+    }
+
+    // Key prepared for pairings once, for keys fixed across many commitments, e.g. the commitment
+    // keys of an SRS. Commitments under it only prepare the messages in G1, which is cheap
+    pub fn prepare_key(k: &[P::G2]) -> Vec<P::G2Prepared> {
+        PairingInnerProduct::<P>::precompute_right(k)
+    }
+
+    pub fn commit_prepared(
+        k: &[P::G2Prepared],
+        m: &[P::G1],
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        check_message_length(k.len(), m.len())?;
+        AFGHOCommitment::<P, B>::pairing_product(&prepare(m), k)
+    }
+}
 
 #[derive(Clone)]
-pub struct AFGHOCommitmentG2<P: PairingEngine>(AFGHOCommitment<P>);
+pub struct AFGHOCommitmentG2<P: Pairing, B = PippengerMsm>(AFGHOCommitment<P, B>);
 
-impl<P: PairingEngine> DoublyHomomorphicCommitment for AFGHOCommitmentG1<P> {
-    type Scalar = P::Fr;
-    type Message = P::G1Projective;
-    type Key = P::G2Projective;
+impl<P: Pairing, B: PairingBackend<P>> AFGHOCommitmentG2<P, B> {
+    pub fn commit_unreduced(k: &[P::G1], m: &[P::G2]) -> Result<P::TargetField, Error> {
+        check_message_length(k.len(), m.len())?;
+        AFGHOCommitment::<P, B>::miller_loop(k, m)
+    }
+
+    pub fn commit_affine(
+        k: &AffineKey<P::G1>,
+        m: &[P::G2],
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        check_message_length(k.len(), m.len())?;
+        let k = cfg_iter!(k.generators())
+            .map(|g| P::G1Prepared::from(*g))
+            .collect::<Vec<_>>();
+        AFGHOCommitment::<P, B>::pairing_product(&k, &prepare(m))
+    }
+}
+
+impl<P, B> DoublyHomomorphicCommitment for AFGHOCommitmentG1<P, B>
+where
+    P: Pairing,
+    B: PairingBackend<P> + MsmBackend<P::G2>,
+{
+    type Scalar = P::ScalarField;
+    type Message = P::G1;
+    type Key = P::G2;
     type Output = ExtensionFieldElement<P>;
 
-    fn setup<R: Rng>(rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
+    fn setup<R: RngCore + CryptoRng>(rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
         Ok(random_generators(rng, size))
     }
 
+    fn setup_from_seed(seed: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        Ok(hash_to_generators(AFGHO_G1_DOMAIN, seed, size))
+    }
+
     fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
-        Ok(PairingInnerProduct::<P>::inner_product(m, k)?)
+        AFGHOCommitment::<P>::final_exponentiation(&Self::commit_unreduced(k, m)?)
+    }
+
+    fn commit_split(
+        k: &[Self::Key],
+        m: &[Self::Message],
+        split: usize,
+    ) -> Result<(Self::Output, Self::Output), Error> {
+        check_message_length(k.len(), m.len())?;
+        let (unreduced_1, unreduced_2) = AFGHOCommitment::<P, B>::miller_loop_split(m, k, split)?;
+        Ok((
+            AFGHOCommitment::<P>::final_exponentiation(&unreduced_1)?,
+            AFGHOCommitment::<P>::final_exponentiation(&unreduced_2)?,
+        ))
+    }
+
+    fn validate_key(k: &[Self::Key]) -> Result<(), Error> {
+        validate_generators(k)
+    }
+
+    fn fold_keys(k: &[Self::Key], c: &Self::Scalar) -> Result<Vec<Self::Key>, Error> {
+        fold_generators::<_, B>(k, c)
+    }
+
+    fn fold_keys_msm(
+        k: &[Self::Key],
+        challenge_products: &[Self::Scalar],
+    ) -> Result<Self::Key, Error> {
+        fold_generators_msm::<_, B>(k, challenge_products)
+    }
+
+    fn commit_batch(
+        k: &[Self::Key],
+        ms: &[Vec<Self::Message>],
+    ) -> Result<Vec<Self::Output>, Error> {
+        let prepared_k = prepare(k);
+        ms.iter()
+            .map(|m| {
+                check_message_length(k.len(), m.len())?;
+                AFGHOCommitment::<P, B>::pairing_product(&prepare(m), &prepared_k)
+            })
+            .collect()
     }
 }
 
-impl<P: PairingEngine> DoublyHomomorphicCommitment for AFGHOCommitmentG2<P> {
-    type Scalar = P::Fr;
-    type Message = P::G2Projective;
-    type Key = P::G1Projective;
+impl<P, B> DoublyHomomorphicCommitment for AFGHOCommitmentG2<P, B>
+where
+    P: Pairing,
+    B: PairingBackend<P> + MsmBackend<P::G1>,
+{
+    type Scalar = P::ScalarField;
+    type Message = P::G2;
+    type Key = P::G1;
     type Output = ExtensionFieldElement<P>;
 
-    fn setup<R: Rng>(rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
+    fn setup<R: RngCore + CryptoRng>(rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
         Ok(random_generators(rng, size))
     }
 
+    fn setup_from_seed(seed: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        Ok(hash_to_generators(AFGHO_G2_DOMAIN, seed, size))
+    }
+
     fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
-        Ok(PairingInnerProduct::<P>::inner_product(k, m)?)
+        AFGHOCommitment::<P>::final_exponentiation(&Self::commit_unreduced(k, m)?)
+    }
+
+    fn commit_split(
+        k: &[Self::Key],
+        m: &[Self::Message],
+        split: usize,
+    ) -> Result<(Self::Output, Self::Output), Error> {
+        check_message_length(k.len(), m.len())?;
+        let (unreduced_1, unreduced_2) = AFGHOCommitment::<P, B>::miller_loop_split(k, m, split)?;
+        Ok((
+            AFGHOCommitment::<P>::final_exponentiation(&unreduced_1)?,
+            AFGHOCommitment::<P>::final_exponentiation(&unreduced_2)?,
+        ))
+    }
+
+    fn validate_key(k: &[Self::Key]) -> Result<(), Error> {
+        validate_generators(k)
+    }
+
+    fn fold_keys(k: &[Self::Key], c: &Self::Scalar) -> Result<Vec<Self::Key>, Error> {
+        fold_generators::<_, B>(k, c)
+    }
+
+    fn fold_keys_msm(
+        k: &[Self::Key],
+        challenge_products: &[Self::Scalar],
+    ) -> Result<Self::Key, Error> {
+        fold_generators_msm::<_, B>(k, challenge_products)
+    }
+
+    fn commit_batch(
+        k: &[Self::Key],
+        ms: &[Vec<Self::Message>],
+    ) -> Result<Vec<Self::Output>, Error> {
+        let prepared_k = prepare(k);
+        ms.iter()
+            .map(|m| {
+                check_message_length(k.len(), m.len())?;
+                AFGHOCommitment::<P, B>::pairing_product(&prepared_k, &prepare(m))
+            })
+            .collect()
+    }
+}
+
+impl<P, B> HidingCommitment for AFGHOCommitmentG1<P, B>
+where
+    P: Pairing,
+    B: PairingBackend<P> + MsmBackend<P::G2>,
+{
+    type BlindingKey = P::G2;
+    type Randomness = P::G1;
+
+    fn setup_blinding_key<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Self::BlindingKey, Error> {
+        Ok(P::G2::rand(rng))
+    }
+
+    fn sample_randomness<R: RngCore + CryptoRng>(rng: &mut R, _size: usize) -> Self::Randomness {
+        P::G1::rand(rng)
+    }
+
+    fn blind(
+        blinding_key: &Self::BlindingKey,
+        r: &Self::Randomness,
+    ) -> Result<Self::Output, Error> {
+        Self::commit(&[*blinding_key], &[*r])
     }
 }
 
+impl<P, B> HidingCommitment for AFGHOCommitmentG2<P, B>
+where
+    P: Pairing,
+    B: PairingBackend<P> + MsmBackend<P::G1>,
+{
+    type BlindingKey = P::G1;
+    type Randomness = P::G2;
+
+    fn setup_blinding_key<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Self::BlindingKey, Error> {
+        Ok(P::G1::rand(rng))
+    }
+
+    fn sample_randomness<R: RngCore + CryptoRng>(rng: &mut R, _size: usize) -> Self::Randomness {
+        P::G2::rand(rng)
+    }
+
+    fn blind(
+        blinding_key: &Self::BlindingKey,
+        r: &Self::Randomness,
+    ) -> Result<Self::Output, Error> {
+        Self::commit(&[*blinding_key], &[*r])
+    }
+}
+
+// AFGHO key k_i + rho_i h rerandomized under the blinding key h, for the zero-knowledge variants
+// of TIPP and MIPP. The rerandomizer rho is folded along with the key, so a folded key stays
+// k' + rho' h for the folded key k' and rerandomizer rho'.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlindedAFGHOKey<G: CurveGroup> {
+    key: Vec<G>,
+    base: G,
+    rerandomizer: Vec<G::ScalarField>,
+}
+
+impl<G: CurveGroup> BlindedAFGHOKey<G> {
+    pub fn new(key: &[G], base: &G, rerandomizer: Vec<G::ScalarField>) -> Result<Self, Error> {
+        if key.len() != rerandomizer.len() {
+            return Err(InnerProductError::LengthMismatch {
+                inner_product: "key rerandomization",
+                left: key.len(),
+                right: rerandomizer.len(),
+            }
+            .into());
+        }
+        Ok(BlindedAFGHOKey {
+            key: key.to_vec(),
+            base: *base,
+            rerandomizer,
+        })
+    }
+
+    pub fn sample<R: RngCore + CryptoRng>(rng: &mut R, key: &[G], base: &G) -> Self {
+        BlindedAFGHOKey {
+            key: key.to_vec(),
+            base: *base,
+            rerandomizer: (0..key.len()).map(|_| G::ScalarField::rand(rng)).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.key.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.key.is_empty()
+    }
+
+    pub fn base(&self) -> &G {
+        &self.base
+    }
+
+    pub fn rerandomizer(&self) -> &[G::ScalarField] {
+        &self.rerandomizer
+    }
+
+    pub fn unblinded_key(&self) -> &[G] {
+        &self.key
+    }
+
+    pub fn blinded_key(&self) -> Vec<G> {
+        let scalar_bits = G::ScalarField::MODULUS_BIT_SIZE as usize;
+        let scaled_base = FixedBase::msm::<G>(
+            scalar_bits,
+            BLINDED_KEY_WINDOW_SIZE,
+            &FixedBase::get_window_table(scalar_bits, BLINDED_KEY_WINDOW_SIZE, self.base),
+            &self.rerandomizer,
+        );
+        self.key
+            .iter()
+            .zip(scaled_base)
+            .map(|(k, b)| *k + b)
+            .collect()
+    }
+
+    pub fn fold(&self, c: &G::ScalarField) -> Result<Self, Error> {
+        let (rho_1, rho_2) = split_halves(&self.rerandomizer)?;
+        Ok(BlindedAFGHOKey {
+            key: fold_generators::<_, PippengerMsm>(&self.key, c)?,
+            base: self.base,
+            rerandomizer: rho_1
+                .iter()
+                .zip(rho_2)
+                .map(|(rho_1, rho_2)| *rho_1 + *rho_2 * c)
+                .collect(),
+        })
+    }
+
+    // Final folded key and rerandomizer given the products of the challenges
+    pub fn fold_msm(
+        &self,
+        challenge_products: &[G::ScalarField],
+    ) -> Result<(G, G::ScalarField), Error> {
+        Ok((
+            fold_generators_msm::<_, PippengerMsm>(&self.key, challenge_products)?,
+            ScalarInnerProduct::inner_product(&self.rerandomizer, challenge_products)?,
+        ))
+    }
+}
+
+impl<P: Pairing, B: PairingBackend<P> + MsmBackend<P::G2>> AFGHOCommitmentG1<P, B> {
+    pub fn commit_blinded(
+        k: &BlindedAFGHOKey<P::G2>,
+        m: &[P::G1],
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        Self::commit(&k.blinded_key(), m)
+    }
+
+    // Difference e(<rho, m>, h) between the commitments under the blinded and unblinded keys
+    pub fn rerandomization_term(
+        k: &BlindedAFGHOKey<P::G2>,
+        m: &[P::G1],
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        let r = MultiexponentiationInnerProduct::inner_product(m, k.rerandomizer())?;
+        Self::blind(k.base(), &r)
+    }
+}
+
+impl<P: Pairing, B: PairingBackend<P> + MsmBackend<P::G1>> AFGHOCommitmentG2<P, B> {
+    pub fn commit_blinded(
+        k: &BlindedAFGHOKey<P::G1>,
+        m: &[P::G2],
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        Self::commit(&k.blinded_key(), m)
+    }
+
+    // Difference e(h, <rho, m>) between the commitments under the blinded and unblinded keys
+    pub fn rerandomization_term(
+        k: &BlindedAFGHOKey<P::G1>,
+        m: &[P::G2],
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        let r = MultiexponentiationInnerProduct::inner_product(m, k.rerandomizer())?;
+        Self::blind(k.base(), &r)
+    }
+}
+
+// Proof that a key was rerandomized to k'_i = alpha k_i, as the generator of the other source
+// group raised to alpha. Commitments under k are migrated to k' by raising them to alpha.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyUpdateProof<G: CurveGroup> {
+    pub alpha_generator: G,
+}
+
+impl<G: CurveGroup> CanonicalSerialize for KeyUpdateProof<G> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.alpha_generator.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.alpha_generator.serialized_size(compress)
+    }
+}
+
+impl<G: CurveGroup> Valid for KeyUpdateProof<G> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.alpha_generator.check()
+    }
+}
+
+impl<G: CurveGroup> CanonicalDeserialize for KeyUpdateProof<G> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(KeyUpdateProof {
+            alpha_generator: G::deserialize_with_mode(reader, compress, validate)?,
+        })
+    }
+}
+
+impl<P: Pairing> AFGHOCommitmentG1<P> {
+    pub fn rerandomize_key(
+        k: &[P::G2],
+        alpha: &P::ScalarField,
+    ) -> Result<(Vec<P::G2>, KeyUpdateProof<P::G1>), Error> {
+        if alpha.is_zero() {
+            return Err(CommitmentError::RerandomizedByZero);
+        }
+        let alpha = alpha.into_bigint();
+        Ok((
+            k.iter().map(|k| k.mul_bigint(alpha)).collect(),
+            KeyUpdateProof {
+                alpha_generator: P::G1::generator().mul_bigint(alpha),
+            },
+        ))
+    }
+
+    // Checks e(alpha g, sum_i r_i k_i) = e(g, sum_i r_i k'_i) for random r_i
+    pub fn verify_key_update<R: RngCore + CryptoRng>(
+        k: &[P::G2],
+        new_k: &[P::G2],
+        proof: &KeyUpdateProof<P::G1>,
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let (k, new_k) = key_update_combination(k, new_k, rng)?;
+        Ok(!proof.alpha_generator.is_zero()
+            && P::pairing(proof.alpha_generator, k) == P::pairing(P::G1::generator(), new_k))
+    }
+}
+
+impl<P: Pairing> AFGHOCommitmentG2<P> {
+    pub fn rerandomize_key(
+        k: &[P::G1],
+        alpha: &P::ScalarField,
+    ) -> Result<(Vec<P::G1>, KeyUpdateProof<P::G2>), Error> {
+        if alpha.is_zero() {
+            return Err(CommitmentError::RerandomizedByZero);
+        }
+        let alpha = alpha.into_bigint();
+        Ok((
+            k.iter().map(|k| k.mul_bigint(alpha)).collect(),
+            KeyUpdateProof {
+                alpha_generator: P::G2::generator().mul_bigint(alpha),
+            },
+        ))
+    }
+
+    // Checks e(sum_i r_i k_i, alpha h) = e(sum_i r_i k'_i, h) for random r_i
+    pub fn verify_key_update<R: RngCore + CryptoRng>(
+        k: &[P::G1],
+        new_k: &[P::G1],
+        proof: &KeyUpdateProof<P::G2>,
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let (k, new_k) = key_update_combination(k, new_k, rng)?;
+        Ok(!proof.alpha_generator.is_zero()
+            && P::pairing(k, proof.alpha_generator) == P::pairing(new_k, P::G2::generator()))
+    }
+}
+
+// Random linear combinations of the old and new keys with the same coefficients
+fn key_update_combination<G: CurveGroup, R: RngCore + CryptoRng>(
+    k: &[G],
+    new_k: &[G],
+    rng: &mut R,
+) -> Result<(G, G), Error> {
+    if k.len() != new_k.len() {
+        return Err(InnerProductError::LengthMismatch {
+            inner_product: "key update",
+            left: k.len(),
+            right: new_k.len(),
+        }
+        .into());
+    }
+    let r = (0..k.len())
+        .map(|_| G::ScalarField::rand(rng))
+        .collect::<Vec<_>>();
+    Ok((
+        MultiexponentiationInnerProduct::inner_product(k, &r)?,
+        MultiexponentiationInnerProduct::inner_product(new_k, &r)?,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{derive_generators, ScalarMul};
     use ark_bls12_381::Bls12_381;
-    use ark_ff::UniformRand;
+    use ark_ff::One;
+    use ark_inner_products::{msm::GlvMsm, CompressedExtensionFieldElement};
+    use ark_std::sync::atomic::{AtomicUsize, Ordering};
     use rand::{rngs::StdRng, SeedableRng};
 
     type C1 = AFGHOCommitmentG1<Bls12_381>;
@@ -65,13 +616,13 @@ mod tests {
         let mut message = Vec::new();
         let mut wrong_message = Vec::new();
         for _ in 0..TEST_SIZE {
-            message.push(<Bls12_381 as PairingEngine>::G1Projective::rand(&mut rng));
-            wrong_message.push(<Bls12_381 as PairingEngine>::G1Projective::rand(&mut rng));
+            message.push(<Bls12_381 as Pairing>::G1::rand(&mut rng));
+            wrong_message.push(<Bls12_381 as Pairing>::G1::rand(&mut rng));
         }
         let com = C1::commit(&commit_keys, &message).unwrap();
         assert!(C1::verify(&commit_keys, &message, &com).unwrap());
         assert!(!C1::verify(&commit_keys, &wrong_message, &com).unwrap());
-        message.push(<Bls12_381 as PairingEngine>::G1Projective::rand(&mut rng));
+        message.push(<Bls12_381 as Pairing>::G1::rand(&mut rng));
         assert!(C1::verify(&commit_keys, &message, &com).is_err());
     }
 
@@ -82,13 +633,432 @@ mod tests {
         let mut message = Vec::new();
         let mut wrong_message = Vec::new();
         for _ in 0..TEST_SIZE {
-            message.push(<Bls12_381 as PairingEngine>::G2Projective::rand(&mut rng));
-            wrong_message.push(<Bls12_381 as PairingEngine>::G2Projective::rand(&mut rng));
+            message.push(<Bls12_381 as Pairing>::G2::rand(&mut rng));
+            wrong_message.push(<Bls12_381 as Pairing>::G2::rand(&mut rng));
         }
         let com = C2::commit(&commit_keys, &message).unwrap();
         assert!(C2::verify(&commit_keys, &message, &com).unwrap());
         assert!(!C2::verify(&commit_keys, &wrong_message, &com).unwrap());
-        message.push(<Bls12_381 as PairingEngine>::G2Projective::rand(&mut rng));
+        message.push(<Bls12_381 as Pairing>::G2::rand(&mut rng));
         assert!(C2::verify(&commit_keys, &message, &com).is_err());
     }
+
+    // Backend counting its Miller loops, standing in for one routing them to GPU kernels
+    #[derive(Clone)]
+    struct CountingBackend;
+
+    static MILLER_LOOPS: AtomicUsize = AtomicUsize::new(0);
+
+    impl PairingBackend<Bls12_381> for CountingBackend {
+        fn multi_miller_loop(
+            left: &[<Bls12_381 as Pairing>::G1Prepared],
+            right: &[<Bls12_381 as Pairing>::G2Prepared],
+        ) -> Result<<Bls12_381 as Pairing>::TargetField, InnerProductError> {
+            MILLER_LOOPS.fetch_add(1, Ordering::SeqCst);
+            <PippengerMsm as PairingBackend<Bls12_381>>::multi_miller_loop(left, right)
+        }
+    }
+
+    impl<G: CurveGroup> MsmBackend<G> for CountingBackend {
+        fn multi_scalar_mul(
+            bases: &[G::Affine],
+            scalars: &[<G::ScalarField as PrimeField>::BigInt],
+        ) -> Result<G, InnerProductError> {
+            PippengerMsm::multi_scalar_mul(bases, scalars)
+        }
+    }
+
+    #[test]
+    fn afgho_backend_test() {
+        type CountingC1 = AFGHOCommitmentG1<Bls12_381, CountingBackend>;
+        type GlvC2 = AFGHOCommitmentG2<Bls12_381, GlvMsm>;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys_1 = C1::setup(&mut rng, TEST_SIZE).unwrap();
+        let commit_keys_2 = C2::setup(&mut rng, TEST_SIZE).unwrap();
+        let message_1 = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let message_2 = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G2::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        let com_1 = C1::commit(&commit_keys_1, &message_1).unwrap();
+        assert_eq!(
+            CountingC1::commit(&commit_keys_1, &message_1).unwrap(),
+            com_1
+        );
+        assert!(MILLER_LOOPS.load(Ordering::SeqCst) > 0);
+        let calls = MILLER_LOOPS.load(Ordering::SeqCst);
+        let prepared_k = CountingC1::prepare_key(&commit_keys_1);
+        assert_eq!(
+            CountingC1::commit_prepared(&prepared_k, &message_1).unwrap(),
+            com_1
+        );
+        assert_eq!(
+            CountingC1::commit_batch(&commit_keys_1, ark_std::slice::from_ref(&message_1)).unwrap(),
+            vec![com_1]
+        );
+        assert!(MILLER_LOOPS.load(Ordering::SeqCst) >= calls + 2);
+
+        let com_2 = C2::commit(&commit_keys_2, &message_2).unwrap();
+        assert_eq!(GlvC2::commit(&commit_keys_2, &message_2).unwrap(), com_2);
+        assert_eq!(
+            GlvC2::commit_affine(&AffineKey::from_projective(&commit_keys_2), &message_2).unwrap(),
+            com_2
+        );
+        let c = <Bls12_381 as Pairing>::ScalarField::rand(&mut rng);
+        assert_eq!(
+            GlvC2::fold_keys(&commit_keys_2, &c).unwrap(),
+            C2::fold_keys(&commit_keys_2, &c).unwrap()
+        );
+        let challenge_products = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::ScalarField::rand(&mut rng))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            GlvC2::fold_keys_msm(&commit_keys_2, &challenge_products).unwrap(),
+            C2::fold_keys_msm(&commit_keys_2, &challenge_products).unwrap()
+        );
+        assert!(matches!(
+            C2::fold_keys_msm(&commit_keys_2[1..], &challenge_products),
+            Err(CommitmentError::KeyMessageLengthMismatch { .. })
+        ));
+        assert!(matches!(
+            AFGHOCommitment::<Bls12_381>::miller_loop(&message_1, &commit_keys_1[1..]),
+            Err(CommitmentError::KeyMessageLengthMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn afgho_batch_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys_1 = C1::setup(&mut rng, TEST_SIZE).unwrap();
+        let commit_keys_2 = C2::setup(&mut rng, TEST_SIZE).unwrap();
+        let messages_1 = (0..4)
+            .map(|_| {
+                (0..TEST_SIZE)
+                    .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let messages_2 = (0..4)
+            .map(|_| {
+                (0..TEST_SIZE)
+                    .map(|_| <Bls12_381 as Pairing>::G2::rand(&mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let coms_1 = C1::commit_batch(&commit_keys_1, &messages_1).unwrap();
+        for (m, com) in messages_1.iter().zip(&coms_1) {
+            assert_eq!(*com, C1::commit(&commit_keys_1, m).unwrap());
+        }
+        let coms_2 = C2::commit_batch(&commit_keys_2, &messages_2).unwrap();
+        for (m, com) in messages_2.iter().zip(&coms_2) {
+            assert_eq!(*com, C2::commit(&commit_keys_2, m).unwrap());
+        }
+        assert!(C1::commit_batch(&commit_keys_1[1..], &messages_1).is_err());
+    }
+
+    #[test]
+    fn afgho_affine_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys_1 = C1::setup(&mut rng, TEST_SIZE).unwrap();
+        let commit_keys_2 = C2::setup(&mut rng, TEST_SIZE).unwrap();
+        let affine_keys_1 = AffineKey::from_projective(&commit_keys_1);
+        let affine_keys_2 = AffineKey::from_projective(&commit_keys_2);
+        let message_1 = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let message_2 = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G2::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            C1::commit_affine(&affine_keys_1, &message_1).unwrap(),
+            C1::commit(&commit_keys_1, &message_1).unwrap()
+        );
+        assert_eq!(
+            C2::commit_affine(&affine_keys_2, &message_2).unwrap(),
+            C2::commit(&commit_keys_2, &message_2).unwrap()
+        );
+        assert!(C1::commit_affine(&affine_keys_1, &message_1[1..]).is_err());
+        assert!(C2::commit_affine(&affine_keys_2, &message_2[1..]).is_err());
+
+        // Keys in G2 prepared for pairings once
+        let prepared_keys_1 = C1::prepare_key(&commit_keys_1);
+        assert_eq!(
+            C1::commit_prepared(&prepared_keys_1, &message_1).unwrap(),
+            C1::commit(&commit_keys_1, &message_1).unwrap()
+        );
+        assert!(C1::commit_prepared(&prepared_keys_1, &message_1[1..]).is_err());
+    }
+
+    #[test]
+    fn derived_generators_test() {
+        type G1 = <Bls12_381 as Pairing>::G1;
+        type G2 = <Bls12_381 as Pairing>::G2;
+        let g1s = derive_generators::<G1>(b"tag", TEST_SIZE);
+        let g2s = derive_generators::<G2>(b"tag", TEST_SIZE);
+        assert!(g1s == derive_generators::<G1>(b"tag", TEST_SIZE));
+        assert!(g1s[..2] == derive_generators::<G1>(b"tag", 2)[..]);
+        assert!(g1s != derive_generators::<G1>(b"other tag", TEST_SIZE));
+        assert!(g2s == derive_generators::<G2>(b"tag", TEST_SIZE));
+        C2::validate_key(&g1s).unwrap();
+        C1::validate_key(&g2s).unwrap();
+        for i in 1..TEST_SIZE {
+            assert!(g1s[i] != g1s[0]);
+        }
+    }
+
+    #[test]
+    fn seeded_afgho_test() {
+        let commit_keys_1 = C1::setup_from_seed(b"seed", TEST_SIZE).unwrap();
+        let commit_keys_2 = C2::setup_from_seed(b"seed", TEST_SIZE).unwrap();
+        assert!(commit_keys_1 == C1::setup_from_seed(b"seed", TEST_SIZE).unwrap());
+        assert!(commit_keys_1 != C1::setup_from_seed(b"other seed", TEST_SIZE).unwrap());
+        for g in commit_keys_1.iter() {
+            assert!(g.into_affine().is_in_correct_subgroup_assuming_on_curve());
+        }
+        for g in commit_keys_2.iter() {
+            assert!(g.into_affine().is_in_correct_subgroup_assuming_on_curve());
+        }
+    }
+
+    #[test]
+    fn afgho_verify_batch_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C1::setup(&mut rng, TEST_SIZE).unwrap();
+        let mut openings = (0..4)
+            .map(|_| {
+                let message = (0..TEST_SIZE)
+                    .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+                    .collect::<Vec<_>>();
+                let com = C1::commit(&commit_keys, &message).unwrap();
+                (message, com)
+            })
+            .collect::<Vec<_>>();
+        assert!(C1::verify_batch(&commit_keys, &openings, &mut rng).unwrap());
+
+        openings[1].1 = openings[0].1.clone();
+        assert!(!C1::verify_batch(&commit_keys, &openings, &mut rng).unwrap());
+    }
+
+    #[test]
+    fn afgho_unreduced_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C1::setup(&mut rng, TEST_SIZE).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let other_message = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = C1::commit(&commit_keys, &message).unwrap();
+        let other_com = C1::commit(&commit_keys, &other_message).unwrap();
+
+        let unreduced = C1::commit_unreduced(&commit_keys, &message).unwrap()
+            * C1::commit_unreduced(&commit_keys, &other_message).unwrap();
+        assert_eq!(
+            AFGHOCommitment::<Bls12_381>::final_exponentiation(&unreduced).unwrap(),
+            com + other_com
+        );
+        assert!(C1::commit_unreduced(&commit_keys[1..], &message).is_err());
+    }
+
+    #[test]
+    fn afgho_commit_split_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let split = TEST_SIZE / 2;
+        let commit_keys = C2::setup(&mut rng, TEST_SIZE).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G2::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let (com_1, com_2) = C2::commit_split(&commit_keys, &message, split).unwrap();
+        assert_eq!(
+            com_1,
+            C2::commit(&commit_keys[..split], &message[..split]).unwrap()
+        );
+        assert_eq!(
+            com_2,
+            C2::commit(&commit_keys[split..], &message[split..]).unwrap()
+        );
+        assert_eq!(com_1 + com_2, C2::commit(&commit_keys, &message).unwrap());
+        assert!(C2::commit_split(&commit_keys, &message, TEST_SIZE + 1).is_err());
+        assert!(C2::commit_split(&commit_keys[1..], &message, split).is_err());
+    }
+
+    #[test]
+    fn afgho_serialization_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C1::setup(&mut rng, TEST_SIZE).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = C1::commit(&commit_keys, &message).unwrap();
+
+        let mut key_bytes = Vec::new();
+        commit_keys.serialize_compressed(&mut key_bytes).unwrap();
+        let mut com_bytes = Vec::new();
+        com.serialize_compressed(&mut com_bytes).unwrap();
+        let commit_keys_2 =
+            Vec::<<Bls12_381 as Pairing>::G2>::deserialize_compressed(&key_bytes[..]).unwrap();
+        let com_2 =
+            ExtensionFieldElement::<Bls12_381>::deserialize_compressed(&com_bytes[..]).unwrap();
+        assert!(C1::verify(&commit_keys_2, &message, &com_2).unwrap());
+
+        // Target group elements outside of the prime order subgroup are rejected
+        let not_in_subgroup = <Bls12_381 as Pairing>::TargetField::rand(&mut rng);
+        let mut bytes = Vec::new();
+        not_in_subgroup.serialize_compressed(&mut bytes).unwrap();
+        assert!(ExtensionFieldElement::<Bls12_381>::deserialize_compressed(&bytes[..]).is_err());
+        assert!(
+            ExtensionFieldElement::<Bls12_381>::deserialize_compressed_unchecked(&bytes[..])
+                .is_ok()
+        );
+
+        // The default is the identity, which round-trips through checked deserialization
+        let identity = ExtensionFieldElement::<Bls12_381>::default();
+        assert_eq!(com.clone() + identity.clone(), com);
+        let mut bytes = Vec::new();
+        identity.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            ExtensionFieldElement::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap(),
+            identity
+        );
+    }
+
+    #[test]
+    fn afgho_compressed_output_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C1::setup(&mut rng, TEST_SIZE).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = CompressedExtensionFieldElement(C1::commit(&commit_keys, &message).unwrap());
+
+        let mut bytes = Vec::new();
+        com.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), com.compressed_size());
+        assert_eq!(2 * bytes.len(), com.0.compressed_size());
+        let com_2 =
+            CompressedExtensionFieldElement::<Bls12_381>::deserialize_compressed(&bytes[..])
+                .unwrap();
+        assert!(C1::verify(&commit_keys, &message, &com_2.0).unwrap());
+
+        let identity = CompressedExtensionFieldElement(ExtensionFieldElement::<Bls12_381>(
+            <Bls12_381 as Pairing>::TargetField::one(),
+        ));
+        let mut bytes = Vec::new();
+        identity.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            CompressedExtensionFieldElement::<Bls12_381>::deserialize_compressed(&bytes[..])
+                .unwrap(),
+            identity
+        );
+
+        // Compressed encodings of elements outside of the target group are rejected
+        let mut bytes = Vec::new();
+        <Bls12_381 as Pairing>::TargetField::rand(&mut rng)
+            .c0
+            .serialize_compressed(&mut bytes)
+            .unwrap();
+        assert!(
+            CompressedExtensionFieldElement::<Bls12_381>::deserialize_compressed(&bytes[..])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn hiding_afgho_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C1::setup(&mut rng, TEST_SIZE).unwrap();
+        let blinding_key = C1::setup_blinding_key(&mut rng).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let r = C1::sample_randomness(&mut rng, TEST_SIZE);
+        let wrong_r = C1::sample_randomness(&mut rng, TEST_SIZE);
+
+        let com = C1::commit_with_randomness(&commit_keys, &blinding_key, &message, &r).unwrap();
+        assert!(
+            C1::verify_with_randomness(&commit_keys, &blinding_key, &message, &r, &com).unwrap()
+        );
+        assert!(
+            !C1::verify_with_randomness(&commit_keys, &blinding_key, &message, &wrong_r, &com)
+                .unwrap()
+        );
+        assert!(!C1::verify(&commit_keys, &message, &com).unwrap());
+    }
+
+    #[test]
+    fn blinded_afgho_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C1::setup(&mut rng, TEST_SIZE).unwrap();
+        let blinding_key = C1::setup_blinding_key(&mut rng).unwrap();
+        let blinded_keys = BlindedAFGHOKey::sample(&mut rng, &commit_keys, &blinding_key);
+        assert!(blinded_keys.blinded_key() != commit_keys);
+        let message = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        let com = C1::commit_blinded(&blinded_keys, &message).unwrap();
+        assert_eq!(
+            com,
+            C1::commit(&commit_keys, &message).unwrap()
+                + C1::rerandomization_term(&blinded_keys, &message).unwrap()
+        );
+
+        // Folding tracks the rerandomizer
+        let c = <Bls12_381 as Pairing>::ScalarField::rand(&mut rng);
+        let folded_keys = blinded_keys.fold(&c).unwrap();
+        assert!(
+            folded_keys.blinded_key() == C1::fold_keys(&blinded_keys.blinded_key(), &c).unwrap()
+        );
+        assert!(folded_keys.unblinded_key() == &C1::fold_keys(&commit_keys, &c).unwrap()[..]);
+
+        let challenge_products = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::ScalarField::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let (final_key, final_rho) = blinded_keys.fold_msm(&challenge_products).unwrap();
+        assert_eq!(
+            final_key + blinding_key * final_rho,
+            C1::fold_keys_msm(&blinded_keys.blinded_key(), &challenge_products).unwrap()
+        );
+
+        assert!(BlindedAFGHOKey::new(&commit_keys, &blinding_key, vec![c]).is_err());
+        assert!(blinded_keys.fold_msm(&challenge_products[1..]).is_err());
+    }
+
+    #[test]
+    fn key_update_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys_1 = C1::setup(&mut rng, TEST_SIZE).unwrap();
+        let commit_keys_2 = C2::setup(&mut rng, TEST_SIZE).unwrap();
+        let alpha = <Bls12_381 as Pairing>::ScalarField::rand(&mut rng);
+        let (new_keys_1, proof_1) = C1::rerandomize_key(&commit_keys_1, &alpha).unwrap();
+        let (new_keys_2, proof_2) = C2::rerandomize_key(&commit_keys_2, &alpha).unwrap();
+        assert!(C1::verify_key_update(&commit_keys_1, &new_keys_1, &proof_1, &mut rng).unwrap());
+        assert!(C2::verify_key_update(&commit_keys_2, &new_keys_2, &proof_2, &mut rng).unwrap());
+
+        // Commitments migrate to the new key
+        let message = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = C1::commit(&commit_keys_1, &message).unwrap();
+        assert!(C1::verify(&new_keys_1, &message, &com.scalar_mul(&alpha)).unwrap());
+
+        let mut wrong_keys_1 = new_keys_1.clone();
+        wrong_keys_1[1] = commit_keys_1[1];
+        assert!(!C1::verify_key_update(&commit_keys_1, &wrong_keys_1, &proof_1, &mut rng).unwrap());
+        assert!(
+            !C2::verify_key_update(&commit_keys_2, &commit_keys_2, &proof_2, &mut rng).unwrap()
+        );
+        assert!(
+            C1::verify_key_update(&commit_keys_1, &new_keys_1[1..], &proof_1, &mut rng).is_err()
+        );
+        assert!(
+            C1::rerandomize_key(&commit_keys_1, &<Bls12_381 as Pairing>::ScalarField::zero())
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,77 @@
+use ark_ec::CurveGroup;
+use ark_inner_products::impl_canonical_serde;
+
+use crate::{
+    afgho16::KeyUpdateProof,
+    identity::{HomomorphicPlaceholderValue, IdentityOutput},
+    two_tier::TwoTierKey,
+    AffineKey, CommitmentOpening, NoRandomness,
+};
+
+// Serde support through the canonical byte encoding, shared with the other crates of the workspace
+pub use ark_inner_products::canonical_serde::{deserialize, serialize, Canonical};
+
+impl_canonical_serde!([G: CurveGroup] AffineKey<G>);
+impl_canonical_serde!([G: CurveGroup] KeyUpdateProof<G>);
+impl_canonical_serde!([K1, K2] TwoTierKey<K1, K2>);
+impl_canonical_serde!([T: Clone + Default + Eq] IdentityOutput<T>);
+impl_canonical_serde!([M, R] CommitmentOpening<M, R>);
+impl_canonical_serde!([] NoRandomness);
+impl_canonical_serde!([] HomomorphicPlaceholderValue);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pedersen::PedersenCommitment, DoublyHomomorphicCommitment};
+    use ark_ed_on_bls12_381_bandersnatch::{EdwardsProjective as Bandersnatch, Fr};
+    use ark_ff::UniformRand;
+    use ark_inner_products::canonical_serde::serde::{
+        de::{
+            value::{BytesDeserializer, Error as ValueError, SeqDeserializer},
+            IntoDeserializer,
+        },
+        Deserialize,
+    };
+    use ark_serialize::CanonicalSerialize;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type C = PedersenCommitment<Bandersnatch>;
+    const TEST_SIZE: usize = 8;
+
+    fn canonical_bytes<T: CanonicalSerialize>(t: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        t.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn canonical_serde_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let (com, opening) = C::open(&commit_keys, &message).unwrap();
+
+        let affine_keys = AffineKey::from_projective(&commit_keys);
+        let bytes = canonical_bytes(&affine_keys);
+        let deserializer = BytesDeserializer::<ValueError>::new(&bytes);
+        let deserialized: AffineKey<Bandersnatch> = Deserialize::deserialize(deserializer).unwrap();
+        assert_eq!(deserialized, affine_keys);
+
+        let bytes = canonical_bytes(&opening);
+        let deserializer = BytesDeserializer::<ValueError>::new(&bytes);
+        let deserialized: CommitmentOpening<Fr, NoRandomness> =
+            Deserialize::deserialize(deserializer).unwrap();
+        assert_eq!(deserialized, opening);
+
+        // Byte sequences, as encoded by formats without a byte type
+        let bytes = canonical_bytes(&com);
+        let deserializer: SeqDeserializer<_, ValueError> = bytes.clone().into_deserializer();
+        let deserialized: Canonical<Bandersnatch> = Deserialize::deserialize(deserializer).unwrap();
+        assert_eq!(deserialized.0, com);
+
+        let deserializer = BytesDeserializer::<ValueError>::new(&bytes[1..]);
+        assert!(<Canonical<Bandersnatch> as Deserialize>::deserialize(deserializer).is_err());
+    }
+}
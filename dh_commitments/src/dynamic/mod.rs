@@ -0,0 +1,252 @@
+use ark_ec::{pairing::Pairing, scalar_mul::fixed_base::FixedBase, CurveGroup};
+use ark_ff::{Field, One, PrimeField, UniformRand};
+use ark_std::{fmt, str::FromStr, string::ToString, vec::Vec};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+    pedersen::PedersenCommitment,
+    CommitmentError, DoublyHomomorphicCommitment, Error,
+};
+
+use ark_inner_products::ExtensionFieldElement;
+
+// Commitment schemes selectable at runtime, e.g. from a configuration file, over keys, messages
+// and outputs tagged with their group. Structured keys are powers (g, g^{a^2}, g^{a^4}, ...) of a
+// fresh trapdoor a, as used by TIPA.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CommitmentScheme {
+    PedersenG1,
+    PedersenG2,
+    AFGHOG1,
+    AFGHOG2,
+    StructuredAFGHOG1,
+    StructuredAFGHOG2,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DynKey<P: Pairing> {
+    G1(Vec<P::G1>),
+    G2(Vec<P::G2>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DynMessage<P: Pairing> {
+    Scalar(Vec<P::ScalarField>),
+    G1(Vec<P::G1>),
+    G2(Vec<P::G2>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DynOutput<P: Pairing> {
+    G1(P::G1),
+    G2(P::G2),
+    Target(ExtensionFieldElement<P>),
+}
+
+const SCHEME_NAMES: [(&str, CommitmentScheme); 6] = [
+    ("pedersen-g1", CommitmentScheme::PedersenG1),
+    ("pedersen-g2", CommitmentScheme::PedersenG2),
+    ("afgho-g1", CommitmentScheme::AFGHOG1),
+    ("afgho-g2", CommitmentScheme::AFGHOG2),
+    ("structured-afgho-g1", CommitmentScheme::StructuredAFGHOG1),
+    ("structured-afgho-g2", CommitmentScheme::StructuredAFGHOG2),
+];
+
+impl FromStr for CommitmentScheme {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SCHEME_NAMES
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, scheme)| *scheme)
+            .ok_or_else(|| CommitmentError::UnknownScheme(s.to_string()))
+    }
+}
+
+impl fmt::Display for CommitmentScheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, _) = SCHEME_NAMES.iter().find(|(_, s)| s == self).unwrap();
+        f.write_str(name)
+    }
+}
+
+fn mismatch(scheme: &CommitmentScheme, what: &'static str) -> Error {
+    CommitmentError::SchemeMismatch {
+        scheme: *scheme,
+        what,
+    }
+}
+
+fn structured_key<G: CurveGroup, R: RngCore + CryptoRng>(rng: &mut R, size: usize) -> Vec<G> {
+    let a_squared = G::ScalarField::rand(rng).square();
+    let mut powers = Vec::with_capacity(size);
+    let mut pow = G::ScalarField::one();
+    for _ in 0..size {
+        powers.push(pow);
+        pow *= &a_squared;
+    }
+    let scalar_bits = G::ScalarField::MODULUS_BIT_SIZE as usize;
+    let window_size = FixedBase::get_mul_window_size(size);
+    let table = FixedBase::get_window_table(scalar_bits, window_size, G::generator());
+    FixedBase::msm::<G>(scalar_bits, window_size, &table, &powers)
+}
+
+impl CommitmentScheme {
+    pub fn setup<P: Pairing, R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        size: usize,
+    ) -> Result<DynKey<P>, Error> {
+        Ok(match self {
+            CommitmentScheme::PedersenG1 => {
+                DynKey::G1(PedersenCommitment::<P::G1>::setup(rng, size)?)
+            }
+            CommitmentScheme::PedersenG2 => {
+                DynKey::G2(PedersenCommitment::<P::G2>::setup(rng, size)?)
+            }
+            CommitmentScheme::AFGHOG1 => DynKey::G2(AFGHOCommitmentG1::<P>::setup(rng, size)?),
+            CommitmentScheme::AFGHOG2 => DynKey::G1(AFGHOCommitmentG2::<P>::setup(rng, size)?),
+            CommitmentScheme::StructuredAFGHOG1 => DynKey::G2(structured_key(rng, size)),
+            CommitmentScheme::StructuredAFGHOG2 => DynKey::G1(structured_key(rng, size)),
+        })
+    }
+
+    pub fn setup_from_seed<P: Pairing>(
+        &self,
+        seed: &[u8],
+        size: usize,
+    ) -> Result<DynKey<P>, Error> {
+        Ok(match self {
+            CommitmentScheme::PedersenG1 => {
+                DynKey::G1(PedersenCommitment::<P::G1>::setup_from_seed(seed, size)?)
+            }
+            CommitmentScheme::PedersenG2 => {
+                DynKey::G2(PedersenCommitment::<P::G2>::setup_from_seed(seed, size)?)
+            }
+            CommitmentScheme::AFGHOG1 => {
+                DynKey::G2(AFGHOCommitmentG1::<P>::setup_from_seed(seed, size)?)
+            }
+            CommitmentScheme::AFGHOG2 => {
+                DynKey::G1(AFGHOCommitmentG2::<P>::setup_from_seed(seed, size)?)
+            }
+            CommitmentScheme::StructuredAFGHOG1 | CommitmentScheme::StructuredAFGHOG2 => {
+                return Err(CommitmentError::TrustedSetupRequired)
+            }
+        })
+    }
+
+    pub fn commit<P: Pairing>(
+        &self,
+        k: &DynKey<P>,
+        m: &DynMessage<P>,
+    ) -> Result<DynOutput<P>, Error> {
+        match (self, k, m) {
+            (CommitmentScheme::PedersenG1, DynKey::G1(k), DynMessage::Scalar(m)) => {
+                Ok(DynOutput::G1(PedersenCommitment::<P::G1>::commit(k, m)?))
+            }
+            (CommitmentScheme::PedersenG2, DynKey::G2(k), DynMessage::Scalar(m)) => {
+                Ok(DynOutput::G2(PedersenCommitment::<P::G2>::commit(k, m)?))
+            }
+            (
+                CommitmentScheme::AFGHOG1 | CommitmentScheme::StructuredAFGHOG1,
+                DynKey::G2(k),
+                DynMessage::G1(m),
+            ) => Ok(DynOutput::Target(AFGHOCommitmentG1::<P>::commit(k, m)?)),
+            (
+                CommitmentScheme::AFGHOG2 | CommitmentScheme::StructuredAFGHOG2,
+                DynKey::G1(k),
+                DynMessage::G2(m),
+            ) => Ok(DynOutput::Target(AFGHOCommitmentG2::<P>::commit(k, m)?)),
+            _ if !self.accepts_key(k) => Err(mismatch(self, "key")),
+            _ => Err(mismatch(self, "message")),
+        }
+    }
+
+    pub fn verify<P: Pairing>(
+        &self,
+        k: &DynKey<P>,
+        m: &DynMessage<P>,
+        com: &DynOutput<P>,
+    ) -> Result<bool, Error> {
+        Ok(self.commit(k, m)? == *com)
+    }
+
+    fn accepts_key<P: Pairing>(&self, k: &DynKey<P>) -> bool {
+        match self {
+            CommitmentScheme::PedersenG1
+            | CommitmentScheme::AFGHOG2
+            | CommitmentScheme::StructuredAFGHOG2 => matches!(k, DynKey::G1(_)),
+            _ => matches!(k, DynKey::G2(_)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn dynamic_commitment_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let scalars =
+            DynMessage::<Bls12_381>::Scalar((0..TEST_SIZE).map(|_| Fr::rand(&mut rng)).collect());
+        let g1s = DynMessage::<Bls12_381>::G1(
+            (0..TEST_SIZE)
+                .map(|_| G1Projective::rand(&mut rng))
+                .collect(),
+        );
+        let g2s = DynMessage::<Bls12_381>::G2(
+            (0..TEST_SIZE)
+                .map(|_| G2Projective::rand(&mut rng))
+                .collect(),
+        );
+
+        for (name, m) in [
+            ("pedersen-g1", &scalars),
+            ("pedersen-g2", &scalars),
+            ("afgho-g1", &g1s),
+            ("afgho-g2", &g2s),
+            ("structured-afgho-g1", &g1s),
+            ("structured-afgho-g2", &g2s),
+        ]
+        .iter()
+        {
+            let scheme = CommitmentScheme::from_str(name).unwrap();
+            assert_eq!(scheme.to_string(), *name);
+            let k = scheme.setup::<Bls12_381, _>(&mut rng, TEST_SIZE).unwrap();
+            let com = scheme.commit(&k, m).unwrap();
+            assert!(scheme.verify(&k, m, &com).unwrap());
+            let wrong_m = if *m == &scalars { &g1s } else { &scalars };
+            assert!(scheme.commit(&k, wrong_m).is_err());
+        }
+
+        // Dynamic dispatch agrees with the statically selected schemes
+        let scheme = CommitmentScheme::from_str("afgho-g1").unwrap();
+        let k = scheme
+            .setup_from_seed::<Bls12_381>(b"seed", TEST_SIZE)
+            .unwrap();
+        let static_k = AFGHOCommitmentG1::<Bls12_381>::setup_from_seed(b"seed", TEST_SIZE).unwrap();
+        assert_eq!(k, DynKey::G2(static_k.clone()));
+        if let DynMessage::G1(m) = &g1s {
+            assert_eq!(
+                scheme.commit(&k, &g1s).unwrap(),
+                DynOutput::Target(AFGHOCommitmentG1::<Bls12_381>::commit(&static_k, m).unwrap())
+            );
+        }
+
+        let pedersen_k = CommitmentScheme::PedersenG1
+            .setup::<Bls12_381, _>(&mut rng, TEST_SIZE)
+            .unwrap();
+        assert!(scheme.commit(&pedersen_k, &g1s).is_err());
+        assert!(CommitmentScheme::StructuredAFGHOG1
+            .setup_from_seed::<Bls12_381>(b"seed", TEST_SIZE)
+            .is_err());
+        assert!(CommitmentScheme::from_str("kzg").is_err());
+    }
+}
@@ -1,12 +1,21 @@
-use ark_ff::{bytes::ToBytes, fields::PrimeField};
-use rand::Rng;
-use std::{
-    io::{Result as IoResult, Write},
+use ark_ff::{fields::PrimeField, UniformRand};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{
     marker::PhantomData,
     ops::{Add, MulAssign},
+    vec,
+    vec::Vec,
+};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    check_message_length, DoublyHomomorphicCommitment, Error, HidingCommitment, ScalarMul,
 };
 
-use crate::{DoublyHomomorphicCommitment, Error};
+use ark_inner_products::InnerProductError;
 
 #[derive(Clone)]
 pub struct IdentityCommitment<T, F: PrimeField> {
@@ -17,10 +26,34 @@ pub struct IdentityCommitment<T, F: PrimeField> {
 #[derive(Clone, Default, Eq, PartialEq)]
 pub struct HomomorphicPlaceholderValue;
 
-impl ToBytes for HomomorphicPlaceholderValue {
-    fn write<W: Write>(&self, _writer: W) -> IoResult<()> {
+impl CanonicalSerialize for HomomorphicPlaceholderValue {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        _writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
         Ok(())
     }
+
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        0
+    }
+}
+
+impl Valid for HomomorphicPlaceholderValue {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for HomomorphicPlaceholderValue {
+    fn deserialize_with_mode<R: Read>(
+        _reader: R,
+        _compress: Compress,
+        _validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(HomomorphicPlaceholderValue {})
+    }
 }
 
 impl Add for HomomorphicPlaceholderValue {
@@ -38,9 +71,35 @@ impl<T> MulAssign<T> for HomomorphicPlaceholderValue {
 #[derive(Clone, Default, Eq, PartialEq)]
 pub struct IdentityOutput<T: Clone + Default + Eq>(pub Vec<T>);
 
-impl<T: ToBytes + Clone + Default + Eq> ToBytes for IdentityOutput<T> {
-    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
-        self.0.write(&mut writer)
+impl<T: CanonicalSerialize + Clone + Default + Eq> CanonicalSerialize for IdentityOutput<T> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress)
+    }
+}
+
+impl<T: Valid + Clone + Default + Eq> Valid for IdentityOutput<T> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.0.check()
+    }
+}
+
+impl<T: CanonicalDeserialize + Clone + Default + Eq> CanonicalDeserialize for IdentityOutput<T> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(IdentityOutput(Vec::<T>::deserialize_with_mode(
+            reader, compress, validate,
+        )?))
     }
 }
 
@@ -66,7 +125,15 @@ impl<T: MulAssign<F> + Clone + Default + Eq, F: Clone> MulAssign<F> for Identity
 
 impl<T, F> DoublyHomomorphicCommitment for IdentityCommitment<T, F>
 where
-    T: ToBytes + Clone + Default + Eq + Add<T, Output = T> + MulAssign<F>,
+    T: CanonicalSerialize
+        + CanonicalDeserialize
+        + Clone
+        + Default
+        + Eq
+        + Add<T, Output = T>
+        + MulAssign<F>
+        + Send
+        + Sync,
     F: PrimeField,
 {
     type Scalar = F;
@@ -74,11 +141,159 @@ where
     type Key = HomomorphicPlaceholderValue;
     type Output = IdentityOutput<T>;
 
-    fn setup<R: Rng>(_rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
+    fn setup<R: RngCore + CryptoRng>(_rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
         Ok(vec![HomomorphicPlaceholderValue {}; size])
     }
 
-    fn commit(_k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
+    fn setup_from_seed(_seed: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        Ok(vec![HomomorphicPlaceholderValue {}; size])
+    }
+
+    fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
+        check_message_length(k.len(), m.len())?;
         Ok(IdentityOutput(m.to_vec()))
     }
 }
+
+// Blinds each entry m_i of the identity commitment to m_i + r_i h, upgrading an identity output
+// commitment to a hiding one within the same commitment family
+impl<T, F> HidingCommitment for IdentityCommitment<T, F>
+where
+    T: CanonicalSerialize
+        + CanonicalDeserialize
+        + Clone
+        + Default
+        + Eq
+        + Add<T, Output = T>
+        + MulAssign<F>
+        + UniformRand
+        + Send
+        + Sync,
+    F: PrimeField,
+{
+    type BlindingKey = T;
+    type Randomness = Vec<F>;
+
+    fn setup_blinding_key<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Self::BlindingKey, Error> {
+        Ok(T::rand(rng))
+    }
+
+    fn sample_randomness<R: RngCore + CryptoRng>(rng: &mut R, size: usize) -> Self::Randomness {
+        (0..size).map(|_| F::rand(rng)).collect()
+    }
+
+    fn blind(
+        blinding_key: &Self::BlindingKey,
+        r: &Self::Randomness,
+    ) -> Result<Self::Output, Error> {
+        Ok(IdentityOutput(
+            r.iter().map(|r| blinding_key.scalar_mul(r)).collect(),
+        ))
+    }
+
+    fn commit_with_randomness(
+        k: &[Self::Key],
+        blinding_key: &Self::BlindingKey,
+        m: &[Self::Message],
+        r: &Self::Randomness,
+    ) -> Result<Self::Output, Error> {
+        if m.len() != r.len() {
+            return Err(InnerProductError::LengthMismatch {
+                inner_product: "blinding",
+                left: m.len(),
+                right: r.len(),
+            }
+            .into());
+        }
+        Ok(Self::commit(k, m)? + Self::blind(blinding_key, r)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CommitmentError;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_inner_products::ExtensionFieldElement;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type C = IdentityCommitment<ExtensionFieldElement<Bls12_381>, Fr>;
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn hiding_identity_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let blinding_key = C::setup_blinding_key(&mut rng).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| ExtensionFieldElement::<Bls12_381>::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let r = C::sample_randomness(&mut rng, TEST_SIZE);
+        let wrong_r = C::sample_randomness(&mut rng, TEST_SIZE);
+
+        let com = C::commit_with_randomness(&commit_keys, &blinding_key, &message, &r).unwrap();
+        assert!(com.0 != message);
+        assert!(
+            C::verify_with_randomness(&commit_keys, &blinding_key, &message, &r, &com).unwrap()
+        );
+        assert!(
+            !C::verify_with_randomness(&commit_keys, &blinding_key, &message, &wrong_r, &com)
+                .unwrap()
+        );
+        assert!(
+            C::commit_with_randomness(&commit_keys, &blinding_key, &message, &r[1..].to_vec())
+                .is_err()
+        );
+
+        // Homomorphic in both message and randomness
+        let other_com =
+            C::commit_with_randomness(&commit_keys, &blinding_key, &message, &wrong_r).unwrap();
+        let sum_message = message
+            .iter()
+            .map(|m| m.clone() + m.clone())
+            .collect::<Vec<_>>();
+        let sum_r = r.iter().zip(&wrong_r).map(|(a, b)| *a + b).collect();
+        assert!(C::verify_with_randomness(
+            &commit_keys,
+            &blinding_key,
+            &sum_message,
+            &sum_r,
+            &(com + other_com)
+        )
+        .unwrap());
+    }
+
+    // Schemes outside this crate only need setup and commit
+    #[derive(Clone)]
+    struct External;
+
+    impl DoublyHomomorphicCommitment for External {
+        type Scalar = Fr;
+        type Message = Fr;
+        type Key = HomomorphicPlaceholderValue;
+        type Output = IdentityOutput<Fr>;
+
+        fn setup<R: RngCore + CryptoRng>(r: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
+            IdentityCommitment::<Fr, Fr>::setup(r, size)
+        }
+
+        fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
+            IdentityCommitment::<Fr, Fr>::commit(k, m)
+        }
+    }
+
+    #[test]
+    fn default_seeded_setup_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = External::setup(&mut rng, TEST_SIZE).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = External::commit(&commit_keys, &message).unwrap();
+        assert!(External::verify(&commit_keys, &message, &com).unwrap());
+        assert!(matches!(
+            External::setup_from_seed(b"seed", TEST_SIZE),
+            Err(CommitmentError::SeededSetupUnsupported)
+        ));
+    }
+}
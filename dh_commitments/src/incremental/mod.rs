@@ -0,0 +1,122 @@
+use crate::{CommitmentError, DoublyHomomorphicCommitment, Error};
+use ark_std::vec::Vec;
+
+// Running commitment to a message whose elements arrive one at a time, each consuming the next
+// key position. Commitments are additive in the message, so appending only commits to the new
+// elements. The running commitment equals the commitment under the full key to the message padded
+// with default elements.
+#[derive(Clone)]
+pub struct IncrementalCommitter<C: DoublyHomomorphicCommitment> {
+    key: Vec<C::Key>,
+    len: usize,
+    commitment: C::Output,
+}
+
+impl<C: DoublyHomomorphicCommitment> IncrementalCommitter<C> {
+    pub fn new(key: Vec<C::Key>) -> Result<Self, Error> {
+        Ok(IncrementalCommitter {
+            key,
+            len: 0,
+            commitment: C::commit(&[], &[])?,
+        })
+    }
+
+    pub fn append(&mut self, m: C::Message) -> Result<(), Error> {
+        self.extend(&[m])
+    }
+
+    pub fn extend(&mut self, m: &[C::Message]) -> Result<(), Error> {
+        if self.len + m.len() > self.key.len() {
+            return Err(CommitmentError::KeyMessageLengthMismatch {
+                key: self.key.len(),
+                message: self.len + m.len(),
+            });
+        }
+        let com = C::commit(&self.key[self.len..self.len + m.len()], m)?;
+        self.commitment = self.commitment.clone() + com;
+        self.len += m.len();
+        Ok(())
+    }
+
+    // Number of elements appended so far
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.key.len() - self.len
+    }
+
+    pub fn key(&self) -> &[C::Key] {
+        &self.key
+    }
+
+    pub fn commitment(&self) -> &C::Output {
+        &self.commitment
+    }
+
+    pub fn into_commitment(self) -> C::Output {
+        self.commitment
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{afgho16::AFGHOCommitmentG1, pedersen::PedersenCommitment};
+    use ark_bls12_381::Bls12_381;
+    use ark_ec::pairing::Pairing;
+    use ark_ed_on_bls12_381_bandersnatch::{EdwardsProjective as Bandersnatch, Fr};
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type C1 = PedersenCommitment<Bandersnatch>;
+    type C2 = AFGHOCommitmentG1<Bls12_381>;
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn incremental_commitment_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C1::setup(&mut rng, TEST_SIZE).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        let mut committer = IncrementalCommitter::<C1>::new(commit_keys.clone()).unwrap();
+        assert!(committer.is_empty());
+        committer.append(message[0]).unwrap();
+        committer.extend(&message[1..TEST_SIZE / 2]).unwrap();
+        assert_eq!(committer.remaining(), TEST_SIZE / 2);
+
+        // Partial commitments are commitments to the padded message
+        let mut padded_message = message[..TEST_SIZE / 2].to_vec();
+        padded_message.resize(TEST_SIZE, Fr::default());
+        assert!(C1::verify(&commit_keys, &padded_message, committer.commitment()).unwrap());
+
+        for m in &message[TEST_SIZE / 2..] {
+            committer.append(*m).unwrap();
+        }
+        assert_eq!(committer.len(), TEST_SIZE);
+        assert!(committer.append(Fr::rand(&mut rng)).is_err());
+        assert!(C1::verify(&commit_keys, &message, &committer.into_commitment()).unwrap());
+    }
+
+    #[test]
+    fn incremental_afgho_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C2::setup(&mut rng, TEST_SIZE).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        let mut committer = IncrementalCommitter::<C2>::new(commit_keys.clone()).unwrap();
+        for m in &message {
+            committer.append(*m).unwrap();
+        }
+        assert!(C2::verify(&commit_keys, &message, committer.commitment()).unwrap());
+    }
+}
@@ -0,0 +1,327 @@
+use ark_ff::{Field, UniformRand};
+use ark_inner_products::InnerProduct;
+use ark_std::{ops::Add, vec::Vec};
+use proptest::{prop_assert, test_runner::TestCaseError};
+use rand::{rngs::StdRng, CryptoRng, RngCore, SeedableRng};
+
+use crate::{DoublyHomomorphicCommitment, ScalarMul};
+
+pub use proptest;
+
+// Algebraic laws the arguments rely on, checked on values sampled from a seed. `commitment_laws!`
+// and `inner_product_laws!` turn them into proptest suites, so schemes and inner products defined
+// outside this crate can be checked by invoking the macros in their own tests
+
+fn sample<T: UniformRand, R: RngCore + CryptoRng>(rng: &mut R, len: usize) -> Vec<T> {
+    (0..len).map(|_| T::rand(rng)).collect()
+}
+
+fn sum<T: Add<T, Output = T> + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| a.clone() + b.clone())
+        .collect()
+}
+
+fn scale<T: ScalarMul<F>, F: Clone>(a: &[T], c: &F) -> Vec<T> {
+    a.iter().map(|a| a.scalar_mul(c)).collect()
+}
+
+fn check(law: bool, name: &str) -> Result<(), TestCaseError> {
+    prop_assert!(law, "{} does not hold", name);
+    Ok(())
+}
+
+// scalar_mul agrees with MulAssign, distributes over sums of elements and of scalars, and is
+// compatible with the product of scalars
+pub fn scalar_mul_laws<T, F>(a: &T, b: &T, c: &F, d: &F) -> Result<(), TestCaseError>
+where
+    T: ScalarMul<F> + Add<T, Output = T> + Eq,
+    F: Field,
+{
+    let mut assigned = a.clone();
+    assigned.mul_assign(*c);
+    check(a.scalar_mul(c) == assigned, "scalar_mul = mul_assign")?;
+    check(
+        (a.clone() + b.clone()).scalar_mul(c) == a.scalar_mul(c) + b.scalar_mul(c),
+        "c(a + b) = ca + cb",
+    )?;
+    check(
+        a.scalar_mul(&(*c + d)) == a.scalar_mul(c) + a.scalar_mul(d),
+        "(c + d)a = ca + da",
+    )?;
+    check(
+        a.scalar_mul(&(*c * d)) == a.scalar_mul(d).scalar_mul(c),
+        "(cd)a = c(da)",
+    )?;
+    check(a.scalar_mul(&F::one()) == a.clone(), "1a = a")
+}
+
+// commit(k, m_1 + m_2) = commit(k, m_1) + commit(k, m_2) and commit(k, c m) = c commit(k, m), and
+// the scalar laws on messages, keys and commitments
+pub fn message_homomorphism<C>(
+    seed: u64,
+    key_len: usize,
+    message_len: usize,
+) -> Result<(), TestCaseError>
+where
+    C: DoublyHomomorphicCommitment,
+    C::Message: UniformRand,
+{
+    let mut rng = StdRng::seed_from_u64(seed);
+    let k = C::setup(&mut rng, key_len).map_err(|e| TestCaseError::fail(e.to_string()))?;
+    let m_1 = sample::<C::Message, _>(&mut rng, message_len);
+    let m_2 = sample::<C::Message, _>(&mut rng, message_len);
+    let c = C::Scalar::rand(&mut rng);
+    let d = C::Scalar::rand(&mut rng);
+    let commit =
+        |m: &[C::Message]| C::commit(&k, m).map_err(|e| TestCaseError::fail(e.to_string()));
+
+    let com_1 = commit(&m_1)?;
+    let com_2 = commit(&m_2)?;
+    check(
+        commit(&sum(&m_1, &m_2))? == com_1.clone() + com_2.clone(),
+        "commit(k, m_1 + m_2) = commit(k, m_1) + commit(k, m_2)",
+    )?;
+    check(
+        commit(&scale(&m_1, &c))? == com_1.scalar_mul(&c),
+        "commit(k, c m) = c commit(k, m)",
+    )?;
+
+    scalar_mul_laws(&m_1[0], &m_2[0], &c, &d)?;
+    scalar_mul_laws(&k[0], &k[key_len - 1], &c, &d)?;
+    scalar_mul_laws(&com_1, &com_2, &c, &d)
+}
+
+// commit(k_1 + k_2, m) = commit(k_1, m) + commit(k_2, m) and commit(c k, m) = c commit(k, m), with
+// commitments summing over positions, which GIPA relies on to fold keys. The key folding and
+// batched commitments of the scheme must agree with these definitions. The identity commitment,
+// whose key is a placeholder, does not have these laws
+pub fn key_homomorphism<C>(seed: u64, len: usize) -> Result<(), TestCaseError>
+where
+    C: DoublyHomomorphicCommitment,
+    C::Message: UniformRand,
+{
+    let fail = |e: crate::Error| TestCaseError::fail(e.to_string());
+    let mut rng = StdRng::seed_from_u64(seed);
+    let k_1 = C::setup(&mut rng, len).map_err(fail)?;
+    let k_2 = C::setup(&mut rng, len).map_err(fail)?;
+    let m = sample::<C::Message, _>(&mut rng, len);
+    let c = C::Scalar::rand(&mut rng);
+
+    let com_1 = C::commit(&k_1, &m).map_err(fail)?;
+    let com_2 = C::commit(&k_2, &m).map_err(fail)?;
+    check(
+        C::commit(&sum(&k_1, &k_2), &m).map_err(fail)? == com_1.clone() + com_2.clone(),
+        "commit(k_1 + k_2, m) = commit(k_1, m) + commit(k_2, m)",
+    )?;
+    check(
+        C::commit(&scale(&k_1, &c), &m).map_err(fail)? == com_1.scalar_mul(&c),
+        "commit(c k, m) = c commit(k, m)",
+    )?;
+
+    let split = len / 2;
+    let (left, right) = C::commit_split(&k_1, &m, split).map_err(fail)?;
+    check(
+        left == C::commit(&k_1[..split], &m[..split]).map_err(fail)?
+            && right == C::commit(&k_1[split..], &m[split..]).map_err(fail)?
+            && left + right == com_1,
+        "commit_split(k, m) sums to commit(k, m)",
+    )?;
+    check(
+        C::commit_batch(&k_1, &[m.clone(), m.clone()]).map_err(fail)? == [com_1.clone(), com_1],
+        "commit_batch = commit",
+    )?;
+
+    let (k_lo, k_hi) = k_1.split_at(split);
+    check(
+        C::fold_keys(&k_1, &c).map_err(fail)? == sum(k_lo, &scale(k_hi, &c)),
+        "fold_keys(k, c) = k[..n/2] + c k[n/2..]",
+    )?;
+    let products = sample::<C::Scalar, _>(&mut rng, len);
+    let msm = k_1
+        .iter()
+        .zip(&products)
+        .map(|(k, c)| k.scalar_mul(c))
+        .reduce(|a, b| a + b)
+        .expect("key is not empty");
+    check(
+        C::fold_keys_msm(&k_1, &products).map_err(fail)? == msm,
+        "fold_keys_msm(k, c) = sum_i c_i k_i",
+    )
+}
+
+// <a_1 + a_2, b> = <a_1, b> + <a_2, b>, <a, b_1 + b_2> = <a, b_1> + <a, b_2> and
+// <c a, b> = <a, c b> = c <a, b>, with inner products summing over positions and the empty inner
+// product the identity. The prepared and chunked inner products must agree
+pub fn inner_product_bilinearity<IP, F>(seed: u64, len: usize) -> Result<(), TestCaseError>
+where
+    IP: InnerProduct,
+    IP::LeftMessage:
+        UniformRand + ScalarMul<F> + Add<IP::LeftMessage, Output = IP::LeftMessage> + Eq,
+    IP::RightMessage:
+        UniformRand + ScalarMul<F> + Add<IP::RightMessage, Output = IP::RightMessage> + Eq,
+    IP::Output: ScalarMul<F> + Add<IP::Output, Output = IP::Output> + Eq,
+    F: Field,
+{
+    let ip = |a: &[IP::LeftMessage], b: &[IP::RightMessage]| {
+        IP::inner_product(a, b).map_err(|e| TestCaseError::fail(e.to_string()))
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let a_1 = sample::<IP::LeftMessage, _>(&mut rng, len);
+    let a_2 = sample::<IP::LeftMessage, _>(&mut rng, len);
+    let b_1 = sample::<IP::RightMessage, _>(&mut rng, len);
+    let b_2 = sample::<IP::RightMessage, _>(&mut rng, len);
+    let c = F::rand(&mut rng);
+    let d = F::rand(&mut rng);
+
+    let t = ip(&a_1, &b_1)?;
+    check(
+        ip(&sum(&a_1, &a_2), &b_1)? == t.clone() + ip(&a_2, &b_1)?,
+        "<a_1 + a_2, b> = <a_1, b> + <a_2, b>",
+    )?;
+    check(
+        ip(&a_1, &sum(&b_1, &b_2))? == t.clone() + ip(&a_1, &b_2)?,
+        "<a, b_1 + b_2> = <a, b_1> + <a, b_2>",
+    )?;
+    check(
+        ip(&scale(&a_1, &c), &b_1)? == t.scalar_mul(&c),
+        "<c a, b> = c <a, b>",
+    )?;
+    check(
+        ip(&a_1, &scale(&b_1, &c))? == t.scalar_mul(&c),
+        "<a, c b> = c <a, b>",
+    )?;
+
+    let split = len / 2;
+    check(
+        ip(&a_1[..split], &b_1[..split])? + ip(&a_1[split..], &b_1[split..])? == t,
+        "<a, b> = <a[..s], b[..s]> + <a[s..], b[s..]>",
+    )?;
+    check(ip(&[], &[])? + t.clone() == t, "<[], []> = 0")?;
+    let prepared = IP::inner_product_prepared(&IP::precompute_left(&a_1), &b_1)
+        .map_err(|e| TestCaseError::fail(e.to_string()))?;
+    check(prepared == t, "prepared inner product = inner product")?;
+    let chunked = IP::inner_product_chunked(&a_1, &b_1, 1 + split)
+        .map_err(|e| TestCaseError::fail(e.to_string()))?;
+    check(chunked == t, "chunked inner product = inner product")?;
+
+    scalar_mul_laws(&a_1[0], &a_2[0], &c, &d)?;
+    scalar_mul_laws(&b_1[0], &b_2[0], &c, &d)?;
+    scalar_mul_laws(&t, &ip(&a_2, &b_2)?, &c, &d)
+}
+
+// Proptest suite of the commitment laws for a scheme, in a module `$name`. With `message_len` only
+// the message laws are checked, for messages of length `message_len(key_len)`, e.g. for schemes
+// that do not commit to messages of the key length
+#[macro_export]
+macro_rules! commitment_laws {
+    ($name:ident, $commitment:ty) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+            use $crate::laws::proptest::prelude::*;
+
+            $crate::laws::proptest::proptest! {
+                #![proptest_config(ProptestConfig::with_cases(16))]
+
+                #[test]
+                fn message_homomorphism(seed in any::<u64>(), log_len in 0usize..4) {
+                    $crate::laws::message_homomorphism::<$commitment>(seed, 1 << log_len, 1 << log_len)?;
+                }
+
+                #[test]
+                fn key_homomorphism(seed in any::<u64>(), log_len in 1usize..4) {
+                    $crate::laws::key_homomorphism::<$commitment>(seed, 1 << log_len)?;
+                }
+            }
+        }
+    };
+    ($name:ident, $commitment:ty, message_len = $message_len:expr) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+            use $crate::laws::proptest::prelude::*;
+
+            $crate::laws::proptest::proptest! {
+                #![proptest_config(ProptestConfig::with_cases(16))]
+
+                #[test]
+                fn message_homomorphism(seed in any::<u64>(), log_len in 0usize..4) {
+                    let message_len: fn(usize) -> usize = $message_len;
+                    $crate::laws::message_homomorphism::<$commitment>(seed, 1 << log_len, message_len(1 << log_len))?;
+                }
+            }
+        }
+    };
+}
+
+// Proptest suite of the bilinearity laws for an inner product over scalars `$scalar`, in a module
+// `$name`
+#[macro_export]
+macro_rules! inner_product_laws {
+    ($name:ident, $inner_product:ty, $scalar:ty) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+            use $crate::laws::proptest::prelude::*;
+
+            $crate::laws::proptest::proptest! {
+                #![proptest_config(ProptestConfig::with_cases(16))]
+
+                #[test]
+                fn bilinearity(seed in any::<u64>(), log_len in 0usize..4) {
+                    $crate::laws::inner_product_bilinearity::<$inner_product, $scalar>(seed, 1 << log_len)?;
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+        identity::IdentityCommitment,
+        pedersen::PedersenCommitment,
+        two_tier::TwoTierCommitment,
+    };
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use ark_inner_products::{
+        ExtensionFieldElement, ExtensionFieldExponentiationInnerProduct,
+        MultiexponentiationInnerProduct, PairingInnerProduct, ScalarInnerProduct,
+    };
+
+    commitment_laws!(afgho_g1, AFGHOCommitmentG1<Bls12_381>);
+    commitment_laws!(afgho_g2, AFGHOCommitmentG2<Bls12_381>);
+    commitment_laws!(pedersen_g1, PedersenCommitment<G1Projective>);
+    commitment_laws!(pedersen_g2, PedersenCommitment<G2Projective>);
+    commitment_laws!(
+        identity_g1,
+        IdentityCommitment<G1Projective, Fr>,
+        message_len = |n| n
+    );
+    commitment_laws!(
+        identity_extension_field,
+        IdentityCommitment<ExtensionFieldElement<Bls12_381>, Fr>,
+        message_len = |n| n
+    );
+    commitment_laws!(
+        two_tier,
+        TwoTierCommitment<PedersenCommitment<G1Projective>, AFGHOCommitmentG1<Bls12_381>>,
+        message_len = |n| n * n
+    );
+
+    inner_product_laws!(pairing, PairingInnerProduct<Bls12_381>, Fr);
+    inner_product_laws!(
+        multiexponentiation,
+        MultiexponentiationInnerProduct<G1Projective>,
+        Fr
+    );
+    inner_product_laws!(scalar, ScalarInnerProduct<Fr>, Fr);
+    inner_product_laws!(
+        extension_field_exponentiation,
+        ExtensionFieldExponentiationInnerProduct<Bls12_381>,
+        Fr
+    );
+}
@@ -1,53 +1,656 @@
-use ark_ec::group::Group;
-use ark_ff::{bytes::ToBytes, fields::PrimeField};
-use rand::Rng;
-use std::{
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::type_complexity)]
+use ark_ec::{AffineRepr, CurveGroup, Group};
+use ark_ff::fields::PrimeField;
+use ark_inner_products::{msm::MsmBackend, InnerProduct, InnerProductError, MsmInnerProduct};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{
+    cfg_iter,
     cmp::Eq,
-    error::Error as ErrorTrait,
     ops::{Add, MulAssign},
+    string::String,
+    vec::Vec,
 };
+use blake2::{Blake2b512, Digest};
+use rand::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use thiserror::Error;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+// Reports work to the `Metrics` hook of ark-inner-products for the current thread, a no-op
+// without the metrics feature
+macro_rules! report_metrics {
+    ($name:ident, $value:expr) => {
+        #[cfg(feature = "metrics")]
+        ark_inner_products::metrics::report(|metrics| metrics.$name($value as u64));
+    };
+}
 
 pub mod afgho16;
+#[cfg(feature = "serde")]
+pub mod canonical_serde;
+pub mod dynamic;
 pub mod identity;
+pub mod incremental;
+#[cfg(any(feature = "laws", test))]
+pub mod laws;
+pub mod matrix;
 pub mod pedersen;
+pub mod rng;
+pub mod two_tier;
+
+use dynamic::CommitmentScheme;
+use rng::WitnessRng;
+
+pub type Error = CommitmentError;
+
+#[derive(Debug, Error)]
+pub enum CommitmentError {
+    #[error("key of length {key} does not match message of length {message}")]
+    KeyMessageLengthMismatch { key: usize, message: usize },
+    #[error("key is empty")]
+    EmptyKey,
+    #[error("key of odd length {0} folded")]
+    OddKeyLength(usize),
+    #[error("key element {0} not in the prime order subgroup")]
+    KeyNotInSubgroup(usize),
+    #[error("key element {0} is the identity")]
+    KeyIsIdentity(usize),
+    #[error("split {split} out of range for length {len}")]
+    SplitOutOfRange { split: usize, len: usize },
+    #[error("key rerandomized by zero")]
+    RerandomizedByZero,
+    #[error("block size must be positive")]
+    BlockSizeZero,
+    #[error("block {block} of key with {num_blocks} blocks")]
+    BlockOutOfRange { block: usize, num_blocks: usize },
+    #[error("row {row} of {num_rows} row commitments")]
+    RowOutOfRange { row: usize, num_rows: usize },
+    #[error("unknown commitment scheme {0}")]
+    UnknownScheme(String),
+    #[error("{what} of the wrong group for {scheme} commitments")]
+    SchemeMismatch {
+        scheme: CommitmentScheme,
+        what: &'static str,
+    },
+    #[error("structured commitment keys require a trusted setup")]
+    TrustedSetupRequired,
+    #[error("commitment scheme has no seeded setup")]
+    SeededSetupUnsupported,
+    #[error(transparent)]
+    InnerProduct(#[from] InnerProductError),
+    // Not a source, as without std serialization errors do not implement the core error trait
+    #[error("serialization: {0}")]
+    Serialization(SerializationError),
+}
+
+impl From<SerializationError> for CommitmentError {
+    fn from(e: SerializationError) -> Self {
+        CommitmentError::Serialization(e)
+    }
+}
+
+// Validation shared by all schemes
+
+pub fn check_message_length(key_len: usize, message_len: usize) -> Result<(), Error> {
+    if key_len != message_len {
+        return Err(CommitmentError::KeyMessageLengthMismatch {
+            key: key_len,
+            message: message_len,
+        });
+    }
+    Ok(())
+}
+
+pub fn check_split(len: usize, split: usize) -> Result<(), Error> {
+    if split > len {
+        return Err(CommitmentError::SplitOutOfRange { split, len });
+    }
+    Ok(())
+}
 
-pub type Error = Box<dyn ErrorTrait>;
+// Checks that untrusted generators are non-identity elements of the prime order subgroup
+pub fn validate_generators<G: CurveGroup>(k: &[G]) -> Result<(), Error> {
+    for (i, g) in k.iter().enumerate() {
+        if g.is_zero() {
+            return Err(CommitmentError::KeyIsIdentity(i));
+        }
+        if !g.mul_bigint(G::ScalarField::MODULUS).is_zero() {
+            return Err(CommitmentError::KeyNotInSubgroup(i));
+        }
+    }
+    Ok(())
+}
+
+// Chunks of MSMs and multi-pairings are computed on separate threads, one chunk per thread
+#[cfg(feature = "parallel")]
+pub(crate) use ark_inner_products::parallel_chunk_size;
 
-//TODO: support CanonicalSerialize
-//TODO: Using MulAssign instead of Mul because Group does not support Mul
+// Scalar action on messages, keys and outputs. Blanket implemented from MulAssign since Group does
+// not support Mul.
+pub trait ScalarMul<F: Clone>: MulAssign<F> + Clone {
+    fn scalar_mul(&self, f: &F) -> Self {
+        let mut clone = self.clone();
+        clone.mul_assign(f.clone());
+        clone
+    }
+}
+
+impl<T: MulAssign<F> + Clone, F: Clone> ScalarMul<F> for T {}
 
 pub trait DoublyHomomorphicCommitment: Clone {
     type Scalar: PrimeField;
-    type Message: ToBytes
+    type Message: CanonicalSerialize
         + Clone
         + Default
         + Eq
         + Add<Self::Message, Output = Self::Message>
-        + MulAssign<Self::Scalar>;
-    type Key: ToBytes
+        + ScalarMul<Self::Scalar>
+        + Send
+        + Sync;
+    type Key: CanonicalSerialize
+        + CanonicalDeserialize
         + Clone
         + Default
         + Eq
         + Add<Self::Key, Output = Self::Key>
-        + MulAssign<Self::Scalar>;
-    type Output: ToBytes
+        + ScalarMul<Self::Scalar>
+        + Send
+        + Sync;
+    type Output: CanonicalSerialize
+        + CanonicalDeserialize
         + Clone
         + Default
         + Eq
         + Add<Self::Output, Output = Self::Output>
-        + MulAssign<Self::Scalar>;
+        + ScalarMul<Self::Scalar>
+        + Send
+        + Sync;
+
+    fn setup<R: RngCore + CryptoRng>(r: &mut R, size: usize) -> Result<Vec<Self::Key>, Error>;
 
-    fn setup<R: Rng>(r: &mut R, size: usize) -> Result<Vec<Self::Key>, Error>;
+    // Transparent key derived from a public seed, without trapdoor. Schemes without a hash to their
+    // key space keep the default, which errors.
+    fn setup_from_seed(_seed: &[u8], _size: usize) -> Result<Vec<Self::Key>, Error> {
+        Err(CommitmentError::SeededSetupUnsupported)
+    }
 
     fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error>;
 
     fn verify(k: &[Self::Key], m: &[Self::Message], com: &Self::Output) -> Result<bool, Error> {
         Ok(Self::commit(k, m)? == *com)
     }
+
+    // Checks keys from untrusted sources, e.g. group elements outside the prime order subgroup
+    fn validate_key(_k: &[Self::Key]) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn verify_with_untrusted_key(
+        k: &[Self::Key],
+        m: &[Self::Message],
+        com: &Self::Output,
+    ) -> Result<bool, Error> {
+        Self::validate_key(k)?;
+        Self::verify(k, m, com)
+    }
+
+    // Fingerprint of the key, for binding it into transcripts, caching and detecting mismatched keys
+    // between prover and verifier deployments
+    fn key_digest<D: Digest>(k: &[Self::Key]) -> Result<Vec<u8>, Error> {
+        key_digest::<D, _>(k)
+    }
+
+    fn open(
+        k: &[Self::Key],
+        m: &[Self::Message],
+    ) -> Result<(Self::Output, CommitmentOpening<Self::Message, NoRandomness>), Error> {
+        Ok((
+            Self::commit(k, m)?,
+            CommitmentOpening::new(m.to_vec(), NoRandomness),
+        ))
+    }
+
+    fn verify_opening(
+        k: &[Self::Key],
+        com: &Self::Output,
+        opening: &CommitmentOpening<Self::Message, NoRandomness>,
+    ) -> Result<bool, Error> {
+        Self::verify(k, &opening.message, com)
+    }
+
+    // Checks many openings under the same key at once, by checking a random linear combination of
+    // the openings with a single commitment
+    fn verify_batch<R: RngCore + CryptoRng>(
+        k: &[Self::Key],
+        openings: &[(Vec<Self::Message>, Self::Output)],
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let rho = batch_verification_scalars(k.len(), openings, rng)?;
+        if openings.is_empty() {
+            return Ok(true);
+        }
+        let combine = |j: usize| {
+            let (m_j, com_j) = &openings[j];
+            (
+                m_j.iter()
+                    .map(|m| m.scalar_mul(&rho[j]))
+                    .collect::<Vec<_>>(),
+                com_j.scalar_mul(&rho[j]),
+            )
+        };
+        let sum = |(m, com): (Vec<Self::Message>, Self::Output), (m_j, com_j)| {
+            (
+                m.into_iter().zip(m_j).map(|(m, m_j)| m + m_j).collect(),
+                com + com_j,
+            )
+        };
+        #[cfg(feature = "parallel")]
+        let combined = (0..openings.len())
+            .into_par_iter()
+            .map(combine)
+            .reduce_with(sum);
+        #[cfg(not(feature = "parallel"))]
+        let combined = (0..openings.len()).map(combine).reduce(sum);
+        let (m, com) = combined.expect("openings are not empty");
+        Ok(Self::commit(k, &m)? == com)
+    }
+
+    // Commitments to m[..split] under k[..split] and to m[split..] under k[split..], as computed by
+    // the GIPA prover every round. Implementations share the preparation of the key and message.
+    fn commit_split(
+        k: &[Self::Key],
+        m: &[Self::Message],
+        split: usize,
+    ) -> Result<(Self::Output, Self::Output), Error> {
+        check_message_length(k.len(), m.len())?;
+        check_split(k.len(), split)?;
+        Ok((
+            Self::commit(&k[..split], &m[..split])?,
+            Self::commit(&k[split..], &m[split..])?,
+        ))
+    }
+
+    // Commits to many messages under the same key, implementations share the preparation of the key
+    fn commit_batch(
+        k: &[Self::Key],
+        ms: &[Vec<Self::Message>],
+    ) -> Result<Vec<Self::Output>, Error> {
+        cfg_iter!(ms).map(|m| Self::commit(k, m)).collect()
+    }
+
+    // Folds the halves of a key as k[..n/2] + c * k[n/2..], the GIPA recursion step
+    fn fold_keys(k: &[Self::Key], c: &Self::Scalar) -> Result<Vec<Self::Key>, Error> {
+        let (k_1, k_2) = split_halves(k)?;
+        Ok(cfg_iter!(k_1)
+            .zip(k_2)
+            .map(|(k_1, k_2)| k_1.clone() + k_2.scalar_mul(c))
+            .collect())
+    }
+
+    // Folds a key to the single key sum_i c_i * k[i] given the products c_i of the challenges
+    fn fold_keys_msm(
+        k: &[Self::Key],
+        challenge_products: &[Self::Scalar],
+    ) -> Result<Self::Key, Error> {
+        check_message_length(k.len(), challenge_products.len())?;
+        if k.is_empty() {
+            return Err(CommitmentError::EmptyKey);
+        }
+        let terms = cfg_iter!(k)
+            .zip(challenge_products)
+            .map(|(k, c)| k.scalar_mul(c));
+        #[cfg(feature = "parallel")]
+        let sum = terms.reduce_with(|a, b| a + b);
+        #[cfg(not(feature = "parallel"))]
+        let sum = terms.reduce(|a, b| a + b);
+        Ok(sum.expect("key is not empty"))
+    }
+}
+
+// Hiding commitments add a commitment to zero with fresh randomness under an independent blinding
+// key, so they remain doubly homomorphic when summing both messages and randomness
+pub trait HidingCommitment: DoublyHomomorphicCommitment {
+    type BlindingKey: Clone;
+    type Randomness: Clone;
+
+    fn setup_blinding_key<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Self::BlindingKey, Error>;
+
+    // Randomness for blinding a message of the given length
+    fn sample_randomness<R: RngCore + CryptoRng>(rng: &mut R, size: usize) -> Self::Randomness;
+
+    fn blind(blinding_key: &Self::BlindingKey, r: &Self::Randomness)
+        -> Result<Self::Output, Error>;
+
+    fn commit_with_randomness(
+        k: &[Self::Key],
+        blinding_key: &Self::BlindingKey,
+        m: &[Self::Message],
+        r: &Self::Randomness,
+    ) -> Result<Self::Output, Error> {
+        Ok(Self::commit(k, m)? + Self::blind(blinding_key, r)?)
+    }
+
+    fn verify_with_randomness(
+        k: &[Self::Key],
+        blinding_key: &Self::BlindingKey,
+        m: &[Self::Message],
+        r: &Self::Randomness,
+        com: &Self::Output,
+    ) -> Result<bool, Error> {
+        Ok(Self::commit_with_randomness(k, blinding_key, m, r)? == *com)
+    }
+
+    // Commits with fresh randomness, returned as part of the opening
+    fn open_hiding<R: RngCore + CryptoRng>(
+        k: &[Self::Key],
+        blinding_key: &Self::BlindingKey,
+        m: &[Self::Message],
+        rng: &mut R,
+    ) -> Result<
+        (
+            Self::Output,
+            CommitmentOpening<Self::Message, Self::Randomness>,
+        ),
+        Error,
+    > {
+        let r = Self::sample_randomness(rng, m.len());
+        Ok((
+            Self::commit_with_randomness(k, blinding_key, m, &r)?,
+            CommitmentOpening::new(m.to_vec(), r),
+        ))
+    }
+
+    // Commits with randomness derived from a secret seed and the message, as with `WitnessRng`, so
+    // the same seed and message give the same commitment and opening
+    fn open_hiding_deterministic(
+        k: &[Self::Key],
+        blinding_key: &Self::BlindingKey,
+        m: &[Self::Message],
+        seed: &[u8],
+    ) -> Result<
+        (
+            Self::Output,
+            CommitmentOpening<Self::Message, Self::Randomness>,
+        ),
+        Error,
+    >
+    where
+        Self::Message: CanonicalSerialize,
+    {
+        Self::open_hiding(k, blinding_key, m, &mut WitnessRng::new(seed, m)?)
+    }
+
+    fn verify_hiding_opening(
+        k: &[Self::Key],
+        blinding_key: &Self::BlindingKey,
+        com: &Self::Output,
+        opening: &CommitmentOpening<Self::Message, Self::Randomness>,
+    ) -> Result<bool, Error> {
+        Self::verify_with_randomness(k, blinding_key, &opening.message, &opening.randomness, com)
+    }
+}
+
+// Opening of a commitment to a message, with the randomness of hiding commitments
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CommitmentOpening<M, R> {
+    pub message: Vec<M>,
+    pub randomness: R,
+}
+
+impl<M, R> CommitmentOpening<M, R> {
+    pub fn new(message: Vec<M>, randomness: R) -> Self {
+        CommitmentOpening {
+            message,
+            randomness,
+        }
+    }
+}
+
+impl<M: CanonicalSerialize, R: CanonicalSerialize> CanonicalSerialize for CommitmentOpening<M, R> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.message.serialize_with_mode(&mut writer, compress)?;
+        self.randomness.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.message.serialized_size(compress) + self.randomness.serialized_size(compress)
+    }
+}
+
+impl<M: Valid, R: Valid> Valid for CommitmentOpening<M, R> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.message.check()?;
+        self.randomness.check()
+    }
+}
+
+impl<M: CanonicalDeserialize, R: CanonicalDeserialize> CanonicalDeserialize
+    for CommitmentOpening<M, R>
+{
+    fn deserialize_with_mode<Rd: Read>(
+        mut reader: Rd,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(CommitmentOpening {
+            message: Vec::<M>::deserialize_with_mode(&mut reader, compress, validate)?,
+            randomness: R::deserialize_with_mode(&mut reader, compress, validate)?,
+        })
+    }
+}
+
+// Randomness of the openings of non-hiding commitments
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NoRandomness;
+
+impl CanonicalSerialize for NoRandomness {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        _writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
+        Ok(())
+    }
+
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        0
+    }
+}
+
+impl Valid for NoRandomness {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for NoRandomness {
+    fn deserialize_with_mode<R: Read>(
+        _reader: R,
+        _compress: Compress,
+        _validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(NoRandomness)
+    }
 }
 
 // Helpers for generator commitment keys used by Pedersen and AFGHO16
 
-pub fn random_generators<R: Rng, G: Group>(rng: &mut R, num: usize) -> Vec<G> {
+// Random scalars for batch verification, after checking that every message fits the key
+pub(crate) fn batch_verification_scalars<F: PrimeField, M, O, R: RngCore + CryptoRng>(
+    len: usize,
+    openings: &[(Vec<M>, O)],
+    rng: &mut R,
+) -> Result<Vec<F>, Error> {
+    for (m, _) in openings {
+        check_message_length(len, m.len())?;
+    }
+    Ok((0..openings.len()).map(|_| F::rand(rng)).collect())
+}
+
+fn split_halves<T>(k: &[T]) -> Result<(&[T], &[T]), Error> {
+    if !k.len().is_multiple_of(2) {
+        return Err(CommitmentError::OddKeyLength(k.len()));
+    }
+    Ok(k.split_at(k.len() / 2))
+}
+
+// Folding of generator keys, by backend `M`
+pub fn fold_generators<G: CurveGroup, M: MsmBackend<G>>(
+    k: &[G],
+    c: &G::ScalarField,
+) -> Result<Vec<G>, Error> {
+    let (k_1, k_2) = split_halves(k)?;
+    Ok(M::fold_bases(k_1, k_2, c)?)
+}
+
+pub fn fold_generators_msm<G: CurveGroup, M: MsmBackend<G>>(
+    k: &[G],
+    challenge_products: &[G::ScalarField],
+) -> Result<G, Error> {
+    check_message_length(k.len(), challenge_products.len())?;
+    Ok(MsmInnerProduct::<G, M>::inner_product(
+        k,
+        challenge_products,
+    )?)
+}
+
+// Generator key stored in affine form, batch normalized once instead of at every MSM or pairing.
+// Serialization matches that of the projective key, whose points are serialized in affine form.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AffineKey<G: CurveGroup> {
+    generators: Vec<G::Affine>,
+}
+
+impl<G: CurveGroup> AffineKey<G> {
+    pub fn from_projective(k: &[G]) -> Self {
+        AffineKey {
+            generators: G::normalize_batch(k),
+        }
+    }
+
+    pub fn to_projective(&self) -> Vec<G> {
+        self.generators.iter().map(|g| g.into_group()).collect()
+    }
+
+    pub fn generators(&self) -> &[G::Affine] {
+        &self.generators
+    }
+
+    pub fn len(&self) -> usize {
+        self.generators.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.generators.is_empty()
+    }
+
+    // Equal to the digest of the projective key
+    pub fn digest<D: Digest>(&self) -> Result<Vec<u8>, Error> {
+        key_digest::<D, _>(&self.generators)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<G: CurveGroup> Zeroize for AffineKey<G> {
+    fn zeroize(&mut self) {
+        self.generators.zeroize();
+    }
+}
+
+impl<G: CurveGroup> CanonicalSerialize for AffineKey<G> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.generators.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.generators.serialized_size(compress)
+    }
+}
+
+impl<G: CurveGroup> Valid for AffineKey<G> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.generators.check()
+    }
+}
+
+impl<G: CurveGroup> CanonicalDeserialize for AffineKey<G> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(AffineKey {
+            generators: Vec::<G::Affine>::deserialize_with_mode(reader, compress, validate)?,
+        })
+    }
+}
+
+// Hash of the canonical serialization of a key, which is that of the key as a Vec
+pub fn key_digest<D: Digest, K: CanonicalSerialize>(k: &[K]) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    (k.len() as u64).serialize_compressed(&mut bytes)?;
+    for k_i in k {
+        k_i.serialize_compressed(&mut bytes)?;
+    }
+    Ok(D::digest(&bytes).to_vec())
+}
+
+// Generators depending on the rng, for trusted setups. Fixtures and test vectors use
+// derive_generators instead.
+pub fn random_generators<R: RngCore + CryptoRng, G: Group>(rng: &mut R, num: usize) -> Vec<G> {
     (0..num).map(|_| G::rand(rng)).collect()
 }
+
+// Deterministic generators for a domain tag, e.g. b"my-protocol-v1/ck_a", reproducible across
+// implementations. Generator i does not depend on num, so shorter sets are prefixes of longer ones.
+pub fn derive_generators<G: CurveGroup>(domain_tag: &[u8], num: usize) -> Vec<G> {
+    hash_to_generators(domain_tag, &[], num)
+}
+
+// Try-and-increment hash to curve of (domain, seed, index), clearing the cofactor. The domain
+// separates the keys of different schemes derived from the same seed.
+pub fn hash_to_generators<G: CurveGroup>(domain: &[u8], seed: &[u8], num: usize) -> Vec<G> {
+    let num_bytes = 2 * G::Affine::generator().uncompressed_size();
+    (0..num)
+        .map(|i| {
+            let mut counter_nonce: u64 = 0;
+            loop {
+                let mut bytes = Vec::with_capacity(num_bytes);
+                let mut block: u64 = 0;
+                while bytes.len() < num_bytes {
+                    let mut hash_input = Vec::new();
+                    hash_input.extend_from_slice(&(domain.len() as u64).to_be_bytes());
+                    hash_input.extend_from_slice(domain);
+                    hash_input.extend_from_slice(&(seed.len() as u64).to_be_bytes());
+                    hash_input.extend_from_slice(seed);
+                    hash_input.extend_from_slice(&(i as u64).to_be_bytes());
+                    hash_input.extend_from_slice(&counter_nonce.to_be_bytes());
+                    hash_input.extend_from_slice(&block.to_be_bytes());
+                    bytes.extend_from_slice(&Blake2b512::digest(&hash_input));
+                    block += 1;
+                }
+                if let Some(p) = G::Affine::from_random_bytes(&bytes) {
+                    let g = p.mul_by_cofactor_to_group();
+                    if !g.is_zero() {
+                        break g;
+                    }
+                }
+                counter_nonce += 1;
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,177 @@
+use ark_std::{marker::PhantomData, vec::Vec};
+use rand::{CryptoRng, RngCore};
+
+use crate::{check_message_length, CommitmentError, DoublyHomomorphicCommitment, Error};
+
+// Commits to each row of an m x n matrix under a shared row key of length n, and to the m row
+// commitments under a key of length m of a second commitment scheme
+#[derive(Clone)]
+pub struct MatrixCommitment<C1, C2> {
+    _row_commitment: PhantomData<C1>,
+    _combined_commitment: PhantomData<C2>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq)]
+pub struct MatrixCommitmentKey<K1, K2> {
+    pub row_key: Vec<K1>,
+    pub combining_key: Vec<K2>,
+}
+
+impl<K1, K2> MatrixCommitmentKey<K1, K2> {
+    pub fn num_rows(&self) -> usize {
+        self.combining_key.len()
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.row_key.len()
+    }
+}
+
+#[derive(Clone, Default, Eq, PartialEq)]
+pub struct MatrixCommitmentOutput<O1, O2> {
+    pub row_commitments: Vec<O1>,
+    pub commitment: O2,
+}
+
+impl<O1, O2> MatrixCommitmentOutput<O1, O2> {
+    pub fn row(&self, i: usize) -> Option<&O1> {
+        self.row_commitments.get(i)
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.row_commitments.len()
+    }
+}
+
+impl<C1, C2> MatrixCommitment<C1, C2>
+where
+    C1: DoublyHomomorphicCommitment,
+    C2: DoublyHomomorphicCommitment<Scalar = C1::Scalar, Message = C1::Output>,
+{
+    pub fn setup<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        num_rows: usize,
+        num_columns: usize,
+    ) -> Result<MatrixCommitmentKey<C1::Key, C2::Key>, Error> {
+        Ok(MatrixCommitmentKey {
+            row_key: C1::setup(rng, num_columns)?,
+            combining_key: C2::setup(rng, num_rows)?,
+        })
+    }
+
+    pub fn commit_rows(
+        ck: &MatrixCommitmentKey<C1::Key, C2::Key>,
+        matrix: &[Vec<C1::Message>],
+    ) -> Result<Vec<C1::Output>, Error> {
+        check_message_length(ck.num_rows(), matrix.len())?;
+        C1::commit_batch(&ck.row_key, matrix)
+    }
+
+    pub fn combine(
+        ck: &MatrixCommitmentKey<C1::Key, C2::Key>,
+        row_commitments: &[C1::Output],
+    ) -> Result<C2::Output, Error> {
+        C2::commit(&ck.combining_key, row_commitments)
+    }
+
+    pub fn commit(
+        ck: &MatrixCommitmentKey<C1::Key, C2::Key>,
+        matrix: &[Vec<C1::Message>],
+    ) -> Result<MatrixCommitmentOutput<C1::Output, C2::Output>, Error> {
+        let row_commitments = Self::commit_rows(ck, matrix)?;
+        let commitment = Self::combine(ck, &row_commitments)?;
+        Ok(MatrixCommitmentOutput {
+            row_commitments,
+            commitment,
+        })
+    }
+
+    pub fn verify(
+        ck: &MatrixCommitmentKey<C1::Key, C2::Key>,
+        matrix: &[Vec<C1::Message>],
+        com: &MatrixCommitmentOutput<C1::Output, C2::Output>,
+    ) -> Result<bool, Error> {
+        Ok(Self::commit(ck, matrix)? == *com)
+    }
+
+    // Checks that the row commitments open the combined commitment
+    pub fn verify_row_commitments(
+        ck: &MatrixCommitmentKey<C1::Key, C2::Key>,
+        com: &MatrixCommitmentOutput<C1::Output, C2::Output>,
+    ) -> Result<bool, Error> {
+        C2::verify(&ck.combining_key, &com.row_commitments, &com.commitment)
+    }
+
+    // Opens a single row against its row commitment, which is checked against the combined
+    // commitment with verify_row_commitments
+    pub fn verify_row(
+        ck: &MatrixCommitmentKey<C1::Key, C2::Key>,
+        i: usize,
+        row: &[C1::Message],
+        com: &MatrixCommitmentOutput<C1::Output, C2::Output>,
+    ) -> Result<bool, Error> {
+        match com.row(i) {
+            Some(row_com) => C1::verify(&ck.row_key, row, row_com),
+            None => Err(CommitmentError::RowOutOfRange {
+                row: i,
+                num_rows: com.num_rows(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{afgho16::AFGHOCommitmentG1, pedersen::PedersenCommitment};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ec::pairing::Pairing;
+    use ark_ff::{One, UniformRand};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type C = MatrixCommitment<
+        PedersenCommitment<<Bls12_381 as Pairing>::G1>,
+        AFGHOCommitmentG1<Bls12_381>,
+    >;
+    const NUM_ROWS: usize = 4;
+    const NUM_COLUMNS: usize = 8;
+
+    #[test]
+    fn matrix_commitment_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ck = C::setup(&mut rng, NUM_ROWS, NUM_COLUMNS).unwrap();
+        assert_eq!(ck.num_rows(), NUM_ROWS);
+        assert_eq!(ck.num_columns(), NUM_COLUMNS);
+        let matrix = (0..NUM_ROWS)
+            .map(|_| {
+                (0..NUM_COLUMNS)
+                    .map(|_| Fr::rand(&mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let com = C::commit(&ck, &matrix).unwrap();
+        assert_eq!(com.num_rows(), NUM_ROWS);
+        assert!(C::verify(&ck, &matrix, &com).unwrap());
+        assert!(C::verify_row_commitments(&ck, &com).unwrap());
+        for (i, row) in matrix.iter().enumerate() {
+            assert!(C::verify_row(&ck, i, row, &com).unwrap());
+        }
+        assert!(!C::verify_row(&ck, 0, &matrix[1], &com).unwrap());
+        assert!(C::verify_row(&ck, NUM_ROWS, &matrix[0], &com).is_err());
+
+        let mut wrong_matrix = matrix.clone();
+        wrong_matrix[2][3] += Fr::one();
+        assert!(!C::verify(&ck, &wrong_matrix, &com).unwrap());
+        let mut wrong_com = com.clone();
+        wrong_com.row_commitments[2] = C::commit(&ck, &wrong_matrix).unwrap().row_commitments[2];
+        assert!(!C::verify_row_commitments(&ck, &wrong_com).unwrap());
+
+        assert!(matches!(
+            C::commit(&ck, &matrix[1..]),
+            Err(CommitmentError::KeyMessageLengthMismatch { .. })
+        ));
+        wrong_matrix[0].pop();
+        assert!(C::commit(&ck, &wrong_matrix).is_err());
+    }
+}
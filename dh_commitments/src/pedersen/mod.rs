@@ -1,39 +1,436 @@
-use ark_ec::ProjectiveCurve;
-use rand::Rng;
-use std::marker::PhantomData;
+use ark_ec::{scalar_mul::fixed_base::FixedBase, CurveGroup};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{marker::PhantomData, vec, vec::Vec};
+use rand::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
-use crate::{random_generators, DoublyHomomorphicCommitment, Error};
+#[cfg(feature = "parallel")]
+use crate::parallel_chunk_size;
+use crate::{
+    batch_verification_scalars, check_message_length, fold_generators, hash_to_generators,
+    random_generators, split_halves, validate_generators, AffineKey, CommitmentError,
+    DoublyHomomorphicCommitment, Error, HidingCommitment,
+};
 
-use ark_inner_products::{InnerProduct, MultiexponentiationInnerProduct};
+use ark_inner_products::msm::{MsmBackend, PippengerMsm};
 
+#[cfg(feature = "ristretto")]
+pub mod ristretto;
+
+const PREPARED_KEY_WINDOW_SIZE: usize = 4;
+const PEDERSEN_DOMAIN: &[u8] = b"ripp-pedersen";
+
+// Prime order group a Pedersen commitment can be instantiated over, computing its multi-scalar
+// multiplications with backend `M`. Implemented for every arkworks curve and every `MsmBackend` of
+// it; other groups, e.g. Ristretto in `ristretto` under the ristretto feature, provide their
+// multi-scalar multiplication and a hash to the group for transparent keys, with the default
+// backend only selecting their implementation.
+pub trait PedersenGroup<M = PippengerMsm>: ark_ec::Group {
+    fn multi_scalar_mul(bases: &[Self], scalars: &[Self::ScalarField]) -> Result<Self, Error>;
+
+    // Generators of unknown discrete logarithms to one another, derived from (domain, seed)
+    fn hash_to_generators(domain: &[u8], seed: &[u8], num: usize) -> Vec<Self>;
+
+    fn multi_scalar_mul_batch(
+        bases: &[Self],
+        scalars: &[Vec<Self::ScalarField>],
+    ) -> Result<Vec<Self>, Error> {
+        scalars
+            .iter()
+            .map(|s| Self::multi_scalar_mul(bases, s))
+            .collect()
+    }
+
+    // Untrusted generators must not be the identity
+    fn validate_generators(k: &[Self]) -> Result<(), Error> {
+        match k.iter().position(|g| g.is_zero()) {
+            Some(i) => Err(CommitmentError::KeyIsIdentity(i)),
+            None => Ok(()),
+        }
+    }
+
+    // Folding of the key in the GIPA rounds
+    fn fold_generators(k: &[Self], c: &Self::ScalarField) -> Result<Vec<Self>, Error> {
+        let (k_1, k_2) = split_halves(k)?;
+        Ok(k_1
+            .iter()
+            .zip(k_2)
+            .map(|(g_1, g_2)| *g_1 + *g_2 * c)
+            .collect())
+    }
+}
+
+impl<G: CurveGroup, M: MsmBackend<G>> PedersenGroup<M> for G {
+    fn multi_scalar_mul(bases: &[Self], scalars: &[Self::ScalarField]) -> Result<Self, Error> {
+        check_message_length(bases.len(), scalars.len())?;
+        report_metrics!(msm_terms, bases.len());
+        #[cfg(feature = "parallel")]
+        let msm = {
+            let chunk_size = parallel_chunk_size(bases.len());
+            bases
+                .par_chunks(chunk_size)
+                .zip(scalars.par_chunks(chunk_size))
+                .map(|(b, s)| projective_msm::<G, M>(b, s))
+                .sum()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let msm = projective_msm::<G, M>(bases, scalars);
+        msm
+    }
+
+    fn hash_to_generators(domain: &[u8], seed: &[u8], num: usize) -> Vec<Self> {
+        hash_to_generators(domain, seed, num)
+    }
+
+    fn validate_generators(k: &[Self]) -> Result<(), Error> {
+        validate_generators(k)
+    }
+
+    fn multi_scalar_mul_batch(
+        bases: &[Self],
+        scalars: &[Vec<Self::ScalarField>],
+    ) -> Result<Vec<Self>, Error> {
+        let aff_k = AffineKey::from_projective(bases);
+        scalars
+            .iter()
+            .map(|m| PedersenCommitment::<G, M>::commit_affine(&aff_k, m))
+            .collect()
+    }
+
+    fn fold_generators(k: &[Self], c: &Self::ScalarField) -> Result<Vec<Self>, Error> {
+        fold_generators::<G, M>(k, c)
+    }
+}
+
+fn projective_msm<G: CurveGroup, M: MsmBackend<G>>(
+    bases: &[G],
+    scalars: &[G::ScalarField],
+) -> Result<G, Error> {
+    let scalars = scalars.iter().map(|x| x.into_bigint()).collect::<Vec<_>>();
+    Ok(M::multi_scalar_mul(&G::normalize_batch(bases), &scalars)?)
+}
+
+// Pedersen commitment over group `G`, whose MSMs and key folding go through backend `M`
 #[derive(Clone)]
-pub struct PedersenCommitment<G: ProjectiveCurve> {
+pub struct PedersenCommitment<G: PedersenGroup<M>, M: Clone = PippengerMsm> {
     _group: PhantomData<G>,
+    _msm: PhantomData<M>,
 }
 
-impl<G: ProjectiveCurve> DoublyHomomorphicCommitment for PedersenCommitment<G> {
+impl<G: PedersenGroup<M>, M: Clone> DoublyHomomorphicCommitment for PedersenCommitment<G, M> {
     type Scalar = G::ScalarField;
     type Message = G::ScalarField;
     type Key = G;
     type Output = G;
 
-    fn setup<R: Rng>(rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
+    fn setup<R: RngCore + CryptoRng>(rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
         Ok(random_generators(rng, size))
     }
 
+    fn setup_from_seed(seed: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        Ok(G::hash_to_generators(PEDERSEN_DOMAIN, seed, size))
+    }
+
     fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
-        Ok(MultiexponentiationInnerProduct::<G>::inner_product(k, m)?)
+        check_message_length(k.len(), m.len())?;
+        G::multi_scalar_mul(k, m)
+    }
+
+    fn validate_key(k: &[Self::Key]) -> Result<(), Error> {
+        G::validate_generators(k)
+    }
+
+    // Single MSM over the key and the commitments, checking sum_j rho_j (<k, m_j> - com_j) = 0
+    fn verify_batch<R: RngCore + CryptoRng>(
+        k: &[Self::Key],
+        openings: &[(Vec<Self::Message>, Self::Output)],
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let rho = batch_verification_scalars::<G::ScalarField, _, _, _>(k.len(), openings, rng)?;
+        let mut bases = k.to_vec();
+        let mut scalars = vec![G::ScalarField::zero(); k.len()];
+        for ((m_j, com_j), rho_j) in openings.iter().zip(&rho) {
+            for (x, m) in scalars.iter_mut().zip(m_j) {
+                *x += *m * rho_j;
+            }
+            bases.push(*com_j);
+        }
+        scalars.extend(rho.iter().map(|rho_j| -*rho_j));
+        Ok(G::multi_scalar_mul(&bases, &scalars)?.is_zero())
+    }
+
+    fn fold_keys(k: &[Self::Key], c: &Self::Scalar) -> Result<Vec<Self::Key>, Error> {
+        G::fold_generators(k, c)
+    }
+
+    fn fold_keys_msm(
+        k: &[Self::Key],
+        challenge_products: &[Self::Scalar],
+    ) -> Result<Self::Key, Error> {
+        G::multi_scalar_mul(k, challenge_products)
+    }
+
+    fn commit_batch(
+        k: &[Self::Key],
+        ms: &[Vec<Self::Message>],
+    ) -> Result<Vec<Self::Output>, Error> {
+        for m in ms {
+            check_message_length(k.len(), m.len())?;
+        }
+        G::multi_scalar_mul_batch(k, ms)
+    }
+}
+
+// Fixed-base window tables of every generator of a Pedersen commitment key, for keys used for
+// many commitments. Each table stores (2^window_size) * (scalar bits / window_size) points.
+#[derive(Clone)]
+pub struct PreparedPedersenKey<G: CurveGroup> {
+    window_size: usize,
+    num_windows: usize,
+    tables: Vec<Vec<Vec<G::Affine>>>,
+}
+
+impl<G: CurveGroup> PreparedPedersenKey<G> {
+    pub fn new(k: &[G], window_size: usize) -> Self {
+        let scalar_bits = G::ScalarField::MODULUS_BIT_SIZE as usize;
+        PreparedPedersenKey {
+            window_size,
+            num_windows: scalar_bits.div_ceil(window_size),
+            tables: k
+                .iter()
+                .map(|g| FixedBase::get_window_table(scalar_bits, window_size, *g))
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+}
+
+// The window size followed by the tables, e.g. for caching prepared keys on disk. Uncompressed
+// serializations are read back quickly with `deserialize_uncompressed_unchecked` from a trusted
+// source
+impl<G: CurveGroup> CanonicalSerialize for PreparedPedersenKey<G> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        (self.window_size as u64).serialize_with_mode(&mut writer, compress)?;
+        self.tables.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        (self.window_size as u64).serialized_size(compress) + self.tables.serialized_size(compress)
+    }
+}
+
+impl<G: CurveGroup> PreparedPedersenKey<G> {
+    fn from_parts(
+        window_size: u64,
+        tables: Vec<Vec<Vec<G::Affine>>>,
+    ) -> Result<Self, SerializationError> {
+        let scalar_bits = G::ScalarField::MODULUS_BIT_SIZE as usize;
+        let window_size = window_size as usize;
+        if window_size == 0 || window_size > scalar_bits {
+            return Err(SerializationError::InvalidData);
+        }
+        let num_windows = scalar_bits.div_ceil(window_size);
+        if tables.iter().any(|table| table.len() != num_windows) {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(PreparedPedersenKey {
+            window_size,
+            num_windows,
+            tables,
+        })
+    }
+}
+
+impl<G: CurveGroup> Valid for PreparedPedersenKey<G> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.tables.check()
+    }
+}
+
+impl<G: CurveGroup> CanonicalDeserialize for PreparedPedersenKey<G> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let window_size = u64::deserialize_with_mode(&mut reader, compress, validate)?;
+        Self::from_parts(
+            window_size,
+            CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+        )
+    }
+}
+
+// Window tables are multiples of the generators, so are cleared along with secret keys
+#[cfg(feature = "zeroize")]
+impl<G: CurveGroup> Zeroize for PreparedPedersenKey<G> {
+    fn zeroize(&mut self) {
+        self.tables.zeroize();
+    }
+}
+
+impl<G: CurveGroup, M: MsmBackend<G>> PedersenCommitment<G, M> {
+    pub fn commit_affine(k: &AffineKey<G>, m: &[G::ScalarField]) -> Result<G, Error> {
+        check_message_length(k.len(), m.len())?;
+        report_metrics!(msm_terms, m.len());
+        let m_bigints = m.iter().map(|x| x.into_bigint()).collect::<Vec<_>>();
+        Ok(M::multi_scalar_mul(k.generators(), &m_bigints)?)
+    }
+}
+
+// Prepared keys take no MSMs, so do not depend on the backend
+impl<G: CurveGroup> PedersenCommitment<G> {
+    pub fn prepare_key(k: &[G]) -> PreparedPedersenKey<G> {
+        PreparedPedersenKey::new(k, PREPARED_KEY_WINDOW_SIZE)
+    }
+
+    pub fn commit_prepared(k: &PreparedPedersenKey<G>, m: &[G::ScalarField]) -> Result<G, Error> {
+        check_message_length(k.len(), m.len())?;
+        Ok(k.tables
+            .iter()
+            .zip(m)
+            .map(|(table, x)| FixedBase::windowed_mul::<G>(k.num_windows, k.window_size, table, x))
+            .sum())
+    }
+}
+
+// Key of a long vector split into blocks of block_size generators, the last block possibly shorter.
+// Each block is normalized to affine form once, so commitments are sums of independent block MSMs:
+// blocks of zeros are skipped, and a change to one block is applied by committing to that block.
+#[derive(Clone, Eq, PartialEq)]
+pub struct BlockedPedersenKey<G: CurveGroup> {
+    block_size: usize,
+    blocks: Vec<AffineKey<G>>,
+}
+
+impl<G: CurveGroup> BlockedPedersenKey<G> {
+    pub fn new(k: &[G], block_size: usize) -> Result<Self, Error> {
+        if block_size == 0 {
+            return Err(CommitmentError::BlockSizeZero);
+        }
+        Ok(BlockedPedersenKey {
+            block_size,
+            blocks: k
+                .chunks(block_size)
+                .map(AffineKey::from_projective)
+                .collect(),
+        })
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn block(&self, j: usize) -> Option<&AffineKey<G>> {
+        self.blocks.get(j)
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.iter().map(|b| b.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+impl<G: CurveGroup, M: MsmBackend<G>> PedersenCommitment<G, M> {
+    pub fn commit_blocked(k: &BlockedPedersenKey<G>, m: &[G::ScalarField]) -> Result<G, Error> {
+        check_message_length(k.len(), m.len())?;
+        report_metrics!(msm_terms, m.len());
+        let block_msm = |(block, m_j): (&AffineKey<G>, &[G::ScalarField])| {
+            if m_j.iter().all(|x| x.is_zero()) {
+                Ok(G::zero())
+            } else {
+                let m_bigints = m_j.iter().map(|x| x.into_bigint()).collect::<Vec<_>>();
+                M::multi_scalar_mul(block.generators(), &m_bigints)
+            }
+        };
+        #[cfg(feature = "parallel")]
+        let com = k
+            .blocks
+            .par_iter()
+            .zip(m.par_chunks(k.block_size))
+            .map(block_msm)
+            .sum::<Result<G, _>>();
+        #[cfg(not(feature = "parallel"))]
+        let com = k
+            .blocks
+            .iter()
+            .zip(m.chunks(k.block_size))
+            .map(block_msm)
+            .sum::<Result<G, _>>();
+        Ok(com?)
+    }
+
+    // Commitment to the message that is m_j in block j and zero elsewhere
+    pub fn commit_block(
+        k: &BlockedPedersenKey<G>,
+        j: usize,
+        m_j: &[G::ScalarField],
+    ) -> Result<G, Error> {
+        let block = k.block(j).ok_or(CommitmentError::BlockOutOfRange {
+            block: j,
+            num_blocks: k.num_blocks(),
+        })?;
+        Self::commit_affine(block, m_j)
+    }
+}
+
+impl<G: PedersenGroup<M>, M: Clone> HidingCommitment for PedersenCommitment<G, M> {
+    type BlindingKey = G;
+    type Randomness = G::ScalarField;
+
+    fn setup_blinding_key<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Self::BlindingKey, Error> {
+        Ok(G::rand(rng))
+    }
+
+    fn sample_randomness<R: RngCore + CryptoRng>(rng: &mut R, _size: usize) -> Self::Randomness {
+        G::ScalarField::rand(rng)
+    }
+
+    fn blind(
+        blinding_key: &Self::BlindingKey,
+        r: &Self::Randomness,
+    ) -> Result<Self::Output, Error> {
+        Ok(*blinding_key * r)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ark_ed_on_bls12_381::EdwardsProjective as JubJub;
-    use ark_ff::UniformRand;
-    use rand::{rngs::StdRng, SeedableRng};
+    use crate::CommitmentOpening;
+    use ark_ec::{AffineRepr, Group};
+    use ark_ed_on_bls12_381_bandersnatch::EdwardsProjective as Bandersnatch;
+    use ark_ff::One;
+    use ark_inner_products::{check_message_lengths, InnerProductError};
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
 
-    type C = PedersenCommitment<JubJub>;
+    type C = PedersenCommitment<Bandersnatch>;
     const TEST_SIZE: usize = 8;
 
     #[test]
@@ -43,13 +440,475 @@ mod tests {
         let mut message = Vec::new();
         let mut wrong_message = Vec::new();
         for _ in 0..TEST_SIZE {
-            message.push(<JubJub as ProjectiveCurve>::ScalarField::rand(&mut rng));
-            wrong_message.push(<JubJub as ProjectiveCurve>::ScalarField::rand(&mut rng));
+            message.push(<Bandersnatch as Group>::ScalarField::rand(&mut rng));
+            wrong_message.push(<Bandersnatch as Group>::ScalarField::rand(&mut rng));
         }
         let com = C::commit(&commit_keys, &message).unwrap();
         assert!(C::verify(&commit_keys, &message, &com).unwrap());
         assert!(!C::verify(&commit_keys, &wrong_message, &com).unwrap());
-        message.push(<JubJub as ProjectiveCurve>::ScalarField::rand(&mut rng));
+        message.push(<Bandersnatch as Group>::ScalarField::rand(&mut rng));
         assert!(C::verify(&commit_keys, &message, &com).is_err());
     }
+
+    #[test]
+    fn untrusted_key_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| <Bandersnatch as Group>::ScalarField::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = C::commit(&commit_keys, &message).unwrap();
+        assert!(C::verify_with_untrusted_key(&commit_keys, &message, &com).unwrap());
+
+        let err = C::commit(&commit_keys, &message[1..]).unwrap_err();
+        assert!(matches!(
+            err,
+            CommitmentError::KeyMessageLengthMismatch { key, message }
+                if key == TEST_SIZE && message == TEST_SIZE - 1
+        ));
+
+        // Points of the curve outside the prime order subgroup
+        let mut bytes = [0u8; 64];
+        let outside_subgroup = loop {
+            rng.fill_bytes(&mut bytes);
+            if let Some(g) = <Bandersnatch as CurveGroup>::Affine::from_random_bytes(&bytes) {
+                if !g.is_in_correct_subgroup_assuming_on_curve() {
+                    break g.into_group();
+                }
+            }
+        };
+        commit_keys[3] = outside_subgroup;
+        let err = C::verify_with_untrusted_key(&commit_keys, &message, &com).unwrap_err();
+        assert!(matches!(err, CommitmentError::KeyNotInSubgroup(3)));
+        commit_keys[1] = Bandersnatch::zero();
+        let err = C::validate_key(&commit_keys).unwrap_err();
+        assert!(matches!(err, CommitmentError::KeyIsIdentity(1)));
+    }
+
+    // Backend outside of ark-inner-products, e.g. one routing MSMs to GPU kernels
+    #[derive(Clone)]
+    struct NaiveMsm;
+
+    impl MsmBackend<Bandersnatch> for NaiveMsm {
+        fn multi_scalar_mul(
+            bases: &[<Bandersnatch as CurveGroup>::Affine],
+            scalars: &[<<Bandersnatch as Group>::ScalarField as PrimeField>::BigInt],
+        ) -> Result<Bandersnatch, InnerProductError> {
+            check_message_lengths("naive MSM", bases.len(), scalars.len())?;
+            Ok(bases
+                .iter()
+                .zip(scalars)
+                .map(|(g, x)| g.mul_bigint(x))
+                .sum())
+        }
+    }
+
+    #[test]
+    fn msm_backend_test() {
+        type NaiveC = PedersenCommitment<Bandersnatch, NaiveMsm>;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let messages = (0..4)
+            .map(|_| {
+                (0..TEST_SIZE)
+                    .map(|_| <Bandersnatch as Group>::ScalarField::rand(&mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let com = NaiveC::commit(&commit_keys, &messages[0]).unwrap();
+        assert_eq!(com, C::commit(&commit_keys, &messages[0]).unwrap());
+        assert!(
+            NaiveC::commit_batch(&commit_keys, &messages).unwrap()
+                == C::commit_batch(&commit_keys, &messages).unwrap()
+        );
+        let openings = vec![(messages[0].clone(), com)];
+        assert!(NaiveC::verify_batch(&commit_keys, &openings, &mut rng).unwrap());
+        let c = <Bandersnatch as Group>::ScalarField::rand(&mut rng);
+        assert_eq!(
+            NaiveC::fold_keys(&commit_keys, &c).unwrap(),
+            C::fold_keys(&commit_keys, &c).unwrap()
+        );
+    }
+
+    #[test]
+    fn inner_product_msm_backends_test() {
+        use ark_bls12_381::{Fr, G1Projective};
+        use ark_inner_products::{
+            glv::GLV_MIN_POINTS,
+            msm::{self, BatchAffineMsm, GlvMsm},
+        };
+
+        type G = G1Projective;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let len = 8 * TEST_SIZE;
+        let mut commit_keys = PedersenCommitment::<G>::setup(&mut rng, len).unwrap();
+        // Repeated and opposite generators, and scalars of zero and one, make buckets add
+        // doublings and inverses
+        commit_keys[1] = commit_keys[0];
+        commit_keys[2] = -commit_keys[0];
+        let mut message = (0..len).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        message[3] = Fr::zero();
+        message[4] = Fr::one();
+        message[5] = message[0];
+        let expected =
+            PedersenCommitment::<G, msm::NaiveMsm>::commit(&commit_keys, &message).unwrap();
+        assert_eq!(
+            PedersenCommitment::<G, BatchAffineMsm>::commit(&commit_keys, &message).unwrap(),
+            expected
+        );
+        assert_eq!(
+            PedersenCommitment::<G, PippengerMsm>::commit(&commit_keys, &message).unwrap(),
+            expected
+        );
+
+        // Folding adds the halves in affine form under the batch affine backend
+        let c = Fr::rand(&mut rng);
+        assert_eq!(
+            PedersenCommitment::<G, BatchAffineMsm>::fold_keys(&commit_keys, &c).unwrap(),
+            PedersenCommitment::<G>::fold_keys(&commit_keys, &c).unwrap()
+        );
+        assert!(PedersenCommitment::<G, BatchAffineMsm>::fold_keys(&commit_keys[1..], &c).is_err());
+
+        // The GLV endomorphism applies to keys whose halves reach GLV_MIN_POINTS
+        let long_keys = PedersenCommitment::<G>::setup(&mut rng, 2 * GLV_MIN_POINTS).unwrap();
+        let long_message = (0..long_keys.len())
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let folded = PedersenCommitment::<G>::fold_keys(&long_keys, &c).unwrap();
+        assert_eq!(
+            PedersenCommitment::<G, GlvMsm>::fold_keys(&long_keys, &c).unwrap(),
+            folded
+        );
+        assert_eq!(
+            PedersenCommitment::<G, BatchAffineMsm>::fold_keys(&long_keys, &c).unwrap(),
+            folded
+        );
+        assert_eq!(
+            PedersenCommitment::<G, GlvMsm>::commit(&long_keys, &long_message).unwrap(),
+            PedersenCommitment::<G>::commit(&long_keys, &long_message).unwrap()
+        );
+    }
+
+    #[test]
+    fn pedersen_batch_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let messages = (0..4)
+            .map(|_| {
+                (0..TEST_SIZE)
+                    .map(|_| <Bandersnatch as Group>::ScalarField::rand(&mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let coms = C::commit_batch(&commit_keys, &messages).unwrap();
+        assert_eq!(coms.len(), messages.len());
+        for (m, com) in messages.iter().zip(&coms) {
+            assert_eq!(*com, C::commit(&commit_keys, m).unwrap());
+        }
+        assert!(C::commit_batch(&commit_keys[1..], &messages).is_err());
+    }
+
+    #[test]
+    fn pedersen_verify_batch_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let mut openings = (0..4)
+            .map(|_| {
+                let message = (0..TEST_SIZE)
+                    .map(|_| <Bandersnatch as Group>::ScalarField::rand(&mut rng))
+                    .collect::<Vec<_>>();
+                let com = C::commit(&commit_keys, &message).unwrap();
+                (message, com)
+            })
+            .collect::<Vec<_>>();
+        assert!(C::verify_batch(&commit_keys, &openings, &mut rng).unwrap());
+        assert!(C::verify_batch(&commit_keys, &[], &mut rng).unwrap());
+
+        openings[2].0[0] += <Bandersnatch as Group>::ScalarField::one();
+        assert!(!C::verify_batch(&commit_keys, &openings, &mut rng).unwrap());
+        openings[2].0.pop();
+        assert!(C::verify_batch(&commit_keys, &openings, &mut rng).is_err());
+    }
+
+    #[test]
+    fn fold_keys_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let c = <Bandersnatch as Group>::ScalarField::rand(&mut rng);
+        let folded = C::fold_keys(&commit_keys, &c).unwrap();
+        let split = TEST_SIZE / 2;
+        for i in 0..split {
+            assert_eq!(folded[i], commit_keys[i] + commit_keys[split + i] * c);
+        }
+        assert!(C::fold_keys(&commit_keys[1..], &c).is_err());
+
+        let challenge_products = (0..TEST_SIZE)
+            .map(|_| <Bandersnatch as Group>::ScalarField::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let expected = commit_keys
+            .iter()
+            .zip(&challenge_products)
+            .map(|(g, x)| *g * x)
+            .sum::<Bandersnatch>();
+        assert_eq!(
+            C::fold_keys_msm(&commit_keys, &challenge_products).unwrap(),
+            expected
+        );
+        assert!(matches!(
+            C::fold_keys_msm(&commit_keys[1..], &challenge_products),
+            Err(CommitmentError::KeyMessageLengthMismatch { key, message })
+                if key == TEST_SIZE - 1 && message == TEST_SIZE
+        ));
+    }
+
+    #[test]
+    fn seeded_pedersen_test() {
+        let commit_keys = C::setup_from_seed(b"seed", TEST_SIZE).unwrap();
+        assert!(commit_keys == C::setup_from_seed(b"seed", TEST_SIZE).unwrap());
+        assert!(commit_keys != C::setup_from_seed(b"other seed", TEST_SIZE).unwrap());
+        assert!(
+            commit_keys[..TEST_SIZE / 2] == C::setup_from_seed(b"seed", TEST_SIZE / 2).unwrap()[..]
+        );
+        for g in commit_keys.iter() {
+            assert!(!g.is_zero());
+            assert!(g.into_affine().is_in_correct_subgroup_assuming_on_curve());
+        }
+    }
+
+    #[test]
+    fn prepared_pedersen_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let prepared_keys = C::prepare_key(&commit_keys);
+        let mut message = (0..TEST_SIZE)
+            .map(|_| <Bandersnatch as Group>::ScalarField::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = C::commit_prepared(&prepared_keys, &message).unwrap();
+        assert_eq!(com, C::commit(&commit_keys, &message).unwrap());
+        message.push(<Bandersnatch as Group>::ScalarField::rand(&mut rng));
+        assert!(C::commit_prepared(&prepared_keys, &message).is_err());
+    }
+
+    #[test]
+    fn affine_pedersen_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let affine_keys = AffineKey::from_projective(&commit_keys);
+        assert_eq!(affine_keys.len(), TEST_SIZE);
+        assert!(affine_keys.to_projective() == commit_keys);
+
+        let mut message = (0..TEST_SIZE)
+            .map(|_| <Bandersnatch as Group>::ScalarField::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = C::commit_affine(&affine_keys, &message).unwrap();
+        assert_eq!(com, C::commit(&commit_keys, &message).unwrap());
+        message.push(<Bandersnatch as Group>::ScalarField::rand(&mut rng));
+        assert!(C::commit_affine(&affine_keys, &message).is_err());
+
+        // Projective keys deserialize directly to affine keys
+        let mut bytes = Vec::new();
+        commit_keys.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), affine_keys.compressed_size());
+        assert_eq!(
+            AffineKey::<Bandersnatch>::deserialize_compressed(&bytes[..]).unwrap(),
+            affine_keys
+        );
+    }
+
+    #[test]
+    fn blocked_pedersen_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let block_size = 3;
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let blocked_keys = BlockedPedersenKey::new(&commit_keys, block_size).unwrap();
+        assert_eq!(blocked_keys.num_blocks(), 3);
+        assert_eq!(blocked_keys.len(), TEST_SIZE);
+
+        let mut message = (0..TEST_SIZE)
+            .map(|_| <Bandersnatch as Group>::ScalarField::rand(&mut rng))
+            .collect::<Vec<_>>();
+        for x in &mut message[..block_size] {
+            *x = <Bandersnatch as Group>::ScalarField::zero();
+        }
+        let com = C::commit_blocked(&blocked_keys, &message).unwrap();
+        assert_eq!(com, C::commit(&commit_keys, &message).unwrap());
+
+        // Updating the last block
+        let new_block = (0..TEST_SIZE - 2 * block_size)
+            .map(|_| <Bandersnatch as Group>::ScalarField::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let delta = message[2 * block_size..]
+            .iter()
+            .zip(&new_block)
+            .map(|(old, new)| *new - old)
+            .collect::<Vec<_>>();
+        message[2 * block_size..].copy_from_slice(&new_block);
+        assert_eq!(
+            com + C::commit_block(&blocked_keys, 2, &delta).unwrap(),
+            C::commit(&commit_keys, &message).unwrap()
+        );
+
+        assert!(C::commit_block(&blocked_keys, 3, &delta).is_err());
+        assert!(C::commit_blocked(&blocked_keys, &message[1..]).is_err());
+        assert!(BlockedPedersenKey::new(&commit_keys, 0).is_err());
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn zeroize_pedersen_test() {
+        use zeroize::Zeroizing;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let blinding_key = C::setup_blinding_key(&mut rng).unwrap();
+        let message = Zeroizing::new(
+            (0..TEST_SIZE)
+                .map(|_| <Bandersnatch as Group>::ScalarField::rand(&mut rng))
+                .collect::<Vec<_>>(),
+        );
+        let r = Zeroizing::new(C::sample_randomness(&mut rng, TEST_SIZE));
+        let com = C::commit_with_randomness(&commit_keys, &blinding_key, &message, &r).unwrap();
+        assert!(
+            C::verify_with_randomness(&commit_keys, &blinding_key, &message, &r, &com).unwrap()
+        );
+
+        let mut prepared_keys = C::prepare_key(&commit_keys);
+        prepared_keys.zeroize();
+        assert!(prepared_keys.is_empty());
+        let mut affine_keys = AffineKey::from_projective(&commit_keys);
+        affine_keys.zeroize();
+        assert!(affine_keys.is_empty());
+    }
+
+    #[test]
+    fn key_digest_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let digest = C::key_digest::<Blake2b512>(&commit_keys).unwrap();
+        assert_eq!(digest, C::key_digest::<Blake2b512>(&commit_keys).unwrap());
+        assert_eq!(
+            digest,
+            AffineKey::from_projective(&commit_keys)
+                .digest::<Blake2b512>()
+                .unwrap()
+        );
+
+        let mut other_keys = commit_keys.clone();
+        other_keys.swap(0, 1);
+        assert_ne!(digest, C::key_digest::<Blake2b512>(&other_keys).unwrap());
+        assert_ne!(
+            digest,
+            C::key_digest::<Blake2b512>(&commit_keys[1..]).unwrap()
+        );
+    }
+
+    #[test]
+    fn pedersen_opening_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let blinding_key = C::setup_blinding_key(&mut rng).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| <Bandersnatch as Group>::ScalarField::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        let (com, opening) = C::open(&commit_keys, &message).unwrap();
+        assert!(C::verify_opening(&commit_keys, &com, &opening).unwrap());
+        let mut bytes = Vec::new();
+        opening.serialize_compressed(&mut bytes).unwrap();
+        let deserialized = CommitmentOpening::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(opening, deserialized);
+        assert!(C::verify_opening(&commit_keys, &com, &deserialized).unwrap());
+
+        let (hiding_com, mut hiding_opening) =
+            C::open_hiding(&commit_keys, &blinding_key, &message, &mut rng).unwrap();
+        assert!(C::verify_hiding_opening(
+            &commit_keys,
+            &blinding_key,
+            &hiding_com,
+            &hiding_opening
+        )
+        .unwrap());
+        let mut bytes = Vec::new();
+        hiding_opening.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            hiding_opening,
+            CommitmentOpening::deserialize_compressed(&bytes[..]).unwrap()
+        );
+        hiding_opening.randomness += <Bandersnatch as Group>::ScalarField::one();
+        assert!(!C::verify_hiding_opening(
+            &commit_keys,
+            &blinding_key,
+            &hiding_com,
+            &hiding_opening
+        )
+        .unwrap());
+
+        // Randomness derived from the seed and message is reproducible
+        let (seeded_com, seeded_opening) =
+            C::open_hiding_deterministic(&commit_keys, &blinding_key, &message, b"seed").unwrap();
+        assert!(C::verify_hiding_opening(
+            &commit_keys,
+            &blinding_key,
+            &seeded_com,
+            &seeded_opening
+        )
+        .unwrap());
+        assert_eq!(
+            (seeded_com, seeded_opening),
+            C::open_hiding_deterministic(&commit_keys, &blinding_key, &message, b"seed").unwrap()
+        );
+        assert_ne!(
+            seeded_com,
+            C::open_hiding_deterministic(&commit_keys, &blinding_key, &message, b"other seed")
+                .unwrap()
+                .0
+        );
+        let mut other_message = message.clone();
+        other_message[0] += <Bandersnatch as Group>::ScalarField::one();
+        let (_, other_opening) =
+            C::open_hiding_deterministic(&commit_keys, &blinding_key, &other_message, b"seed")
+                .unwrap();
+        assert_ne!(
+            other_opening.randomness,
+            C::open_hiding_deterministic(&commit_keys, &blinding_key, &message, b"seed")
+                .unwrap()
+                .1
+                .randomness
+        );
+    }
+
+    #[test]
+    fn hiding_pedersen_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let blinding_key = C::setup_blinding_key(&mut rng).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| <Bandersnatch as Group>::ScalarField::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let r = C::sample_randomness(&mut rng, TEST_SIZE);
+        let wrong_r = C::sample_randomness(&mut rng, TEST_SIZE);
+
+        let com = C::commit_with_randomness(&commit_keys, &blinding_key, &message, &r).unwrap();
+        assert!(
+            C::verify_with_randomness(&commit_keys, &blinding_key, &message, &r, &com).unwrap()
+        );
+        assert!(
+            !C::verify_with_randomness(&commit_keys, &blinding_key, &message, &wrong_r, &com)
+                .unwrap()
+        );
+        assert!(!C::verify(&commit_keys, &message, &com).unwrap());
+
+        // Homomorphic in both message and randomness
+        let other_com =
+            C::commit_with_randomness(&commit_keys, &blinding_key, &message, &wrong_r).unwrap();
+        let sum_message = message.iter().map(|m| *m + m).collect::<Vec<_>>();
+        assert!(C::verify_with_randomness(
+            &commit_keys,
+            &blinding_key,
+            &sum_message,
+            &(r + wrong_r),
+            &(com + other_com)
+        )
+        .unwrap());
+    }
 }
@@ -0,0 +1,369 @@
+use ark_ff::{BigInteger, PrimeField, UniformRand, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{
+    fmt,
+    hash::{Hash, Hasher},
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    rand::Rng,
+    vec::Vec,
+};
+use blake2::{Blake2b512, Digest};
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT,
+    ristretto::{CompressedRistretto, RistrettoPoint as DalekPoint},
+    scalar::Scalar,
+    traits::{Identity, VartimeMultiscalarMul},
+};
+use zeroize::Zeroize;
+
+use super::PedersenGroup;
+use crate::{check_message_length, Error};
+
+// Scalar field of Ristretto, of the prime order 2^252 + 27742317777372353535851937790883648493
+pub use ark_curve25519::Fr;
+
+const POINT_SIZE: usize = 32;
+
+// Ristretto group of curve25519-dalek as an arkworks group, for Pedersen commitments and GIPA over
+// a group without a pairing. Points serialize to their 32 byte compressed encoding either way.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct RistrettoPoint(pub DalekPoint);
+
+pub type RistrettoPedersen = super::PedersenCommitment<RistrettoPoint>;
+
+fn to_scalar(x: &Fr) -> Scalar {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&x.into_bigint().to_bytes_le());
+    Scalar::from_bytes_mod_order(bytes)
+}
+
+impl RistrettoPoint {
+    pub fn compress(&self) -> [u8; POINT_SIZE] {
+        self.0.compress().to_bytes()
+    }
+}
+
+impl Default for RistrettoPoint {
+    fn default() -> Self {
+        RistrettoPoint(DalekPoint::identity())
+    }
+}
+
+impl Hash for RistrettoPoint {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.compress().hash(state);
+    }
+}
+
+impl fmt::Debug for RistrettoPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RistrettoPoint({})", self)
+    }
+}
+
+impl fmt::Display for RistrettoPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.compress() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl Zeroize for RistrettoPoint {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl UniformRand for RistrettoPoint {
+    fn rand<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut bytes = [0u8; 64];
+        rng.fill_bytes(&mut bytes);
+        RistrettoPoint(DalekPoint::from_uniform_bytes(&bytes))
+    }
+}
+
+impl CanonicalSerialize for RistrettoPoint {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
+        Ok(writer.write_all(&self.compress())?)
+    }
+
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        POINT_SIZE
+    }
+}
+
+// Decompression only succeeds for canonical encodings of group elements
+impl Valid for RistrettoPoint {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for RistrettoPoint {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        _compress: Compress,
+        _validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let mut bytes = [0u8; POINT_SIZE];
+        reader.read_exact(&mut bytes)?;
+        CompressedRistretto(bytes)
+            .decompress()
+            .map(RistrettoPoint)
+            .ok_or(SerializationError::InvalidData)
+    }
+}
+
+impl Zero for RistrettoPoint {
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == DalekPoint::identity()
+    }
+}
+
+impl Neg for RistrettoPoint {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        RistrettoPoint(-self.0)
+    }
+}
+
+impl<'a> Add<&'a Self> for RistrettoPoint {
+    type Output = Self;
+
+    fn add(self, other: &'a Self) -> Self {
+        RistrettoPoint(self.0 + other.0)
+    }
+}
+
+impl<'a> Sub<&'a Self> for RistrettoPoint {
+    type Output = Self;
+
+    fn sub(self, other: &'a Self) -> Self {
+        RistrettoPoint(self.0 - other.0)
+    }
+}
+
+impl<'a> Mul<&'a Fr> for RistrettoPoint {
+    type Output = Self;
+
+    fn mul(self, x: &'a Fr) -> Self {
+        RistrettoPoint(self.0 * to_scalar(x))
+    }
+}
+
+impl Add for RistrettoPoint {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        RistrettoPoint(self.0 + other.0)
+    }
+}
+
+impl Sub for RistrettoPoint {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        RistrettoPoint(self.0 - other.0)
+    }
+}
+
+impl Mul<Fr> for RistrettoPoint {
+    type Output = Self;
+
+    fn mul(self, x: Fr) -> Self {
+        RistrettoPoint(self.0 * to_scalar(&x))
+    }
+}
+
+impl<'a> AddAssign<&'a Self> for RistrettoPoint {
+    fn add_assign(&mut self, other: &'a Self) {
+        self.0 += other.0;
+    }
+}
+
+impl<'a> SubAssign<&'a Self> for RistrettoPoint {
+    fn sub_assign(&mut self, other: &'a Self) {
+        self.0 -= other.0;
+    }
+}
+
+impl<'a> MulAssign<&'a Fr> for RistrettoPoint {
+    fn mul_assign(&mut self, x: &'a Fr) {
+        self.0 *= to_scalar(x);
+    }
+}
+
+impl AddAssign for RistrettoPoint {
+    fn add_assign(&mut self, other: Self) {
+        *self += &other;
+    }
+}
+
+impl SubAssign for RistrettoPoint {
+    fn sub_assign(&mut self, other: Self) {
+        *self -= &other;
+    }
+}
+
+impl MulAssign<Fr> for RistrettoPoint {
+    fn mul_assign(&mut self, x: Fr) {
+        *self *= &x;
+    }
+}
+
+impl Sum for RistrettoPoint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, g| acc + g)
+    }
+}
+
+impl<'a> Sum<&'a Self> for RistrettoPoint {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, g| acc + g)
+    }
+}
+
+impl ark_ec::Group for RistrettoPoint {
+    type ScalarField = Fr;
+
+    fn generator() -> Self {
+        RistrettoPoint(RISTRETTO_BASEPOINT_POINT)
+    }
+
+    fn double_in_place(&mut self) -> &mut Self {
+        self.0 += self.0;
+        self
+    }
+
+    // Integers of any length, reduced modulo the group order limb by limb
+    fn mul_bigint(&self, other: impl AsRef<[u64]>) -> Self {
+        let radix = Scalar::from(u64::MAX) + Scalar::ONE;
+        let x = other
+            .as_ref()
+            .iter()
+            .rev()
+            .fold(Scalar::ZERO, |acc, limb| acc * radix + Scalar::from(*limb));
+        RistrettoPoint(self.0 * x)
+    }
+}
+
+impl PedersenGroup for RistrettoPoint {
+    fn multi_scalar_mul(bases: &[Self], scalars: &[Fr]) -> Result<Self, Error> {
+        check_message_length(bases.len(), scalars.len())?;
+        report_metrics!(msm_terms, bases.len());
+        Ok(RistrettoPoint(DalekPoint::vartime_multiscalar_mul(
+            scalars.iter().map(to_scalar),
+            bases.iter().map(|g| g.0),
+        )))
+    }
+
+    // Elligator map of a Blake2b digest of the domain, seed, index and a counter, the same inputs
+    // as `hash_to_generators` of the arkworks curves. The map is onto the group, so the counter
+    // is only advanced past the identity
+    fn hash_to_generators(domain: &[u8], seed: &[u8], num: usize) -> Vec<Self> {
+        (0..num)
+            .map(|i| {
+                let mut counter_nonce: u64 = 0;
+                loop {
+                    let mut hash_input = Vec::new();
+                    hash_input.extend_from_slice(&(domain.len() as u64).to_be_bytes());
+                    hash_input.extend_from_slice(domain);
+                    hash_input.extend_from_slice(&(seed.len() as u64).to_be_bytes());
+                    hash_input.extend_from_slice(seed);
+                    hash_input.extend_from_slice(&(i as u64).to_be_bytes());
+                    hash_input.extend_from_slice(&counter_nonce.to_be_bytes());
+                    let mut bytes = [0u8; 64];
+                    bytes.copy_from_slice(&Blake2b512::digest(&hash_input));
+                    let g = RistrettoPoint(DalekPoint::from_uniform_bytes(&bytes));
+                    if !g.is_zero() {
+                        break g;
+                    }
+                    counter_nonce += 1;
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DoublyHomomorphicCommitment;
+    use ark_ec::Group;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type C = RistrettoPedersen;
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn ristretto_group_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let g = RistrettoPoint::rand(&mut rng);
+        let x = Fr::rand(&mut rng);
+        let y = Fr::rand(&mut rng);
+        assert_eq!(g * x + g * y, g * (x + y));
+        assert_eq!(g.mul_bigint((x * y).into_bigint()), (g * x) * y);
+        assert_eq!(g.mul_bigint(Fr::MODULUS), RistrettoPoint::zero());
+        assert_eq!(g.double(), g + g);
+        assert_eq!(
+            RistrettoPoint::generator() * -Fr::from(1u64),
+            -RistrettoPoint::generator()
+        );
+
+        let mut bytes = Vec::new();
+        g.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), g.compressed_size());
+        assert_eq!(
+            RistrettoPoint::deserialize_compressed(&bytes[..]).unwrap(),
+            g
+        );
+        // Non-canonical encodings, e.g. of a negative field element, do not decompress
+        bytes[0] |= 1;
+        assert!(RistrettoPoint::deserialize_compressed(&bytes[..]).is_err());
+    }
+
+    #[test]
+    fn ristretto_pedersen_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let message = (0..TEST_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = C::commit(&commit_keys, &message).unwrap();
+        let expected = commit_keys
+            .iter()
+            .zip(&message)
+            .map(|(g, x)| *g * x)
+            .sum::<RistrettoPoint>();
+        assert_eq!(com, expected);
+        assert!(C::verify(&commit_keys, &message, &com).unwrap());
+        assert!(C::verify_batch(&commit_keys, &[(message.clone(), com)], &mut rng).unwrap());
+        assert!(C::commit(&commit_keys, &message[1..]).is_err());
+
+        let c = Fr::rand(&mut rng);
+        let folded = C::fold_keys(&commit_keys, &c).unwrap();
+        assert_eq!(folded[0], commit_keys[0] + commit_keys[TEST_SIZE / 2] * c);
+
+        let seeded_keys = C::setup_from_seed(b"seed", TEST_SIZE).unwrap();
+        assert!(seeded_keys == C::setup_from_seed(b"seed", TEST_SIZE).unwrap());
+        assert!(
+            seeded_keys[..TEST_SIZE / 2] == C::setup_from_seed(b"seed", TEST_SIZE / 2).unwrap()[..]
+        );
+        assert!(C::validate_key(&seeded_keys).is_ok());
+    }
+}
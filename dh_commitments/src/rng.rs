@@ -0,0 +1,54 @@
+use ark_serialize::CanonicalSerialize;
+use ark_std::vec::Vec;
+use blake2::{Blake2b512, Digest};
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+use crate::Error;
+
+const WITNESS_RNG_DOMAIN: &[u8] = b"ark-dh-commitments/witness-rng";
+
+// Deterministic prover randomness derived from a secret seed and the witness it blinds, so hiding
+// commitments and proofs can be reproduced in tests and audit replays. The seed must stay secret
+// and should not be reused across witnesses for which the randomness has to be independent
+pub struct WitnessRng {
+    r: ChaChaRng,
+}
+
+impl WitnessRng {
+    pub fn new<W: CanonicalSerialize + ?Sized>(seed: &[u8], witness: &W) -> Result<Self, Error> {
+        let mut bytes = Vec::new();
+        WITNESS_RNG_DOMAIN.serialize_compressed(&mut bytes)?;
+        seed.serialize_compressed(&mut bytes)?;
+        witness.serialize_compressed(&mut bytes)?;
+        let mut r_seed = [0u8; 32];
+        r_seed.copy_from_slice(&Blake2b512::digest(&bytes)[..32]);
+        Ok(WitnessRng {
+            r: ChaChaRng::from_seed(r_seed),
+        })
+    }
+}
+
+impl RngCore for WitnessRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.r.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.r.next_u64()
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.r.fill_bytes(dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.r.try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for WitnessRng {}
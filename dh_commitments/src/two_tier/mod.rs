@@ -0,0 +1,205 @@
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{
+    marker::PhantomData,
+    ops::{Add, MulAssign},
+    vec::Vec,
+};
+use rand::{CryptoRng, RngCore};
+
+use crate::{check_message_length, DoublyHomomorphicCommitment, Error};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+// Commits to a message of length n^2 under a key of length n: each chunk of length n is committed
+// to with the first tier commitment, and the n chunk commitments with the second tier commitment
+#[derive(Clone)]
+pub struct TwoTierCommitment<C1, C2> {
+    _first_tier: PhantomData<C1>,
+    _second_tier: PhantomData<C2>,
+}
+
+// Key entry i holds the i-th key of both tiers
+#[derive(Clone, Default, Eq, PartialEq)]
+pub struct TwoTierKey<K1, K2> {
+    pub first_tier: K1,
+    pub second_tier: K2,
+}
+
+impl<K1: CanonicalSerialize, K2: CanonicalSerialize> CanonicalSerialize for TwoTierKey<K1, K2> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.first_tier.serialize_with_mode(&mut writer, compress)?;
+        self.second_tier.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.first_tier.serialized_size(compress) + self.second_tier.serialized_size(compress)
+    }
+}
+
+impl<K1: Valid, K2: Valid> Valid for TwoTierKey<K1, K2> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.first_tier.check()?;
+        self.second_tier.check()
+    }
+}
+
+impl<K1: CanonicalDeserialize, K2: CanonicalDeserialize> CanonicalDeserialize
+    for TwoTierKey<K1, K2>
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(TwoTierKey {
+            first_tier: K1::deserialize_with_mode(&mut reader, compress, validate)?,
+            second_tier: K2::deserialize_with_mode(&mut reader, compress, validate)?,
+        })
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<K1: Zeroize, K2: Zeroize> Zeroize for TwoTierKey<K1, K2> {
+    fn zeroize(&mut self) {
+        self.first_tier.zeroize();
+        self.second_tier.zeroize();
+    }
+}
+
+impl<K1: Add<K1, Output = K1>, K2: Add<K2, Output = K2>> Add for TwoTierKey<K1, K2> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        TwoTierKey {
+            first_tier: self.first_tier + rhs.first_tier,
+            second_tier: self.second_tier + rhs.second_tier,
+        }
+    }
+}
+
+impl<K1: MulAssign<F>, K2: MulAssign<F>, F: Clone> MulAssign<F> for TwoTierKey<K1, K2> {
+    fn mul_assign(&mut self, rhs: F) {
+        self.first_tier.mul_assign(rhs.clone());
+        self.second_tier.mul_assign(rhs);
+    }
+}
+
+impl<C1, C2> TwoTierCommitment<C1, C2>
+where
+    C1: DoublyHomomorphicCommitment,
+    C2: DoublyHomomorphicCommitment<Scalar = C1::Scalar, Message = C1::Output>,
+{
+    // First tier commitments to the chunks of the message, which open the second tier commitment
+    pub fn commit_chunks(
+        k: &[TwoTierKey<C1::Key, C2::Key>],
+        m: &[C1::Message],
+    ) -> Result<Vec<C1::Output>, Error> {
+        check_message_length(k.len() * k.len(), m.len())?;
+        let first_tier_k = k.iter().map(|k| k.first_tier.clone()).collect::<Vec<_>>();
+        let chunks = m
+            .chunks(k.len())
+            .map(|chunk| chunk.to_vec())
+            .collect::<Vec<_>>();
+        C1::commit_batch(&first_tier_k, &chunks)
+    }
+}
+
+impl<C1, C2> DoublyHomomorphicCommitment for TwoTierCommitment<C1, C2>
+where
+    C1: DoublyHomomorphicCommitment,
+    C2: DoublyHomomorphicCommitment<Scalar = C1::Scalar, Message = C1::Output>,
+{
+    type Scalar = C1::Scalar;
+    type Message = C1::Message;
+    type Key = TwoTierKey<C1::Key, C2::Key>;
+    type Output = C2::Output;
+
+    // Key for messages of length size^2
+    fn setup<R: RngCore + CryptoRng>(rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
+        let first_tier_k = C1::setup(rng, size)?;
+        let second_tier_k = C2::setup(rng, size)?;
+        Ok(first_tier_k
+            .into_iter()
+            .zip(second_tier_k)
+            .map(|(first_tier, second_tier)| TwoTierKey {
+                first_tier,
+                second_tier,
+            })
+            .collect())
+    }
+
+    fn setup_from_seed(seed: &[u8], size: usize) -> Result<Vec<Self::Key>, Error> {
+        let first_tier_k = C1::setup_from_seed(seed, size)?;
+        let second_tier_k = C2::setup_from_seed(seed, size)?;
+        Ok(first_tier_k
+            .into_iter()
+            .zip(second_tier_k)
+            .map(|(first_tier, second_tier)| TwoTierKey {
+                first_tier,
+                second_tier,
+            })
+            .collect())
+    }
+
+    fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, Error> {
+        let chunk_coms = Self::commit_chunks(k, m)?;
+        let second_tier_k = k.iter().map(|k| k.second_tier.clone()).collect::<Vec<_>>();
+        C2::commit(&second_tier_k, &chunk_coms)
+    }
+
+    fn validate_key(k: &[Self::Key]) -> Result<(), Error> {
+        C1::validate_key(&k.iter().map(|k| k.first_tier.clone()).collect::<Vec<_>>())?;
+        C2::validate_key(&k.iter().map(|k| k.second_tier.clone()).collect::<Vec<_>>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{afgho16::AFGHOCommitmentG1, pedersen::PedersenCommitment};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ec::pairing::Pairing;
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type C = TwoTierCommitment<
+        PedersenCommitment<<Bls12_381 as Pairing>::G1>,
+        AFGHOCommitmentG1<Bls12_381>,
+    >;
+    const TEST_SIZE: usize = 4;
+
+    #[test]
+    fn two_tier_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let commit_keys = C::setup(&mut rng, TEST_SIZE).unwrap();
+        let message = (0..TEST_SIZE * TEST_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let mut wrong_message = message.clone();
+        wrong_message[TEST_SIZE + 1] = Fr::rand(&mut rng);
+
+        let com = C::commit(&commit_keys, &message).unwrap();
+        assert!(C::verify(&commit_keys, &message, &com).unwrap());
+        assert!(!C::verify(&commit_keys, &wrong_message, &com).unwrap());
+        assert!(C::verify(&commit_keys, &message[1..], &com).is_err());
+
+        // Homomorphic in the message
+        let sum_message = message
+            .iter()
+            .zip(&wrong_message)
+            .map(|(a, b)| *a + b)
+            .collect::<Vec<_>>();
+        let wrong_com = C::commit(&commit_keys, &wrong_message).unwrap();
+        assert!(C::verify(&commit_keys, &sum_message, &(com + wrong_com)).unwrap());
+
+        let seeded_keys = C::setup_from_seed(b"seed", TEST_SIZE).unwrap();
+        assert!(seeded_keys == C::setup_from_seed(b"seed", TEST_SIZE).unwrap());
+    }
+}
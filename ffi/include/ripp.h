@@ -0,0 +1,55 @@
+/* C interface to the aggregation of Groth16 proofs over BLS12-381 with Blake2b Fiat-Shamir
+ * challenges. All byte arrays hold the canonical compressed arkworks serialization of the
+ * corresponding type, as read and written by the ripp command line tool. Input pointers may be
+ * NULL only with a length of 0. */
+
+#ifndef RIPP_H
+#define RIPP_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum {
+    RIPP_OK = 0,
+    RIPP_INVALID_PROOF = 1,
+    RIPP_NULL_POINTER = -1,
+    RIPP_DESERIALIZATION = -2,
+    RIPP_SERIALIZATION = -3,
+    RIPP_AGGREGATION = -4,
+    RIPP_VERIFICATION = -5,
+    RIPP_PANIC = -6,
+} RippStatus;
+
+/* Buffer allocated by the library, released with ripp_buffer_free */
+typedef struct {
+    uint8_t *data;
+    size_t len;
+} RippBuffer;
+
+/* Aggregates a serialized Vec<Proof<Bls12_381>>, whose length is a power of 2 no larger than the
+ * SRS supports, under the prover SRS and verifying key. On RIPP_OK, out holds the serialized
+ * aggregate proof; otherwise out is left untouched. */
+RippStatus ripp_aggregate_proofs(const uint8_t *srs, size_t srs_len,
+                                 const uint8_t *vk, size_t vk_len,
+                                 const uint8_t *proofs, size_t proofs_len,
+                                 RippBuffer *out);
+
+/* Verifies an aggregate proof under the verifier SRS and verifying key against a serialized
+ * Vec<Vec<Fr>> of the public inputs of every aggregated proof, in aggregation order. Returns
+ * RIPP_OK for a valid proof and RIPP_INVALID_PROOF for an invalid one. */
+RippStatus ripp_verify_aggregate(const uint8_t *srs, size_t srs_len,
+                                 const uint8_t *vk, size_t vk_len,
+                                 const uint8_t *inputs, size_t inputs_len,
+                                 const uint8_t *proof, size_t proof_len);
+
+void ripp_buffer_free(RippBuffer buffer);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RIPP_H */
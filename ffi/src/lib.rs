@@ -0,0 +1,328 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Proof, VerifyingKey};
+use ark_ip_proofs::{
+    applications::groth16_aggregation::{aggregate_proofs, verify_aggregate_proof, AggregateProof},
+    tipa::{VerifierSRS, SRS},
+    validation::{TrustedInput, Validate},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use blake2::Blake2b512;
+use std::{
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+// C ABI for aggregating Groth16 proofs over BLS12-381, declared in include/ripp.h. Inputs and outputs
+// are byte buffers holding the canonical compressed serialization of the corresponding arkworks
+// types, as read and written by the ripp command line tool.
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RippStatus {
+    Ok = 0,
+    InvalidProof = 1,
+    NullPointer = -1,
+    Deserialization = -2,
+    Serialization = -3,
+    Aggregation = -4,
+    Verification = -5,
+    Panic = -6,
+}
+
+// Buffer allocated by this library, released with `ripp_buffer_free`
+#[repr(C)]
+pub struct RippBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl RippBuffer {
+    fn from_vec(v: Vec<u8>) -> Self {
+        let mut bytes = v.into_boxed_slice();
+        let buffer = RippBuffer {
+            data: bytes.as_mut_ptr(),
+            len: bytes.len(),
+        };
+        std::mem::forget(bytes);
+        buffer
+    }
+}
+
+unsafe fn input<'a>(data: *const u8, len: usize) -> Result<&'a [u8], RippStatus> {
+    if data.is_null() {
+        return if len == 0 {
+            Ok(&[])
+        } else {
+            Err(RippStatus::NullPointer)
+        };
+    }
+    Ok(slice::from_raw_parts(data, len))
+}
+
+fn read<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, RippStatus> {
+    T::deserialize_compressed(bytes).map_err(|_| RippStatus::Deserialization)
+}
+
+// Group elements from the caller are checked to be on the curve and in the prime order subgroup
+fn read_trusted<T: CanonicalDeserialize + Validate>(bytes: &[u8]) -> Result<T, RippStatus> {
+    TrustedInput::deserialize(bytes)
+        .map(TrustedInput::into_inner)
+        .map_err(|_| RippStatus::Deserialization)
+}
+
+// Panics must not unwind into the caller
+fn catch(f: impl FnOnce() -> Result<RippStatus, RippStatus>) -> RippStatus {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(status)) | Ok(Err(status)) => status,
+        Err(_) => RippStatus::Panic,
+    }
+}
+
+/// Aggregates a serialized vector of Groth16 proofs, whose length is a power of 2 no larger than
+/// the SRS supports, into `out`. `out` is left untouched unless the status is `Ok`.
+///
+/// # Safety
+///
+/// Each input pointer is null with a length of 0, or valid for reads of its length. `out` is null
+/// or valid for writes, and a buffer written to it is released with `ripp_buffer_free`.
+#[no_mangle]
+pub unsafe extern "C" fn ripp_aggregate_proofs(
+    srs: *const u8,
+    srs_len: usize,
+    vk: *const u8,
+    vk_len: usize,
+    proofs: *const u8,
+    proofs_len: usize,
+    out: *mut RippBuffer,
+) -> RippStatus {
+    catch(|| {
+        if out.is_null() {
+            return Err(RippStatus::NullPointer);
+        }
+        let srs: SRS<Bls12_381> = read_trusted(input(srs, srs_len)?)?;
+        let vk: VerifyingKey<Bls12_381> = read_trusted(input(vk, vk_len)?)?;
+        let proofs: Vec<Proof<Bls12_381>> = read_trusted(input(proofs, proofs_len)?)?;
+
+        let aggregate_proof = aggregate_proofs::<Bls12_381, Blake2b512>(&srs, &vk, &proofs)
+            .map_err(|_| RippStatus::Aggregation)?;
+        let mut bytes = Vec::with_capacity(aggregate_proof.compressed_size());
+        aggregate_proof
+            .serialize_compressed(&mut bytes)
+            .map_err(|_| RippStatus::Serialization)?;
+        ptr::write(out, RippBuffer::from_vec(bytes));
+        Ok(RippStatus::Ok)
+    })
+}
+
+/// Verifies an aggregate proof against the verifier SRS and the public inputs of every aggregated
+/// proof, given as a serialized vector of vectors in the order the proofs were aggregated. Returns
+/// `Ok` for a valid proof and `InvalidProof` for an invalid one.
+///
+/// # Safety
+///
+/// Each input pointer is null with a length of 0, or valid for reads of its length.
+#[no_mangle]
+pub unsafe extern "C" fn ripp_verify_aggregate(
+    srs: *const u8,
+    srs_len: usize,
+    vk: *const u8,
+    vk_len: usize,
+    inputs: *const u8,
+    inputs_len: usize,
+    proof: *const u8,
+    proof_len: usize,
+) -> RippStatus {
+    catch(|| {
+        let v_srs: VerifierSRS<Bls12_381> = read_trusted(input(srs, srs_len)?)?;
+        let vk: VerifyingKey<Bls12_381> = read_trusted(input(vk, vk_len)?)?;
+        let inputs: Vec<Vec<Fr>> = read(input(inputs, inputs_len)?)?;
+        let proof: AggregateProof<Bls12_381, Blake2b512> = read_trusted(input(proof, proof_len)?)?;
+
+        match verify_aggregate_proof(&v_srs, &vk, &inputs, &proof) {
+            Ok(true) => Ok(RippStatus::Ok),
+            Ok(false) => Ok(RippStatus::InvalidProof),
+            Err(_) => Err(RippStatus::Verification),
+        }
+    })
+}
+
+/// Releases a buffer written by this library.
+///
+/// # Safety
+///
+/// `buffer` was written by `ripp_aggregate_proofs` and is not used or released again.
+#[no_mangle]
+pub unsafe extern "C" fn ripp_buffer_free(buffer: RippBuffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+            buffer.data,
+            buffer.len,
+        )));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{G1Projective, G2Projective};
+    use ark_ec::CurveGroup;
+    use ark_ff::UniformRand;
+    use ark_groth16::Groth16;
+    use ark_ip_proofs::applications::groth16_aggregation::setup_inner_product;
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    fn bytes<T: CanonicalSerialize>(t: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        t.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    // Proves knowledge of a witness equal to the public input
+    struct TestCircuit(Fr);
+
+    impl ConstraintSynthesizer<Fr> for TestCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let input = cs.new_input_variable(|| Ok(self.0))?;
+            let witness = cs.new_witness_variable(|| Ok(self.0))?;
+            cs.enforce_constraint(lc!() + witness, lc!() + Variable::One, lc!() + input)
+        }
+    }
+
+    #[test]
+    fn ffi_aggregation_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let srs = setup_inner_product::<Bls12_381, Blake2b512, _>(&mut rng, TEST_SIZE).unwrap();
+        let vk = VerifyingKey::<Bls12_381> {
+            alpha_g1: G1Projective::rand(&mut rng).into_affine(),
+            beta_g2: G2Projective::rand(&mut rng).into_affine(),
+            gamma_g2: G2Projective::rand(&mut rng).into_affine(),
+            delta_g2: G2Projective::rand(&mut rng).into_affine(),
+            gamma_abc_g1: vec![G1Projective::rand(&mut rng).into_affine(); 2],
+        };
+        // Random proofs aggregate but do not verify
+        let proofs = (0..TEST_SIZE)
+            .map(|_| Proof::<Bls12_381> {
+                a: G1Projective::rand(&mut rng).into_affine(),
+                b: G2Projective::rand(&mut rng).into_affine(),
+                c: G1Projective::rand(&mut rng).into_affine(),
+            })
+            .collect::<Vec<_>>();
+        let inputs = vec![vec![Fr::rand(&mut rng)]; TEST_SIZE];
+
+        let (srs, v_srs) = (bytes(&srs), bytes(&srs.get_verifier_key()));
+        let (vk, proofs, inputs) = (bytes(&vk), bytes(&proofs), bytes(&inputs));
+        let mut out = RippBuffer {
+            data: ptr::null_mut(),
+            len: 0,
+        };
+        unsafe {
+            let status = ripp_aggregate_proofs(
+                srs.as_ptr(),
+                srs.len(),
+                vk.as_ptr(),
+                vk.len(),
+                proofs.as_ptr(),
+                proofs.len(),
+                &mut out,
+            );
+            assert_eq!(status, RippStatus::Ok);
+            let status = ripp_verify_aggregate(
+                v_srs.as_ptr(),
+                v_srs.len(),
+                vk.as_ptr(),
+                vk.len(),
+                inputs.as_ptr(),
+                inputs.len(),
+                out.data,
+                out.len,
+            );
+            assert_eq!(status, RippStatus::InvalidProof);
+
+            let status = ripp_verify_aggregate(
+                v_srs.as_ptr(),
+                v_srs.len(),
+                vk.as_ptr(),
+                vk.len(),
+                inputs.as_ptr(),
+                inputs.len(),
+                out.data,
+                out.len - 1,
+            );
+            assert_eq!(status, RippStatus::Deserialization);
+
+            let status = ripp_aggregate_proofs(
+                srs.as_ptr(),
+                srs.len(),
+                ptr::null(),
+                vk.len(),
+                proofs.as_ptr(),
+                proofs.len(),
+                &mut out,
+            );
+            assert_eq!(status, RippStatus::NullPointer);
+            ripp_buffer_free(out);
+        }
+    }
+    #[test]
+    fn ffi_valid_aggregation_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let srs = setup_inner_product::<Bls12_381, Blake2b512, _>(&mut rng, TEST_SIZE).unwrap();
+        let parameters = Groth16::<Bls12_381>::generate_random_parameters_with_reduction(
+            TestCircuit(Fr::default()),
+            &mut rng,
+        )
+        .unwrap();
+        let inputs = (0..TEST_SIZE)
+            .map(|_| vec![Fr::rand(&mut rng)])
+            .collect::<Vec<_>>();
+        let proofs = inputs
+            .iter()
+            .map(|x| {
+                Groth16::<Bls12_381>::create_random_proof_with_reduction(
+                    TestCircuit(x[0]),
+                    &parameters,
+                    &mut rng,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let (srs, v_srs) = (bytes(&srs), bytes(&srs.get_verifier_key()));
+        let (vk, proofs, inputs) = (bytes(&parameters.vk), bytes(&proofs), bytes(&inputs));
+        let mut out = RippBuffer {
+            data: ptr::null_mut(),
+            len: 0,
+        };
+        unsafe {
+            let status = ripp_aggregate_proofs(
+                srs.as_ptr(),
+                srs.len(),
+                vk.as_ptr(),
+                vk.len(),
+                proofs.as_ptr(),
+                proofs.len(),
+                &mut out,
+            );
+            assert_eq!(status, RippStatus::Ok);
+            assert!(!out.data.is_null());
+            let status = ripp_verify_aggregate(
+                v_srs.as_ptr(),
+                v_srs.len(),
+                vk.as_ptr(),
+                vk.len(),
+                inputs.as_ptr(),
+                inputs.len(),
+                out.data,
+                out.len,
+            );
+            assert_eq!(status, RippStatus::Ok);
+            ripp_buffer_free(out);
+        }
+    }
+}
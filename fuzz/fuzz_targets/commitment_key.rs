@@ -0,0 +1,21 @@
+#![no_main]
+use ark_bls12_381::Bls12_381;
+use ark_dh_commitments::AffineKey;
+use ark_ec::pairing::Pairing;
+use libfuzzer_sys::fuzz_target;
+use ripp_fuzz::check_canonical;
+
+type G1 = <Bls12_381 as Pairing>::G1;
+type G2 = <Bls12_381 as Pairing>::G2;
+
+// Keys of Pedersen and AFGHO commitments, in projective form and as prepared affine keys
+fuzz_target!(|data: &[u8]| {
+    if let Some((kind, data)) = data.split_first() {
+        match kind % 4 {
+            0 => check_canonical::<Vec<G1>>(data),
+            1 => check_canonical::<Vec<G2>>(data),
+            2 => check_canonical::<AffineKey<G1>>(data),
+            _ => check_canonical::<AffineKey<G2>>(data),
+        }
+    }
+});
@@ -0,0 +1,23 @@
+#![no_main]
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_dh_commitments::{
+    afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+    identity::IdentityCommitment,
+};
+use ark_inner_products::{ExtensionFieldElement, PairingInnerProduct};
+use ark_ip_proofs::gipa::GIPAProof;
+use blake2::Blake2b512;
+use libfuzzer_sys::fuzz_target;
+use ripp_fuzz::check_canonical;
+
+type PairingGIPAProof = GIPAProof<
+    PairingInnerProduct<Bls12_381>,
+    AFGHOCommitmentG1<Bls12_381>,
+    AFGHOCommitmentG2<Bls12_381>,
+    IdentityCommitment<ExtensionFieldElement<Bls12_381>, Fr>,
+    Blake2b512,
+>;
+
+fuzz_target!(|data: &[u8]| {
+    check_canonical::<PairingGIPAProof>(data);
+});
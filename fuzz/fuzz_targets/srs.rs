@@ -0,0 +1,16 @@
+#![no_main]
+use ark_bls12_381::Bls12_381;
+use ark_ip_proofs::tipa::{VerifierSRS, SRS};
+use libfuzzer_sys::fuzz_target;
+use ripp_fuzz::check_canonical;
+
+// Prover and verifier SRS files, as written by the ripp setup command
+fuzz_target!(|data: &[u8]| {
+    if let Some((kind, data)) = data.split_first() {
+        if kind % 2 == 0 {
+            check_canonical::<SRS<Bls12_381>>(data);
+        } else {
+            check_canonical::<VerifierSRS<Bls12_381>>(data);
+        }
+    }
+});
@@ -0,0 +1,47 @@
+#![no_main]
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_dh_commitments::{
+    afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+    identity::IdentityCommitment,
+};
+use ark_ec::pairing::Pairing;
+use ark_inner_products::{
+    ExtensionFieldElement, MultiexponentiationInnerProduct, PairingInnerProduct,
+};
+use ark_ip_proofs::{
+    applications::groth16_aggregation::AggregateProof,
+    tipa::{structured_scalar_message::TIPAWithSSMProof, TIPAProof},
+};
+use blake2::Blake2b512;
+use libfuzzer_sys::fuzz_target;
+use ripp_fuzz::check_canonical;
+
+type G1 = <Bls12_381 as Pairing>::G1;
+
+type PairingTIPAProof = TIPAProof<
+    PairingInnerProduct<Bls12_381>,
+    AFGHOCommitmentG1<Bls12_381>,
+    AFGHOCommitmentG2<Bls12_381>,
+    IdentityCommitment<ExtensionFieldElement<Bls12_381>, Fr>,
+    Bls12_381,
+    Blake2b512,
+>;
+
+type MultiexponentiationTIPAProof = TIPAWithSSMProof<
+    MultiexponentiationInnerProduct<G1>,
+    AFGHOCommitmentG1<Bls12_381>,
+    IdentityCommitment<G1, Fr>,
+    Bls12_381,
+    Blake2b512,
+>;
+
+// The first byte picks the proof type, so one corpus covers the TIPA proofs of Groth16 aggregation
+fuzz_target!(|data: &[u8]| {
+    if let Some((kind, data)) = data.split_first() {
+        match kind % 3 {
+            0 => check_canonical::<PairingTIPAProof>(data),
+            1 => check_canonical::<MultiexponentiationTIPAProof>(data),
+            _ => check_canonical::<AggregateProof<Bls12_381, Blake2b512>>(data),
+        }
+    }
+});
@@ -0,0 +1,24 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+// Decodes arbitrary bytes as a T, which must not panic. A decoded value must re-serialize to an
+// encoding of the same length that decodes again and is stable under another round trip. The
+// input itself need not be canonical, e.g. the x coordinate of a point at infinity is ignored.
+pub fn check_canonical<T: CanonicalSerialize + CanonicalDeserialize>(data: &[u8]) {
+    let mut reader = data;
+    let value = match T::deserialize_compressed(&mut reader) {
+        Ok(value) => value,
+        Err(_) => return,
+    };
+    let consumed = data.len() - reader.len();
+
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), consumed);
+    assert_eq!(bytes.len(), value.compressed_size());
+
+    let decoded =
+        T::deserialize_compressed(&bytes[..]).expect("re-serialized value does not decode");
+    let mut round_trip = Vec::new();
+    decoded.serialize_compressed(&mut round_trip).unwrap();
+    assert_eq!(bytes, round_trip);
+}
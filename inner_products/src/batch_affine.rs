@@ -0,0 +1,86 @@
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::{batch_inversion, Field, One, Zero};
+use ark_std::vec::Vec;
+
+use crate::{check_message_lengths, Error};
+
+// Sums a_i + b_i of affine short Weierstrass points sharing a single field inversion across all
+// additions (Montgomery's trick), instead of one inversion per addition or projective formulas
+pub fn batch_affine_add<P: SWCurveConfig>(
+    a: &[Affine<P>],
+    b: &[Affine<P>],
+) -> Result<Vec<Affine<P>>, Error> {
+    check_message_lengths("batch affine addition", a.len(), b.len())?;
+    // Denominator of the slope of each addition, one where there is no slope
+    let mut denominators = a
+        .iter()
+        .zip(b)
+        .map(|(p, q)| {
+            if p.infinity || q.infinity {
+                P::BaseField::one()
+            } else if p.x != q.x {
+                q.x - p.x
+            } else if p.y == q.y && !p.y.is_zero() {
+                p.y.double()
+            } else {
+                P::BaseField::one()
+            }
+        })
+        .collect::<Vec<_>>();
+    batch_inversion(&mut denominators);
+    Ok(a.iter()
+        .zip(b)
+        .zip(denominators)
+        .map(|((p, q), inverse)| {
+            if p.infinity {
+                return *q;
+            }
+            if q.infinity {
+                return *p;
+            }
+            let slope = if p.x != q.x {
+                (q.y - p.y) * inverse
+            } else if p.y == q.y && !p.y.is_zero() {
+                (p.x.square() * P::BaseField::from(3u64) + P::COEFF_A) * inverse
+            } else {
+                // q = -p
+                return Affine::identity();
+            };
+            let x = slope.square() - p.x - q.x;
+            let y = slope * (p.x - x) - p.y;
+            Affine::new_unchecked(x, y)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::G1Projective;
+    use ark_ec::{AffineRepr, CurveGroup, Group};
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type G = G1Projective;
+
+    #[test]
+    fn batch_affine_add_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let p = G::rand(&mut rng).into_affine();
+        let q = G::rand(&mut rng).into_affine();
+        let zero = <G as CurveGroup>::Affine::zero();
+        // Distinct points, a doubling, opposite points and the identity on either side
+        let a = vec![p, p, p, zero, p, zero];
+        let b = vec![q, p, -p, q, zero, zero];
+        let sums = batch_affine_add(&a, &b).unwrap();
+        for ((x, y), sum) in a.iter().zip(&b).zip(&sums) {
+            assert_eq!(sum.into_group(), x.into_group() + y.into_group());
+        }
+        assert_eq!(sums[1].into_group(), p.into_group().double());
+        assert!(sums[2].is_zero());
+        assert!(batch_affine_add(&a[1..], &b).is_err());
+        assert!(batch_affine_add::<ark_bls12_381::g1::Config>(&[], &[])
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use ark_ec::pairing::Pairing;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{fmt, vec::Vec};
+use serde::{
+    de::{Error as DeError, SeqAccess, Visitor},
+    ser::Error as SerError,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{CompressedExtensionFieldElement, ExtensionFieldElement};
+
+// Used by `impl_canonical_serde!` in downstream crates, which need not depend on either
+#[doc(hidden)]
+pub use ark_serialize;
+#[doc(hidden)]
+pub use serde;
+
+// Serde support through the canonical byte encoding, for use as #[serde(with = "canonical_serde")]
+// on fields of arkworks types, e.g. keys and outputs that are curve points or field elements
+pub fn serialize<T: CanonicalSerialize, S: Serializer>(t: &T, s: S) -> Result<S::Ok, S::Error> {
+    let mut bytes = Vec::with_capacity(t.compressed_size());
+    t.serialize_compressed(&mut bytes)
+        .map_err(S::Error::custom)?;
+    s.serialize_bytes(&bytes)
+}
+
+pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+    d: D,
+) -> Result<T, D::Error> {
+    let bytes = d.deserialize_bytes(BytesVisitor)?;
+    T::deserialize_compressed(&bytes[..]).map_err(D::Error::custom)
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("canonically serialized bytes")
+    }
+
+    fn visit_bytes<E: DeError>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: DeError>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    // Formats without a byte type, e.g. JSON, encode bytes as a sequence
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(bytes)
+    }
+}
+
+// Wrapper giving serde support to any canonically serializable value
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Canonical<T>(pub T);
+
+impl<T: CanonicalSerialize> Serialize for Canonical<T> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, s)
+    }
+}
+
+impl<'de, T: CanonicalDeserialize> Deserialize<'de> for Canonical<T> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        Ok(Canonical(deserialize(d)?))
+    }
+}
+
+// Implements Serialize and Deserialize through the canonical byte encoding for a type with
+// canonical serialization. Bounds the type needs to be well-formed go in the optional where clause,
+// and its generic parameters must not be named Ser or De.
+#[macro_export]
+macro_rules! impl_canonical_serde {
+    ([$($generics:tt)*] $ty:ty $(where $($bounds:tt)*)?) => {
+        impl<$($generics)*> $crate::canonical_serde::serde::Serialize for $ty
+        where
+            $ty: $crate::canonical_serde::ark_serialize::CanonicalSerialize,
+            $($($bounds)*)?
+        {
+            fn serialize<Ser: $crate::canonical_serde::serde::Serializer>(
+                &self,
+                s: Ser,
+            ) -> Result<Ser::Ok, Ser::Error> {
+                $crate::canonical_serde::serialize(self, s)
+            }
+        }
+
+        impl<'de, $($generics)*> $crate::canonical_serde::serde::Deserialize<'de> for $ty
+        where
+            $ty: $crate::canonical_serde::ark_serialize::CanonicalDeserialize,
+            $($($bounds)*)?
+        {
+            fn deserialize<De: $crate::canonical_serde::serde::Deserializer<'de>>(
+                d: De,
+            ) -> Result<Self, De::Error> {
+                $crate::canonical_serde::deserialize(d)
+            }
+        }
+    };
+}
+
+impl_canonical_serde!([P: Pairing] ExtensionFieldElement<P>);
+impl_canonical_serde!([P: Pairing] CompressedExtensionFieldElement<P>);
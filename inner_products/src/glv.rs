@@ -0,0 +1,398 @@
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    AffineRepr, CurveConfig, CurveGroup, Group,
+};
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use ark_std::{cfg_iter, convert::TryFrom, vec, vec::Vec};
+use num_bigint::{BigInt as Integer, BigUint, Sign};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    check_message_lengths,
+    msm::{MsmBackend, PippengerMsm},
+    Error,
+};
+
+// Below this many points the decomposition and the tables of images do not pay off
+pub const GLV_MIN_POINTS: usize = 64;
+
+type BigInt<P> = <<P as CurveConfig>::ScalarField as PrimeField>::BigInt;
+
+// Half of a decomposed scalar: whether it is negative, and its absolute value
+pub type Half<P> = (bool, BigInt<P>);
+
+// Endomorphism phi(x, y) = (beta x, y) of a short Weierstrass curve of j-invariant 0, acting on the
+// prime order subgroup as multiplication by a cube root of unity lambda. Scalars are split as
+// k = k_1 + k_2 lambda with k_1 and k_2 of about half the bits of the modulus, so k P =
+// k_1 P + k_2 phi(P) takes half the doublings.
+//
+// Arkworks curves do not expose their endomorphisms, so they are found at runtime from cube roots
+// of unity and checked on the generator, once per curve with std. The search also reduces a basis
+// of the lattice of (a, b) with a + b lambda = 0 mod r, and scalars are decomposed by rounding
+// against it (Babai), which balances the halves for BN curves as well as for BLS12 curves
+pub struct Endomorphism<P: SWCurveConfig> {
+    beta: P::BaseField,
+    lambda: P::ScalarField,
+    basis: [(Integer, Integer); 2],
+}
+
+impl<P: SWCurveConfig> Clone for Endomorphism<P> {
+    fn clone(&self) -> Self {
+        Endomorphism {
+            beta: self.beta,
+            lambda: self.lambda,
+            basis: self.basis.clone(),
+        }
+    }
+}
+
+impl<P: SWCurveConfig> Endomorphism<P> {
+    // Endomorphism of curve `P`, searched for on the first call for each curve with std
+    pub fn find() -> Option<Self> {
+        #[cfg(feature = "std")]
+        return cache::get_or_search::<P>();
+        #[cfg(not(feature = "std"))]
+        Self::search()
+    }
+
+    // Endomorphism for inputs of `len` points, if long enough to make use of it
+    pub fn find_for(len: usize) -> Option<Self> {
+        if len < GLV_MIN_POINTS {
+            return None;
+        }
+        Self::find()
+    }
+
+    fn search() -> Option<Self> {
+        if !P::COEFF_A.is_zero() {
+            return None;
+        }
+        let beta = cube_root_of_unity::<P::BaseField>()?;
+        let lambda = cube_root_of_unity::<P::ScalarField>()?;
+        let generator = Affine::<P>::generator();
+        let expected = generator.mul_bigint(lambda.into_bigint());
+        let beta = [beta, beta.square()]
+            .iter()
+            .copied()
+            .find(|beta| Affine::new_unchecked(generator.x * beta, generator.y) == expected)?;
+        let basis = reduced_basis(
+            &P::ScalarField::MODULUS.into(),
+            &lambda.into_bigint().into(),
+        );
+        Some(Endomorphism {
+            beta,
+            lambda,
+            basis,
+        })
+    }
+
+    pub fn lambda(&self) -> P::ScalarField {
+        self.lambda
+    }
+
+    // phi(x, y) = (beta x, y) on the affine coordinates
+    pub fn apply(&self, p: &Affine<P>) -> Affine<P> {
+        if p.infinity {
+            return *p;
+        }
+        Affine::new_unchecked(p.x * self.beta, p.y)
+    }
+
+    // (k_1, k_2) with k = k_1 + k_2 lambda, rounding k against the reduced basis
+    pub fn decompose(&self, k: &P::ScalarField) -> [Half<P>; 2] {
+        self.decompose_bigint(&k.into_bigint())
+    }
+
+    fn decompose_bigint(&self, k: &BigInt<P>) -> [Half<P>; 2] {
+        let k = Integer::from_biguint(Sign::Plus, (*k).into());
+        let [(a_1, b_1), (a_2, b_2)] = &self.basis;
+        let det = a_1 * b_2 - a_2 * b_1;
+        let c_1 = round_div(&(b_2 * &k), &det);
+        let c_2 = round_div(&-(b_1 * &k), &det);
+        let k_1 = k - &c_1 * a_1 - &c_2 * a_2;
+        let k_2 = -(c_1 * b_1) - c_2 * b_2;
+        [k_1, k_2].map(|k_i| {
+            let magnitude = BigInt::<P>::try_from(k_i.magnitude().clone())
+                .expect("halves are shorter than the modulus");
+            (k_i.sign() == Sign::Minus, magnitude)
+        })
+    }
+
+    // k P by interleaving the doublings of k_1 P and k_2 phi(P)
+    pub fn mul(&self, p: &Affine<P>, k: &P::ScalarField) -> Projective<P> {
+        let [k_1, k_2] = self.decompose(k);
+        let (p, phi_p) = (signed(p, k_1.0), signed(&self.apply(p), k_2.0));
+        Self::mul_decomposed(&[p, phi_p, (p + phi_p).into_affine()], (&k_1.1, &k_2.1))
+    }
+
+    // Table of P, phi(P) and P + phi(P), each negated along with its half
+    fn mul_decomposed(table: &[Affine<P>; 3], k: (&BigInt<P>, &BigInt<P>)) -> Projective<P> {
+        let bits = k.0.num_bits().max(k.1.num_bits()) as usize;
+        let mut acc = Projective::<P>::zero();
+        for i in (0..bits).rev() {
+            acc.double_in_place();
+            match (k.0.get_bit(i), k.1.get_bit(i)) {
+                (true, false) => acc += &table[0],
+                (false, true) => acc += &table[1],
+                (true, true) => acc += &table[2],
+                (false, false) => {}
+            }
+        }
+        acc
+    }
+
+    // c P for every point, sharing the decomposition of c and batch normalizing the tables
+    pub fn mul_all(&self, points: &[Affine<P>], c: &P::ScalarField) -> Vec<Projective<P>> {
+        let [c_1, c_2] = self.decompose(c);
+        let points = cfg_iter!(points)
+            .map(|p| (signed(p, c_1.0), signed(&self.apply(p), c_2.0)))
+            .collect::<Vec<_>>();
+        let sums = Projective::normalize_batch(
+            &points
+                .iter()
+                .map(|(p, phi_p)| *p + phi_p)
+                .collect::<Vec<_>>(),
+        );
+        cfg_iter!(points)
+            .zip(&sums)
+            .map(|((p, phi_p), sum)| Self::mul_decomposed(&[*p, *phi_p, *sum], (&c_1.1, &c_2.1)))
+            .collect()
+    }
+
+    // sum_i k_i P_i as an MSM over the points and their images with the halves of the scalars
+    pub fn msm(&self, points: &[Affine<P>], scalars: &[BigInt<P>]) -> Result<Projective<P>, Error> {
+        check_message_lengths(
+            "GLV multi-scalar multiplication",
+            points.len(),
+            scalars.len(),
+        )?;
+        let mut bases = Vec::with_capacity(2 * points.len());
+        let mut digits = Vec::with_capacity(2 * points.len());
+        for (p, k) in points.iter().zip(scalars) {
+            let [k_1, k_2] = self.decompose_bigint(k);
+            bases.push(signed(p, k_1.0));
+            bases.push(signed(&self.apply(p), k_2.0));
+            digits.push(k_1.1);
+            digits.push(k_2.1);
+        }
+        PippengerMsm::multi_scalar_mul(&bases, &digits)
+    }
+}
+
+fn signed<P: SWCurveConfig>(p: &Affine<P>, negative: bool) -> Affine<P> {
+    if negative {
+        -*p
+    } else {
+        *p
+    }
+}
+
+// Reduced basis of the lattice of (a, b) with a + b lambda = 0 mod r, from the extended Euclidean
+// algorithm on (r, lambda): its remainders r_i = s_i r + t_i lambda give the lattice vectors
+// (r_i, -t_i), the shortest of which straddle the first remainder below sqrt(r)
+fn reduced_basis(r: &BigUint, lambda: &BigUint) -> [(Integer, Integer); 2] {
+    let sqrt_r = Integer::from_biguint(Sign::Plus, r.sqrt());
+    let (mut r_0, mut r_1) = (
+        Integer::from_biguint(Sign::Plus, r.clone()),
+        Integer::from_biguint(Sign::Plus, lambda.clone()),
+    );
+    let (mut t_0, mut t_1) = (Integer::zero(), Integer::from(1u64));
+    while r_1 >= sqrt_r {
+        let q = &r_0 / &r_1;
+        let r_2 = &r_0 - &q * &r_1;
+        let t_2 = &t_0 - &q * &t_1;
+        (r_0, r_1, t_0, t_1) = (r_1, r_2, t_1, t_2);
+    }
+    let q = &r_0 / &r_1;
+    let (r_2, t_2) = (&r_0 - &q * &r_1, &t_0 - &q * &t_1);
+    let norm = |(a, b): &(Integer, Integer)| a * a + b * b;
+    let (v_0, v_2) = ((r_0, -t_0), (r_2, -t_2));
+    let v_2 = if norm(&v_0) <= norm(&v_2) { v_0 } else { v_2 };
+    [(r_1, -t_1), v_2]
+}
+
+// Nearest integer to a / b
+fn round_div(a: &Integer, b: &Integer) -> Integer {
+    let (a, b) = if b.sign() == Sign::Minus {
+        (-a, -b)
+    } else {
+        (a.clone(), b.clone())
+    };
+    // floor((2a + b) / 2b), correcting the truncation of negative quotients
+    let (n, d) = (a * 2u64 + &b, b * 2u64);
+    let q = &n / &d;
+    if n.sign() == Sign::Minus && !(&n % &d).is_zero() {
+        q - 1u64
+    } else {
+        q
+    }
+}
+
+#[cfg(feature = "std")]
+mod cache {
+    use super::*;
+    use std::{
+        any::{Any, TypeId},
+        boxed::Box,
+        sync::RwLock,
+    };
+
+    // Endomorphism found for each curve, none for curves without one
+    static ENDOMORPHISMS: RwLock<Vec<(TypeId, Option<Box<dyn Any + Send + Sync>>)>> =
+        RwLock::new(Vec::new());
+
+    pub fn get_or_search<P: SWCurveConfig>() -> Option<Endomorphism<P>> {
+        let key = TypeId::of::<P>();
+        let cached = |e: &Option<Box<dyn Any + Send + Sync>>| {
+            e.as_ref()
+                .and_then(|e| e.downcast_ref::<Endomorphism<P>>())
+                .cloned()
+        };
+        if let Some((_, e)) = ENDOMORPHISMS
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(g, _)| *g == key)
+        {
+            return cached(e);
+        }
+        let mut endomorphisms = ENDOMORPHISMS.write().unwrap();
+        // Another thread may have searched while the lock was released
+        if let Some((_, e)) = endomorphisms.iter().find(|(g, _)| *g == key) {
+            return cached(e);
+        }
+        let endomorphism = Endomorphism::<P>::search();
+        endomorphisms.push((
+            key,
+            endomorphism
+                .clone()
+                .map(|e| Box::new(e) as Box<dyn Any + Send + Sync>),
+        ));
+        endomorphism
+    }
+}
+
+// A nontrivial cube root of unity of F, from a small element raised to (|F| - 1) / 3
+fn cube_root_of_unity<F: Field>() -> Option<F> {
+    let mut order = vec![1u64];
+    for _ in 0..F::extension_degree() {
+        order = mul_limbs(&order, F::characteristic());
+    }
+    // |F| > 1, so subtracting one does not borrow past the last limb
+    for limb in order.iter_mut() {
+        let (diff, borrow) = limb.overflowing_sub(1);
+        *limb = diff;
+        if !borrow {
+            break;
+        }
+    }
+    let mut exponent = order;
+    let mut rem = 0u128;
+    for limb in exponent.iter_mut().rev() {
+        let acc = (rem << 64) | *limb as u128;
+        *limb = (acc / 3) as u64;
+        rem = acc % 3;
+    }
+    if rem != 0 {
+        return None;
+    }
+    (2..16u64)
+        .map(|a| F::from(a).pow(&exponent))
+        .find(|root| !root.is_one())
+}
+
+fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut product = vec![0u64; a.len() + b.len()];
+    for (i, a_i) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, b_j) in b.iter().enumerate() {
+            let acc = product[i + j] as u128 + (*a_i as u128) * (*b_j as u128) + carry;
+            product[i + j] = acc as u64;
+            carry = acc >> 64;
+        }
+        product[i + b.len()] = carry as u64;
+    }
+    product
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::{One, UniformRand};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    fn check_endomorphism<P: SWCurveConfig>(rng: &mut StdRng) {
+        let endomorphism = Endomorphism::<P>::find().unwrap();
+        let points = (0..TEST_SIZE)
+            .map(|_| Projective::<P>::rand(rng).into_affine())
+            .collect::<Vec<_>>();
+        let mut scalars = (0..TEST_SIZE)
+            .map(|_| P::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        scalars.extend([
+            P::ScalarField::zero(),
+            P::ScalarField::one(),
+            -P::ScalarField::one(),
+            endomorphism.lambda,
+        ]);
+        let lambda = endomorphism.lambda;
+        // Both halves have about half the bits of the modulus, also for BN curves
+        let half_bits = P::ScalarField::MODULUS_BIT_SIZE.div_ceil(2) + 1;
+        for k in &scalars {
+            let [(neg_1, k_1), (neg_2, k_2)] = endomorphism.decompose(k);
+            assert!(k_1.num_bits() <= half_bits && k_2.num_bits() <= half_bits);
+            let signed = |negative: bool, k_i| {
+                let k_i = P::ScalarField::from_bigint(k_i).unwrap();
+                if negative {
+                    -k_i
+                } else {
+                    k_i
+                }
+            };
+            assert_eq!(signed(neg_1, k_1) + signed(neg_2, k_2) * lambda, *k);
+        }
+        for p in &points {
+            assert_eq!(endomorphism.apply(p), *p * lambda);
+        }
+        for (p, k) in points.iter().zip(&scalars) {
+            assert_eq!(endomorphism.mul(p, k), *p * k);
+        }
+        for c in &scalars {
+            assert_eq!(
+                endomorphism.mul_all(&points, c),
+                points.iter().map(|p| *p * c).collect::<Vec<_>>()
+            );
+        }
+        let bigints = scalars[..TEST_SIZE]
+            .iter()
+            .map(|k| k.into_bigint())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            endomorphism.msm(&points, &bigints).unwrap(),
+            <PippengerMsm as MsmBackend<Projective<P>>>::multi_scalar_mul(&points, &bigints)
+                .unwrap()
+        );
+        assert!(endomorphism.msm(&points[1..], &bigints).is_err());
+        assert!(endomorphism.apply(&Affine::identity()).is_zero());
+    }
+
+    #[test]
+    fn endomorphism_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        check_endomorphism::<ark_bls12_381::g1::Config>(&mut rng);
+        check_endomorphism::<ark_bls12_381::g2::Config>(&mut rng);
+        check_endomorphism::<ark_bn254::g1::Config>(&mut rng);
+        check_endomorphism::<ark_bn254::g2::Config>(&mut rng);
+        // Found once and then served from the cache
+        type G1 = ark_bls12_381::g1::Config;
+        assert_eq!(
+            Endomorphism::<G1>::find().unwrap().beta,
+            Endomorphism::<G1>::search().unwrap().beta
+        );
+        assert!(Endomorphism::<G1>::find_for(GLV_MIN_POINTS - 1).is_none());
+        assert!(Endomorphism::<G1>::find_for(GLV_MIN_POINTS).is_some());
+    }
+}
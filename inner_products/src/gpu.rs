@@ -0,0 +1,181 @@
+use ark_ec::{pairing::Pairing, CurveGroup, Group};
+use ark_ff::PrimeField;
+use ark_std::{boxed::Box, vec::Vec};
+use std::{
+    any::{Any, TypeId},
+    sync::RwLock,
+};
+
+use crate::{
+    check_message_lengths,
+    msm::{MsmBackend, PippengerMsm},
+    pairing::PairingBackend,
+    Error,
+};
+
+type BigInt<G> = <<G as Group>::ScalarField as PrimeField>::BigInt;
+
+// Kernels of an accelerator, e.g. CUDA or Metal kernels of an external crate. They return none when
+// they cannot run, e.g. without a device, and the backend then computes on the CPU
+pub type MsmKernel<G> = fn(&[<G as CurveGroup>::Affine], &[BigInt<G>]) -> Option<G>;
+pub type MillerLoopKernel<P> = fn(
+    &[<P as Pairing>::G1Prepared],
+    &[<P as Pairing>::G2Prepared],
+) -> Option<<P as Pairing>::TargetField>;
+
+// Backend handing MSMs and multi-Miller loops to the kernels registered for the curve, falling back
+// to `PippengerMsm` and the arkworks Miller loop. Kernels are registered at runtime, once per curve,
+// so the backend stays a stateless type parameter like the CPU backends
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GpuBackend;
+
+impl<G: CurveGroup> MsmBackend<G> for GpuBackend {
+    fn multi_scalar_mul(bases: &[G::Affine], scalars: &[BigInt<G>]) -> Result<G, Error> {
+        check_message_lengths(
+            "GPU multi-scalar multiplication",
+            bases.len(),
+            scalars.len(),
+        )?;
+        match msm_kernel::<G>().and_then(|kernel| kernel(bases, scalars)) {
+            Some(msm) => Ok(msm),
+            None => PippengerMsm::multi_scalar_mul(bases, scalars),
+        }
+    }
+}
+
+impl<P: Pairing> PairingBackend<P> for GpuBackend {
+    fn multi_miller_loop(
+        left: &[P::G1Prepared],
+        right: &[P::G2Prepared],
+    ) -> Result<P::TargetField, Error> {
+        check_message_lengths("GPU multi-Miller loop", left.len(), right.len())?;
+        match miller_loop_kernel::<P>().and_then(|kernel| kernel(left, right)) {
+            Some(unreduced) => Ok(unreduced),
+            None => <PippengerMsm as PairingBackend<P>>::multi_miller_loop(left, right),
+        }
+    }
+}
+
+// Kernels by the type of their group or pairing, a later registration replacing an earlier one
+type Registry = RwLock<Vec<(TypeId, Box<dyn Any + Send + Sync>)>>;
+
+static MSM_KERNELS: Registry = RwLock::new(Vec::new());
+static MILLER_LOOP_KERNELS: Registry = RwLock::new(Vec::new());
+
+fn register<K: Any + Send + Sync>(registry: &Registry, key: TypeId, kernel: K) {
+    let mut kernels = registry.write().unwrap();
+    kernels.retain(|(k, _)| *k != key);
+    kernels.push((key, Box::new(kernel)));
+}
+
+fn get<K: Any + Copy>(registry: &Registry, key: TypeId) -> Option<K> {
+    registry
+        .read()
+        .unwrap()
+        .iter()
+        .find(|(k, _)| *k == key)
+        .and_then(|(_, kernel)| kernel.downcast_ref::<K>().copied())
+}
+
+pub fn register_msm_kernel<G: CurveGroup>(kernel: MsmKernel<G>) {
+    register(&MSM_KERNELS, TypeId::of::<G>(), kernel)
+}
+
+pub fn register_miller_loop_kernel<P: Pairing>(kernel: MillerLoopKernel<P>) {
+    register(&MILLER_LOOP_KERNELS, TypeId::of::<P>(), kernel)
+}
+
+pub fn msm_kernel<G: CurveGroup>() -> Option<MsmKernel<G>> {
+    get(&MSM_KERNELS, TypeId::of::<G>())
+}
+
+pub fn miller_loop_kernel<P: Pairing>() -> Option<MillerLoopKernel<P>> {
+    get(&MILLER_LOOP_KERNELS, TypeId::of::<P>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msm::NaiveMsm;
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ec::AffineRepr;
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const TEST_SIZE: usize = 8;
+
+    static MSM_CALLS: AtomicUsize = AtomicUsize::new(0);
+    static MILLER_LOOP_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    // Stand-ins for device kernels, counting their calls and declining inputs of one point
+    fn counting_msm_kernel(
+        bases: &[<G1Projective as CurveGroup>::Affine],
+        scalars: &[BigInt<G1Projective>],
+    ) -> Option<G1Projective> {
+        MSM_CALLS.fetch_add(1, Ordering::SeqCst);
+        (bases.len() > 1).then(|| NaiveMsm::multi_scalar_mul(bases, scalars).unwrap())
+    }
+
+    fn counting_miller_loop_kernel(
+        left: &[<Bn254 as Pairing>::G1Prepared],
+        right: &[<Bn254 as Pairing>::G2Prepared],
+    ) -> Option<<Bn254 as Pairing>::TargetField> {
+        MILLER_LOOP_CALLS.fetch_add(1, Ordering::SeqCst);
+        Some(Bn254::multi_miller_loop(left.iter().cloned(), right.iter().cloned()).0)
+    }
+
+    #[test]
+    fn gpu_backend_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let bases = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng).into_affine())
+            .collect::<Vec<_>>();
+        let scalars = (0..TEST_SIZE)
+            .map(|_| Fr::rand(&mut rng).into_bigint())
+            .collect::<Vec<_>>();
+        let g2 = (0..TEST_SIZE)
+            .map(|_| <Bn254 as Pairing>::G2Prepared::from(G2Projective::rand(&mut rng)))
+            .collect::<Vec<_>>();
+        let g1 = bases
+            .iter()
+            .map(|g| <Bn254 as Pairing>::G1Prepared::from(*g))
+            .collect::<Vec<_>>();
+        let expected_msm: G1Projective = PippengerMsm::multi_scalar_mul(&bases, &scalars).unwrap();
+        let expected_loop = <PippengerMsm as PairingBackend<Bn254>>::multi_miller_loop(&g1, &g2);
+
+        // Without kernels the backend computes on the CPU
+        assert!(msm_kernel::<G1Projective>().is_none());
+        assert_eq!(
+            <GpuBackend as MsmBackend<G1Projective>>::multi_scalar_mul(&bases, &scalars).unwrap(),
+            expected_msm
+        );
+
+        register_msm_kernel::<G1Projective>(counting_msm_kernel);
+        register_miller_loop_kernel::<Bn254>(counting_miller_loop_kernel);
+        assert_eq!(
+            <GpuBackend as MsmBackend<G1Projective>>::multi_scalar_mul(&bases, &scalars).unwrap(),
+            expected_msm
+        );
+        assert_eq!(MSM_CALLS.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            <GpuBackend as PairingBackend<Bn254>>::multi_miller_loop(&g1, &g2).unwrap(),
+            expected_loop.unwrap()
+        );
+        assert_eq!(MILLER_LOOP_CALLS.load(Ordering::SeqCst), 1);
+        // Declined by the kernel, and rejected before reaching it
+        assert_eq!(
+            <GpuBackend as MsmBackend<G1Projective>>::multi_scalar_mul(&bases[..1], &scalars[..1])
+                .unwrap(),
+            bases[0].mul_bigint(scalars[0])
+        );
+        assert_eq!(MSM_CALLS.load(Ordering::SeqCst), 2);
+        assert!(
+            <GpuBackend as MsmBackend<G1Projective>>::multi_scalar_mul(&bases[1..], &scalars)
+                .is_err()
+        );
+        assert!(<GpuBackend as PairingBackend<Bn254>>::multi_miller_loop(&g1[1..], &g2).is_err());
+        assert_eq!(MSM_CALLS.load(Ordering::SeqCst), 2);
+        assert_eq!(MILLER_LOOP_CALLS.load(Ordering::SeqCst), 1);
+    }
+}
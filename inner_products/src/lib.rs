@@ -1,107 +1,602 @@
-use ark_ec::{msm::VariableBaseMSM, PairingEngine, ProjectiveCurve};
-use ark_ff::{bytes::ToBytes, Field, PrimeField};
-use ark_std::{cfg_into_iter, cfg_iter};
-use std::{
-    error::Error as ErrorTrait,
-    fmt::{Display, Formatter, Result as FmtResult},
-    io::{Result as IoResult, Write},
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::type_complexity)]
+use ark_ec::{
+    pairing::{MillerLoopOutput, Pairing},
+    scalar_mul::fixed_base::FixedBase,
+    CurveGroup, Group,
+};
+use ark_ff::{Field, One, PrimeField, QuadExtConfig, QuadExtField, UniformRand, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::{
+    cfg_into_iter, cfg_iter,
+    convert::TryInto,
     marker::PhantomData,
     ops::{Add, Mul, MulAssign},
+    vec::Vec,
+};
+use rand::{
+    distributions::{Distribution, Standard},
+    Rng,
 };
+use thiserror::Error;
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-pub type Error = Box<dyn ErrorTrait>;
+// Reports work to the `Metrics` hook of the current thread, a no-op without the metrics feature
+macro_rules! report_metrics {
+    ($name:ident, $value:expr) => {
+        #[cfg(feature = "metrics")]
+        crate::metrics::report(|metrics| metrics.$name($value as u64));
+    };
+}
 
-#[derive(Debug)]
-pub enum InnerProductError {
-    MessageLengthInvalid(usize, usize),
+pub mod batch_affine;
+#[cfg(feature = "serde")]
+pub mod canonical_serde;
+pub mod glv;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod msm;
+pub mod pairing;
+
+use msm::{MsmBackend, PippengerMsm, PrecomputedBases};
+
+pub type Error = InnerProductError;
+
+// Inner products are split into chunks computed on separate threads, one chunk per thread, and
+// the partial products combined at the end
+#[cfg(feature = "parallel")]
+pub fn parallel_chunk_size(len: usize) -> usize {
+    let num_threads = rayon::current_num_threads();
+    ark_std::cmp::max(1, len.div_ceil(num_threads))
+}
+
+// Input lengths at which the multiexponentiation and pairing inner products switch strategy, so
+// small inputs do not pay for the setup of the large-input algorithms
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InnerProductConfig {
+    // Up to this length, multiexponentiations sum individual scalar multiplications instead of
+    // running Pippenger's algorithm
+    pub naive_msm_max_len: usize,
+    // Up to this length, pairing inner products multiply the Miller loops of the individual pairs
+    // instead of running one multi-Miller loop, which shares the squarings across pairs
+    pub naive_pairing_max_len: usize,
+    // From these lengths, with the parallel feature, inputs are split across threads
+    pub parallel_msm_min_len: usize,
+    pub parallel_pairing_min_len: usize,
 }
 
-impl ErrorTrait for InnerProductError {
-    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
-        None
+// The naive switches as measured by `cargo bench --bench inner_products` over BLS12-381: naive
+// sums of G1 scalar multiplications beat Pippenger's algorithm up to two terms, and separate
+// Miller loops never beat a multi-Miller loop. The parallel switches depend on the thread count
+impl Default for InnerProductConfig {
+    fn default() -> Self {
+        InnerProductConfig {
+            naive_msm_max_len: 2,
+            naive_pairing_max_len: 1,
+            parallel_msm_min_len: 256,
+            parallel_pairing_min_len: 4,
+        }
     }
 }
 
-impl Display for InnerProductError {
-    fn fmt(self: &Self, f: &mut Formatter<'_>) -> FmtResult {
-        let msg = match self {
-            InnerProductError::MessageLengthInvalid(left, right) => {
-                format!("left length, right length: {}, {}", left, right)
-            }
-        };
-        write!(f, "{}", msg)
+#[derive(Debug, Error)]
+pub enum InnerProductError {
+    #[error("{inner_product} inner product of left length {left} and right length {right}")]
+    LengthMismatch {
+        inner_product: &'static str,
+        left: usize,
+        right: usize,
+    },
+    #[error("index, length: {0}, {1}")]
+    IndexOutOfRange(usize, usize),
+    #[error("chunk size must be positive")]
+    ChunkSizeZero,
+    #[error("final exponentiation of zero")]
+    FinalExponentiationOfZero,
+}
+
+pub fn check_message_lengths(
+    inner_product: &'static str,
+    left_len: usize,
+    right_len: usize,
+) -> Result<(), Error> {
+    if left_len != right_len {
+        return Err(InnerProductError::LengthMismatch {
+            inner_product,
+            left: left_len,
+            right: right_len,
+        });
     }
+    Ok(())
 }
 
-pub trait InnerProduct: Copy {
+// Extension point for the inner products GIPA is generic over. Implementations check that both
+// messages have the same length, and the output of messages of length zero is the identity of the
+// output group, so that inner products of concatenated messages add up. Bilinear maps on single
+// elements only need to implement BilinearMap.
+pub trait InnerProduct: Clone {
     type LeftMessage;
     type RightMessage;
     type Output;
 
+    // Name in errors, e.g. of mismatched message lengths
+    const NAME: &'static str = "custom";
+
+    // Left message prepared once when it is fixed across many inner products, e.g. a commitment key
+    type PreparedLeft;
+
     fn inner_product(
         left: &[Self::LeftMessage],
         right: &[Self::RightMessage],
     ) -> Result<Self::Output, Error>;
+
+    fn precompute_left(left: &[Self::LeftMessage]) -> Self::PreparedLeft;
+
+    fn inner_product_prepared(
+        prepared: &Self::PreparedLeft,
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error>;
+
+    // Inner product of messages streamed as pairs of chunks of equal length, e.g. read from disk,
+    // summing the inner products of the chunks without holding the whole messages in memory
+    fn inner_product_iter<I, L, R>(chunks: I) -> Result<Self::Output, Error>
+    where
+        I: IntoIterator<Item = (L, R)>,
+        L: AsRef<[Self::LeftMessage]>,
+        R: AsRef<[Self::RightMessage]>,
+        Self::Output: Add<Self::Output, Output = Self::Output>,
+    {
+        sum_inner_products::<Self, _>(chunks, |(left, right)| {
+            Self::inner_product(left.as_ref(), right.as_ref())
+        })
+    }
+    // Inner product computed over chunks of chunk_size elements, bounding the size of the
+    // intermediate values of each call
+    fn inner_product_chunked(
+        left: &[Self::LeftMessage],
+        right: &[Self::RightMessage],
+        chunk_size: usize,
+    ) -> Result<Self::Output, Error>
+    where
+        Self::Output: Add<Self::Output, Output = Self::Output>,
+    {
+        check_message_lengths(Self::NAME, left.len(), right.len())?;
+        if chunk_size == 0 {
+            return Err(InnerProductError::ChunkSizeZero);
+        }
+        Self::inner_product_iter(left.chunks(chunk_size).zip(right.chunks(chunk_size)))
+    }
+
+    // Inner product of `left` with `right` repeated to its length, i.e. the sum of the inner
+    // products of each block of `right.len()` elements of `left` with `right`
+    fn block_inner_product(
+        left: &[Self::LeftMessage],
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error>
+    where
+        Self::Output: Add<Self::Output, Output = Self::Output>,
+    {
+        if right.is_empty() || left.len() % right.len() != 0 {
+            return Err(InnerProductError::LengthMismatch {
+                inner_product: Self::NAME,
+                left: left.len(),
+                right: right.len(),
+            });
+        }
+        Self::inner_product_iter(left.chunks(right.len()).map(|block| (block, right)))
+    }
+}
+
+// Sum of the inner products of the items, seeded with the first rather than with the inner product
+// of empty messages, which is not defined for every inner product, e.g. polynomial evaluation
+fn sum_inner_products<IP, T>(
+    items: impl IntoIterator<Item = T>,
+    mut inner_product: impl FnMut(T) -> Result<IP::Output, Error>,
+) -> Result<IP::Output, Error>
+where
+    IP: InnerProduct,
+    IP::Output: Add<IP::Output, Output = IP::Output>,
+{
+    let mut items = items.into_iter();
+    let first = match items.next() {
+        Some(item) => inner_product(item)?,
+        None => return IP::inner_product(&[], &[]),
+    };
+    items.try_fold(first, |ip, item| Ok(ip + inner_product(item)?))
+}
+
+// Bilinear map e: L x R -> O into an additive group, defining the inner product
+// <a, b> = sum_i e(a_i, b_i) of BilinearInnerProduct, e.g. over lattice-like modules
+pub trait BilinearMap {
+    type Left: Clone;
+    type Right;
+    type Output: Add<Self::Output, Output = Self::Output>;
+
+    fn zero() -> Self::Output;
+
+    fn map(left: &Self::Left, right: &Self::Right) -> Self::Output;
+}
+
+pub struct BilinearInnerProduct<B: BilinearMap> {
+    _map: PhantomData<B>,
+}
+
+impl<B: BilinearMap> Clone for BilinearInnerProduct<B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B: BilinearMap> Copy for BilinearInnerProduct<B> {}
+
+impl<B: BilinearMap> InnerProduct for BilinearInnerProduct<B> {
+    type LeftMessage = B::Left;
+    type RightMessage = B::Right;
+    type Output = B::Output;
+    type PreparedLeft = Vec<B::Left>;
+    const NAME: &'static str = "bilinear";
+
+    fn inner_product(
+        left: &[Self::LeftMessage],
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        check_message_lengths(Self::NAME, left.len(), right.len())?;
+        Ok(left
+            .iter()
+            .zip(right)
+            .fold(B::zero(), |ip, (a, b)| ip + B::map(a, b)))
+    }
+
+    fn precompute_left(left: &[Self::LeftMessage]) -> Self::PreparedLeft {
+        left.to_vec()
+    }
+
+    fn inner_product_prepared(
+        prepared: &Self::PreparedLeft,
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        Self::inner_product(prepared, right)
+    }
 }
 
 #[derive(Copy, Clone)]
-pub struct PairingInnerProduct<P: PairingEngine> {
+pub struct PairingInnerProduct<P: Pairing> {
     _pair: PhantomData<P>,
 }
 
-impl<P: PairingEngine> InnerProduct for PairingInnerProduct<P> {
-    type LeftMessage = P::G1Projective;
-    type RightMessage = P::G2Projective;
+impl<P: Pairing> PairingInnerProduct<P> {
+    // Product of the Miller loops of all pairs, before the final exponentiation. Callers combining
+    // several inner products multiply these and take a single final exponentiation.
+    pub fn inner_product_unreduced(
+        left: &[P::G1],
+        right: &[P::G2],
+    ) -> Result<P::TargetField, Error> {
+        check_message_lengths(Self::NAME, left.len(), right.len())?;
+        Self::inner_product_prepared_unreduced(&Self::precompute_left(left), right)
+    }
+
+    pub fn inner_product_prepared_unreduced(
+        prepared: &[P::G1Prepared],
+        right: &[P::G2],
+    ) -> Result<P::TargetField, Error> {
+        Self::inner_product_prepared_unreduced_with_config(
+            prepared,
+            right,
+            &InnerProductConfig::default(),
+        )
+    }
+
+    pub fn inner_product_with_config(
+        left: &[P::G1],
+        right: &[P::G2],
+        config: &InnerProductConfig,
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        check_message_lengths(Self::NAME, left.len(), right.len())?;
+        Self::final_exponentiation(&Self::inner_product_prepared_unreduced_with_config(
+            &Self::precompute_left(left),
+            right,
+            config,
+        )?)
+    }
+
+    pub fn inner_product_prepared_unreduced_with_config(
+        prepared: &[P::G1Prepared],
+        right: &[P::G2],
+        config: &InnerProductConfig,
+    ) -> Result<P::TargetField, Error> {
+        check_message_lengths(Self::NAME, prepared.len(), right.len())?;
+        Ok(Self::miller_loop_with_config(
+            prepared,
+            &Self::precompute_right(right),
+            config,
+        ))
+    }
+
+    // Right message prepared once when it is fixed across many inner products, e.g. a commitment
+    // key in G2. Preparing a G2 point computes the line coefficients of its Miller loop, which is
+    // most of the cost of a pairing that the G1 side does not share
+    pub fn precompute_right(right: &[P::G2]) -> Vec<P::G2Prepared> {
+        cfg_into_iter!(P::G2::normalize_batch(right))
+            .map(P::G2Prepared::from)
+            .collect()
+    }
+
+    pub fn inner_product_prepared_right(
+        left: &[P::G1],
+        prepared: &[P::G2Prepared],
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        Self::final_exponentiation(&Self::inner_product_prepared_right_unreduced(
+            left, prepared,
+        )?)
+    }
+
+    pub fn inner_product_prepared_right_unreduced(
+        left: &[P::G1],
+        prepared: &[P::G2Prepared],
+    ) -> Result<P::TargetField, Error> {
+        check_message_lengths(Self::NAME, left.len(), prepared.len())?;
+        Ok(Self::miller_loop_with_config(
+            &Self::precompute_left(left),
+            prepared,
+            &InnerProductConfig::default(),
+        ))
+    }
+
+    fn miller_loop_with_config(
+        left: &[P::G1Prepared],
+        right: &[P::G2Prepared],
+        config: &InnerProductConfig,
+    ) -> P::TargetField {
+        report_metrics!(pairings, left.len());
+        if left.len() <= config.naive_pairing_max_len {
+            return left
+                .iter()
+                .zip(right)
+                .map(|(l, r)| {
+                    Self::miller_loop(ark_std::slice::from_ref(l), ark_std::slice::from_ref(r))
+                })
+                .product();
+        }
+        #[cfg(feature = "parallel")]
+        if left.len() >= config.parallel_pairing_min_len {
+            let chunk_size = parallel_chunk_size(left.len());
+            return left
+                .par_chunks(chunk_size)
+                .zip(right.par_chunks(chunk_size))
+                .map(|(l, r)| Self::miller_loop(l, r))
+                .product();
+        }
+        Self::miller_loop(left, right)
+    }
+
+    fn miller_loop(left: &[P::G1Prepared], right: &[P::G2Prepared]) -> P::TargetField {
+        P::multi_miller_loop(left.iter().cloned(), right.iter().cloned()).0
+    }
+
+    pub fn final_exponentiation(
+        unreduced: &P::TargetField,
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        report_metrics!(final_exponentiations, 1);
+        match P::final_exponentiation(MillerLoopOutput(*unreduced)) {
+            Some(el) => Ok(ExtensionFieldElement(el.0)),
+            None => Err(InnerProductError::FinalExponentiationOfZero),
+        }
+    }
+}
+
+impl<P: Pairing> InnerProduct for PairingInnerProduct<P> {
+    type LeftMessage = P::G1;
+    type RightMessage = P::G2;
     type Output = ExtensionFieldElement<P>;
+    type PreparedLeft = Vec<P::G1Prepared>;
+    const NAME: &'static str = "pairing";
 
+    // One multi-Miller loop over all pairs and one final exponentiation
     fn inner_product(
         left: &[Self::LeftMessage],
         right: &[Self::RightMessage],
     ) -> Result<Self::Output, Error> {
-        if left.len() != right.len() {
-            return Err(Box::new(InnerProductError::MessageLengthInvalid(
-                left.len(),
-                right.len(),
-            )));
-        };
-        let aff_left = P::G1Projective::batch_normalization_into_affine(left);
-        let aff_right = P::G2Projective::batch_normalization_into_affine(right);
-        let aff_pairs = cfg_into_iter!(aff_left)
-            .zip(aff_right)
-            .map(|(a, b)| (P::G1Prepared::from(a), P::G2Prepared::from(b)))
+        Self::final_exponentiation(&Self::inner_product_unreduced(left, right)?)
+    }
+
+    fn precompute_left(left: &[Self::LeftMessage]) -> Self::PreparedLeft {
+        cfg_into_iter!(P::G1::normalize_batch(left))
+            .map(P::G1Prepared::from)
+            .collect()
+    }
+
+    fn inner_product_prepared(
+        prepared: &Self::PreparedLeft,
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        Self::final_exponentiation(&Self::inner_product_prepared_unreduced(prepared, right)?)
+    }
+
+    // Single final exponentiation of the product of the Miller loops of all chunks
+    fn inner_product_iter<I, L, R>(chunks: I) -> Result<Self::Output, Error>
+    where
+        I: IntoIterator<Item = (L, R)>,
+        L: AsRef<[Self::LeftMessage]>,
+        R: AsRef<[Self::RightMessage]>,
+        Self::Output: Add<Self::Output, Output = Self::Output>,
+    {
+        let unreduced =
+            chunks
+                .into_iter()
+                .try_fold(P::TargetField::one(), |unreduced, (left, right)| {
+                    Ok::<_, Error>(
+                        unreduced * Self::inner_product_unreduced(left.as_ref(), right.as_ref())?,
+                    )
+                })?;
+        Self::final_exponentiation(&unreduced)
+    }
+}
+
+// Multiexponentiation computing its MSMs with backend `M`
+#[derive(Copy, Clone)]
+pub struct MsmInnerProduct<G: CurveGroup, M: MsmBackend<G>> {
+    _projective: PhantomData<(G, M)>,
+}
+
+pub type MultiexponentiationInnerProduct<G> = MsmInnerProduct<G, PippengerMsm>;
+
+impl<G: CurveGroup, M: MsmBackend<G>> InnerProduct for MsmInnerProduct<G, M> {
+    type LeftMessage = G;
+    type RightMessage = G::ScalarField;
+    type Output = G;
+    type PreparedLeft = PrecomputedBases<G>;
+    const NAME: &'static str = "multiexponentiation";
+
+    fn inner_product(
+        left: &[Self::LeftMessage],
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        check_message_lengths(Self::NAME, left.len(), right.len())?;
+        Self::inner_product_with_config(left, right, &InnerProductConfig::default())
+    }
+
+    fn precompute_left(left: &[Self::LeftMessage]) -> Self::PreparedLeft {
+        PrecomputedBases::new(left)
+    }
+
+    fn inner_product_prepared(
+        prepared: &Self::PreparedLeft,
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        check_message_lengths(Self::NAME, prepared.len(), right.len())?;
+        prepared.multi_scalar_mul(right)
+    }
+}
+
+impl<G: CurveGroup, M: MsmBackend<G>> MsmInnerProduct<G, M> {
+    pub fn inner_product_with_config(
+        left: &[G],
+        right: &[G::ScalarField],
+        config: &InnerProductConfig,
+    ) -> Result<G, Error> {
+        check_message_lengths(Self::NAME, left.len(), right.len())?;
+        report_metrics!(msm_terms, left.len());
+        if left.len() <= config.naive_msm_max_len {
+            return Ok(left
+                .iter()
+                .zip(right)
+                .map(|(g, b)| g.mul_bigint(b.into_bigint()))
+                .sum());
+        }
+        let aff_left = G::normalize_batch(left);
+        let right_bigints = cfg_iter!(right)
+            .map(|b| b.into_bigint())
             .collect::<Vec<_>>();
-        Ok(ExtensionFieldElement(P::product_of_pairings(&aff_pairs)))
+        #[cfg(feature = "parallel")]
+        if left.len() >= config.parallel_msm_min_len {
+            let chunk_size = parallel_chunk_size(aff_left.len());
+            return aff_left
+                .par_chunks(chunk_size)
+                .zip(right_bigints.par_chunks(chunk_size))
+                .map(|(b, s)| M::multi_scalar_mul(b, s))
+                .sum();
+        }
+        M::multi_scalar_mul(&aff_left, &right_bigints)
+    }
+}
+
+// Sparse messages are (index, value) pairs of the non-trivial entries, e.g. of selective openings
+// or padded instances, and only these entries take part in the inner product
+impl<G: CurveGroup, M: MsmBackend<G>> MsmInnerProduct<G, M> {
+    pub fn inner_product_sparse_left(
+        left: &[(usize, G)],
+        right: &[G::ScalarField],
+    ) -> Result<G, Error> {
+        let (bases, scalars) = gather_sparse(left, right)?;
+        Self::inner_product(&bases, &scalars)
+    }
+
+    pub fn inner_product_sparse_right(
+        left: &[G],
+        right: &[(usize, G::ScalarField)],
+    ) -> Result<G, Error> {
+        let (scalars, bases) = gather_sparse(right, left)?;
+        Self::inner_product(&bases, &scalars)
+    }
+}
+
+// Values of the sparse entries and the dense entries at their indices
+fn gather_sparse<S: Clone, D: Clone>(
+    sparse: &[(usize, S)],
+    dense: &[D],
+) -> Result<(Vec<S>, Vec<D>), Error> {
+    sparse
+        .iter()
+        .map(|(i, s)| match dense.get(*i) {
+            Some(d) => Ok((s.clone(), d.clone())),
+            None => Err(InnerProductError::IndexOutOfRange(*i, dense.len())),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|pairs| pairs.into_iter().unzip())
+}
+
+// Fixed-base window table of a generator known at setup, storing
+// (2^window_size) * (scalar bits / window_size) multiples of it
+#[derive(Clone)]
+pub struct FixedBaseTable<G: CurveGroup> {
+    window_size: usize,
+    num_windows: usize,
+    table: Vec<Vec<G::Affine>>,
+}
+
+impl<G: CurveGroup> FixedBaseTable<G> {
+    pub fn new(g: &G, window_size: usize) -> Self {
+        let scalar_bits = G::ScalarField::MODULUS_BIT_SIZE as usize;
+        FixedBaseTable {
+            window_size,
+            num_windows: scalar_bits.div_ceil(window_size),
+            table: FixedBase::get_window_table(scalar_bits, window_size, *g),
+        }
+    }
+
+    pub fn mul(&self, scalar: &G::ScalarField) -> G {
+        FixedBase::windowed_mul::<G>(self.num_windows, self.window_size, &self.table, scalar)
     }
 }
 
+// Multiexponentiation prod_i g_i^{b_i} over generators fixed at setup, e.g. a Pedersen commitment
+// key, as a sum of fixed-base multiplications with the window tables of the generators
 #[derive(Copy, Clone)]
-pub struct MultiexponentiationInnerProduct<G: ProjectiveCurve> {
+pub struct FixedBaseMultiexponentiationInnerProduct<G: CurveGroup> {
     _projective: PhantomData<G>,
 }
 
-impl<G: ProjectiveCurve> InnerProduct for MultiexponentiationInnerProduct<G> {
-    type LeftMessage = G;
+impl<G: CurveGroup> InnerProduct for FixedBaseMultiexponentiationInnerProduct<G> {
+    type LeftMessage = FixedBaseTable<G>;
     type RightMessage = G::ScalarField;
     type Output = G;
+    type PreparedLeft = Vec<FixedBaseTable<G>>;
+    const NAME: &'static str = "fixed-base multiexponentiation";
 
     fn inner_product(
         left: &[Self::LeftMessage],
         right: &[Self::RightMessage],
     ) -> Result<Self::Output, Error> {
-        if left.len() != right.len() {
-            return Err(Box::new(InnerProductError::MessageLengthInvalid(
-                left.len(),
-                right.len(),
-            )));
-        };
-        let right_bigints = cfg_iter!(right).map(|b| b.into_repr()).collect::<Vec<_>>();
-        Ok(VariableBaseMSM::multi_scalar_mul(
-            &G::batch_normalization_into_affine(left),
-            &right_bigints,
-        ))
+        check_message_lengths(Self::NAME, left.len(), right.len())?;
+        Ok(cfg_iter!(left).zip(right).map(|(g, b)| g.mul(b)).sum())
+    }
+
+    fn precompute_left(left: &[Self::LeftMessage]) -> Self::PreparedLeft {
+        left.to_vec()
+    }
+
+    fn inner_product_prepared(
+        prepared: &Self::PreparedLeft,
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        Self::inner_product(prepared, right)
     }
 }
 
@@ -110,61 +605,710 @@ pub struct ScalarInnerProduct<F: Field> {
     _field: PhantomData<F>,
 }
 
+// Products are summed in chunks with `Field::sum_of_products`, which for Montgomery prime fields
+// with spare bits in the top limb adds up the unreduced products and reduces once per chunk
+const SUM_OF_PRODUCTS_CHUNK: usize = 16;
+
+fn sum_of_products<F: Field>(left: &[F], right: &[F]) -> F {
+    let left_chunks = left.chunks_exact(SUM_OF_PRODUCTS_CHUNK);
+    let right_chunks = right.chunks_exact(SUM_OF_PRODUCTS_CHUNK);
+    let tail = left_chunks
+        .remainder()
+        .iter()
+        .zip(right_chunks.remainder())
+        .map(|(x, y)| *x * y)
+        .sum::<F>();
+    left_chunks
+        .zip(right_chunks)
+        .map(|(l, r)| {
+            F::sum_of_products::<SUM_OF_PRODUCTS_CHUNK>(
+                l.try_into().unwrap(),
+                r.try_into().unwrap(),
+            )
+        })
+        .sum::<F>()
+        + tail
+}
+
 impl<F: Field> InnerProduct for ScalarInnerProduct<F> {
     type LeftMessage = F;
     type RightMessage = F;
     type Output = F;
+    type PreparedLeft = Vec<Self::LeftMessage>;
+    const NAME: &'static str = "scalar";
+
+    fn inner_product(
+        left: &[Self::LeftMessage],
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        check_message_lengths(Self::NAME, left.len(), right.len())?;
+        #[cfg(feature = "parallel")]
+        let ip = {
+            let chunk_size = parallel_chunk_size(left.len());
+            left.par_chunks(chunk_size)
+                .zip(right.par_chunks(chunk_size))
+                .map(|(l, r)| sum_of_products(l, r))
+                .sum()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let ip = sum_of_products(left, right);
+        Ok(ip)
+    }
+
+    fn precompute_left(left: &[Self::LeftMessage]) -> Self::PreparedLeft {
+        left.to_vec()
+    }
+
+    fn inner_product_prepared(
+        prepared: &Self::PreparedLeft,
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        Self::inner_product(prepared, right)
+    }
+}
+
+impl<F: Field> ScalarInnerProduct<F> {
+    pub fn inner_product_sparse_left(left: &[(usize, F)], right: &[F]) -> Result<F, Error> {
+        let (left, right) = gather_sparse(left, right)?;
+        Self::inner_product(&left, &right)
+    }
+
+    pub fn inner_product_sparse_right(left: &[F], right: &[(usize, F)]) -> Result<F, Error> {
+        Self::inner_product_sparse_left(right, left)
+    }
+}
+
+// Frobenius inner product <A, B> = sum_ij A_ij B_ij of matrices given as rows, summing the inner
+// products of the rows, e.g. for matrix multiplication or bivariate polynomial coefficients
+#[derive(Copy, Clone)]
+pub struct MatrixInnerProduct<IP: InnerProduct> {
+    _inner_product: PhantomData<IP>,
+}
+
+impl<IP> InnerProduct for MatrixInnerProduct<IP>
+where
+    IP: InnerProduct,
+    IP::Output: Add<IP::Output, Output = IP::Output>,
+{
+    type LeftMessage = Vec<IP::LeftMessage>;
+    type RightMessage = Vec<IP::RightMessage>;
+    type Output = IP::Output;
+    type PreparedLeft = Vec<IP::PreparedLeft>;
+    const NAME: &'static str = "matrix";
 
     fn inner_product(
         left: &[Self::LeftMessage],
         right: &[Self::RightMessage],
     ) -> Result<Self::Output, Error> {
-        if left.len() != right.len() {
-            return Err(Box::new(InnerProductError::MessageLengthInvalid(
-                left.len(),
-                right.len(),
-            )));
+        check_message_lengths(Self::NAME, left.len(), right.len())?;
+        IP::inner_product_iter(left.iter().zip(right))
+    }
+
+    fn precompute_left(left: &[Self::LeftMessage]) -> Self::PreparedLeft {
+        left.iter().map(|row| IP::precompute_left(row)).collect()
+    }
+
+    fn inner_product_prepared(
+        prepared: &Self::PreparedLeft,
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        check_message_lengths(Self::NAME, prepared.len(), right.len())?;
+        sum_inner_products::<IP, _>(prepared.iter().zip(right), |(row, right_row)| {
+            IP::inner_product_prepared(row, right_row)
+        })
+    }
+}
+
+// Multiexponentiation prod_i t_i^{b_i} of target group elements, e.g. of AFGHO commitment outputs
+#[derive(Copy, Clone)]
+pub struct ExtensionFieldExponentiationInnerProduct<P: Pairing> {
+    _pair: PhantomData<P>,
+}
+
+impl<P: Pairing> InnerProduct for ExtensionFieldExponentiationInnerProduct<P> {
+    type LeftMessage = ExtensionFieldElement<P>;
+    type RightMessage = P::ScalarField;
+    type Output = ExtensionFieldElement<P>;
+    type PreparedLeft = Vec<Self::LeftMessage>;
+    const NAME: &'static str = "extension field exponentiation";
+
+    fn inner_product(
+        left: &[Self::LeftMessage],
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        check_message_lengths(Self::NAME, left.len(), right.len())?;
+        let exp = |(t, b): (&ExtensionFieldElement<P>, &P::ScalarField)| t.0.pow(b.into_bigint());
+        #[cfg(feature = "parallel")]
+        let ip = {
+            let chunk_size = parallel_chunk_size(left.len());
+            left.par_chunks(chunk_size)
+                .zip(right.par_chunks(chunk_size))
+                .map(|(l, r)| l.iter().zip(r).map(exp).product::<P::TargetField>())
+                .product()
         };
-        Ok(cfg_iter!(left).zip(right).map(|(x, y)| *x * y).sum())
+        #[cfg(not(feature = "parallel"))]
+        let ip = left.iter().zip(right).map(exp).product();
+        Ok(ExtensionFieldElement(ip))
+    }
+
+    fn precompute_left(left: &[Self::LeftMessage]) -> Self::PreparedLeft {
+        left.to_vec()
+    }
+
+    fn inner_product_prepared(
+        prepared: &Self::PreparedLeft,
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        Self::inner_product(prepared, right)
+    }
+}
+
+// Evaluation of the polynomial with coefficients a at x, i.e. <a, (1, x, x^2, ...)>. The right
+// message is the single element x, whose powers are folded in by Horner's rule instead of being
+// allocated. Unlike the other inner products, the messages are not of the same length.
+#[derive(Copy, Clone)]
+pub struct PolynomialEvaluationInnerProduct<F: Field> {
+    _field: PhantomData<F>,
+}
+
+impl<F: Field> InnerProduct for PolynomialEvaluationInnerProduct<F> {
+    type LeftMessage = F;
+    type RightMessage = F;
+    type Output = F;
+    type PreparedLeft = Vec<Self::LeftMessage>;
+    const NAME: &'static str = "polynomial evaluation";
+
+    fn inner_product(
+        left: &[Self::LeftMessage],
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        check_message_lengths(Self::NAME, 1, right.len())?;
+        let x = right[0];
+        Ok(left.iter().rev().fold(F::zero(), |eval, a| eval * x + a))
+    }
+
+    fn precompute_left(left: &[Self::LeftMessage]) -> Self::PreparedLeft {
+        left.to_vec()
+    }
+
+    fn inner_product_prepared(
+        prepared: &Self::PreparedLeft,
+        right: &[Self::RightMessage],
+    ) -> Result<Self::Output, Error> {
+        Self::inner_product(prepared, right)
+    }
+}
+
+// Inner product with public weights, sum_i w_i <a_i, b_i>, e.g. the powers of r of rescaled TIPP
+// and of random linear combinations, without callers materializing the twisted messages
+pub trait TwistedInnerProduct: InnerProduct {
+    type Weight;
+
+    fn twisted_inner_product(
+        left: &[Self::LeftMessage],
+        right: &[Self::RightMessage],
+        weights: &[Self::Weight],
+    ) -> Result<Self::Output, Error>;
+}
+
+// Weights are applied to the G1 message, the cheaper of the two to scale
+impl<P: Pairing> TwistedInnerProduct for PairingInnerProduct<P> {
+    type Weight = P::ScalarField;
+
+    fn twisted_inner_product(
+        left: &[Self::LeftMessage],
+        right: &[Self::RightMessage],
+        weights: &[Self::Weight],
+    ) -> Result<Self::Output, Error> {
+        check_message_lengths(Self::NAME, left.len(), weights.len())?;
+        let left_weighted = cfg_iter!(left)
+            .zip(weights)
+            .map(|(a, w)| a.mul_bigint(w.into_bigint()))
+            .collect::<Vec<_>>();
+        Self::inner_product(&left_weighted, right)
+    }
+}
+
+impl<G: CurveGroup, M: MsmBackend<G>> TwistedInnerProduct for MsmInnerProduct<G, M> {
+    type Weight = G::ScalarField;
+
+    fn twisted_inner_product(
+        left: &[Self::LeftMessage],
+        right: &[Self::RightMessage],
+        weights: &[Self::Weight],
+    ) -> Result<Self::Output, Error> {
+        check_message_lengths(Self::NAME, right.len(), weights.len())?;
+        let right_weighted = cfg_iter!(right)
+            .zip(weights)
+            .map(|(b, w)| *b * w)
+            .collect::<Vec<_>>();
+        Self::inner_product(left, &right_weighted)
+    }
+}
+
+impl<F: Field> TwistedInnerProduct for ScalarInnerProduct<F> {
+    type Weight = F;
+
+    fn twisted_inner_product(
+        left: &[Self::LeftMessage],
+        right: &[Self::RightMessage],
+        weights: &[Self::Weight],
+    ) -> Result<Self::Output, Error> {
+        check_message_lengths(Self::NAME, right.len(), weights.len())?;
+        let right_weighted = cfg_iter!(right)
+            .zip(weights)
+            .map(|(b, w)| *b * w)
+            .collect::<Vec<_>>();
+        Self::inner_product(left, &right_weighted)
     }
 }
 
 // Helper wrapper type around target group commitment output in order to implement MulAssign (needed for dh_commitments)
-//TODO: PairingEngine provides target group GT implementing Group for prime order P::Fr
+//TODO: Pairing provides target group GT implementing Group for prime order P::ScalarField
 
 #[derive(Clone, Debug)]
-pub struct ExtensionFieldElement<P: PairingEngine>(pub P::Fqk);
+pub struct ExtensionFieldElement<P: Pairing>(pub P::TargetField);
 
-impl<P: PairingEngine> Default for ExtensionFieldElement<P> {
+// The identity of the target group, written additively, rather than zero in Fqk, which is not a
+// group element and would fail checked deserialization
+impl<P: Pairing> Default for ExtensionFieldElement<P> {
     fn default() -> Self {
-        ExtensionFieldElement(<P::Fqk>::default())
+        ExtensionFieldElement(<P::TargetField>::one())
     }
 }
 
-impl<P: PairingEngine> PartialEq for ExtensionFieldElement<P> {
+impl<P: Pairing> PartialEq for ExtensionFieldElement<P> {
     fn eq(&self, other: &Self) -> bool {
         self.0.eq(&other.0)
     }
 }
 
-impl<P: PairingEngine> Eq for ExtensionFieldElement<P> {}
+impl<P: Pairing> Eq for ExtensionFieldElement<P> {}
 
-impl<P: PairingEngine> MulAssign<P::Fr> for ExtensionFieldElement<P> {
-    fn mul_assign(&mut self, rhs: P::Fr) {
-        *self = ExtensionFieldElement(self.0.pow(rhs.into_repr()))
+// Uniform target group element, as the pairing of uniform group elements
+impl<P: Pairing> Distribution<ExtensionFieldElement<P>> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ExtensionFieldElement<P> {
+        ExtensionFieldElement(P::pairing(P::G1::rand(rng), P::G2::rand(rng)).0)
     }
 }
 
-impl<P: PairingEngine> Add<Self> for ExtensionFieldElement<P> {
+impl<P: Pairing> MulAssign<P::ScalarField> for ExtensionFieldElement<P> {
+    fn mul_assign(&mut self, rhs: P::ScalarField) {
+        *self = ExtensionFieldElement(self.0.pow(rhs.into_bigint()))
+    }
+}
+
+impl<P: Pairing> Add<Self> for ExtensionFieldElement<P> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        ExtensionFieldElement(<P::Fqk as Mul>::mul(self.0, rhs.0))
+        ExtensionFieldElement(<P::TargetField as Mul>::mul(self.0, rhs.0))
+    }
+}
+
+impl<P: Pairing> CanonicalSerialize for ExtensionFieldElement<P> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress)
+    }
+}
+
+// Checked deserialization requires the element to be in the prime order subgroup of the target
+// group
+impl<P: Pairing> Valid for ExtensionFieldElement<P> {
+    fn check(&self) -> Result<(), SerializationError> {
+        check_target_group::<P>(&self.0)
+    }
+}
+
+impl<P: Pairing> CanonicalDeserialize for ExtensionFieldElement<P> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let el = ExtensionFieldElement(<P::TargetField>::deserialize_with_mode(
+            reader, compress, validate,
+        )?);
+        if validate == Validate::Yes {
+            el.check()?;
+        }
+        Ok(el)
+    }
+}
+
+fn check_target_group<P: Pairing>(el: &P::TargetField) -> Result<(), SerializationError> {
+    if el.pow(P::ScalarField::characteristic()) != <P::TargetField>::one() {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(())
+}
+
+// Torus compression of target group elements in quadratic extensions such as
+// Fp12 = Fp6[w]/(w^2 - v), halving their size: an element a = c0 + c1 w of norm one is
+// represented by g = (1 + c0) / c1 and recovered as (g + w) / (g - w). The identity, with c1 = 0,
+// is represented by g = 0, which would otherwise decompress to -1, outside of the target group.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompressedExtensionFieldElement<P: Pairing>(pub ExtensionFieldElement<P>);
+
+impl<P, Q> CompressedExtensionFieldElement<P>
+where
+    Q: QuadExtConfig,
+    P: Pairing<TargetField = QuadExtField<Q>>,
+{
+    fn compress(&self) -> Result<Q::BaseField, SerializationError> {
+        let a = &(self.0).0;
+        if a.c1.is_zero() {
+            if a.c0.is_one() {
+                return Ok(Q::BaseField::zero());
+            }
+            return Err(SerializationError::InvalidData);
+        }
+        Ok((Q::BaseField::one() + a.c0) / a.c1)
+    }
+
+    fn decompress(g: Q::BaseField) -> Self {
+        if g.is_zero() {
+            return CompressedExtensionFieldElement(ExtensionFieldElement(QuadExtField::one()));
+        }
+        let one = Q::BaseField::one();
+        CompressedExtensionFieldElement(ExtensionFieldElement(
+            QuadExtField::new(g, one) / QuadExtField::new(g, -one),
+        ))
+    }
+}
+
+impl<P, Q> CanonicalSerialize for CompressedExtensionFieldElement<P>
+where
+    Q: QuadExtConfig,
+    P: Pairing<TargetField = QuadExtField<Q>>,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.compress()?.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        Q::BaseField::zero().serialized_size(compress)
+    }
+}
+
+impl<P, Q> Valid for CompressedExtensionFieldElement<P>
+where
+    Q: QuadExtConfig,
+    P: Pairing<TargetField = QuadExtField<Q>>,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        check_target_group::<P>(&(self.0).0)
+    }
+}
+
+impl<P, Q> CanonicalDeserialize for CompressedExtensionFieldElement<P>
+where
+    Q: QuadExtConfig,
+    P: Pairing<TargetField = QuadExtField<Q>>,
+{
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let el = Self::decompress(Q::BaseField::deserialize_with_mode(
+            reader, compress, validate,
+        )?);
+        if validate == Validate::Yes {
+            el.check()?;
+        }
+        Ok(el)
     }
 }
 
-impl<P: PairingEngine> ToBytes for ExtensionFieldElement<P> {
-    fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
-        self.0.write(&mut writer)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type PairingIP = PairingInnerProduct<Bls12_381>;
+    type MultiexponentiationIP = MultiexponentiationInnerProduct<G1Projective>;
+    type ScalarIP = ScalarInnerProduct<Fr>;
+
+    fn random_vec<T: UniformRand>(rng: &mut StdRng, len: usize) -> Vec<T> {
+        (0..len).map(|_| T::rand(rng)).collect()
+    }
+
+    fn scalar_inner_product_matches<F: Field>(rng: &mut StdRng) {
+        for &len in &[0, 1, 15, 16, 17, 100] {
+            let left = random_vec::<F>(rng, len);
+            let right = random_vec::<F>(rng, len);
+            let expected = left.iter().zip(&right).map(|(x, y)| *x * y).sum::<F>();
+            assert_eq!(
+                ScalarInnerProduct::inner_product(&left, &right).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn scalar_inner_product_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        // BN254 has spare bits for delayed reduction and BLS12-381 does not
+        scalar_inner_product_matches::<ark_bn254::Fr>(&mut rng);
+        scalar_inner_product_matches::<ark_bls12_381::Fr>(&mut rng);
+    }
+
+    #[test]
+    fn twisted_inner_product_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let weights = random_vec::<Fr>(&mut rng, 5);
+
+        let g1 = random_vec::<G1Projective>(&mut rng, 5);
+        let g2 = random_vec::<G2Projective>(&mut rng, 5);
+        let g1_weighted = g1
+            .iter()
+            .zip(&weights)
+            .map(|(a, w)| *a * w)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            PairingIP::twisted_inner_product(&g1, &g2, &weights).unwrap(),
+            PairingIP::inner_product(&g1_weighted, &g2).unwrap()
+        );
+
+        let a = random_vec::<Fr>(&mut rng, 5);
+        let b = random_vec::<Fr>(&mut rng, 5);
+        let b_weighted = b
+            .iter()
+            .zip(&weights)
+            .map(|(b, w)| *b * w)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            MultiexponentiationIP::twisted_inner_product(&g1, &b, &weights).unwrap(),
+            MultiexponentiationIP::inner_product(&g1, &b_weighted).unwrap()
+        );
+        assert_eq!(
+            ScalarIP::twisted_inner_product(&a, &b, &weights).unwrap(),
+            ScalarIP::inner_product(&a, &b_weighted).unwrap()
+        );
+        assert!(ScalarIP::twisted_inner_product(&a, &b, &weights[1..]).is_err());
+    }
+
+    #[test]
+    fn sparse_inner_product_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let g = random_vec::<G1Projective>(&mut rng, 8);
+        let a = random_vec::<Fr>(&mut rng, 8);
+        let b = random_vec::<Fr>(&mut rng, 8);
+        let indices = [1, 4, 7];
+
+        // Dense messages with zeros outside of the sparse entries
+        let mut g_dense = vec![G1Projective::zero(); 8];
+        let mut a_dense = vec![Fr::zero(); 8];
+        for &i in &indices {
+            g_dense[i] = g[i];
+            a_dense[i] = a[i];
+        }
+        let g_sparse = indices.iter().map(|&i| (i, g[i])).collect::<Vec<_>>();
+        let a_sparse = indices.iter().map(|&i| (i, a[i])).collect::<Vec<_>>();
+
+        assert_eq!(
+            MultiexponentiationIP::inner_product_sparse_left(&g_sparse, &b).unwrap(),
+            MultiexponentiationIP::inner_product(&g_dense, &b).unwrap()
+        );
+        assert_eq!(
+            MultiexponentiationIP::inner_product_sparse_right(&g, &a_sparse).unwrap(),
+            MultiexponentiationIP::inner_product(&g, &a_dense).unwrap()
+        );
+        assert_eq!(
+            ScalarIP::inner_product_sparse_left(&a_sparse, &b).unwrap(),
+            ScalarIP::inner_product(&a_dense, &b).unwrap()
+        );
+        assert_eq!(
+            ScalarIP::inner_product_sparse_right(&b, &a_sparse).unwrap(),
+            ScalarIP::inner_product(&b, &a_dense).unwrap()
+        );
+        assert_eq!(
+            ScalarIP::inner_product_sparse_left(&[], &b).unwrap(),
+            Fr::zero()
+        );
+
+        let out_of_range = [(3, a[3]), (8, a[0])];
+        assert!(matches!(
+            ScalarIP::inner_product_sparse_left(&out_of_range, &b),
+            Err(InnerProductError::IndexOutOfRange(8, 8))
+        ));
+        assert!(matches!(
+            MultiexponentiationIP::inner_product_sparse_right(&g, &out_of_range),
+            Err(InnerProductError::IndexOutOfRange(8, 8))
+        ));
+    }
+
+    #[test]
+    fn matrix_inner_product_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let left = (0..3)
+            .map(|_| random_vec::<Fr>(&mut rng, 4))
+            .collect::<Vec<_>>();
+        let right = (0..3)
+            .map(|_| random_vec::<Fr>(&mut rng, 4))
+            .collect::<Vec<_>>();
+        let expected = ScalarIP::inner_product(&left.concat(), &right.concat()).unwrap();
+        assert_eq!(
+            MatrixInnerProduct::<ScalarIP>::inner_product(&left, &right).unwrap(),
+            expected
+        );
+        let prepared = MatrixInnerProduct::<ScalarIP>::precompute_left(&left);
+        assert_eq!(
+            MatrixInnerProduct::<ScalarIP>::inner_product_prepared(&prepared, &right).unwrap(),
+            expected
+        );
+        assert!(MatrixInnerProduct::<ScalarIP>::inner_product(&left, &right[1..]).is_err());
+
+        // Rows are polynomials evaluated at one point each, so the inner product of empty
+        // messages, which has no evaluation point, is never computed
+        type PolyIP = PolynomialEvaluationInnerProduct<Fr>;
+        let points = (0..3).map(|_| vec![Fr::rand(&mut rng)]).collect::<Vec<_>>();
+        let expected = left
+            .iter()
+            .zip(&points)
+            .map(|(row, x)| PolyIP::inner_product(row, x).unwrap())
+            .sum::<Fr>();
+        assert_eq!(
+            MatrixInnerProduct::<PolyIP>::inner_product(&left, &points).unwrap(),
+            expected
+        );
+        let prepared = MatrixInnerProduct::<PolyIP>::precompute_left(&left);
+        assert_eq!(
+            MatrixInnerProduct::<PolyIP>::inner_product_prepared(&prepared, &points).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn inner_product_iter_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let g1 = random_vec::<G1Projective>(&mut rng, 7);
+        let g2 = random_vec::<G2Projective>(&mut rng, 7);
+        let b = random_vec::<Fr>(&mut rng, 7);
+
+        assert_eq!(
+            PairingIP::inner_product_iter(g1.chunks(3).zip(g2.chunks(3))).unwrap(),
+            PairingIP::inner_product(&g1, &g2).unwrap()
+        );
+        assert_eq!(
+            MultiexponentiationIP::inner_product_iter(g1.chunks(3).zip(b.chunks(3))).unwrap(),
+            MultiexponentiationIP::inner_product(&g1, &b).unwrap()
+        );
+        assert_eq!(
+            MultiexponentiationIP::inner_product_chunked(&g1, &b, 2).unwrap(),
+            MultiexponentiationIP::inner_product(&g1, &b).unwrap()
+        );
+        assert!(matches!(
+            MultiexponentiationIP::inner_product_chunked(&g1, &b, 0),
+            Err(InnerProductError::ChunkSizeZero)
+        ));
+        assert_eq!(
+            PairingIP::inner_product_iter(core::iter::empty::<(&[_], &[_])>()).unwrap(),
+            ExtensionFieldElement::default()
+        );
+        assert!(PairingIP::inner_product_iter(vec![(&g1[..3], &g2[..2])]).is_err());
+
+        // Sum of evaluations at the same point of polynomials of coefficient chunks
+        type PolyIP = PolynomialEvaluationInnerProduct<Fr>;
+        let x = [Fr::rand(&mut rng)];
+        let expected = b
+            .chunks(3)
+            .map(|chunk| PolyIP::inner_product(chunk, &x).unwrap())
+            .sum::<Fr>();
+        assert_eq!(
+            PolyIP::inner_product_iter(b.chunks(3).map(|chunk| (chunk, &x))).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn pairing_inner_product_unreduced_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let g1 = random_vec::<G1Projective>(&mut rng, 6);
+        let g2 = random_vec::<G2Projective>(&mut rng, 6);
+
+        let unreduced = PairingIP::inner_product_unreduced(&g1, &g2).unwrap();
+        assert_eq!(
+            PairingIP::final_exponentiation(&unreduced).unwrap(),
+            PairingIP::inner_product(&g1, &g2).unwrap()
+        );
+
+        // Unreduced inner products of halves multiply to the unreduced inner product of the whole,
+        // up to the final exponentiation
+        let product = PairingIP::inner_product_unreduced(&g1[..3], &g2[..3]).unwrap()
+            * PairingIP::inner_product_unreduced(&g1[3..], &g2[3..]).unwrap();
+        assert_eq!(
+            PairingIP::final_exponentiation(&product).unwrap(),
+            PairingIP::inner_product(&g1, &g2).unwrap()
+        );
+        let prepared = PairingIP::precompute_right(&g2);
+        assert_eq!(
+            PairingIP::inner_product_prepared_right(&g1, &prepared).unwrap(),
+            PairingIP::inner_product(&g1, &g2).unwrap()
+        );
+        assert_eq!(
+            PairingIP::inner_product(&[], &[]).unwrap(),
+            ExtensionFieldElement::default()
+        );
+
+        // Separate Miller loops of each pair give the same product
+        let naive = InnerProductConfig {
+            naive_pairing_max_len: usize::MAX,
+            ..InnerProductConfig::default()
+        };
+        assert_eq!(
+            PairingIP::inner_product_with_config(&g1, &g2, &naive).unwrap(),
+            PairingIP::inner_product(&g1, &g2).unwrap()
+        );
+
+        assert!(PairingIP::inner_product_unreduced(&g1, &g2[1..]).is_err());
+        assert!(matches!(
+            PairingIP::final_exponentiation(&Zero::zero()),
+            Err(InnerProductError::FinalExponentiationOfZero)
+        ));
+    }
+
+    #[test]
+    fn precomputed_multiexponentiation_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        for &len in &[0, 1, 7, 64] {
+            let g = random_vec::<G1Projective>(&mut rng, len);
+            let mut b = random_vec::<Fr>(&mut rng, len);
+            // Scalars with zero and full windows
+            if len > 1 {
+                b[0] = Fr::zero();
+                b[1] = -Fr::one();
+            }
+            let expected = MultiexponentiationIP::inner_product(&g, &b).unwrap();
+            let prepared = MultiexponentiationIP::precompute_left(&g);
+            assert_eq!(prepared.len(), len);
+            assert_eq!(
+                MultiexponentiationIP::inner_product_prepared(&prepared, &b).unwrap(),
+                expected
+            );
+            for window in [1, 5, 13] {
+                let prepared = msm::PrecomputedBases::with_window(&g, window);
+                assert_eq!(prepared.multi_scalar_mul(&b).unwrap(), expected);
+            }
+        }
+        let g = random_vec::<G1Projective>(&mut rng, 4);
+        let prepared = MultiexponentiationIP::precompute_left(&g);
+        assert!(MultiexponentiationIP::inner_product_prepared(&prepared, &[Fr::one()]).is_err());
+        assert!(msm::precomputed_window(1 << 16, 255) > msm::precomputed_window(1 << 8, 255));
     }
 }
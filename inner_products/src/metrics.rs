@@ -0,0 +1,119 @@
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+// Hook for the workload of provers and verifiers, e.g. to export it as Prometheus metrics. The
+// inner products, the commitments and the protocols built on them call it as they do the work,
+// while it is installed with `with_metrics`. Each method takes the amount of work and defaults to
+// ignoring it
+pub trait Metrics: Send + Sync {
+    fn gipa_rounds(&self, _count: u64) {}
+
+    // Pairs of the Miller loops of pairing inner products, commitments and checks
+    fn pairings(&self, _count: u64) {}
+
+    fn final_exponentiations(&self, _count: u64) {}
+
+    // Called once for each multiexponentiation with its number of terms
+    fn msm_terms(&self, _size: u64) {}
+
+    // Fiat-Shamir digests, and the serialized bytes they hash
+    fn hashes(&self, _count: u64) {}
+
+    fn hashed_bytes(&self, _count: u64) {}
+
+    // Canonical serializations of group and field elements for the transcripts, counted once
+    // however often they are hashed
+    fn serialized_bytes(&self, _count: u64) {}
+}
+
+thread_local! {
+    static METRICS: RefCell<Option<Arc<dyn Metrics>>> = RefCell::new(None);
+}
+
+// Runs `f` with `metrics` installed, restoring the previous hook after. The hook is installed for
+// the current thread and, with the parallel feature, `f` runs in a rayon pool of its own whose
+// workers have it installed too, so work split across threads is reported. Concurrent calls on
+// other threads report to their own hooks
+pub fn with_metrics<T: Send>(metrics: Arc<dyn Metrics>, f: impl FnOnce() -> T + Send) -> T {
+    struct Restore(Option<Arc<dyn Metrics>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            METRICS.with(|m| *m.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = Restore(METRICS.with(|m| m.borrow_mut().replace(metrics.clone())));
+    #[cfg(feature = "parallel")]
+    {
+        let worker_metrics = metrics.clone();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(rayon::current_num_threads())
+            .start_handler(move |_| {
+                METRICS.with(|m| *m.borrow_mut() = Some(worker_metrics.clone()));
+            })
+            .build();
+        if let Ok(pool) = pool {
+            return pool.install(f);
+        }
+    }
+    f()
+}
+
+// Reports to the hook installed for the current thread, if any
+pub fn report(f: impl FnOnce(&dyn Metrics)) {
+    if let Some(metrics) = METRICS.with(|m| m.borrow().clone()) {
+        f(metrics.as_ref());
+    }
+}
+
+// Metrics summed in atomic counters, which can be shared between threads and read at any time
+#[derive(Debug, Default)]
+pub struct Counters {
+    pub gipa_rounds: AtomicU64,
+    pub pairings: AtomicU64,
+    pub final_exponentiations: AtomicU64,
+    pub msms: AtomicU64,
+    pub msm_terms: AtomicU64,
+    pub hashes: AtomicU64,
+    pub hashed_bytes: AtomicU64,
+    pub serialized_bytes: AtomicU64,
+}
+
+impl Metrics for Counters {
+    fn gipa_rounds(&self, count: u64) {
+        self.gipa_rounds.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn pairings(&self, count: u64) {
+        self.pairings.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn final_exponentiations(&self, count: u64) {
+        self.final_exponentiations
+            .fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn msm_terms(&self, size: u64) {
+        self.msms.fetch_add(1, Ordering::Relaxed);
+        self.msm_terms.fetch_add(size, Ordering::Relaxed);
+    }
+
+    fn hashes(&self, count: u64) {
+        self.hashes.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn hashed_bytes(&self, count: u64) {
+        self.hashed_bytes.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn serialized_bytes(&self, count: u64) {
+        self.serialized_bytes.fetch_add(count, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,722 @@
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    AffineRepr, CurveGroup, Group,
+};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_std::{cfg_chunks_mut, cfg_into_iter, cfg_iter, vec, vec::Vec};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{batch_affine::batch_affine_add, check_message_lengths, glv::Endomorphism, Error};
+
+const MSM_NAME: &str = "multi-scalar multiplication";
+const FOLD_NAME: &str = "key folding";
+
+// Windows beyond this many bits need more buckets than any input length here would fill
+pub const MAX_WINDOW: usize = 20;
+
+type BigInt<G> = <<G as Group>::ScalarField as PrimeField>::BigInt;
+
+// Strategy computing the multi-scalar multiplications of commitments, inner products and key
+// folding. Implementations are stateless, so the strategy is chosen by type, e.g. as the backend
+// parameter of `MultiexponentiationInnerProduct`
+pub trait MsmBackend<G: CurveGroup>: Clone {
+    fn multi_scalar_mul(bases: &[G::Affine], scalars: &[BigInt<G>]) -> Result<G, Error>;
+
+    // Folding k_1 + c k_2 of the halves of a key, e.g. in the GIPA rounds, with mixed additions of
+    // the scaled affine high half
+    fn fold_bases(low: &[G], high: &[G], c: &G::ScalarField) -> Result<Vec<G>, Error> {
+        check_message_lengths(FOLD_NAME, low.len(), high.len())?;
+        Ok(add_scaled(low, &scale_bases(high, c)))
+    }
+}
+
+// Backends running the bucket method with a window of any size, which `auto_tune` chooses for them
+pub trait WindowedMsm<G: CurveGroup>: MsmBackend<G> + 'static {
+    fn multi_scalar_mul_with_window(bases: &[G::Affine], scalars: &[BigInt<G>], window: usize)
+        -> G;
+}
+
+// Sum of the individual scalar multiplications, fastest for a handful of terms
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NaiveMsm;
+
+impl<G: CurveGroup> MsmBackend<G> for NaiveMsm {
+    fn multi_scalar_mul(bases: &[G::Affine], scalars: &[BigInt<G>]) -> Result<G, Error> {
+        check_message_lengths(MSM_NAME, bases.len(), scalars.len())?;
+        Ok(naive_msm(bases, scalars))
+    }
+}
+
+// Pippenger's bucket method with projective buckets. Untuned, it is the arkworks MSM
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PippengerMsm;
+
+impl<G: CurveGroup> MsmBackend<G> for PippengerMsm {
+    fn multi_scalar_mul(bases: &[G::Affine], scalars: &[BigInt<G>]) -> Result<G, Error> {
+        check_message_lengths(MSM_NAME, bases.len(), scalars.len())?;
+        Ok(tuned_msm::<G, Self>(bases, scalars).unwrap_or_else(|| G::msm_bigint(bases, scalars)))
+    }
+}
+
+impl<G: CurveGroup> WindowedMsm<G> for PippengerMsm {
+    fn multi_scalar_mul_with_window(
+        bases: &[G::Affine],
+        scalars: &[BigInt<G>],
+        window: usize,
+    ) -> G {
+        bucket_msm::<G, _>(scalars, window, |buckets, w_start, window| {
+            let mut projective = vec![G::zero(); buckets.len()];
+            for (base, scalar) in bases.iter().zip(scalars) {
+                if let Some(i) = bucket_index::<G>(scalar, w_start, window) {
+                    projective[i] += base;
+                }
+            }
+            buckets.copy_from_slice(&projective);
+        })
+    }
+}
+
+// Bucket method for short Weierstrass curves whose buckets are reduced in affine coordinates, the
+// additions of all buckets sharing one inversion per level. Cheaper per addition than projective
+// buckets, so it favours larger windows
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BatchAffineMsm;
+
+impl<P: SWCurveConfig> MsmBackend<Projective<P>> for BatchAffineMsm {
+    fn multi_scalar_mul(
+        bases: &[Affine<P>],
+        scalars: &[BigInt<Projective<P>>],
+    ) -> Result<Projective<P>, Error> {
+        check_message_lengths(MSM_NAME, bases.len(), scalars.len())?;
+        Ok(
+            tuned_msm::<Projective<P>, Self>(bases, scalars).unwrap_or_else(|| {
+                Self::multi_scalar_mul_with_window(bases, scalars, default_window(bases.len()))
+            }),
+        )
+    }
+
+    // Adds both halves in affine form with one inversion shared across the additions
+    fn fold_bases(
+        low: &[Projective<P>],
+        high: &[Projective<P>],
+        c: &P::ScalarField,
+    ) -> Result<Vec<Projective<P>>, Error> {
+        check_message_lengths(FOLD_NAME, low.len(), high.len())?;
+        let sums = batch_affine_add(&Projective::normalize_batch(low), &scale_bases_glv(high, c))?;
+        Ok(sums.iter().map(|g| g.into_group()).collect())
+    }
+}
+
+impl<P: SWCurveConfig> WindowedMsm<Projective<P>> for BatchAffineMsm {
+    fn multi_scalar_mul_with_window(
+        bases: &[Affine<P>],
+        scalars: &[BigInt<Projective<P>>],
+        window: usize,
+    ) -> Projective<P> {
+        bucket_msm::<Projective<P>, _>(scalars, window, |buckets, w_start, window| {
+            let mut affine = vec![Vec::new(); buckets.len()];
+            for (base, scalar) in bases.iter().zip(scalars) {
+                if let Some(i) = bucket_index::<Projective<P>>(scalar, w_start, window) {
+                    affine[i].push(*base);
+                }
+            }
+            // Halve every bucket with one batch of additions until each holds a single point
+            loop {
+                let (mut lhs, mut rhs, mut targets) = (Vec::new(), Vec::new(), Vec::new());
+                for (i, bucket) in affine.iter_mut().enumerate() {
+                    while bucket.len() >= 2 {
+                        lhs.extend(bucket.pop());
+                        rhs.extend(bucket.pop());
+                        targets.push(i);
+                    }
+                }
+                if targets.is_empty() {
+                    break;
+                }
+                let sums = batch_affine_add(&lhs, &rhs).expect("halves of equal length");
+                for (i, sum) in targets.into_iter().zip(sums) {
+                    affine[i].push(sum);
+                }
+            }
+            for (bucket, points) in buckets.iter_mut().zip(affine) {
+                *bucket = points
+                    .first()
+                    .map_or(Projective::zero(), |p| p.into_group());
+            }
+        })
+    }
+}
+
+// Pippenger's bucket method over the points and their images under the GLV endomorphism, with
+// scalars of half the bits, on short Weierstrass curves that have one. Inputs shorter than
+// `glv::GLV_MIN_POINTS` and curves without it take the plain bucket method
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GlvMsm;
+
+impl<P: SWCurveConfig> MsmBackend<Projective<P>> for GlvMsm {
+    fn multi_scalar_mul(
+        bases: &[Affine<P>],
+        scalars: &[BigInt<Projective<P>>],
+    ) -> Result<Projective<P>, Error> {
+        match Endomorphism::<P>::find_for(bases.len()) {
+            Some(endomorphism) => endomorphism.msm(bases, scalars),
+            None => PippengerMsm::multi_scalar_mul(bases, scalars),
+        }
+    }
+
+    fn fold_bases(
+        low: &[Projective<P>],
+        high: &[Projective<P>],
+        c: &P::ScalarField,
+    ) -> Result<Vec<Projective<P>>, Error> {
+        check_message_lengths(FOLD_NAME, low.len(), high.len())?;
+        Ok(add_scaled(low, &scale_bases_glv(high, c)))
+    }
+}
+
+// c times each base, in affine form
+fn scale_bases<G: CurveGroup>(bases: &[G], c: &G::ScalarField) -> Vec<G::Affine> {
+    let c = c.into_bigint();
+    let scaled = G::normalize_batch(bases)
+        .iter()
+        .map(|g| g.mul_bigint(c))
+        .collect::<Vec<_>>();
+    G::normalize_batch(&scaled)
+}
+
+// `scale_bases` with the GLV endomorphism of the curve if it has one
+fn scale_bases_glv<P: SWCurveConfig>(
+    bases: &[Projective<P>],
+    c: &P::ScalarField,
+) -> Vec<Affine<P>> {
+    match Endomorphism::<P>::find_for(bases.len()) {
+        Some(endomorphism) => Projective::normalize_batch(
+            &endomorphism.mul_all(&Projective::normalize_batch(bases), c),
+        ),
+        None => scale_bases(bases, c),
+    }
+}
+
+fn add_scaled<G: CurveGroup>(bases: &[G], scaled: &[G::Affine]) -> Vec<G> {
+    bases
+        .iter()
+        .zip(scaled)
+        .map(|(g_1, g_2)| {
+            let mut sum = *g_1;
+            sum += g_2;
+            sum
+        })
+        .collect()
+}
+
+fn naive_msm<G: CurveGroup>(bases: &[G::Affine], scalars: &[BigInt<G>]) -> G {
+    bases
+        .iter()
+        .zip(scalars)
+        .map(|(g, s)| g.mul_bigint(s))
+        .sum()
+}
+
+// Bucket of the term with `scalar` in the window starting at bit `w_start`, none for a zero digit
+fn bucket_index<G: CurveGroup>(scalar: &BigInt<G>, w_start: usize, window: usize) -> Option<usize> {
+    if scalar.is_zero() {
+        return None;
+    }
+    let mut scalar = *scalar;
+    scalar.divn(w_start as u32);
+    match scalar.as_ref()[0] % (1 << window) {
+        0 => None,
+        digit => Some(digit as usize - 1),
+    }
+}
+
+// Bucket method over windows of `window` bits, in parallel across windows. `fill` sorts the terms
+// of the window starting at a bit into its 2^window - 1 buckets, and is given the window clamped
+// to 1..=MAX_WINDOW
+fn bucket_msm<G, F>(scalars: &[BigInt<G>], window: usize, fill: F) -> G
+where
+    G: CurveGroup,
+    F: Fn(&mut [G], usize, usize) + Send + Sync,
+{
+    let window = window.clamp(1, MAX_WINDOW);
+    if scalars.is_empty() {
+        return G::zero();
+    }
+    let num_bits = G::ScalarField::MODULUS_BIT_SIZE as usize;
+    let window_starts = (0..num_bits).step_by(window).collect::<Vec<_>>();
+    let window_sums = cfg_into_iter!(window_starts)
+        .map(|w_start| {
+            let mut res = G::zero();
+            let mut buckets = vec![G::zero(); (1 << window) - 1];
+            fill(&mut buckets, w_start, window);
+            let mut running_sum = G::zero();
+            for bucket in buckets.into_iter().rev() {
+                running_sum += &bucket;
+                res += &running_sum;
+            }
+            res
+        })
+        .collect::<Vec<_>>();
+    let lowest = window_sums[0];
+    lowest
+        + window_sums[1..]
+            .iter()
+            .rev()
+            .fold(G::zero(), |mut total, sum| {
+                total += sum;
+                for _ in 0..window {
+                    total.double_in_place();
+                }
+                total
+            })
+}
+
+// Window of the arkworks MSM for inputs of length `len`
+pub fn default_window(len: usize) -> usize {
+    if len < 32 {
+        3
+    } else {
+        (ark_std::log2(len) * 69 / 100) as usize + 2
+    }
+}
+
+// Bits of `window` bits of the scalar starting at bit `start`
+fn window_digit(limbs: &[u64], start: usize, window: usize) -> usize {
+    let (limb, shift) = (start / 64, start % 64);
+    let mut digit = limbs[limb] >> shift;
+    if shift + window > 64 && limb + 1 < limbs.len() {
+        digit |= limbs[limb + 1] << (64 - shift);
+    }
+    (digit & ((1 << window) - 1)) as usize
+}
+
+// Window minimizing the additions of a precomputed MSM of length `len` over scalars of `num_bits`
+// bits, one per term and window and two per bucket
+pub fn precomputed_window(len: usize, num_bits: usize) -> usize {
+    (1..=MAX_WINDOW)
+        .min_by_key(|window| len * num_bits.div_ceil(*window) + (2 << window))
+        .unwrap_or(1)
+}
+
+// Bases fixed across many MSMs, e.g. a commitment key, stored with their shifts 2^(window j) g for
+// every window j. An MSM is then a single bucket pass over the terms of all windows, with no
+// doublings and one bucket reduction. Each base takes (scalar bits / window) points, shared
+// buckets keep the additions below those of the bucket method with the same window
+#[derive(Clone)]
+pub struct PrecomputedBases<G: CurveGroup> {
+    window: usize,
+    num_windows: usize,
+    // Shifts of base i at i * num_windows..(i + 1) * num_windows
+    shifts: Vec<G::Affine>,
+}
+
+impl<G: CurveGroup> PrecomputedBases<G> {
+    pub fn new(bases: &[G]) -> Self {
+        let num_bits = G::ScalarField::MODULUS_BIT_SIZE as usize;
+        Self::with_window(bases, precomputed_window(bases.len(), num_bits))
+    }
+
+    pub fn with_window(bases: &[G], window: usize) -> Self {
+        let window = window.clamp(1, MAX_WINDOW);
+        let num_windows = (G::ScalarField::MODULUS_BIT_SIZE as usize).div_ceil(window);
+        let mut shifts = vec![G::zero(); bases.len() * num_windows];
+        cfg_chunks_mut!(shifts, num_windows)
+            .zip(bases)
+            .for_each(|(shifts, g)| {
+                let mut shift = *g;
+                for s in shifts {
+                    *s = shift;
+                    for _ in 0..window {
+                        shift.double_in_place();
+                    }
+                }
+            });
+        PrecomputedBases {
+            window,
+            num_windows,
+            shifts: G::normalize_batch(&shifts),
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    pub fn len(&self) -> usize {
+        self.shifts.len() / self.num_windows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shifts.is_empty()
+    }
+
+    pub fn multi_scalar_mul(&self, scalars: &[G::ScalarField]) -> Result<G, Error> {
+        check_message_lengths(MSM_NAME, self.len(), scalars.len())?;
+        report_metrics!(msm_terms, scalars.len());
+        let scalars = cfg_iter!(scalars)
+            .map(|s| s.into_bigint())
+            .collect::<Vec<_>>();
+        let chunk_msm = |(shifts, scalars): (&[G::Affine], &[BigInt<G>])| {
+            let mut buckets = vec![G::zero(); (1 << self.window) - 1];
+            for (shifts, scalar) in shifts.chunks(self.num_windows).zip(scalars) {
+                for (j, shift) in shifts.iter().enumerate() {
+                    match window_digit(scalar.as_ref(), j * self.window, self.window) {
+                        0 => {}
+                        digit => buckets[digit - 1] += shift,
+                    }
+                }
+            }
+            let mut res = G::zero();
+            let mut running_sum = G::zero();
+            for bucket in buckets.into_iter().rev() {
+                running_sum += &bucket;
+                res += &running_sum;
+            }
+            res
+        };
+        #[cfg(feature = "parallel")]
+        let msm = {
+            let chunk_size = crate::parallel_chunk_size(scalars.len());
+            self.shifts
+                .par_chunks(chunk_size * self.num_windows)
+                .zip(scalars.par_chunks(chunk_size))
+                .map(chunk_msm)
+                .sum()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let msm = chunk_msm((&self.shifts, &scalars));
+        Ok(msm)
+    }
+}
+
+// Strategy of a windowed backend on one machine: naive sums up to `naive_max_len` terms, and above
+// it the bucket method with `windows[i]` bits for lengths up to 2^i, the last window for longer ones
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsmTuning {
+    pub naive_max_len: usize,
+    pub windows: Vec<usize>,
+}
+
+impl MsmTuning {
+    pub fn window(&self, len: usize) -> usize {
+        let i = ark_std::log2(len) as usize;
+        self.windows
+            .get(i)
+            .or_else(|| self.windows.last())
+            .map_or(default_window(len), |w| *w)
+    }
+
+    fn msm<G: CurveGroup, M: WindowedMsm<G>>(
+        &self,
+        bases: &[G::Affine],
+        scalars: &[BigInt<G>],
+    ) -> G {
+        if bases.len() <= self.naive_max_len {
+            naive_msm(bases, scalars)
+        } else {
+            M::multi_scalar_mul_with_window(bases, scalars, self.window(bases.len()))
+        }
+    }
+}
+
+fn tuned_msm<G: CurveGroup, M: WindowedMsm<G>>(
+    bases: &[G::Affine],
+    scalars: &[BigInt<G>],
+) -> Option<G> {
+    tuning::<G, M>().map(|t| t.msm::<G, M>(bases, scalars))
+}
+
+#[cfg(feature = "std")]
+mod cache {
+    use super::*;
+    use std::{any::TypeId, sync::RwLock};
+
+    static TUNINGS: RwLock<Vec<(TypeId, TypeId, MsmTuning)>> = RwLock::new(Vec::new());
+
+    pub fn get<G: CurveGroup, M: WindowedMsm<G>>() -> Option<MsmTuning> {
+        let key = (TypeId::of::<G>(), TypeId::of::<M>());
+        TUNINGS
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(g, m, _)| (*g, *m) == key)
+            .map(|(_, _, t)| t.clone())
+    }
+
+    pub fn set<G: CurveGroup, M: WindowedMsm<G>>(tuning: MsmTuning) {
+        let key = (TypeId::of::<G>(), TypeId::of::<M>());
+        let mut tunings = TUNINGS.write().unwrap();
+        tunings.retain(|(g, m, _)| (*g, *m) != key);
+        tunings.push((key.0, key.1, tuning));
+    }
+}
+
+// Tuning `auto_tune` or `set_tuning` installed for backend `M` on the curve of `G`, always none
+// without std
+pub fn tuning<G: CurveGroup, M: WindowedMsm<G>>() -> Option<MsmTuning> {
+    #[cfg(feature = "std")]
+    return cache::get::<G, M>();
+    #[cfg(not(feature = "std"))]
+    None
+}
+
+// Installs a tuning, e.g. one measured by an earlier run and stored by the caller
+#[cfg(feature = "std")]
+pub fn set_tuning<G: CurveGroup, M: WindowedMsm<G>>(tuning: MsmTuning) {
+    cache::set::<G, M>(tuning)
+}
+
+// Times backend `M` on the curve of `G` against naive sums for lengths of powers of two up to
+// `max_len`, with windows around the default one, and installs the fastest strategies. Runs once
+// per curve and backend: later calls return the installed tuning if it covers `max_len`
+#[cfg(feature = "std")]
+pub fn auto_tune<G: CurveGroup, M: WindowedMsm<G>>(max_len: usize) -> MsmTuning {
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::time::{Duration, Instant};
+
+    let max_log_len = ark_std::log2(max_len.max(2)) as usize;
+    if let Some(tuning) = tuning::<G, M>() {
+        if tuning.windows.len() > max_log_len {
+            return tuning;
+        }
+    }
+    let time = |f: &dyn Fn() -> G, len: usize| {
+        // Best of three for short inputs, against noise from other threads
+        let runs = if len < 1 << 10 { 3 } else { 1 };
+        (0..runs)
+            .map(|_| {
+                let start = Instant::now();
+                f();
+                start.elapsed()
+            })
+            .min()
+            .unwrap_or(Duration::MAX)
+    };
+
+    let mut rng = StdRng::seed_from_u64(0u64);
+    // Multiples of one point, cheaper to sample than independent points and as costly to sum
+    let g = G::rand(&mut rng);
+    let mut bases = vec![g];
+    for i in 1..(1 << max_log_len) {
+        bases.push(bases[i - 1] + g);
+    }
+    let bases = G::normalize_batch(&bases);
+    let scalars = (0..bases.len())
+        .map(|_| G::ScalarField::rand(&mut rng).into_bigint())
+        .collect::<Vec<_>>();
+
+    let mut tuning = MsmTuning {
+        naive_max_len: 0,
+        windows: vec![default_window(1)],
+    };
+    let mut naive_wins = true;
+    for log_len in 1..=max_log_len {
+        let (bases, scalars) = (&bases[..1 << log_len], &scalars[..1 << log_len]);
+        let default = default_window(bases.len());
+        let (window, windowed_time) = (default.saturating_sub(2).max(1)
+            ..=(default + 3).min(MAX_WINDOW))
+            .map(|w| {
+                let msm = || M::multi_scalar_mul_with_window(bases, scalars, w);
+                (w, time(&msm, bases.len()))
+            })
+            .min_by_key(|(_, t)| *t)
+            .expect("nonempty window range");
+        tuning.windows.push(window);
+        // Naive sums only win below some length, and stop being timed once they lose
+        if naive_wins {
+            naive_wins = time(&|| naive_msm(bases, scalars), bases.len()) < windowed_time;
+            if naive_wins {
+                tuning.naive_max_len = bases.len();
+            }
+        }
+    }
+    set_tuning::<G, M>(tuning.clone());
+    tuning
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glv::GLV_MIN_POINTS;
+    use ark_bls12_381::{Fr, G1Projective};
+    use ark_ff::{Field, One, UniformRand};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type G = G1Projective;
+
+    // Bases with repeated and opposite points, and scalars with zeros, ones and minus one, make
+    // buckets add doublings and inverses
+    fn instance(rng: &mut StdRng, len: usize) -> (Vec<<G as CurveGroup>::Affine>, Vec<Fr>) {
+        let mut bases = (0..len).map(|_| G::rand(rng)).collect::<Vec<_>>();
+        let mut scalars = (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+        let special = [Fr::zero(), Fr::one(), -Fr::one(), Fr::one()];
+        for (i, s) in special.iter().enumerate().filter(|(i, _)| 2 * i + 1 < len) {
+            scalars[2 * i + 1] = *s;
+        }
+        if len > 2 {
+            bases[1] = bases[0];
+            bases[2] = -bases[0];
+            scalars[2] = scalars[0];
+        }
+        (G::normalize_batch(&bases), scalars)
+    }
+
+    fn expected(bases: &[<G as CurveGroup>::Affine], scalars: &[Fr]) -> G {
+        bases.iter().zip(scalars).map(|(g, s)| *g * s).sum()
+    }
+
+    fn bigints(scalars: &[Fr]) -> Vec<BigInt<G>> {
+        scalars.iter().map(|s| s.into_bigint()).collect()
+    }
+
+    #[test]
+    fn msm_backends_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        for len in [0, 1, 2, 3, 7, 13, 2 * GLV_MIN_POINTS + 5] {
+            let (bases, scalars) = instance(&mut rng, len);
+            let expected = expected(&bases, &scalars);
+            let scalars = bigints(&scalars);
+            let msm = [
+                <NaiveMsm as MsmBackend<G>>::multi_scalar_mul,
+                <PippengerMsm as MsmBackend<G>>::multi_scalar_mul,
+                <BatchAffineMsm as MsmBackend<G>>::multi_scalar_mul,
+                <GlvMsm as MsmBackend<G>>::multi_scalar_mul,
+            ];
+            for msm in msm {
+                assert_eq!(msm(&bases, &scalars).unwrap(), expected);
+                if len > 0 {
+                    assert!(msm(&bases[1..], &scalars).is_err());
+                }
+            }
+            // Windows out of range are clamped to 1..=MAX_WINDOW
+            for window in [0, 1, 2, 3, 4, 7, 11] {
+                assert_eq!(
+                    <PippengerMsm as WindowedMsm<G>>::multi_scalar_mul_with_window(
+                        &bases, &scalars, window
+                    ),
+                    expected
+                );
+                assert_eq!(
+                    BatchAffineMsm::multi_scalar_mul_with_window(&bases, &scalars, window),
+                    expected
+                );
+            }
+        }
+
+        // All scalars zero or one
+        let (bases, _) = instance(&mut rng, 9);
+        for scalar in [Fr::zero(), Fr::one()] {
+            let scalars = vec![scalar; bases.len()];
+            let expected = expected(&bases, &scalars);
+            let scalars = bigints(&scalars);
+            for window in [1, 3, 8] {
+                assert_eq!(
+                    <PippengerMsm as WindowedMsm<G>>::multi_scalar_mul_with_window(
+                        &bases, &scalars, window
+                    ),
+                    expected
+                );
+                assert_eq!(
+                    BatchAffineMsm::multi_scalar_mul_with_window(&bases, &scalars, window),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fold_bases_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let c = Fr::rand(&mut rng);
+        for len in [0, 1, 3, GLV_MIN_POINTS + 1] {
+            let low = (0..len).map(|_| G::rand(&mut rng)).collect::<Vec<_>>();
+            let mut high = (0..len).map(|_| G::rand(&mut rng)).collect::<Vec<_>>();
+            if len > 0 {
+                // Sums of a point and its negation
+                high[0] = -low[0] * c.inverse().unwrap();
+            }
+            let expected = low
+                .iter()
+                .zip(&high)
+                .map(|(l, h)| *l + *h * c)
+                .collect::<Vec<_>>();
+            let fold = [
+                <NaiveMsm as MsmBackend<G>>::fold_bases,
+                <BatchAffineMsm as MsmBackend<G>>::fold_bases,
+                <GlvMsm as MsmBackend<G>>::fold_bases,
+            ];
+            for fold in fold {
+                assert_eq!(fold(&low, &high, &c).unwrap(), expected);
+                if len > 0 {
+                    assert!(fold(&low[1..], &high, &c).is_err());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn precomputed_bases_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        for len in [0, 1, 5, 33] {
+            let (bases, scalars) = instance(&mut rng, len);
+            let expected = expected(&bases, &scalars);
+            let bases = bases.iter().map(|g| g.into_group()).collect::<Vec<_>>();
+            let precomputed = PrecomputedBases::new(&bases);
+            assert_eq!(precomputed.len(), len);
+            assert_eq!(precomputed.is_empty(), len == 0);
+            assert_eq!(precomputed.multi_scalar_mul(&scalars).unwrap(), expected);
+            for window in [0, 1, 4, 9] {
+                let precomputed = PrecomputedBases::with_window(&bases, window);
+                assert_eq!(precomputed.window(), window.clamp(1, MAX_WINDOW));
+                assert_eq!(precomputed.multi_scalar_mul(&scalars).unwrap(), expected);
+            }
+            assert!(precomputed.multi_scalar_mul(&[Fr::one(); 2]).is_err());
+        }
+    }
+
+    #[test]
+    fn msm_tuning_test() {
+        let fixed = MsmTuning {
+            naive_max_len: 4,
+            windows: vec![1, 2, 3],
+        };
+        assert_eq!(fixed.window(1), 1);
+        assert_eq!(fixed.window(4), 3);
+        // Lengths beyond the windows take the last one
+        assert_eq!(fixed.window(1 << 10), 3);
+        let empty = MsmTuning {
+            naive_max_len: 0,
+            windows: Vec::new(),
+        };
+        assert_eq!(empty.window(100), default_window(100));
+
+        // Tuned backends agree with the naive sums, on both sides of `naive_max_len`, and tuning
+        // runs once
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let len = 64;
+        let tuned = auto_tune::<G, BatchAffineMsm>(len);
+        assert_eq!(tuned.windows.len(), ark_std::log2(len) as usize + 1);
+        assert_eq!(tuning::<G, BatchAffineMsm>(), Some(tuned.clone()));
+        assert_eq!(auto_tune::<G, BatchAffineMsm>(len / 2), tuned);
+        set_tuning::<G, PippengerMsm>(MsmTuning {
+            naive_max_len: 4,
+            windows: vec![2; 8],
+        });
+        for len in [0, 1, 4, 5, 2 * len] {
+            let (bases, scalars) = instance(&mut rng, len);
+            let expected = expected(&bases, &scalars);
+            let scalars = bigints(&scalars);
+            assert_eq!(
+                <BatchAffineMsm as MsmBackend<G>>::multi_scalar_mul(&bases, &scalars).unwrap(),
+                expected
+            );
+            assert_eq!(
+                <PippengerMsm as MsmBackend<G>>::multi_scalar_mul(&bases, &scalars).unwrap(),
+                expected
+            );
+        }
+    }
+}
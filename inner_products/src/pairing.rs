@@ -0,0 +1,31 @@
+use ark_ec::pairing::Pairing;
+
+use crate::{
+    check_message_lengths,
+    msm::{BatchAffineMsm, GlvMsm, NaiveMsm, PippengerMsm},
+    Error,
+};
+
+const MILLER_LOOP_NAME: &str = "multi-Miller loop";
+
+// Strategy computing the multi-Miller loops of pairing commitments. Like `MsmBackend`, it is
+// stateless and chosen by type, and one type usually implements both, e.g. to route MSMs and
+// pairings to GPU kernels. The points come prepared, so keys shared by many loops are prepared once
+pub trait PairingBackend<P: Pairing>: Clone {
+    fn multi_miller_loop(
+        left: &[P::G1Prepared],
+        right: &[P::G2Prepared],
+    ) -> Result<P::TargetField, Error> {
+        check_message_lengths(MILLER_LOOP_NAME, left.len(), right.len())?;
+        Ok(P::multi_miller_loop(left.iter().cloned(), right.iter().cloned()).0)
+    }
+}
+
+// The CPU backends run the arkworks multi-Miller loop
+impl<P: Pairing> PairingBackend<P> for NaiveMsm {}
+
+impl<P: Pairing> PairingBackend<P> for PippengerMsm {}
+
+impl<P: Pairing> PairingBackend<P> for BatchAffineMsm {}
+
+impl<P: Pairing> PairingBackend<P> for GlvMsm {}
@@ -0,0 +1,500 @@
+use ark_ec::{pairing::Pairing, Group};
+use ark_ff::{One, PrimeField, Zero};
+use ark_poly::polynomial::{
+    univariate::DensePolynomial as UnivariatePolynomial, DenseUVPolynomial, Polynomial,
+};
+
+use ark_std::marker::PhantomData;
+use ark_std::{end_timer, start_timer, vec, vec::Vec};
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    applications::poly_commit::KZG,
+    tipa::{
+        structured_scalar_message::{structured_scalar_power, TIPAWithSSM, TIPAWithSSMProof},
+        TIPAProof, VerifierSRS, SRS, TIPA,
+    },
+    transcript::{ChallengeInput, TranscriptDigest},
+    Error, InnerProductArgumentError,
+};
+use ark_dh_commitments::{
+    afgho16::AFGHOCommitmentG1,
+    identity::{HomomorphicPlaceholderValue, IdentityCommitment, IdentityOutput},
+    pedersen::PedersenCommitment,
+    DoublyHomomorphicCommitment,
+};
+use ark_inner_products::{
+    ExtensionFieldElement, InnerProduct, MultiexponentiationInnerProduct, ScalarInnerProduct,
+};
+
+type WitnessIPA<P, D> = TIPAWithSSM<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+type WitnessIPAProof<P, D> = TIPAWithSSMProof<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+type ShiftedWitnessIPA<P, D> = TIPA<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+type ShiftedWitnessIPAProof<P, D> = TIPAProof<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+#[derive(Clone)]
+pub struct AccumulatorSRS<P: Pairing> {
+    pub kzg_powers: Vec<P::G1Affine>,
+    pub kzg_v_srs: VerifierSRS<P>,
+    pub ip_srs: SRS<P>,
+}
+
+#[derive(Clone)]
+pub struct AccumulatorVerifierSRS<P: Pairing> {
+    pub kzg_v_srs: VerifierSRS<P>,
+    pub ip_v_srs: VerifierSRS<P>,
+}
+
+impl<P: Pairing> AccumulatorSRS<P> {
+    pub fn get_verifier_key(&self) -> AccumulatorVerifierSRS<P> {
+        AccumulatorVerifierSRS {
+            kzg_v_srs: self.kzg_v_srs.clone(),
+            ip_v_srs: self.ip_srs.get_verifier_key(),
+        }
+    }
+}
+
+// KZG opening of the set polynomial at an element: the element is a member iff the evaluation is zero
+#[derive(Clone)]
+pub struct AccumulatorWitness<P: Pairing> {
+    pub eval: P::ScalarField,
+    pub proof: P::G1,
+}
+
+// Witnesses for many elements aggregated into a random linear combination of the opening proofs
+// along with inner product proofs that the combination was computed from the committed witnesses
+pub struct BatchAccumulatorWitness<P: Pairing, D: TranscriptDigest> {
+    evals: Vec<P::ScalarField>,
+    com_witnesses: ExtensionFieldElement<P>,
+    agg_witness: P::G1,
+    agg_shifted_witness: P::G1,
+    witness_proof: WitnessIPAProof<P, D>,
+    shifted_witness_proof: ShiftedWitnessIPAProof<P, D>,
+}
+
+// Pairing-based accumulator committing to a set as the roots of a polynomial
+pub struct Accumulator<P: Pairing, D: TranscriptDigest> {
+    _pairing: PhantomData<P>,
+    _digest: PhantomData<D>,
+}
+
+impl<P: Pairing, D: TranscriptDigest> Accumulator<P, D> {
+    // Batch size must be a power of 2; smaller batches are padded
+    pub fn setup<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        max_set_size: usize,
+        max_batch_size: usize,
+    ) -> Result<AccumulatorSRS<P>, Error> {
+        let (kzg_powers, kzg_v_srs) = KZG::<P>::setup(rng, max_set_size)?;
+        let (ip_srs, _) = ShiftedWitnessIPA::<P, D>::setup(rng, max_batch_size)?;
+        Ok(AccumulatorSRS {
+            kzg_powers,
+            kzg_v_srs,
+            ip_srs,
+        })
+    }
+
+    pub fn set_polynomial(set: &[P::ScalarField]) -> UnivariatePolynomial<P::ScalarField> {
+        // Multiply out prod_i (X - s_i)
+        let mut coeffs = vec![P::ScalarField::one()];
+        for s in set.iter() {
+            let mut shifted = vec![P::ScalarField::zero()];
+            shifted.extend_from_slice(&coeffs);
+            for (c, prev) in shifted.iter_mut().zip(&coeffs) {
+                *c -= &(*prev * s);
+            }
+            coeffs = shifted;
+        }
+        UnivariatePolynomial::from_coefficients_vec(coeffs)
+    }
+
+    pub fn accumulate(srs: &AccumulatorSRS<P>, set: &[P::ScalarField]) -> Result<P::G1, Error> {
+        Self::check_set_size(srs, set)?;
+        KZG::<P>::commit(&srs.kzg_powers, &Self::set_polynomial(set))
+    }
+
+    pub fn prove_membership(
+        srs: &AccumulatorSRS<P>,
+        set: &[P::ScalarField],
+        element: &P::ScalarField,
+    ) -> Result<AccumulatorWitness<P>, Error> {
+        let witness = Self::open(srs, set, element)?;
+        if !witness.eval.is_zero() {
+            return Err(InnerProductArgumentError::InnerProductInvalid);
+        }
+        Ok(witness)
+    }
+
+    pub fn prove_non_membership(
+        srs: &AccumulatorSRS<P>,
+        set: &[P::ScalarField],
+        element: &P::ScalarField,
+    ) -> Result<AccumulatorWitness<P>, Error> {
+        let witness = Self::open(srs, set, element)?;
+        if witness.eval.is_zero() {
+            return Err(InnerProductArgumentError::InnerProductInvalid);
+        }
+        Ok(witness)
+    }
+
+    pub fn verify_membership(
+        v_srs: &AccumulatorVerifierSRS<P>,
+        acc: &P::G1,
+        element: &P::ScalarField,
+        witness: &AccumulatorWitness<P>,
+    ) -> Result<bool, Error> {
+        Ok(witness.eval.is_zero()
+            && KZG::<P>::verify(
+                &v_srs.kzg_v_srs,
+                acc,
+                element,
+                &witness.eval,
+                &witness.proof,
+            )?)
+    }
+
+    pub fn verify_non_membership(
+        v_srs: &AccumulatorVerifierSRS<P>,
+        acc: &P::G1,
+        element: &P::ScalarField,
+        witness: &AccumulatorWitness<P>,
+    ) -> Result<bool, Error> {
+        Ok(!witness.eval.is_zero()
+            && KZG::<P>::verify(
+                &v_srs.kzg_v_srs,
+                acc,
+                element,
+                &witness.eval,
+                &witness.proof,
+            )?)
+    }
+
+    pub fn aggregate_witnesses(
+        srs: &AccumulatorSRS<P>,
+        acc: &P::G1,
+        elements: &[P::ScalarField],
+        witnesses: &[AccumulatorWitness<P>],
+    ) -> Result<BatchAccumulatorWitness<P, D>, Error> {
+        let (ck_1, ck_2) = srs.ip_srs.get_commitment_keys();
+        if elements.len() != witnesses.len() || elements.len() > ck_1.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                elements.len(),
+                witnesses.len(),
+            ));
+        }
+
+        // Pad with identity witnesses at zero which do not contribute to the aggregated witnesses
+        let mut proofs = witnesses
+            .iter()
+            .map(|witness| witness.proof)
+            .collect::<Vec<P::G1>>();
+        proofs.resize(ck_1.len(), P::G1::zero());
+        let evals = witnesses
+            .iter()
+            .map(|witness| witness.eval)
+            .collect::<Vec<P::ScalarField>>();
+
+        let com_witnesses = AFGHOCommitmentG1::<P>::commit(&ck_1, &proofs)?;
+        let r = Self::compute_batch_challenge(acc, &com_witnesses, elements, &evals)?;
+        let r_vec = structured_scalar_power(ck_1.len(), &r);
+        let r_elements = Self::shifted_challenge_powers(&r_vec, elements);
+
+        let agg_witness = MultiexponentiationInnerProduct::inner_product(&proofs, &r_vec)?;
+        let agg_shifted_witness =
+            MultiexponentiationInnerProduct::inner_product(&proofs, &r_elements)?;
+
+        let ipa_time = start_timer!(|| "Computing witness aggregation proofs");
+        let witness_proof = WitnessIPA::<P, D>::prove_with_structured_scalar_message(
+            &srs.ip_srs,
+            (&proofs, &r_vec),
+            (&ck_1, &HomomorphicPlaceholderValue),
+        )?;
+        let shifted_witness_proof = ShiftedWitnessIPA::<P, D>::prove(
+            &srs.ip_srs,
+            (&proofs, &r_elements),
+            (&ck_1, &ck_2, &HomomorphicPlaceholderValue),
+        )?;
+        end_timer!(ipa_time);
+
+        Ok(BatchAccumulatorWitness {
+            evals,
+            com_witnesses,
+            agg_witness,
+            agg_shifted_witness,
+            witness_proof,
+            shifted_witness_proof,
+        })
+    }
+
+    pub fn verify_batch_membership(
+        v_srs: &AccumulatorVerifierSRS<P>,
+        ck_2: &[P::G1],
+        acc: &P::G1,
+        elements: &[P::ScalarField],
+        batch_witness: &BatchAccumulatorWitness<P, D>,
+    ) -> Result<bool, Error> {
+        Ok(batch_witness.evals.iter().all(|eval| eval.is_zero())
+            && Self::verify_batch(v_srs, ck_2, acc, elements, batch_witness)?)
+    }
+
+    pub fn verify_batch_non_membership(
+        v_srs: &AccumulatorVerifierSRS<P>,
+        ck_2: &[P::G1],
+        acc: &P::G1,
+        elements: &[P::ScalarField],
+        batch_witness: &BatchAccumulatorWitness<P, D>,
+    ) -> Result<bool, Error> {
+        Ok(batch_witness.evals.iter().all(|eval| !eval.is_zero())
+            && Self::verify_batch(v_srs, ck_2, acc, elements, batch_witness)?)
+    }
+
+    // Checks the random linear combination of the KZG verification equations
+    // e(acc - eval_i g, h) = e(witness_i, h^alpha) e(element_i witness_i, h)^-1
+    fn verify_batch(
+        v_srs: &AccumulatorVerifierSRS<P>,
+        ck_2: &[P::G1],
+        acc: &P::G1,
+        elements: &[P::ScalarField],
+        batch_witness: &BatchAccumulatorWitness<P, D>,
+    ) -> Result<bool, Error> {
+        if elements.len() != batch_witness.evals.len() || elements.len() > ck_2.len() {
+            return Ok(false);
+        }
+        let r = Self::compute_batch_challenge(
+            acc,
+            &batch_witness.com_witnesses,
+            elements,
+            &batch_witness.evals,
+        )?;
+        let r_vec = structured_scalar_power(ck_2.len(), &r);
+        let r_elements = Self::shifted_challenge_powers(&r_vec, elements);
+
+        // Check aggregated witnesses against committed witnesses
+        let witness_valid = WitnessIPA::<P, D>::verify_with_structured_scalar_message(
+            &v_srs.ip_v_srs,
+            &HomomorphicPlaceholderValue,
+            (
+                &batch_witness.com_witnesses,
+                &IdentityOutput(vec![batch_witness.agg_witness]),
+            ),
+            &r,
+            &batch_witness.witness_proof,
+        )?;
+        let com_r_elements = PedersenCommitment::<P::G1>::commit(ck_2, &r_elements)?;
+        let shifted_witness_valid = ShiftedWitnessIPA::<P, D>::verify(
+            &v_srs.ip_v_srs,
+            &HomomorphicPlaceholderValue,
+            (
+                &batch_witness.com_witnesses,
+                &com_r_elements,
+                &IdentityOutput(vec![batch_witness.agg_shifted_witness]),
+            ),
+            &batch_witness.shifted_witness_proof,
+        )?;
+
+        // Check aggregated KZG verification equation
+        let kzg_v_srs = &v_srs.kzg_v_srs;
+        let r_sum = r_vec[..elements.len()].iter().sum::<P::ScalarField>();
+        let r_evals =
+            ScalarInnerProduct::inner_product(&batch_witness.evals, &r_vec[..elements.len()])?;
+        let kzg_valid = P::pairing(
+            acc.mul_bigint(r_sum.into_bigint()) - kzg_v_srs.g.mul_bigint(r_evals.into_bigint()),
+            kzg_v_srs.h,
+        )
+        .0 == P::pairing(batch_witness.agg_witness, kzg_v_srs.h_alpha).0
+            * P::pairing(-batch_witness.agg_shifted_witness, kzg_v_srs.h).0;
+
+        Ok(witness_valid && shifted_witness_valid && kzg_valid)
+    }
+
+    fn open(
+        srs: &AccumulatorSRS<P>,
+        set: &[P::ScalarField],
+        element: &P::ScalarField,
+    ) -> Result<AccumulatorWitness<P>, Error> {
+        Self::check_set_size(srs, set)?;
+        let polynomial = Self::set_polynomial(set);
+        Ok(AccumulatorWitness {
+            eval: polynomial.evaluate(element),
+            proof: KZG::<P>::open(&srs.kzg_powers, &polynomial, element)?,
+        })
+    }
+
+    fn check_set_size(srs: &AccumulatorSRS<P>, set: &[P::ScalarField]) -> Result<(), Error> {
+        if set.len() >= srs.kzg_powers.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                set.len(),
+                srs.kzg_powers.len() - 1,
+            ));
+        }
+        Ok(())
+    }
+
+    fn compute_batch_challenge(
+        acc: &P::G1,
+        com_witnesses: &ExtensionFieldElement<P>,
+        elements: &[P::ScalarField],
+        evals: &[P::ScalarField],
+    ) -> Result<P::ScalarField, Error> {
+        let mut input = D::input();
+        input.absorb(&canonical_bytes![acc, com_witnesses, elements, evals]?)?;
+        let mut counter_nonce: usize = 0;
+        let r = loop {
+            if let Some(r) = input.challenge::<P::ScalarField>(counter_nonce) {
+                break r;
+            };
+            counter_nonce += 1;
+        };
+        Ok(r)
+    }
+
+    // Powers of the challenge scaled by the batch elements, zero on padded positions
+    fn shifted_challenge_powers(
+        r_vec: &[P::ScalarField],
+        elements: &[P::ScalarField],
+    ) -> Vec<P::ScalarField> {
+        let mut r_elements = r_vec
+            .iter()
+            .zip(elements)
+            .map(|(r, x)| *r * x)
+            .collect::<Vec<P::ScalarField>>();
+        r_elements.resize(r_vec.len(), P::ScalarField::zero());
+        r_elements
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_ff::UniformRand;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type Fr = <Bls12_381 as Pairing>::ScalarField;
+    type TestAccumulator = Accumulator<Bls12_381, Blake2b512>;
+
+    const SET_SIZE: usize = 16;
+    const BATCH_SIZE: usize = 8;
+
+    #[test]
+    fn accumulator_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let srs = TestAccumulator::setup(&mut rng, SET_SIZE, BATCH_SIZE).unwrap();
+        let v_srs = srs.get_verifier_key();
+
+        let set = (0..SET_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<Fr>>();
+        let non_member = Fr::rand(&mut rng);
+        let acc = TestAccumulator::accumulate(&srs, &set).unwrap();
+
+        let witness = TestAccumulator::prove_membership(&srs, &set, &set[3]).unwrap();
+        assert!(TestAccumulator::verify_membership(&v_srs, &acc, &set[3], &witness).unwrap());
+        assert!(!TestAccumulator::verify_membership(&v_srs, &acc, &set[4], &witness).unwrap());
+        assert!(TestAccumulator::prove_membership(&srs, &set, &non_member).is_err());
+
+        let witness = TestAccumulator::prove_non_membership(&srs, &set, &non_member).unwrap();
+        assert!(
+            TestAccumulator::verify_non_membership(&v_srs, &acc, &non_member, &witness).unwrap()
+        );
+        assert!(!TestAccumulator::verify_membership(&v_srs, &acc, &non_member, &witness).unwrap());
+        assert!(TestAccumulator::prove_non_membership(&srs, &set, &set[3]).is_err());
+    }
+
+    #[test]
+    fn accumulator_batch_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let srs = TestAccumulator::setup(&mut rng, SET_SIZE, BATCH_SIZE).unwrap();
+        let v_srs = srs.get_verifier_key();
+        let (_, ck_2) = srs.ip_srs.get_commitment_keys();
+
+        let set = (0..SET_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<Fr>>();
+        let acc = TestAccumulator::accumulate(&srs, &set).unwrap();
+
+        // Batch smaller than the maximum batch size
+        let members = set[..BATCH_SIZE - 2].to_vec();
+        let witnesses = members
+            .iter()
+            .map(|x| TestAccumulator::prove_membership(&srs, &set, x).unwrap())
+            .collect::<Vec<_>>();
+        let batch_witness =
+            TestAccumulator::aggregate_witnesses(&srs, &acc, &members, &witnesses).unwrap();
+        assert!(TestAccumulator::verify_batch_membership(
+            &v_srs,
+            &ck_2,
+            &acc,
+            &members,
+            &batch_witness
+        )
+        .unwrap());
+        let mut wrong_members = members.clone();
+        wrong_members[0] = Fr::rand(&mut rng);
+        assert!(!TestAccumulator::verify_batch_membership(
+            &v_srs,
+            &ck_2,
+            &acc,
+            &wrong_members,
+            &batch_witness
+        )
+        .unwrap());
+
+        let non_members = (0..BATCH_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<Fr>>();
+        let witnesses = non_members
+            .iter()
+            .map(|x| TestAccumulator::prove_non_membership(&srs, &set, x).unwrap())
+            .collect::<Vec<_>>();
+        let batch_witness =
+            TestAccumulator::aggregate_witnesses(&srs, &acc, &non_members, &witnesses).unwrap();
+        assert!(TestAccumulator::verify_batch_non_membership(
+            &v_srs,
+            &ck_2,
+            &acc,
+            &non_members,
+            &batch_witness
+        )
+        .unwrap());
+        assert!(!TestAccumulator::verify_batch_membership(
+            &v_srs,
+            &ck_2,
+            &acc,
+            &non_members,
+            &batch_witness
+        )
+        .unwrap());
+    }
+}
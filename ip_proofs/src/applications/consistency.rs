@@ -0,0 +1,253 @@
+use ark_ec::pairing::Pairing;
+use ark_std::{marker::PhantomData, vec};
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    gipa::GIPAProof,
+    tipa::{
+        structured_scalar_message::{
+            structured_scalar_power, GIPAWithSSM, SSMPlaceholderCommitment, TIPAWithSSM,
+            TIPAWithSSMProof,
+        },
+        VerifierSRS, SRS,
+    },
+    transcript::{ChallengeInput, TranscriptDigest},
+    Error,
+};
+use ark_dh_commitments::{
+    afgho16::AFGHOCommitmentG1,
+    identity::{HomomorphicPlaceholderValue, IdentityCommitment, IdentityOutput},
+    DoublyHomomorphicCommitment,
+};
+use ark_inner_products::{ExtensionFieldElement, InnerProduct, MultiexponentiationInnerProduct};
+
+type ConsistencyGIPA<P, D> = GIPAWithSSM<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    D,
+>;
+
+type ConsistencyGIPAProof<P, D> = GIPAProof<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    SSMPlaceholderCommitment<<P as Pairing>::ScalarField>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    D,
+>;
+
+type ConsistencyTIPA<P, D> = TIPAWithSSM<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+type ConsistencyTIPAProof<P, D> = TIPAWithSSMProof<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+// Two AFGHO commitments to G1 vectors under different keys open to the same vector if both
+// openings agree on <A, (1, c, c^2, ...)> for a challenge c bound to the two commitments
+pub struct ConsistencyProof<P: Pairing, D: TranscriptDigest> {
+    ip_a: P::G1,
+    left_proof: ConsistencyGIPAProof<P, D>,
+    right_proof: ConsistencyGIPAProof<P, D>,
+}
+
+// Right commitment is under the structured TIPA commitment key, so its opening is checked with a
+// KZG proof of the final key instead of recomputing the folded key
+pub struct StructuredConsistencyProof<P: Pairing, D: TranscriptDigest> {
+    ip_a: P::G1,
+    left_proof: ConsistencyGIPAProof<P, D>,
+    right_proof: ConsistencyTIPAProof<P, D>,
+}
+
+pub struct CommitmentConsistency<P: Pairing, D: TranscriptDigest> {
+    _pairing: PhantomData<P>,
+    _digest: PhantomData<D>,
+}
+
+impl<P: Pairing, D: TranscriptDigest> CommitmentConsistency<P, D> {
+    pub fn setup<R: RngCore + CryptoRng>(rng: &mut R, size: usize) -> Result<SRS<P>, Error> {
+        let (srs, _) = ConsistencyTIPA::<P, D>::setup(rng, size)?;
+        Ok(srs)
+    }
+
+    pub fn prove(
+        ck_1: &[P::G2],
+        ck_2: &[P::G2],
+        a: &[P::G1],
+    ) -> Result<ConsistencyProof<P, D>, Error> {
+        let com_1 = AFGHOCommitmentG1::<P>::commit(ck_1, a)?;
+        let com_2 = AFGHOCommitmentG1::<P>::commit(ck_2, a)?;
+        let c_powers = structured_scalar_power(a.len(), &Self::compute_challenge(&com_1, &com_2)?);
+
+        Ok(ConsistencyProof {
+            ip_a: MultiexponentiationInnerProduct::inner_product(a, &c_powers)?,
+            left_proof: ConsistencyGIPA::<P, D>::prove_with_structured_scalar_message(
+                (a, &c_powers),
+                (ck_1, &HomomorphicPlaceholderValue),
+            )?,
+            right_proof: ConsistencyGIPA::<P, D>::prove_with_structured_scalar_message(
+                (a, &c_powers),
+                (ck_2, &HomomorphicPlaceholderValue),
+            )?,
+        })
+    }
+
+    pub fn verify(
+        ck_1: &[P::G2],
+        ck_2: &[P::G2],
+        com: (&ExtensionFieldElement<P>, &ExtensionFieldElement<P>),
+        proof: &ConsistencyProof<P, D>,
+    ) -> Result<bool, Error> {
+        let c = Self::compute_challenge(com.0, com.1)?;
+        let ip_com = IdentityOutput(vec![proof.ip_a]);
+        let left_valid = ConsistencyGIPA::<P, D>::verify_with_structured_scalar_message(
+            (ck_1, &HomomorphicPlaceholderValue),
+            (com.0, &ip_com),
+            &c,
+            &proof.left_proof,
+        )?;
+        let right_valid = ConsistencyGIPA::<P, D>::verify_with_structured_scalar_message(
+            (ck_2, &HomomorphicPlaceholderValue),
+            (com.1, &ip_com),
+            &c,
+            &proof.right_proof,
+        )?;
+        Ok(left_valid && right_valid)
+    }
+
+    // Proves that a commitment under `ck` opens to the same vector as the commitment under the
+    // left commitment key of the TIPA SRS
+    pub fn prove_with_srs(
+        srs: &SRS<P>,
+        ck: &[P::G2],
+        a: &[P::G1],
+    ) -> Result<StructuredConsistencyProof<P, D>, Error> {
+        let (srs_ck, _) = srs.get_commitment_keys();
+        let com_1 = AFGHOCommitmentG1::<P>::commit(ck, a)?;
+        let com_2 = AFGHOCommitmentG1::<P>::commit(&srs_ck, a)?;
+        let c_powers = structured_scalar_power(a.len(), &Self::compute_challenge(&com_1, &com_2)?);
+
+        Ok(StructuredConsistencyProof {
+            ip_a: MultiexponentiationInnerProduct::inner_product(a, &c_powers)?,
+            left_proof: ConsistencyGIPA::<P, D>::prove_with_structured_scalar_message(
+                (a, &c_powers),
+                (ck, &HomomorphicPlaceholderValue),
+            )?,
+            right_proof: ConsistencyTIPA::<P, D>::prove_with_structured_scalar_message(
+                srs,
+                (a, &c_powers),
+                (&srs_ck, &HomomorphicPlaceholderValue),
+            )?,
+        })
+    }
+
+    pub fn verify_with_srs(
+        v_srs: &VerifierSRS<P>,
+        ck: &[P::G2],
+        com: (&ExtensionFieldElement<P>, &ExtensionFieldElement<P>),
+        proof: &StructuredConsistencyProof<P, D>,
+    ) -> Result<bool, Error> {
+        let c = Self::compute_challenge(com.0, com.1)?;
+        let ip_com = IdentityOutput(vec![proof.ip_a]);
+        let left_valid = ConsistencyGIPA::<P, D>::verify_with_structured_scalar_message(
+            (ck, &HomomorphicPlaceholderValue),
+            (com.0, &ip_com),
+            &c,
+            &proof.left_proof,
+        )?;
+        let right_valid = ConsistencyTIPA::<P, D>::verify_with_structured_scalar_message(
+            v_srs,
+            &HomomorphicPlaceholderValue,
+            (com.1, &ip_com),
+            &c,
+            &proof.right_proof,
+        )?;
+        Ok(left_valid && right_valid)
+    }
+
+    fn compute_challenge(
+        com_1: &ExtensionFieldElement<P>,
+        com_2: &ExtensionFieldElement<P>,
+    ) -> Result<P::ScalarField, Error> {
+        let mut input = D::input();
+        input.absorb(com_1)?;
+        input.absorb(com_2)?;
+        let mut counter_nonce: usize = 0;
+        let c = loop {
+            if let Some(c) = input.challenge::<P::ScalarField>(counter_nonce) {
+                break c;
+            };
+            counter_nonce += 1;
+        };
+        Ok(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_ff::UniformRand;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type G1 = <Bls12_381 as Pairing>::G1;
+    type TestConsistency = CommitmentConsistency<Bls12_381, Blake2b512>;
+
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn commitment_consistency_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ck_1 = AFGHOCommitmentG1::<Bls12_381>::setup(&mut rng, TEST_SIZE).unwrap();
+        let ck_2 = AFGHOCommitmentG1::<Bls12_381>::setup(&mut rng, TEST_SIZE).unwrap();
+        let a = (0..TEST_SIZE)
+            .map(|_| G1::rand(&mut rng))
+            .collect::<Vec<G1>>();
+        let mut b = a.clone();
+        b[0] = G1::rand(&mut rng);
+
+        let com_1 = AFGHOCommitmentG1::<Bls12_381>::commit(&ck_1, &a).unwrap();
+        let com_2 = AFGHOCommitmentG1::<Bls12_381>::commit(&ck_2, &a).unwrap();
+        let wrong_com_2 = AFGHOCommitmentG1::<Bls12_381>::commit(&ck_2, &b).unwrap();
+
+        let proof = TestConsistency::prove(&ck_1, &ck_2, &a).unwrap();
+        assert!(TestConsistency::verify(&ck_1, &ck_2, (&com_1, &com_2), &proof).unwrap());
+        assert!(!TestConsistency::verify(&ck_1, &ck_2, (&com_1, &wrong_com_2), &proof).unwrap());
+        assert!(!TestConsistency::verify(&ck_2, &ck_1, (&com_1, &com_2), &proof).unwrap());
+    }
+
+    #[test]
+    fn structured_commitment_consistency_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let srs = TestConsistency::setup(&mut rng, TEST_SIZE).unwrap();
+        let v_srs = srs.get_verifier_key();
+        let (srs_ck, _) = srs.get_commitment_keys();
+        let ck = AFGHOCommitmentG1::<Bls12_381>::setup(&mut rng, TEST_SIZE).unwrap();
+        let a = (0..TEST_SIZE)
+            .map(|_| G1::rand(&mut rng))
+            .collect::<Vec<G1>>();
+        let mut b = a.clone();
+        b[0] = G1::rand(&mut rng);
+
+        let com_1 = AFGHOCommitmentG1::<Bls12_381>::commit(&ck, &a).unwrap();
+        let com_2 = AFGHOCommitmentG1::<Bls12_381>::commit(&srs_ck, &a).unwrap();
+        let wrong_com_1 = AFGHOCommitmentG1::<Bls12_381>::commit(&ck, &b).unwrap();
+
+        let proof = TestConsistency::prove_with_srs(&srs, &ck, &a).unwrap();
+        assert!(TestConsistency::verify_with_srs(&v_srs, &ck, (&com_1, &com_2), &proof).unwrap());
+        assert!(
+            !TestConsistency::verify_with_srs(&v_srs, &ck, (&wrong_com_1, &com_2), &proof).unwrap()
+        );
+    }
+}
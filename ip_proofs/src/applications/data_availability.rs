@@ -0,0 +1,551 @@
+use ark_ec::{pairing::Pairing, CurveGroup, Group, VariableBaseMSM};
+use ark_ff::{PrimeField, Zero};
+use ark_poly::{
+    polynomial::{univariate::DensePolynomial as UnivariatePolynomial, DenseUVPolynomial},
+    EvaluationDomain, Radix2EvaluationDomain,
+};
+
+use ark_std::marker::PhantomData;
+use ark_std::{end_timer, start_timer, vec, vec::Vec};
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    applications::poly_commit::KZG,
+    tipa::{
+        structured_scalar_message::{structured_scalar_power, TIPAWithSSM, TIPAWithSSMProof},
+        TIPAProof, VerifierSRS, SRS, TIPA,
+    },
+    transcript::{ChallengeInput, TranscriptDigest},
+    Error, InnerProductArgumentError,
+};
+use ark_dh_commitments::{
+    afgho16::AFGHOCommitmentG1,
+    identity::{HomomorphicPlaceholderValue, IdentityCommitment, IdentityOutput},
+    pedersen::PedersenCommitment,
+    DoublyHomomorphicCommitment,
+};
+use ark_inner_products::{
+    ExtensionFieldElement, InnerProduct, MultiexponentiationInnerProduct, ScalarInnerProduct,
+};
+
+type SampleIPA<P, D> = TIPAWithSSM<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+type SampleIPAProof<P, D> = TIPAWithSSMProof<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+type ShiftedSampleIPA<P, D> = TIPA<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+type ShiftedSampleIPAProof<P, D> = TIPAProof<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+#[derive(Clone)]
+pub struct DataAvailabilitySRS<P: Pairing> {
+    pub kzg_powers: Vec<P::G1Affine>,
+    pub kzg_v_srs: VerifierSRS<P>,
+    pub ip_srs: SRS<P>,
+}
+
+#[derive(Clone)]
+pub struct DataAvailabilityVerifierSRS<P: Pairing> {
+    pub kzg_v_srs: VerifierSRS<P>,
+    pub ip_v_srs: VerifierSRS<P>,
+}
+
+impl<P: Pairing> DataAvailabilitySRS<P> {
+    pub fn get_verifier_key(&self) -> DataAvailabilityVerifierSRS<P> {
+        DataAvailabilityVerifierSRS {
+            kzg_v_srs: self.kzg_v_srs.clone(),
+            ip_v_srs: self.ip_srs.get_verifier_key(),
+        }
+    }
+}
+
+// A k x k data matrix extended to 2k x 2k with a Reed-Solomon code along both dimensions. Cell
+// (i, j) of the extended matrix is the evaluation of the underlying bivariate polynomial of degree
+// less than k in each variable at (w^i, w^j) for w generating the domain of size 2k, so the
+// original data sits at the even rows and columns.
+pub struct EncodedData<F: PrimeField> {
+    pub extended: Vec<Vec<F>>,
+    row_polynomials: Vec<UnivariatePolynomial<F>>,
+}
+
+// KZG commitments to the rows of the extended matrix
+#[derive(Clone)]
+pub struct DataCommitment<P: Pairing> {
+    pub row_coms: Vec<P::G1>,
+}
+
+pub struct ColumnOpening<P: Pairing> {
+    pub values: Vec<P::ScalarField>,
+    proof: P::G1,
+}
+
+// Aggregated KZG openings of sampled cells, the opening proofs being committed and combined with
+// inner product arguments so that the light client only checks a single KZG equation
+pub struct SampleProof<P: Pairing, D: TranscriptDigest> {
+    pub values: Vec<P::ScalarField>,
+    com_proofs: ExtensionFieldElement<P>,
+    agg_proof: P::G1,
+    agg_shifted_proof: P::G1,
+    proof_ip_proof: SampleIPAProof<P, D>,
+    shifted_proof_ip_proof: ShiftedSampleIPAProof<P, D>,
+}
+
+pub struct DataAvailability<P: Pairing, D: TranscriptDigest> {
+    _pairing: PhantomData<P>,
+    _digest: PhantomData<D>,
+}
+
+impl<P: Pairing, D: TranscriptDigest> DataAvailability<P, D> {
+    // Data dimension and maximum number of samples must be powers of 2
+    pub fn setup<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        data_size: usize,
+        max_samples: usize,
+    ) -> Result<DataAvailabilitySRS<P>, Error> {
+        let (kzg_powers, kzg_v_srs) = KZG::<P>::setup(rng, data_size - 1)?;
+        let (ip_srs, _) = ShiftedSampleIPA::<P, D>::setup(rng, max_samples)?;
+        Ok(DataAvailabilitySRS {
+            kzg_powers,
+            kzg_v_srs,
+            ip_srs,
+        })
+    }
+
+    pub fn encode(data: &[Vec<P::ScalarField>]) -> Result<EncodedData<P::ScalarField>, Error> {
+        let (domain, extended_domain) = Self::domains(data.len())?;
+        if let Some(row) = data.iter().find(|row| row.len() != data.len()) {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                row.len(),
+                data.len(),
+            ));
+        }
+
+        let extend = |values: &[P::ScalarField]| extended_domain.fft(&domain.ifft(values));
+        let rows = data.iter().map(|row| extend(row)).collect::<Vec<_>>();
+        let columns = (0..extended_domain.size())
+            .map(|j| extend(&rows.iter().map(|row| row[j]).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        let extended = (0..extended_domain.size())
+            .map(|i| columns.iter().map(|column| column[i]).collect())
+            .collect::<Vec<Vec<P::ScalarField>>>();
+
+        let row_polynomials = extended
+            .iter()
+            .map(|row| UnivariatePolynomial::from_coefficients_vec(extended_domain.ifft(row)))
+            .collect();
+        Ok(EncodedData {
+            extended,
+            row_polynomials,
+        })
+    }
+
+    pub fn commit(
+        srs: &DataAvailabilitySRS<P>,
+        encoded: &EncodedData<P::ScalarField>,
+    ) -> Result<DataCommitment<P>, Error> {
+        Ok(DataCommitment {
+            row_coms: encoded
+                .row_polynomials
+                .iter()
+                .map(|row_polynomial| KZG::<P>::commit(&srs.kzg_powers, row_polynomial))
+                .collect::<Result<Vec<P::G1>, Error>>()?,
+        })
+    }
+
+    // Checks that the row commitments are consistent with a 2D encoding: rows have low degree by
+    // the size of the KZG key, and the commitments themselves must interpolate a polynomial of
+    // degree less than k, checked on a random combination of the high coefficients
+    pub fn verify_commitment(com: &DataCommitment<P>) -> Result<bool, Error> {
+        let (domain, extended_domain) = match Self::domains(com.row_coms.len() / 2) {
+            Ok(domains) => domains,
+            Err(_) => return Ok(false),
+        };
+        if com.row_coms.len() != extended_domain.size() {
+            return Ok(false);
+        }
+
+        let gamma = Self::compute_challenge(&canonical_bytes![com.row_coms]?)?;
+        let mut high_coeffs = structured_scalar_power(extended_domain.size(), &gamma);
+        for coeff in high_coeffs.iter_mut().take(domain.size()) {
+            *coeff = P::ScalarField::zero();
+        }
+        let weights = extended_domain.ifft(&high_coeffs);
+        let combined = P::G1::msm_bigint(
+            &P::G1::normalize_batch(&com.row_coms),
+            &weights.iter().map(|w| w.into_bigint()).collect::<Vec<_>>(),
+        );
+        Ok(combined.is_zero())
+    }
+
+    pub fn verify_row(
+        srs: &DataAvailabilitySRS<P>,
+        com: &DataCommitment<P>,
+        row: usize,
+        values: &[P::ScalarField],
+    ) -> Result<bool, Error> {
+        if row >= com.row_coms.len() || values.len() != com.row_coms.len() {
+            return Ok(false);
+        }
+        let (domain, extended_domain) = Self::domains(values.len() / 2)?;
+        let coeffs = extended_domain.ifft(values);
+        if coeffs.iter().skip(domain.size()).any(|c| !c.is_zero()) {
+            return Ok(false);
+        }
+        Ok(KZG::<P>::commit(
+            &srs.kzg_powers,
+            &UnivariatePolynomial::from_coefficients_vec(coeffs),
+        )? == com.row_coms[row])
+    }
+
+    // Opens all rows at the column point with a single KZG proof for a random combination of rows
+    pub fn open_column(
+        srs: &DataAvailabilitySRS<P>,
+        encoded: &EncodedData<P::ScalarField>,
+        com: &DataCommitment<P>,
+        column: usize,
+    ) -> Result<ColumnOpening<P>, Error> {
+        let (_, extended_domain) = Self::domains(encoded.extended.len() / 2)?;
+        let values = encoded
+            .extended
+            .iter()
+            .map(|row| row[column])
+            .collect::<Vec<P::ScalarField>>();
+        let r = Self::compute_challenge(&canonical_bytes![com.row_coms, column as u64, values]?)?;
+        let r_vec = structured_scalar_power(encoded.row_polynomials.len(), &r);
+
+        let mut combined = UnivariatePolynomial::zero();
+        for (r, row_polynomial) in r_vec.iter().zip(&encoded.row_polynomials) {
+            combined += (*r, row_polynomial);
+        }
+        let proof = KZG::<P>::open(&srs.kzg_powers, &combined, &extended_domain.element(column))?;
+        Ok(ColumnOpening { values, proof })
+    }
+
+    pub fn verify_column(
+        v_srs: &DataAvailabilityVerifierSRS<P>,
+        com: &DataCommitment<P>,
+        column: usize,
+        opening: &ColumnOpening<P>,
+    ) -> Result<bool, Error> {
+        let (_, extended_domain) = match Self::domains(com.row_coms.len() / 2) {
+            Ok(domains) => domains,
+            Err(_) => return Ok(false),
+        };
+        if column >= extended_domain.size() || opening.values.len() != com.row_coms.len() {
+            return Ok(false);
+        }
+        let r = Self::compute_challenge(&canonical_bytes![
+            com.row_coms,
+            column as u64,
+            opening.values
+        ]?)?;
+        let r_vec = structured_scalar_power(com.row_coms.len(), &r);
+
+        let combined_com = MultiexponentiationInnerProduct::inner_product(&com.row_coms, &r_vec)?;
+        let combined_eval = ScalarInnerProduct::inner_product(&opening.values, &r_vec)?;
+        KZG::<P>::verify(
+            &v_srs.kzg_v_srs,
+            &combined_com,
+            &extended_domain.element(column),
+            &combined_eval,
+            &opening.proof,
+        )
+    }
+
+    pub fn prove_samples(
+        srs: &DataAvailabilitySRS<P>,
+        encoded: &EncodedData<P::ScalarField>,
+        com: &DataCommitment<P>,
+        samples: &[(usize, usize)],
+    ) -> Result<SampleProof<P, D>, Error> {
+        let (_, extended_domain) = Self::domains(encoded.extended.len() / 2)?;
+        let (ck_1, ck_2) = srs.ip_srs.get_commitment_keys();
+        if samples.len() > ck_1.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                samples.len(),
+                ck_1.len(),
+            ));
+        }
+
+        let opening_time = start_timer!(|| "Computing sample openings");
+        let mut proofs = samples
+            .iter()
+            .map(|(i, j)| {
+                KZG::<P>::open(
+                    &srs.kzg_powers,
+                    &encoded.row_polynomials[*i],
+                    &extended_domain.element(*j),
+                )
+            })
+            .collect::<Result<Vec<P::G1>, Error>>()?;
+        proofs.resize(ck_1.len(), P::G1::zero());
+        let values = samples
+            .iter()
+            .map(|(i, j)| encoded.extended[*i][*j])
+            .collect::<Vec<P::ScalarField>>();
+        end_timer!(opening_time);
+
+        let com_proofs = AFGHOCommitmentG1::<P>::commit(&ck_1, &proofs)?;
+        let (_, r_vec, r_points) = Self::sample_challenges(
+            &extended_domain,
+            ck_1.len(),
+            com,
+            samples,
+            &values,
+            &com_proofs,
+        )?;
+
+        let ipa_time = start_timer!(|| "Computing sample aggregation proofs");
+        let agg_proof = MultiexponentiationInnerProduct::inner_product(&proofs, &r_vec)?;
+        let agg_shifted_proof = MultiexponentiationInnerProduct::inner_product(&proofs, &r_points)?;
+        let proof_ip_proof = SampleIPA::<P, D>::prove_with_structured_scalar_message(
+            &srs.ip_srs,
+            (&proofs, &r_vec),
+            (&ck_1, &HomomorphicPlaceholderValue),
+        )?;
+        let shifted_proof_ip_proof = ShiftedSampleIPA::<P, D>::prove(
+            &srs.ip_srs,
+            (&proofs, &r_points),
+            (&ck_1, &ck_2, &HomomorphicPlaceholderValue),
+        )?;
+        end_timer!(ipa_time);
+
+        Ok(SampleProof {
+            values,
+            com_proofs,
+            agg_proof,
+            agg_shifted_proof,
+            proof_ip_proof,
+            shifted_proof_ip_proof,
+        })
+    }
+
+    // Checks the random linear combination of the KZG equations of all samples
+    // e(row_com_s - value_s g, h) = e(proof_s, h^alpha) e(point_s proof_s, h)^-1
+    pub fn verify_samples(
+        v_srs: &DataAvailabilityVerifierSRS<P>,
+        ck_2: &[P::G1],
+        com: &DataCommitment<P>,
+        samples: &[(usize, usize)],
+        sample_proof: &SampleProof<P, D>,
+    ) -> Result<bool, Error> {
+        let (_, extended_domain) = match Self::domains(com.row_coms.len() / 2) {
+            Ok(domains) => domains,
+            Err(_) => return Ok(false),
+        };
+        if samples.len() != sample_proof.values.len()
+            || samples.len() > ck_2.len()
+            || samples
+                .iter()
+                .any(|(i, j)| *i >= extended_domain.size() || *j >= extended_domain.size())
+        {
+            return Ok(false);
+        }
+        let (r, r_vec, r_points) = Self::sample_challenges(
+            &extended_domain,
+            ck_2.len(),
+            com,
+            samples,
+            &sample_proof.values,
+            &sample_proof.com_proofs,
+        )?;
+
+        // Check aggregated proofs against committed proofs
+        let proof_valid = SampleIPA::<P, D>::verify_with_structured_scalar_message(
+            &v_srs.ip_v_srs,
+            &HomomorphicPlaceholderValue,
+            (
+                &sample_proof.com_proofs,
+                &IdentityOutput(vec![sample_proof.agg_proof]),
+            ),
+            &r,
+            &sample_proof.proof_ip_proof,
+        )?;
+        let com_r_points = PedersenCommitment::<P::G1>::commit(ck_2, &r_points)?;
+        let shifted_proof_valid = ShiftedSampleIPA::<P, D>::verify(
+            &v_srs.ip_v_srs,
+            &HomomorphicPlaceholderValue,
+            (
+                &sample_proof.com_proofs,
+                &com_r_points,
+                &IdentityOutput(vec![sample_proof.agg_shifted_proof]),
+            ),
+            &sample_proof.shifted_proof_ip_proof,
+        )?;
+
+        // Check aggregated KZG verification equation
+        let kzg_v_srs = &v_srs.kzg_v_srs;
+        let r_vec = &r_vec[..samples.len()];
+        let sampled_coms = samples
+            .iter()
+            .map(|(i, _)| com.row_coms[*i])
+            .collect::<Vec<P::G1>>();
+        let r_coms = MultiexponentiationInnerProduct::inner_product(&sampled_coms, r_vec)?;
+        let r_values = ScalarInnerProduct::inner_product(&sample_proof.values, r_vec)?;
+        let kzg_valid = P::pairing(
+            r_coms - kzg_v_srs.g.mul_bigint(r_values.into_bigint())
+                + sample_proof.agg_shifted_proof,
+            kzg_v_srs.h,
+        )
+        .0 == P::pairing(sample_proof.agg_proof, kzg_v_srs.h_alpha).0;
+
+        Ok(proof_valid && shifted_proof_valid && kzg_valid)
+    }
+
+    fn domains(
+        size: usize,
+    ) -> Result<
+        (
+            Radix2EvaluationDomain<P::ScalarField>,
+            Radix2EvaluationDomain<P::ScalarField>,
+        ),
+        Error,
+    > {
+        if !size.is_power_of_two() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                size,
+                size.next_power_of_two(),
+            ));
+        }
+        match (
+            Radix2EvaluationDomain::new(size),
+            Radix2EvaluationDomain::new(2 * size),
+        ) {
+            (Some(domain), Some(extended_domain)) => Ok((domain, extended_domain)),
+            _ => Err(InnerProductArgumentError::MessageLengthInvalid(size, size)),
+        }
+    }
+
+    // Batching challenge with its powers, and powers scaled by the sampled column points, zero on
+    // padded positions
+    fn sample_challenges(
+        extended_domain: &Radix2EvaluationDomain<P::ScalarField>,
+        len: usize,
+        com: &DataCommitment<P>,
+        samples: &[(usize, usize)],
+        values: &[P::ScalarField],
+        com_proofs: &ExtensionFieldElement<P>,
+    ) -> Result<(P::ScalarField, Vec<P::ScalarField>, Vec<P::ScalarField>), Error> {
+        let sample_indices = samples
+            .iter()
+            .flat_map(|(i, j)| vec![*i as u64, *j as u64])
+            .collect::<Vec<u64>>();
+        let r = Self::compute_challenge(&canonical_bytes![
+            com.row_coms,
+            sample_indices,
+            values,
+            com_proofs
+        ]?)?;
+        let r_vec = structured_scalar_power(len, &r);
+        let mut r_points = r_vec
+            .iter()
+            .zip(samples)
+            .map(|(r, (_, j))| *r * extended_domain.element(*j))
+            .collect::<Vec<P::ScalarField>>();
+        r_points.resize(len, P::ScalarField::zero());
+        Ok((r, r_vec, r_points))
+    }
+
+    fn compute_challenge(transcript: &[u8]) -> Result<P::ScalarField, Error> {
+        let mut input = D::input();
+        input.absorb(transcript)?;
+        let mut counter_nonce: usize = 0;
+        let c = loop {
+            if let Some(c) = input.challenge::<P::ScalarField>(counter_nonce) {
+                break c;
+            };
+            counter_nonce += 1;
+        };
+        Ok(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_ff::{One, UniformRand};
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type Fr = <Bls12_381 as Pairing>::ScalarField;
+    type TestDataAvailability = DataAvailability<Bls12_381, Blake2b512>;
+
+    const DATA_SIZE: usize = 4;
+    const MAX_SAMPLES: usize = 8;
+
+    #[test]
+    fn data_availability_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let srs = TestDataAvailability::setup(&mut rng, DATA_SIZE, MAX_SAMPLES).unwrap();
+        let v_srs = srs.get_verifier_key();
+        let (_, ck_2) = srs.ip_srs.get_commitment_keys();
+
+        let data = (0..DATA_SIZE)
+            .map(|_| (0..DATA_SIZE).map(|_| Fr::rand(&mut rng)).collect())
+            .collect::<Vec<Vec<Fr>>>();
+        let encoded = TestDataAvailability::encode(&data).unwrap();
+        assert_eq!(encoded.extended[2][4], data[1][2]);
+        let com = TestDataAvailability::commit(&srs, &encoded).unwrap();
+        assert!(TestDataAvailability::verify_commitment(&com).unwrap());
+
+        // Row and column openings
+        assert!(TestDataAvailability::verify_row(&srs, &com, 3, &encoded.extended[3]).unwrap());
+        let mut wrong_row = encoded.extended[3].clone();
+        wrong_row[0] += &Fr::one();
+        assert!(!TestDataAvailability::verify_row(&srs, &com, 3, &wrong_row).unwrap());
+        let column_opening = TestDataAvailability::open_column(&srs, &encoded, &com, 5).unwrap();
+        assert!(TestDataAvailability::verify_column(&v_srs, &com, 5, &column_opening).unwrap());
+        assert!(!TestDataAvailability::verify_column(&v_srs, &com, 6, &column_opening).unwrap());
+
+        // Aggregated samples, fewer than the maximum
+        let samples = vec![(0, 7), (3, 1), (6, 6), (7, 2), (1, 1)];
+        let sample_proof =
+            TestDataAvailability::prove_samples(&srs, &encoded, &com, &samples).unwrap();
+        assert!(
+            TestDataAvailability::verify_samples(&v_srs, &ck_2, &com, &samples, &sample_proof)
+                .unwrap()
+        );
+        let mut wrong_samples = samples.clone();
+        wrong_samples[2] = (6, 5);
+        assert!(!TestDataAvailability::verify_samples(
+            &v_srs,
+            &ck_2,
+            &com,
+            &wrong_samples,
+            &sample_proof
+        )
+        .unwrap());
+
+        // Row commitments not matching a 2D encoding
+        let mut wrong_com = com.clone();
+        wrong_com.row_coms[1] = wrong_com.row_coms[2];
+        assert!(!TestDataAvailability::verify_commitment(&wrong_com).unwrap());
+    }
+}
@@ -1,33 +1,49 @@
-use ark_ec::{group::Group, AffineCurve, PairingEngine};
-use ark_ff::{to_bytes, Field, One};
+use ark_ec::{pairing::Pairing, AffineRepr, Group};
+use ark_ff::{Field, One};
 use ark_groth16::{Proof, VerifyingKey};
+use ark_poly::polynomial::{
+    univariate::DensePolynomial as UnivariatePolynomial, DenseUVPolynomial,
+};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Write,
+};
 
-use std::ops::AddAssign;
+use ark_std::{borrow::Cow, ops::AddAssign, vec, vec::Vec};
 
-use digest::Digest;
-use rand::Rng;
+use rand::{CryptoRng, RngCore};
 
+use super::poly_commit::{BivariatePolynomial, BivariatePolynomialCommitment, OpeningProof};
 use crate::{
+    gipa::{GIPAProverState, GIPA},
+    limits::{deserialize_vec, limits},
+    session::session_transcript,
+    sponge::SpongeDigest,
     tipa::{
-        structured_scalar_message::{structured_scalar_power, TIPAWithSSM, TIPAWithSSMProof},
-        TIPAProof, VerifierSRS, SRS, TIPA,
+        structured_scalar_message::{
+            structured_scalar_power, SSMPlaceholderCommitment, TIPAWithSSM, TIPAWithSSMProof,
+        },
+        CommitmentKeyOpening, PairingCheck, PairingCheckBatch, TIPAProof, VerifierSRS, SRS, TIPA,
     },
-    Error,
+    transcript::{ChallengeInput, TranscriptDigest},
+    validation::Validate,
+    Error, InnerProductArgumentError,
 };
 use ark_dh_commitments::{
     afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
     identity::{HomomorphicPlaceholderValue, IdentityCommitment, IdentityOutput},
 };
+#[cfg(feature = "serde")]
+use ark_inner_products::impl_canonical_serde;
 use ark_inner_products::{
-    ExtensionFieldElement, InnerProduct, MultiexponentiationInnerProduct, PairingInnerProduct,
-    ScalarInnerProduct,
+    ExtensionFieldElement, InnerProduct, InnerProductError, MultiexponentiationInnerProduct,
+    PairingInnerProduct, ScalarInnerProduct,
 };
 
 type PairingInnerProductAB<P, D> = TIPA<
     PairingInnerProduct<P>,
     AFGHOCommitmentG1<P>,
     AFGHOCommitmentG2<P>,
-    IdentityCommitment<ExtensionFieldElement<P>, <P as PairingEngine>::Fr>,
+    IdentityCommitment<ExtensionFieldElement<P>, <P as Pairing>::ScalarField>,
     P,
     D,
 >;
@@ -36,120 +52,1088 @@ type PairingInnerProductABProof<P, D> = TIPAProof<
     PairingInnerProduct<P>,
     AFGHOCommitmentG1<P>,
     AFGHOCommitmentG2<P>,
-    IdentityCommitment<ExtensionFieldElement<P>, <P as PairingEngine>::Fr>,
+    IdentityCommitment<ExtensionFieldElement<P>, <P as Pairing>::ScalarField>,
     P,
     D,
 >;
 
 type MultiExpInnerProductC<P, D> = TIPAWithSSM<
-    MultiexponentiationInnerProduct<<P as PairingEngine>::G1Projective>,
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
     AFGHOCommitmentG1<P>,
-    IdentityCommitment<<P as PairingEngine>::G1Projective, <P as PairingEngine>::Fr>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
     P,
     D,
 >;
 
 type MultiExpInnerProductCProof<P, D> = TIPAWithSSMProof<
-    MultiexponentiationInnerProduct<<P as PairingEngine>::G1Projective>,
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
     AFGHOCommitmentG1<P>,
-    IdentityCommitment<<P as PairingEngine>::G1Projective, <P as PairingEngine>::Fr>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
     P,
     D,
 >;
 
-pub struct AggregateProof<P: PairingEngine, D: Digest> {
+type PairingInnerProductABState<P, D> = GIPAProverState<
+    PairingInnerProduct<P>,
+    AFGHOCommitmentG1<P>,
+    AFGHOCommitmentG2<P>,
+    IdentityCommitment<ExtensionFieldElement<P>, <P as Pairing>::ScalarField>,
+    D,
+>;
+
+type MultiExpInnerProductCState<P, D> = GIPAProverState<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    SSMPlaceholderCommitment<<P as Pairing>::ScalarField>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    D,
+>;
+
+// Carries the number of aggregated proofs and a digest of the verifying key alongside the inner
+// product proofs. Canonical deserialization rejects proofs whose shape does not match this metadata.
+// Application metadata, e.g. a block hash or batch id, is bound into the challenge r, so the proof
+// does not verify once it is changed
+pub struct AggregateProof<P: Pairing, D: TranscriptDigest> {
+    num_proofs: u64,
+    vk_digest: Vec<u8>,
+    metadata: Vec<u8>,
     com_a: ExtensionFieldElement<P>,
     com_b: ExtensionFieldElement<P>,
     com_c: ExtensionFieldElement<P>,
     ip_ab: ExtensionFieldElement<P>,
-    agg_c: P::G1Projective,
+    agg_c: P::G1,
     tipa_proof_ab: PairingInnerProductABProof<P, D>,
     tipa_proof_c: MultiExpInnerProductCProof<P, D>,
 }
 
-pub fn setup_inner_product<P, D, R: Rng>(rng: &mut R, size: usize) -> Result<SRS<P>, Error>
+// Aggregate proof with all Fiat-Shamir challenges derived from an algebraic sponge. Together with
+// `verify_aggregate_proof_deferred` this leaves the verifier with field and group operations only,
+// with all pairings, including those of the base commitments, returned for an outer circuit to check
+pub type SpongeAggregateProof<P, S> =
+    AggregateProof<P, SpongeDigest<<P as Pairing>::ScalarField, S>>;
+
+impl<P: Pairing, D: TranscriptDigest> AggregateProof<P, D> {
+    pub fn num_proofs(&self) -> usize {
+        self.num_proofs as usize
+    }
+
+    pub fn vk_digest(&self) -> &[u8] {
+        &self.vk_digest
+    }
+
+    pub fn metadata(&self) -> &[u8] {
+        &self.metadata
+    }
+
+    // Checks the number of proofs and the lengths of the proof components
+    pub fn validate_shape(&self) -> Result<(), Error> {
+        if self.num_proofs > limits().max_vector_len as u64 {
+            return Err(InnerProductArgumentError::LimitExceeded {
+                limit: "number of proofs",
+                size: self.num_proofs as usize,
+                max: limits().max_vector_len,
+            });
+        }
+        let num_proofs = self.num_proofs as usize;
+        if num_proofs == 0 || !num_proofs.is_power_of_two() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                num_proofs,
+                num_proofs.next_power_of_two(),
+            ));
+        }
+        if self.vk_digest.len() != D::output_size() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                self.vk_digest.len(),
+                D::output_size(),
+            ));
+        }
+        let num_rounds = num_proofs.trailing_zeros() as usize;
+        for rounds in [
+            self.tipa_proof_ab.gipa_proof.r_commitment_steps.len(),
+            self.tipa_proof_c.gipa_proof.r_commitment_steps.len(),
+        ]
+        .iter()
+        {
+            if *rounds != num_rounds {
+                return Err(InnerProductArgumentError::MessageLengthInvalid(
+                    *rounds, num_rounds,
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<P: Pairing, D: TranscriptDigest> Validate for AggregateProof<P, D>
+where
+    P::G1: Validate,
+    P::G2: Validate,
+    P::ScalarField: Validate,
+{
+    fn validate(&self) -> Result<(), Error> {
+        self.validate_shape()?;
+        self.com_a.validate()?;
+        self.com_b.validate()?;
+        self.com_c.validate()?;
+        self.ip_ab.validate()?;
+        self.agg_c.validate()?;
+        self.tipa_proof_ab.validate()?;
+        self.tipa_proof_c.validate()
+    }
+}
+
+impl<P: Pairing, D: TranscriptDigest> CanonicalSerialize for AggregateProof<P, D> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.num_proofs.serialize_with_mode(&mut writer, compress)?;
+        self.vk_digest.serialize_with_mode(&mut writer, compress)?;
+        self.metadata.serialize_with_mode(&mut writer, compress)?;
+        self.com_a.serialize_with_mode(&mut writer, compress)?;
+        self.com_b.serialize_with_mode(&mut writer, compress)?;
+        self.com_c.serialize_with_mode(&mut writer, compress)?;
+        self.ip_ab.serialize_with_mode(&mut writer, compress)?;
+        self.agg_c.serialize_with_mode(&mut writer, compress)?;
+        self.tipa_proof_ab
+            .serialize_with_mode(&mut writer, compress)?;
+        self.tipa_proof_c.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.num_proofs.serialized_size(compress)
+            + self.vk_digest.serialized_size(compress)
+            + self.metadata.serialized_size(compress)
+            + self.com_a.serialized_size(compress)
+            + self.com_b.serialized_size(compress)
+            + self.com_c.serialized_size(compress)
+            + self.ip_ab.serialized_size(compress)
+            + self.agg_c.serialized_size(compress)
+            + self.tipa_proof_ab.serialized_size(compress)
+            + self.tipa_proof_c.serialized_size(compress)
+    }
+}
+
+impl<P: Pairing, D: TranscriptDigest> Valid for AggregateProof<P, D> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.num_proofs.check()?;
+        self.vk_digest.check()?;
+        self.metadata.check()?;
+        self.com_a.check()?;
+        self.com_b.check()?;
+        self.com_c.check()?;
+        self.ip_ab.check()?;
+        self.agg_c.check()?;
+        self.tipa_proof_ab.check()?;
+        self.tipa_proof_c.check()
+    }
+}
+
+// Digest of the verifying key, of the output size of `D`. The length is checked before reading
+// any byte, so a corrupt length cannot allocate more
+fn deserialize_vk_digest<D: TranscriptDigest, R: Read>(
+    reader: R,
+    compress: Compress,
+    validate: ark_serialize::Validate,
+) -> Result<Vec<u8>, SerializationError> {
+    let vk_digest = deserialize_vec(reader, compress, validate, D::output_size())?;
+    if vk_digest.len() != D::output_size() {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(vk_digest)
+}
+
+impl<P: Pairing, D: TranscriptDigest> CanonicalDeserialize for AggregateProof<P, D> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        let proof = AggregateProof {
+            num_proofs: CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+            vk_digest: deserialize_vk_digest::<D, _>(&mut reader, compress, validate)?,
+            metadata: deserialize_vec(&mut reader, compress, validate, limits().max_vector_len)?,
+            com_a: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            com_b: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            com_c: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            ip_ab: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            agg_c: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            tipa_proof_ab: CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+            tipa_proof_c: CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+        };
+        proof
+            .validate_shape()
+            .map_err(|_| SerializationError::InvalidData)?;
+        Ok(proof)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl_canonical_serde!([P: Pairing, D: TranscriptDigest] AggregateProof<P, D>);
+
+// Aggregate proof for a verifier that only holds a commitment to the public inputs: a single
+// bivariate polynomial commitment with one Y polynomial per public input position, whose
+// coefficients are that input across all proofs
+pub struct AggregateProofWithInputCommitment<P: Pairing, D: TranscriptDigest> {
+    aggregate_proof: AggregateProof<P, D>,
+    agg_inputs: Vec<P::ScalarField>,
+    input_proof: OpeningProof<P, D>,
+}
+
+impl<P: Pairing, D: TranscriptDigest> Validate for AggregateProofWithInputCommitment<P, D>
+where
+    AggregateProof<P, D>: Validate,
+    OpeningProof<P, D>: Validate,
+    P::ScalarField: Validate,
+{
+    fn validate(&self) -> Result<(), Error> {
+        self.aggregate_proof.validate()?;
+        self.agg_inputs.validate()?;
+        self.input_proof.validate()
+    }
+}
+
+impl<P: Pairing, D: TranscriptDigest> CanonicalSerialize
+    for AggregateProofWithInputCommitment<P, D>
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.aggregate_proof
+            .serialize_with_mode(&mut writer, compress)?;
+        self.agg_inputs.serialize_with_mode(&mut writer, compress)?;
+        self.input_proof.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.aggregate_proof.serialized_size(compress)
+            + self.agg_inputs.serialized_size(compress)
+            + self.input_proof.serialized_size(compress)
+    }
+}
+
+impl<P: Pairing, D: TranscriptDigest> Valid for AggregateProofWithInputCommitment<P, D> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.aggregate_proof.check()?;
+        self.agg_inputs.check()?;
+        self.input_proof.check()
+    }
+}
+
+impl<P: Pairing, D: TranscriptDigest> CanonicalDeserialize
+    for AggregateProofWithInputCommitment<P, D>
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(AggregateProofWithInputCommitment {
+            aggregate_proof: CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+            agg_inputs: deserialize_vec(&mut reader, compress, validate, limits().max_vector_len)?,
+            input_proof: CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl_canonical_serde!([P: Pairing, D: TranscriptDigest] AggregateProofWithInputCommitment<P, D>);
+
+// Bivariate polynomial commitment key for the public inputs. The verifier only needs the
+// `get_verifier_key` of its first half
+pub type PublicInputSRS<P> = (SRS<P>, Vec<<P as Pairing>::G1Affine>);
+
+// Progress of an aggregation, handed out between GIPA rounds. Written to disk during a long
+// aggregation, it lets `resume_aggregate_proofs` continue after a crash instead of starting over.
+// The prover state is borrowed while aggregating and owned once deserialized
+pub struct AggregationCheckpoint<'a, P: Pairing, D: TranscriptDigest> {
+    commitments: Cow<'a, AggregationCommitments<P>>,
+    stage: AggregationStage<'a, P, D>,
+}
+
+// Everything computed before the inner product proofs, including the expensive commitments
+#[derive(Clone)]
+struct AggregationCommitments<P: Pairing> {
+    num_proofs: u64,
+    vk_digest: Vec<u8>,
+    metadata: Vec<u8>,
+    com_a: ExtensionFieldElement<P>,
+    com_b: ExtensionFieldElement<P>,
+    com_c: ExtensionFieldElement<P>,
+    ip_ab: ExtensionFieldElement<P>,
+    agg_c: P::G1,
+    r: P::ScalarField,
+}
+
+enum AggregationStage<'a, P: Pairing, D: TranscriptDigest> {
+    // Proving the pairing inner product of A^r and B
+    AB(Cow<'a, PairingInnerProductABState<P, D>>),
+    // Proving the multiexponentiation of C by the powers of r, once A^r and B are proven
+    C(
+        Cow<'a, PairingInnerProductABProof<P, D>>,
+        Cow<'a, MultiExpInnerProductCState<P, D>>,
+    ),
+}
+
+impl<'a, P: Pairing, D: TranscriptDigest> AggregationCheckpoint<'a, P, D> {
+    pub fn num_proofs(&self) -> usize {
+        self.commitments.num_proofs as usize
+    }
+
+    // GIPA rounds completed across both inner product proofs
+    pub fn rounds(&self) -> usize {
+        match &self.stage {
+            AggregationStage::AB(state) => state.rounds(),
+            AggregationStage::C(_, state) => {
+                self.commitments.num_proofs.trailing_zeros() as usize + state.rounds()
+            }
+        }
+    }
+}
+
+impl<'a, P: Pairing, D: TranscriptDigest> CanonicalSerialize for AggregationCheckpoint<'a, P, D> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        let c = &self.commitments;
+        c.num_proofs.serialize_with_mode(&mut writer, compress)?;
+        c.vk_digest.serialize_with_mode(&mut writer, compress)?;
+        c.metadata.serialize_with_mode(&mut writer, compress)?;
+        c.com_a.serialize_with_mode(&mut writer, compress)?;
+        c.com_b.serialize_with_mode(&mut writer, compress)?;
+        c.com_c.serialize_with_mode(&mut writer, compress)?;
+        c.ip_ab.serialize_with_mode(&mut writer, compress)?;
+        c.agg_c.serialize_with_mode(&mut writer, compress)?;
+        c.r.serialize_with_mode(&mut writer, compress)?;
+        match &self.stage {
+            AggregationStage::AB(state) => {
+                0u8.serialize_with_mode(&mut writer, compress)?;
+                state.as_ref().serialize_with_mode(&mut writer, compress)
+            }
+            AggregationStage::C(tipa_proof_ab, state) => {
+                1u8.serialize_with_mode(&mut writer, compress)?;
+                tipa_proof_ab
+                    .as_ref()
+                    .serialize_with_mode(&mut writer, compress)?;
+                state.as_ref().serialize_with_mode(&mut writer, compress)
+            }
+        }
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        let c = &self.commitments;
+        let stage_size = match &self.stage {
+            AggregationStage::AB(state) => state.serialized_size(compress),
+            AggregationStage::C(tipa_proof_ab, state) => {
+                tipa_proof_ab.serialized_size(compress) + state.serialized_size(compress)
+            }
+        };
+        c.num_proofs.serialized_size(compress)
+            + c.vk_digest.serialized_size(compress)
+            + c.metadata.serialized_size(compress)
+            + c.com_a.serialized_size(compress)
+            + c.com_b.serialized_size(compress)
+            + c.com_c.serialized_size(compress)
+            + c.ip_ab.serialized_size(compress)
+            + c.agg_c.serialized_size(compress)
+            + c.r.serialized_size(compress)
+            + 1
+            + stage_size
+    }
+}
+
+impl<'a, P: Pairing, D: TranscriptDigest> Valid for AggregationCheckpoint<'a, P, D> {
+    fn check(&self) -> Result<(), SerializationError> {
+        let c = &self.commitments;
+        c.com_a.check()?;
+        c.com_b.check()?;
+        c.com_c.check()?;
+        c.ip_ab.check()?;
+        c.agg_c.check()?;
+        c.r.check()?;
+        match &self.stage {
+            AggregationStage::AB(state) => state.check(),
+            AggregationStage::C(tipa_proof_ab, state) => {
+                tipa_proof_ab.check()?;
+                state.check()
+            }
+        }
+    }
+}
+
+impl<'a, P: Pairing, D: TranscriptDigest> CanonicalDeserialize for AggregationCheckpoint<'a, P, D> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        let commitments = AggregationCommitments {
+            num_proofs: CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+            vk_digest: deserialize_vk_digest::<D, _>(&mut reader, compress, validate)?,
+            metadata: deserialize_vec(&mut reader, compress, validate, limits().max_vector_len)?,
+            com_a: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            com_b: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            com_c: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            ip_ab: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            agg_c: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            r: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+        };
+        let stage = match u8::deserialize_with_mode(&mut reader, compress, validate)? {
+            0 => AggregationStage::AB(Cow::Owned(CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?)),
+            1 => AggregationStage::C(
+                Cow::Owned(CanonicalDeserialize::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    validate,
+                )?),
+                Cow::Owned(CanonicalDeserialize::deserialize_with_mode(
+                    &mut reader,
+                    compress,
+                    validate,
+                )?),
+            ),
+            _ => return Err(SerializationError::InvalidData),
+        };
+        Ok(AggregationCheckpoint {
+            commitments: Cow::Owned(commitments),
+            stage,
+        })
+    }
+}
+
+pub fn setup_inner_product<P, D, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    size: usize,
+) -> Result<SRS<P>, Error>
 where
-    P: PairingEngine,
-    D: Digest,
+    P: Pairing,
+    D: TranscriptDigest,
 {
     let (srs, _) = PairingInnerProductAB::<P, D>::setup(rng, size)?;
     Ok(srs)
 }
 
-pub fn aggregate_proofs<P, D>(
-    ip_srs: &SRS<P>,
-    proofs: &[Proof<P>],
-) -> Result<AggregateProof<P, D>, Error>
+// Sets up the public input commitment for up to `num_proofs` statements of `num_inputs` public
+// inputs each
+pub fn setup_public_input_commitment<P, D, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    num_proofs: usize,
+    num_inputs: usize,
+) -> Result<PublicInputSRS<P>, Error>
 where
-    P: PairingEngine,
-    D: Digest,
+    P: Pairing,
+    D: TranscriptDigest,
 {
-    let a = proofs
-        .iter()
-        .map(|proof| proof.a.into_projective())
-        .collect::<Vec<P::G1Projective>>();
-    let b = proofs
+    BivariatePolynomialCommitment::<P, D>::setup(
+        rng,
+        num_inputs.next_power_of_two() - 1,
+        num_proofs.saturating_sub(1),
+    )
+}
+
+// Commits to the public inputs of all statements under a single root
+pub fn commit_public_inputs<P, D>(
+    input_srs: &PublicInputSRS<P>,
+    public_inputs: &[Vec<P::ScalarField>],
+) -> Result<ExtensionFieldElement<P>, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    let (input_com, _) = BivariatePolynomialCommitment::<P, D>::commit(
+        input_srs,
+        &public_input_polynomial::<P>(public_inputs)?,
+    )?;
+    Ok(input_com)
+}
+
+// Groth16 proofs in the layout of the aggregation provers: A and C in G1 and B in G2, one entry
+// for each proof in order. The number of proofs is a power of two within the limits
+pub struct AggregationInputs<P: Pairing> {
+    pub a: Vec<P::G1>,
+    pub b: Vec<P::G2>,
+    pub c: Vec<P::G1>,
+}
+
+impl<P: Pairing> AggregationInputs<P> {
+    pub fn new(proofs: &[Proof<P>]) -> Result<Self, Error> {
+        limits().check_vector_len(proofs.len())?;
+        if !proofs.len().is_power_of_two() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                proofs.len(),
+                proofs.len().next_power_of_two(),
+            ));
+        }
+        Ok(AggregationInputs {
+            a: proofs.iter().map(|proof| proof.a.into_group()).collect(),
+            b: proofs.iter().map(|proof| proof.b.into_group()).collect(),
+            c: proofs.iter().map(|proof| proof.c.into_group()).collect(),
+        })
+    }
+
+    // As `new`, first checking that the points of every proof are in the prime order subgroups,
+    // e.g. for proofs received from other provers
+    pub fn validated(proofs: &[Proof<P>]) -> Result<Self, Error>
+    where
+        Proof<P>: Validate,
+    {
+        for proof in proofs {
+            proof.validate()?;
+        }
+        Self::new(proofs)
+    }
+
+    pub fn len(&self) -> usize {
+        self.a.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.a.is_empty()
+    }
+
+    // A_i^(r^i), the left message of TIPP, for `r_vec` the powers of the challenge
+    pub fn a_r(&self, r_vec: &[P::ScalarField]) -> Result<Vec<P::G1>, Error> {
+        if r_vec.len() != self.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                self.len(),
+                r_vec.len(),
+            ));
+        }
+        Ok(self.a.iter().zip(r_vec).map(|(a, r)| *a * r).collect())
+    }
+}
+
+// ck_i^(r^-i), the key under which A^r commits to the same value as A under `ck`
+pub fn rescale_key<G: Group>(ck: &[G], r_vec: &[G::ScalarField]) -> Result<Vec<G>, Error> {
+    if r_vec.len() != ck.len() {
+        return Err(InnerProductArgumentError::MessageLengthInvalid(
+            ck.len(),
+            r_vec.len(),
+        ));
+    }
+    ck.iter()
+        .zip(r_vec)
+        .map(|(ck, r)| {
+            r.inverse()
+                .map(|r_inv| *ck * r_inv)
+                .ok_or(InnerProductArgumentError::ZeroChallenge)
+        })
+        .collect()
+}
+
+// Public inputs of each position combined across the statements with the powers of `r`, after
+// checking that every statement has as many inputs as the verifying key
+pub fn aggregate_public_inputs<P: Pairing>(
+    vk: &VerifyingKey<P>,
+    public_inputs: &[Vec<P::ScalarField>],
+    r: &P::ScalarField,
+) -> Result<Vec<P::ScalarField>, Error> {
+    let num_inputs = vk.gamma_abc_g1.len() - 1;
+    if let Some(inputs) = public_inputs
         .iter()
-        .map(|proof| proof.b.into_projective())
-        .collect::<Vec<P::G2Projective>>();
-    let c = proofs
+        .find(|inputs| inputs.len() != num_inputs)
+    {
+        return Err(InnerProductArgumentError::MessageLengthInvalid(
+            inputs.len(),
+            num_inputs,
+        ));
+    }
+    let r_vec = structured_scalar_power(public_inputs.len(), r);
+    Ok(public_input_columns::<P>(public_inputs)?
         .iter()
-        .map(|proof| proof.c.into_projective())
-        .collect::<Vec<P::G1Projective>>();
+        .map(|column| ScalarInnerProduct::inner_product(column, &r_vec))
+        .collect::<Result<Vec<P::ScalarField>, InnerProductError>>()?)
+}
 
-    let (ck_1, ck_2) = ip_srs.get_commitment_keys();
+// Multiexponentiation of the public input bases of the verifying key by the aggregated inputs,
+// with the constant base raised to `r_sum`, the sum of the powers of `r`
+pub fn public_input_msm<P: Pairing>(
+    vk: &VerifyingKey<P>,
+    agg_inputs: &[P::ScalarField],
+    r_sum: &P::ScalarField,
+) -> Result<P::G1, Error> {
+    if vk.gamma_abc_g1.len() != agg_inputs.len() + 1 {
+        return Err(InnerProductArgumentError::MessageLengthInvalid(
+            agg_inputs.len(),
+            vk.gamma_abc_g1.len() - 1,
+        ));
+    }
+    let mut g_ic = vk.gamma_abc_g1[0] * r_sum;
+    for (b, agg_input) in vk.gamma_abc_g1.iter().skip(1).zip(agg_inputs) {
+        g_ic.add_assign(&(*b * agg_input));
+    }
+    Ok(g_ic)
+}
 
-    let com_a = PairingInnerProduct::<P>::inner_product(&a, &ck_1)?;
-    let com_b = PairingInnerProduct::<P>::inner_product(&ck_2, &b)?;
-    let com_c = PairingInnerProduct::<P>::inner_product(&c, &ck_1)?;
+// Public inputs as a bivariate polynomial: X selects the input position and Y the statement
+fn public_input_polynomial<P: Pairing>(
+    public_inputs: &[Vec<P::ScalarField>],
+) -> Result<BivariatePolynomial<P::ScalarField>, Error> {
+    Ok(BivariatePolynomial::new(
+        public_input_columns::<P>(public_inputs)?
+            .iter()
+            .map(|column| UnivariatePolynomial::from_coefficients_slice(column))
+            .collect(),
+    ))
+}
 
-    // Random linear combination of proofs
+fn public_input_columns<P: Pairing>(
+    public_inputs: &[Vec<P::ScalarField>],
+) -> Result<Vec<Vec<P::ScalarField>>, Error> {
+    let num_inputs = public_inputs.first().map_or(0, |inputs| inputs.len());
+    if let Some(inputs) = public_inputs
+        .iter()
+        .find(|inputs| inputs.len() != num_inputs)
+    {
+        return Err(InnerProductArgumentError::MessageLengthInvalid(
+            inputs.len(),
+            num_inputs,
+        ));
+    }
+    Ok((0..num_inputs)
+        .map(|i| {
+            public_inputs
+                .iter()
+                .map(|inputs| inputs[i])
+                .collect::<Vec<P::ScalarField>>()
+        })
+        .collect())
+}
+
+// Random linear combination challenge, bound to the number of proofs and the verifying key and
+// additionally to any public input commitment, application metadata and session
+fn compute_aggregation_challenge<P, D>(
+    com: (
+        &ExtensionFieldElement<P>,
+        &ExtensionFieldElement<P>,
+        &ExtensionFieldElement<P>,
+    ),
+    num_proofs: usize,
+    vk_digest: &[u8],
+    input_com: Option<&ExtensionFieldElement<P>>,
+    metadata: &[u8],
+    session: Option<&[u8]>,
+) -> Result<P::ScalarField, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    let mut input = D::input();
+    input.absorb(com.0)?;
+    input.absorb(com.1)?;
+    input.absorb(com.2)?;
+    input.absorb(&(num_proofs as u64))?;
+    input.absorb(vk_digest)?;
+    input.absorb(&input_com.cloned())?;
+    // Length prefixed, like every slice in the canonical encoding
+    input.absorb(metadata)?;
+    // Only absorbed in a session, so the challenges of proofs outside of one are unchanged
+    if let Some(id) = session {
+        input.absorb(id)?;
+    }
     let mut counter_nonce: usize = 0;
     let r = loop {
-        let mut hash_input = Vec::new();
-        hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-        //TODO: Should use CanonicalSerialize instead of ToBytes
-        hash_input.extend_from_slice(&to_bytes![com_a, com_b, com_c]?);
-        if let Some(r) = <P::Fr>::from_random_bytes(&D::digest(&hash_input)) {
+        if let Some(r) = input.challenge::<P::ScalarField>(counter_nonce) {
+            transcript_log!("groth16", None, b"r", &r, nonce = counter_nonce);
             break r;
         };
         counter_nonce += 1;
     };
+    Ok(r)
+}
 
-    let r_vec = structured_scalar_power(proofs.len(), &r);
-    let a_r = a
-        .iter()
-        .zip(&r_vec)
-        .map(|(a, r)| a.mul(r))
-        .collect::<Vec<P::G1Projective>>();
-    let ip_ab = PairingInnerProduct::<P>::inner_product(&a_r, &b)?;
-    let agg_c = MultiexponentiationInnerProduct::<P::G1Projective>::inner_product(&c, &r_vec)?;
+// Challenge for opening the public input commitment, derived once the aggregated inputs are fixed
+fn compute_input_opening_challenge<P, D>(
+    r: &P::ScalarField,
+    agg_inputs: &[P::ScalarField],
+) -> Result<P::ScalarField, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    let mut input = D::input();
+    input.absorb_scalar(r)?;
+    input.absorb(&(agg_inputs.len() as u64))?;
+    for x in agg_inputs {
+        input.absorb_scalar(x)?;
+    }
+    let mut counter_nonce: usize = 0;
+    let s = loop {
+        if let Some(s) = input.challenge::<P::ScalarField>(counter_nonce) {
+            transcript_log!("groth16", None, b"s", &s, nonce = counter_nonce);
+            break s;
+        };
+        counter_nonce += 1;
+    };
+    Ok(s)
+}
 
-    let ck_1_r = ck_1
-        .iter()
-        .zip(&r_vec)
-        .map(|(ck, r)| ck.mul(&r.inverse().unwrap()))
-        .collect::<Vec<P::G2Projective>>();
+// Seed of the first GIPA challenge of both inner product proofs, derived from the session
+// transcript of `session::session_transcript` and zero outside of a session
+fn session_seed<P, D>(session: Option<&[u8]>) -> Result<P::ScalarField, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    match session {
+        Some(id) => session_transcript::<D>(id)?.challenge(b"groth16-gipa-seed"),
+        None => Ok(<P::ScalarField>::default()),
+    }
+}
 
-    assert_eq!(
-        com_a,
-        PairingInnerProduct::<P>::inner_product(&a_r, &ck_1_r)?
-    );
+pub fn verifying_key_digest<P, D>(vk: &VerifyingKey<P>) -> Result<Vec<u8>, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    let mut vk_bytes = Vec::new();
+    vk.serialize_compressed(&mut vk_bytes)?;
+    hash_count!(vk_bytes);
+    Ok(D::hash(&vk_bytes))
+}
 
-    let tipa_proof_ab = PairingInnerProductAB::<P, D>::prove_with_srs_shift(
-        &ip_srs,
-        (&a_r, &b),
-        (&ck_1_r, &ck_2, &HomomorphicPlaceholderValue),
-        &r,
+pub fn aggregate_proofs<P, D>(
+    ip_srs: &SRS<P>,
+    vk: &VerifyingKey<P>,
+    proofs: &[Proof<P>],
+) -> Result<AggregateProof<P, D>, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    aggregate_proofs_with_metadata(ip_srs, vk, proofs, &[])
+}
+
+// Aggregates as `aggregate_proofs`, binding `metadata` into the proof so that it only verifies
+// for the same metadata, e.g. with `verify_aggregate_proof_with_metadata`
+pub fn aggregate_proofs_with_metadata<P, D>(
+    ip_srs: &SRS<P>,
+    vk: &VerifyingKey<P>,
+    proofs: &[Proof<P>],
+    metadata: &[u8],
+) -> Result<AggregateProof<P, D>, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    let (proof, _) =
+        _aggregate_proofs::<P, D, Error>(ip_srs, vk, proofs, None, metadata, None, |_| Ok(()))?;
+    Ok(proof)
+}
+
+// Aggregates as `aggregate_proofs` in the session `id` supplied by the application, see
+// `session::session_transcript`. The session is bound into the aggregation challenge and seeds
+// both inner product proofs, so the proof only verifies with `verify_aggregate_proof_with_session`
+// for the same session
+pub fn aggregate_proofs_with_session<P, D>(
+    ip_srs: &SRS<P>,
+    vk: &VerifyingKey<P>,
+    proofs: &[Proof<P>],
+    id: &[u8],
+) -> Result<AggregateProof<P, D>, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    let (proof, _) =
+        _aggregate_proofs::<P, D, Error>(ip_srs, vk, proofs, None, &[], Some(id), |_| Ok(()))?;
+    Ok(proof)
+}
+
+// Aggregates as `aggregate_proofs`, handing a checkpoint to `save` once the commitments are
+// computed and after every GIPA round. An error from `save` stops the aggregation
+pub fn aggregate_proofs_with_checkpoints<P, D, E>(
+    ip_srs: &SRS<P>,
+    vk: &VerifyingKey<P>,
+    proofs: &[Proof<P>],
+    save: impl FnMut(&AggregationCheckpoint<P, D>) -> Result<(), E>,
+) -> Result<AggregateProof<P, D>, E>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+    E: From<Error>,
+{
+    let (proof, _) = _aggregate_proofs::<P, D, E>(ip_srs, vk, proofs, None, &[], None, save)?;
+    Ok(proof)
+}
+
+// Continues an aggregation from a checkpoint saved by `aggregate_proofs_with_checkpoints` for the
+// same proofs and verifying key, and keeps handing new checkpoints to `save`
+pub fn resume_aggregate_proofs<P, D, E>(
+    ip_srs: &SRS<P>,
+    vk: &VerifyingKey<P>,
+    proofs: &[Proof<P>],
+    checkpoint: AggregationCheckpoint<P, D>,
+    save: impl FnMut(&AggregationCheckpoint<P, D>) -> Result<(), E>,
+) -> Result<AggregateProof<P, D>, E>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+    E: From<Error>,
+{
+    let _aggregate = phase_span!("aggregate_proofs", num_proofs = proofs.len());
+    if checkpoint.num_proofs() != proofs.len() {
+        return Err(InnerProductArgumentError::MessageLengthInvalid(
+            checkpoint.num_proofs(),
+            proofs.len(),
+        )
+        .into());
+    }
+    if checkpoint.commitments.vk_digest != verifying_key_digest::<P, D>(vk)? {
+        return Err(InnerProductArgumentError::CheckpointMismatch.into());
+    }
+    _resume_aggregation(ip_srs, proofs, checkpoint, save)
+}
+
+// Aggregates as `aggregate_proofs`, additionally proving the aggregated public inputs against
+// `commit_public_inputs` of `public_inputs` under `input_srs`
+pub fn aggregate_proofs_with_input_commitment<P, D>(
+    ip_srs: &SRS<P>,
+    vk: &VerifyingKey<P>,
+    input_srs: &PublicInputSRS<P>,
+    proofs: &[Proof<P>],
+    public_inputs: &[Vec<P::ScalarField>],
+) -> Result<AggregateProofWithInputCommitment<P, D>, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    if proofs.len() != public_inputs.len() {
+        return Err(InnerProductArgumentError::MessageLengthInvalid(
+            proofs.len(),
+            public_inputs.len(),
+        ));
+    }
+    let input_polynomial = public_input_polynomial::<P>(public_inputs)?;
+    let (input_com, y_polynomial_coms) =
+        BivariatePolynomialCommitment::<P, D>::commit(input_srs, &input_polynomial)?;
+    let (aggregate_proof, r) =
+        _aggregate_proofs::<P, D, Error>(ip_srs, vk, proofs, Some(&input_com), &[], None, |_| {
+            Ok(())
+        })?;
+
+    // The aggregated inputs are the evaluations of the Y polynomials at r, checked all at once
+    // through a single opening at (s, r)
+    let agg_inputs = aggregate_public_inputs(vk, public_inputs, &r)?;
+    let s = compute_input_opening_challenge::<P, D>(&r, &agg_inputs)?;
+    let input_proof = BivariatePolynomialCommitment::<P, D>::open(
+        input_srs,
+        &input_polynomial,
+        &y_polynomial_coms,
+        &(s, r),
     )?;
 
-    let tipa_proof_c = MultiExpInnerProductC::<P, D>::prove_with_structured_scalar_message(
-        &ip_srs,
-        (&c, &r_vec),
-        (&ck_1, &HomomorphicPlaceholderValue),
+    Ok(AggregateProofWithInputCommitment {
+        aggregate_proof,
+        agg_inputs,
+        input_proof,
+    })
+}
+
+fn _aggregate_proofs<P, D, E>(
+    ip_srs: &SRS<P>,
+    vk: &VerifyingKey<P>,
+    proofs: &[Proof<P>],
+    input_com: Option<&ExtensionFieldElement<P>>,
+    metadata: &[u8],
+    session: Option<&[u8]>,
+    mut save: impl FnMut(&AggregationCheckpoint<P, D>) -> Result<(), E>,
+) -> Result<(AggregateProof<P, D>, P::ScalarField), E>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+    E: From<Error>,
+{
+    let _aggregate = phase_span!("aggregate_proofs", num_proofs = proofs.len());
+    let checkpoint = _start_aggregation::<P, D>(ip_srs, vk, proofs, input_com, metadata, session)?;
+    save(&checkpoint)?;
+    let r = checkpoint.commitments.r;
+    Ok((_resume_aggregation(ip_srs, proofs, checkpoint, save)?, r))
+}
+
+// Commits to the proofs and sets up the inner product proof for A^r and B
+fn _start_aggregation<'a, P, D>(
+    ip_srs: &SRS<P>,
+    vk: &VerifyingKey<P>,
+    proofs: &[Proof<P>],
+    input_com: Option<&ExtensionFieldElement<P>>,
+    metadata: &[u8],
+    session: Option<&[u8]>,
+) -> Result<AggregationCheckpoint<'a, P, D>, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    let inputs = AggregationInputs::new(proofs)?;
+    let (ck_1, ck_2) = ip_srs.get_commitment_keys();
+
+    let (com_a, com_b, com_c) = {
+        let _commit = phase_span!("commit", len = proofs.len());
+        // A and C are committed under the same key in G2, prepared once for both
+        let ck_1_prepared = PairingInnerProduct::<P>::precompute_right(&ck_1);
+        (
+            PairingInnerProduct::<P>::inner_product_prepared_right(&inputs.a, &ck_1_prepared)?,
+            PairingInnerProduct::<P>::inner_product(&ck_2, &inputs.b)?,
+            PairingInnerProduct::<P>::inner_product_prepared_right(&inputs.c, &ck_1_prepared)?,
+        )
+    };
+
+    // Random linear combination of proofs
+    let vk_digest = verifying_key_digest::<P, D>(vk)?;
+    let r = compute_aggregation_challenge::<P, D>(
+        (&com_a, &com_b, &com_c),
+        proofs.len(),
+        &vk_digest,
+        input_com,
+        metadata,
+        session,
     )?;
 
+    let r_vec = structured_scalar_power(proofs.len(), &r);
+    let a_r = inputs.a_r(&r_vec)?;
+    let ip_ab = PairingInnerProduct::<P>::inner_product(&a_r, &inputs.b)?;
+    let agg_c = MultiexponentiationInnerProduct::<P::G1>::inner_product(&inputs.c, &r_vec)?;
+    let ck_1_r = rescale_key(&ck_1, &r_vec)?;
+
+    // A^r under the rescaled key must open com_a, which the verifier relies on
+    if com_a != PairingInnerProduct::<P>::inner_product(&a_r, &ck_1_r)? {
+        return Err(InnerProductArgumentError::InnerProductInvalid);
+    }
+
+    let state = GIPA::prover_state(
+        (&a_r, &inputs.b),
+        (&ck_1_r, &ck_2, &[HomomorphicPlaceholderValue]),
+    )?
+    .with_seed(session_seed::<P, D>(session)?);
+    Ok(AggregationCheckpoint {
+        commitments: Cow::Owned(AggregationCommitments {
+            num_proofs: proofs.len() as u64,
+            vk_digest,
+            metadata: metadata.to_vec(),
+            com_a,
+            com_b,
+            com_c,
+            ip_ab,
+            agg_c,
+            r,
+        }),
+        stage: AggregationStage::AB(Cow::Owned(state)),
+    })
+}
+
+// Runs the inner product proofs from where the checkpoint left off
+fn _resume_aggregation<P, D, E>(
+    ip_srs: &SRS<P>,
+    proofs: &[Proof<P>],
+    checkpoint: AggregationCheckpoint<P, D>,
+    mut save: impl FnMut(&AggregationCheckpoint<P, D>) -> Result<(), E>,
+) -> Result<AggregateProof<P, D>, E>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+    E: From<Error>,
+{
+    let commitments = checkpoint.commitments.into_owned();
+    let r = commitments.r;
+    let (tipa_proof_ab, c_state) = match checkpoint.stage {
+        AggregationStage::AB(state) => {
+            // The proof for C is seeded as the proof for A^r and B
+            let seed = state.seed;
+            let tipa_proof_ab = PairingInnerProductAB::<P, D>::prove_with_srs_shift_from_state(
+                ip_srs,
+                state.into_owned(),
+                &r,
+                |state| {
+                    save(&AggregationCheckpoint {
+                        commitments: Cow::Borrowed(&commitments),
+                        stage: AggregationStage::AB(Cow::Borrowed(state)),
+                    })
+                },
+            )?;
+
+            let c = AggregationInputs::new(proofs)?.c;
+            let r_vec = structured_scalar_power(proofs.len(), &r);
+            let (ck_1, _) = ip_srs.get_commitment_keys();
+            let c_state = GIPA::prover_state(
+                (&c, &r_vec),
+                (
+                    &ck_1,
+                    &vec![HomomorphicPlaceholderValue; proofs.len()],
+                    &[HomomorphicPlaceholderValue],
+                ),
+            )?
+            .with_seed(seed);
+            save(&AggregationCheckpoint {
+                commitments: Cow::Borrowed(&commitments),
+                stage: AggregationStage::C(Cow::Borrowed(&tipa_proof_ab), Cow::Borrowed(&c_state)),
+            })?;
+            (tipa_proof_ab, c_state)
+        }
+        AggregationStage::C(tipa_proof_ab, state) => {
+            (tipa_proof_ab.into_owned(), state.into_owned())
+        }
+    };
+
+    let tipa_proof_c =
+        MultiExpInnerProductC::<P, D>::prove_with_structured_scalar_message_from_state(
+            ip_srs,
+            c_state,
+            |state| {
+                save(&AggregationCheckpoint {
+                    commitments: Cow::Borrowed(&commitments),
+                    stage: AggregationStage::C(Cow::Borrowed(&tipa_proof_ab), Cow::Borrowed(state)),
+                })
+            },
+        )?;
+
+    let AggregationCommitments {
+        num_proofs,
+        vk_digest,
+        metadata,
+        com_a,
+        com_b,
+        com_c,
+        ip_ab,
+        agg_c,
+        ..
+    } = commitments;
     Ok(AggregateProof {
+        num_proofs,
+        vk_digest,
+        metadata,
         com_a,
         com_b,
         com_c,
@@ -163,70 +1147,1064 @@ where
 pub fn verify_aggregate_proof<P, D>(
     ip_verifier_srs: &VerifierSRS<P>,
     vk: &VerifyingKey<P>,
-    public_inputs: &Vec<Vec<P::Fr>>, //TODO: Should use ToConstraintField instead
+    public_inputs: &[Vec<P::ScalarField>], //TODO: Should use ToConstraintField instead
+    proof: &AggregateProof<P, D>,
+) -> Result<bool, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    verify_aggregate_proof_with_metadata(ip_verifier_srs, vk, public_inputs, &[], proof)
+}
+
+// Verifies an aggregate proof made by `aggregate_proofs_with_metadata` for the expected metadata,
+// rejecting a proof made for another context. `verify_aggregate_proof` expects empty metadata
+pub fn verify_aggregate_proof_with_metadata<P, D>(
+    ip_verifier_srs: &VerifierSRS<P>,
+    vk: &VerifyingKey<P>,
+    public_inputs: &[Vec<P::ScalarField>],
+    metadata: &[u8],
     proof: &AggregateProof<P, D>,
 ) -> Result<bool, Error>
 where
-    P: PairingEngine,
-    D: Digest,
+    P: Pairing,
+    D: TranscriptDigest,
 {
+    let (valid, pairing_checks) = _verify_aggregate_proof_deferred_with_metadata(
+        ip_verifier_srs,
+        vk,
+        public_inputs,
+        metadata,
+        None,
+        proof,
+    )?;
+    Ok(valid && pairing_checks.iter().all(|check| check.verify()))
+}
+
+// Verifies an aggregate proof made by `aggregate_proofs_with_session` in the session `id`,
+// rejecting a proof made in another session or outside of one
+pub fn verify_aggregate_proof_with_session<P, D>(
+    ip_verifier_srs: &VerifierSRS<P>,
+    vk: &VerifyingKey<P>,
+    public_inputs: &[Vec<P::ScalarField>],
+    id: &[u8],
+    proof: &AggregateProof<P, D>,
+) -> Result<bool, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    let (valid, pairing_checks) = _verify_aggregate_proof_deferred_with_metadata(
+        ip_verifier_srs,
+        vk,
+        public_inputs,
+        &[],
+        Some(id),
+        proof,
+    )?;
+    Ok(valid && pairing_checks.iter().all(|check| check.verify()))
+}
+
+// Verifies several aggregate proofs with the pairing checks of all of them in one batch. Each
+// instance carries the metadata its proof is expected to bind, empty for `aggregate_proofs`
+pub fn verify_aggregate_proofs_batch<P, D, R>(
+    ip_verifier_srs: &VerifierSRS<P>,
+    vk: &VerifyingKey<P>,
+    instances: &[(&[Vec<P::ScalarField>], &[u8], &AggregateProof<P, D>)],
+    rng: &mut R,
+) -> Result<bool, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+    R: RngCore + CryptoRng,
+{
+    let mut batch = PairingCheckBatch::new();
+    for (public_inputs, metadata, proof) in instances {
+        let (valid, pairing_checks) =
+            verify_aggregate_proof_deferred(ip_verifier_srs, vk, public_inputs, metadata, proof)?;
+        if !valid {
+            return Ok(false);
+        }
+        batch.extend(pairing_checks);
+    }
+    Ok(batch.verify(rng))
+}
+
+// Returns the result of all checks not involving pairings along with the unevaluated pairing checks,
+// for a proof expected to bind `metadata`
+pub fn verify_aggregate_proof_deferred<P, D>(
+    ip_verifier_srs: &VerifierSRS<P>,
+    vk: &VerifyingKey<P>,
+    public_inputs: &[Vec<P::ScalarField>],
+    metadata: &[u8],
+    proof: &AggregateProof<P, D>,
+) -> Result<(bool, Vec<PairingCheck<P>>), Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    _verify_aggregate_proof_deferred_with_metadata(
+        ip_verifier_srs,
+        vk,
+        public_inputs,
+        metadata,
+        None,
+        proof,
+    )
+}
+
+fn _verify_aggregate_proof_deferred_with_metadata<P, D>(
+    ip_verifier_srs: &VerifierSRS<P>,
+    vk: &VerifyingKey<P>,
+    public_inputs: &[Vec<P::ScalarField>],
+    metadata: &[u8],
+    session: Option<&[u8]>,
+    proof: &AggregateProof<P, D>,
+) -> Result<(bool, Vec<PairingCheck<P>>), Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    if proof.metadata != metadata {
+        return Ok((false, vec![]));
+    }
+
     // Random linear combination of proofs
-    let mut counter_nonce: usize = 0;
-    let r = loop {
-        let mut hash_input = Vec::new();
-        hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-        //TODO: Should use CanonicalSerialize instead of ToBytes
-        hash_input.extend_from_slice(&to_bytes![proof.com_a, proof.com_b, proof.com_c]?);
-        if let Some(r) = <P::Fr>::from_random_bytes(&D::digest(&hash_input)) {
-            break r;
-        };
-        counter_nonce += 1;
-    };
+    let r = compute_aggregation_challenge::<P, D>(
+        (&proof.com_a, &proof.com_b, &proof.com_c),
+        public_inputs.len(),
+        &verifying_key_digest::<P, D>(vk)?,
+        None,
+        &proof.metadata,
+        session,
+    )?;
 
-    // Check TIPA proofs
-    let tipa_proof_ab_valid = PairingInnerProductAB::<P, D>::verify_with_srs_shift(
+    let agg_inputs = aggregate_public_inputs(vk, public_inputs, &r)?;
+
+    _verify_aggregate_proof_deferred(
         ip_verifier_srs,
-        &HomomorphicPlaceholderValue,
+        vk,
+        public_inputs.len(),
+        &agg_inputs,
+        proof,
+        &r,
+        &session_seed::<P, D>(session)?,
+    )
+}
+
+// Openings of the final commitment keys of the aggregate proof, whose pairing checks have constant
+// target and can be evaluated separately, e.g. with the EVM pairing precompile
+pub fn aggregate_proof_commitment_key_openings<P, D>(
+    proof: &AggregateProof<P, D>,
+) -> Result<
+    (
+        Vec<CommitmentKeyOpening<P::G2>>,
+        Vec<CommitmentKeyOpening<P::G1>>,
+    ),
+    Error,
+>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    _aggregate_proof_commitment_key_openings(proof, None)
+}
+
+// As `aggregate_proof_commitment_key_openings` for a proof made by `aggregate_proofs_with_session`
+// in the session `id`
+pub fn aggregate_proof_commitment_key_openings_with_session<P, D>(
+    proof: &AggregateProof<P, D>,
+    id: &[u8],
+) -> Result<
+    (
+        Vec<CommitmentKeyOpening<P::G2>>,
+        Vec<CommitmentKeyOpening<P::G1>>,
+    ),
+    Error,
+>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    _aggregate_proof_commitment_key_openings(proof, Some(id))
+}
+
+fn _aggregate_proof_commitment_key_openings<P, D>(
+    proof: &AggregateProof<P, D>,
+    session: Option<&[u8]>,
+) -> Result<
+    (
+        Vec<CommitmentKeyOpening<P::G2>>,
+        Vec<CommitmentKeyOpening<P::G1>>,
+    ),
+    Error,
+>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    let r = compute_aggregation_challenge::<P, D>(
+        (&proof.com_a, &proof.com_b, &proof.com_c),
+        proof.num_proofs(),
+        &proof.vk_digest,
+        None,
+        &proof.metadata,
+        session,
+    )?;
+    let seed = session_seed::<P, D>(session)?;
+    let (ck_a_opening, ck_b_opening) =
+        PairingInnerProductAB::<P, D>::commitment_key_openings_with_seed(
+            (
+                &proof.com_a,
+                &proof.com_b,
+                &IdentityOutput(vec![proof.ip_ab.clone()]),
+            ),
+            &proof.tipa_proof_ab,
+            &r,
+            &seed,
+        )?;
+    let ck_c_opening = MultiExpInnerProductC::<P, D>::commitment_key_opening_with_seed(
+        (&proof.com_c, &IdentityOutput(vec![proof.agg_c])),
+        &r,
+        &proof.tipa_proof_c,
+        &seed,
+    )?;
+    Ok((vec![ck_a_opening, ck_c_opening], vec![ck_b_opening]))
+}
+
+// Verifies an aggregate proof against the single public input commitment `input_com`, needing
+// only the verifier key of the public input SRS
+pub fn verify_aggregate_proof_with_input_commitment<P, D>(
+    ip_verifier_srs: &VerifierSRS<P>,
+    vk: &VerifyingKey<P>,
+    input_verifier_srs: &VerifierSRS<P>,
+    input_com: &ExtensionFieldElement<P>,
+    proof: &AggregateProofWithInputCommitment<P, D>,
+) -> Result<bool, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    let aggregate_proof = &proof.aggregate_proof;
+    if proof.agg_inputs.len() + 1 != vk.gamma_abc_g1.len() || !aggregate_proof.metadata.is_empty() {
+        return Ok(false);
+    }
+
+    let r = compute_aggregation_challenge::<P, D>(
         (
-            &proof.com_a,
-            &proof.com_b,
-            &IdentityOutput(vec![proof.ip_ab.clone()]),
+            &aggregate_proof.com_a,
+            &aggregate_proof.com_b,
+            &aggregate_proof.com_c,
         ),
-        &proof.tipa_proof_ab,
-        &r,
+        aggregate_proof.num_proofs(),
+        &verifying_key_digest::<P, D>(vk)?,
+        Some(input_com),
+        &aggregate_proof.metadata,
+        None,
     )?;
-    let tipa_proof_c_valid = MultiExpInnerProductC::<P, D>::verify_with_structured_scalar_message(
+
+    // Check aggregated public inputs against the input commitment: at (s, r) the committed
+    // polynomial evaluates to the aggregated inputs combined with the powers of s
+    let s = compute_input_opening_challenge::<P, D>(&r, &proof.agg_inputs)?;
+    let s_vec = structured_scalar_power(proof.agg_inputs.len(), &s);
+    let eval = ScalarInnerProduct::inner_product(&proof.agg_inputs, &s_vec)?;
+    let inputs_valid = BivariatePolynomialCommitment::<P, D>::verify(
+        input_verifier_srs,
+        input_com,
+        &(s, r),
+        &eval,
+        &proof.input_proof,
+    )?;
+
+    let aggregate_valid = _verify_aggregate_proof(
         ip_verifier_srs,
-        &HomomorphicPlaceholderValue,
-        (&proof.com_c, &IdentityOutput(vec![proof.agg_c.clone()])),
+        vk,
+        aggregate_proof.num_proofs(),
+        &proof.agg_inputs,
+        aggregate_proof,
         &r,
-        &proof.tipa_proof_c,
+        &<P::ScalarField>::default(),
     )?;
 
+    Ok(inputs_valid && aggregate_valid)
+}
+
+// Verifies the aggregate proof given the public inputs of each position already combined with
+// powers of the challenge `r`
+fn _verify_aggregate_proof<P, D>(
+    ip_verifier_srs: &VerifierSRS<P>,
+    vk: &VerifyingKey<P>,
+    num_proofs: usize,
+    agg_inputs: &[P::ScalarField],
+    proof: &AggregateProof<P, D>,
+    r: &P::ScalarField,
+    seed: &P::ScalarField,
+) -> Result<bool, Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    let _verify = phase_span!("verify_aggregate_proof", num_proofs = num_proofs);
+    let (valid, pairing_checks) = _verify_aggregate_proof_deferred(
+        ip_verifier_srs,
+        vk,
+        num_proofs,
+        agg_inputs,
+        proof,
+        r,
+        seed,
+    )?;
+    Ok(valid && pairing_checks.iter().all(|check| check.verify()))
+}
+
+fn _verify_aggregate_proof_deferred<P, D>(
+    ip_verifier_srs: &VerifierSRS<P>,
+    vk: &VerifyingKey<P>,
+    num_proofs: usize,
+    agg_inputs: &[P::ScalarField],
+    proof: &AggregateProof<P, D>,
+    r: &P::ScalarField,
+    seed: &P::ScalarField,
+) -> Result<(bool, Vec<PairingCheck<P>>), Error>
+where
+    P: Pairing,
+    D: TranscriptDigest,
+{
+    // Check proof metadata
+    if proof.validate_shape().is_err()
+        || proof.num_proofs() != num_proofs
+        || proof.vk_digest != verifying_key_digest::<P, D>(vk)?
+    {
+        return Ok((false, vec![]));
+    }
+
+    // Check TIPA proofs
+    let (ab_base, mut pairing_checks) =
+        PairingInnerProductAB::<P, D>::verify_with_srs_shift_base_deferred(
+            ip_verifier_srs,
+            (
+                &proof.com_a,
+                &proof.com_b,
+                &IdentityOutput(vec![proof.ip_ab.clone()]),
+            ),
+            &proof.tipa_proof_ab,
+            r,
+            seed,
+        )?;
+    let (c_base, tipa_proof_c_checks) =
+        MultiExpInnerProductC::<P, D>::verify_with_structured_scalar_message_base_deferred(
+            ip_verifier_srs,
+            (&proof.com_c, &IdentityOutput(vec![proof.agg_c])),
+            r,
+            &proof.tipa_proof_c,
+            seed,
+        )?;
+    pairing_checks.extend(tipa_proof_c_checks);
+
+    // Base commitments are pairings of the base messages and keys, also returned as checks
+    let (com_a_base, com_b_base, com_t_base) = &ab_base.com;
+    let (a_base, b_base) = &ab_base.m;
+    let ip_ab_base = match com_t_base.0.as_slice() {
+        [ip_ab_base] => ip_ab_base,
+        _ => return Ok((false, vec![])),
+    };
+    pairing_checks.extend(vec![
+        PairingCheck {
+            pairs: vec![(*a_base, ab_base.ck.0)],
+            target: com_a_base.0,
+        },
+        PairingCheck {
+            pairs: vec![(ab_base.ck.1, *b_base)],
+            target: com_b_base.0,
+        },
+        PairingCheck {
+            pairs: vec![(*a_base, *b_base)],
+            target: ip_ab_base.0,
+        },
+        PairingCheck {
+            pairs: vec![(c_base.m.0, c_base.ck)],
+            target: c_base.com.0 .0,
+        },
+    ]);
+    let agg_c_base =
+        MultiexponentiationInnerProduct::<P::G1>::inner_product(&[c_base.m.0], &[c_base.m.1])?;
+    let tipa_proof_c_valid = c_base.scalar_valid() && c_base.com.1 .0 == [agg_c_base];
+
     // Check aggregate pairing product equation
 
     let r_sum =
-        (r.pow(&[public_inputs.len() as u64]) - &<P::Fr>::one()) / &(r.clone() - &<P::Fr>::one());
-    let p1 = P::pairing(vk.alpha_g1.into_projective().mul(&r_sum), vk.beta_g2);
-
-    assert_eq!(vk.gamma_abc_g1.len(), public_inputs[0].len() + 1);
-    let r_vec = structured_scalar_power(public_inputs.len(), &r);
-    let mut g_ic = vk.gamma_abc_g1[0].into_projective().mul(&r_sum);
-    for (i, b) in vk.gamma_abc_g1.iter().skip(1).enumerate() {
-        g_ic.add_assign(
-            &b.into_projective().mul(&ScalarInnerProduct::inner_product(
-                &public_inputs
-                    .iter()
-                    .map(|inputs| inputs[i].clone())
-                    .collect::<Vec<P::Fr>>(),
-                &r_vec,
-            )?),
+        (r.pow([num_proofs as u64]) - <P::ScalarField>::one()) / (*r - <P::ScalarField>::one());
+
+    let g_ic = public_input_msm(vk, agg_inputs, &r_sum)?;
+    pairing_checks.push(PairingCheck {
+        pairs: vec![
+            (vk.alpha_g1 * r_sum, vk.beta_g2.into_group()),
+            (g_ic, vk.gamma_g2.into_group()),
+            (proof.agg_c, vk.delta_g2.into_group()),
+        ],
+        target: proof.ip_ab.0,
+    });
+
+    Ok((tipa_proof_c_valid, pairing_checks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tipa::{verify_commitment_key_g1_kzg_opening, verify_commitment_key_g2_kzg_opening};
+    use crate::{sponge::AlgebraicSponge, validation::TrustedInput};
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use ark_ff::UniformRand;
+    use ark_groth16::Groth16;
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+    };
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const NUM_PUBLIC_INPUTS: usize = 4;
+    const NUM_PROOFS_TO_AGGREGATE: usize = 8;
+
+    // Proves knowledge of a witness equal to the sum of the public inputs
+    #[derive(Clone)]
+    struct TestCircuit {
+        public_inputs: Vec<Fr>,
+        witness_input: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for TestCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let witness_input = self.witness_input;
+            let mut sum = lc!();
+            for x in self.public_inputs {
+                sum = sum + cs.new_input_variable(|| Ok(x))?;
+            }
+            let witness = cs.new_witness_variable(|| Ok(witness_input))?;
+            cs.enforce_constraint(lc!() + witness, lc!() + Variable::One, sum)?;
+            Ok(())
+        }
+    }
+
+    // Stand-in for a real algebraic sponge such as Poseidon, not secure
+    #[derive(Clone, Default)]
+    struct TestSponge {
+        state: Fr,
+    }
+
+    impl AlgebraicSponge<Fr> for TestSponge {
+        fn absorb(&mut self, input: &[Fr]) {
+            for x in input {
+                self.state = (self.state + x).pow([5u64]) + Fr::one();
+            }
+        }
+
+        fn squeeze(&mut self, num: usize) -> Vec<Fr> {
+            (0..num)
+                .map(|_| {
+                    self.state = self.state.pow([5u64]) + Fr::one();
+                    self.state
+                })
+                .collect()
+        }
+    }
+
+    fn generate_proofs(
+        rng: &mut StdRng,
+    ) -> (VerifyingKey<Bls12_381>, Vec<Proof<Bls12_381>>, Vec<Vec<Fr>>) {
+        let parameters = Groth16::<Bls12_381>::generate_random_parameters_with_reduction(
+            TestCircuit {
+                public_inputs: vec![Fr::default(); NUM_PUBLIC_INPUTS],
+                witness_input: Fr::default(),
+            },
+            rng,
+        )
+        .unwrap();
+
+        let mut proofs = Vec::new();
+        let mut statements = Vec::new();
+        for _ in 0..NUM_PROOFS_TO_AGGREGATE {
+            let public_inputs = (0..NUM_PUBLIC_INPUTS)
+                .map(|_| Fr::rand(rng))
+                .collect::<Vec<Fr>>();
+            let circuit = TestCircuit {
+                public_inputs: public_inputs.clone(),
+                witness_input: public_inputs.iter().sum(),
+            };
+            proofs.push(
+                Groth16::<Bls12_381>::create_random_proof_with_reduction(circuit, &parameters, rng)
+                    .unwrap(),
+            );
+            statements.push(public_inputs);
+        }
+        (parameters.vk, proofs, statements)
+    }
+
+    #[test]
+    fn aggregate_proof_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (vk, proofs, statements) = generate_proofs(&mut rng);
+        let srs =
+            setup_inner_product::<Bls12_381, Blake2b512, _>(&mut rng, NUM_PROOFS_TO_AGGREGATE)
+                .unwrap();
+        let v_srs = srs.get_verifier_key();
+
+        let aggregate_proof =
+            aggregate_proofs::<Bls12_381, Blake2b512>(&srs, &vk, &proofs).unwrap();
+        assert!(verify_aggregate_proof(&v_srs, &vk, &statements, &aggregate_proof).unwrap());
+
+        let mut wrong_statements = statements.clone();
+        wrong_statements[0][0] += &Fr::one();
+        assert!(!verify_aggregate_proof(&v_srs, &vk, &wrong_statements, &aggregate_proof).unwrap());
+    }
+
+    #[test]
+    fn aggregation_inputs_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (vk, proofs, statements) = generate_proofs(&mut rng);
+        let inputs = AggregationInputs::validated(&proofs).unwrap();
+        assert_eq!(inputs.len(), NUM_PROOFS_TO_AGGREGATE);
+        assert_eq!(inputs.b[2], proofs[2].b.into_group());
+
+        // The Groth16 equation of each proof from the helpers, with a single statement and r^0
+        for (i, statement) in statements.iter().enumerate() {
+            let agg_inputs = aggregate_public_inputs(
+                &vk,
+                ark_std::slice::from_ref(statement),
+                &Fr::rand(&mut rng),
+            )
+            .unwrap();
+            let g_ic = public_input_msm(&vk, &agg_inputs, &Fr::one()).unwrap();
+            assert_eq!(
+                Bls12_381::pairing(inputs.a[i], inputs.b[i]).0,
+                Bls12_381::pairing(vk.alpha_g1, vk.beta_g2).0
+                    * Bls12_381::pairing(g_ic, vk.gamma_g2).0
+                    * Bls12_381::pairing(inputs.c[i], vk.delta_g2).0
+            );
+        }
+
+        // A^r under the rescaled key commits to the same value as A
+        let srs =
+            setup_inner_product::<Bls12_381, Blake2b512, _>(&mut rng, NUM_PROOFS_TO_AGGREGATE)
+                .unwrap();
+        let (ck_1, _) = srs.get_commitment_keys();
+        let r_vec = structured_scalar_power(NUM_PROOFS_TO_AGGREGATE, &Fr::rand(&mut rng));
+        assert_eq!(
+            PairingInnerProduct::<Bls12_381>::inner_product(
+                &inputs.a_r(&r_vec).unwrap(),
+                &rescale_key(&ck_1, &r_vec).unwrap()
+            )
+            .unwrap(),
+            PairingInnerProduct::<Bls12_381>::inner_product(&inputs.a, &ck_1).unwrap()
+        );
+
+        // Malformed inputs are errors rather than panics
+        assert!(AggregationInputs::new(&proofs[..3]).is_err());
+        assert!(inputs.a_r(&r_vec[..4]).is_err());
+        let mut short_statements = statements.clone();
+        short_statements[5].pop();
+        assert!(aggregate_public_inputs(&vk, &short_statements, &Fr::one()).is_err());
+        assert!(public_input_msm(&vk, &[Fr::one()], &Fr::one()).is_err());
+    }
+
+    #[test]
+    fn aggregate_proofs_batch_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (vk, proofs, statements) = generate_proofs(&mut rng);
+        let srs =
+            setup_inner_product::<Bls12_381, Blake2b512, _>(&mut rng, NUM_PROOFS_TO_AGGREGATE)
+                .unwrap();
+        let v_srs = srs.get_verifier_key();
+
+        let aggregate_proof =
+            aggregate_proofs::<Bls12_381, Blake2b512>(&srs, &vk, &proofs).unwrap();
+        let reversed_proofs = proofs.iter().rev().cloned().collect::<Vec<_>>();
+        let reversed_statements = statements.iter().rev().cloned().collect::<Vec<_>>();
+        let reversed_aggregate_proof =
+            aggregate_proofs::<Bls12_381, Blake2b512>(&srs, &vk, &reversed_proofs).unwrap();
+        assert!(verify_aggregate_proofs_batch(
+            &v_srs,
+            &vk,
+            &[
+                (&statements, &[], &aggregate_proof),
+                (&reversed_statements, &[], &reversed_aggregate_proof)
+            ],
+            &mut rng
+        )
+        .unwrap());
+
+        let mut wrong_statements = statements.clone();
+        wrong_statements[0][0] += &Fr::one();
+        assert!(!verify_aggregate_proofs_batch(
+            &v_srs,
+            &vk,
+            &[
+                (&wrong_statements, &[], &aggregate_proof),
+                (&reversed_statements, &[], &reversed_aggregate_proof)
+            ],
+            &mut rng
+        )
+        .unwrap());
+        // Proofs checked against each other's statements
+        assert!(!verify_aggregate_proofs_batch(
+            &v_srs,
+            &vk,
+            &[
+                (&reversed_statements, &[], &aggregate_proof),
+                (&statements, &[], &reversed_aggregate_proof)
+            ],
+            &mut rng
+        )
+        .unwrap());
+
+        // Proofs carrying metadata are checked against the metadata of their instance
+        let tagged_proof = aggregate_proofs_with_metadata::<Bls12_381, Blake2b512>(
+            &srs,
+            &vk,
+            &reversed_proofs,
+            b"batch 7",
+        )
+        .unwrap();
+        let verify = |metadata: &[u8]| {
+            verify_aggregate_proofs_batch(
+                &v_srs,
+                &vk,
+                &[
+                    (&statements, &[], &aggregate_proof),
+                    (&reversed_statements, metadata, &tagged_proof),
+                ],
+                &mut StdRng::seed_from_u64(1u64),
+            )
+            .unwrap()
+        };
+        assert!(verify(b"batch 7"));
+        assert!(!verify(b"batch 8"));
+        assert!(!verify(&[]));
+    }
+
+    #[test]
+    fn aggregate_proof_serialization_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (vk, proofs, statements) = generate_proofs(&mut rng);
+        let srs =
+            setup_inner_product::<Bls12_381, Blake2b512, _>(&mut rng, NUM_PROOFS_TO_AGGREGATE)
+                .unwrap();
+        let v_srs = srs.get_verifier_key();
+        let aggregate_proof =
+            aggregate_proofs::<Bls12_381, Blake2b512>(&srs, &vk, &proofs).unwrap();
+
+        let mut bytes = Vec::new();
+        aggregate_proof.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), aggregate_proof.compressed_size());
+        let deserialized =
+            AggregateProof::<Bls12_381, Blake2b512>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(deserialized.num_proofs(), NUM_PROOFS_TO_AGGREGATE);
+        assert!(verify_aggregate_proof(&v_srs, &vk, &statements, &deserialized).unwrap());
+        let validated =
+            TrustedInput::<AggregateProof<Bls12_381, Blake2b512>>::deserialize(&bytes[..]).unwrap();
+        assert!(verify_aggregate_proof(&v_srs, &vk, &statements, &validated).unwrap());
+
+        // Metadata must match the proof shape and the verifying key
+        let mut wrong_bytes = bytes.clone();
+        wrong_bytes[0] = 4;
+        assert!(
+            AggregateProof::<Bls12_381, Blake2b512>::deserialize_compressed(&wrong_bytes[..])
+                .is_err()
+        );
+        // The digest of the verifying key is read only at the output size of the digest
+        for len in [u64::MAX, 63, 65] {
+            let mut wrong_bytes = bytes.clone();
+            wrong_bytes[8..16].copy_from_slice(&len.to_le_bytes());
+            assert!(
+                AggregateProof::<Bls12_381, Blake2b512>::deserialize_compressed(&wrong_bytes[..])
+                    .is_err()
+            );
+        }
+        assert!(!verify_aggregate_proof(&v_srs, &vk, &statements[..4], &deserialized).unwrap());
+        let mut wrong_vk = vk.clone();
+        wrong_vk.alpha_g1 = wrong_vk.gamma_abc_g1[0];
+        assert!(!verify_aggregate_proof(&v_srs, &wrong_vk, &statements, &deserialized).unwrap());
+    }
+
+    #[test]
+    fn aggregate_proof_metadata_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (vk, proofs, statements) = generate_proofs(&mut rng);
+        let srs =
+            setup_inner_product::<Bls12_381, Blake2b512, _>(&mut rng, NUM_PROOFS_TO_AGGREGATE)
+                .unwrap();
+        let v_srs = srs.get_verifier_key();
+        let metadata = b"batch 7";
+
+        let aggregate_proof =
+            aggregate_proofs_with_metadata::<Bls12_381, Blake2b512>(&srs, &vk, &proofs, metadata)
+                .unwrap();
+        assert_eq!(aggregate_proof.metadata(), metadata);
+        assert!(verify_aggregate_proof_with_metadata(
+            &v_srs,
+            &vk,
+            &statements,
+            metadata,
+            &aggregate_proof
+        )
+        .unwrap());
+        assert!(!verify_aggregate_proof_with_metadata(
+            &v_srs,
+            &vk,
+            &statements,
+            b"batch 8",
+            &aggregate_proof
+        )
+        .unwrap());
+
+        // The metadata survives serialization and cannot be swapped for another batch
+        let mut bytes = Vec::new();
+        aggregate_proof.serialize_compressed(&mut bytes).unwrap();
+        let deserialized =
+            AggregateProof::<Bls12_381, Blake2b512>::deserialize_compressed(&bytes[..]).unwrap();
+        assert!(verify_aggregate_proof_with_metadata(
+            &v_srs,
+            &vk,
+            &statements,
+            metadata,
+            &deserialized
+        )
+        .unwrap());
+        let offset = aggregate_proof.num_proofs.compressed_size()
+            + aggregate_proof.vk_digest.compressed_size()
+            + 8;
+        bytes[offset + metadata.len() - 1] = b'8';
+        let replayed =
+            AggregateProof::<Bls12_381, Blake2b512>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(replayed.metadata(), b"batch 8");
+        assert!(!verify_aggregate_proof_with_metadata(
+            &v_srs,
+            &vk,
+            &statements,
+            b"batch 8",
+            &replayed
+        )
+        .unwrap());
+
+        // The verifiers without expected metadata reject proofs that carry some
+        assert!(!verify_aggregate_proof(&v_srs, &vk, &statements, &aggregate_proof).unwrap());
+        let (valid, pairing_checks) =
+            verify_aggregate_proof_deferred(&v_srs, &vk, &statements, &[], &aggregate_proof)
+                .unwrap();
+        assert!(!valid && pairing_checks.is_empty());
+        let (valid, pairing_checks) =
+            verify_aggregate_proof_deferred(&v_srs, &vk, &statements, metadata, &aggregate_proof)
+                .unwrap();
+        assert!(valid && pairing_checks.iter().all(|check| check.verify()));
+
+        // Proofs aggregated without metadata verify for empty metadata
+        let plain = aggregate_proofs::<Bls12_381, Blake2b512>(&srs, &vk, &proofs).unwrap();
+        assert!(plain.metadata().is_empty());
+        assert!(
+            verify_aggregate_proof_with_metadata(&v_srs, &vk, &statements, &[], &plain).unwrap()
+        );
+    }
+
+    #[test]
+    fn aggregate_proof_session_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (vk, proofs, statements) = generate_proofs(&mut rng);
+        let srs =
+            setup_inner_product::<Bls12_381, Blake2b512, _>(&mut rng, NUM_PROOFS_TO_AGGREGATE)
+                .unwrap();
+        let v_srs = srs.get_verifier_key();
+
+        let aggregate_proof =
+            aggregate_proofs_with_session::<Bls12_381, Blake2b512>(&srs, &vk, &proofs, b"tenant-a")
+                .unwrap();
+        let verify = |id: &[u8], proof: &AggregateProof<Bls12_381, Blake2b512>| {
+            verify_aggregate_proof_with_session(&v_srs, &vk, &statements, id, proof).unwrap()
+        };
+        assert!(verify(b"tenant-a", &aggregate_proof));
+        // Replayed in another session, or outside of any
+        assert!(!verify(b"tenant-b", &aggregate_proof));
+        assert!(!verify(b"", &aggregate_proof));
+        assert!(!verify_aggregate_proof(&v_srs, &vk, &statements, &aggregate_proof).unwrap());
+
+        // Nor do proofs aggregated outside of a session verify in one
+        let plain = aggregate_proofs::<Bls12_381, Blake2b512>(&srs, &vk, &proofs).unwrap();
+        assert!(!verify(b"tenant-a", &plain));
+
+        // Key openings are taken at the challenges of the session
+        let openings_valid = |(g2_openings, g1_openings): (
+            Vec<CommitmentKeyOpening<G2Projective>>,
+            Vec<CommitmentKeyOpening<G1Projective>>,
+        )| {
+            g2_openings.iter().all(|o| {
+                verify_commitment_key_g2_kzg_opening(
+                    &v_srs,
+                    &o.ck_final,
+                    &o.ck_opening,
+                    &o.transcript,
+                    &o.shift,
+                    &o.kzg_challenge,
+                )
+                .unwrap()
+            }) && g1_openings.iter().all(|o| {
+                verify_commitment_key_g1_kzg_opening(
+                    &v_srs,
+                    &o.ck_final,
+                    &o.ck_opening,
+                    &o.transcript,
+                    &o.shift,
+                    &o.kzg_challenge,
+                )
+                .unwrap()
+            })
+        };
+        assert!(openings_valid(
+            aggregate_proof_commitment_key_openings_with_session(&aggregate_proof, b"tenant-a")
+                .unwrap()
+        ));
+        assert!(!openings_valid(
+            aggregate_proof_commitment_key_openings_with_session(&aggregate_proof, b"tenant-b")
+                .unwrap()
+        ));
+        assert!(!openings_valid(
+            aggregate_proof_commitment_key_openings(&aggregate_proof).unwrap()
+        ));
+        assert!(openings_valid(
+            aggregate_proof_commitment_key_openings(&plain).unwrap()
+        ));
+    }
+
+    #[test]
+    fn resume_aggregate_proofs_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (vk, proofs, statements) = generate_proofs(&mut rng);
+        let srs =
+            setup_inner_product::<Bls12_381, Blake2b512, _>(&mut rng, NUM_PROOFS_TO_AGGREGATE)
+                .unwrap();
+        let v_srs = srs.get_verifier_key();
+
+        let mut checkpoints = Vec::new();
+        let aggregate_proof = aggregate_proofs_with_checkpoints::<Bls12_381, Blake2b512, Error>(
+            &srs,
+            &vk,
+            &proofs,
+            |c| {
+                let mut bytes = Vec::new();
+                c.serialize_compressed(&mut bytes)?;
+                assert_eq!(bytes.len(), c.compressed_size());
+                checkpoints.push(bytes);
+                Ok(())
+            },
+        )
+        .unwrap();
+        // After the commitments, after each round for A^r and B, on to C, and after each round for C
+        assert_eq!(checkpoints.len(), 8);
+        let mut proof_bytes = Vec::new();
+        aggregate_proof
+            .serialize_compressed(&mut proof_bytes)
+            .unwrap();
+        assert_eq!(proof_bytes, {
+            let mut bytes = Vec::new();
+            aggregate_proofs::<Bls12_381, Blake2b512>(&srs, &vk, &proofs)
+                .unwrap()
+                .serialize_compressed(&mut bytes)
+                .unwrap();
+            bytes
+        });
+        assert!(verify_aggregate_proof(&v_srs, &vk, &statements, &aggregate_proof).unwrap());
+
+        for (i, bytes) in checkpoints.iter().enumerate() {
+            let checkpoint =
+                AggregationCheckpoint::<Bls12_381, Blake2b512>::deserialize_compressed(&bytes[..])
+                    .unwrap();
+            assert_eq!(checkpoint.num_proofs(), NUM_PROOFS_TO_AGGREGATE);
+            assert_eq!(checkpoint.rounds(), if i < 4 { i } else { i - 1 });
+            let resumed = resume_aggregate_proofs::<Bls12_381, Blake2b512, Error>(
+                &srs,
+                &vk,
+                &proofs,
+                checkpoint,
+                |_| Ok(()),
+            )
+            .unwrap();
+            let mut resumed_bytes = Vec::new();
+            resumed.serialize_compressed(&mut resumed_bytes).unwrap();
+            assert_eq!(resumed_bytes, proof_bytes);
+        }
+
+        let mut wrong_bytes = checkpoints[2].clone();
+        wrong_bytes[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(
+            AggregationCheckpoint::<Bls12_381, Blake2b512>::deserialize_compressed(
+                &wrong_bytes[..]
+            )
+            .is_err()
         );
+
+        // Checkpoints only resume for the proofs and verifying key they were saved for
+        let checkpoint = AggregationCheckpoint::<Bls12_381, Blake2b512>::deserialize_compressed(
+            &checkpoints[2][..],
+        )
+        .unwrap();
+        let mut wrong_vk = vk.clone();
+        wrong_vk.alpha_g1 = wrong_vk.gamma_abc_g1[0];
+        assert!(matches!(
+            resume_aggregate_proofs::<Bls12_381, Blake2b512, Error>(
+                &srs,
+                &wrong_vk,
+                &proofs,
+                checkpoint,
+                |_| Ok(())
+            ),
+            Err(InnerProductArgumentError::CheckpointMismatch)
+        ));
+        let checkpoint = AggregationCheckpoint::<Bls12_381, Blake2b512>::deserialize_compressed(
+            &checkpoints[2][..],
+        )
+        .unwrap();
+        assert!(resume_aggregate_proofs::<Bls12_381, Blake2b512, Error>(
+            &srs,
+            &vk,
+            &proofs[..4],
+            checkpoint,
+            |_| Ok(())
+        )
+        .is_err());
     }
-    let p2 = P::pairing(g_ic, vk.gamma_g2);
-    let p3 = P::pairing(proof.agg_c, vk.delta_g2);
 
-    let ppe_valid = proof.ip_ab.0 == (p1 * &p2) * &p3;
+    #[test]
+    fn aggregate_proof_with_input_commitment_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (vk, proofs, statements) = generate_proofs(&mut rng);
+        let srs =
+            setup_inner_product::<Bls12_381, Blake2b512, _>(&mut rng, NUM_PROOFS_TO_AGGREGATE)
+                .unwrap();
+        let v_srs = srs.get_verifier_key();
+        let input_srs = setup_public_input_commitment::<Bls12_381, Blake2b512, _>(
+            &mut rng,
+            NUM_PROOFS_TO_AGGREGATE,
+            statements[0].len(),
+        )
+        .unwrap();
+        let input_v_srs = input_srs.0.get_verifier_key();
+
+        let aggregate_proof = aggregate_proofs_with_input_commitment::<Bls12_381, Blake2b512>(
+            &srs,
+            &vk,
+            &input_srs,
+            &proofs,
+            &statements,
+        )
+        .unwrap();
+        let input_com =
+            commit_public_inputs::<Bls12_381, Blake2b512>(&input_srs, &statements).unwrap();
+        assert!(verify_aggregate_proof_with_input_commitment(
+            &v_srs,
+            &vk,
+            &input_v_srs,
+            &input_com,
+            &aggregate_proof
+        )
+        .unwrap());
 
-    Ok(tipa_proof_ab_valid && tipa_proof_c_valid && ppe_valid)
+        let mut bytes = Vec::new();
+        aggregate_proof.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), aggregate_proof.compressed_size());
+        let aggregate_proof =
+            AggregateProofWithInputCommitment::<Bls12_381, Blake2b512>::deserialize_compressed(
+                &bytes[..],
+            )
+            .unwrap();
+        aggregate_proof.validate().unwrap();
+        assert!(verify_aggregate_proof_with_input_commitment(
+            &v_srs,
+            &vk,
+            &input_v_srs,
+            &input_com,
+            &aggregate_proof
+        )
+        .unwrap());
+
+        let mut wrong_statements = statements.clone();
+        wrong_statements[0][0] += &Fr::one();
+        let wrong_input_com =
+            commit_public_inputs::<Bls12_381, Blake2b512>(&input_srs, &wrong_statements).unwrap();
+        assert!(!verify_aggregate_proof_with_input_commitment(
+            &v_srs,
+            &vk,
+            &input_v_srs,
+            &wrong_input_com,
+            &aggregate_proof
+        )
+        .unwrap());
+
+        // Ragged or oversized inputs are errors rather than panics
+        let mut ragged_statements = statements.clone();
+        ragged_statements[3].pop();
+        assert!(
+            commit_public_inputs::<Bls12_381, Blake2b512>(&input_srs, &ragged_statements).is_err()
+        );
+        assert!(
+            aggregate_proofs_with_input_commitment::<Bls12_381, Blake2b512>(
+                &srs,
+                &vk,
+                &input_srs,
+                &proofs,
+                &ragged_statements,
+            )
+            .is_err()
+        );
+        let mut long_statements = statements.clone();
+        long_statements.push(statements[0].clone());
+        assert!(
+            commit_public_inputs::<Bls12_381, Blake2b512>(&input_srs, &long_statements).is_err()
+        );
+    }
+
+    #[test]
+    fn sponge_aggregate_proof_deferred_test() {
+        type TestSpongeDigest = SpongeDigest<Fr, TestSponge>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (vk, proofs, statements) = generate_proofs(&mut rng);
+        let srs = setup_inner_product::<Bls12_381, TestSpongeDigest, _>(
+            &mut rng,
+            NUM_PROOFS_TO_AGGREGATE,
+        )
+        .unwrap();
+        let v_srs = srs.get_verifier_key();
+
+        let aggregate_proof: SpongeAggregateProof<Bls12_381, TestSponge> =
+            aggregate_proofs(&srs, &vk, &proofs).unwrap();
+        let (valid, pairing_checks) =
+            verify_aggregate_proof_deferred(&v_srs, &vk, &statements, &[], &aggregate_proof)
+                .unwrap();
+        assert!(valid);
+        // Commitment key openings, the aggregate equation and the four base commitments
+        assert_eq!(pairing_checks.len(), 8);
+        assert!(pairing_checks.iter().all(|check| check.verify()));
+
+        let mut wrong_statements = statements.clone();
+        wrong_statements[0][0] += &Fr::one();
+        let (valid, pairing_checks) =
+            verify_aggregate_proof_deferred(&v_srs, &vk, &wrong_statements, &[], &aggregate_proof)
+                .unwrap();
+        assert!(!valid || !pairing_checks.iter().all(|check| check.verify()));
+
+        // A wrong base message is only caught by the returned pairing checks
+        let mut wrong_base: SpongeAggregateProof<Bls12_381, TestSponge> =
+            aggregate_proofs(&srs, &vk, &proofs).unwrap();
+        wrong_base.tipa_proof_ab.gipa_proof.r_base.0 += &G1Projective::rand(&mut rng);
+        let (valid, pairing_checks) =
+            verify_aggregate_proof_deferred(&v_srs, &vk, &statements, &[], &wrong_base).unwrap();
+        assert!(valid);
+        assert!(!pairing_checks.iter().all(|check| check.verify()));
+
+        // The folded scalar sent in the proof is checked, so that proofs are not malleable
+        let mut wrong_scalar: SpongeAggregateProof<Bls12_381, TestSponge> =
+            aggregate_proofs(&srs, &vk, &proofs).unwrap();
+        wrong_scalar.tipa_proof_c.gipa_proof.r_base.1 += &Fr::one();
+        let (valid, _) =
+            verify_aggregate_proof_deferred(&v_srs, &vk, &statements, &[], &wrong_scalar).unwrap();
+        assert!(!valid);
+    }
 }
@@ -0,0 +1,380 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, One, Zero};
+use ark_std::{collections::BTreeMap, marker::PhantomData, vec, vec::Vec};
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    tipa::{
+        structured_scalar_message::{structured_scalar_power, TIPAWithSSM, TIPAWithSSMProof},
+        TIPAProof, VerifierSRS, SRS, TIPA,
+    },
+    transcript::{ChallengeInput, TranscriptDigest},
+    Error, InnerProductArgumentError,
+};
+use ark_dh_commitments::{
+    identity::{HomomorphicPlaceholderValue, IdentityCommitment, IdentityOutput},
+    pedersen::PedersenCommitment,
+    DoublyHomomorphicCommitment,
+};
+use ark_inner_products::{InnerProduct, ScalarInnerProduct};
+
+type LookupIPA<P, D> = TIPA<
+    ScalarInnerProduct<<P as Pairing>::ScalarField>,
+    PedersenCommitment<<P as Pairing>::G2>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    IdentityCommitment<<P as Pairing>::ScalarField, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+type LookupIPAProof<P, D> = TIPAProof<
+    ScalarInnerProduct<<P as Pairing>::ScalarField>,
+    PedersenCommitment<<P as Pairing>::G2>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    IdentityCommitment<<P as Pairing>::ScalarField, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+type LookupSumIPA<P, D> = TIPAWithSSM<
+    ScalarInnerProduct<<P as Pairing>::ScalarField>,
+    PedersenCommitment<<P as Pairing>::G2>,
+    IdentityCommitment<<P as Pairing>::ScalarField, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+type LookupSumIPAProof<P, D> = TIPAWithSSMProof<
+    ScalarInnerProduct<<P as Pairing>::ScalarField>,
+    PedersenCommitment<<P as Pairing>::G2>,
+    IdentityCommitment<<P as Pairing>::ScalarField, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+// Log-derivative lookup: for challenge beta, with a_i = 1/(beta - f_i) and b_j = m_j/(beta - t_j),
+// values f are contained in table t iff sum a_i = sum b_j. Well-formedness of a and b is checked
+// against a random combination by powers of gamma, reducing every check to inner products
+pub struct LookupProof<P: Pairing, D: TranscriptDigest> {
+    com_m: P::G2,
+    com_a: P::G2,
+    com_b: P::G2,
+    sum: P::ScalarField,
+    ip_a_gamma: P::ScalarField,
+    ip_b_gamma: P::ScalarField,
+    ip_m_gamma: P::ScalarField,
+    ip_a_values: P::ScalarField,
+    ip_b_table: P::ScalarField,
+    sum_a_proof: LookupSumIPAProof<P, D>,
+    sum_b_proof: LookupSumIPAProof<P, D>,
+    a_gamma_proof: LookupSumIPAProof<P, D>,
+    b_gamma_proof: LookupSumIPAProof<P, D>,
+    m_gamma_proof: LookupSumIPAProof<P, D>,
+    a_values_proof: LookupIPAProof<P, D>,
+    b_table_proof: LookupIPAProof<P, D>,
+}
+
+pub struct LookupArgument<P: Pairing, D: TranscriptDigest> {
+    _pairing: PhantomData<P>,
+    _digest: PhantomData<D>,
+}
+
+impl<P: Pairing, D: TranscriptDigest> LookupArgument<P, D> {
+    // Table and lookup vectors must both have length `size`, a power of 2
+    pub fn setup<R: RngCore + CryptoRng>(rng: &mut R, size: usize) -> Result<SRS<P>, Error> {
+        let (srs, _) = LookupIPA::<P, D>::setup(rng, size)?;
+        Ok(srs)
+    }
+
+    pub fn commit_table(srs: &SRS<P>, table: &[P::ScalarField]) -> Result<P::G1, Error> {
+        let (_, ck_2) = srs.get_commitment_keys();
+        Ok(PedersenCommitment::<P::G1>::commit(&ck_2, table)?)
+    }
+
+    pub fn commit_values(srs: &SRS<P>, values: &[P::ScalarField]) -> Result<P::G1, Error> {
+        let (_, ck_2) = srs.get_commitment_keys();
+        Ok(PedersenCommitment::<P::G1>::commit(&ck_2, values)?)
+    }
+
+    pub fn prove(
+        srs: &SRS<P>,
+        table: &[P::ScalarField],
+        values: &[P::ScalarField],
+    ) -> Result<LookupProof<P, D>, Error> {
+        let (ck_1, ck_2) = srs.get_commitment_keys();
+        if table.len() != ck_2.len() || values.len() != ck_2.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                table.len(),
+                values.len(),
+            ));
+        }
+        let com_table = PedersenCommitment::<P::G1>::commit(&ck_2, table)?;
+        let com_values = PedersenCommitment::<P::G1>::commit(&ck_2, values)?;
+
+        // Multiplicities counted against the first occurrence of each table entry
+        let mut table_index = BTreeMap::new();
+        for (j, t) in table.iter().enumerate() {
+            table_index.entry(*t).or_insert(j);
+        }
+        let mut m = vec![P::ScalarField::zero(); table.len()];
+        for f in values.iter() {
+            match table_index.get(f) {
+                Some(j) => m[*j] += &P::ScalarField::one(),
+                None => return Err(InnerProductArgumentError::InnerProductInvalid),
+            }
+        }
+        let com_m = PedersenCommitment::<P::G2>::commit(&ck_1, &m)?;
+
+        let beta = Self::compute_challenge(&canonical_bytes![com_table, com_values, com_m]?)?;
+        let inverse_shifted = |v: &[P::ScalarField]| -> Result<Vec<P::ScalarField>, Error> {
+            v.iter()
+                .map(|x| {
+                    (beta - x)
+                        .inverse()
+                        .ok_or(InnerProductArgumentError::InnerProductInvalid)
+                })
+                .collect()
+        };
+        let a = inverse_shifted(values)?;
+        let b = inverse_shifted(table)?
+            .iter()
+            .zip(&m)
+            .map(|(inv, m)| *inv * m)
+            .collect::<Vec<P::ScalarField>>();
+        let com_a = PedersenCommitment::<P::G2>::commit(&ck_1, &a)?;
+        let com_b = PedersenCommitment::<P::G2>::commit(&ck_1, &b)?;
+
+        let gamma = Self::compute_challenge(&canonical_bytes![beta, com_a, com_b]?)?;
+        let ones = vec![P::ScalarField::one(); ck_1.len()];
+        let gamma_vec = structured_scalar_power(ck_1.len(), &gamma);
+        let a_gamma = Self::hadamard(&a, &gamma_vec);
+        let b_gamma = Self::hadamard(&b, &gamma_vec);
+
+        // Rescale commitment key by gamma inverse powers for the shifted inner products
+        let ck_1_gamma = ck_1
+            .iter()
+            .zip(&gamma_vec)
+            .map(|(ck, g)| *ck * g.inverse().unwrap())
+            .collect::<Vec<P::G2>>();
+
+        let prove_sum = |v: &[P::ScalarField], s: &[P::ScalarField]| {
+            LookupSumIPA::<P, D>::prove_with_structured_scalar_message(
+                srs,
+                (v, s),
+                (&ck_1, &HomomorphicPlaceholderValue),
+            )
+        };
+        let prove_shifted = |v: &[P::ScalarField], w: &[P::ScalarField]| {
+            LookupIPA::<P, D>::prove_with_srs_shift(
+                srs,
+                (v, w),
+                (&ck_1_gamma, &ck_2, &HomomorphicPlaceholderValue),
+                &gamma,
+            )
+        };
+
+        Ok(LookupProof {
+            com_m,
+            com_a,
+            com_b,
+            sum: a.iter().sum(),
+            ip_a_gamma: ScalarInnerProduct::inner_product(&a, &gamma_vec)?,
+            ip_b_gamma: ScalarInnerProduct::inner_product(&b, &gamma_vec)?,
+            ip_m_gamma: ScalarInnerProduct::inner_product(&m, &gamma_vec)?,
+            ip_a_values: ScalarInnerProduct::inner_product(&a_gamma, values)?,
+            ip_b_table: ScalarInnerProduct::inner_product(&b_gamma, table)?,
+            sum_a_proof: prove_sum(&a, &ones)?,
+            sum_b_proof: prove_sum(&b, &ones)?,
+            a_gamma_proof: prove_sum(&a, &gamma_vec)?,
+            b_gamma_proof: prove_sum(&b, &gamma_vec)?,
+            m_gamma_proof: prove_sum(&m, &gamma_vec)?,
+            a_values_proof: prove_shifted(&a_gamma, values)?,
+            b_table_proof: prove_shifted(&b_gamma, table)?,
+        })
+    }
+
+    // Verifies a lookup of `num_values` values into a table of `table_size` entries. Sub-proofs of
+    // any other size are rejected
+    pub fn verify(
+        v_srs: &VerifierSRS<P>,
+        table_size: usize,
+        num_values: usize,
+        com_table: &P::G1,
+        com_values: &P::G1,
+        proof: &LookupProof<P, D>,
+    ) -> Result<bool, Error> {
+        if table_size != num_values || !table_size.is_power_of_two() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                table_size, num_values,
+            ));
+        }
+        let num_rounds = table_size.trailing_zeros() as usize;
+        let rounds = [
+            proof.sum_a_proof.gipa_proof.r_commitment_steps.len(),
+            proof.sum_b_proof.gipa_proof.r_commitment_steps.len(),
+            proof.a_gamma_proof.gipa_proof.r_commitment_steps.len(),
+            proof.b_gamma_proof.gipa_proof.r_commitment_steps.len(),
+            proof.m_gamma_proof.gipa_proof.r_commitment_steps.len(),
+            proof.a_values_proof.gipa_proof.r_commitment_steps.len(),
+            proof.b_table_proof.gipa_proof.r_commitment_steps.len(),
+        ];
+        if rounds.iter().any(|rounds| *rounds != num_rounds) {
+            return Ok(false);
+        }
+
+        let beta = Self::compute_challenge(&canonical_bytes![com_table, com_values, proof.com_m]?)?;
+        let gamma = Self::compute_challenge(&canonical_bytes![beta, proof.com_a, proof.com_b]?)?;
+        let one = P::ScalarField::one();
+
+        let verify_sum = |com: &P::G2, s: &P::ScalarField, b: &P::ScalarField, ip_proof| {
+            LookupSumIPA::<P, D>::verify_with_structured_scalar_message(
+                v_srs,
+                &HomomorphicPlaceholderValue,
+                (com, &IdentityOutput(vec![*s])),
+                b,
+                ip_proof,
+            )
+        };
+        let verify_shifted = |com: (&P::G2, &P::G1), s: &P::ScalarField, ip_proof| {
+            LookupIPA::<P, D>::verify_with_srs_shift(
+                v_srs,
+                &HomomorphicPlaceholderValue,
+                (com.0, com.1, &IdentityOutput(vec![*s])),
+                ip_proof,
+                &gamma,
+            )
+        };
+
+        let ip_valid = verify_sum(&proof.com_a, &proof.sum, &one, &proof.sum_a_proof)?
+            && verify_sum(&proof.com_b, &proof.sum, &one, &proof.sum_b_proof)?
+            && verify_sum(
+                &proof.com_a,
+                &proof.ip_a_gamma,
+                &gamma,
+                &proof.a_gamma_proof,
+            )?
+            && verify_sum(
+                &proof.com_b,
+                &proof.ip_b_gamma,
+                &gamma,
+                &proof.b_gamma_proof,
+            )?
+            && verify_sum(
+                &proof.com_m,
+                &proof.ip_m_gamma,
+                &gamma,
+                &proof.m_gamma_proof,
+            )?
+            && verify_shifted(
+                (&proof.com_a, com_values),
+                &proof.ip_a_values,
+                &proof.a_values_proof,
+            )?
+            && verify_shifted(
+                (&proof.com_b, com_table),
+                &proof.ip_b_table,
+                &proof.b_table_proof,
+            )?;
+
+        // Check a_i (beta - f_i) = 1 and b_j (beta - t_j) = m_j against powers of gamma
+        let gamma_sum = (gamma.pow([table_size as u64]) - one) / (gamma - one);
+        let a_valid = beta * proof.ip_a_gamma - proof.ip_a_values == gamma_sum;
+        let b_valid = beta * proof.ip_b_gamma - proof.ip_b_table == proof.ip_m_gamma;
+
+        Ok(ip_valid && a_valid && b_valid)
+    }
+
+    fn hadamard(a: &[P::ScalarField], b: &[P::ScalarField]) -> Vec<P::ScalarField> {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| *a * b)
+            .collect::<Vec<P::ScalarField>>()
+    }
+
+    fn compute_challenge(transcript: &[u8]) -> Result<P::ScalarField, Error> {
+        let mut input = D::input();
+        input.absorb(transcript)?;
+        let mut counter_nonce: usize = 0;
+        let c = loop {
+            if let Some(c) = input.challenge::<P::ScalarField>(counter_nonce) {
+                break c;
+            };
+            counter_nonce += 1;
+        };
+        Ok(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_ff::UniformRand;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type Fr = <Bls12_381 as Pairing>::ScalarField;
+    type TestLookup = LookupArgument<Bls12_381, Blake2b512>;
+
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn lookup_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let srs = TestLookup::setup(&mut rng, TEST_SIZE).unwrap();
+        let v_srs = srs.get_verifier_key();
+
+        let table = (0..TEST_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<Fr>>();
+        let values = vec![
+            table[1], table[5], table[5], table[0], table[7], table[1], table[1], table[2],
+        ];
+        let com_table = TestLookup::commit_table(&srs, &table).unwrap();
+        let com_values = TestLookup::commit_values(&srs, &values).unwrap();
+
+        let proof = TestLookup::prove(&srs, &table, &values).unwrap();
+        assert!(TestLookup::verify(
+            &v_srs,
+            TEST_SIZE,
+            TEST_SIZE,
+            &com_table,
+            &com_values,
+            &proof
+        )
+        .unwrap());
+        assert!(!TestLookup::verify(
+            &v_srs,
+            TEST_SIZE / 2,
+            TEST_SIZE / 2,
+            &com_table,
+            &com_values,
+            &proof
+        )
+        .unwrap());
+        assert!(TestLookup::verify(
+            &v_srs,
+            TEST_SIZE,
+            TEST_SIZE / 2,
+            &com_table,
+            &com_values,
+            &proof
+        )
+        .is_err());
+
+        let mut wrong_values = values.clone();
+        wrong_values[3] = Fr::rand(&mut rng);
+        let com_wrong_values = TestLookup::commit_values(&srs, &wrong_values).unwrap();
+        assert!(!TestLookup::verify(
+            &v_srs,
+            TEST_SIZE,
+            TEST_SIZE,
+            &com_table,
+            &com_wrong_values,
+            &proof
+        )
+        .unwrap());
+        assert!(TestLookup::prove(&srs, &table, &wrong_values).is_err());
+    }
+}
@@ -1,2 +1,9 @@
+pub mod accumulator;
+pub mod consistency;
+pub mod data_availability;
 pub mod groth16_aggregation;
+pub mod lookup;
 pub mod poly_commit;
+pub mod poly_eval;
+pub mod sumcheck;
+pub mod threshold_bls;
@@ -1,14 +1,16 @@
-use ark_ec::{msm::VariableBaseMSM, PairingEngine, ProjectiveCurve};
+use ark_ec::{pairing::Pairing, CurveGroup, Group, VariableBaseMSM};
 use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
 use ark_poly::polynomial::{
-    univariate::DensePolynomial as UnivariatePolynomial, Polynomial, UVPolynomial,
+    univariate::DensePolynomial as UnivariatePolynomial, DenseUVPolynomial, Polynomial,
 };
 
-use ark_std::{end_timer, start_timer};
-use std::marker::PhantomData;
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Write,
+};
+use ark_std::marker::PhantomData;
+use ark_std::{end_timer, start_timer, vec, vec::Vec};
 
-use digest::Digest;
-use rand::Rng;
+use rand::{CryptoRng, RngCore};
 
 use crate::{
     tipa::{
@@ -16,7 +18,9 @@ use crate::{
         structured_scalar_message::{TIPAWithSSM, TIPAWithSSMProof},
         VerifierSRS, SRS,
     },
-    Error,
+    transcript::TranscriptDigest,
+    validation::Validate,
+    Error, InnerProductArgumentError,
 };
 use ark_dh_commitments::{
     afgho16::AFGHOCommitmentG1,
@@ -28,96 +32,110 @@ use ark_inner_products::{ExtensionFieldElement, MultiexponentiationInnerProduct}
 pub mod transparent;
 
 type PolynomialEvaluationSecondTierIPA<P, D> = TIPAWithSSM<
-    MultiexponentiationInnerProduct<<P as PairingEngine>::G1Projective>,
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
     AFGHOCommitmentG1<P>,
-    IdentityCommitment<<P as PairingEngine>::G1Projective, <P as PairingEngine>::Fr>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
     P,
     D,
 >;
 
 type PolynomialEvaluationSecondTierIPAProof<P, D> = TIPAWithSSMProof<
-    MultiexponentiationInnerProduct<<P as PairingEngine>::G1Projective>,
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
     AFGHOCommitmentG1<P>,
-    IdentityCommitment<<P as PairingEngine>::G1Projective, <P as PairingEngine>::Fr>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
     P,
     D,
 >;
 
-pub struct KZG<P: PairingEngine> {
+pub struct KZG<P: Pairing> {
     _pairing: PhantomData<P>,
 }
 
 // Simple implementation of KZG polynomial commitment scheme
-impl<P: PairingEngine> KZG<P> {
-    pub fn setup<R: Rng>(
+impl<P: Pairing> KZG<P> {
+    pub fn setup<R: RngCore + CryptoRng>(
         rng: &mut R,
         degree: usize,
     ) -> Result<(Vec<P::G1Affine>, VerifierSRS<P>), Error> {
-        let alpha = <P::Fr>::rand(rng);
-        let beta = <P::Fr>::rand(rng);
-        let g = <P::G1Projective>::prime_subgroup_generator();
-        let h = <P::G2Projective>::prime_subgroup_generator();
+        let alpha = <P::ScalarField>::rand(rng);
+        let beta = <P::ScalarField>::rand(rng);
+        let g = <P::G1>::generator();
+        let h = <P::G2>::generator();
         let g_alpha_powers = structured_generators_scalar_power(degree + 1, &g, &alpha);
         Ok((
-            <P as PairingEngine>::G1Projective::batch_normalization_into_affine(&g_alpha_powers),
+            <P as Pairing>::G1::normalize_batch(&g_alpha_powers),
             VerifierSRS {
-                g: g.clone(),
-                h: h.clone(),
-                g_beta: g.mul(beta.into_repr()),
-                h_alpha: h.mul(alpha.into_repr()),
+                g,
+                h,
+                g_beta: g.mul_bigint(beta.into_bigint()),
+                h_alpha: h.mul_bigint(alpha.into_bigint()),
             },
         ))
     }
 
     pub fn commit(
         powers: &[P::G1Affine],
-        polynomial: &UnivariatePolynomial<P::Fr>,
-    ) -> Result<P::G1Projective, Error> {
-        assert!(powers.len() >= polynomial.degree() + 1);
+        polynomial: &UnivariatePolynomial<P::ScalarField>,
+    ) -> Result<P::G1, Error> {
+        Self::check_degree(powers, polynomial)?;
         let mut coeffs = polynomial.coeffs.to_vec();
-        coeffs.resize(powers.len(), <P::Fr>::zero());
+        coeffs.resize(powers.len(), <P::ScalarField>::zero());
 
-        Ok(VariableBaseMSM::multi_scalar_mul(
+        Ok(P::G1::msm_bigint(
             powers,
-            &coeffs.iter().map(|b| b.into_repr()).collect::<Vec<_>>(),
+            &coeffs.iter().map(|b| b.into_bigint()).collect::<Vec<_>>(),
         ))
     }
 
     pub fn open(
         powers: &[P::G1Affine],
-        polynomial: &UnivariatePolynomial<P::Fr>,
-        point: &P::Fr,
-    ) -> Result<P::G1Projective, Error> {
-        assert!(powers.len() >= polynomial.degree() + 1);
+        polynomial: &UnivariatePolynomial<P::ScalarField>,
+        point: &P::ScalarField,
+    ) -> Result<P::G1, Error> {
+        Self::check_degree(powers, polynomial)?;
 
         // Trick to calculate (p(x) - p(z)) / (x - z) as p(x) / (x - z) ignoring remainder p(z)
         let quotient_polynomial = polynomial
-            / &UnivariatePolynomial::from_coefficients_vec(vec![-point.clone(), P::Fr::one()]);
+            / &UnivariatePolynomial::from_coefficients_vec(vec![-*point, P::ScalarField::one()]);
         let mut quotient_coeffs = quotient_polynomial.coeffs.to_vec();
-        quotient_coeffs.resize(powers.len(), <P::Fr>::zero());
-        Ok(VariableBaseMSM::multi_scalar_mul(
+        quotient_coeffs.resize(powers.len(), <P::ScalarField>::zero());
+        Ok(P::G1::msm_bigint(
             powers,
             &quotient_coeffs
                 .iter()
-                .map(|b| b.into_repr())
+                .map(|b| b.into_bigint())
                 .collect::<Vec<_>>(),
         ))
     }
 
     pub fn verify(
         v_srs: &VerifierSRS<P>,
-        com: &P::G1Projective,
-        point: &P::Fr,
-        eval: &P::Fr,
-        proof: &P::G1Projective,
+        com: &P::G1,
+        point: &P::ScalarField,
+        eval: &P::ScalarField,
+        proof: &P::G1,
     ) -> Result<bool, Error> {
-        Ok(P::pairing(
-            com.clone() - &v_srs.g.mul(eval.into_repr()),
-            v_srs.h.clone(),
-        ) == P::pairing(
-            proof.clone(),
-            v_srs.h_alpha.clone() - &v_srs.h.mul(point.into_repr()),
-        ))
+        Ok(
+            P::pairing(*com - v_srs.g.mul_bigint(eval.into_bigint()), v_srs.h).0
+                == P::pairing(
+                    *proof,
+                    v_srs.h_alpha - v_srs.h.mul_bigint(point.into_bigint()),
+                )
+                .0,
+        )
+    }
+
+    fn check_degree(
+        powers: &[P::G1Affine],
+        polynomial: &UnivariatePolynomial<P::ScalarField>,
+    ) -> Result<(), Error> {
+        if powers.len() < polynomial.degree() + 1 {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                polynomial.degree() + 1,
+                powers.len(),
+            ));
+        }
+        Ok(())
     }
 }
 
@@ -126,6 +144,10 @@ pub struct BivariatePolynomial<F: Field> {
 }
 
 impl<F: Field> BivariatePolynomial<F> {
+    pub fn new(y_polynomials: Vec<UnivariatePolynomial<F>>) -> Self {
+        Self { y_polynomials }
+    }
+
     pub fn evaluate(&self, point: &(F, F)) -> F {
         let (x, y) = point;
         let mut point_x_powers = vec![];
@@ -137,60 +159,142 @@ impl<F: Field> BivariatePolynomial<F> {
         point_x_powers
             .iter()
             .zip(&self.y_polynomials)
-            .map(|(x_power, y_polynomial)| x_power.clone() * y_polynomial.evaluate(&y))
+            .map(|(x_power, y_polynomial)| *x_power * y_polynomial.evaluate(y))
             .sum()
     }
+
+    // Checks the polynomial fits keys of `x_len` and `y_len` powers
+    fn check_degrees(&self, x_len: usize, y_len: usize) -> Result<(), Error> {
+        if x_len < self.y_polynomials.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                self.y_polynomials.len(),
+                x_len,
+            ));
+        }
+        for y_polynomial in &self.y_polynomials {
+            if y_len < y_polynomial.degree() + 1 {
+                return Err(InnerProductArgumentError::MessageLengthInvalid(
+                    y_polynomial.degree() + 1,
+                    y_len,
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
-pub struct OpeningProof<P: PairingEngine, D: Digest> {
+pub struct OpeningProof<P: Pairing, D: TranscriptDigest> {
     ip_proof: PolynomialEvaluationSecondTierIPAProof<P, D>,
-    y_eval_comm: P::G1Projective,
-    kzg_proof: P::G1Projective,
+    y_eval_comm: P::G1,
+    kzg_proof: P::G1,
+}
+
+impl<P: Pairing, D: TranscriptDigest> CanonicalSerialize for OpeningProof<P, D> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.ip_proof.serialize_with_mode(&mut writer, compress)?;
+        self.y_eval_comm
+            .serialize_with_mode(&mut writer, compress)?;
+        self.kzg_proof.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.ip_proof.serialized_size(compress)
+            + self.y_eval_comm.serialized_size(compress)
+            + self.kzg_proof.serialized_size(compress)
+    }
+}
+
+impl<P: Pairing, D: TranscriptDigest> Valid for OpeningProof<P, D> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.ip_proof.check()?;
+        self.y_eval_comm.check()?;
+        self.kzg_proof.check()
+    }
+}
+
+impl<P: Pairing, D: TranscriptDigest> CanonicalDeserialize for OpeningProof<P, D> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(OpeningProof {
+            ip_proof: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            y_eval_comm: CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+            kzg_proof: CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+        })
+    }
+}
+
+impl<P: Pairing, D: TranscriptDigest> Validate for OpeningProof<P, D>
+where
+    PolynomialEvaluationSecondTierIPAProof<P, D>: Validate,
+    P::G1: Validate,
+{
+    fn validate(&self) -> Result<(), Error> {
+        self.ip_proof.validate()?;
+        self.y_eval_comm.validate()?;
+        self.kzg_proof.validate()
+    }
 }
 
-pub struct BivariatePolynomialCommitment<P: PairingEngine, D: Digest> {
+pub struct BivariatePolynomialCommitment<P: Pairing, D: TranscriptDigest> {
     _pairing: PhantomData<P>,
     _digest: PhantomData<D>,
 }
 
-impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
-    pub fn setup<R: Rng>(
+impl<P: Pairing, D: TranscriptDigest> BivariatePolynomialCommitment<P, D> {
+    pub fn setup<R: RngCore + CryptoRng>(
         rng: &mut R,
         x_degree: usize,
         y_degree: usize,
     ) -> Result<(SRS<P>, Vec<P::G1Affine>), Error> {
-        let alpha = <P::Fr>::rand(rng);
-        let beta = <P::Fr>::rand(rng);
-        let g = <P::G1Projective>::prime_subgroup_generator();
-        let h = <P::G2Projective>::prime_subgroup_generator();
-        let kzg_srs = <P as PairingEngine>::G1Projective::batch_normalization_into_affine(
-            &structured_generators_scalar_power(y_degree + 1, &g, &alpha),
-        );
+        let alpha = <P::ScalarField>::rand(rng);
+        let beta = <P::ScalarField>::rand(rng);
+        let g = <P::G1>::generator();
+        let h = <P::G2>::generator();
+        let kzg_srs = <P as Pairing>::G1::normalize_batch(&structured_generators_scalar_power(
+            y_degree + 1,
+            &g,
+            &alpha,
+        ));
         let srs = SRS {
-            g_alpha_powers: vec![g.clone()],
+            g_alpha_powers: vec![g],
             h_beta_powers: structured_generators_scalar_power(2 * x_degree + 1, &h, &beta),
-            g_beta: g.mul(beta.into_repr()),
-            h_alpha: h.mul(alpha.into_repr()),
+            g_beta: g.mul_bigint(beta.into_bigint()),
+            h_alpha: h.mul_bigint(alpha.into_bigint()),
         };
         Ok((srs, kzg_srs))
     }
 
     pub fn commit(
         srs: &(SRS<P>, Vec<P::G1Affine>),
-        bivariate_polynomial: &BivariatePolynomial<P::Fr>,
-    ) -> Result<(ExtensionFieldElement<P>, Vec<P::G1Projective>), Error> {
+        bivariate_polynomial: &BivariatePolynomial<P::ScalarField>,
+    ) -> Result<(ExtensionFieldElement<P>, Vec<P::G1>), Error> {
         let (ip_srs, kzg_srs) = srs;
         let (ck, _) = ip_srs.get_commitment_keys();
-        assert!(ck.len() >= bivariate_polynomial.y_polynomials.len());
+        bivariate_polynomial.check_degrees(ck.len(), kzg_srs.len())?;
 
         // Create KZG commitments to Y polynomials
         let y_polynomial_coms = bivariate_polynomial
             .y_polynomials
             .iter()
-            .chain(vec![UnivariatePolynomial::zero()].iter().cycle())
+            .chain([UnivariatePolynomial::zero()].iter().cycle())
             .take(ck.len())
             .map(|y_polynomial| KZG::<P>::commit(kzg_srs, y_polynomial))
-            .collect::<Result<Vec<P::G1Projective>, Error>>()?;
+            .collect::<Result<Vec<P::G1>, Error>>()?;
 
         // Create AFGHO commitment to Y polynomial commitments
         Ok((
@@ -201,18 +305,18 @@ impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
 
     pub fn open(
         srs: &(SRS<P>, Vec<P::G1Affine>),
-        bivariate_polynomial: &BivariatePolynomial<P::Fr>,
-        y_polynomial_comms: &Vec<P::G1Projective>,
-        point: &(P::Fr, P::Fr),
+        bivariate_polynomial: &BivariatePolynomial<P::ScalarField>,
+        y_polynomial_comms: &[P::G1],
+        point: &(P::ScalarField, P::ScalarField),
     ) -> Result<OpeningProof<P, D>, Error> {
         let (x, y) = point;
         let (ip_srs, kzg_srs) = srs;
         let (ck_1, _) = ip_srs.get_commitment_keys();
-        assert!(ck_1.len() >= bivariate_polynomial.y_polynomials.len());
+        bivariate_polynomial.check_degrees(ck_1.len(), kzg_srs.len())?;
 
         let precomp_time = start_timer!(|| "Computing coefficients and KZG commitment");
         let mut powers_of_x = vec![];
-        let mut cur = P::Fr::one();
+        let mut cur = P::ScalarField::one();
         for _ in 0..(ck_1.len()) {
             powers_of_x.push(cur);
             cur *= x;
@@ -221,26 +325,22 @@ impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
         let coeffs = bivariate_polynomial
             .y_polynomials
             .iter()
-            .chain(vec![UnivariatePolynomial::zero()].iter().cycle())
+            .chain([UnivariatePolynomial::zero()].iter().cycle())
             .take(ck_1.len())
             .map(|y_polynomial| {
                 let mut c = y_polynomial.coeffs.to_vec();
-                c.resize(kzg_srs.len(), <P::Fr>::zero());
+                c.resize(kzg_srs.len(), <P::ScalarField>::zero());
                 c
             })
-            .collect::<Vec<Vec<P::Fr>>>();
+            .collect::<Vec<Vec<P::ScalarField>>>();
         let y_eval_coeffs = (0..kzg_srs.len())
-            .map(|j| {
-                (0..ck_1.len())
-                    .map(|i| powers_of_x[i].clone() * &coeffs[i][j])
-                    .sum()
-            })
-            .collect::<Vec<P::Fr>>();
-        let y_eval_comm = VariableBaseMSM::multi_scalar_mul(
+            .map(|j| (0..ck_1.len()).map(|i| powers_of_x[i] * coeffs[i][j]).sum())
+            .collect::<Vec<P::ScalarField>>();
+        let y_eval_comm = P::G1::msm_bigint(
             kzg_srs,
             &y_eval_coeffs
                 .iter()
-                .map(|b| b.into_repr())
+                .map(|b| b.into_bigint())
                 .collect::<Vec<_>>(),
         );
         end_timer!(precomp_time);
@@ -248,7 +348,7 @@ impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
         let ipa_time = start_timer!(|| "Computing IPA proof");
         let ip_proof =
             PolynomialEvaluationSecondTierIPA::<P, D>::prove_with_structured_scalar_message(
-                &ip_srs,
+                ip_srs,
                 (y_polynomial_comms, &powers_of_x),
                 (&ck_1, &HomomorphicPlaceholderValue),
             )?;
@@ -271,8 +371,8 @@ impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
     pub fn verify(
         v_srs: &VerifierSRS<P>,
         com: &ExtensionFieldElement<P>,
-        point: &(P::Fr, P::Fr),
-        eval: &P::Fr,
+        point: &(P::ScalarField, P::ScalarField),
+        eval: &P::ScalarField,
         proof: &OpeningProof<P, D>,
     ) -> Result<bool, Error> {
         let (x, y) = point;
@@ -280,7 +380,7 @@ impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
             PolynomialEvaluationSecondTierIPA::<P, D>::verify_with_structured_scalar_message(
                 v_srs,
                 &HomomorphicPlaceholderValue,
-                (com, &IdentityOutput(vec![proof.y_eval_comm.clone()])),
+                (com, &IdentityOutput(vec![proof.y_eval_comm])),
                 x,
                 &proof.ip_proof,
             )?;
@@ -290,15 +390,25 @@ impl<P: PairingEngine, D: Digest> BivariatePolynomialCommitment<P, D> {
     }
 }
 
-pub struct UnivariatePolynomialCommitment<P: PairingEngine, D: Digest> {
+// Smallest power of two whose square is at least `n`, computed without floating point so that it
+// is available without std.
+pub(crate) fn sqrt_power_of_two(n: usize) -> usize {
+    let mut sqrt = 1_usize;
+    while sqrt * sqrt < n {
+        sqrt <<= 1;
+    }
+    sqrt
+}
+
+pub struct UnivariatePolynomialCommitment<P: Pairing, D: TranscriptDigest> {
     _pairing: PhantomData<P>,
     _digest: PhantomData<D>,
 }
 
-impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D> {
+impl<P: Pairing, D: TranscriptDigest> UnivariatePolynomialCommitment<P, D> {
     fn bivariate_degrees(univariate_degree: usize) -> (usize, usize) {
         //(((univariate_degree + 1) as f64).sqrt().ceil() as usize).next_power_of_two() - 1;
-        let sqrt = (((univariate_degree + 1) as f64).sqrt().ceil() as usize).next_power_of_two();
+        let sqrt = sqrt_power_of_two(univariate_degree + 1);
         // Skew split between bivariate degrees to account for KZG being less expensive than MIPP
         let skew_factor = if sqrt >= 32 { 16_usize } else { sqrt / 2 };
         (sqrt / skew_factor - 1, sqrt * skew_factor - 1)
@@ -312,10 +422,10 @@ impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D> {
 
     fn bivariate_form(
         bivariate_degrees: (usize, usize),
-        polynomial: &UnivariatePolynomial<P::Fr>,
-    ) -> BivariatePolynomial<P::Fr> {
+        polynomial: &UnivariatePolynomial<P::ScalarField>,
+    ) -> BivariatePolynomial<P::ScalarField> {
         let (x_degree, y_degree) = bivariate_degrees;
-        let default_zero = vec![P::Fr::zero()];
+        let default_zero = [P::ScalarField::zero()];
         let mut coeff_iter = polynomial
             .coeffs
             .iter()
@@ -335,15 +445,18 @@ impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D> {
         BivariatePolynomial { y_polynomials }
     }
 
-    pub fn setup<R: Rng>(rng: &mut R, degree: usize) -> Result<(SRS<P>, Vec<P::G1Affine>), Error> {
+    pub fn setup<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        degree: usize,
+    ) -> Result<(SRS<P>, Vec<P::G1Affine>), Error> {
         let (x_degree, y_degree) = Self::bivariate_degrees(degree);
         BivariatePolynomialCommitment::<P, D>::setup(rng, x_degree, y_degree)
     }
 
     pub fn commit(
         srs: &(SRS<P>, Vec<P::G1Affine>),
-        polynomial: &UnivariatePolynomial<P::Fr>,
-    ) -> Result<(ExtensionFieldElement<P>, Vec<P::G1Projective>), Error> {
+        polynomial: &UnivariatePolynomial<P::ScalarField>,
+    ) -> Result<(ExtensionFieldElement<P>, Vec<P::G1>), Error> {
         let bivariate_degrees = Self::parse_bivariate_degrees_from_srs(srs);
         BivariatePolynomialCommitment::<P, D>::commit(
             srs,
@@ -353,13 +466,13 @@ impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D> {
 
     pub fn open(
         srs: &(SRS<P>, Vec<P::G1Affine>),
-        polynomial: &UnivariatePolynomial<P::Fr>,
-        y_polynomial_comms: &Vec<P::G1Projective>,
-        point: &P::Fr,
+        polynomial: &UnivariatePolynomial<P::ScalarField>,
+        y_polynomial_comms: &[P::G1],
+        point: &P::ScalarField,
     ) -> Result<OpeningProof<P, D>, Error> {
         let (x_degree, y_degree) = Self::parse_bivariate_degrees_from_srs(srs);
-        let y = point.clone();
-        let x = point.pow(&vec![(y_degree + 1) as u64]);
+        let y = *point;
+        let x = point.pow(vec![(y_degree + 1) as u64]);
         BivariatePolynomialCommitment::open(
             srs,
             &Self::bivariate_form((x_degree, y_degree), polynomial),
@@ -372,13 +485,13 @@ impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D> {
         v_srs: &VerifierSRS<P>,
         max_degree: usize,
         com: &ExtensionFieldElement<P>,
-        point: &P::Fr,
-        eval: &P::Fr,
+        point: &P::ScalarField,
+        eval: &P::ScalarField,
         proof: &OpeningProof<P, D>,
     ) -> Result<bool, Error> {
         let (_, y_degree) = Self::bivariate_degrees(max_degree);
-        let y = point.clone();
-        let x = y.pow(&vec![(y_degree + 1) as u64]);
+        let y = *point;
+        let x = y.pow(vec![(y_degree + 1) as u64]);
         BivariatePolynomialCommitment::verify(v_srs, com, &(x, y), eval, proof)
     }
 }
@@ -387,7 +500,7 @@ impl<P: PairingEngine, D: Digest> UnivariatePolynomialCommitment<P, D> {
 mod tests {
     use super::*;
     use ark_bls12_381::Bls12_381;
-    use blake2::Blake2b;
+    use blake2::Blake2b512;
     use rand::{rngs::StdRng, SeedableRng};
 
     const BIVARIATE_X_DEGREE: usize = 7;
@@ -396,8 +509,8 @@ mod tests {
     const UNIVARIATE_DEGREE: usize = 65535;
     //const UNIVARIATE_DEGREE: usize = 1048575;
 
-    type TestBivariatePolyCommitment = BivariatePolynomialCommitment<Bls12_381, Blake2b>;
-    type TestUnivariatePolyCommitment = UnivariatePolynomialCommitment<Bls12_381, Blake2b>;
+    type TestBivariatePolyCommitment = BivariatePolynomialCommitment<Bls12_381, Blake2b512>;
+    type TestUnivariatePolyCommitment = UnivariatePolynomialCommitment<Bls12_381, Blake2b512>;
 
     #[test]
     fn bivariate_poly_commit_test() {
@@ -411,7 +524,7 @@ mod tests {
         for _ in 0..BIVARIATE_X_DEGREE + 1 {
             let mut y_polynomial_coeffs = vec![];
             for _ in 0..BIVARIATE_Y_DEGREE + 1 {
-                y_polynomial_coeffs.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
+                y_polynomial_coeffs.push(<Bls12_381 as Pairing>::ScalarField::rand(&mut rng));
             }
             y_polynomials.push(UnivariatePolynomial::from_coefficients_slice(
                 &y_polynomial_coeffs,
@@ -450,7 +563,7 @@ mod tests {
 
         let mut polynomial_coeffs = vec![];
         for _ in 0..UNIVARIATE_DEGREE + 1 {
-            polynomial_coeffs.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
+            polynomial_coeffs.push(<Bls12_381 as Pairing>::ScalarField::rand(&mut rng));
         }
         let polynomial = UnivariatePolynomial::from_coefficients_slice(&polynomial_coeffs);
 
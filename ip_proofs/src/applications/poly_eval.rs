@@ -0,0 +1,190 @@
+use ark_ec::{CurveGroup, Group};
+use ark_ff::Zero;
+use ark_std::{marker::PhantomData, vec, vec::Vec};
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    gipa::GIPAProof,
+    tipa::structured_scalar_message::{
+        structured_scalar_power, GIPAWithSSM, SSMPlaceholderCommitment,
+    },
+    transcript::{ChallengeInput, TranscriptDigest},
+    Error, InnerProductArgumentError,
+};
+use ark_dh_commitments::{
+    identity::{HomomorphicPlaceholderValue, IdentityCommitment, IdentityOutput},
+    pedersen::PedersenCommitment,
+    DoublyHomomorphicCommitment,
+};
+use ark_inner_products::{InnerProduct, PolynomialEvaluationInnerProduct, ScalarInnerProduct};
+
+type EvaluationIPA<G, D> = GIPAWithSSM<
+    ScalarInnerProduct<<G as Group>::ScalarField>,
+    PedersenCommitment<G>,
+    IdentityCommitment<<G as Group>::ScalarField, <G as Group>::ScalarField>,
+    D,
+>;
+
+pub type EvaluationProof<G, D> = GIPAProof<
+    ScalarInnerProduct<<G as Group>::ScalarField>,
+    PedersenCommitment<G>,
+    SSMPlaceholderCommitment<<G as Group>::ScalarField>,
+    IdentityCommitment<<G as Group>::ScalarField, <G as Group>::ScalarField>,
+    D,
+>;
+
+// Treats a Pedersen committed vector as polynomial coefficients and proves its evaluation at a
+// public point as the inner product with the powers of the point. Lighter weight than the
+// bivariate polynomial commitments as there is no second tier and no structured setup.
+pub struct VectorEvaluationArgument<G: CurveGroup, D: TranscriptDigest> {
+    _group: PhantomData<G>,
+    _digest: PhantomData<D>,
+}
+
+impl<G: CurveGroup, D: TranscriptDigest> VectorEvaluationArgument<G, D> {
+    // Commitment key length must be a power of 2 and bounds the number of coefficients
+    pub fn setup<R: RngCore + CryptoRng>(rng: &mut R, size: usize) -> Result<Vec<G>, Error> {
+        let (ck, _) = EvaluationIPA::<G, D>::setup(rng, size)?;
+        Ok(ck)
+    }
+
+    pub fn commit(ck: &[G], coeffs: &[G::ScalarField]) -> Result<G, Error> {
+        Ok(PedersenCommitment::<G>::commit(
+            ck,
+            &Self::pad_coefficients(ck, coeffs)?,
+        )?)
+    }
+
+    pub fn prove_eval(
+        ck: &[G],
+        coeffs: &[G::ScalarField],
+        com: &G,
+        point: &G::ScalarField,
+        eval: &G::ScalarField,
+    ) -> Result<EvaluationProof<G, D>, Error> {
+        let coeffs = Self::pad_coefficients(ck, coeffs)?;
+        let powers_of_point = structured_scalar_power(ck.len(), point);
+        if !PedersenCommitment::<G>::verify(ck, &coeffs, com)?
+            || PolynomialEvaluationInnerProduct::inner_product(&coeffs, &[*point])? != *eval
+        {
+            return Err(InnerProductArgumentError::InnerProductInvalid);
+        }
+        EvaluationIPA::<G, D>::prove_with_structured_scalar_message_and_seed(
+            (&coeffs, &powers_of_point),
+            (ck, &HomomorphicPlaceholderValue),
+            &Self::statement_seed(com, point, eval)?,
+        )
+    }
+
+    pub fn verify_eval(
+        ck: &[G],
+        com: &G,
+        point: &G::ScalarField,
+        eval: &G::ScalarField,
+        proof: &EvaluationProof<G, D>,
+    ) -> Result<bool, Error> {
+        EvaluationIPA::<G, D>::verify_with_structured_scalar_message_and_seed(
+            (ck, &HomomorphicPlaceholderValue),
+            (com, &IdentityOutput(vec![*eval])),
+            point,
+            proof,
+            &Self::statement_seed(com, point, eval)?,
+        )
+    }
+
+    // The first challenge is seeded with the statement, as the structured scalar message leaves
+    // the point out of the commitments
+    fn statement_seed(
+        com: &G,
+        point: &G::ScalarField,
+        eval: &G::ScalarField,
+    ) -> Result<G::ScalarField, Error> {
+        let mut input = D::input();
+        input.absorb(com)?;
+        input.absorb_scalar(point)?;
+        input.absorb_scalar(eval)?;
+        let mut counter_nonce: usize = 0;
+        let seed = loop {
+            if let Some(seed) = input.challenge::<G::ScalarField>(counter_nonce) {
+                break seed;
+            };
+            counter_nonce += 1;
+        };
+        Ok(seed)
+    }
+
+    fn pad_coefficients(ck: &[G], coeffs: &[G::ScalarField]) -> Result<Vec<G::ScalarField>, Error> {
+        if coeffs.len() > ck.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                coeffs.len(),
+                ck.len(),
+            ));
+        }
+        let mut padded = coeffs.to_vec();
+        padded.resize(ck.len(), <G::ScalarField>::zero());
+        Ok(padded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_ec::pairing::Pairing;
+    use ark_ff::{One, UniformRand};
+    use ark_poly::polynomial::{
+        univariate::DensePolynomial as UnivariatePolynomial, DenseUVPolynomial, Polynomial,
+    };
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type G1 = <Bls12_381 as Pairing>::G1;
+    type Fr = <Bls12_381 as Pairing>::ScalarField;
+    type TestEvaluationArgument = VectorEvaluationArgument<G1, Blake2b512>;
+
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn vector_evaluation_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ck = TestEvaluationArgument::setup(&mut rng, TEST_SIZE).unwrap();
+
+        // Fewer coefficients than the key length are padded with zeros
+        let coeffs = (0..TEST_SIZE - 2)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<Fr>>();
+        let com = TestEvaluationArgument::commit(&ck, &coeffs).unwrap();
+
+        let point = Fr::rand(&mut rng);
+        let eval = UnivariatePolynomial::from_coefficients_slice(&coeffs).evaluate(&point);
+        let proof = TestEvaluationArgument::prove_eval(&ck, &coeffs, &com, &point, &eval).unwrap();
+
+        assert!(TestEvaluationArgument::verify_eval(&ck, &com, &point, &eval, &proof).unwrap());
+        assert!(!TestEvaluationArgument::verify_eval(
+            &ck,
+            &com,
+            &point,
+            &(eval + Fr::one()),
+            &proof
+        )
+        .unwrap());
+
+        // The challenges bind the point, so the proof does not carry over to another one
+        let other_point = Fr::rand(&mut rng);
+        let other_eval =
+            UnivariatePolynomial::from_coefficients_slice(&coeffs).evaluate(&other_point);
+        assert!(
+            !TestEvaluationArgument::verify_eval(&ck, &com, &other_point, &other_eval, &proof)
+                .unwrap()
+        );
+        assert!(TestEvaluationArgument::prove_eval(
+            &ck,
+            &coeffs,
+            &com,
+            &point,
+            &(eval + Fr::one())
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,334 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::{One, Zero};
+use ark_std::{marker::PhantomData, vec, vec::Vec};
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    gipa::GIPAProof,
+    tipa::structured_scalar_message::{
+        tensor_scalar_product, GIPAWithSSM, SSMPlaceholderCommitment,
+    },
+    transcript::TranscriptDigest,
+    Error, InnerProductArgumentError,
+};
+use ark_dh_commitments::{
+    afgho16::AFGHOCommitmentG1,
+    identity::{HomomorphicPlaceholderValue, IdentityCommitment, IdentityOutput},
+    pedersen::PedersenCommitment,
+    CommitmentError, DoublyHomomorphicCommitment,
+};
+use ark_inner_products::{
+    ExtensionFieldElement, InnerProduct, MultiexponentiationInnerProduct, ScalarInnerProduct,
+};
+
+type RowEvaluationIPA<P, D> = GIPAWithSSM<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    D,
+>;
+
+type RowEvaluationIPAProof<P, D> = GIPAProof<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    SSMPlaceholderCommitment<<P as Pairing>::ScalarField>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    D,
+>;
+
+type ColumnEvaluationIPA<P, D> = GIPAWithSSM<
+    ScalarInnerProduct<<P as Pairing>::ScalarField>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    IdentityCommitment<<P as Pairing>::ScalarField, <P as Pairing>::ScalarField>,
+    D,
+>;
+
+type ColumnEvaluationIPAProof<P, D> = GIPAProof<
+    ScalarInnerProduct<<P as Pairing>::ScalarField>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    SSMPlaceholderCommitment<<P as Pairing>::ScalarField>,
+    IdentityCommitment<<P as Pairing>::ScalarField, <P as Pairing>::ScalarField>,
+    D,
+>;
+
+// Final claim of a sumcheck run: the multilinear extension of the table evaluates to `eval` at
+// `point`, where point[i] is bound to bit i of the table index
+#[derive(Clone)]
+pub struct EvaluationClaim<F> {
+    pub point: Vec<F>,
+    pub eval: F,
+}
+
+pub struct MultilinearCommitmentKey<P: Pairing> {
+    pub row_ck: Vec<P::G2>,
+    pub column_ck: Vec<P::G1>,
+}
+
+pub struct MultilinearEvaluationProof<P: Pairing, D: TranscriptDigest> {
+    row_eval_com: P::G1,
+    row_proof: RowEvaluationIPAProof<P, D>,
+    column_proof: ColumnEvaluationIPAProof<P, D>,
+}
+
+// The table is arranged as a matrix whose rows are indexed by the high bits of the table index.
+// Rows are Pedersen committed and the row commitments AFGHO committed. As the equality polynomial
+// at the point is the tensor product of its row and column halves, an evaluation is proved with a
+// MIPP folding the row commitments followed by a scalar inner product on the folded row.
+pub struct MultilinearEvaluationArgument<P: Pairing, D: TranscriptDigest> {
+    _pairing: PhantomData<P>,
+    _digest: PhantomData<D>,
+}
+
+impl<P: Pairing, D: TranscriptDigest> MultilinearEvaluationArgument<P, D> {
+    pub fn setup<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        num_vars: usize,
+    ) -> Result<MultilinearCommitmentKey<P>, Error> {
+        let (row_vars, column_vars) = Self::split_vars(num_vars);
+        Ok(MultilinearCommitmentKey {
+            row_ck: RowEvaluationIPA::<P, D>::setup(rng, 1 << row_vars)?.0,
+            column_ck: ColumnEvaluationIPA::<P, D>::setup(rng, 1 << column_vars)?.0,
+        })
+    }
+
+    // Returns the commitment and the row commitments kept by the prover for opening
+    pub fn commit(
+        ck: &MultilinearCommitmentKey<P>,
+        table: &[P::ScalarField],
+    ) -> Result<(ExtensionFieldElement<P>, Vec<P::G1>), Error> {
+        Self::check_table_length(ck, table)?;
+        let row_coms = table
+            .chunks(ck.column_ck.len())
+            .map(|row| PedersenCommitment::<P::G1>::commit(&ck.column_ck, row))
+            .collect::<Result<Vec<P::G1>, CommitmentError>>()?;
+        Ok((
+            AFGHOCommitmentG1::<P>::commit(&ck.row_ck, &row_coms)?,
+            row_coms,
+        ))
+    }
+
+    pub fn evaluate(
+        table: &[P::ScalarField],
+        point: &[P::ScalarField],
+    ) -> Result<P::ScalarField, Error> {
+        if table.len() != 1 << point.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                table.len(),
+                1 << point.len(),
+            ));
+        }
+        Ok(ScalarInnerProduct::inner_product(
+            table,
+            &tensor_scalar_product(&Self::eq_factors(point)),
+        )?)
+    }
+
+    pub fn prove(
+        ck: &MultilinearCommitmentKey<P>,
+        table: &[P::ScalarField],
+        row_coms: &[P::G1],
+        point: &[P::ScalarField],
+    ) -> Result<
+        (
+            EvaluationClaim<P::ScalarField>,
+            MultilinearEvaluationProof<P, D>,
+        ),
+        Error,
+    > {
+        Self::check_table_length(ck, table)?;
+        let (row_factors, column_factors) = Self::split_point(ck, point)?;
+        let row_eq = tensor_scalar_product(&row_factors);
+        let column_eq = tensor_scalar_product(&column_factors);
+
+        // Fold rows with the row half of the equality polynomial
+        let mut row_eval = vec![<P::ScalarField>::zero(); ck.column_ck.len()];
+        for (row, eq) in table.chunks(ck.column_ck.len()).zip(&row_eq) {
+            row_eval
+                .iter_mut()
+                .zip(row)
+                .for_each(|(r, v)| *r += &(*v * eq));
+        }
+        let row_eval_com = MultiexponentiationInnerProduct::inner_product(row_coms, &row_eq)?;
+
+        let row_proof = RowEvaluationIPA::<P, D>::prove_with_structured_scalar_message(
+            (row_coms, &row_eq),
+            (&ck.row_ck, &HomomorphicPlaceholderValue),
+        )?;
+        let column_proof = ColumnEvaluationIPA::<P, D>::prove_with_structured_scalar_message(
+            (&row_eval, &column_eq),
+            (&ck.column_ck, &HomomorphicPlaceholderValue),
+        )?;
+
+        Ok((
+            EvaluationClaim {
+                point: point.to_vec(),
+                eval: ScalarInnerProduct::inner_product(&row_eval, &column_eq)?,
+            },
+            MultilinearEvaluationProof {
+                row_eval_com,
+                row_proof,
+                column_proof,
+            },
+        ))
+    }
+
+    pub fn verify(
+        ck: &MultilinearCommitmentKey<P>,
+        com: &ExtensionFieldElement<P>,
+        claim: &EvaluationClaim<P::ScalarField>,
+        proof: &MultilinearEvaluationProof<P, D>,
+    ) -> Result<bool, Error> {
+        let (row_factors, column_factors) = match Self::split_point(ck, &claim.point) {
+            Ok(factors) => factors,
+            Err(_) => return Ok(false),
+        };
+        let row_valid = RowEvaluationIPA::<P, D>::verify_with_tensor_scalar_message(
+            (&ck.row_ck, &HomomorphicPlaceholderValue),
+            (com, &IdentityOutput(vec![proof.row_eval_com])),
+            &row_factors,
+            &proof.row_proof,
+        )?;
+        let column_valid = ColumnEvaluationIPA::<P, D>::verify_with_tensor_scalar_message(
+            (&ck.column_ck, &HomomorphicPlaceholderValue),
+            (&proof.row_eval_com, &IdentityOutput(vec![claim.eval])),
+            &column_factors,
+            &proof.column_proof,
+        )?;
+        Ok(row_valid && column_valid)
+    }
+
+    // Proves every claim of a sumcheck run against the same committed table
+    pub fn prove_batch(
+        ck: &MultilinearCommitmentKey<P>,
+        table: &[P::ScalarField],
+        row_coms: &[P::G1],
+        points: &[Vec<P::ScalarField>],
+    ) -> Result<
+        Vec<(
+            EvaluationClaim<P::ScalarField>,
+            MultilinearEvaluationProof<P, D>,
+        )>,
+        Error,
+    > {
+        points
+            .iter()
+            .map(|point| Self::prove(ck, table, row_coms, point))
+            .collect()
+    }
+
+    pub fn verify_batch(
+        ck: &MultilinearCommitmentKey<P>,
+        com: &ExtensionFieldElement<P>,
+        claims: &[(
+            EvaluationClaim<P::ScalarField>,
+            MultilinearEvaluationProof<P, D>,
+        )],
+    ) -> Result<bool, Error> {
+        for (claim, proof) in claims.iter() {
+            if !Self::verify(ck, com, claim, proof)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    // Row variables are the high bits of the table index, taking the smaller half
+    fn split_vars(num_vars: usize) -> (usize, usize) {
+        (num_vars / 2, num_vars - num_vars / 2)
+    }
+
+    fn eq_factors(point: &[P::ScalarField]) -> Vec<(P::ScalarField, P::ScalarField)> {
+        point
+            .iter()
+            .map(|r| (<P::ScalarField>::one() - r, *r))
+            .collect()
+    }
+
+    fn split_point(
+        ck: &MultilinearCommitmentKey<P>,
+        point: &[P::ScalarField],
+    ) -> Result<
+        (
+            Vec<(P::ScalarField, P::ScalarField)>,
+            Vec<(P::ScalarField, P::ScalarField)>,
+        ),
+        Error,
+    > {
+        let column_vars = ck.column_ck.len().trailing_zeros() as usize;
+        let row_vars = ck.row_ck.len().trailing_zeros() as usize;
+        if point.len() != row_vars + column_vars {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                point.len(),
+                row_vars + column_vars,
+            ));
+        }
+        let factors = Self::eq_factors(point);
+        Ok((
+            factors[column_vars..].to_vec(),
+            factors[..column_vars].to_vec(),
+        ))
+    }
+
+    fn check_table_length(
+        ck: &MultilinearCommitmentKey<P>,
+        table: &[P::ScalarField],
+    ) -> Result<(), Error> {
+        let size = ck.row_ck.len() * ck.column_ck.len();
+        if table.len() != size {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                table.len(),
+                size,
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_ff::UniformRand;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type Fr = <Bls12_381 as Pairing>::ScalarField;
+    type TestEvaluationArgument = MultilinearEvaluationArgument<Bls12_381, Blake2b512>;
+
+    const NUM_VARS: usize = 5;
+
+    #[test]
+    fn multilinear_evaluation_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let ck = TestEvaluationArgument::setup(&mut rng, NUM_VARS).unwrap();
+        let table = (0..(1 << NUM_VARS))
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<Fr>>();
+        let (com, row_coms) = TestEvaluationArgument::commit(&ck, &table).unwrap();
+
+        // Multilinear extension agrees with the table on the boolean hypercube
+        let vertex = vec![Fr::one(), Fr::zero(), Fr::one(), Fr::one(), Fr::zero()];
+        assert_eq!(
+            TestEvaluationArgument::evaluate(&table, &vertex).unwrap(),
+            table[0b01101]
+        );
+
+        let points = (0..2)
+            .map(|_| (0..NUM_VARS).map(|_| Fr::rand(&mut rng)).collect())
+            .collect::<Vec<Vec<Fr>>>();
+        let claims = TestEvaluationArgument::prove_batch(&ck, &table, &row_coms, &points).unwrap();
+        assert_eq!(
+            claims[0].0.eval,
+            TestEvaluationArgument::evaluate(&table, &points[0]).unwrap()
+        );
+        assert!(TestEvaluationArgument::verify_batch(&ck, &com, &claims).unwrap());
+
+        let mut wrong_claim = claims[1].0.clone();
+        wrong_claim.eval += &Fr::one();
+        assert!(!TestEvaluationArgument::verify(&ck, &com, &wrong_claim, &claims[1].1).unwrap());
+        wrong_claim = claims[1].0.clone();
+        wrong_claim.point.pop();
+        assert!(!TestEvaluationArgument::verify(&ck, &com, &wrong_claim, &claims[1].1).unwrap());
+    }
+}
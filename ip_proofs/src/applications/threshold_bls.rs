@@ -0,0 +1,284 @@
+use ark_ec::{pairing::Pairing, Group};
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_poly::polynomial::{
+    univariate::DensePolynomial as UnivariatePolynomial, DenseUVPolynomial, Polynomial,
+};
+use ark_std::{marker::PhantomData, vec, vec::Vec};
+
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    tipa::{TIPAProof, VerifierSRS, SRS, TIPA},
+    transcript::TranscriptDigest,
+    Error, InnerProductArgumentError,
+};
+use ark_dh_commitments::{
+    afgho16::AFGHOCommitmentG1,
+    identity::{HomomorphicPlaceholderValue, IdentityCommitment, IdentityOutput},
+    pedersen::PedersenCommitment,
+    DoublyHomomorphicCommitment,
+};
+use ark_inner_products::{ExtensionFieldElement, InnerProduct, MultiexponentiationInnerProduct};
+
+type KeyAggregationIPA<P, D> = TIPA<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+type KeyAggregationIPAProof<P, D> = TIPAProof<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>,
+    P,
+    D,
+>;
+
+// Public keys live in G1 and signatures in G2 so that the public key vector can be committed with
+// AFGHO and aggregated with a multiexponentiation inner product. Signing share i is f(i + 1) for
+// the dealer polynomial f; the aggregate key is the Lagrange combination of the signers' keys.
+pub struct ThresholdAggregateSignature<P: Pairing, D: TranscriptDigest> {
+    pub signature: P::G2,
+    agg_pk: P::G1,
+    agg_pk_proof: KeyAggregationIPAProof<P, D>,
+}
+
+#[derive(Clone)]
+pub struct ThresholdVerifierKey<P: Pairing> {
+    pub ck_2: Vec<P::G1>,
+    pub com_pks: ExtensionFieldElement<P>,
+    pub group_pk: P::G1,
+    pub threshold: usize,
+}
+
+pub struct ThresholdBLS<P: Pairing, D: TranscriptDigest> {
+    _pairing: PhantomData<P>,
+    _digest: PhantomData<D>,
+}
+
+impl<P: Pairing, D: TranscriptDigest> ThresholdBLS<P, D> {
+    // Number of signers is bounded by `size`, a power of 2
+    pub fn setup<R: RngCore + CryptoRng>(rng: &mut R, size: usize) -> Result<SRS<P>, Error> {
+        let (srs, _) = KeyAggregationIPA::<P, D>::setup(rng, size)?;
+        Ok(srs)
+    }
+
+    // Trusted dealer key generation, returning group public key, signing shares and public keys
+    pub fn deal_keys<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        threshold: usize,
+        num_signers: usize,
+    ) -> (P::G1, Vec<P::ScalarField>, Vec<P::G1>) {
+        assert!(threshold > 0 && threshold <= num_signers);
+        let g = <P::G1>::generator();
+        let f = UnivariatePolynomial::rand(threshold - 1, rng);
+        let sks = (0..num_signers)
+            .map(|i| f.evaluate(&P::ScalarField::from((i + 1) as u64)))
+            .collect::<Vec<P::ScalarField>>();
+        let pks = sks
+            .iter()
+            .map(|sk| g.mul_bigint(sk.into_bigint()))
+            .collect();
+        (
+            g.mul_bigint(f.evaluate(&P::ScalarField::zero()).into_bigint()),
+            sks,
+            pks,
+        )
+    }
+
+    pub fn commit_public_keys(
+        srs: &SRS<P>,
+        pks: &[P::G1],
+    ) -> Result<ExtensionFieldElement<P>, Error> {
+        let (ck_1, _) = srs.get_commitment_keys();
+        Ok(AFGHOCommitmentG1::<P>::commit(
+            &ck_1,
+            &Self::pad_public_keys(&ck_1, pks)?,
+        )?)
+    }
+
+    pub fn get_verifier_key(
+        srs: &SRS<P>,
+        pks: &[P::G1],
+        group_pk: &P::G1,
+        threshold: usize,
+    ) -> Result<ThresholdVerifierKey<P>, Error> {
+        let (_, ck_2) = srs.get_commitment_keys();
+        Ok(ThresholdVerifierKey {
+            ck_2,
+            com_pks: Self::commit_public_keys(srs, pks)?,
+            group_pk: *group_pk,
+            threshold,
+        })
+    }
+
+    // Signs a message already hashed to G2
+    pub fn sign_partial(sk: &P::ScalarField, message: &P::G2) -> P::G2 {
+        message.mul_bigint(sk.into_bigint())
+    }
+
+    pub fn verify_partial(pk: &P::G1, message: &P::G2, signature: &P::G2) -> bool {
+        P::pairing(<P::G1>::generator(), *signature).0 == P::pairing(*pk, *message).0
+    }
+
+    // Combines partial signatures given as (signer index, signature) after checking each of them
+    pub fn aggregate(
+        srs: &SRS<P>,
+        pks: &[P::G1],
+        message: &P::G2,
+        threshold: usize,
+        partials: &[(usize, P::G2)],
+    ) -> Result<ThresholdAggregateSignature<P, D>, Error> {
+        let (ck_1, ck_2) = srs.get_commitment_keys();
+        let signers = partials.iter().map(|(i, _)| *i).collect::<Vec<usize>>();
+        let lagrange = Self::lagrange_coefficients(&ck_2, threshold, &signers)?;
+        for (i, partial) in partials.iter() {
+            if *i >= pks.len() || !Self::verify_partial(&pks[*i], message, partial) {
+                return Err(InnerProductArgumentError::InnerProductInvalid);
+            }
+        }
+
+        let mut signature = <P::G2>::zero();
+        for (i, partial) in partials.iter() {
+            signature += &partial.mul_bigint(lagrange[*i].into_bigint());
+        }
+        let pks = Self::pad_public_keys(&ck_1, pks)?;
+        let agg_pk = MultiexponentiationInnerProduct::inner_product(&pks, &lagrange)?;
+        let agg_pk_proof = KeyAggregationIPA::<P, D>::prove(
+            srs,
+            (&pks, &lagrange),
+            (&ck_1, &ck_2, &HomomorphicPlaceholderValue),
+        )?;
+        Ok(ThresholdAggregateSignature {
+            signature,
+            agg_pk,
+            agg_pk_proof,
+        })
+    }
+
+    // Checks the aggregate signature under the group public key and that it was combined from the
+    // given signers' keys in the committed public key vector
+    pub fn verify(
+        v_srs: &VerifierSRS<P>,
+        vk: &ThresholdVerifierKey<P>,
+        message: &P::G2,
+        signers: &[usize],
+        aggregate: &ThresholdAggregateSignature<P, D>,
+    ) -> Result<bool, Error> {
+        let lagrange = match Self::lagrange_coefficients(&vk.ck_2, vk.threshold, signers) {
+            Ok(lagrange) => lagrange,
+            Err(_) => return Ok(false),
+        };
+        let com_lagrange = PedersenCommitment::<P::G1>::commit(&vk.ck_2, &lagrange)?;
+        let agg_pk_valid = KeyAggregationIPA::<P, D>::verify(
+            v_srs,
+            &HomomorphicPlaceholderValue,
+            (
+                &vk.com_pks,
+                &com_lagrange,
+                &IdentityOutput(vec![aggregate.agg_pk]),
+            ),
+            &aggregate.agg_pk_proof,
+        )?;
+        Ok(agg_pk_valid
+            && aggregate.agg_pk == vk.group_pk
+            && Self::verify_partial(&aggregate.agg_pk, message, &aggregate.signature))
+    }
+
+    // Lagrange coefficients at zero for the signer set, zero for signers not taking part
+    fn lagrange_coefficients(
+        ck: &[P::G1],
+        threshold: usize,
+        signers: &[usize],
+    ) -> Result<Vec<P::ScalarField>, Error> {
+        let mut sorted_signers = signers.to_vec();
+        sorted_signers.sort_unstable();
+        sorted_signers.dedup();
+        if sorted_signers.len() != signers.len()
+            || signers.len() < threshold
+            || signers.iter().any(|i| *i >= ck.len())
+        {
+            return Err(InnerProductArgumentError::InnerProductInvalid);
+        }
+
+        let mut lagrange = vec![P::ScalarField::zero(); ck.len()];
+        for i in signers.iter() {
+            let x_i = P::ScalarField::from((i + 1) as u64);
+            let mut numerator = P::ScalarField::one();
+            let mut denominator = P::ScalarField::one();
+            for j in signers.iter().filter(|j| *j != i) {
+                let x_j = P::ScalarField::from((j + 1) as u64);
+                numerator *= &x_j;
+                denominator *= &(x_j - x_i);
+            }
+            lagrange[*i] = numerator * denominator.inverse().unwrap();
+        }
+        Ok(lagrange)
+    }
+
+    fn pad_public_keys(ck: &[P::G2], pks: &[P::G1]) -> Result<Vec<P::G1>, Error> {
+        if pks.len() > ck.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                pks.len(),
+                ck.len(),
+            ));
+        }
+        let mut padded = pks.to_vec();
+        padded.resize(ck.len(), <P::G1>::zero());
+        Ok(padded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_ff::UniformRand;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type G2 = <Bls12_381 as Pairing>::G2;
+    type TestThresholdBLS = ThresholdBLS<Bls12_381, Blake2b512>;
+
+    const TEST_SIZE: usize = 8;
+    const NUM_SIGNERS: usize = 7;
+    const THRESHOLD: usize = 4;
+
+    #[test]
+    fn threshold_bls_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let srs = TestThresholdBLS::setup(&mut rng, TEST_SIZE).unwrap();
+        let v_srs = srs.get_verifier_key();
+
+        let (group_pk, sks, pks) = TestThresholdBLS::deal_keys(&mut rng, THRESHOLD, NUM_SIGNERS);
+        let vk = TestThresholdBLS::get_verifier_key(&srs, &pks, &group_pk, THRESHOLD).unwrap();
+
+        let message = G2::rand(&mut rng);
+        let signers = vec![1, 2, 4, 6];
+        let partials = signers
+            .iter()
+            .map(|i| (*i, TestThresholdBLS::sign_partial(&sks[*i], &message)))
+            .collect::<Vec<(usize, G2)>>();
+
+        let aggregate =
+            TestThresholdBLS::aggregate(&srs, &pks, &message, THRESHOLD, &partials).unwrap();
+        assert!(TestThresholdBLS::verify(&v_srs, &vk, &message, &signers, &aggregate).unwrap());
+
+        // Wrong signer set, too few signers, and an invalid partial signature
+        assert!(
+            !TestThresholdBLS::verify(&v_srs, &vk, &message, &[0, 2, 4, 6], &aggregate).unwrap()
+        );
+        assert!(
+            TestThresholdBLS::aggregate(&srs, &pks, &message, THRESHOLD, &partials[1..]).is_err()
+        );
+        let mut wrong_partials = partials.clone();
+        wrong_partials[0].1 = G2::rand(&mut rng);
+        assert!(
+            TestThresholdBLS::aggregate(&srs, &pks, &message, THRESHOLD, &wrong_partials).is_err()
+        );
+    }
+}
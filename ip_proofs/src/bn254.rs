@@ -0,0 +1,237 @@
+use ark_bn254::Bn254;
+
+use crate::prelude;
+
+// Instantiations over BN254, the curve of the Ethereum pairing precompiles. Its scalar field is
+// large enough for the 128-bit GIPA challenges and KZG challenges sampled from a digest, and its
+// points use the same canonical serialization as other curves, so the protocols apply unchanged
+
+pub type PairingIPC = prelude::PairingIPC<Bn254>;
+pub type MultiExpIPC = prelude::MultiExpIPC<Bn254>;
+
+pub type PairingGIPA<D> = prelude::PairingGIPA<Bn254, D>;
+pub type PairingGIPAProof<D> = prelude::PairingGIPAProof<Bn254, D>;
+
+pub type PairingTIPA<D> = prelude::PairingTIPA<Bn254, D>;
+pub type PairingTIPAProof<D> = prelude::PairingTIPAProof<Bn254, D>;
+
+pub type MultiExpTIPA<D> = prelude::MultiExpTIPA<Bn254, D>;
+pub type MultiExpTIPAProof<D> = prelude::MultiExpTIPAProof<Bn254, D>;
+
+pub type SRS = prelude::SRS<Bn254>;
+pub type VerifierSRS = prelude::VerifierSRS<Bn254>;
+pub type AggregateProof<D> = prelude::AggregateProof<Bn254, D>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        applications::groth16_aggregation::{
+            aggregate_proofs, setup_inner_product, verify_aggregate_proof,
+        },
+        tipa::structured_scalar_message::structured_scalar_power,
+    };
+    use ark_bn254::{Fr, G1Projective, G2Projective};
+    use ark_dh_commitments::{
+        afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+        DoublyHomomorphicCommitment,
+    };
+    use ark_ff::{One, UniformRand};
+    use ark_groth16::Groth16;
+    use ark_inner_products::{InnerProduct, MultiexponentiationInnerProduct, PairingInnerProduct};
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+    };
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type GC1 = AFGHOCommitmentG1<Bn254>;
+    type GC2 = AFGHOCommitmentG2<Bn254>;
+
+    const TEST_SIZE: usize = 8;
+
+    fn random_pairing_messages(rng: &mut StdRng) -> (Vec<G1Projective>, Vec<G2Projective>) {
+        (
+            (0..TEST_SIZE).map(|_| G1Projective::rand(rng)).collect(),
+            (0..TEST_SIZE).map(|_| G2Projective::rand(rng)).collect(),
+        )
+    }
+
+    #[test]
+    fn bn254_gipa_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (ck_a, ck_b, ck_t) = PairingGIPA::<Blake2b512>::setup(&mut rng, TEST_SIZE).unwrap();
+        let (m_a, m_b) = random_pairing_messages(&mut rng);
+        let com_a = GC1::commit(&ck_a, &m_a).unwrap();
+        let com_b = GC2::commit(&ck_b, &m_b).unwrap();
+        let t = vec![PairingInnerProduct::<Bn254>::inner_product(&m_a, &m_b).unwrap()];
+        let com_t = PairingIPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
+
+        let proof = PairingGIPA::<Blake2b512>::prove(
+            (&m_a, &m_b, &t[0]),
+            (&ck_a, &ck_b, &ck_t),
+            (&com_a, &com_b, &com_t),
+        )
+        .unwrap();
+        assert!(PairingGIPA::<Blake2b512>::verify(
+            (&ck_a, &ck_b, &ck_t),
+            (&com_a, &com_b, &com_t),
+            &proof
+        )
+        .unwrap());
+
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let deserialized =
+            PairingGIPAProof::<Blake2b512>::deserialize_compressed(&bytes[..]).unwrap();
+        assert!(PairingGIPA::<Blake2b512>::verify(
+            (&ck_a, &ck_b, &ck_t),
+            (&com_a, &com_b, &com_t),
+            &deserialized
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn bn254_tipa_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = PairingTIPA::<Blake2b512>::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let (m_a, m_b) = random_pairing_messages(&mut rng);
+        let com_a = GC1::commit(&ck_a, &m_a).unwrap();
+        let com_b = GC2::commit(&ck_b, &m_b).unwrap();
+        let t = vec![PairingInnerProduct::<Bn254>::inner_product(&m_a, &m_b).unwrap()];
+        let com_t = PairingIPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
+
+        let proof =
+            PairingTIPA::<Blake2b512>::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let deserialized =
+            PairingTIPAProof::<Blake2b512>::deserialize_compressed(&bytes[..]).unwrap();
+        assert!(PairingTIPA::<Blake2b512>::verify(
+            &v_srs,
+            &ck_t,
+            (&com_a, &com_b, &com_t),
+            &deserialized
+        )
+        .unwrap());
+
+        let wrong_com_t = PairingIPC::commit(
+            ark_std::slice::from_ref(&ck_t),
+            &[t[0].clone() + t[0].clone()],
+        )
+        .unwrap();
+        assert!(!PairingTIPA::<Blake2b512>::verify(
+            &v_srs,
+            &ck_t,
+            (&com_a, &com_b, &wrong_com_t),
+            &proof
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn bn254_multiexponentiation_tipa_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = MultiExpTIPA::<Blake2b512>::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, _) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let (m_a, _) = random_pairing_messages(&mut rng);
+        let b = Fr::rand(&mut rng);
+        let m_b = structured_scalar_power(TEST_SIZE, &b);
+        let com_a = GC1::commit(&ck_a, &m_a).unwrap();
+        let t = vec![MultiexponentiationInnerProduct::inner_product(&m_a, &m_b).unwrap()];
+        let com_t = MultiExpIPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
+
+        let proof = MultiExpTIPA::<Blake2b512>::prove_with_structured_scalar_message(
+            &srs,
+            (&m_a, &m_b),
+            (&ck_a, &ck_t),
+        )
+        .unwrap();
+        assert!(
+            MultiExpTIPA::<Blake2b512>::verify_with_structured_scalar_message(
+                &v_srs,
+                &ck_t,
+                (&com_a, &com_t),
+                &b,
+                &proof
+            )
+            .unwrap()
+        );
+    }
+
+    // Proves knowledge of a witness equal to the sum of the public inputs
+    #[derive(Clone)]
+    struct TestCircuit {
+        public_inputs: Vec<Fr>,
+        witness_input: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for TestCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let witness_input = self.witness_input;
+            let mut sum = lc!();
+            for x in self.public_inputs {
+                sum = sum + cs.new_input_variable(|| Ok(x))?;
+            }
+            let witness = cs.new_witness_variable(|| Ok(witness_input))?;
+            cs.enforce_constraint(lc!() + witness, lc!() + Variable::One, sum)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn bn254_aggregate_proof_test() {
+        const NUM_PUBLIC_INPUTS: usize = 4;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let parameters = Groth16::<Bn254>::generate_random_parameters_with_reduction(
+            TestCircuit {
+                public_inputs: vec![Fr::default(); NUM_PUBLIC_INPUTS],
+                witness_input: Fr::default(),
+            },
+            &mut rng,
+        )
+        .unwrap();
+        let mut proofs = Vec::new();
+        let mut statements = Vec::new();
+        for _ in 0..TEST_SIZE {
+            let public_inputs = (0..NUM_PUBLIC_INPUTS)
+                .map(|_| Fr::rand(&mut rng))
+                .collect::<Vec<Fr>>();
+            let circuit = TestCircuit {
+                public_inputs: public_inputs.clone(),
+                witness_input: public_inputs.iter().sum(),
+            };
+            proofs.push(
+                Groth16::<Bn254>::create_random_proof_with_reduction(
+                    circuit,
+                    &parameters,
+                    &mut rng,
+                )
+                .unwrap(),
+            );
+            statements.push(public_inputs);
+        }
+        let vk = parameters.vk;
+
+        let srs: SRS = setup_inner_product::<Bn254, Blake2b512, _>(&mut rng, TEST_SIZE).unwrap();
+        let v_srs: VerifierSRS = srs.get_verifier_key();
+        let aggregate_proof = aggregate_proofs::<Bn254, Blake2b512>(&srs, &vk, &proofs).unwrap();
+
+        let mut bytes = Vec::new();
+        aggregate_proof.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), aggregate_proof.compressed_size());
+        let deserialized =
+            AggregateProof::<Blake2b512>::deserialize_compressed(&bytes[..]).unwrap();
+        assert!(verify_aggregate_proof(&v_srs, &vk, &statements, &deserialized).unwrap());
+
+        let mut wrong_statements = statements.clone();
+        wrong_statements[0][0] += &Fr::one();
+        assert!(!verify_aggregate_proof(&v_srs, &vk, &wrong_statements, &deserialized).unwrap());
+    }
+}
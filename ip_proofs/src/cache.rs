@@ -0,0 +1,305 @@
+use ark_dh_commitments::{
+    afgho16::AFGHOCommitmentG1,
+    key_digest,
+    pedersen::{PedersenCommitment, PreparedPedersenKey},
+};
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_groth16::{prepare_verifying_key, PreparedVerifyingKey, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use digest::Digest;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    applications::groth16_aggregation::verifying_key_digest,
+    tipa::{PreparedVerifierSRS, VerifierSRS},
+    Error,
+};
+
+type Entry = Arc<dyn Any + Send + Sync>;
+
+// Prepared forms of keys keyed by the fingerprint of the key, the digest of its canonical
+// serialization, for services verifying proofs under a handful of key sets. Each form is prepared
+// once and shared between threads. Forms that can be serialized are also kept in a directory when
+// one is given. Files carry the fingerprint of their key and a digest of their contents, so files
+// that are corrupt, truncated or of another key are prepared again. Their points are not checked
+// to be in the group, which costs more than preparing them, so the directory must not be writable
+// by untrusted parties
+#[derive(Default)]
+pub struct KeyCache {
+    entries: Mutex<HashMap<(TypeId, Vec<u8>), Entry>>,
+    dir: Option<PathBuf>,
+}
+
+impl KeyCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        KeyCache {
+            entries: Mutex::new(HashMap::new()),
+            dir: Some(dir.into()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Drops the prepared forms held in memory, keeping those on disk
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    // Prepared form of the key with `fingerprint`, computed by `prepare` on a miss. Threads missing
+    // at the same time may both prepare it, the first to finish being kept
+    pub fn get_or_prepare<T: Any + Send + Sync>(
+        &self,
+        fingerprint: &[u8],
+        prepare: impl FnOnce() -> T,
+    ) -> Arc<T> {
+        let key = (TypeId::of::<T>(), fingerprint.to_vec());
+        if let Some(entry) = self.entries.lock().unwrap().get(&key) {
+            return entry.clone().downcast().unwrap();
+        }
+        let entry: Entry = Arc::new(prepare());
+        let entry = self
+            .entries
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert(entry)
+            .clone();
+        entry.downcast().unwrap()
+    }
+
+    // As `get_or_prepare`, also reading and writing the prepared form in the directory of the
+    // cache under `kind` and the fingerprint. Files whose fingerprint or digest under `D` do not
+    // match are prepared again and overwritten
+    pub fn get_or_prepare_persistent<T, D>(
+        &self,
+        kind: &str,
+        fingerprint: &[u8],
+        prepare: impl FnOnce() -> T,
+    ) -> Result<Arc<T>, Error>
+    where
+        T: Any + Send + Sync + CanonicalSerialize + CanonicalDeserialize,
+        D: Digest,
+    {
+        let path = match &self.dir {
+            Some(dir) => dir.join(format!("{}-{}.bin", kind, hex(fingerprint))),
+            None => return Ok(self.get_or_prepare(fingerprint, prepare)),
+        };
+        let mut written = Ok(());
+        let prepared = self.get_or_prepare(fingerprint, || {
+            if let Some(prepared) = fs::read(&path)
+                .ok()
+                .and_then(|bytes| read_checked::<T, D>(&bytes, fingerprint))
+            {
+                return prepared;
+            }
+            let prepared = prepare();
+            written = write_atomic::<T, D>(&path, fingerprint, &prepared);
+            prepared
+        });
+        written?;
+        Ok(prepared)
+    }
+
+    pub fn prepared_verifying_key<P: Pairing, D: Digest>(
+        &self,
+        vk: &VerifyingKey<P>,
+    ) -> Result<Arc<PreparedVerifyingKey<P>>, Error> {
+        let fingerprint = verifying_key_digest::<P, D>(vk)?;
+        Ok(self.get_or_prepare(&fingerprint, || prepare_verifying_key(vk)))
+    }
+
+    pub fn prepared_verifier_srs<P: Pairing, D: Digest>(
+        &self,
+        v_srs: &VerifierSRS<P>,
+    ) -> Result<Arc<PreparedVerifierSRS<P>>, Error> {
+        let fingerprint = D::digest(&canonical_bytes!(v_srs)?).to_vec();
+        Ok(self.get_or_prepare(&fingerprint, || v_srs.prepare()))
+    }
+
+    // AFGHO commitment key in G2 prepared for pairings, e.g. the key of the messages in G1 under
+    // an SRS used for every proof
+    pub fn prepared_afgho_key<P: Pairing, D: Digest>(
+        &self,
+        k: &[P::G2],
+    ) -> Result<Arc<Vec<P::G2Prepared>>, Error> {
+        let fingerprint = key_digest::<D, P::G2>(k)?;
+        Ok(self.get_or_prepare(&fingerprint, || AFGHOCommitmentG1::<P>::prepare_key(k)))
+    }
+
+    // Window tables of a Pedersen commitment key, kept on disk when the cache has a directory
+    pub fn prepared_pedersen_key<G: CurveGroup, D: Digest>(
+        &self,
+        k: &[G],
+    ) -> Result<Arc<PreparedPedersenKey<G>>, Error> {
+        let fingerprint = key_digest::<D, G>(k)?;
+        self.get_or_prepare_persistent::<_, D>("pedersen", &fingerprint, || {
+            PedersenCommitment::<G>::prepare_key(k)
+        })
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Files hold the fingerprint of the key, the digest of the fingerprint and the prepared form, and
+// the prepared form serialized uncompressed
+fn file_digest<D: Digest>(fingerprint: &[u8], prepared: &[u8]) -> Vec<u8> {
+    let mut hasher = D::new();
+    hasher.update(fingerprint);
+    hasher.update(prepared);
+    hasher.finalize().to_vec()
+}
+
+fn read_checked<T: CanonicalDeserialize, D: Digest>(bytes: &[u8], fingerprint: &[u8]) -> Option<T> {
+    let mut reader = bytes;
+    let stored_fingerprint = Vec::<u8>::deserialize_uncompressed(&mut reader).ok()?;
+    let digest = Vec::<u8>::deserialize_uncompressed(&mut reader).ok()?;
+    if stored_fingerprint != fingerprint || digest != file_digest::<D>(fingerprint, reader) {
+        return None;
+    }
+    let prepared = T::deserialize_uncompressed_unchecked(&mut reader).ok()?;
+    reader.is_empty().then_some(prepared)
+}
+
+// Written to a temporary file renamed into place, so readers never see a partial file
+fn write_atomic<T: CanonicalSerialize, D: Digest>(
+    path: &PathBuf,
+    fingerprint: &[u8],
+    value: &T,
+) -> Result<(), Error> {
+    let mut prepared = Vec::new();
+    value.serialize_uncompressed(&mut prepared)?;
+    let mut bytes = Vec::new();
+    fingerprint.to_vec().serialize_uncompressed(&mut bytes)?;
+    file_digest::<D>(fingerprint, &prepared).serialize_uncompressed(&mut bytes)?;
+    bytes.extend(prepared);
+    let tmp = path.with_extension(format!("tmp-{}", std::process::id()));
+    fs::write(&tmp, &bytes)
+        .and_then(|_| fs::rename(&tmp, path))
+        .map_err(|e| SerializationError::from(e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::PairingTIPA;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective};
+    use ark_dh_commitments::{random_generators, DoublyHomomorphicCommitment};
+    use ark_ff::UniformRand;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn key_cache_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, _) = PairingTIPA::<Bls12_381, Blake2b512>::setup(&mut rng, TEST_SIZE).unwrap();
+        let v_srs = srs.get_verifier_key();
+        let cache = KeyCache::new();
+        let prepared = cache
+            .prepared_verifier_srs::<Bls12_381, Blake2b512>(&v_srs)
+            .unwrap();
+        assert!(Arc::ptr_eq(
+            &prepared,
+            &cache
+                .prepared_verifier_srs::<Bls12_381, Blake2b512>(&v_srs)
+                .unwrap()
+        ));
+        let other_v_srs = PairingTIPA::<Bls12_381, Blake2b512>::setup(&mut rng, TEST_SIZE)
+            .unwrap()
+            .0
+            .get_verifier_key();
+        assert!(!Arc::ptr_eq(
+            &prepared,
+            &cache
+                .prepared_verifier_srs::<Bls12_381, Blake2b512>(&other_v_srs)
+                .unwrap()
+        ));
+        assert_eq!(cache.len(), 2);
+
+        let (ck_a, _) = srs.get_commitment_keys();
+        let prepared_ck_a = cache
+            .prepared_afgho_key::<Bls12_381, Blake2b512>(&ck_a)
+            .unwrap();
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            AFGHOCommitmentG1::<Bls12_381>::commit_prepared(&prepared_ck_a, &m_a).unwrap(),
+            AFGHOCommitmentG1::<Bls12_381>::commit(&ck_a, &m_a).unwrap()
+        );
+
+        // Window tables read back from disk by another cache over the same directory
+        let dir = std::env::temp_dir().join(format!("ripp-key-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let k = random_generators::<_, G1Projective>(&mut rng, TEST_SIZE);
+        let m = (0..TEST_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let prepared_k = KeyCache::with_dir(&dir)
+            .prepared_pedersen_key::<_, Blake2b512>(&k)
+            .unwrap();
+        let cache = KeyCache::with_dir(&dir);
+        let loaded_k = cache
+            .prepared_pedersen_key::<G1Projective, Blake2b512>(&k)
+            .unwrap();
+        let expected = PedersenCommitment::<G1Projective>::commit(&k, &m).unwrap();
+        assert_eq!(
+            PedersenCommitment::commit_prepared(&prepared_k, &m).unwrap(),
+            expected
+        );
+        assert_eq!(
+            PedersenCommitment::commit_prepared(&loaded_k, &m).unwrap(),
+            expected
+        );
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        // Corrupt files and files of another key are prepared again and overwritten
+        let path = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+        let bytes = fs::read(&path).unwrap();
+        let mut corrupt = bytes.clone();
+        *corrupt.last_mut().unwrap() ^= 1;
+        for contents in [&corrupt[..], &bytes[..bytes.len() - 1]] {
+            fs::write(&path, contents).unwrap();
+            let reloaded_k = KeyCache::with_dir(&dir)
+                .prepared_pedersen_key::<G1Projective, Blake2b512>(&k)
+                .unwrap();
+            assert_eq!(
+                PedersenCommitment::commit_prepared(&reloaded_k, &m).unwrap(),
+                expected
+            );
+            assert_eq!(fs::read(&path).unwrap(), bytes);
+        }
+        let other_k = random_generators::<_, G1Projective>(&mut rng, TEST_SIZE);
+        let other_fingerprint = key_digest::<Blake2b512, _>(&other_k).unwrap();
+        let other_path = dir.join(format!("pedersen-{}.bin", hex(&other_fingerprint)));
+        fs::write(&other_path, &bytes).unwrap();
+        let other_prepared = KeyCache::with_dir(&dir)
+            .prepared_pedersen_key::<G1Projective, Blake2b512>(&other_k)
+            .unwrap();
+        assert_eq!(
+            PedersenCommitment::commit_prepared(&other_prepared, &m).unwrap(),
+            PedersenCommitment::<G1Projective>::commit(&other_k, &m).unwrap()
+        );
+        assert_ne!(fs::read(&other_path).unwrap(), bytes);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
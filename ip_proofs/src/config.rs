@@ -0,0 +1,380 @@
+use ark_bls12_377::Bls12_377;
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
+use ark_dh_commitments::{
+    afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+    pedersen::PedersenCommitment,
+    DoublyHomomorphicCommitment,
+};
+use ark_ec::pairing::Pairing;
+use ark_inner_products::{InnerProduct, PairingInnerProduct, ScalarInnerProduct};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{fmt, str::FromStr, string::ToString, vec::Vec};
+use blake2::Blake2b512;
+use rand::{CryptoRng, RngCore};
+use sha2::Sha256;
+
+use crate::{
+    gipa::{GIPAProof, GIPA},
+    prelude::{Blake3, PairingIPC, ScalarIPC, Sha3_256},
+    transcript::TranscriptDigest,
+    Error, InnerProductArgumentError,
+};
+
+// Protocol parameters selectable at runtime, e.g. from a configuration file, instead of fixing the
+// generics at compile time. The GIPA operations of `ProtocolConfig` dispatch to the instantiation
+// for the configured curve, hash function and commitment family, and take and return canonical
+// serializations:
+// - keys are (ck_a, ck_b, ck_t) and messages are (m_a, m_b)
+// - AFGHO commits to messages in G1 and G2 with pairing inner products
+// - Pedersen commits to scalar messages in G1 with scalar inner products
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Curve {
+    Bls12381,
+    Bls12377,
+    Bn254,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HashFunction {
+    Blake2b512,
+    Sha256,
+    Sha3_256,
+    Blake3,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CommitmentFamily {
+    AFGHO,
+    Pedersen,
+}
+
+const CURVE_NAMES: [(&str, Curve); 3] = [
+    ("bls12-381", Curve::Bls12381),
+    ("bls12-377", Curve::Bls12377),
+    ("bn254", Curve::Bn254),
+];
+
+const HASH_NAMES: [(&str, HashFunction); 4] = [
+    ("blake2b", HashFunction::Blake2b512),
+    ("sha256", HashFunction::Sha256),
+    ("sha3-256", HashFunction::Sha3_256),
+    ("blake3", HashFunction::Blake3),
+];
+
+const COMMITMENT_NAMES: [(&str, CommitmentFamily); 2] = [
+    ("afgho", CommitmentFamily::AFGHO),
+    ("pedersen", CommitmentFamily::Pedersen),
+];
+
+macro_rules! impl_named_option {
+    ($option:ty, $names:expr, $what:literal) => {
+        impl FromStr for $option {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $names
+                    .iter()
+                    .find(|(name, _)| *name == s)
+                    .map(|(_, option)| *option)
+                    .ok_or_else(|| InnerProductArgumentError::UnknownOption {
+                        option: $what,
+                        name: s.to_string(),
+                    })
+            }
+        }
+
+        impl fmt::Display for $option {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let (name, _) = $names.iter().find(|(_, o)| o == self).unwrap();
+                f.write_str(name)
+            }
+        }
+    };
+}
+
+impl_named_option!(Curve, CURVE_NAMES, "curve");
+impl_named_option!(HashFunction, HASH_NAMES, "hash function");
+impl_named_option!(CommitmentFamily, COMMITMENT_NAMES, "commitment family");
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ProtocolConfig {
+    pub curve: Curve,
+    pub hash: HashFunction,
+    pub commitment: CommitmentFamily,
+}
+
+impl Default for ProtocolConfig {
+    fn default() -> Self {
+        ProtocolConfig {
+            curve: Curve::Bls12381,
+            hash: HashFunction::Blake2b512,
+            commitment: CommitmentFamily::AFGHO,
+        }
+    }
+}
+
+// Builds a configuration from the defaults of BLS12-381, BLAKE2b and AFGHO commitments
+#[derive(Clone, Debug, Default)]
+pub struct ProtocolConfigBuilder {
+    config: ProtocolConfig,
+}
+
+impl ProtocolConfigBuilder {
+    pub fn curve(mut self, curve: Curve) -> Self {
+        self.config.curve = curve;
+        self
+    }
+
+    pub fn hash(mut self, hash: HashFunction) -> Self {
+        self.config.hash = hash;
+        self
+    }
+
+    pub fn commitment(mut self, commitment: CommitmentFamily) -> Self {
+        self.config.commitment = commitment;
+        self
+    }
+
+    // Sets an option from configuration strings, e.g. ("curve", "bn254"), ("hash", "sha256") or
+    // ("commitment", "pedersen")
+    pub fn option(self, key: &str, value: &str) -> Result<Self, Error> {
+        Ok(match key {
+            "curve" => self.curve(value.parse()?),
+            "hash" => self.hash(value.parse()?),
+            "commitment" => self.commitment(value.parse()?),
+            _ => {
+                return Err(InnerProductArgumentError::UnknownOption {
+                    option: "option",
+                    name: key.to_string(),
+                })
+            }
+        })
+    }
+
+    pub fn build(self) -> ProtocolConfig {
+        self.config
+    }
+}
+
+// Calls the function generic over (IP, LMC, RMC, IPC, D) instantiated for the configuration
+macro_rules! dispatch {
+    ($config:expr, $f:ident($($arg:expr),*)) => {
+        match $config.curve {
+            Curve::Bls12381 => dispatch!(@hash $config, Bls12_381, $f($($arg),*)),
+            Curve::Bls12377 => dispatch!(@hash $config, Bls12_377, $f($($arg),*)),
+            Curve::Bn254 => dispatch!(@hash $config, Bn254, $f($($arg),*)),
+        }
+    };
+    (@hash $config:expr, $P:ty, $f:ident($($arg:expr),*)) => {
+        match $config.hash {
+            HashFunction::Blake2b512 => dispatch!(@commitment $config, $P, Blake2b512, $f($($arg),*)),
+            HashFunction::Sha256 => dispatch!(@commitment $config, $P, Sha256, $f($($arg),*)),
+            HashFunction::Sha3_256 => dispatch!(@commitment $config, $P, Sha3_256, $f($($arg),*)),
+            HashFunction::Blake3 => dispatch!(@commitment $config, $P, Blake3, $f($($arg),*)),
+        }
+    };
+    (@commitment $config:expr, $P:ty, $D:ty, $f:ident($($arg:expr),*)) => {
+        match $config.commitment {
+            CommitmentFamily::AFGHO => $f::<
+                PairingInnerProduct<$P>,
+                AFGHOCommitmentG1<$P>,
+                AFGHOCommitmentG2<$P>,
+                PairingIPC<$P>,
+                $D,
+            >($($arg),*),
+            CommitmentFamily::Pedersen => $f::<
+                ScalarInnerProduct<<$P as Pairing>::ScalarField>,
+                PedersenCommitment<<$P as Pairing>::G1>,
+                PedersenCommitment<<$P as Pairing>::G1>,
+                ScalarIPC<$P>,
+                $D,
+            >($($arg),*),
+        }
+    };
+}
+
+impl ProtocolConfig {
+    pub fn builder() -> ProtocolConfigBuilder {
+        ProtocolConfigBuilder::default()
+    }
+
+    // Commitment keys for messages of length `size`
+    pub fn setup<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        size: usize,
+    ) -> Result<Vec<u8>, Error> {
+        dispatch!(self, setup(rng, size))
+    }
+
+    // Commitments to the messages and to their inner product, the statement of `verify`
+    pub fn commit(&self, ck: &[u8], values: &[u8]) -> Result<Vec<u8>, Error> {
+        dispatch!(self, commit(ck, values))
+    }
+
+    pub fn prove(&self, ck: &[u8], values: &[u8]) -> Result<Vec<u8>, Error> {
+        dispatch!(self, prove(ck, values))
+    }
+
+    pub fn verify(&self, ck: &[u8], com: &[u8], proof: &[u8]) -> Result<bool, Error> {
+        dispatch!(self, verify(ck, com, proof))
+    }
+}
+
+type Keys<LMC, RMC, IPC> = (
+    Vec<<LMC as DoublyHomomorphicCommitment>::Key>,
+    Vec<<RMC as DoublyHomomorphicCommitment>::Key>,
+    <IPC as DoublyHomomorphicCommitment>::Key,
+);
+
+fn to_bytes(value: &impl CanonicalSerialize) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn setup<IP, LMC, RMC, IPC, D>(
+    rng: &mut (impl RngCore + CryptoRng),
+    size: usize,
+) -> Result<Vec<u8>, Error>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    to_bytes(&<GIPA<IP, LMC, RMC, IPC, D>>::setup(rng, size)?)
+}
+
+fn commit<IP, LMC, RMC, IPC, D>(ck: &[u8], values: &[u8]) -> Result<Vec<u8>, Error>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: CanonicalDeserialize,
+    RMC::Message: CanonicalDeserialize,
+{
+    let (ck_a, ck_b, ck_t) = Keys::<LMC, RMC, IPC>::deserialize_compressed(ck)?;
+    let (m_a, m_b) = <(Vec<LMC::Message>, Vec<RMC::Message>)>::deserialize_compressed(values)?;
+    to_bytes(&<GIPA<IP, LMC, RMC, IPC, D>>::commit(
+        (&m_a, &m_b),
+        (&ck_a, &ck_b, &ck_t),
+    )?)
+}
+
+fn prove<IP, LMC, RMC, IPC, D>(ck: &[u8], values: &[u8]) -> Result<Vec<u8>, Error>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: CanonicalSerialize + CanonicalDeserialize,
+    RMC::Message: CanonicalSerialize + CanonicalDeserialize,
+{
+    let (ck_a, ck_b, ck_t) = Keys::<LMC, RMC, IPC>::deserialize_compressed(ck)?;
+    let (m_a, m_b) = <(Vec<LMC::Message>, Vec<RMC::Message>)>::deserialize_compressed(values)?;
+    let (proof, _) =
+        <GIPA<IP, LMC, RMC, IPC, D>>::prove_with_aux((&m_a, &m_b), (&ck_a, &ck_b, &[ck_t]))?;
+    to_bytes(&proof)
+}
+
+fn verify<IP, LMC, RMC, IPC, D>(ck: &[u8], com: &[u8], proof: &[u8]) -> Result<bool, Error>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: CanonicalDeserialize,
+    RMC::Message: CanonicalDeserialize,
+{
+    let (ck_a, ck_b, ck_t) = Keys::<LMC, RMC, IPC>::deserialize_compressed(ck)?;
+    let (com_a, com_b, com_t) =
+        <(LMC::Output, RMC::Output, IPC::Output)>::deserialize_compressed(com)?;
+    let proof = GIPAProof::<IP, LMC, RMC, IPC, D>::deserialize_compressed(proof)?;
+    <GIPA<IP, LMC, RMC, IPC, D>>::verify((&ck_a, &ck_b, &ck_t), (&com_a, &com_b, &com_t), &proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    // Takes every parameter that `dispatch!` passes, though only the commitments are used
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn random_values<IP, LMC, RMC, IPC, D>(rng: &mut StdRng) -> Vec<u8>
+    where
+        IP: InnerProduct,
+        LMC: DoublyHomomorphicCommitment,
+        RMC: DoublyHomomorphicCommitment,
+        LMC::Message: UniformRand + CanonicalSerialize,
+        RMC::Message: UniformRand + CanonicalSerialize,
+    {
+        let m_a = (0..TEST_SIZE)
+            .map(|_| LMC::Message::rand(rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| RMC::Message::rand(rng))
+            .collect::<Vec<_>>();
+        to_bytes(&(m_a, m_b)).unwrap()
+    }
+
+    #[test]
+    fn protocol_config_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        for curve in ["bls12-381", "bls12-377", "bn254"].iter() {
+            for hash in ["blake2b", "sha256", "sha3-256", "blake3"].iter() {
+                for commitment in ["afgho", "pedersen"].iter() {
+                    let config = ProtocolConfig::builder()
+                        .option("curve", curve)
+                        .unwrap()
+                        .option("hash", hash)
+                        .unwrap()
+                        .option("commitment", commitment)
+                        .unwrap()
+                        .build();
+                    assert_eq!(config.curve.to_string(), *curve);
+
+                    let ck = config.setup(&mut rng, TEST_SIZE).unwrap();
+                    let values = dispatch!(config, random_values(&mut rng));
+                    let com = config.commit(&ck, &values).unwrap();
+                    let proof = config.prove(&ck, &values).unwrap();
+                    assert!(config.verify(&ck, &com, &proof).unwrap());
+
+                    let other_values = dispatch!(config, random_values(&mut rng));
+                    let other_com = config.commit(&ck, &other_values).unwrap();
+                    assert!(!config.verify(&ck, &other_com, &proof).unwrap());
+                }
+            }
+        }
+
+        assert_eq!(ProtocolConfig::builder().build(), ProtocolConfig::default());
+        assert!(ProtocolConfig::builder().option("curve", "mnt4").is_err());
+        assert!(ProtocolConfig::builder().option("field", "bn254").is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::One;
+use ark_serialize::CanonicalSerialize;
+
+use crate::{Error, InnerProductArgumentError};
+
+// Arguments for the inner pairing product of messages in G1 and G2, compared by `estimate`:
+// - GIPA with AFGHO commitments and keys as long as the messages, so the verifier folds the keys
+// - SIPP over the messages themselves, with no commitments and a verifier reading them in full
+// - TIPA with the keys folded by the prover and opened against a KZG SRS
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Protocol {
+    GIPA,
+    SIPP,
+    TIPA,
+}
+
+// Costs of proving and verifying one instance, counted in the operations that dominate them.
+// Scalar multiplications include the terms of multi-scalar multiplications, and pairings are
+// counted as Miller loops with the final exponentiations apart
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CostEstimate {
+    pub proof_bytes: usize,
+    pub prover_pairings: usize,
+    pub prover_g1_muls: usize,
+    pub prover_g2_muls: usize,
+    pub verifier_pairings: usize,
+    pub verifier_final_exponentiations: usize,
+    pub verifier_msm_terms: usize,
+    pub verifier_gt_exponentiations: usize,
+}
+
+// Compressed sizes of the curve elements making up proofs
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ElementSizes {
+    pub g1: usize,
+    pub g2: usize,
+    pub gt: usize,
+}
+
+impl ElementSizes {
+    pub fn of<P: Pairing>() -> Self {
+        ElementSizes {
+            g1: P::G1Affine::zero().compressed_size(),
+            g2: P::G2Affine::zero().compressed_size(),
+            gt: P::TargetField::one().compressed_size(),
+        }
+    }
+}
+
+// Estimated costs of `protocol` for messages of length `len` over the curve of `P`, a power of two
+// of at least 2. Proof sizes are exact for the serializations of this crate, and for SIPP as given
+// by `Proof::size_in_bytes`
+pub fn estimate<P: Pairing>(protocol: Protocol, len: usize) -> Result<CostEstimate, Error> {
+    if len < 2 || !len.is_power_of_two() {
+        return Err(InnerProductArgumentError::MessageLengthInvalid(
+            len,
+            len.next_power_of_two().max(2),
+        ));
+    }
+    let sizes = ElementSizes::of::<P>();
+    let rounds = len.trailing_zeros() as usize;
+    // Length prefix of the round commitments, and of each inner product commitment as it is a
+    // vector of one element
+    let vec_prefix = 0u64.compressed_size();
+
+    Ok(match protocol {
+        // Each round commits to both halves of both messages and their inner products, then folds
+        // the messages and keys. The verifier folds the keys with one MSM in each group
+        Protocol::GIPA => CostEstimate {
+            proof_bytes: vec_prefix
+                + rounds * 2 * (3 * sizes.gt + vec_prefix)
+                + sizes.g1
+                + sizes.g2,
+            prover_pairings: 6 * (len - 1),
+            prover_g1_muls: 2 * (len - 1),
+            prover_g2_muls: 2 * (len - 1),
+            verifier_pairings: 3,
+            verifier_final_exponentiations: 3,
+            verifier_msm_terms: 2 * len,
+            verifier_gt_exponentiations: 6 * rounds,
+        },
+        // The messages are rescaled once, then each round pairs the cross halves only. The
+        // verifier folds the messages with one MSM in each group
+        Protocol::SIPP => CostEstimate {
+            proof_bytes: rounds * 2 * sizes.gt,
+            prover_pairings: 2 * (len - 1),
+            prover_g1_muls: len + (len - 1),
+            prover_g2_muls: len - 1,
+            verifier_pairings: 1,
+            verifier_final_exponentiations: 1,
+            verifier_msm_terms: 2 * len,
+            verifier_gt_exponentiations: 2 * rounds,
+        },
+        // GIPA, then an opening of each folded key against the SRS. The verifier evaluates the
+        // key polynomials from the challenges and checks the openings with two pairings each
+        Protocol::TIPA => CostEstimate {
+            proof_bytes: estimate::<P>(Protocol::GIPA, len)?.proof_bytes
+                + 2 * (sizes.g1 + sizes.g2),
+            prover_pairings: 6 * (len - 1),
+            prover_g1_muls: 2 * (len - 1) + len,
+            prover_g2_muls: 2 * (len - 1) + len,
+            verifier_pairings: 3 + 4,
+            verifier_final_exponentiations: 3 + 2,
+            verifier_msm_terms: 4,
+            verifier_gt_exponentiations: 6 * rounds,
+        },
+    })
+}
+
+// Estimates of every protocol for messages of length `len`, for choosing one programmatically
+pub fn compare<P: Pairing>(len: usize) -> Result<[(Protocol, CostEstimate); 3], Error> {
+    Ok([
+        (Protocol::GIPA, estimate::<P>(Protocol::GIPA, len)?),
+        (Protocol::SIPP, estimate::<P>(Protocol::SIPP, len)?),
+        (Protocol::TIPA, estimate::<P>(Protocol::TIPA, len)?),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{PairingGIPA, PairingTIPA};
+    use ark_bls12_381::{Bls12_381, G1Projective, G2Projective};
+    use ark_bn254::Bn254;
+    use ark_ff::UniformRand;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn cost_model_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        // Proof sizes match the serializations
+        let (ck_a, ck_b, ck_t) =
+            PairingGIPA::<Bls12_381, Blake2b512>::setup(&mut rng, TEST_SIZE).unwrap();
+        let (proof, _) = PairingGIPA::<Bls12_381, Blake2b512>::prove_with_aux(
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, &[ck_t]),
+        )
+        .unwrap();
+        let gipa = estimate::<Bls12_381>(Protocol::GIPA, TEST_SIZE).unwrap();
+        assert_eq!(gipa.proof_bytes, proof.compressed_size());
+
+        let (srs, ck_t) = PairingTIPA::<Bls12_381, Blake2b512>::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let proof =
+            PairingTIPA::<Bls12_381, Blake2b512>::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t))
+                .unwrap();
+        let tipa = estimate::<Bls12_381>(Protocol::TIPA, TEST_SIZE).unwrap();
+        assert_eq!(tipa.proof_bytes, proof.compressed_size());
+
+        // TIPA trades a larger proof and more pairings for a verifier without linear MSMs
+        let estimates = compare::<Bls12_381>(1 << 10).unwrap();
+        let (_, tipa) = estimates[2];
+        assert!(estimates
+            .iter()
+            .all(|(_, other)| tipa.verifier_msm_terms <= other.verifier_msm_terms));
+        assert!(estimates[1].1.proof_bytes < estimates[0].1.proof_bytes);
+        assert!(
+            estimate::<Bn254>(Protocol::TIPA, TEST_SIZE)
+                .unwrap()
+                .proof_bytes
+                < estimate::<Bls12_381>(Protocol::TIPA, TEST_SIZE)
+                    .unwrap()
+                    .proof_bytes
+        );
+
+        assert!(estimate::<Bls12_381>(Protocol::SIPP, 1).is_err());
+        assert!(estimate::<Bls12_381>(Protocol::GIPA, 6).is_err());
+    }
+}
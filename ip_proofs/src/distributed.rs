@@ -0,0 +1,588 @@
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Write,
+};
+use ark_std::{marker::PhantomData, vec::Vec};
+
+use crate::{
+    gipa::{GIPAProverState, GIPA},
+    transcript::TranscriptDigest,
+    Error, InnerProductArgumentError,
+};
+use ark_dh_commitments::DoublyHomomorphicCommitment;
+use ark_inner_products::InnerProduct;
+
+// GIPA prover split across workers, e.g. on different machines, that each hold a shard of the
+// messages and keys. Worker `i` of `k` holds the positions congruent to `i` mod `k`, so every
+// round pairs positions held by the same worker and the shards fold locally just as the full
+// vectors would. Each round the workers send their share of the round commitments to the
+// coordinator, which sums them, derives the challenge and sends it back for the workers to fold
+// with. Once every shard is a single element, the coordinator gathers them into a prover state
+// and runs the last log k rounds itself, with `GIPA::prove_from_state` or the TIPA equivalents.
+//
+// Shares sum to the round commitments of the full vectors because the commitments are sums over
+// positions, as AFGHO and Pedersen commitments are. Shares, challenges and final shares serialize
+// canonically, leaving the transport between the coordinator and workers to the caller
+
+// Positions of the messages or keys held by worker `index` of `num_shards`
+pub fn shard<T: Clone>(values: &[T], index: usize, num_shards: usize) -> Vec<T> {
+    values
+        .iter()
+        .skip(index)
+        .step_by(num_shards)
+        .cloned()
+        .collect()
+}
+
+// A worker's commitments to the cross terms of a round and their inner products
+pub struct RoundShare<LMC, RMC, IPC>
+where
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    com_1: (LMC::Output, RMC::Output, IPC::Message),
+    com_2: (LMC::Output, RMC::Output, IPC::Message),
+}
+
+// A worker's messages and keys once its shard is folded to a single element
+pub struct FinalShare<LMC, RMC>
+where
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    m_a: LMC::Message,
+    m_b: RMC::Message,
+    ck_a: LMC::Key,
+    ck_b: RMC::Key,
+}
+
+pub struct GIPAWorker<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    m_a: Vec<LMC::Message>,
+    m_b: Vec<RMC::Message>,
+    ck_a: Vec<LMC::Key>,
+    ck_b: Vec<RMC::Key>,
+    _gipa: PhantomData<GIPA<IP, LMC, RMC, IPC, D>>,
+}
+
+impl<IP, LMC, RMC, IPC, D> GIPAWorker<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    // Worker for a shard of the messages and keys, as selected by `shard`
+    pub fn new(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key]),
+    ) -> Result<Self, Error> {
+        let (m_a, m_b) = values;
+        let (ck_a, ck_b) = ck;
+        if !m_a.len().is_power_of_two() || m_a.len() != m_b.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                m_a.len(),
+                m_b.len(),
+            ));
+        }
+        if ck_a.len() != m_a.len() || ck_b.len() != m_b.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                ck_a.len(),
+                ck_b.len(),
+            ));
+        }
+        Ok(GIPAWorker {
+            m_a: m_a.to_vec(),
+            m_b: m_b.to_vec(),
+            ck_a: ck_a.to_vec(),
+            ck_b: ck_b.to_vec(),
+            _gipa: PhantomData,
+        })
+    }
+
+    // Length of the shard left to fold
+    pub fn len(&self) -> usize {
+        self.m_a.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.m_a.is_empty()
+    }
+
+    pub fn round_share(&self) -> Result<RoundShare<LMC, RMC, IPC>, Error> {
+        if self.len() < 2 {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                self.len(),
+                self.len(),
+            ));
+        }
+        let _share = phase_span!("worker_round", len = self.len());
+        let (com_1, com_2) = <GIPA<IP, LMC, RMC, IPC, D>>::_commit_halves(
+            (&self.m_a, &self.m_b),
+            (&self.ck_a, &self.ck_b),
+        )?;
+        Ok(RoundShare { com_1, com_2 })
+    }
+
+    // Folds the shard with the challenge and its inverse sent by the coordinator
+    pub fn fold(&mut self, challenge: &(LMC::Scalar, LMC::Scalar)) -> Result<(), Error> {
+        <GIPA<IP, LMC, RMC, IPC, D>>::_fold_round(
+            (&mut self.m_a, &mut self.m_b),
+            (&mut self.ck_a, &mut self.ck_b),
+            (&challenge.0, &challenge.1),
+        )
+    }
+
+    pub fn into_final_share(self) -> Result<FinalShare<LMC, RMC>, Error> {
+        if self.len() != 1 {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                self.len(),
+                1,
+            ));
+        }
+        let GIPAWorker {
+            mut m_a,
+            mut m_b,
+            mut ck_a,
+            mut ck_b,
+            ..
+        } = self;
+        Ok(FinalShare {
+            m_a: m_a.remove(0),
+            m_b: m_b.remove(0),
+            ck_a: ck_a.remove(0),
+            ck_b: ck_b.remove(0),
+        })
+    }
+}
+
+pub struct GIPACoordinator<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    len: usize,
+    num_shards: usize,
+    ck_t: Vec<IPC::Key>,
+    r_commitment_steps: Vec<(
+        (LMC::Output, RMC::Output, IPC::Output),
+        (LMC::Output, RMC::Output, IPC::Output),
+    )>,
+    r_transcript: Vec<LMC::Scalar>,
+    _gipa: PhantomData<GIPA<IP, LMC, RMC, IPC, D>>,
+}
+
+impl<IP, LMC, RMC, IPC, D> GIPACoordinator<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    // Coordinator for messages of length `len` split into `num_shards` shards
+    pub fn new(len: usize, num_shards: usize, ck_t: &[IPC::Key]) -> Result<Self, Error> {
+        if !len.is_power_of_two() || !num_shards.is_power_of_two() || num_shards > len {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                len, num_shards,
+            ));
+        }
+        Ok(GIPACoordinator {
+            len,
+            num_shards,
+            ck_t: ck_t.to_vec(),
+            r_commitment_steps: Vec::new(),
+            r_transcript: Vec::new(),
+            _gipa: PhantomData,
+        })
+    }
+
+    pub fn num_shards(&self) -> usize {
+        self.num_shards
+    }
+
+    // Rounds left for the workers before they send their final shares
+    pub fn worker_rounds(&self) -> usize {
+        (self.len / self.num_shards).trailing_zeros() as usize
+    }
+
+    // Sums the shares of a round, one from each worker, and returns the challenge and its
+    // inverse for the workers to fold with
+    pub fn combine_round(
+        &mut self,
+        shares: &[RoundShare<LMC, RMC, IPC>],
+    ) -> Result<(LMC::Scalar, LMC::Scalar), Error> {
+        if self.worker_rounds() == 0 || shares.len() != self.num_shards {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                shares.len(),
+                self.num_shards,
+            ));
+        }
+        let _combine = phase_span!("combine_round", len = self.len);
+        phase_count!(gipa_rounds, 1);
+        let sum = |a: &(LMC::Output, RMC::Output, IPC::Message),
+                   b: &(LMC::Output, RMC::Output, IPC::Message)| {
+            (
+                a.0.clone() + b.0.clone(),
+                a.1.clone() + b.1.clone(),
+                a.2.clone() + b.2.clone(),
+            )
+        };
+        let mut com_1 = shares[0].com_1.clone();
+        let mut com_2 = shares[0].com_2.clone();
+        for share in &shares[1..] {
+            com_1 = sum(&com_1, &share.com_1);
+            com_2 = sum(&com_2, &share.com_2);
+        }
+        let com_1 = (com_1.0, com_1.1, IPC::commit(&self.ck_t, &[com_1.2])?);
+        let com_2 = (com_2.0, com_2.1, IPC::commit(&self.ck_t, &[com_2.2])?);
+
+        let challenge = <GIPA<IP, LMC, RMC, IPC, D>>::_round_challenge(
+            self.r_transcript.len(),
+            self.r_transcript.last(),
+            &com_1,
+            &com_2,
+        )?;
+        self.r_commitment_steps.push((com_1, com_2));
+        self.r_transcript.push(challenge.0);
+        self.len /= 2;
+        Ok(challenge)
+    }
+
+    // Gathers the final shares, in worker order, into the prover state for the remaining rounds
+    pub fn into_prover_state(
+        self,
+        shares: Vec<FinalShare<LMC, RMC>>,
+    ) -> Result<GIPAProverState<IP, LMC, RMC, IPC, D>, Error> {
+        if self.worker_rounds() != 0 || shares.len() != self.num_shards {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                shares.len(),
+                self.num_shards,
+            ));
+        }
+        let mut state = GIPAProverState {
+            m_a: Vec::with_capacity(self.num_shards),
+            m_b: Vec::with_capacity(self.num_shards),
+            ck_a: Vec::with_capacity(self.num_shards),
+            ck_b: Vec::with_capacity(self.num_shards),
+            ck_t: self.ck_t,
+            r_commitment_steps: self.r_commitment_steps,
+            r_transcript: self.r_transcript,
+            seed: LMC::Scalar::default(),
+            _gipa: PhantomData,
+        };
+        for share in shares {
+            state.m_a.push(share.m_a);
+            state.m_b.push(share.m_b);
+            state.ck_a.push(share.ck_a);
+            state.ck_b.push(share.ck_b);
+        }
+        Ok(state)
+    }
+}
+
+impl<LMC, RMC, IPC> Clone for RoundShare<LMC, RMC, IPC>
+where
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    fn clone(&self) -> Self {
+        RoundShare {
+            com_1: self.com_1.clone(),
+            com_2: self.com_2.clone(),
+        }
+    }
+}
+
+impl<LMC, RMC, IPC> CanonicalSerialize for RoundShare<LMC, RMC, IPC>
+where
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC::Message: CanonicalSerialize,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.com_1.serialize_with_mode(&mut writer, compress)?;
+        self.com_2.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.com_1.serialized_size(compress) + self.com_2.serialized_size(compress)
+    }
+}
+
+impl<LMC, RMC, IPC> Valid for RoundShare<LMC, RMC, IPC>
+where
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC::Message: CanonicalDeserialize,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        self.com_1.check()?;
+        self.com_2.check()
+    }
+}
+
+impl<LMC, RMC, IPC> CanonicalDeserialize for RoundShare<LMC, RMC, IPC>
+where
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC::Message: CanonicalDeserialize,
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(RoundShare {
+            com_1: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            com_2: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+        })
+    }
+}
+
+impl<LMC, RMC> Clone for FinalShare<LMC, RMC>
+where
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    fn clone(&self) -> Self {
+        FinalShare {
+            m_a: self.m_a.clone(),
+            m_b: self.m_b.clone(),
+            ck_a: self.ck_a.clone(),
+            ck_b: self.ck_b.clone(),
+        }
+    }
+}
+
+impl<LMC, RMC> CanonicalSerialize for FinalShare<LMC, RMC>
+where
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: CanonicalSerialize,
+    RMC::Message: CanonicalSerialize,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.m_a.serialize_with_mode(&mut writer, compress)?;
+        self.m_b.serialize_with_mode(&mut writer, compress)?;
+        self.ck_a.serialize_with_mode(&mut writer, compress)?;
+        self.ck_b.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.m_a.serialized_size(compress)
+            + self.m_b.serialized_size(compress)
+            + self.ck_a.serialized_size(compress)
+            + self.ck_b.serialized_size(compress)
+    }
+}
+
+impl<LMC, RMC> Valid for FinalShare<LMC, RMC>
+where
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: CanonicalDeserialize,
+    RMC::Message: CanonicalDeserialize,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        self.m_a.check()?;
+        self.m_b.check()?;
+        self.ck_a.check()?;
+        self.ck_b.check()
+    }
+}
+
+impl<LMC, RMC> CanonicalDeserialize for FinalShare<LMC, RMC>
+where
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: CanonicalDeserialize,
+    RMC::Message: CanonicalDeserialize,
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(FinalShare {
+            m_a: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            m_b: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            ck_a: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            ck_b: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tipa::TIPA;
+    use ark_bls12_381::Bls12_381;
+    use ark_ec::pairing::Pairing;
+    use ark_ff::One;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use ark_dh_commitments::{
+        afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+        identity::IdentityCommitment,
+        random_generators,
+    };
+    use ark_inner_products::{ExtensionFieldElement, PairingInnerProduct};
+
+    type GC1 = AFGHOCommitmentG1<Bls12_381>;
+    type GC2 = AFGHOCommitmentG2<Bls12_381>;
+    type IP = PairingInnerProduct<Bls12_381>;
+    type IPC =
+        IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as Pairing>::ScalarField>;
+    type PairingGIPA = GIPA<IP, GC1, GC2, IPC, Blake2b512>;
+    type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b512>;
+    type PairingGIPAState = GIPAProverState<IP, GC1, GC2, IPC, Blake2b512>;
+    const TEST_SIZE: usize = 8;
+
+    // Runs the workers in process, passing every message through its serialization as a
+    // transport between machines would
+    fn distributed_prover_state(
+        values: (&[<Bls12_381 as Pairing>::G1], &[<Bls12_381 as Pairing>::G2]),
+        ck: (&[<Bls12_381 as Pairing>::G2], &[<Bls12_381 as Pairing>::G1]),
+        ck_t: &[<IPC as DoublyHomomorphicCommitment>::Key],
+        num_shards: usize,
+    ) -> PairingGIPAState {
+        fn send<T: CanonicalSerialize + CanonicalDeserialize>(value: &T) -> T {
+            let mut bytes = Vec::new();
+            value.serialize_compressed(&mut bytes).unwrap();
+            T::deserialize_compressed(&bytes[..]).unwrap()
+        }
+
+        let mut coordinator =
+            GIPACoordinator::<IP, GC1, GC2, IPC, Blake2b512>::new(values.0.len(), num_shards, ck_t)
+                .unwrap();
+        let mut workers = (0..num_shards)
+            .map(|i| {
+                GIPAWorker::<IP, GC1, GC2, IPC, Blake2b512>::new(
+                    (
+                        &shard(values.0, i, num_shards),
+                        &shard(values.1, i, num_shards),
+                    ),
+                    (&shard(ck.0, i, num_shards), &shard(ck.1, i, num_shards)),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        while coordinator.worker_rounds() > 0 {
+            let shares = workers
+                .iter()
+                .map(|worker| send(&worker.round_share().unwrap()))
+                .collect::<Vec<_>>();
+            let challenge = send(&coordinator.combine_round(&shares).unwrap());
+            for worker in workers.iter_mut() {
+                worker.fold(&challenge).unwrap();
+            }
+        }
+        let shares = workers
+            .into_iter()
+            .map(|worker| send(&worker.into_final_share().unwrap()))
+            .collect();
+        coordinator.into_prover_state(shares).unwrap()
+    }
+
+    #[test]
+    fn distributed_gipa_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (ck_a, ck_b, ck_t) = PairingGIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        let ck_t = vec![ck_t];
+
+        let (proof, _) = PairingGIPA::prove_with_aux((&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        let mut proof_bytes = Vec::new();
+        proof.serialize_compressed(&mut proof_bytes).unwrap();
+
+        // Any number of shards up to the message length gives the single prover's proof
+        for num_shards in [1, 2, 4, 8].iter().copied() {
+            let state = distributed_prover_state((&m_a, &m_b), (&ck_a, &ck_b), &ck_t, num_shards);
+            assert_eq!(state.len(), num_shards);
+            let (distributed, _) =
+                PairingGIPA::prove_from_state(state, |_| Ok::<_, Error>(())).unwrap();
+            let mut distributed_bytes = Vec::new();
+            distributed
+                .serialize_compressed(&mut distributed_bytes)
+                .unwrap();
+            assert_eq!(distributed_bytes, proof_bytes);
+        }
+
+        assert!(
+            GIPACoordinator::<IP, GC1, GC2, IPC, Blake2b512>::new(TEST_SIZE, 16, &ck_t).is_err()
+        );
+        assert!(
+            GIPACoordinator::<IP, GC1, GC2, IPC, Blake2b512>::new(TEST_SIZE, 3, &ck_t).is_err()
+        );
+    }
+
+    #[test]
+    fn distributed_tipa_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = PairingTIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        let com_a = GC1::commit(&ck_a, &m_a).unwrap();
+        let com_b = GC2::commit(&ck_b, &m_b).unwrap();
+        let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
+        let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
+
+        let state = distributed_prover_state(
+            (&m_a, &m_b),
+            (&ck_a, &ck_b),
+            ark_std::slice::from_ref(&ck_t),
+            4,
+        );
+        let proof = PairingTIPA::prove_with_srs_shift_from_state(
+            &srs,
+            state,
+            &<Bls12_381 as Pairing>::ScalarField::one(),
+            |_| Ok::<_, Error>(()),
+        )
+        .unwrap();
+        assert!(PairingTIPA::verify(&v_srs, &ck_t, (&com_a, &com_b, &com_t), &proof).unwrap());
+    }
+}
@@ -0,0 +1,119 @@
+use ark_ec::{pairing::Pairing, Group};
+use ark_ff::{Field, PrimeField};
+use ark_std::{cfg_iter, vec, vec::Vec};
+use rand::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::tipa::{multi_pow_128, PairingCheck};
+
+// Exponentiations in GT delegated to the prover, who sends `result = base^exponent` for the
+// verifier to check more cheaply than by exponentiating. Wesolowski proofs of exponentiation do
+// not apply since the order of GT is public, which lets a prover take l-th roots and prove any
+// result. Claims are instead checked in batches, or as pairing checks when the base is a pairing
+// of public points
+#[derive(Clone)]
+pub struct GTExponentiation<P: Pairing> {
+    pub base: P::TargetField,
+    pub exponent: P::ScalarField,
+    pub result: P::TargetField,
+}
+
+impl<P: Pairing> GTExponentiation<P> {
+    pub fn prove(base: P::TargetField, exponent: P::ScalarField) -> Self {
+        GTExponentiation {
+            base,
+            exponent,
+            result: base.pow(exponent.into_bigint()),
+        }
+    }
+
+    // Checks prod result_i^rho_i = prod base_i^(rho_i exponent_i) for random 128-bit rho_i. The
+    // powers of the results share their squarings, and the right side takes one full size
+    // exponentiation for each distinct base rather than for each claim, e.g. for a base shared by
+    // the proofs of a batch. Results must be elements of GT, e.g. checked with `Validate`
+    pub fn verify_batch<R: RngCore + CryptoRng>(claims: &[Self], rng: &mut R) -> bool {
+        let coeffs = claims
+            .iter()
+            .map(|_| [rng.next_u64(), rng.next_u64()])
+            .collect::<Vec<_>>();
+        let results = claims
+            .iter()
+            .zip(&coeffs)
+            .map(|(claim, coeff)| (claim.result, *coeff))
+            .collect::<Vec<_>>();
+
+        let mut bases: Vec<(P::TargetField, P::ScalarField)> = Vec::new();
+        for (claim, coeff) in claims.iter().zip(&coeffs) {
+            let rho = P::ScalarField::from(((coeff[1] as u128) << 64) | coeff[0] as u128);
+            let exponent = rho * claim.exponent;
+            match bases.iter_mut().find(|(base, _)| *base == claim.base) {
+                Some((_, sum)) => *sum += &exponent,
+                None => bases.push((claim.base, exponent)),
+            }
+        }
+        let expected = cfg_iter!(bases)
+            .map(|(base, exponent)| base.pow(exponent.into_bigint()))
+            .product::<P::TargetField>();
+
+        multi_pow_128(&results) == expected
+    }
+}
+
+// Claim that `result = e(a, b)^exponent` for public `a` and `b`, as the pairing check
+// e(exponent * a, b) = result. Added to a `PairingCheckBatch` it takes a scalar multiplication in
+// G1 and a Miller loop sharing the final exponentiation of the batch instead of an exponentiation
+// in GT
+pub fn pairing_power_check<P: Pairing>(
+    a: &P::G1,
+    b: &P::G2,
+    exponent: &P::ScalarField,
+    result: P::TargetField,
+) -> PairingCheck<P> {
+    PairingCheck {
+        pairs: vec![(a.mul_bigint(exponent.into_bigint()), *b)],
+        target: result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tipa::PairingCheckBatch;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn gt_exponentiation_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let g = G1Projective::rand(&mut rng);
+        let h = G2Projective::rand(&mut rng);
+        let shared_base = Bls12_381::pairing(g, h).0;
+        let mut claims = (0..TEST_SIZE)
+            .map(|i| {
+                let base = if i % 2 == 0 {
+                    shared_base
+                } else {
+                    Bls12_381::pairing(G1Projective::rand(&mut rng), h).0
+                };
+                GTExponentiation::<Bls12_381>::prove(base, Fr::rand(&mut rng))
+            })
+            .collect::<Vec<_>>();
+        assert!(GTExponentiation::verify_batch(&claims, &mut rng));
+
+        claims[3].result *= shared_base;
+        assert!(!GTExponentiation::verify_batch(&claims, &mut rng));
+
+        // Powers of e(g, h) checked with the pairing checks of a batch
+        let exponent = Fr::rand(&mut rng);
+        let result = shared_base.pow(exponent.into_bigint());
+        let mut batch = PairingCheckBatch::<Bls12_381>::new();
+        batch.add(pairing_power_check(&g, &h, &exponent, result));
+        assert!(batch.verify(&mut rng));
+        batch.add(pairing_power_check(&g, &h, &exponent, shared_base));
+        assert!(!batch.verify(&mut rng));
+    }
+}
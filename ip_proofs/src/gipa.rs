@@ -1,24 +1,39 @@
-use ark_ff::{to_bytes, Field, One};
-use ark_std::{end_timer, start_timer};
-use digest::Digest;
-use rand::Rng;
-use std::{convert::TryInto, marker::PhantomData, ops::MulAssign};
-
-use crate::{mul_helper, Error, InnerProductArgumentError};
-use ark_dh_commitments::DoublyHomomorphicCommitment;
+use ark_ff::{Field, One};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Write,
+};
+use ark_std::marker::PhantomData;
+use ark_std::{cfg_iter, end_timer, start_timer, vec, vec::Vec};
+use rand::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    limits::{deserialize_vec, limits},
+    padding::{padding_seed, PaddedInstance},
+    session::session_transcript,
+    transcript::{ChallengeInput, SharedTranscript, TranscriptDigest},
+    validation::Validate,
+    Error, InnerProductArgumentError,
+};
+use ark_dh_commitments::{DoublyHomomorphicCommitment, ScalarMul};
+#[cfg(feature = "serde")]
+use ark_inner_products::impl_canonical_serde;
 use ark_inner_products::InnerProduct;
 
+// Proofs carry the protocol as a marker. Its fields hold no values of the types, so proofs are
+// `Sync`, as deserializing them requires, whatever the digest and backends are
 pub struct GIPA<IP, LMC, RMC, IPC, D> {
-    _inner_product: PhantomData<IP>,
-    _left_commitment: PhantomData<LMC>,
-    _right_commitment: PhantomData<RMC>,
-    _inner_product_commitment: PhantomData<IPC>,
-    _digest: PhantomData<D>,
+    _inner_product: PhantomData<fn() -> IP>,
+    _left_commitment: PhantomData<fn() -> LMC>,
+    _right_commitment: PhantomData<fn() -> RMC>,
+    _inner_product_commitment: PhantomData<fn() -> IPC>,
+    _digest: PhantomData<fn() -> D>,
 }
 
 pub struct GIPAProof<IP, LMC, RMC, IPC, D>
 where
-    D: Digest,
+    D: TranscriptDigest,
     IP: InnerProduct<
         LeftMessage = LMC::Message,
         RightMessage = RMC::Message,
@@ -27,12 +42,6 @@ where
     LMC: DoublyHomomorphicCommitment,
     RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    RMC::Message: MulAssign<LMC::Scalar>,
-    IPC::Message: MulAssign<LMC::Scalar>,
-    RMC::Key: MulAssign<LMC::Scalar>,
-    IPC::Key: MulAssign<LMC::Scalar>,
-    RMC::Output: MulAssign<LMC::Scalar>,
-    IPC::Output: MulAssign<LMC::Scalar>,
 {
     pub(crate) r_commitment_steps: Vec<(
         (LMC::Output, RMC::Output, IPC::Output),
@@ -45,7 +54,7 @@ where
 #[derive(Clone)]
 pub struct GIPAAux<IP, LMC, RMC, IPC, D>
 where
-    D: Digest,
+    D: TranscriptDigest,
     IP: InnerProduct<
         LeftMessage = LMC::Message,
         RightMessage = RMC::Message,
@@ -54,23 +63,99 @@ where
     LMC: DoublyHomomorphicCommitment,
     RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    RMC::Message: MulAssign<LMC::Scalar>,
-    IPC::Message: MulAssign<LMC::Scalar>,
-    RMC::Key: MulAssign<LMC::Scalar>,
-    IPC::Key: MulAssign<LMC::Scalar>,
-    RMC::Output: MulAssign<LMC::Scalar>,
-    IPC::Output: MulAssign<LMC::Scalar>,
 {
     pub(crate) r_transcript: Vec<LMC::Scalar>,
     pub(crate) ck_base: (LMC::Key, RMC::Key),
     _gipa: PhantomData<GIPA<IP, LMC, RMC, IPC, D>>,
 }
 
+// Prover state between recursion rounds: the folded messages and keys, and the commitments and
+// challenges of the rounds so far. Serializing it after a round and resuming from it with
+// `prove_from_state` gives the same proof as an uninterrupted run
+pub struct GIPAProverState<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    pub(crate) m_a: Vec<LMC::Message>,
+    pub(crate) m_b: Vec<RMC::Message>,
+    pub(crate) ck_a: Vec<LMC::Key>,
+    pub(crate) ck_b: Vec<RMC::Key>,
+    pub(crate) ck_t: Vec<IPC::Key>,
+    pub(crate) r_commitment_steps: Vec<(
+        (LMC::Output, RMC::Output, IPC::Output),
+        (LMC::Output, RMC::Output, IPC::Output),
+    )>,
+    pub(crate) r_transcript: Vec<LMC::Scalar>,
+    pub(crate) seed: LMC::Scalar,
+    pub(crate) _gipa: PhantomData<GIPA<IP, LMC, RMC, IPC, D>>,
+}
+
+impl<IP, LMC, RMC, IPC, D> GIPAProverState<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    // Recursion rounds completed
+    pub fn rounds(&self) -> usize {
+        self.r_transcript.len()
+    }
+
+    // Length of the messages left to fold, 1 once the recursion is done
+    pub fn len(&self) -> usize {
+        self.m_a.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.m_a.is_empty()
+    }
+
+    // Seeds the challenge of the first round, as `prove_with_aux_and_seed`. It has no effect once
+    // a round has been run
+    pub fn with_seed(mut self, seed: LMC::Scalar) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl<IP, LMC, RMC, IPC, D> GIPAAux<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    // Challenges of the rounds, last round first as returned by
+    // `verify_recursive_challenge_transcript`
+    pub fn r_transcript(&self) -> &[LMC::Scalar] {
+        &self.r_transcript
+    }
+}
+
 //TODO: Can extend GIPA to support "identity commitments" in addition to "compact commitments", i.e. for SIPP
 
 impl<IP, LMC, RMC, IPC, D> GIPA<IP, LMC, RMC, IPC, D>
 where
-    D: Digest,
+    D: TranscriptDigest,
     IP: InnerProduct<
         LeftMessage = LMC::Message,
         RightMessage = RMC::Message,
@@ -79,17 +164,12 @@ where
     LMC: DoublyHomomorphicCommitment,
     RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    RMC::Message: MulAssign<LMC::Scalar>,
-    IPC::Message: MulAssign<LMC::Scalar>,
-    RMC::Key: MulAssign<LMC::Scalar>,
-    IPC::Key: MulAssign<LMC::Scalar>,
-    RMC::Output: MulAssign<LMC::Scalar>,
-    IPC::Output: MulAssign<LMC::Scalar>,
 {
-    pub fn setup<R: Rng>(
+    pub fn setup<R: RngCore + CryptoRng>(
         rng: &mut R,
         size: usize,
     ) -> Result<(Vec<LMC::Key>, Vec<RMC::Key>, IPC::Key), Error> {
+        limits().check_vector_len(size)?;
         Ok((
             LMC::setup(rng, size)?,
             RMC::setup(rng, size)?,
@@ -97,30 +177,46 @@ where
         ))
     }
 
+    // Keys for a left message of `left_len` against a shorter right message of `right_len`,
+    // repeated in blocks to the left length by the inner product, both powers of two
+    pub fn setup_asymmetric<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        left_len: usize,
+        right_len: usize,
+    ) -> Result<(Vec<LMC::Key>, Vec<RMC::Key>, IPC::Key), Error> {
+        limits().check_vector_len(left_len)?;
+        check_block_lengths(left_len, right_len)?;
+        Ok((
+            LMC::setup(rng, left_len)?,
+            RMC::setup(rng, right_len)?,
+            IPC::setup(rng, 1)?.pop().unwrap(),
+        ))
+    }
+
     pub fn prove(
         values: (&[IP::LeftMessage], &[IP::RightMessage], &IP::Output),
         ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
         com: (&LMC::Output, &RMC::Output, &IPC::Output),
     ) -> Result<GIPAProof<IP, LMC, RMC, IPC, D>, Error> {
-        if IP::inner_product(values.0, values.1)? != values.2.clone() {
-            return Err(Box::new(InnerProductArgumentError::InnerProductInvalid));
-        }
-        if values.0.len().count_ones() != 1 {
-            // Power of 2 length
-            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
-                values.0.len(),
-                values.1.len(),
-            )));
+        check_block_lengths(values.0.len(), values.1.len())?;
+        if IP::block_inner_product(values.0, values.1)? != values.2.clone() {
+            return Err(InnerProductArgumentError::InnerProductInvalid);
         }
         if !(LMC::verify(ck.0, values.0, com.0)?
             && RMC::verify(ck.1, values.1, com.1)?
-            && IPC::verify(&vec![ck.2.clone()], &vec![values.2.clone()], com.2)?)
+            && IPC::verify(
+                ark_std::slice::from_ref(ck.2),
+                ark_std::slice::from_ref(values.2),
+                com.2,
+            )?)
         {
-            return Err(Box::new(InnerProductArgumentError::InnerProductInvalid));
+            return Err(InnerProductArgumentError::InnerProductInvalid);
         }
 
-        let (proof, _) =
-            Self::prove_with_aux((values.0, values.1), (ck.0, ck.1, &vec![ck.2.clone()]))?;
+        let (proof, _) = Self::prove_with_aux(
+            (values.0, values.1),
+            (ck.0, ck.1, ark_std::slice::from_ref(ck.2)),
+        )?;
         Ok(proof)
     }
 
@@ -129,28 +225,294 @@ where
         com: (&LMC::Output, &RMC::Output, &IPC::Output),
         proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
     ) -> Result<bool, Error> {
-        if ck.0.len().count_ones() != 1 || ck.0.len() != ck.1.len() {
-            // Power of 2 length
-            return Err(Box::new(InnerProductArgumentError::MessageLengthInvalid(
-                ck.0.len(),
-                ck.1.len(),
-            )));
+        Self::_verify(ck, com, proof, &LMC::Scalar::default())
+    }
+
+    // Verifies with the challenges of the rounds already known and trusted, last round first, e.g.
+    // those of `GIPAAux::r_transcript` when checking a proof just made, skipping the digests.
+    // Challenges not derived from the proof itself make the check unsound, so they must never be
+    // taken from the prover of an untrusted proof. Keys may be of unequal lengths as made by
+    // `setup_asymmetric`, the left one fixing the number of rounds
+    pub fn verify_with_challenges(
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+        challenges: &[LMC::Scalar],
+    ) -> Result<bool, Error> {
+        check_block_lengths(ck.0.len(), ck.1.len())?;
+        let rounds = proof.r_commitment_steps.len();
+        if ck.0.len().trailing_zeros() as usize != rounds {
+            return Err(InnerProductArgumentError::TranscriptKeyMismatch {
+                transcript: rounds,
+                key: ck.0.len(),
+            });
         }
-        // Calculate base commitment and transcript
-        let (base_com, transcript) = Self::_compute_recursive_challenges(
-            (com.0.clone(), com.1.clone(), com.2.clone()),
-            proof,
-        )?;
-        // Calculate base commitment keys
-        let (ck_a_base, ck_b_base) = Self::_compute_final_commitment_keys(ck, &transcript)?;
-        // Verify base commitment
+        if challenges.len() != rounds {
+            return Err(InnerProductArgumentError::ChallengeCountMismatch {
+                challenges: challenges.len(),
+                rounds,
+            });
+        }
+        let _verify = phase_span!("gipa_verify", rounds = rounds);
+        let block_rounds = block_rounds(ck.0.len(), ck.1.len());
+        let mut com = (com.0.clone(), com.1.clone(), com.2.clone());
+        for (i, ((com_1, com_2), c)) in proof
+            .r_commitment_steps
+            .iter()
+            .rev()
+            .zip(challenges.iter().rev())
+            .enumerate()
+        {
+            let c_inv = c
+                .inverse()
+                .ok_or(InnerProductArgumentError::ZeroChallenge)?;
+            com = match Self::_fold_commitments(com, (com_1, com_2), (c, &c_inv), i < block_rounds)
+            {
+                Some(com) => com,
+                None => return Ok(false),
+            };
+        }
+        let (ck_a_base, ck_b_base) = Self::_compute_final_commitment_keys(ck, challenges)?;
         Self::_verify_base_commitment(
             (&ck_a_base, &ck_b_base, &vec![ck.2.clone()]),
-            base_com,
+            com,
+            &proof.r_base,
+        )
+    }
+
+    // Proves as a sub-protocol of a composite argument: the statement `com` is absorbed into
+    // `transcript` and seeds the first challenge, and the proof is absorbed after
+    pub fn prove_in_transcript(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        transcript: &mut SharedTranscript<D>,
+    ) -> Result<GIPAProof<IP, LMC, RMC, IPC, D>, Error>
+    where
+        GIPAProof<IP, LMC, RMC, IPC, D>: CanonicalSerialize,
+    {
+        let seed = Self::seed_from_transcript(com, transcript)?;
+        let state = Self::prover_state(values, (ck.0, ck.1, ark_std::slice::from_ref(ck.2)))?
+            .with_seed(seed);
+        let (proof, _) = Self::prove_from_state(state, |_| Ok::<_, Error>(()))?;
+        transcript.absorb(b"gipa-proof", &proof)?;
+        Ok(proof)
+    }
+
+    pub fn verify_in_transcript(
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+        transcript: &mut SharedTranscript<D>,
+    ) -> Result<bool, Error>
+    where
+        GIPAProof<IP, LMC, RMC, IPC, D>: CanonicalSerialize,
+    {
+        let seed = Self::seed_from_transcript(com, transcript)?;
+        transcript.absorb(b"gipa-proof", proof)?;
+        Self::_verify(ck, com, proof, &seed)
+    }
+
+    // Proves in the session `id` supplied by the application, see `session::session_transcript`
+    pub fn prove_with_session(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        id: &[u8],
+    ) -> Result<GIPAProof<IP, LMC, RMC, IPC, D>, Error>
+    where
+        GIPAProof<IP, LMC, RMC, IPC, D>: CanonicalSerialize,
+    {
+        Self::prove_in_transcript(values, ck, com, &mut session_transcript(id)?)
+    }
+
+    pub fn verify_with_session(
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+        id: &[u8],
+    ) -> Result<bool, Error>
+    where
+        GIPAProof<IP, LMC, RMC, IPC, D>: CanonicalSerialize,
+    {
+        Self::verify_in_transcript(ck, com, proof, &mut session_transcript(id)?)
+    }
+
+    // Absorbs the statement into a shared transcript and derives the seed of the first challenge
+    pub(crate) fn seed_from_transcript(
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        transcript: &mut SharedTranscript<D>,
+    ) -> Result<LMC::Scalar, Error> {
+        transcript.absorb(b"gipa-com-a", com.0)?;
+        transcript.absorb(b"gipa-com-b", com.1)?;
+        transcript.absorb(b"gipa-com-t", com.2)?;
+        transcript.challenge(b"gipa-seed")
+    }
+
+    // Commitments to padded messages under the first generators of keys of the padded length
+    pub fn commit_padded(
+        values: (
+            &PaddedInstance<IP::LeftMessage>,
+            &PaddedInstance<IP::RightMessage>,
+        ),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+    ) -> Result<(LMC::Output, RMC::Output, IPC::Output), Error> {
+        let len = Self::_padded_len(values, ck)?;
+        Self::commit(
+            (values.0.unpadded(), values.1.unpadded()),
+            (&ck.0[..len], &ck.1[..len], ck.2),
+        )
+    }
+
+    // Proves over the padded messages with the true length bound into the challenges
+    pub fn prove_padded(
+        values: (
+            &PaddedInstance<IP::LeftMessage>,
+            &PaddedInstance<IP::RightMessage>,
+        ),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+    ) -> Result<GIPAProof<IP, LMC, RMC, IPC, D>, Error> {
+        let len = Self::_padded_len(values, ck)?;
+        let seed = padding_seed::<_, D>(len, ck.0.len())?;
+        let state = Self::prover_state(
+            (values.0.values(), values.1.values()),
+            (ck.0, ck.1, ark_std::slice::from_ref(ck.2)),
+        )?
+        .with_seed(seed);
+        let (proof, _) = Self::prove_from_state(state, |_| Ok::<_, Error>(()))?;
+        Ok(proof)
+    }
+
+    // Verifies a proof over messages of true length `len` padded to the length of the keys
+    pub fn verify_padded(
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        len: usize,
+        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+    ) -> Result<bool, Error> {
+        let seed = match padding_seed::<_, D>(len, ck.0.len()) {
+            Ok(seed) => seed,
+            Err(_) => return Ok(false),
+        };
+        Self::_verify(ck, com, proof, &seed)
+    }
+
+    fn _padded_len(
+        values: (
+            &PaddedInstance<IP::LeftMessage>,
+            &PaddedInstance<IP::RightMessage>,
+        ),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+    ) -> Result<usize, Error> {
+        let len = values.0.unpadded_len();
+        if values.1.unpadded_len() != len
+            || ck.0.len() != values.0.padded_len()
+            || ck.1.len() != values.0.padded_len()
+        {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                len,
+                values.1.unpadded_len(),
+            ));
+        }
+        Ok(len)
+    }
+
+    fn _verify(
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+        seed: &LMC::Scalar,
+    ) -> Result<bool, Error> {
+        check_block_lengths(ck.0.len(), ck.1.len())?;
+        let (com_final, transcript) = match Self::_verify_commitment_consistency(
+            com,
             proof,
+            seed,
+            block_rounds(ck.0.len(), ck.1.len()),
+        )? {
+            Some(result) => result,
+            None => return Ok(false),
+        };
+        let (ck_a_final, ck_b_final) = Self::_compute_final_commitment_keys(ck, &transcript)?;
+        Self::verify_final_opening(
+            (&ck_a_final, &ck_b_final, ck.2),
+            &proof.r_base,
+            (&com_final.0, &com_final.1, &com_final.2),
+        )
+    }
+
+    // First half of `verify`, for light clients receiving the final messages out-of-band and only
+    // checking that the proof folds the statement `com` consistently. Derives the challenges from
+    // the round commitments and folds `com` with them, returning the final statement and the
+    // challenges last round first, or `None` if a round is inconsistent. Proofs of messages of
+    // equal length only, as `verify` needs the key lengths to tell the rounds of shorter right
+    // messages apart
+    pub fn verify_commitment_consistency(
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+    ) -> Result<Option<((LMC::Output, RMC::Output, IPC::Output), Vec<LMC::Scalar>)>, Error> {
+        Self::_verify_commitment_consistency(com, proof, &LMC::Scalar::default(), 0)
+    }
+
+    // Keys the final messages are committed with, the commitment keys folded with the challenges
+    // of `verify_commitment_consistency`
+    pub fn final_commitment_keys(
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        challenges: &[LMC::Scalar],
+    ) -> Result<(LMC::Key, RMC::Key), Error> {
+        Self::_compute_final_commitment_keys(ck, challenges)
+    }
+
+    // Second half of `verify`: checks that the final messages `base` open the final statement
+    // `com_final` of `verify_commitment_consistency` under the keys of `final_commitment_keys`
+    pub fn verify_final_opening(
+        ck_final: (&LMC::Key, &RMC::Key, &IPC::Key),
+        base: &(LMC::Message, RMC::Message),
+        com_final: (&LMC::Output, &RMC::Output, &IPC::Output),
+    ) -> Result<bool, Error> {
+        Self::_verify_base_commitment(
+            (ck_final.0, ck_final.1, &vec![ck_final.2.clone()]),
+            (
+                com_final.0.clone(),
+                com_final.1.clone(),
+                com_final.2.clone(),
+            ),
+            base,
         )
     }
 
+    fn _verify_commitment_consistency(
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+        seed: &LMC::Scalar,
+        block_rounds: usize,
+    ) -> Result<Option<((LMC::Output, RMC::Output, IPC::Output), Vec<LMC::Scalar>)>, Error> {
+        match Self::_compute_recursive_challenges(
+            (com.0.clone(), com.1.clone(), com.2.clone()),
+            proof,
+            seed,
+            block_rounds,
+        ) {
+            Err(InnerProductArgumentError::InnerProductInvalid) => Ok(None),
+            result => result.map(Some),
+        }
+    }
+
+    // Commitments to the messages and to their inner product, the statement of `prove` and `verify`
+    pub fn commit(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+    ) -> Result<(LMC::Output, RMC::Output, IPC::Output), Error> {
+        Ok((
+            LMC::commit(ck.0, values.0)?,
+            RMC::commit(ck.1, values.1)?,
+            IPC::commit(
+                ark_std::slice::from_ref(ck.2),
+                &[IP::block_inner_product(values.0, values.1)?],
+            )?,
+        ))
+    }
+
     pub fn prove_with_aux(
         values: (&[IP::LeftMessage], &[IP::RightMessage]),
         ck: (&[LMC::Key], &[RMC::Key], &[IPC::Key]),
@@ -161,250 +523,458 @@ where
         ),
         Error,
     > {
+        Self::prove_from_state(Self::prover_state(values, ck)?, |_| Ok::<_, Error>(()))
+    }
+
+    // Seeds the challenge of the first round, e.g. with a hash of a statement that the commitments
+    // do not bind
+    pub fn prove_with_aux_and_seed(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &[IPC::Key]),
+        seed: &LMC::Scalar,
+    ) -> Result<
+        (
+            GIPAProof<IP, LMC, RMC, IPC, D>,
+            GIPAAux<IP, LMC, RMC, IPC, D>,
+        ),
+        Error,
+    > {
+        Self::prove_from_state(Self::prover_state(values, ck)?.with_seed(*seed), |_| {
+            Ok::<_, Error>(())
+        })
+    }
+
+    // State of the prover before the first round, to be run with `prove_from_state`
+    pub fn prover_state(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &[IPC::Key]),
+    ) -> Result<GIPAProverState<IP, LMC, RMC, IPC, D>, Error> {
         let (m_a, m_b) = values;
         let (ck_a, ck_b, ck_t) = ck;
-        Self::_prove(
-            (m_a.to_vec(), m_b.to_vec()),
-            (ck_a.to_vec(), ck_b.to_vec(), ck_t.to_vec()),
-        )
+        limits().check_vector_len(m_a.len())?;
+        check_block_lengths(m_a.len(), m_b.len())?;
+        Ok(GIPAProverState {
+            m_a: m_a.to_vec(),
+            m_b: m_b.to_vec(),
+            ck_a: ck_a.to_vec(),
+            ck_b: ck_b.to_vec(),
+            ck_t: ck_t.to_vec(),
+            r_commitment_steps: Vec::new(),
+            r_transcript: Vec::new(),
+            seed: LMC::Scalar::default(),
+            _gipa: PhantomData,
+        })
     }
 
-    // Returns vector of recursive commitments and transcripts in reverse order
-    fn _prove(
-        values: (Vec<IP::LeftMessage>, Vec<IP::RightMessage>),
-        ck: (Vec<LMC::Key>, Vec<RMC::Key>, Vec<IPC::Key>),
+    // Runs the remaining rounds from `state` and hands the state after each round to
+    // `checkpoint`, e.g. to write it to disk. An error from `checkpoint` stops the prover
+    pub fn prove_from_state<E: From<Error>>(
+        mut state: GIPAProverState<IP, LMC, RMC, IPC, D>,
+        mut checkpoint: impl FnMut(&GIPAProverState<IP, LMC, RMC, IPC, D>) -> Result<(), E>,
     ) -> Result<
         (
             GIPAProof<IP, LMC, RMC, IPC, D>,
             GIPAAux<IP, LMC, RMC, IPC, D>,
         ),
-        Error,
+        E,
     > {
-        let (mut m_a, mut m_b) = values;
-        let (mut ck_a, mut ck_b, ck_t) = ck;
-        let mut r_commitment_steps = Vec::new();
-        let mut r_transcript = Vec::new();
-        assert!(m_a.len().is_power_of_two());
-        let (m_base, ck_base) = 'recurse: loop {
-            let recurse = start_timer!(|| format!("Recurse round size {}", m_a.len()));
-            if m_a.len() == 1 {
-                // base case
-                break 'recurse (
-                    (m_a[0].clone(), m_b[0].clone()),
-                    (ck_a[0].clone(), ck_b[0].clone()),
-                );
-            } else {
-                // recursive step
-                // Recurse with problem of half size
-                let split = m_a.len() / 2;
-
-                let m_a_1 = &m_a[split..];
-                let m_a_2 = &m_a[..split];
-                let ck_a_1 = &ck_a[..split];
-                let ck_a_2 = &ck_a[split..];
-
-                let m_b_1 = &m_b[..split];
-                let m_b_2 = &m_b[split..];
-                let ck_b_1 = &ck_b[split..];
-                let ck_b_2 = &ck_b[..split];
-
-                let cl = start_timer!(|| "Commit L");
-                let com_1 = (
-                    LMC::commit(ck_a_1, m_a_1)?,
-                    RMC::commit(ck_b_1, m_b_1)?,
-                    IPC::commit(&ck_t, &vec![IP::inner_product(m_a_1, m_b_1)?])?,
-                );
-                end_timer!(cl);
-                let cr = start_timer!(|| "Commit R");
-                let com_2 = (
-                    LMC::commit(ck_a_2, m_a_2)?,
-                    RMC::commit(ck_b_2, m_b_2)?,
-                    IPC::commit(&ck_t, &vec![IP::inner_product(m_a_2, m_b_2)?])?,
-                );
-                end_timer!(cr);
-
-                // Fiat-Shamir challenge
-                let mut counter_nonce: usize = 0;
-                let default_transcript = Default::default();
-                let transcript = r_transcript.last().unwrap_or(&default_transcript);
-                let (c, c_inv) = 'challenge: loop {
-                    let mut hash_input = Vec::new();
-                    hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-                    //TODO: Should use CanonicalSerialize instead of ToBytes
-                    hash_input.extend_from_slice(&to_bytes![
-                        transcript, com_1.0, com_1.1, com_1.2, com_2.0, com_2.1, com_2.2
-                    ]?);
-                    let c: LMC::Scalar = u128::from_be_bytes(
-                        D::digest(&hash_input).as_slice()[0..16].try_into().unwrap(),
-                    )
-                    .into();
-                    if let Some(c_inv) = c.inverse() {
-                        // Optimization for multiexponentiation to rescale G2 elements with 128-bit challenge
-                        // Swap 'c' and 'c_inv' since can't control bit size of c_inv
-                        break 'challenge (c_inv, c);
-                    }
-                    counter_nonce += 1;
-                };
-
-                // Set up values for next step of recursion
-                let rescale_m1 = start_timer!(|| "Rescale M1");
-                m_a = m_a_1
-                    .iter()
-                    .map(|a| mul_helper(a, &c))
-                    .zip(m_a_2)
-                    .map(|(a_1, a_2)| a_1.clone() + a_2.clone())
-                    .collect::<Vec<LMC::Message>>();
-                end_timer!(rescale_m1);
-
-                let rescale_m2 = start_timer!(|| "Rescale M2");
-                m_b = m_b_2
-                    .iter()
-                    .map(|b| mul_helper(b, &c_inv))
-                    .zip(m_b_1)
-                    .map(|(b_1, b_2)| b_1.clone() + b_2.clone())
-                    .collect::<Vec<RMC::Message>>();
-                end_timer!(rescale_m2);
-
-                let rescale_ck1 = start_timer!(|| "Rescale CK1");
-                ck_a = ck_a_2
-                    .iter()
-                    .map(|a| mul_helper(a, &c_inv))
-                    .zip(ck_a_1)
-                    .map(|(a_1, a_2)| a_1.clone() + a_2.clone())
-                    .collect::<Vec<LMC::Key>>();
-                end_timer!(rescale_ck1);
-
-                let rescale_ck2 = start_timer!(|| "Rescale CK2");
-                ck_b = ck_b_1
-                    .iter()
-                    .map(|b| mul_helper(b, &c))
-                    .zip(ck_b_2)
-                    .map(|(b_1, b_2)| b_1.clone() + b_2.clone())
-                    .collect::<Vec<RMC::Key>>();
-                end_timer!(rescale_ck2);
-
-                r_commitment_steps.push((com_1, com_2));
-                r_transcript.push(c);
-                end_timer!(recurse);
-            }
-        };
+        let _prove = phase_span!("gipa_prove", len = state.len());
+        while state.len() > 1 {
+            Self::_prove_round(&mut state)?;
+            checkpoint(&state)?;
+        }
+
+        // Returns vector of recursive commitments and transcripts in reverse order
+        let GIPAProverState {
+            m_a,
+            m_b,
+            ck_a,
+            ck_b,
+            mut r_commitment_steps,
+            mut r_transcript,
+            ..
+        } = state;
         r_transcript.reverse();
         r_commitment_steps.reverse();
         Ok((
             GIPAProof {
                 r_commitment_steps,
-                r_base: m_base,
+                r_base: (m_a[0].clone(), m_b[0].clone()),
                 _gipa: PhantomData,
             },
             GIPAAux {
                 r_transcript,
-                ck_base,
+                ck_base: (ck_a[0].clone(), ck_b[0].clone()),
                 _gipa: PhantomData,
             },
         ))
     }
 
+    // Recurses with problem of half size
+    fn _prove_round(state: &mut GIPAProverState<IP, LMC, RMC, IPC, D>) -> Result<(), Error> {
+        let recurse = start_timer!(|| format!("Recurse round size {}", state.len()));
+        let _round = phase_span!("gipa_round", len = state.len());
+        phase_count!(gipa_rounds, 1);
+
+        let (com_1, com_2) = Self::_round_commitments(state)?;
+        let (c, c_inv) = Self::_round_challenge(
+            state.r_transcript.len(),
+            Some(state.r_transcript.last().unwrap_or(&state.seed)),
+            &com_1,
+            &com_2,
+        )?;
+        Self::_finish_round(state, (com_1, com_2), (c, c_inv))?;
+        end_timer!(recurse);
+        Ok(())
+    }
+
+    // Commitments of the prover in a round, from which the challenge is derived
+    pub(crate) fn _round_commitments(
+        state: &GIPAProverState<IP, LMC, RMC, IPC, D>,
+    ) -> Result<
+        (
+            (LMC::Output, RMC::Output, IPC::Output),
+            (LMC::Output, RMC::Output, IPC::Output),
+        ),
+        Error,
+    > {
+        let cl = start_timer!(|| "Commit L and R");
+        let halves = if state.m_a.len() > state.m_b.len() {
+            Self::_commit_block_halves((&state.m_a, &state.m_b), &state.ck_a)?
+        } else {
+            Self::_commit_halves((&state.m_a, &state.m_b), (&state.ck_a, &state.ck_b))?
+        };
+        let ((com_a_1, com_b_1, ip_1), (com_a_2, com_b_2, ip_2)) = halves;
+        let com_1 = (com_a_1, com_b_1, IPC::commit(&state.ck_t, &[ip_1])?);
+        let com_2 = (com_a_2, com_b_2, IPC::commit(&state.ck_t, &[ip_2])?);
+        end_timer!(cl);
+        Ok((com_1, com_2))
+    }
+
+    // Folds the state with the challenge of a round and its inverse, and records the round
+    pub(crate) fn _finish_round(
+        state: &mut GIPAProverState<IP, LMC, RMC, IPC, D>,
+        com: (
+            (LMC::Output, RMC::Output, IPC::Output),
+            (LMC::Output, RMC::Output, IPC::Output),
+        ),
+        challenge: (LMC::Scalar, LMC::Scalar),
+    ) -> Result<(), Error> {
+        let (c, c_inv) = challenge;
+        Self::_fold_round(
+            (&mut state.m_a, &mut state.m_b),
+            (&mut state.ck_a, &mut state.ck_b),
+            (&c, &c_inv),
+        )?;
+        state.r_commitment_steps.push(com);
+        state.r_transcript.push(c);
+        Ok(())
+    }
+
+    // Commitments to the cross terms of a round and their inner products, left to be committed to
+    // under the inner product key. Both halves are committed to under the key (ck[..split],
+    // ck[split..]) in one pass
+    pub(crate) fn _commit_halves(
+        m: (&[LMC::Message], &[RMC::Message]),
+        ck: (&[LMC::Key], &[RMC::Key]),
+    ) -> Result<
+        (
+            (LMC::Output, RMC::Output, IP::Output),
+            (LMC::Output, RMC::Output, IP::Output),
+        ),
+        Error,
+    > {
+        let split = m.0.len() / 2;
+        let _commit = phase_span!("commit", len = split);
+        let m_a_1 = &m.0[split..];
+        let m_a_2 = &m.0[..split];
+        let m_b_1 = &m.1[..split];
+        let m_b_2 = &m.1[split..];
+
+        let (com_a_1, com_a_2) = LMC::commit_split(ck.0, &[m_a_1, m_a_2].concat(), split)?;
+        let (com_b_2, com_b_1) = RMC::commit_split(ck.1, &[m_b_2, m_b_1].concat(), split)?;
+        Ok((
+            (com_a_1, com_b_1, IP::inner_product(m_a_1, m_b_1)?),
+            (com_a_2, com_b_2, IP::inner_product(m_a_2, m_b_2)?),
+        ))
+    }
+
+    // As `_commit_halves` in the rounds folding only the left message, while it is longer than the
+    // right one. The inner products are of each half with the right message repeated, which are
+    // left unchanged along with their key, so their commitments are the default
+    fn _commit_block_halves(
+        m: (&[LMC::Message], &[RMC::Message]),
+        ck_a: &[LMC::Key],
+    ) -> Result<
+        (
+            (LMC::Output, RMC::Output, IP::Output),
+            (LMC::Output, RMC::Output, IP::Output),
+        ),
+        Error,
+    > {
+        let split = m.0.len() / 2;
+        let _commit = phase_span!("commit", len = split);
+        let m_a_1 = &m.0[split..];
+        let m_a_2 = &m.0[..split];
+
+        let (com_a_1, com_a_2) = LMC::commit_split(ck_a, &[m_a_1, m_a_2].concat(), split)?;
+        Ok((
+            (
+                com_a_1,
+                RMC::Output::default(),
+                IP::block_inner_product(m_a_1, m.1)?,
+            ),
+            (
+                com_a_2,
+                RMC::Output::default(),
+                IP::block_inner_product(m_a_2, m.1)?,
+            ),
+        ))
+    }
+
+    // Fiat-Shamir challenge of a round and its inverse, from the previous challenge and the
+    // round commitments. The round only labels the transcript log
+    #[cfg_attr(not(any(feature = "transcript-log", test)), allow(unused_variables))]
+    pub(crate) fn _round_challenge(
+        round: usize,
+        transcript: Option<&LMC::Scalar>,
+        com_1: &(LMC::Output, RMC::Output, IPC::Output),
+        com_2: &(LMC::Output, RMC::Output, IPC::Output),
+    ) -> Result<(LMC::Scalar, LMC::Scalar), Error> {
+        let default_transcript = LMC::Scalar::default();
+        let transcript = transcript.unwrap_or(&default_transcript);
+        transcript_log!("gipa", Some(round), b"com-1", com_1);
+        transcript_log!("gipa", Some(round), b"com-2", com_2);
+        let mut input = D::input();
+        input.absorb_scalar(transcript)?;
+        // Canonical encoding normalizes projective points, unlike ToBytes, so a deserialized proof
+        // replays the same challenges
+        input.absorb(com_1)?;
+        input.absorb(com_2)?;
+        let mut counter_nonce: usize = 0;
+        loop {
+            let c: LMC::Scalar = input.short_challenge(counter_nonce);
+            if let Some(c_inv) = c.inverse() {
+                transcript_log!(
+                    "gipa",
+                    Some(round),
+                    b"challenge",
+                    &c_inv,
+                    nonce = counter_nonce
+                );
+                // Optimization for multiexponentiation to rescale G2 elements with 128-bit challenge
+                // Swap 'c' and 'c_inv' since can't control bit size of c_inv
+                return Ok((c_inv, c));
+            }
+            counter_nonce += 1;
+        }
+    }
+
+    // Folds the messages and keys to half their length with the round challenge
+    pub(crate) fn _fold_round(
+        m: (&mut Vec<LMC::Message>, &mut Vec<RMC::Message>),
+        ck: (&mut Vec<LMC::Key>, &mut Vec<RMC::Key>),
+        challenge: (&LMC::Scalar, &LMC::Scalar),
+    ) -> Result<(), Error> {
+        let (c, c_inv) = challenge;
+        let split = m.0.len() / 2;
+        let block_round = m.0.len() > m.1.len();
+
+        let rescale_m1 = start_timer!(|| "Rescale M1");
+        let m_a = cfg_iter!(m.0[split..])
+            .map(|a| a.scalar_mul(c))
+            .zip(&m.0[..split])
+            .map(|(a_1, a_2)| a_1.clone() + a_2.clone())
+            .collect::<Vec<LMC::Message>>();
+        end_timer!(rescale_m1);
+        if block_round {
+            *ck.0 = LMC::fold_keys(ck.0, c_inv)?;
+            *m.0 = m_a;
+            return Ok(());
+        }
+
+        let rescale_m2 = start_timer!(|| "Rescale M2");
+        let m_b = cfg_iter!(m.1[split..])
+            .map(|b| b.scalar_mul(c_inv))
+            .zip(&m.1[..split])
+            .map(|(b_1, b_2)| b_1.clone() + b_2.clone())
+            .collect::<Vec<RMC::Message>>();
+        end_timer!(rescale_m2);
+
+        let _fold = phase_span!("fold_keys", len = split);
+        let rescale_ck1 = start_timer!(|| "Rescale CK1");
+        *ck.0 = LMC::fold_keys(ck.0, c_inv)?;
+        end_timer!(rescale_ck1);
+
+        let rescale_ck2 = start_timer!(|| "Rescale CK2");
+        *ck.1 = RMC::fold_keys(ck.1, c)?;
+        end_timer!(rescale_ck2);
+
+        *m.0 = m_a;
+        *m.1 = m_b;
+        Ok(())
+    }
+
     // Helper function used to calculate recursive challenges from proof execution (transcript in reverse)
     pub fn verify_recursive_challenge_transcript(
         com: (&LMC::Output, &RMC::Output, &IPC::Output),
         proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
     ) -> Result<((LMC::Output, RMC::Output, IPC::Output), Vec<LMC::Scalar>), Error> {
-        Self::_compute_recursive_challenges((com.0.clone(), com.1.clone(), com.2.clone()), proof)
+        Self::verify_recursive_challenge_transcript_with_seed(com, proof, &LMC::Scalar::default())
     }
 
+    // As `verify_recursive_challenge_transcript` for a proof seeded with `prove_with_aux_and_seed`
+    pub fn verify_recursive_challenge_transcript_with_seed(
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+        seed: &LMC::Scalar,
+    ) -> Result<((LMC::Output, RMC::Output, IPC::Output), Vec<LMC::Scalar>), Error> {
+        Self::_compute_recursive_challenges(
+            (com.0.clone(), com.1.clone(), com.2.clone()),
+            proof,
+            seed,
+            0,
+        )
+    }
+
+    // The first `block_rounds` rounds fold only the left message. Their commitments are checked
+    // to be consistent, or `InnerProductInvalid` is returned
     fn _compute_recursive_challenges(
         com: (LMC::Output, RMC::Output, IPC::Output),
         proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+        seed: &LMC::Scalar,
+        block_rounds: usize,
     ) -> Result<((LMC::Output, RMC::Output, IPC::Output), Vec<LMC::Scalar>), Error> {
-        let (mut com_a, mut com_b, mut com_t) = com;
+        let _verify = phase_span!("gipa_verify", rounds = proof.r_commitment_steps.len());
+        limits().check_rounds(proof.r_commitment_steps.len())?;
+        let mut com = com;
         let mut r_transcript = Vec::new();
         for (com_1, com_2) in proof.r_commitment_steps.iter().rev() {
-            // Fiat-Shamir challenge
-            let mut counter_nonce: usize = 0;
-            let default_transcript = Default::default();
-            let transcript = r_transcript.last().unwrap_or(&default_transcript);
-            let (c, c_inv) = 'challenge: loop {
-                let mut hash_input = Vec::new();
-                hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-                hash_input.extend_from_slice(&to_bytes![
-                    transcript, com_1.0, com_1.1, com_1.2, com_2.0, com_2.1, com_2.2
-                ]?);
-                let c: LMC::Scalar = u128::from_be_bytes(
-                    D::digest(&hash_input).as_slice()[0..16].try_into().unwrap(),
-                )
-                .into();
-                if let Some(c_inv) = c.inverse() {
-                    // Optimization for multiexponentiation to rescale G2 elements with 128-bit challenge
-                    // Swap 'c' and 'c_inv' since can't control bit size of c_inv
-                    break 'challenge (c_inv, c);
-                }
-                counter_nonce += 1;
-            };
+            let (c, c_inv) = Self::_round_challenge(
+                r_transcript.len(),
+                Some(r_transcript.last().unwrap_or(seed)),
+                com_1,
+                com_2,
+            )?;
 
-            com_a = mul_helper(&com_1.0, &c) + com_a.clone() + mul_helper(&com_2.0, &c_inv);
-            com_b = mul_helper(&com_1.1, &c) + com_b.clone() + mul_helper(&com_2.1, &c_inv);
-            com_t = mul_helper(&com_1.2, &c) + com_t.clone() + mul_helper(&com_2.2, &c_inv);
+            com = Self::_fold_commitments(
+                com,
+                (com_1, com_2),
+                (&c, &c_inv),
+                r_transcript.len() < block_rounds,
+            )
+            .ok_or(InnerProductArgumentError::InnerProductInvalid)?;
 
             r_transcript.push(c);
         }
         r_transcript.reverse();
-        Ok(((com_a, com_b, com_t), r_transcript))
+        Ok((com, r_transcript))
+    }
+
+    // Statement after a round, or `None` if the commitments of a round folding only the left
+    // message are inconsistent. Such rounds keep the right commitment, and split the inner product
+    // commitment into those of the halves of the left message
+    fn _fold_commitments(
+        com: (LMC::Output, RMC::Output, IPC::Output),
+        step: (
+            &(LMC::Output, RMC::Output, IPC::Output),
+            &(LMC::Output, RMC::Output, IPC::Output),
+        ),
+        challenge: (&LMC::Scalar, &LMC::Scalar),
+        block_round: bool,
+    ) -> Option<(LMC::Output, RMC::Output, IPC::Output)> {
+        let ((com_a, com_b, com_t), (com_1, com_2), (c, c_inv)) = (com, step, challenge);
+        let com_a = com_1.0.scalar_mul(c) + com_a + com_2.0.scalar_mul(c_inv);
+        if block_round {
+            let default = RMC::Output::default();
+            if com_1.1 != default
+                || com_2.1 != default
+                || com_1.2.clone() + com_2.2.clone() != com_t
+            {
+                return None;
+            }
+            return Some((com_a, com_b, com_1.2.scalar_mul(c) + com_2.2.clone()));
+        }
+        Some((
+            com_a,
+            com_1.1.scalar_mul(c) + com_b + com_2.1.scalar_mul(c_inv),
+            com_1.2.scalar_mul(c) + com_t + com_2.2.scalar_mul(c_inv),
+        ))
     }
 
+    // Fails rather than folding keys whose length the transcript of a malformed proof does not
+    // match, e.g. of a proof with rounds cut off or appended
     pub(crate) fn _compute_final_commitment_keys(
         ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
-        transcript: &Vec<LMC::Scalar>,
+        transcript: &[LMC::Scalar],
     ) -> Result<(LMC::Key, RMC::Key), Error> {
         // Calculate base commitment keys
         let (ck_a, ck_b, _) = ck;
-        assert!(ck_a.len().is_power_of_two());
+        check_block_lengths(ck_a.len(), ck_b.len())?;
+        if 1usize.checked_shl(transcript.len() as u32) != Some(ck_a.len()) {
+            return Err(InnerProductArgumentError::TranscriptKeyMismatch {
+                transcript: transcript.len(),
+                key: ck_a.len(),
+            });
+        }
+        let _final_ck = phase_span!("final_ck", len = ck_a.len());
 
+        // The right key is only folded in the last rounds, the first in the transcript
         let mut ck_a_agg_challenge_exponents = vec![LMC::Scalar::one()];
         let mut ck_b_agg_challenge_exponents = vec![LMC::Scalar::one()];
         for (i, c) in transcript.iter().enumerate() {
-            let c_inv = c.inverse().unwrap();
+            let c_inv = c
+                .inverse()
+                .ok_or(InnerProductArgumentError::ZeroChallenge)?;
             for j in 0..(2_usize).pow(i as u32) {
-                ck_a_agg_challenge_exponents.push(ck_a_agg_challenge_exponents[j] * &c_inv);
-                ck_b_agg_challenge_exponents.push(ck_b_agg_challenge_exponents[j] * c);
+                ck_a_agg_challenge_exponents.push(ck_a_agg_challenge_exponents[j] * c_inv);
+                if ck_b_agg_challenge_exponents.len() < ck_b.len() {
+                    ck_b_agg_challenge_exponents.push(ck_b_agg_challenge_exponents[j] * c);
+                }
             }
         }
-        assert_eq!(ck_a_agg_challenge_exponents.len(), ck_a.len());
-        //TODO: Optimization: Use VariableMSM multiexponentiation
-        let ck_a_base_init = mul_helper(&ck_a[0], &ck_a_agg_challenge_exponents[0]);
-        let ck_a_base = ck_a[1..]
-            .iter()
-            .zip(&ck_a_agg_challenge_exponents[1..])
-            .map(|(g, x)| mul_helper(g, &x))
-            .fold(ck_a_base_init, |sum, x| sum + x);
-        let ck_b_base_init = mul_helper(&ck_b[0], &ck_b_agg_challenge_exponents[0]);
-        let ck_b_base = ck_b[1..]
-            .iter()
-            .zip(&ck_b_agg_challenge_exponents[1..])
-            .map(|(g, x)| mul_helper(g, &x))
-            .fold(ck_b_base_init, |sum, x| sum + x);
-        Ok((ck_a_base, ck_b_base))
+        Ok((
+            LMC::fold_keys_msm(ck_a, &ck_a_agg_challenge_exponents)?,
+            RMC::fold_keys_msm(ck_b, &ck_b_agg_challenge_exponents)?,
+        ))
     }
 
     pub(crate) fn _verify_base_commitment(
         base_ck: (&LMC::Key, &RMC::Key, &Vec<IPC::Key>),
         base_com: (LMC::Output, RMC::Output, IPC::Output),
-        proof: &GIPAProof<IP, LMC, RMC, IPC, D>,
+        r_base: &(LMC::Message, RMC::Message),
     ) -> Result<bool, Error> {
         let (com_a, com_b, com_t) = base_com;
         let (ck_a_base, ck_b_base, ck_t) = base_ck;
-        let a_base = vec![proof.r_base.0.clone()];
-        let b_base = vec![proof.r_base.1.clone()];
+        let a_base = vec![r_base.0.clone()];
+        let b_base = vec![r_base.1.clone()];
         let t_base = vec![IP::inner_product(&a_base, &b_base)?];
 
-        Ok(LMC::verify(&vec![ck_a_base.clone()], &a_base, &com_a)?
-            && RMC::verify(&vec![ck_b_base.clone()], &b_base, &com_b)?
-            && IPC::verify(&ck_t, &t_base, &com_t)?)
+        Ok(
+            LMC::verify(ark_std::slice::from_ref(ck_a_base), &a_base, &com_a)?
+                && RMC::verify(ark_std::slice::from_ref(ck_b_base), &b_base, &com_b)?
+                && IPC::verify(ck_t, &t_base, &com_t)?,
+        )
+    }
+}
+
+// Messages are powers of two in length, with the right message no longer than the left one. A
+// shorter right message is repeated in blocks to the left length by the inner product
+pub(crate) fn check_block_lengths(left: usize, right: usize) -> Result<(), Error> {
+    if !left.is_power_of_two() || !right.is_power_of_two() || right > left {
+        return Err(InnerProductArgumentError::MessageLengthInvalid(left, right));
     }
+    Ok(())
+}
+
+// Rounds folding only the left message before the lengths are equal
+pub(crate) fn block_rounds(left: usize, right: usize) -> usize {
+    (left.trailing_zeros() - right.trailing_zeros()) as usize
 }
 
 impl<IP, LMC, RMC, IPC, D> Clone for GIPAProof<IP, LMC, RMC, IPC, D>
 where
-    D: Digest,
+    D: TranscriptDigest,
     IP: InnerProduct<
         LeftMessage = LMC::Message,
         RightMessage = RMC::Message,
@@ -413,12 +983,6 @@ where
     LMC: DoublyHomomorphicCommitment,
     RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    RMC::Message: MulAssign<LMC::Scalar>,
-    IPC::Message: MulAssign<LMC::Scalar>,
-    RMC::Key: MulAssign<LMC::Scalar>,
-    IPC::Key: MulAssign<LMC::Scalar>,
-    RMC::Output: MulAssign<LMC::Scalar>,
-    IPC::Output: MulAssign<LMC::Scalar>,
 {
     fn clone(&self) -> Self {
         GIPAProof {
@@ -429,13 +993,285 @@ where
     }
 }
 
+impl<IP, LMC, RMC, IPC, D> CanonicalSerialize for GIPAProof<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: CanonicalSerialize,
+    RMC::Message: CanonicalSerialize,
+    LMC::Output: CanonicalSerialize,
+    RMC::Output: CanonicalSerialize,
+    IPC::Output: CanonicalSerialize,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.r_commitment_steps
+            .serialize_with_mode(&mut writer, compress)?;
+        self.r_base.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.r_commitment_steps.serialized_size(compress) + self.r_base.serialized_size(compress)
+    }
+}
+
+impl<IP, LMC, RMC, IPC, D> Valid for GIPAProof<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: CanonicalDeserialize,
+    RMC::Message: CanonicalDeserialize,
+    LMC::Output: CanonicalDeserialize,
+    RMC::Output: CanonicalDeserialize,
+    IPC::Output: CanonicalDeserialize,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        self.r_commitment_steps.check()?;
+        self.r_base.check()
+    }
+}
+
+impl<IP, LMC, RMC, IPC, D> CanonicalDeserialize for GIPAProof<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: CanonicalDeserialize,
+    RMC::Message: CanonicalDeserialize,
+    LMC::Output: CanonicalDeserialize,
+    RMC::Output: CanonicalDeserialize,
+    IPC::Output: CanonicalDeserialize,
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(GIPAProof {
+            r_commitment_steps: deserialize_vec(
+                &mut reader,
+                compress,
+                validate,
+                limits().max_rounds,
+            )?,
+            r_base: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            _gipa: PhantomData,
+        })
+    }
+}
+
+impl<IP, LMC, RMC, IPC, D> Validate for GIPAProof<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: Validate,
+    RMC::Message: Validate,
+    LMC::Output: Validate,
+    RMC::Output: Validate,
+    IPC::Output: Validate,
+{
+    fn validate(&self) -> Result<(), Error> {
+        self.r_commitment_steps.validate()?;
+        self.r_base.validate()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl_canonical_serde!([IP, LMC, RMC, IPC, D] GIPAProof<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+);
+
+impl<IP, LMC, RMC, IPC, D> Clone for GIPAProverState<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    fn clone(&self) -> Self {
+        GIPAProverState {
+            m_a: self.m_a.clone(),
+            m_b: self.m_b.clone(),
+            ck_a: self.ck_a.clone(),
+            ck_b: self.ck_b.clone(),
+            ck_t: self.ck_t.clone(),
+            r_commitment_steps: self.r_commitment_steps.clone(),
+            r_transcript: self.r_transcript.clone(),
+            seed: self.seed,
+            _gipa: PhantomData,
+        }
+    }
+}
+
+impl<IP, LMC, RMC, IPC, D> CanonicalSerialize for GIPAProverState<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: CanonicalSerialize,
+    RMC::Message: CanonicalSerialize,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.m_a.serialize_with_mode(&mut writer, compress)?;
+        self.m_b.serialize_with_mode(&mut writer, compress)?;
+        self.ck_a.serialize_with_mode(&mut writer, compress)?;
+        self.ck_b.serialize_with_mode(&mut writer, compress)?;
+        self.ck_t.serialize_with_mode(&mut writer, compress)?;
+        self.r_commitment_steps
+            .serialize_with_mode(&mut writer, compress)?;
+        self.r_transcript
+            .serialize_with_mode(&mut writer, compress)?;
+        self.seed.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.m_a.serialized_size(compress)
+            + self.m_b.serialized_size(compress)
+            + self.ck_a.serialized_size(compress)
+            + self.ck_b.serialized_size(compress)
+            + self.ck_t.serialized_size(compress)
+            + self.r_commitment_steps.serialized_size(compress)
+            + self.r_transcript.serialized_size(compress)
+            + self.seed.serialized_size(compress)
+    }
+}
+
+impl<IP, LMC, RMC, IPC, D> Valid for GIPAProverState<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: CanonicalDeserialize,
+    RMC::Message: CanonicalDeserialize,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        self.m_a.check()?;
+        self.m_b.check()?;
+        self.ck_a.check()?;
+        self.ck_b.check()?;
+        self.ck_t.check()?;
+        self.r_commitment_steps.check()?;
+        self.r_transcript.check()?;
+        self.seed.check()
+    }
+}
+
+impl<IP, LMC, RMC, IPC, D> CanonicalDeserialize for GIPAProverState<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    LMC::Message: CanonicalDeserialize,
+    RMC::Message: CanonicalDeserialize,
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        let limits = limits();
+        let state = GIPAProverState {
+            m_a: deserialize_vec(&mut reader, compress, validate, limits.max_vector_len)?,
+            m_b: deserialize_vec(&mut reader, compress, validate, limits.max_vector_len)?,
+            ck_a: deserialize_vec(&mut reader, compress, validate, limits.max_vector_len)?,
+            ck_b: deserialize_vec(&mut reader, compress, validate, limits.max_vector_len)?,
+            ck_t: deserialize_vec(&mut reader, compress, validate, limits.max_vector_len)?,
+            r_commitment_steps: deserialize_vec(
+                &mut reader,
+                compress,
+                validate,
+                limits.max_rounds,
+            )?,
+            r_transcript: deserialize_vec(&mut reader, compress, validate, limits.max_rounds)?,
+            seed: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            _gipa: PhantomData,
+        };
+        // A state that the prover could not have reached would fail later rounds or give a bad proof
+        let len = state.m_a.len();
+        if check_block_lengths(len, state.m_b.len()).is_err()
+            || state.ck_a.len() != len
+            || state.ck_b.len() != state.m_b.len()
+            || state.r_commitment_steps.len() != state.r_transcript.len()
+        {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(state)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use ark_bls12_381::Bls12_381;
-    use ark_ec::PairingEngine;
-    use ark_ff::UniformRand;
-    use blake2::Blake2b;
+    use ark_ec::pairing::Pairing;
+    use ark_ff::{UniformRand, Zero};
+    use blake2::Blake2b512;
     use rand::{rngs::StdRng, SeedableRng};
 
     use ark_dh_commitments::{
@@ -451,16 +1287,18 @@ mod tests {
 
     type GC1 = AFGHOCommitmentG1<Bls12_381>;
     type GC2 = AFGHOCommitmentG2<Bls12_381>;
-    type SC1 = PedersenCommitment<<Bls12_381 as PairingEngine>::G1Projective>;
-    type SC2 = PedersenCommitment<<Bls12_381 as PairingEngine>::G2Projective>;
+    type SC1 = PedersenCommitment<<Bls12_381 as Pairing>::G1>;
+    type SC2 = PedersenCommitment<<Bls12_381 as Pairing>::G2>;
     const TEST_SIZE: usize = 8;
 
     #[test]
     fn pairing_inner_product_test() {
         type IP = PairingInnerProduct<Bls12_381>;
-        type IPC =
-            IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>;
-        type PairingGIPA = GIPA<IP, GC1, GC2, IPC, Blake2b>;
+        type IPC = IdentityCommitment<
+            ExtensionFieldElement<Bls12_381>,
+            <Bls12_381 as Pairing>::ScalarField,
+        >;
+        type PairingGIPA = GIPA<IP, GC1, GC2, IPC, Blake2b512>;
 
         let mut rng = StdRng::seed_from_u64(0u64);
         let (ck_a, ck_b, ck_t) = PairingGIPA::setup(&mut rng, TEST_SIZE).unwrap();
@@ -469,7 +1307,7 @@ mod tests {
         let com_a = GC1::commit(&ck_a, &m_a).unwrap();
         let com_b = GC2::commit(&ck_b, &m_b).unwrap();
         let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
-        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+        let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
 
         let proof = PairingGIPA::prove(
             (&m_a, &m_b, &t[0]),
@@ -481,28 +1319,195 @@ mod tests {
         assert!(
             PairingGIPA::verify((&ck_a, &ck_b, &ck_t), (&com_a, &com_b, &com_t), &proof,).unwrap()
         );
+
+        // Proofs with a round cut off or appended do not fold the keys
+        let mut truncated = proof.clone();
+        truncated.r_commitment_steps.pop();
+        let mut extended = proof.clone();
+        extended
+            .r_commitment_steps
+            .push(proof.r_commitment_steps[0].clone());
+        for malformed in [&truncated, &extended] {
+            assert!(matches!(
+                PairingGIPA::verify((&ck_a, &ck_b, &ck_t), (&com_a, &com_b, &com_t), malformed),
+                Err(InnerProductArgumentError::TranscriptKeyMismatch { .. })
+            ));
+        }
     }
 
     #[test]
-    fn multiexponentiation_inner_product_test() {
-        type IP = MultiexponentiationInnerProduct<<Bls12_381 as PairingEngine>::G1Projective>;
+    fn verify_with_challenges_test() {
+        type IP = PairingInnerProduct<Bls12_381>;
+        type IPC = IdentityCommitment<
+            ExtensionFieldElement<Bls12_381>,
+            <Bls12_381 as Pairing>::ScalarField,
+        >;
+        type PairingGIPA = GIPA<IP, GC1, GC2, IPC, Blake2b512>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (ck_a, ck_b, ck_t) = PairingGIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        let (com_a, com_b, com_t) =
+            PairingGIPA::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        let (proof, aux) = PairingGIPA::prove_with_aux(
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, ark_std::slice::from_ref(&ck_t)),
+        )
+        .unwrap();
+        let ck = (&ck_a[..], &ck_b[..], &ck_t);
+        let com = (&com_a, &com_b, &com_t);
+
+        // Same challenges as derived by the verifier, without hashing them again
+        let (_, challenges) =
+            PairingGIPA::verify_recursive_challenge_transcript(com, &proof).unwrap();
+        assert_eq!(aux.r_transcript(), &challenges[..]);
+        let counters = ark_std::sync::Arc::new(crate::metrics::Counters::default());
+        let valid = crate::metrics::with_metrics(counters.clone(), || {
+            PairingGIPA::verify_with_challenges(ck, com, &proof, aux.r_transcript()).unwrap()
+        });
+        assert!(valid);
+        assert_eq!(
+            counters
+                .hashes
+                .load(ark_std::sync::atomic::Ordering::Relaxed),
+            0
+        );
+
+        // Other challenges, or the right ones in the wrong order, fail
+        let mut reordered = challenges.clone();
+        reordered.swap(0, 1);
+        assert!(!PairingGIPA::verify_with_challenges(ck, com, &proof, &reordered).unwrap());
+        assert!(matches!(
+            PairingGIPA::verify_with_challenges(ck, com, &proof, &challenges[1..]),
+            Err(InnerProductArgumentError::ChallengeCountMismatch { .. })
+        ));
+        let half = (&ck_a[..TEST_SIZE / 2], &ck_b[..TEST_SIZE / 2], &ck_t);
+        assert!(matches!(
+            PairingGIPA::verify_with_challenges(half, com, &proof, &challenges),
+            Err(InnerProductArgumentError::TranscriptKeyMismatch { .. })
+        ));
+        let mut zero = challenges.clone();
+        zero[0] = <Bls12_381 as Pairing>::ScalarField::zero();
+        assert!(matches!(
+            PairingGIPA::verify_with_challenges(ck, com, &proof, &zero),
+            Err(InnerProductArgumentError::ZeroChallenge)
+        ));
+    }
+
+    #[test]
+    fn split_verification_test() {
+        type IP = PairingInnerProduct<Bls12_381>;
         type IPC = IdentityCommitment<
-            <Bls12_381 as PairingEngine>::G1Projective,
-            <Bls12_381 as PairingEngine>::Fr,
+            ExtensionFieldElement<Bls12_381>,
+            <Bls12_381 as Pairing>::ScalarField,
         >;
-        type MultiExpGIPA = GIPA<IP, GC1, SC1, IPC, Blake2b>;
+        type PairingGIPA = GIPA<IP, GC1, GC2, IPC, Blake2b512>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (ck_a, ck_b, ck_t) = PairingGIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        let (com_a, com_b, com_t) =
+            PairingGIPA::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        let (proof, aux) = PairingGIPA::prove_with_aux(
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, ark_std::slice::from_ref(&ck_t)),
+        )
+        .unwrap();
+        let ck = (&ck_a[..], &ck_b[..], &ck_t);
+
+        // The consistency check folds the statement without the keys, and the final opening
+        // with keys folded apart, e.g. received along with the final messages
+        let (com_final, challenges) =
+            PairingGIPA::verify_commitment_consistency((&com_a, &com_b, &com_t), &proof)
+                .unwrap()
+                .unwrap();
+        assert_eq!(aux.r_transcript(), &challenges[..]);
+        let (ck_a_final, ck_b_final) = PairingGIPA::final_commitment_keys(ck, &challenges).unwrap();
+        assert!(ck_a_final == aux.ck_base.0 && ck_b_final == aux.ck_base.1);
+        let ck_final = (&ck_a_final, &ck_b_final, &ck_t);
+        let com_final = (&com_final.0, &com_final.1, &com_final.2);
+        assert!(PairingGIPA::verify_final_opening(ck_final, &proof.r_base, com_final).unwrap());
+
+        // Final messages other than the proof's do not open the final statement
+        let other = (proof.r_base.0 + proof.r_base.0, proof.r_base.1);
+        assert!(!PairingGIPA::verify_final_opening(ck_final, &other, com_final).unwrap());
+        assert!(PairingGIPA::final_commitment_keys(ck, &challenges[1..]).is_err());
+    }
+
+    #[test]
+    fn resume_from_checkpoint_test() {
+        type IP = PairingInnerProduct<Bls12_381>;
+        type IPC = IdentityCommitment<
+            ExtensionFieldElement<Bls12_381>,
+            <Bls12_381 as Pairing>::ScalarField,
+        >;
+        type PairingGIPA = GIPA<IP, GC1, GC2, IPC, Blake2b512>;
+        type PairingGIPAState = GIPAProverState<IP, GC1, GC2, IPC, Blake2b512>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (ck_a, ck_b, ck_t) = PairingGIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = random_generators(&mut rng, TEST_SIZE);
+        let ck = (&ck_a[..], &ck_b[..], &[ck_t.clone()][..]);
+
+        let mut checkpoints = Vec::new();
+        let (proof, _) = PairingGIPA::prove_from_state(
+            PairingGIPA::prover_state((&m_a, &m_b), ck).unwrap(),
+            |state| {
+                let mut bytes = Vec::new();
+                state.serialize_compressed(&mut bytes)?;
+                checkpoints.push(bytes);
+                Ok::<_, Error>(())
+            },
+        )
+        .unwrap();
+        assert_eq!(checkpoints.len(), 3);
+        let mut proof_bytes = Vec::new();
+        proof.serialize_compressed(&mut proof_bytes).unwrap();
+
+        // Resuming after any round gives the same proof as the uninterrupted run
+        for (i, bytes) in checkpoints.iter().enumerate() {
+            let state = PairingGIPAState::deserialize_compressed(&bytes[..]).unwrap();
+            assert_eq!(state.rounds(), i + 1);
+            assert_eq!(state.len(), TEST_SIZE >> (i + 1));
+            let (resumed, _) =
+                PairingGIPA::prove_from_state(state, |_| Ok::<_, Error>(())).unwrap();
+            let mut resumed_bytes = Vec::new();
+            resumed.serialize_compressed(&mut resumed_bytes).unwrap();
+            assert_eq!(resumed_bytes, proof_bytes);
+        }
+
+        // A failing checkpoint stops the prover
+        let stopped = PairingGIPA::prove_from_state(
+            PairingGIPA::prover_state((&m_a, &m_b), ck).unwrap(),
+            |_| Err(InnerProductArgumentError::InnerProductInvalid),
+        );
+        assert!(matches!(
+            stopped,
+            Err(InnerProductArgumentError::InnerProductInvalid)
+        ));
+    }
+
+    #[test]
+    fn multiexponentiation_inner_product_test() {
+        type IP = MultiexponentiationInnerProduct<<Bls12_381 as Pairing>::G1>;
+        type IPC =
+            IdentityCommitment<<Bls12_381 as Pairing>::G1, <Bls12_381 as Pairing>::ScalarField>;
+        type MultiExpGIPA = GIPA<IP, GC1, SC1, IPC, Blake2b512>;
 
         let mut rng = StdRng::seed_from_u64(0u64);
         let (ck_a, ck_b, ck_t) = MultiExpGIPA::setup(&mut rng, TEST_SIZE).unwrap();
         let m_a = random_generators(&mut rng, TEST_SIZE);
         let mut m_b = Vec::new();
         for _ in 0..TEST_SIZE {
-            m_b.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
+            m_b.push(<Bls12_381 as Pairing>::ScalarField::rand(&mut rng));
         }
         let com_a = GC1::commit(&ck_a, &m_a).unwrap();
         let com_b = SC1::commit(&ck_b, &m_b).unwrap();
         let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
-        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+        let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
 
         let proof = MultiExpGIPA::prove(
             (&m_a, &m_b, &t[0]),
@@ -517,24 +1522,79 @@ mod tests {
     }
 
     #[test]
-    fn scalar_inner_product_test() {
-        type IP = ScalarInnerProduct<<Bls12_381 as PairingEngine>::Fr>;
+    fn asymmetric_inner_product_test() {
+        type IP = MultiexponentiationInnerProduct<<Bls12_381 as Pairing>::G1>;
         type IPC =
-            IdentityCommitment<<Bls12_381 as PairingEngine>::Fr, <Bls12_381 as PairingEngine>::Fr>;
-        type ScalarGIPA = GIPA<IP, SC2, SC2, IPC, Blake2b>;
+            IdentityCommitment<<Bls12_381 as Pairing>::G1, <Bls12_381 as Pairing>::ScalarField>;
+        type MultiExpGIPA = GIPA<IP, GC1, SC1, IPC, Blake2b512>;
+        const BLOCK_SIZE: usize = 2;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (ck_a, ck_b, ck_t) =
+            MultiExpGIPA::setup_asymmetric(&mut rng, TEST_SIZE, BLOCK_SIZE).unwrap();
+        assert_eq!((ck_a.len(), ck_b.len()), (TEST_SIZE, BLOCK_SIZE));
+        let m_a = random_generators(&mut rng, TEST_SIZE);
+        let m_b = (0..BLOCK_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::ScalarField::rand(&mut rng))
+            .collect::<Vec<_>>();
+        // Same as the inner product with the right message repeated
+        let t = IP::block_inner_product(&m_a, &m_b).unwrap();
+        let tiled = m_b
+            .iter()
+            .cycle()
+            .take(TEST_SIZE)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(t, IP::inner_product(&m_a, &tiled).unwrap());
+
+        let ck = (&ck_a[..], &ck_b[..], &ck_t);
+        let (com_a, com_b, com_t) = MultiExpGIPA::commit((&m_a, &m_b), ck).unwrap();
+        let com = (&com_a, &com_b, &com_t);
+        let proof = MultiExpGIPA::prove((&m_a, &m_b, &t), ck, com).unwrap();
+        assert_eq!(proof.r_commitment_steps.len(), 3);
+        assert!(MultiExpGIPA::verify(ck, com, &proof).unwrap());
+        let (_, challenges) =
+            MultiExpGIPA::verify_recursive_challenge_transcript(com, &proof).unwrap();
+        assert!(MultiExpGIPA::verify_with_challenges(ck, com, &proof, &challenges).unwrap());
+
+        // Inner products of the halves of the left message not summing to the statement
+        let mut tampered = proof.clone();
+        let first = tampered.r_commitment_steps.len() - 1;
+        tampered.r_commitment_steps[first].0 .2 = tampered.r_commitment_steps[first].1 .2.clone();
+        assert!(!MultiExpGIPA::verify(ck, com, &tampered).unwrap());
+        // Nor for another claimed inner product
+        let other_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &[t + t]).unwrap();
+        assert!(!MultiExpGIPA::verify(ck, (&com_a, &com_b, &other_t), &proof).unwrap());
+
+        // Right messages longer than the left one, or not dividing it
+        assert!(MultiExpGIPA::setup_asymmetric(&mut rng, BLOCK_SIZE, TEST_SIZE).is_err());
+        assert!(
+            MultiExpGIPA::prover_state((&m_a, &m_b[..1]), (&ck_a, &ck_b[..1], &[ck_t])).is_ok()
+        );
+        assert!(MultiExpGIPA::prover_state((&m_a[..6], &m_b), (&ck_a, &ck_b, &[])).is_err());
+    }
+
+    #[test]
+    fn scalar_inner_product_test() {
+        type IP = ScalarInnerProduct<<Bls12_381 as Pairing>::ScalarField>;
+        type IPC = IdentityCommitment<
+            <Bls12_381 as Pairing>::ScalarField,
+            <Bls12_381 as Pairing>::ScalarField,
+        >;
+        type ScalarGIPA = GIPA<IP, SC2, SC2, IPC, Blake2b512>;
 
         let mut rng = StdRng::seed_from_u64(0u64);
         let (ck_a, ck_b, ck_t) = ScalarGIPA::setup(&mut rng, TEST_SIZE).unwrap();
         let mut m_a = Vec::new();
         let mut m_b = Vec::new();
         for _ in 0..TEST_SIZE {
-            m_a.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
-            m_b.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
+            m_a.push(<Bls12_381 as Pairing>::ScalarField::rand(&mut rng));
+            m_b.push(<Bls12_381 as Pairing>::ScalarField::rand(&mut rng));
         }
         let com_a = SC2::commit(&ck_a, &m_a).unwrap();
         let com_b = SC2::commit(&ck_b, &m_b).unwrap();
         let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
-        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+        let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
 
         let proof = ScalarGIPA::prove(
             (&m_a, &m_b, &t[0]),
@@ -547,4 +1607,52 @@ mod tests {
             ScalarGIPA::verify((&ck_a, &ck_b, &ck_t), (&com_a, &com_b, &com_t), &proof,).unwrap()
         );
     }
+
+    // Pedersen commitments over a group outside of arkworks
+    #[test]
+    fn ristretto_scalar_inner_product_test() {
+        use ark_dh_commitments::pedersen::ristretto::{Fr, RistrettoPedersen};
+
+        type IP = ScalarInnerProduct<Fr>;
+        type IPC = IdentityCommitment<Fr, Fr>;
+        type RistrettoGIPA = GIPA<IP, RistrettoPedersen, RistrettoPedersen, IPC, Blake2b512>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (ck_a, ck_b, ck_t) = RistrettoGIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let m_a = (0..TEST_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let (com_a, com_b, com_t) =
+            RistrettoGIPA::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        let t = IP::inner_product(&m_a, &m_b).unwrap();
+
+        let proof = RistrettoGIPA::prove(
+            (&m_a, &m_b, &t),
+            (&ck_a, &ck_b, &ck_t),
+            (&com_a, &com_b, &com_t),
+        )
+        .unwrap();
+        assert!(
+            RistrettoGIPA::verify((&ck_a, &ck_b, &ck_t), (&com_a, &com_b, &com_t), &proof).unwrap()
+        );
+
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let proof = CanonicalDeserialize::deserialize_compressed(&bytes[..]).unwrap();
+        assert!(
+            RistrettoGIPA::verify((&ck_a, &ck_b, &ck_t), (&com_a, &com_b, &com_t), &proof).unwrap()
+        );
+
+        let wrong_t = t + Fr::from(1u64);
+        let wrong_com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &[wrong_t]).unwrap();
+        assert!(!RistrettoGIPA::verify(
+            (&ck_a, &ck_b, &ck_t),
+            (&com_a, &com_b, &wrong_com_t),
+            &proof
+        )
+        .unwrap());
+    }
 }
@@ -0,0 +1,331 @@
+use ark_ff::Field;
+use ark_std::{marker::PhantomData, vec, vec::Vec};
+
+use crate::{
+    gipa::{GIPAAux, GIPAProof, GIPAProverState, GIPA},
+    transcript::TranscriptDigest,
+    Error, InnerProductArgumentError,
+};
+use ark_dh_commitments::{DoublyHomomorphicCommitment, ScalarMul};
+use ark_inner_products::InnerProduct;
+
+// GIPA run interactively: each round the prover sends its round commitments with `next_message`
+// and folds with the challenge it receives, which the verifier chooses and folds the statement
+// with. Challenges can be drawn from any distribution of nonzero scalars, e.g. to study the
+// soundness of shorter challenges, and rounds can be interleaved with other protocols without a
+// Fiat-Shamir digest. `D` only selects the proof types and is never hashed with
+pub struct InteractiveProver<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    state: GIPAProverState<IP, LMC, RMC, IPC, D>,
+    sent: Option<(
+        (LMC::Output, RMC::Output, IPC::Output),
+        (LMC::Output, RMC::Output, IPC::Output),
+    )>,
+}
+
+impl<IP, LMC, RMC, IPC, D> InteractiveProver<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    pub fn new(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &[IPC::Key]),
+    ) -> Result<Self, Error> {
+        // The verifier folds the right commitment in every round
+        if values.0.len() != values.1.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                values.0.len(),
+                values.1.len(),
+            ));
+        }
+        Ok(InteractiveProver {
+            state: GIPA::prover_state(values, ck)?,
+            sent: None,
+        })
+    }
+
+    // Rounds left, each one message and one challenge
+    pub fn rounds_left(&self) -> usize {
+        self.state.len().trailing_zeros() as usize
+    }
+
+    // Round commitments of the next round. Sending them again before the challenge is received
+    // gives the same commitments
+    pub fn next_message(
+        &mut self,
+    ) -> Result<
+        (
+            (LMC::Output, RMC::Output, IPC::Output),
+            (LMC::Output, RMC::Output, IPC::Output),
+        ),
+        Error,
+    > {
+        if self.rounds_left() == 0 {
+            return Err(InnerProductArgumentError::UnexpectedStep("final message"));
+        }
+        if self.sent.is_none() {
+            let _round = phase_span!("gipa_round", len = self.state.len());
+            phase_count!(gipa_rounds, 1);
+            self.sent = Some(GIPA::_round_commitments(&self.state)?);
+        }
+        Ok(self.sent.clone().unwrap())
+    }
+
+    // Folds the messages and keys with the challenge of the round
+    pub fn receive_challenge(&mut self, c: LMC::Scalar) -> Result<(), Error> {
+        if self.sent.is_none() {
+            return Err(InnerProductArgumentError::UnexpectedStep("round message"));
+        }
+        let c_inv = c
+            .inverse()
+            .ok_or(InnerProductArgumentError::ZeroChallenge)?;
+        let com = self.sent.take().unwrap();
+        GIPA::_finish_round(&mut self.state, com, (c, c_inv))
+    }
+
+    // Messages folded to a single element, sent once the rounds are done
+    pub fn final_message(&self) -> Result<(LMC::Message, RMC::Message), Error> {
+        if self.rounds_left() != 0 {
+            return Err(InnerProductArgumentError::UnexpectedStep("round message"));
+        }
+        Ok((self.state.m_a[0].clone(), self.state.m_b[0].clone()))
+    }
+
+    // Proof of the rounds run, with the final message as its base
+    pub fn finish(
+        self,
+    ) -> Result<
+        (
+            GIPAProof<IP, LMC, RMC, IPC, D>,
+            GIPAAux<IP, LMC, RMC, IPC, D>,
+        ),
+        Error,
+    > {
+        if self.rounds_left() != 0 {
+            return Err(InnerProductArgumentError::UnexpectedStep("round message"));
+        }
+        GIPA::prove_from_state(self.state, |_| Ok::<_, Error>(()))
+    }
+}
+
+pub struct InteractiveVerifier<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    ck_a: Vec<LMC::Key>,
+    ck_b: Vec<RMC::Key>,
+    ck_t: IPC::Key,
+    com: (LMC::Output, RMC::Output, IPC::Output),
+    received: Option<(
+        (LMC::Output, RMC::Output, IPC::Output),
+        (LMC::Output, RMC::Output, IPC::Output),
+    )>,
+    r_transcript: Vec<LMC::Scalar>,
+    _gipa: PhantomData<GIPA<IP, LMC, RMC, IPC, D>>,
+}
+
+impl<IP, LMC, RMC, IPC, D> InteractiveVerifier<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    pub fn new(
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+    ) -> Result<Self, Error> {
+        if !ck.0.len().is_power_of_two() || ck.0.len() != ck.1.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                ck.0.len(),
+                ck.1.len(),
+            ));
+        }
+        Ok(InteractiveVerifier {
+            ck_a: ck.0.to_vec(),
+            ck_b: ck.1.to_vec(),
+            ck_t: ck.2.clone(),
+            com: (com.0.clone(), com.1.clone(), com.2.clone()),
+            received: None,
+            r_transcript: Vec::new(),
+            _gipa: PhantomData,
+        })
+    }
+
+    pub fn rounds_left(&self) -> usize {
+        self.ck_a.len().trailing_zeros() as usize - self.r_transcript.len()
+    }
+
+    pub fn receive_message(
+        &mut self,
+        com: (
+            (LMC::Output, RMC::Output, IPC::Output),
+            (LMC::Output, RMC::Output, IPC::Output),
+        ),
+    ) -> Result<(), Error> {
+        if self.received.is_some() {
+            return Err(InnerProductArgumentError::UnexpectedStep("challenge"));
+        }
+        if self.rounds_left() == 0 {
+            return Err(InnerProductArgumentError::UnexpectedStep("final message"));
+        }
+        self.received = Some(com);
+        Ok(())
+    }
+
+    // Folds the statement with `c`, the challenge of the round to be sent to the prover
+    pub fn send_challenge(&mut self, c: LMC::Scalar) -> Result<(), Error> {
+        if self.received.is_none() {
+            return Err(InnerProductArgumentError::UnexpectedStep("round message"));
+        }
+        let c_inv = c
+            .inverse()
+            .ok_or(InnerProductArgumentError::ZeroChallenge)?;
+        let (com_1, com_2) = self.received.take().unwrap();
+        let (com_a, com_b, com_t) = &self.com;
+        self.com = (
+            com_1.0.scalar_mul(&c) + com_a.clone() + com_2.0.scalar_mul(&c_inv),
+            com_1.1.scalar_mul(&c) + com_b.clone() + com_2.1.scalar_mul(&c_inv),
+            com_1.2.scalar_mul(&c) + com_t.clone() + com_2.2.scalar_mul(&c_inv),
+        );
+        self.r_transcript.push(c);
+        Ok(())
+    }
+
+    // Checks the final message of the prover, the messages folded to a single element, against
+    // the folded statement and keys
+    pub fn verify(mut self, r_base: &(LMC::Message, RMC::Message)) -> Result<bool, Error> {
+        if self.received.is_some() {
+            return Err(InnerProductArgumentError::UnexpectedStep("challenge"));
+        }
+        if self.rounds_left() != 0 {
+            return Err(InnerProductArgumentError::UnexpectedStep("round message"));
+        }
+        self.r_transcript.reverse();
+        let (ck_a_base, ck_b_base) = GIPA::<IP, LMC, RMC, IPC, D>::_compute_final_commitment_keys(
+            (&self.ck_a, &self.ck_b, &self.ck_t),
+            &self.r_transcript,
+        )?;
+        GIPA::<IP, LMC, RMC, IPC, D>::_verify_base_commitment(
+            (&ck_a_base, &ck_b_base, &vec![self.ck_t]),
+            self.com,
+            r_base,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{PairingGIPA, PairingIPC};
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use ark_dh_commitments::afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2};
+    use ark_ff::UniformRand;
+    use ark_inner_products::PairingInnerProduct;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    type TestGIPA = PairingGIPA<Bls12_381, Blake2b512>;
+    type TestProver = InteractiveProver<
+        PairingInnerProduct<Bls12_381>,
+        AFGHOCommitmentG1<Bls12_381>,
+        AFGHOCommitmentG2<Bls12_381>,
+        PairingIPC<Bls12_381>,
+        Blake2b512,
+    >;
+    type TestVerifier = InteractiveVerifier<
+        PairingInnerProduct<Bls12_381>,
+        AFGHOCommitmentG1<Bls12_381>,
+        AFGHOCommitmentG2<Bls12_381>,
+        PairingIPC<Bls12_381>,
+        Blake2b512,
+    >;
+
+    #[test]
+    fn interactive_gipa_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (ck_a, ck_b, ck_t) = TestGIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = TestGIPA::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+
+        let run = |rng: &mut StdRng, m_a: &[G1Projective]| {
+            let mut prover =
+                TestProver::new((m_a, &m_b), (&ck_a, &ck_b, ark_std::slice::from_ref(&ck_t)))
+                    .unwrap();
+            let mut verifier =
+                TestVerifier::new((&ck_a, &ck_b, &ck_t), (&com.0, &com.1, &com.2)).unwrap();
+            let mut rounds = 0;
+            while prover.rounds_left() > 0 {
+                verifier
+                    .receive_message(prover.next_message().unwrap())
+                    .unwrap();
+                // 64-bit challenges in place of the 128-bit Fiat-Shamir ones
+                let c = Fr::from(rng.next_u64());
+                verifier.send_challenge(c).unwrap();
+                prover.receive_challenge(c).unwrap();
+                rounds += 1;
+            }
+            assert_eq!(rounds, 3);
+            assert!(prover.next_message().is_err());
+            let r_base = prover.final_message().unwrap();
+            let (proof, _) = prover.finish().unwrap();
+            assert!(proof.r_base == r_base);
+            verifier.verify(&r_base).unwrap()
+        };
+        assert!(run(&mut rng, &m_a));
+        let mut other_m_a = m_a.clone();
+        other_m_a[1] = G1Projective::rand(&mut rng);
+        assert!(!run(&mut rng, &other_m_a));
+
+        // Steps out of order
+        let mut prover = TestProver::new(
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, ark_std::slice::from_ref(&ck_t)),
+        )
+        .unwrap();
+        assert!(prover.receive_challenge(Fr::from(2u64)).is_err());
+        prover.next_message().unwrap();
+        assert!(matches!(
+            prover.receive_challenge(Fr::from(0u64)),
+            Err(InnerProductArgumentError::ZeroChallenge)
+        ));
+    }
+}
@@ -1,43 +1,159 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(warnings, unused, future_incompatible, nonstandard_style)]
-use std::{
-    error::Error as ErrorTrait,
-    fmt::{Display, Formatter, Result as FmtResult},
-    ops::MulAssign,
-};
+#![allow(clippy::type_complexity, clippy::upper_case_acronyms)]
+extern crate alloc;
+
+use ark_dh_commitments::CommitmentError;
+use ark_inner_products::InnerProductError;
+use ark_serialize::SerializationError;
+use ark_std::string::String;
+use thiserror::Error;
+
+// Concatenated canonical serializations of the values, for hashing into Fiat-Shamir challenges
+macro_rules! canonical_bytes {
+    ($($x:expr),*) => {
+        (|| -> Result<ark_std::vec::Vec<u8>, ark_serialize::SerializationError> {
+            #[allow(unused_imports)]
+            use ark_serialize::CanonicalSerialize;
+            let mut bytes = ark_std::vec::Vec::new();
+            $(($x).serialize_compressed(&mut bytes)?;)*
+            phase_count!(serialized_bytes, bytes.len());
+            Ok(bytes)
+        })()
+    };
+}
+
+// Enters a tracing span for a prover or verifier phase, a no-op without the tracing feature
+macro_rules! phase_span {
+    ($name:literal $(, $field:ident = $value:expr)*) => {{
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!($name $(, $field = $value)*).entered();
+        #[cfg(not(feature = "tracing"))]
+        let span = crate::NoSpan;
+        span
+    }};
+}
+
+// Stands in for an entered span without the tracing feature, so phases bind a guard either way
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
+
+// Adds to a counter, emitted as a `monotonic_counter.` event so metrics layers can aggregate it,
+// and reported to the `Metrics` hook of the current thread
+macro_rules! phase_count {
+    ($name:ident, $value:expr) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(monotonic_counter.$name = $value as u64);
+        #[cfg(any(feature = "metrics", test))]
+        crate::metrics::report(|metrics| metrics.$name($value as u64));
+    };
+}
+
+// Counts a Fiat-Shamir digest of the serialized input
+macro_rules! hash_count {
+    ($input:expr) => {
+        phase_count!(hashes, 1);
+        phase_count!(hashed_bytes, $input.len());
+    };
+}
+
+// Records a transcript message, or a challenge with the nonce it was derived with, in the
+// `TranscriptLog` of the current thread. Without the transcript-log feature it expands to nothing,
+// so its arguments are not evaluated
+macro_rules! transcript_log {
+    ($protocol:literal, $round:expr, $label:expr, $value:expr $(, nonce = $nonce:expr)?) => {
+        #[cfg(any(feature = "transcript-log", test))]
+        crate::transcript_log::record(
+            $protocol,
+            $round,
+            $label,
+            $value,
+            None$(.or(Some($nonce as u64)))?,
+        );
+    };
+}
 
 pub mod applications;
+#[cfg(any(feature = "bn254", test))]
+pub mod bn254;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(any(feature = "config", test))]
+pub mod config;
+pub mod cost_model;
+pub mod distributed;
+pub mod exponentiation;
 pub mod gipa;
+pub mod interactive;
+pub mod limits;
+#[cfg(feature = "memory-stats")]
+pub mod memory;
+#[cfg(any(feature = "metrics", test))]
+pub mod metrics;
+pub mod padding;
+pub mod prelude;
+pub mod session;
+#[cfg(any(feature = "bn254", test))]
+pub mod solidity;
+pub mod sponge;
+pub mod tasks;
 pub mod tipa;
+pub mod transcript;
+#[cfg(any(feature = "transcript-log", test))]
+pub mod transcript_log;
+pub mod validation;
 
-pub type Error = Box<dyn ErrorTrait>;
-
-//TODO: helper function for mul because relying on MulAssign
-pub(crate) fn mul_helper<T: MulAssign<F> + Clone, F: Clone>(t: &T, f: &F) -> T {
-    let mut clone = t.clone();
-    clone.mul_assign(f.clone());
-    clone
-}
+pub type Error = InnerProductArgumentError;
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum InnerProductArgumentError {
+    #[error("left length, right length: {0}, {1}")]
     MessageLengthInvalid(usize, usize),
+    #[error("inner product not sound")]
     InnerProductInvalid,
+    #[error("{0} SRS powers is not 2n - 1 for n > 1")]
+    SrsLength(usize),
+    #[error("transcript of length {transcript} does not fold key of length {key}")]
+    TranscriptKeyMismatch { transcript: usize, key: usize },
+    #[error("{challenges} challenges for a proof of {rounds} rounds")]
+    ChallengeCountMismatch { challenges: usize, rounds: usize },
+    #[error("checkpoint is for a different verifying key")]
+    CheckpointMismatch,
+    #[error("group element not on the curve")]
+    NotOnCurve,
+    #[error("group element not in the prime order subgroup")]
+    NotInSubgroup,
+    #[error("field element not reduced")]
+    NotReduced,
+    #[error("SRS generator is the identity")]
+    IdentityGenerator,
+    #[error("{limit} {size} exceeds the limit of {max}")]
+    LimitExceeded {
+        limit: &'static str,
+        size: usize,
+        max: usize,
+    },
+    #[error("interactive protocol expected a {0}")]
+    UnexpectedStep(&'static str),
+    #[error("challenge is zero")]
+    ZeroChallenge,
+    #[error("proof has no GIPA rounds")]
+    EmptyTranscript,
+    #[error("message is empty")]
+    EmptyMessage,
+    #[error("unknown {option} {name}")]
+    UnknownOption { option: &'static str, name: String },
+    #[error(transparent)]
+    Commitment(#[from] CommitmentError),
+    #[error(transparent)]
+    InnerProduct(#[from] InnerProductError),
+    // Not a source, as without std serialization errors do not implement the core error trait
+    #[error("serialization: {0}")]
+    Serialization(SerializationError),
 }
 
-impl ErrorTrait for InnerProductArgumentError {
-    fn source(self: &Self) -> Option<&(dyn ErrorTrait + 'static)> {
-        None
-    }
-}
-
-impl Display for InnerProductArgumentError {
-    fn fmt(self: &Self, f: &mut Formatter<'_>) -> FmtResult {
-        let msg = match self {
-            InnerProductArgumentError::MessageLengthInvalid(left, right) => {
-                format!("left length, right length: {}, {}", left, right)
-            }
-            InnerProductArgumentError::InnerProductInvalid => "inner product not sound".to_string(),
-        };
-        write!(f, "{}", msg)
+impl From<SerializationError> for InnerProductArgumentError {
+    fn from(e: SerializationError) -> Self {
+        InnerProductArgumentError::Serialization(e)
     }
 }
@@ -0,0 +1,170 @@
+use ark_serialize::{CanonicalDeserialize, Compress, SerializationError, Validate};
+use ark_std::{io::Read, vec::Vec};
+#[cfg(feature = "std")]
+use std::cell::Cell;
+
+use crate::{Error, InnerProductArgumentError};
+
+// Bounds on the sizes of instances accepted from untrusted sources. Deserialization checks the
+// length prefix of every vector against them before reading any element, so a proof claiming 2^40
+// rounds is rejected after reading 8 bytes rather than driving the verifier into reading or
+// allocating for each of them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    // Length of messages and commitment keys
+    pub max_vector_len: usize,
+    // Rounds of a GIPA proof, the log of the message length
+    pub max_rounds: usize,
+    // Powers in a TIPA SRS, 2n - 1 for messages of length n
+    pub max_srs_size: usize,
+}
+
+impl Limits {
+    // Messages of up to 2^24 elements, e.g. for aggregating 2^24 Groth16 proofs
+    pub const DEFAULT: Limits = Limits {
+        max_vector_len: 1 << 24,
+        max_rounds: 24,
+        max_srs_size: (1 << 25) - 1,
+    };
+
+    // Limits for messages of up to `len` elements
+    pub fn for_vector_len(len: usize) -> Self {
+        let len = len.next_power_of_two();
+        Limits {
+            max_vector_len: len,
+            max_rounds: len.trailing_zeros() as usize,
+            max_srs_size: 2 * len - 1,
+        }
+    }
+
+    pub fn check_vector_len(&self, len: usize) -> Result<(), Error> {
+        check("vector length", len, self.max_vector_len)
+    }
+
+    pub fn check_rounds(&self, rounds: usize) -> Result<(), Error> {
+        check("proof rounds", rounds, self.max_rounds)
+    }
+
+    pub fn check_srs_size(&self, size: usize) -> Result<(), Error> {
+        check("SRS size", size, self.max_srs_size)
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits::DEFAULT
+    }
+}
+
+fn check(limit: &'static str, size: usize, max: usize) -> Result<(), Error> {
+    if size > max {
+        return Err(InnerProductArgumentError::LimitExceeded { limit, size, max });
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    static LIMITS: Cell<Limits> = const { Cell::new(Limits::DEFAULT) };
+}
+
+// Runs `f` with `limits` enforced on the current thread, restoring the previous limits after
+#[cfg(feature = "std")]
+pub fn with_limits<T>(limits: Limits, f: impl FnOnce() -> T) -> T {
+    struct Restore(Limits);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            LIMITS.with(|l| l.set(self.0));
+        }
+    }
+
+    let _restore = Restore(LIMITS.with(|l| l.replace(limits)));
+    f()
+}
+
+// Limits enforced on the current thread, always the defaults without std
+pub fn limits() -> Limits {
+    #[cfg(feature = "std")]
+    return LIMITS.with(|l| l.get());
+    #[cfg(not(feature = "std"))]
+    return Limits::DEFAULT;
+}
+
+// Deserializes a vector in the canonical encoding, rejecting a length over `max` before reading
+// any element
+pub(crate) fn deserialize_vec<T: CanonicalDeserialize, R: Read>(
+    mut reader: R,
+    compress: Compress,
+    validate: Validate,
+    max: usize,
+) -> Result<Vec<T>, SerializationError> {
+    let len = u64::deserialize_with_mode(&mut reader, compress, validate)?;
+    if len > max as u64 {
+        return Err(SerializationError::InvalidData);
+    }
+    (0..len)
+        .map(|_| T::deserialize_with_mode(&mut reader, compress, validate))
+        .collect::<Result<Vec<_>, _>>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{PairingTIPA, PairingTIPAProof};
+    use ark_bls12_381::{Bls12_381, G1Projective, G2Projective};
+    use ark_ff::UniformRand;
+    use ark_serialize::CanonicalSerialize;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    type TestTIPA = PairingTIPA<Bls12_381, Blake2b512>;
+    type TestProof = PairingTIPAProof<Bls12_381, Blake2b512>;
+
+    #[test]
+    fn limits_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = TestTIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = TestTIPA::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        let proof = TestTIPA::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+
+        // A proof header claiming 2^40 rounds, rejected before reading any round
+        let mut header = (1u64 << 40).to_le_bytes().to_vec();
+        header.extend_from_slice(&bytes[8..]);
+        assert!(matches!(
+            TestProof::deserialize_compressed(&header[..]),
+            Err(SerializationError::InvalidData)
+        ));
+
+        let small = Limits::for_vector_len(TEST_SIZE / 2);
+        assert_eq!(small.max_rounds, 2);
+        with_limits(small, || {
+            assert!(TestProof::deserialize_compressed(&bytes[..]).is_err());
+            assert!(matches!(
+                TestTIPA::verify(&v_srs, &ck_t, (&com.0, &com.1, &com.2), &proof),
+                Err(InnerProductArgumentError::LimitExceeded { max: 2, .. })
+            ));
+            assert!(matches!(
+                TestTIPA::setup(&mut rng, TEST_SIZE),
+                Err(InnerProductArgumentError::LimitExceeded { .. })
+            ));
+            assert!(TestTIPA::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).is_err());
+        });
+        // Restored after
+        assert_eq!(limits(), Limits::DEFAULT);
+        let proof = TestProof::deserialize_compressed(&bytes[..]).unwrap();
+        assert!(TestTIPA::verify(&v_srs, &ck_t, (&com.0, &com.1, &com.2), &proof).unwrap());
+    }
+}
@@ -0,0 +1,125 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+// Allocator wrapping the system allocator that counts the bytes live on the heap and their
+// high-water mark. Binaries opt in to it with
+// `#[global_allocator] static ALLOCATOR: PeakAllocator = PeakAllocator::new();`
+pub struct PeakAllocator {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl PeakAllocator {
+    pub const fn new() -> Self {
+        PeakAllocator {
+            current: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn current_bytes(&self) -> usize {
+        self.current.load(Ordering::SeqCst)
+    }
+
+    pub fn peak_bytes(&self) -> usize {
+        self.peak.load(Ordering::SeqCst)
+    }
+
+    // Restarts the high-water mark from the bytes live now
+    pub fn reset_peak(&self) {
+        self.peak.store(self.current_bytes(), Ordering::SeqCst);
+    }
+
+    // Runs `f` and reports the bytes live before it and the peak reached while it ran. Allocations
+    // of all threads are counted, including those of the rayon pool
+    pub fn measure<T>(&self, f: impl FnOnce() -> T) -> (T, MemoryReport) {
+        self.reset_peak();
+        let baseline_bytes = self.current_bytes();
+        let result = f();
+        (
+            result,
+            MemoryReport {
+                baseline_bytes,
+                peak_bytes: self.peak_bytes(),
+            },
+        )
+    }
+
+    fn grow(&self, size: usize) {
+        let current = self.current.fetch_add(size, Ordering::SeqCst) + size;
+        self.peak.fetch_max(current, Ordering::SeqCst);
+    }
+
+    fn shrink(&self, size: usize) {
+        self.current.fetch_sub(size, Ordering::SeqCst);
+    }
+}
+
+impl Default for PeakAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                self.grow(new_size - layout.size());
+            } else {
+                self.shrink(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryReport {
+    pub baseline_bytes: usize,
+    pub peak_bytes: usize,
+}
+
+impl MemoryReport {
+    // Peak bytes allocated on top of those live before the measured run
+    pub fn peak_increase_bytes(&self) -> usize {
+        self.peak_bytes.saturating_sub(self.baseline_bytes)
+    }
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "peak memory {} bytes, {} bytes above the {} bytes live before",
+            self.peak_bytes,
+            self.peak_increase_bytes(),
+            self.baseline_bytes
+        )
+    }
+}
@@ -0,0 +1,108 @@
+// The hook lives in ark-inner-products, so the inner products and commitments the protocols are
+// built on report their pairings and multiexponentiations to it too
+pub use ark_inner_products::metrics::{with_metrics, Counters, Metrics};
+
+pub(crate) use ark_inner_products::metrics::report;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::PairingTIPA;
+    use ark_bls12_381::{Bls12_381, G1Projective, G2Projective};
+    use ark_ff::UniformRand;
+    use ark_std::sync::{atomic::Ordering, Arc};
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn metrics_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = PairingTIPA::<Bls12_381, Blake2b512>::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = PairingTIPA::<Bls12_381, Blake2b512>::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t))
+            .unwrap();
+
+        let prover = Arc::new(Counters::default());
+        let proof = with_metrics(prover.clone(), || {
+            PairingTIPA::<Bls12_381, Blake2b512>::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t))
+                .unwrap()
+        });
+        assert_eq!(prover.gipa_rounds.load(Ordering::Relaxed), 3);
+        // Rounds of length n commit to both cross terms of both messages, n pairs each, and take
+        // the inner products of the cross terms, n / 2 pairs each. Each of the 6 takes a final
+        // exponentiation
+        assert_eq!(prover.pairings.load(Ordering::Relaxed), 3 * (8 + 4 + 2));
+        assert_eq!(prover.final_exponentiations.load(Ordering::Relaxed), 6 * 3);
+        // KZG openings of both final commitment keys
+        assert_eq!(prover.msms.load(Ordering::Relaxed), 2);
+        assert_eq!(
+            prover.msm_terms.load(Ordering::Relaxed),
+            2 * (2 * TEST_SIZE as u64 - 1)
+        );
+        // A challenge for each round and the KZG challenge
+        assert_eq!(prover.hashes.load(Ordering::Relaxed), 4);
+        assert!(prover.hashed_bytes.load(Ordering::Relaxed) > 0);
+        assert!(prover.serialized_bytes.load(Ordering::Relaxed) > 0);
+
+        let verifier = Arc::new(Counters::default());
+        assert!(with_metrics(verifier.clone(), || {
+            PairingTIPA::<Bls12_381, Blake2b512>::verify(
+                &srs.get_verifier_key(),
+                &ck_t,
+                (&com.0, &com.1, &com.2),
+                &proof,
+            )
+            .unwrap()
+        }));
+        assert_eq!(verifier.msms.load(Ordering::Relaxed), 0);
+        assert_eq!(verifier.hashes.load(Ordering::Relaxed), 4);
+        // Openings of the base commitments and inner product, a pair each, and the two KZG checks
+        assert_eq!(verifier.pairings.load(Ordering::Relaxed), 3 + 2 * 2);
+        assert_eq!(
+            verifier.final_exponentiations.load(Ordering::Relaxed),
+            3 + 2
+        );
+        assert_eq!(
+            verifier.serialized_bytes.load(Ordering::Relaxed),
+            prover.serialized_bytes.load(Ordering::Relaxed)
+        );
+
+        // Nothing is reported once the hook is uninstalled
+        PairingTIPA::<Bls12_381, Blake2b512>::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t))
+            .unwrap();
+        assert_eq!(prover.gipa_rounds.load(Ordering::Relaxed), 3);
+    }
+
+    // Work split across rayon workers is reported to the hook of the calling thread
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn metrics_parallel_test() {
+        use ark_ec::Group;
+        use ark_inner_products::{InnerProduct, PairingInnerProduct};
+        use rayon::prelude::*;
+
+        let counters = Arc::new(Counters::default());
+        with_metrics(counters.clone(), || {
+            (0..TEST_SIZE).into_par_iter().for_each(|_| {
+                PairingInnerProduct::<Bls12_381>::inner_product(
+                    &[G1Projective::generator()],
+                    &[G2Projective::generator()],
+                )
+                .unwrap();
+            })
+        });
+        assert_eq!(counters.pairings.load(Ordering::Relaxed), TEST_SIZE as u64);
+        assert_eq!(
+            counters.final_exponentiations.load(Ordering::Relaxed),
+            TEST_SIZE as u64
+        );
+    }
+}
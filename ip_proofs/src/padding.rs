@@ -0,0 +1,152 @@
+use ark_ff::{PrimeField, Zero};
+use ark_std::vec::Vec;
+
+use crate::{
+    transcript::{SharedTranscript, TranscriptDigest},
+    Error, InnerProductArgumentError,
+};
+
+// Message padded to a power of two length with identity elements, carrying its true length. Its
+// commitment is taken under the first `unpadded_len` generators of the key, which is also the
+// commitment to the padded message under the full key. Since commitments are binding, this is the
+// only opening of the commitment under the full key, so proofs over it cannot use other values at
+// the padded positions. Verifiers must take such commitments from the committer, as a prover
+// committing itself could put any values there
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaddedInstance<T> {
+    len: usize,
+    values: Vec<T>,
+}
+
+impl<T: Clone + Zero> PaddedInstance<T> {
+    pub fn new(values: &[T]) -> Result<Self, Error> {
+        if values.is_empty() {
+            return Err(InnerProductArgumentError::EmptyMessage);
+        }
+        let mut padded = values.to_vec();
+        padded.resize(values.len().next_power_of_two(), T::zero());
+        Ok(PaddedInstance {
+            len: values.len(),
+            values: padded,
+        })
+    }
+}
+
+impl<T> PaddedInstance<T> {
+    pub fn unpadded_len(&self) -> usize {
+        self.len
+    }
+
+    pub fn padded_len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn unpadded(&self) -> &[T] {
+        &self.values[..self.len]
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+}
+
+// Checks that the padded length is the next power of two after the true length, e.g. before
+// accepting a true length claimed alongside a proof
+pub fn check_padded_len(len: usize, padded_len: usize) -> Result<(), Error> {
+    if len == 0 || padded_len != len.next_power_of_two() {
+        return Err(InnerProductArgumentError::MessageLengthInvalid(
+            len, padded_len,
+        ));
+    }
+    Ok(())
+}
+
+// Seed of the first challenge of a proof over a padded instance, binding both lengths. Proofs
+// for one true length do not verify for another, nor as proofs of unpadded instances
+pub(crate) fn padding_seed<F: PrimeField, D: TranscriptDigest>(
+    len: usize,
+    padded_len: usize,
+) -> Result<F, Error> {
+    check_padded_len(len, padded_len)?;
+    let mut transcript = SharedTranscript::<D>::new(b"ripp-padded-instance");
+    transcript.absorb(b"len", &(len as u64))?;
+    transcript.absorb(b"padded-len", &(padded_len as u64))?;
+    transcript.challenge(b"padding-seed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{PairingGIPA, PairingTIPA};
+    use ark_bls12_381::{Bls12_381, G1Projective, G2Projective};
+    use ark_ff::UniformRand;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 5;
+
+    type TestTIPA = PairingTIPA<Bls12_381, Blake2b512>;
+
+    #[test]
+    fn padded_instance_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let a = PaddedInstance::new(&m_a).unwrap();
+        let b = PaddedInstance::new(&m_b).unwrap();
+        assert_eq!(a.padded_len(), 8);
+        assert!(a.values()[TEST_SIZE..].iter().all(|x| x.is_zero()));
+        assert!(matches!(
+            PaddedInstance::<G1Projective>::new(&[]),
+            Err(InnerProductArgumentError::EmptyMessage)
+        ));
+
+        let (srs, ck_t) = TestTIPA::setup(&mut rng, a.padded_len()).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let com = TestTIPA::commit_padded((&a, &b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        // Same as the commitment to the padded messages under the full keys
+        let full_com = TestTIPA::commit((a.values(), b.values()), (&ck_a, &ck_b, &ck_t)).unwrap();
+        assert!(com == full_com);
+
+        let proof = TestTIPA::prove_padded(&srs, (&a, &b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        let verify = |len| {
+            TestTIPA::verify_padded(&v_srs, &ck_t, (&com.0, &com.1, &com.2), len, &proof)
+                .unwrap_or(false)
+        };
+        assert!(verify(TEST_SIZE));
+        // Claimed for another true length, or for lengths not padded to 8
+        assert!(!verify(TEST_SIZE + 1));
+        assert!(!verify(3));
+        assert!(!verify(16));
+        // Not accepted as the proof of an unpadded instance
+        assert!(!TestTIPA::verify(&v_srs, &ck_t, (&com.0, &com.1, &com.2), &proof).unwrap());
+
+        let (ck_a, ck_b, ck_t) =
+            PairingGIPA::<Bls12_381, Blake2b512>::setup(&mut rng, a.padded_len()).unwrap();
+        let com =
+            PairingGIPA::<Bls12_381, Blake2b512>::commit_padded((&a, &b), (&ck_a, &ck_b, &ck_t))
+                .unwrap();
+        let proof =
+            PairingGIPA::<Bls12_381, Blake2b512>::prove_padded((&a, &b), (&ck_a, &ck_b, &ck_t))
+                .unwrap();
+        assert!(PairingGIPA::<Bls12_381, Blake2b512>::verify_padded(
+            (&ck_a, &ck_b, &ck_t),
+            (&com.0, &com.1, &com.2),
+            TEST_SIZE,
+            &proof
+        )
+        .unwrap());
+        assert!(!PairingGIPA::<Bls12_381, Blake2b512>::verify_padded(
+            (&ck_a, &ck_b, &ck_t),
+            (&com.0, &com.1, &com.2),
+            TEST_SIZE + 2,
+            &proof
+        )
+        .unwrap());
+    }
+}
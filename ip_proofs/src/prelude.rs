@@ -0,0 +1,217 @@
+use ark_dh_commitments::{
+    afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+    identity::IdentityCommitment,
+    pedersen::PedersenCommitment,
+};
+use ark_ec::pairing::Pairing;
+use ark_inner_products::{
+    ExtensionFieldElement, MultiexponentiationInnerProduct, PairingInnerProduct, ScalarInnerProduct,
+};
+
+pub use crate::{
+    applications::groth16_aggregation::{
+        aggregate_proofs, setup_inner_product, verify_aggregate_proof, AggregateProof,
+    },
+    gipa::{GIPAProof, GIPA},
+    tipa::{
+        structured_scalar_message::{structured_scalar_power, TIPAWithSSM, TIPAWithSSMProof},
+        TIPAProof, VerifierSRS, SRS, TIPA,
+    },
+    Error,
+};
+pub use ark_dh_commitments::DoublyHomomorphicCommitment;
+pub use ark_inner_products::InnerProduct;
+
+// Digests for `D` besides Blake2b512, e.g. where SHA-3 hashing is mandated, or Keccak-256 for
+// challenges the Solidity verifier derives on chain. Round challenges take the first 16 bytes of a
+// digest and other challenges the bytes of a scalar, so 256-bit digests give full size challenges
+// over the scalar fields of the supported curves
+#[cfg(any(feature = "sha3", test))]
+pub use sha3::{Keccak256, Sha3_256};
+#[cfg(any(feature = "blake3", test))]
+pub type Blake3 = blake3::Hasher;
+
+// The arguments instantiated over a pairing engine `P` and a digest `D`, e.g.
+// `PairingTIPA<Bls12_381, Blake2b512>`, with the traits and functions used alongside them. Pairing
+// arguments take messages in G1 and G2, and multiexponentiation arguments messages in G1 and
+// scalars. `commit` gives the commitments that verifiers take as the statement
+
+pub type PairingIPC<P> = IdentityCommitment<ExtensionFieldElement<P>, <P as Pairing>::ScalarField>;
+pub type MultiExpIPC<P> = IdentityCommitment<<P as Pairing>::G1, <P as Pairing>::ScalarField>;
+
+pub type PairingGIPA<P, D> =
+    GIPA<PairingInnerProduct<P>, AFGHOCommitmentG1<P>, AFGHOCommitmentG2<P>, PairingIPC<P>, D>;
+pub type PairingGIPAProof<P, D> =
+    GIPAProof<PairingInnerProduct<P>, AFGHOCommitmentG1<P>, AFGHOCommitmentG2<P>, PairingIPC<P>, D>;
+
+pub type MultiExpGIPA<P, D> = GIPA<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    MultiExpIPC<P>,
+    D,
+>;
+pub type MultiExpGIPAProof<P, D> = GIPAProof<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    MultiExpIPC<P>,
+    D,
+>;
+
+pub type PairingTIPA<P, D> =
+    TIPA<PairingInnerProduct<P>, AFGHOCommitmentG1<P>, AFGHOCommitmentG2<P>, PairingIPC<P>, P, D>;
+pub type PairingTIPAProof<P, D> = TIPAProof<
+    PairingInnerProduct<P>,
+    AFGHOCommitmentG1<P>,
+    AFGHOCommitmentG2<P>,
+    PairingIPC<P>,
+    P,
+    D,
+>;
+
+// GIPA for inner products of scalars committed to with Pedersen commitments in G1
+pub type ScalarIPC<P> =
+    IdentityCommitment<<P as Pairing>::ScalarField, <P as Pairing>::ScalarField>;
+pub type ScalarGIPA<P, D> = GIPA<
+    ScalarInnerProduct<<P as Pairing>::ScalarField>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    ScalarIPC<P>,
+    D,
+>;
+pub type ScalarGIPAProof<P, D> = GIPAProof<
+    ScalarInnerProduct<<P as Pairing>::ScalarField>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    PedersenCommitment<<P as Pairing>::G1>,
+    ScalarIPC<P>,
+    D,
+>;
+
+// TIPA for multiexponentiations with the structured scalar message (1, b, b^2, ...), as in the
+// aggregation of Groth16 proofs
+pub type MultiExpTIPA<P, D> = TIPAWithSSM<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    MultiExpIPC<P>,
+    P,
+    D,
+>;
+pub type MultiExpTIPAProof<P, D> = TIPAWithSSMProof<
+    MultiexponentiationInnerProduct<<P as Pairing>::G1>,
+    AFGHOCommitmentG1<P>,
+    MultiExpIPC<P>,
+    P,
+    D,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use ark_ff::UniformRand;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn prelude_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let scalars = (0..TEST_SIZE)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        let (srs, ck_t) = PairingTIPA::<Bls12_381, Blake2b512>::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let com = PairingTIPA::<Bls12_381, Blake2b512>::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t))
+            .unwrap();
+        let proof =
+            PairingTIPA::<Bls12_381, Blake2b512>::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t))
+                .unwrap();
+        assert!(PairingTIPA::<Bls12_381, Blake2b512>::verify(
+            &srs.get_verifier_key(),
+            &ck_t,
+            (&com.0, &com.1, &com.2),
+            &proof
+        )
+        .unwrap());
+
+        let (ck_a, ck_b, ck_t) =
+            MultiExpGIPA::<Bls12_381, Blake2b512>::setup(&mut rng, TEST_SIZE).unwrap();
+        let com =
+            MultiExpGIPA::<Bls12_381, Blake2b512>::commit((&m_a, &scalars), (&ck_a, &ck_b, &ck_t))
+                .unwrap();
+        let (proof, _) = MultiExpGIPA::<Bls12_381, Blake2b512>::prove_with_aux(
+            (&m_a, &scalars),
+            (&ck_a, &ck_b, ark_std::slice::from_ref(&ck_t)),
+        )
+        .unwrap();
+        assert!(MultiExpGIPA::<Bls12_381, Blake2b512>::verify(
+            (&ck_a, &ck_b, &ck_t),
+            (&com.0, &com.1, &com.2),
+            &proof
+        )
+        .unwrap());
+    }
+
+    fn prove_and_verify_with_digest<D: digest::Digest>(rng: &mut StdRng) -> bool {
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(rng))
+            .collect::<Vec<_>>();
+        let r = Fr::rand(rng);
+        let r_vec = structured_scalar_power(TEST_SIZE, &r);
+
+        let (srs, ck_t) = PairingTIPA::<Bls12_381, D>::setup(rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let com = PairingTIPA::<Bls12_381, D>::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        let proof =
+            PairingTIPA::<Bls12_381, D>::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+
+        let com_c = AFGHOCommitmentG1::<Bls12_381>::commit(&ck_a, &m_a).unwrap();
+        let agg_c = MultiExpIPC::<Bls12_381>::commit(
+            ark_std::slice::from_ref(&ck_t),
+            &[
+                MultiexponentiationInnerProduct::<G1Projective>::inner_product(&m_a, &r_vec)
+                    .unwrap(),
+            ],
+        )
+        .unwrap();
+        let proof_c = MultiExpTIPA::<Bls12_381, D>::prove_with_structured_scalar_message(
+            &srs,
+            (&m_a, &r_vec),
+            (&ck_a, &ck_t),
+        )
+        .unwrap();
+
+        PairingTIPA::<Bls12_381, D>::verify(&v_srs, &ck_t, (&com.0, &com.1, &com.2), &proof)
+            .unwrap()
+            && MultiExpTIPA::<Bls12_381, D>::verify_with_structured_scalar_message(
+                &v_srs,
+                &ck_t,
+                (&com_c, &agg_c),
+                &r,
+                &proof_c,
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn digest_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        // 64, 32 and 32 byte digests
+        assert!(prove_and_verify_with_digest::<Blake2b512>(&mut rng));
+        assert!(prove_and_verify_with_digest::<Sha3_256>(&mut rng));
+        assert!(prove_and_verify_with_digest::<Blake3>(&mut rng));
+    }
+}
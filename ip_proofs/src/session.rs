@@ -0,0 +1,55 @@
+use crate::{
+    transcript::{SharedTranscript, TranscriptDigest},
+    Error,
+};
+
+const SESSION_DOMAIN: &[u8] = b"ripp-session";
+
+// Transcript keyed with the session `id` supplied by the application, which is bound after the
+// domain tag and seeds the challenges of `prove_with_session` and `verify_with_session` of GIPA
+// and TIPA. Proofs made in one session do not verify in another, e.g. for a proving service shared
+// by several consumers, nor as proofs outside of a session
+pub fn session_transcript<D: TranscriptDigest>(id: &[u8]) -> Result<SharedTranscript<D>, Error> {
+    let mut transcript = SharedTranscript::new(SESSION_DOMAIN);
+    transcript.absorb(b"session-id", id)?;
+    Ok(transcript)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::PairingTIPA;
+    use ark_bls12_381::{Bls12_381, G1Projective, G2Projective};
+    use ark_ff::UniformRand;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    type TIPA = PairingTIPA<Bls12_381, Blake2b512>;
+
+    #[test]
+    fn session_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = TIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = TIPA::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        let com = (&com.0, &com.1, &com.2);
+
+        let proof =
+            TIPA::prove_with_session(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t), com, b"tenant-a")
+                .unwrap();
+        let verify = |id: &[u8]| TIPA::verify_with_session(&v_srs, &ck_t, com, &proof, id);
+        assert!(verify(b"tenant-a").unwrap());
+        // Replayed in another session, or outside of any
+        assert!(!verify(b"tenant-b").unwrap());
+        assert!(!verify(b"").unwrap());
+        assert!(!TIPA::verify(&v_srs, &ck_t, com, &proof).unwrap_or(false));
+    }
+}
@@ -0,0 +1,846 @@
+use ark_bn254::{Bn254, Fq, Fr, G1Projective, G2Projective};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::{
+    fmt::Write,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::tipa::{CommitmentKeyOpening, VerifierSRS};
+
+// Solidity verifier of the KZG openings of the final commitment keys of TIPA proofs over BN254,
+// e.g. those of `aggregate_proof_commitment_key_openings`. Their pairing checks have target one
+// and pair with points of the verifier SRS only, so the contract evaluates them with the ecAdd,
+// ecMul and ecPairing precompiles. The contract derives the KZG challenge from the GIPA challenges
+// and the final keys with keccak256, as `TranscriptDigest` does for `Keccak256`, and computes the
+// evaluations from the GIPA challenges, so proofs must be made with `D = Keccak256`.
+//
+// The GIPA challenges and the SRS shift, e.g. the aggregation challenge r, are taken as given: the
+// contract does not check the GIPA recursion, whose target group equations the precompiles cannot
+// evaluate, nor derive its challenges. It checks that the final keys are the keys the given
+// challenges fold to, and a full verifier must check the rest of the TIPP and MIPP proofs off chain.
+
+// Big-endian 32-byte word of the ABI and of the precompile inputs
+pub type Word = [u8; 32];
+
+fn bigint_word<B: BigInteger>(b: &B) -> Word {
+    let bytes = b.to_bytes_be();
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    word
+}
+
+fn field_word<F: PrimeField>(f: &F) -> Word {
+    bigint_word(&f.into_bigint())
+}
+
+// Affine coordinates, with the identity as (0, 0)
+fn g1_words(p: &G1Projective) -> [Word; 2] {
+    match p.into_affine().xy() {
+        Some((x, y)) => [field_word(x), field_word(y)],
+        None => [[0u8; 32]; 2],
+    }
+}
+
+// Affine coordinates in Fq2 with the imaginary part first, as the pairing precompile reads them
+fn g2_words(p: &G2Projective) -> [Word; 4] {
+    match p.into_affine().xy() {
+        Some((x, y)) => [
+            field_word(&x.c1),
+            field_word(&x.c0),
+            field_word(&y.c1),
+            field_word(&y.c0),
+        ],
+        None => [[0u8; 32]; 4],
+    }
+}
+
+fn usize_word(n: usize) -> Word {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(n as u64).to_be_bytes());
+    word
+}
+
+// Dynamic uint256[] arguments after the static ones, each as the offset of its tail in the head
+fn abi_encode(mut head: Vec<Word>, arrays: &[&[Fr]]) -> Vec<Word> {
+    let mut tail = Vec::new();
+    let head_len = head.len() + arrays.len();
+    for array in arrays {
+        head.push(usize_word(32 * (head_len + tail.len())));
+        tail.push(usize_word(array.len()));
+        tail.extend(array.iter().map(field_word));
+    }
+    head.extend(tail);
+    head
+}
+
+fn hex(word: &Word) -> String {
+    word.iter().fold("0x".to_string(), |mut s, b| {
+        write!(s, "{:02x}", b).unwrap();
+        s
+    })
+}
+
+// ABI encoded arguments of `verifyTIPAOpenings` for the openings of the final keys of a TIPA
+// proof, without the function selector
+pub fn tipa_opening_arguments(
+    ck_a: &CommitmentKeyOpening<G2Projective>,
+    ck_b: &CommitmentKeyOpening<G1Projective>,
+) -> Vec<Word> {
+    let mut words = Vec::new();
+    words.extend_from_slice(&g2_words(&ck_a.ck_final));
+    words.extend_from_slice(&g2_words(&ck_a.ck_opening));
+    words.extend_from_slice(&g1_words(&ck_b.ck_final));
+    words.extend_from_slice(&g1_words(&ck_b.ck_opening));
+    words.push(field_word(&ck_a.shift));
+    abi_encode(words, &[&ck_a.transcript, &ck_b.transcript])
+}
+
+// ABI encoded arguments of `verifySSMOpening` for the opening of the final key of a TIPA proof
+// with structured scalar message, without the function selector
+pub fn ssm_opening_arguments(ck: &CommitmentKeyOpening<G2Projective>) -> Vec<Word> {
+    let mut words = Vec::new();
+    words.extend_from_slice(&g2_words(&ck.ck_final));
+    words.extend_from_slice(&g2_words(&ck.ck_opening));
+    abi_encode(words, &[&ck.transcript])
+}
+
+// Source of the verifier contract for the verifier SRS `v_srs`, whose points are constants of the
+// contract
+pub fn commitment_key_opening_verifier(v_srs: &VerifierSRS<Bn254>) -> String {
+    let mut constants = String::new();
+    let mut constant = |name: &str, word: &Word| {
+        writeln!(constants, "    uint256 constant {} = {};", name, hex(word)).unwrap();
+    };
+    constant("Q", &bigint_word(&Fq::MODULUS));
+    constant("R", &bigint_word(&Fr::MODULUS));
+    // Challenges keep the bits of the digest below the bit size of R
+    let mut mask = [0xffu8; 32];
+    let shaved_bits = 256 - Fr::MODULUS_BIT_SIZE as usize;
+    mask[..shaved_bits / 8].iter_mut().for_each(|b| *b = 0);
+    mask[shaved_bits / 8] >>= shaved_bits % 8;
+    constant("CHALLENGE_MASK", &mask);
+    let [g_x, g_y] = g1_words(&v_srs.g);
+    constant("G_X", &g_x);
+    constant("G_Y", &g_y);
+    let [g_beta_x, g_beta_y] = g1_words(&v_srs.g_beta);
+    constant("G_BETA_X", &g_beta_x);
+    constant("G_BETA_Y", &g_beta_y);
+    for (name, p) in &[("H", &v_srs.h), ("H_ALPHA", &v_srs.h_alpha)] {
+        let [x_1, x_0, y_1, y_0] = g2_words(p);
+        constant(&[*name, "_X_1"].concat(), &x_1);
+        constant(&[*name, "_X_0"].concat(), &x_0);
+        constant(&[*name, "_Y_1"].concat(), &y_1);
+        constant(&[*name, "_Y_0"].concat(), &y_0);
+    }
+    [VERIFIER_HEADER, &constants, VERIFIER_BODY].concat()
+}
+
+const VERIFIER_HEADER: &str = "\
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.0;
+
+// Checks the KZG openings of the final commitment keys of TIPA proofs over BN254 against a fixed
+// verifier SRS. Generated by ark-ip-proofs.
+//
+// The KZG challenge is derived here from the GIPA challenges and the final keys with keccak256,
+// matching proofs made with the Keccak256 transcript digest, and the evaluations are computed from
+// the GIPA challenges. The GIPA challenges and the SRS shift are taken as given: this contract does
+// not check the GIPA recursion of the TIPP and MIPP proofs, which must be verified off chain.
+contract CommitmentKeyOpeningVerifier {
+";
+
+const VERIFIER_BODY: &str = "
+    // Flags of the compressed point encoding of the Rust verifier, in the last byte of x
+    uint256 constant Y_NEGATIVE = 1 << 255;
+    uint256 constant INFINITY = 1 << 254;
+
+    // Openings of the final keys of a TIPA proof: ckA in G2 at the inverses of the GIPA challenges
+    // with SRS shift shiftA, given as transcriptA, and ckB in G1 at the GIPA challenges transcriptB
+    function verifyTIPAOpenings(
+        uint256[4] calldata ckAFinal,
+        uint256[4] calldata ckAOpening,
+        uint256[2] calldata ckBFinal,
+        uint256[2] calldata ckBOpening,
+        uint256 shiftA,
+        uint256[] calldata transcriptA,
+        uint256[] calldata transcriptB
+    ) external view returns (bool) {
+        if (transcriptB.length == 0 || transcriptA.length != transcriptB.length) {
+            return false;
+        }
+        for (uint256 i = 0; i < transcriptB.length; i++) {
+            if (transcriptB[i] >= R || mulmod(transcriptA[i], transcriptB[i], R) != 1) {
+                return false;
+            }
+        }
+        uint256 c = kzgChallenge(
+            abi.encodePacked(scalarBytes(transcriptB[0]), g2Bytes(ckAFinal), g1Bytes(ckBFinal))
+        );
+        return
+            g2Opening(ckAFinal, ckAOpening, c, evaluate(transcriptA, c, shiftA)) &&
+            g1Opening(ckBFinal, ckBOpening, c, evaluate(transcriptB, c, 1));
+    }
+
+    // Opening of the final key in G2 of a TIPA proof with structured scalar message, at the
+    // inverses of the GIPA challenges, given as transcript
+    function verifySSMOpening(
+        uint256[4] calldata ckFinal,
+        uint256[4] calldata ckOpening,
+        uint256[] calldata transcript
+    ) external view returns (bool) {
+        if (transcript.length == 0 || transcript[0] == 0 || transcript[0] >= R) {
+            return false;
+        }
+        uint256 c = kzgChallenge(abi.encodePacked(scalarBytes(inverse(transcript[0])), g2Bytes(ckFinal)));
+        return g2Opening(ckFinal, ckOpening, c, evaluate(transcript, c, 1));
+    }
+
+    // e(ckFinal - eval g + challenge ckOpening, h) e(-ckOpening, h^alpha) = 1, for a key in G1
+    function g1Opening(
+        uint256[2] memory ckFinal,
+        uint256[2] memory ckOpening,
+        uint256 challenge,
+        uint256 eval
+    ) internal view returns (bool) {
+        uint256[2] memory lhs = ecAdd(
+            ecAdd(ckFinal, negate(ecMul([G_X, G_Y], eval))),
+            ecMul(ckOpening, challenge)
+        );
+        uint256[2] memory opening = negate(ckOpening);
+        uint256[12] memory input;
+        input[0] = lhs[0];
+        input[1] = lhs[1];
+        input[2] = H_X_1;
+        input[3] = H_X_0;
+        input[4] = H_Y_1;
+        input[5] = H_Y_0;
+        input[6] = opening[0];
+        input[7] = opening[1];
+        input[8] = H_ALPHA_X_1;
+        input[9] = H_ALPHA_X_0;
+        input[10] = H_ALPHA_Y_1;
+        input[11] = H_ALPHA_Y_0;
+        return pairing(input);
+    }
+
+    // e(g, ckFinal - eval h + challenge ckOpening) e(-g^beta, ckOpening) = 1, for a key in G2.
+    // There is no G2 precompile, so the scalars move to G1:
+    // e(g, ckFinal) e(-eval g, h) e(challenge g - g^beta, ckOpening) = 1
+    function g2Opening(
+        uint256[4] memory ckFinal,
+        uint256[4] memory ckOpening,
+        uint256 challenge,
+        uint256 eval
+    ) internal view returns (bool) {
+        uint256[2] memory evalG = negate(ecMul([G_X, G_Y], eval));
+        uint256[2] memory challengeG = ecAdd(
+            ecMul([G_X, G_Y], challenge),
+            negate([G_BETA_X, G_BETA_Y])
+        );
+        uint256[18] memory input;
+        input[0] = G_X;
+        input[1] = G_Y;
+        input[2] = ckFinal[0];
+        input[3] = ckFinal[1];
+        input[4] = ckFinal[2];
+        input[5] = ckFinal[3];
+        input[6] = evalG[0];
+        input[7] = evalG[1];
+        input[8] = H_X_1;
+        input[9] = H_X_0;
+        input[10] = H_Y_1;
+        input[11] = H_Y_0;
+        input[12] = challengeG[0];
+        input[13] = challengeG[1];
+        input[14] = ckOpening[0];
+        input[15] = ckOpening[1];
+        input[16] = ckOpening[2];
+        input[17] = ckOpening[3];
+        return pairing(input);
+    }
+
+    // Challenge from the digest of the big-endian 64-bit nonce and the input, read as a
+    // little-endian integer below the bit size of R, with the next nonce while it is not below R
+    function kzgChallenge(bytes memory input) internal pure returns (uint256 c) {
+        for (uint64 nonce = 0; ; nonce++) {
+            c = reverseBytes(uint256(keccak256(abi.encodePacked(nonce, input)))) & CHALLENGE_MASK;
+            if (c < R) {
+                return c;
+            }
+        }
+    }
+
+    // Product of 1 + transcript_i (shift z^2)^(2^i), the folded key polynomial at z
+    function evaluate(
+        uint256[] calldata transcript,
+        uint256 z,
+        uint256 shift
+    ) internal pure returns (uint256 eval) {
+        uint256 power = mulmod(mulmod(z, z, R), shift, R);
+        eval = 1;
+        for (uint256 i = 0; i < transcript.length; i++) {
+            eval = mulmod(eval, addmod(1, mulmod(transcript[i], power, R), R), R);
+            power = mulmod(power, power, R);
+        }
+    }
+
+    // Little-endian encoding of a scalar
+    function scalarBytes(uint256 s) internal pure returns (bytes32) {
+        return bytes32(reverseBytes(s));
+    }
+
+    // Compressed encoding of a point in G1: x with the sign of y, whose negation is larger for a
+    // positive y, or the infinity flag
+    function g1Bytes(uint256[2] memory p) internal pure returns (bytes32) {
+        if (p[0] == 0 && p[1] == 0) {
+            return bytes32(reverseBytes(INFINITY));
+        }
+        uint256 flag = p[1] <= (Q - p[1]) % Q ? uint256(0) : Y_NEGATIVE;
+        return bytes32(reverseBytes(p[0] | flag));
+    }
+
+    // Compressed encoding of a point in G2: the real and then the imaginary part of x, with the
+    // sign of y compared on the imaginary part first
+    function g2Bytes(uint256[4] memory p) internal pure returns (bytes memory) {
+        if (p[0] == 0 && p[1] == 0 && p[2] == 0 && p[3] == 0) {
+            return abi.encodePacked(bytes32(0), bytes32(reverseBytes(INFINITY)));
+        }
+        uint256 negY1 = (Q - p[2]) % Q;
+        bool positive = p[2] < negY1 || (p[2] == negY1 && p[3] <= (Q - p[3]) % Q);
+        return
+            abi.encodePacked(
+                bytes32(reverseBytes(p[1])),
+                bytes32(reverseBytes(p[0] | (positive ? uint256(0) : Y_NEGATIVE)))
+            );
+    }
+
+    function reverseBytes(uint256 v) internal pure returns (uint256) {
+        v =
+            ((v >> 8) & 0x00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF) |
+            ((v & 0x00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF) << 8);
+        v =
+            ((v >> 16) & 0x0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF) |
+            ((v & 0x0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF) << 16);
+        v =
+            ((v >> 32) & 0x00000000FFFFFFFF00000000FFFFFFFF00000000FFFFFFFF00000000FFFFFFFF) |
+            ((v & 0x00000000FFFFFFFF00000000FFFFFFFF00000000FFFFFFFF00000000FFFFFFFF) << 32);
+        v =
+            ((v >> 64) & 0x0000000000000000FFFFFFFFFFFFFFFF0000000000000000FFFFFFFFFFFFFFFF) |
+            ((v & 0x0000000000000000FFFFFFFFFFFFFFFF0000000000000000FFFFFFFFFFFFFFFF) << 64);
+        return (v >> 128) | (v << 128);
+    }
+
+    function inverse(uint256 s) internal view returns (uint256) {
+        uint256[6] memory input = [uint256(32), 32, 32, s, R - 2, R];
+        uint256[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x05, input, 0xc0, out, 0x20)
+        }
+        require(success, \"modexp failed\");
+        return out[0];
+    }
+
+    function negate(uint256[2] memory p) internal pure returns (uint256[2] memory) {
+        return [p[0], (Q - p[1]) % Q];
+    }
+    function ecAdd(uint256[2] memory p, uint256[2] memory q)
+        internal
+        view
+        returns (uint256[2] memory r)
+    {
+        uint256[4] memory input = [p[0], p[1], q[0], q[1]];
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x06, input, 0x80, r, 0x40)
+        }
+        require(success, \"ecAdd failed\");
+    }
+
+    function ecMul(uint256[2] memory p, uint256 s) internal view returns (uint256[2] memory r) {
+        uint256[3] memory input = [p[0], p[1], s];
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x07, input, 0x60, r, 0x40)
+        }
+        require(success, \"ecMul failed\");
+    }
+
+    function pairing(uint256[12] memory input) internal view returns (bool) {
+        uint256[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x08, input, 0x180, out, 0x20)
+        }
+        require(success, \"ecPairing failed\");
+        return out[0] == 1;
+    }
+
+    function pairing(uint256[18] memory input) internal view returns (bool) {
+        uint256[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x08, input, 0x240, out, 0x20)
+        }
+        require(success, \"ecPairing failed\");
+        return out[0] == 1;
+    }
+}
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bn254::{MultiExpIPC, MultiExpTIPA, PairingIPC, PairingTIPA},
+        prelude::Keccak256,
+        tipa::structured_scalar_message::structured_scalar_power,
+    };
+    use ark_bn254::{Fq2, G1Affine, G2Affine};
+    use ark_dh_commitments::{
+        afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+        DoublyHomomorphicCommitment,
+    };
+    use ark_ec::pairing::Pairing;
+    use ark_ff::{BigInt, Field, One, UniformRand};
+    use ark_inner_products::{InnerProduct, MultiexponentiationInnerProduct, PairingInnerProduct};
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::convert::TryInto;
+    use blake2::Blake2b512;
+    use digest::Digest;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    fn fq(word: &Word) -> Fq {
+        Fq::from_be_bytes_mod_order(word)
+    }
+
+    fn fr(word: &Word) -> Fr {
+        Fr::from_be_bytes_mod_order(word)
+    }
+
+    fn g1(words: &[Word]) -> G1Projective {
+        G1Affine::new(fq(&words[0]), fq(&words[1])).into()
+    }
+
+    fn g2(words: &[Word]) -> G2Projective {
+        G2Affine::new(
+            Fq2::new(fq(&words[1]), fq(&words[0])),
+            Fq2::new(fq(&words[3]), fq(&words[2])),
+        )
+        .into()
+    }
+
+    // uint256[] argument at the offset in `word`
+    fn array(args: &[Word], word: &Word) -> Vec<Fr> {
+        let start = u64::from_be_bytes(word[24..].try_into().unwrap()) as usize / 32;
+        let len = u64::from_be_bytes(args[start][24..].try_into().unwrap()) as usize;
+        args[start + 1..start + 1 + len].iter().map(fr).collect()
+    }
+
+    // The encodings and challenge of the contract, on words: little-endian x with the flags in its
+    // last byte, and y compared with its negation as integers, imaginary part first
+    fn le(word: &Word, flags: u8) -> Vec<u8> {
+        let mut bytes = word.to_vec();
+        bytes.reverse();
+        bytes[31] |= flags;
+        bytes
+    }
+
+    fn is_positive(y: &Fq) -> bool {
+        y.into_bigint() <= (-*y).into_bigint()
+    }
+
+    fn g1_bytes(words: &[Word]) -> Vec<u8> {
+        if words[..2] == [[0u8; 32]; 2] {
+            return le(&[0u8; 32], 0x40);
+        }
+        le(
+            &words[0],
+            if is_positive(&fq(&words[1])) { 0 } else { 0x80 },
+        )
+    }
+
+    fn g2_bytes(words: &[Word]) -> Vec<u8> {
+        if words[..4] == [[0u8; 32]; 4] {
+            return [le(&[0u8; 32], 0), le(&[0u8; 32], 0x40)].concat();
+        }
+        let (y_1, y_0) = (fq(&words[2]), fq(&words[3]));
+        let positive = if y_1 == -y_1 {
+            is_positive(&y_0)
+        } else {
+            is_positive(&y_1)
+        };
+        [
+            le(&words[1], 0),
+            le(&words[0], if positive { 0 } else { 0x80 }),
+        ]
+        .concat()
+    }
+
+    fn challenge(input: &[u8]) -> Fr {
+        let mask = {
+            let mut mask = [0xffu8; 32];
+            mask[0] >>= 256 - Fr::MODULUS_BIT_SIZE as usize;
+            mask
+        };
+        (0u64..)
+            .find_map(|nonce| {
+                let mut digest: Word =
+                    Keccak256::digest([&nonce.to_be_bytes()[..], input].concat()).into();
+                digest.reverse();
+                digest.iter_mut().zip(&mask).for_each(|(b, m)| *b &= m);
+                let limbs = digest
+                    .rchunks(8)
+                    .map(|limb| u64::from_be_bytes(limb.try_into().unwrap()))
+                    .collect::<Vec<_>>();
+                Fr::from_bigint(BigInt::new(limbs.try_into().unwrap()))
+            })
+            .unwrap()
+    }
+
+    fn evaluate(transcript: &[Fr], z: &Fr, shift: &Fr) -> Fr {
+        let mut power = *z * z * shift;
+        let mut eval = Fr::one();
+        for x in transcript {
+            eval *= Fr::one() + *x * power;
+            power = power.square();
+        }
+        eval
+    }
+
+    fn g1_opening(v_srs: &VerifierSRS<Bn254>, ck: &[Word], c: &Fr, eval: &Fr) -> bool {
+        let (ck_final, ck_opening) = (g1(&ck[0..2]), g1(&ck[2..4]));
+        let lhs = ck_final - v_srs.g * eval + ck_opening * c;
+        Bn254::multi_pairing([lhs, -ck_opening], [v_srs.h, v_srs.h_alpha]).0 == One::one()
+    }
+
+    fn g2_opening(v_srs: &VerifierSRS<Bn254>, ck: &[Word], c: &Fr, eval: &Fr) -> bool {
+        let (ck_final, ck_opening) = (g2(&ck[0..4]), g2(&ck[4..8]));
+        Bn254::multi_pairing(
+            [v_srs.g, -(v_srs.g * eval), v_srs.g * c - v_srs.g_beta],
+            [ck_final, v_srs.h, ck_opening],
+        )
+        .0 == One::one()
+    }
+
+    // The checks of `verifyTIPAOpenings`, on the words it is called with, and its challenge
+    fn verify_tipa_openings(v_srs: &VerifierSRS<Bn254>, args: &[Word]) -> (bool, Fr) {
+        let (transcript_a, transcript_b) = (array(args, &args[13]), array(args, &args[14]));
+        let shift_a = fr(&args[12]);
+        let c = challenge(
+            &[
+                le(&field_word(&transcript_b[0]), 0),
+                g2_bytes(&args[0..4]),
+                g1_bytes(&args[8..10]),
+            ]
+            .concat(),
+        );
+        let valid = !transcript_b.is_empty()
+            && transcript_a.len() == transcript_b.len()
+            && transcript_a
+                .iter()
+                .zip(&transcript_b)
+                .all(|(a, b)| *a * b == Fr::one())
+            && g2_opening(
+                v_srs,
+                &args[0..8],
+                &c,
+                &evaluate(&transcript_a, &c, &shift_a),
+            )
+            && g1_opening(
+                v_srs,
+                &args[8..12],
+                &c,
+                &evaluate(&transcript_b, &c, &Fr::one()),
+            );
+        (valid, c)
+    }
+
+    // The checks of `verifySSMOpening` and its challenge
+    fn verify_ssm_opening(v_srs: &VerifierSRS<Bn254>, args: &[Word]) -> (bool, Fr) {
+        let transcript = array(args, &args[8]);
+        let first = field_word(&transcript[0].inverse().unwrap());
+        let c = challenge(&[le(&first, 0), g2_bytes(&args[0..4])].concat());
+        let valid = g2_opening(
+            v_srs,
+            &args[0..8],
+            &c,
+            &evaluate(&transcript, &c, &Fr::one()),
+        );
+        (valid, c)
+    }
+
+    #[test]
+    fn point_encoding_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        for p in (0..8)
+            .map(|_| G1Projective::rand(&mut rng))
+            .chain([G1Projective::default()])
+        {
+            let mut bytes = Vec::new();
+            p.serialize_compressed(&mut bytes).unwrap();
+            assert_eq!(g1_bytes(&g1_words(&p)), bytes);
+        }
+        for p in (0..8)
+            .map(|_| G2Projective::rand(&mut rng))
+            .chain([G2Projective::default()])
+        {
+            let mut bytes = Vec::new();
+            p.serialize_compressed(&mut bytes).unwrap();
+            assert_eq!(g2_bytes(&g2_words(&p)), bytes);
+        }
+    }
+
+    #[test]
+    fn tipa_opening_arguments_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = PairingTIPA::<Keccak256>::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com_a = AFGHOCommitmentG1::<Bn254>::commit(&ck_a, &m_a).unwrap();
+        let com_b = AFGHOCommitmentG2::<Bn254>::commit(&ck_b, &m_b).unwrap();
+        let t = vec![PairingInnerProduct::<Bn254>::inner_product(&m_a, &m_b).unwrap()];
+        let com_t = PairingIPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
+        let proof =
+            PairingTIPA::<Keccak256>::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+
+        let (ck_a_opening, ck_b_opening) = PairingTIPA::<Keccak256>::commitment_key_openings(
+            (&com_a, &com_b, &com_t),
+            &proof,
+            &Fr::one(),
+        )
+        .unwrap();
+        let args = tipa_opening_arguments(&ck_a_opening, &ck_b_opening);
+        assert_eq!(args.len(), 15 + 2 * (1 + 3));
+        let (valid, c) = verify_tipa_openings(&v_srs, &args);
+        assert!(valid);
+        // The contract derives the challenge of the Rust verifier
+        assert_eq!(c, ck_a_opening.kzg_challenge);
+
+        // Another transcript changes the challenge and the evaluations
+        let mut wrong_b = ck_b_opening.clone();
+        wrong_b.transcript[1] += Fr::one();
+        let mut wrong_a = ck_a_opening.clone();
+        wrong_a.transcript[1] = wrong_b.transcript[1].inverse().unwrap();
+        assert!(!verify_tipa_openings(&v_srs, &tipa_opening_arguments(&wrong_a, &wrong_b)).0);
+        let mut wrong_a = ck_a_opening.clone();
+        wrong_a.shift += Fr::one();
+        assert!(!verify_tipa_openings(&v_srs, &tipa_opening_arguments(&wrong_a, &ck_b_opening)).0);
+
+        // Proofs with another transcript digest do not open at the contract's challenge
+        let proof =
+            PairingTIPA::<Blake2b512>::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        let (ck_a_opening, ck_b_opening) = PairingTIPA::<Blake2b512>::commitment_key_openings(
+            (&com_a, &com_b, &com_t),
+            &proof,
+            &Fr::one(),
+        )
+        .unwrap();
+        assert!(
+            !verify_tipa_openings(
+                &v_srs,
+                &tipa_opening_arguments(&ck_a_opening, &ck_b_opening)
+            )
+            .0
+        );
+    }
+
+    #[test]
+    fn ssm_opening_arguments_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = MultiExpTIPA::<Keccak256>::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, _) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let b = Fr::rand(&mut rng);
+        let m_b = structured_scalar_power(TEST_SIZE, &b);
+        let com_a = AFGHOCommitmentG1::<Bn254>::commit(&ck_a, &m_a).unwrap();
+        let t = vec![MultiexponentiationInnerProduct::inner_product(&m_a, &m_b).unwrap()];
+        let com_t = MultiExpIPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
+        let proof = MultiExpTIPA::<Keccak256>::prove_with_structured_scalar_message(
+            &srs,
+            (&m_a, &m_b),
+            (&ck_a, &ck_t),
+        )
+        .unwrap();
+
+        let opening =
+            MultiExpTIPA::<Keccak256>::commitment_key_opening((&com_a, &com_t), &b, &proof)
+                .unwrap();
+        let args = ssm_opening_arguments(&opening);
+        let (valid, c) = verify_ssm_opening(&v_srs, &args);
+        assert!(valid);
+        assert_eq!(c, opening.kzg_challenge);
+
+        let mut wrong = opening.clone();
+        wrong.transcript[2] += Fr::one();
+        assert!(!verify_ssm_opening(&v_srs, &ssm_opening_arguments(&wrong)).0);
+    }
+
+    // Bytecode of the contract from solc, taken from SOLC or else the PATH
+    #[cfg(feature = "solidity-evm")]
+    fn compile(source: &str) -> Vec<u8> {
+        use std::process::Command;
+
+        let path = std::env::temp_dir().join("CommitmentKeyOpeningVerifier.sol");
+        std::fs::write(&path, source).unwrap();
+        let solc = std::env::var("SOLC").unwrap_or_else(|_| "solc".to_string());
+        let output = Command::new(&solc)
+            .args(["--bin", "--optimize"])
+            .arg(&path)
+            .output()
+            .unwrap_or_else(|e| panic!("running {}: {}", solc, e));
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let bin = stdout
+            .lines()
+            .skip_while(|line| *line != "Binary:")
+            .nth(1)
+            .expect("solc printed no binary");
+        (0..bin.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&bin[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    // Compiles the contract and runs it in revm on the openings of a real aggregate proof, against
+    // the Rust model of the contract. Needs solc, hence the solidity-evm feature
+    #[cfg(feature = "solidity-evm")]
+    #[test]
+    fn commitment_key_opening_verifier_evm_test() {
+        use crate::applications::groth16_aggregation::{
+            aggregate_proof_commitment_key_openings, aggregate_proofs, setup_inner_product,
+        };
+        use ark_groth16::Groth16;
+        use ark_relations::{
+            lc,
+            r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+        };
+        use revm::{
+            db::{CacheDB, EmptyDB},
+            primitives::{Address, ExecutionResult, Output, TxKind},
+            Evm,
+        };
+
+        struct TestCircuit(Fr);
+
+        impl ConstraintSynthesizer<Fr> for TestCircuit {
+            fn generate_constraints(
+                self,
+                cs: ConstraintSystemRef<Fr>,
+            ) -> Result<(), SynthesisError> {
+                let input = cs.new_input_variable(|| Ok(self.0))?;
+                let witness = cs.new_witness_variable(|| Ok(self.0))?;
+                cs.enforce_constraint(lc!() + witness, lc!() + Variable::One, lc!() + input)
+            }
+        }
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let srs = setup_inner_product::<Bn254, Keccak256, _>(&mut rng, TEST_SIZE).unwrap();
+        let v_srs = srs.get_verifier_key();
+        let parameters = Groth16::<Bn254>::generate_random_parameters_with_reduction(
+            TestCircuit(Fr::default()),
+            &mut rng,
+        )
+        .unwrap();
+        let proofs = (0..TEST_SIZE)
+            .map(|_| {
+                Groth16::<Bn254>::create_random_proof_with_reduction(
+                    TestCircuit(Fr::rand(&mut rng)),
+                    &parameters,
+                    &mut rng,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let proof = aggregate_proofs::<Bn254, Keccak256>(&srs, &parameters.vk, &proofs).unwrap();
+        let (g2_openings, g1_openings) = aggregate_proof_commitment_key_openings(&proof).unwrap();
+
+        let mut evm = Evm::builder()
+            .with_db(CacheDB::new(EmptyDB::default()))
+            .modify_tx_env(|tx| {
+                tx.transact_to = TxKind::Create;
+                tx.data = compile(&commitment_key_opening_verifier(&v_srs)).into();
+            })
+            .build();
+        let contract = match evm.transact_commit().unwrap() {
+            ExecutionResult::Success {
+                output: Output::Create(_, Some(address)),
+                ..
+            } => address,
+            result => panic!("deployment failed: {:?}", result),
+        };
+        let mut call = |signature: &str, args: &[Word]| -> bool {
+            let mut data = Keccak256::digest(signature.as_bytes())[..4].to_vec();
+            args.iter().for_each(|word| data.extend_from_slice(word));
+            let tx = evm.tx_mut();
+            tx.caller = Address::ZERO;
+            tx.transact_to = TxKind::Call(contract);
+            tx.data = data.into();
+            match evm.transact().unwrap().result {
+                ExecutionResult::Success {
+                    output: Output::Call(output),
+                    ..
+                } => {
+                    assert_eq!(output.len(), 32);
+                    output[31] == 1
+                }
+                result => panic!("call failed: {:?}", result),
+            }
+        };
+        let tipa_signature = "verifyTIPAOpenings(uint256[4],uint256[4],uint256[2],uint256[2],\
+                              uint256,uint256[],uint256[])";
+        let ssm_signature = "verifySSMOpening(uint256[4],uint256[4],uint256[])";
+
+        let tipa_args = tipa_opening_arguments(&g2_openings[0], &g1_openings[0]);
+        let ssm_args = ssm_opening_arguments(&g2_openings[1]);
+        assert!(verify_tipa_openings(&v_srs, &tipa_args).0);
+        assert!(verify_ssm_opening(&v_srs, &ssm_args).0);
+        assert!(call(tipa_signature, &tipa_args));
+        assert!(call(ssm_signature, &ssm_args));
+
+        // Openings at another challenge, or of another key, are rejected on chain as off chain
+        let mut wrong_a = g2_openings[0].clone();
+        wrong_a.shift += Fr::one();
+        let wrong_tipa_args = tipa_opening_arguments(&wrong_a, &g1_openings[0]);
+        assert!(!verify_tipa_openings(&v_srs, &wrong_tipa_args).0);
+        assert!(!call(tipa_signature, &wrong_tipa_args));
+        let mut wrong = g2_openings[1].clone();
+        wrong.ck_final += G2Projective::rand(&mut rng);
+        let wrong_ssm_args = ssm_opening_arguments(&wrong);
+        assert!(!verify_ssm_opening(&v_srs, &wrong_ssm_args).0);
+        assert!(!call(ssm_signature, &wrong_ssm_args));
+    }
+
+    // When the contract changes on purpose, run with RIPP_SOLIDITY_SNAPSHOT set to the path of the
+    // snapshot to regenerate it
+    #[test]
+    fn commitment_key_opening_verifier_snapshot_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, _) = PairingTIPA::<Blake2b512>::setup(&mut rng, 2).unwrap();
+        let source = commitment_key_opening_verifier(&srs.get_verifier_key());
+        if let Ok(path) = std::env::var("RIPP_SOLIDITY_SNAPSHOT") {
+            std::fs::write(path, &source).unwrap();
+        }
+        assert_eq!(
+            source,
+            include_str!("../testdata/CommitmentKeyOpeningVerifier.sol")
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalSerialize;
+use ark_std::{marker::PhantomData, vec, vec::Vec};
+
+use crate::{
+    transcript::{ChallengeInput, TranscriptDigest},
+    Error,
+};
+
+// Sponge over the scalar field, e.g. Poseidon, whose permutation is cheap to evaluate in a circuit
+pub trait AlgebraicSponge<F: PrimeField>: Clone + Default {
+    fn absorb(&mut self, input: &[F]);
+    fn squeeze(&mut self, num: usize) -> Vec<F>;
+}
+
+// Drives the Fiat-Shamir transcripts of GIPA and TIPA with an algebraic sponge. Scalars of `F` are
+// absorbed as single elements and challenges are the squeezed elements, so that a circuit over
+// `F` derives them without bit decompositions. Other values, e.g. commitments in the target group,
+// are absorbed as their canonical encoding packed into elements below the modulus
+#[derive(Clone, Default)]
+pub struct SpongeDigest<F: PrimeField, S: AlgebraicSponge<F>> {
+    _field: PhantomData<F>,
+    _sponge: PhantomData<S>,
+}
+
+// Sponge state after absorbing the values of a challenge
+#[derive(Clone)]
+pub struct SpongeInput<F: PrimeField, S: AlgebraicSponge<F>> {
+    sponge: S,
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField, S: AlgebraicSponge<F>> SpongeInput<F, S> {
+    fn absorb_bytes(&mut self, bytes: &[u8]) {
+        let mut input = vec![F::from(bytes.len() as u64)];
+        input.extend(
+            bytes
+                .chunks((F::MODULUS_BIT_SIZE as usize - 1) / 8)
+                .map(F::from_le_bytes_mod_order),
+        );
+        self.sponge.absorb(&input);
+    }
+
+    // Squeezes an element after absorbing the nonce. Challenges in another field are the element
+    // reduced into it
+    fn squeeze<G: PrimeField>(&self, nonce: usize) -> G {
+        phase_count!(hashes, 1);
+        let mut sponge = self.sponge.clone();
+        sponge.absorb(&[F::from(nonce as u64)]);
+        G::from_le_bytes_mod_order(&sponge.squeeze(1)[0].into_bigint().to_bytes_le())
+    }
+}
+
+impl<F: PrimeField, S: AlgebraicSponge<F>> ChallengeInput for SpongeInput<F, S> {
+    fn absorb<T: CanonicalSerialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.absorb_bytes(&canonical_bytes!(value)?);
+        Ok(())
+    }
+
+    fn absorb_scalar<G: PrimeField>(&mut self, scalar: &G) -> Result<(), Error> {
+        if G::characteristic() != F::characteristic() {
+            return self.absorb(scalar);
+        }
+        let element = F::from_le_bytes_mod_order(&scalar.into_bigint().to_bytes_le());
+        self.sponge.absorb(&[element]);
+        Ok(())
+    }
+
+    fn challenge<G: PrimeField>(&self, nonce: usize) -> Option<G> {
+        Some(self.squeeze(nonce))
+    }
+
+    fn short_challenge<G: PrimeField>(&self, nonce: usize) -> G {
+        self.squeeze(nonce)
+    }
+}
+
+impl<F: PrimeField, S: AlgebraicSponge<F>> TranscriptDigest for SpongeDigest<F, S> {
+    type Input = SpongeInput<F, S>;
+
+    fn input() -> Self::Input {
+        SpongeInput {
+            sponge: S::default(),
+            _field: PhantomData,
+        }
+    }
+
+    // Fills the output from squeezed elements
+    fn hash(bytes: &[u8]) -> Vec<u8> {
+        let mut input = Self::input();
+        input.absorb_bytes(bytes);
+        let mut output = Vec::new();
+        while output.len() < Self::output_size() {
+            output.extend_from_slice(&input.sponge.squeeze(1)[0].into_bigint().to_bytes_le());
+        }
+        output.truncate(Self::output_size());
+        output
+    }
+
+    fn output_size() -> usize {
+        64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Fr;
+    use ark_ff::{Field, One, UniformRand};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[derive(Clone, Default)]
+    struct TestSponge {
+        state: Fr,
+    }
+
+    impl AlgebraicSponge<Fr> for TestSponge {
+        fn absorb(&mut self, input: &[Fr]) {
+            for x in input {
+                self.state = (self.state + x).pow([5u64]) + Fr::one();
+            }
+        }
+
+        fn squeeze(&mut self, num: usize) -> Vec<Fr> {
+            (0..num)
+                .map(|_| {
+                    self.state = self.state.pow([5u64]) + Fr::one();
+                    self.state
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn sponge_native_challenge_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let scalar = Fr::rand(&mut rng);
+
+        let mut input = SpongeDigest::<Fr, TestSponge>::input();
+        input.absorb_scalar(&scalar).unwrap();
+        let c: Fr = input.challenge(1).unwrap();
+
+        // The scalar and the nonce are single elements and the challenge is the squeezed element
+        let mut sponge = TestSponge::default();
+        sponge.absorb(&[scalar, Fr::one()]);
+        assert_eq!(c, sponge.squeeze(1)[0]);
+        assert_eq!(c, input.short_challenge(1));
+        assert_ne!(c, input.challenge::<Fr>(0).unwrap());
+    }
+}
@@ -0,0 +1,367 @@
+use ark_std::{marker::PhantomData, ops::Add, vec, vec::Vec};
+
+use crate::{
+    gipa::{GIPAAux, GIPAProof, GIPAProverState, GIPA},
+    transcript::TranscriptDigest,
+    Error, InnerProductArgumentError,
+};
+use ark_dh_commitments::DoublyHomomorphicCommitment;
+use ark_inner_products::InnerProduct;
+
+// GIPA prover that hands out the heavy computations of each round as independent tasks, leaving
+// the threading model to the caller: the commitments to both halves of the left message, to both
+// halves of the right message, and each of the two cross inner products. Tasks own copies of
+// their inputs, so they can be sent to any executor, e.g. a tokio blocking pool or a custom thread
+// pool, and run in any order. Once all have run, `finish_round` takes their outputs, derives the
+// challenge and folds, giving the same proof as `GIPA::prove_from_state`. Copying the inputs is
+// cheap next to the commitments and inner products
+pub struct TaskProver<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    state: GIPAProverState<IP, LMC, RMC, IPC, D>,
+}
+
+// A computation of a round, run with `run`
+pub struct RoundTask<IP, LMC, RMC>
+where
+    IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = RMC::Message>,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    input: TaskInput<LMC, RMC>,
+    _inner_product: PhantomData<IP>,
+}
+
+enum TaskInput<LMC, RMC>
+where
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    // The halves of the left message in swapped order, committed to under the halves of the key
+    LeftCommitment {
+        ck: Vec<LMC::Key>,
+        m: Vec<LMC::Message>,
+    },
+    RightCommitment {
+        ck: Vec<RMC::Key>,
+        m: Vec<RMC::Message>,
+    },
+    // Inner product of a half of the left message with the opposite half of the right message,
+    // or with the whole right message repeated while it is shorter
+    CrossInnerProduct {
+        index: usize,
+        m_a: Vec<LMC::Message>,
+        m_b: Vec<RMC::Message>,
+    },
+}
+
+pub enum RoundTaskOutput<IP, LMC, RMC>
+where
+    IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = RMC::Message>,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    LeftCommitment(LMC::Output, LMC::Output),
+    RightCommitment(RMC::Output, RMC::Output),
+    CrossInnerProduct(usize, IP::Output),
+}
+
+impl<IP, LMC, RMC> RoundTask<IP, LMC, RMC>
+where
+    IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = RMC::Message>,
+    IP::Output: Add<IP::Output, Output = IP::Output>,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    fn new(input: TaskInput<LMC, RMC>) -> Self {
+        RoundTask {
+            input,
+            _inner_product: PhantomData,
+        }
+    }
+
+    pub fn run(self) -> Result<RoundTaskOutput<IP, LMC, RMC>, Error> {
+        Ok(match self.input {
+            TaskInput::LeftCommitment { ck, m } => {
+                let (com_1, com_2) = LMC::commit_split(&ck, &m, m.len() / 2)?;
+                RoundTaskOutput::LeftCommitment(com_1, com_2)
+            }
+            TaskInput::RightCommitment { ck, m } => {
+                let (com_2, com_1) = RMC::commit_split(&ck, &m, m.len() / 2)?;
+                RoundTaskOutput::RightCommitment(com_1, com_2)
+            }
+            TaskInput::CrossInnerProduct { index, m_a, m_b } => {
+                let ip = if m_a.len() > m_b.len() {
+                    IP::block_inner_product(&m_a, &m_b)?
+                } else {
+                    IP::inner_product(&m_a, &m_b)?
+                };
+                RoundTaskOutput::CrossInnerProduct(index, ip)
+            }
+        })
+    }
+}
+
+impl<IP, LMC, RMC, IPC, D> TaskProver<IP, LMC, RMC, IPC, D>
+where
+    D: TranscriptDigest,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    // Prover from `GIPA::prover_state` or a saved state
+    pub fn new(state: GIPAProverState<IP, LMC, RMC, IPC, D>) -> Self {
+        TaskProver { state }
+    }
+
+    pub fn rounds_left(&self) -> usize {
+        self.state.len().trailing_zeros() as usize
+    }
+
+    pub fn state(&self) -> &GIPAProverState<IP, LMC, RMC, IPC, D> {
+        &self.state
+    }
+
+    // Tasks of the next round. While the left message is longer than the right one, the right
+    // message is not folded and there is no right commitment task
+    pub fn round_tasks(&self) -> Result<Vec<RoundTask<IP, LMC, RMC>>, Error> {
+        if self.rounds_left() == 0 {
+            return Err(InnerProductArgumentError::UnexpectedStep("final message"));
+        }
+        let (m_a, m_b) = (&self.state.m_a, &self.state.m_b);
+        let split = m_a.len() / 2;
+        let mut tasks = vec![RoundTask::new(TaskInput::LeftCommitment {
+            ck: self.state.ck_a.clone(),
+            m: [&m_a[split..], &m_a[..split]].concat(),
+        })];
+        if m_a.len() > m_b.len() {
+            tasks.push(RoundTask::new(TaskInput::CrossInnerProduct {
+                index: 0,
+                m_a: m_a[split..].to_vec(),
+                m_b: m_b.clone(),
+            }));
+            tasks.push(RoundTask::new(TaskInput::CrossInnerProduct {
+                index: 1,
+                m_a: m_a[..split].to_vec(),
+                m_b: m_b.clone(),
+            }));
+        } else {
+            tasks.push(RoundTask::new(TaskInput::RightCommitment {
+                ck: self.state.ck_b.clone(),
+                m: [&m_b[split..], &m_b[..split]].concat(),
+            }));
+            tasks.push(RoundTask::new(TaskInput::CrossInnerProduct {
+                index: 0,
+                m_a: m_a[split..].to_vec(),
+                m_b: m_b[..split].to_vec(),
+            }));
+            tasks.push(RoundTask::new(TaskInput::CrossInnerProduct {
+                index: 1,
+                m_a: m_a[..split].to_vec(),
+                m_b: m_b[split..].to_vec(),
+            }));
+        }
+        Ok(tasks)
+    }
+
+    // Completes the round from the outputs of all its tasks, in any order
+    pub fn finish_round(
+        &mut self,
+        outputs: Vec<RoundTaskOutput<IP, LMC, RMC>>,
+    ) -> Result<(), Error> {
+        if self.rounds_left() == 0 {
+            return Err(InnerProductArgumentError::UnexpectedStep("final message"));
+        }
+        let block_round = self.state.m_a.len() > self.state.m_b.len();
+        let mut com_a = None;
+        let mut com_b = if block_round {
+            Some((RMC::Output::default(), RMC::Output::default()))
+        } else {
+            None
+        };
+        let mut ip = [None, None];
+        for output in outputs {
+            let duplicate = match output {
+                RoundTaskOutput::LeftCommitment(com_1, com_2) => {
+                    com_a.replace((com_1, com_2)).is_some()
+                }
+                RoundTaskOutput::RightCommitment(com_1, com_2) => {
+                    block_round || com_b.replace((com_1, com_2)).is_some()
+                }
+                // Indices other than 0 and 1 are not from a task of this prover
+                RoundTaskOutput::CrossInnerProduct(index, output) => match ip.get_mut(index) {
+                    Some(ip) => ip.replace(output).is_some(),
+                    None => true,
+                },
+            };
+            if duplicate {
+                return Err(InnerProductArgumentError::UnexpectedStep(
+                    "round task output",
+                ));
+            }
+        }
+        let (com_a, com_b, ip_1, ip_2) = match (com_a, com_b, ip) {
+            (Some(com_a), Some(com_b), [Some(ip_1), Some(ip_2)]) => (com_a, com_b, ip_1, ip_2),
+            _ => {
+                return Err(InnerProductArgumentError::UnexpectedStep(
+                    "round task output",
+                ))
+            }
+        };
+
+        let _round = phase_span!("gipa_round", len = self.state.len());
+        phase_count!(gipa_rounds, 1);
+        let com_1 = (com_a.0, com_b.0, IPC::commit(&self.state.ck_t, &[ip_1])?);
+        let com_2 = (com_a.1, com_b.1, IPC::commit(&self.state.ck_t, &[ip_2])?);
+        let challenge = GIPA::<IP, LMC, RMC, IPC, D>::_round_challenge(
+            self.state.r_transcript.len(),
+            Some(self.state.r_transcript.last().unwrap_or(&self.state.seed)),
+            &com_1,
+            &com_2,
+        )?;
+        GIPA::_finish_round(&mut self.state, (com_1, com_2), challenge)
+    }
+
+    pub fn finish(
+        self,
+    ) -> Result<
+        (
+            GIPAProof<IP, LMC, RMC, IPC, D>,
+            GIPAAux<IP, LMC, RMC, IPC, D>,
+        ),
+        Error,
+    > {
+        if self.rounds_left() != 0 {
+            return Err(InnerProductArgumentError::UnexpectedStep(
+                "round task output",
+            ));
+        }
+        GIPA::prove_from_state(self.state, |_| Ok::<_, Error>(()))
+    }
+
+    // State once the rounds are done, e.g. for the TIPA provers from a state
+    pub fn into_state(self) -> GIPAProverState<IP, LMC, RMC, IPC, D> {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{MultiExpGIPA, PairingGIPA};
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use ark_ff::UniformRand;
+    use ark_serialize::CanonicalSerialize;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::thread;
+
+    const TEST_SIZE: usize = 8;
+
+    type TestGIPA = PairingGIPA<Bls12_381, Blake2b512>;
+
+    fn bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        value.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn task_prover_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (ck_a, ck_b, ck_t) = TestGIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let ck = (&ck_a[..], &ck_b[..], &[ck_t.clone()][..]);
+        let com = TestGIPA::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+
+        // Each task on its own thread, gathered in reverse order
+        let mut prover = TaskProver::new(TestGIPA::prover_state((&m_a, &m_b), ck).unwrap());
+        while prover.rounds_left() > 0 {
+            let tasks = prover.round_tasks().unwrap();
+            assert_eq!(tasks.len(), 4);
+            let handles = tasks
+                .into_iter()
+                .map(|task| thread::spawn(move || task.run()))
+                .collect::<Vec<_>>();
+            let outputs = handles
+                .into_iter()
+                .rev()
+                .map(|handle| handle.join().unwrap().unwrap())
+                .collect();
+            prover.finish_round(outputs).unwrap();
+        }
+        assert!(prover.round_tasks().is_err());
+        let (proof, _) = prover.finish().unwrap();
+        assert!(TestGIPA::verify((&ck_a, &ck_b, &ck_t), (&com.0, &com.1, &com.2), &proof).unwrap());
+        let (expected, _) = TestGIPA::prove_with_aux((&m_a, &m_b), ck).unwrap();
+        assert_eq!(bytes(&proof), bytes(&expected));
+
+        // Missing and duplicate outputs
+        let mut prover = TaskProver::new(TestGIPA::prover_state((&m_a, &m_b), ck).unwrap());
+        assert!(prover.finish_round(vec![]).is_err());
+        let run_all = |tasks: Vec<RoundTask<_, _, _>>| {
+            tasks
+                .into_iter()
+                .map(|task| task.run().unwrap())
+                .collect::<Vec<_>>()
+        };
+        let mut outputs = run_all(prover.round_tasks().unwrap());
+        outputs.extend(run_all(prover.round_tasks().unwrap()).pop());
+        assert!(matches!(
+            prover.finish_round(outputs),
+            Err(InnerProductArgumentError::UnexpectedStep(_))
+        ));
+        assert!(prover.finish().is_err());
+
+        // Rounds folding only a left message longer than the right one
+        let (ck_a, ck_b, ck_t) =
+            MultiExpGIPA::<Bls12_381, Blake2b512>::setup_asymmetric(&mut rng, TEST_SIZE, 2)
+                .unwrap();
+        let m_b = vec![Fr::rand(&mut rng), Fr::rand(&mut rng)];
+        let ck = (&ck_a[..], &ck_b[..], &[ck_t.clone()][..]);
+        let mut prover = TaskProver::new(
+            MultiExpGIPA::<Bls12_381, Blake2b512>::prover_state((&m_a, &m_b), ck).unwrap(),
+        );
+        let mut num_tasks = Vec::new();
+        while prover.rounds_left() > 0 {
+            let tasks = prover.round_tasks().unwrap();
+            num_tasks.push(tasks.len());
+            prover
+                .finish_round(tasks.into_iter().map(|task| task.run().unwrap()).collect())
+                .unwrap();
+        }
+        assert_eq!(num_tasks, vec![3, 3, 4]);
+        let (proof, _) = prover.finish().unwrap();
+        let (expected, _) =
+            MultiExpGIPA::<Bls12_381, Blake2b512>::prove_with_aux((&m_a, &m_b), ck).unwrap();
+        assert_eq!(bytes(&proof), bytes(&expected));
+        let com =
+            MultiExpGIPA::<Bls12_381, Blake2b512>::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t))
+                .unwrap();
+        assert!(MultiExpGIPA::<Bls12_381, Blake2b512>::verify(
+            (&ck_a, &ck_b, &ck_t),
+            (&com.0, &com.1, &com.2),
+            &proof
+        )
+        .unwrap());
+    }
+}
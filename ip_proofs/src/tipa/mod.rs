@@ -1,46 +1,66 @@
-use ark_ec::{msm::FixedBaseMSM, PairingEngine, ProjectiveCurve};
-use ark_ff::{to_bytes, Field, One, PrimeField, UniformRand, Zero};
-use ark_poly::polynomial::{univariate::DensePolynomial, UVPolynomial};
-use ark_std::{end_timer, start_timer};
-use digest::Digest;
+use alloc::sync::Arc;
+use ark_ec::{
+    pairing::{MillerLoopOutput, Pairing},
+    scalar_mul::fixed_base::FixedBase,
+    CurveGroup, Group,
+};
+use ark_ff::{
+    BigInt, BigInteger, CyclotomicMultSubgroup, Field, One, PrimeField, UniformRand, Zero,
+};
+use ark_poly::polynomial::{univariate::DensePolynomial, DenseUVPolynomial};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Write,
+};
+use ark_std::marker::PhantomData;
+use ark_std::{cfg_chunks, cfg_iter, end_timer, start_timer, vec, vec::Vec};
 use itertools::Itertools;
-use rand::Rng;
-use std::{marker::PhantomData, ops::MulAssign};
+use rand::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use crate::{
-    gipa::{GIPAProof, GIPA},
-    Error,
+    gipa::{GIPAAux, GIPAProof, GIPAProverState, GIPA},
+    limits::{deserialize_vec, limits},
+    padding::{padding_seed, PaddedInstance},
+    session::session_transcript,
+    tipa::structured_key::{StructuredCommitmentKey, StructuredCommitmentKeys},
+    transcript::{ChallengeInput, SharedTranscript, TranscriptDigest},
+    validation::{validate_generators, Validate},
+    Error, InnerProductArgumentError,
 };
 use ark_dh_commitments::{
     afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
     pedersen::PedersenCommitment,
     DoublyHomomorphicCommitment,
 };
+#[cfg(feature = "serde")]
+use ark_inner_products::impl_canonical_serde;
 use ark_inner_products::{InnerProduct, MultiexponentiationInnerProduct};
 
+pub mod structured_key;
 pub mod structured_scalar_message;
 
 //TODO: Could generalize: Don't need TIPA over G1 and G2, would work with G1 and G1 or over different pairing engines
 pub trait TIPACompatibleSetup {}
 
-impl<G: ProjectiveCurve> TIPACompatibleSetup for PedersenCommitment<G> {}
-impl<P: PairingEngine> TIPACompatibleSetup for AFGHOCommitmentG1<P> {}
-impl<P: PairingEngine> TIPACompatibleSetup for AFGHOCommitmentG2<P> {}
+impl<G: CurveGroup> TIPACompatibleSetup for PedersenCommitment<G> {}
+impl<P: Pairing> TIPACompatibleSetup for AFGHOCommitmentG1<P> {}
+impl<P: Pairing> TIPACompatibleSetup for AFGHOCommitmentG2<P> {}
 
 //TODO: May need to add "reverse" MultiexponentiationInnerProduct to allow for MIP with G2 messages (because TIP hard-coded G1 left and G2 right)
 pub struct TIPA<IP, LMC, RMC, IPC, P, D> {
-    _inner_product: PhantomData<IP>,
-    _left_commitment: PhantomData<LMC>,
-    _right_commitment: PhantomData<RMC>,
-    _inner_product_commitment: PhantomData<IPC>,
-    _pair: PhantomData<P>,
-    _digest: PhantomData<D>,
+    _inner_product: PhantomData<fn() -> IP>,
+    _left_commitment: PhantomData<fn() -> LMC>,
+    _right_commitment: PhantomData<fn() -> RMC>,
+    _inner_product_commitment: PhantomData<fn() -> IPC>,
+    _pair: PhantomData<fn() -> P>,
+    _digest: PhantomData<fn() -> D>,
 }
 
 pub struct TIPAProof<IP, LMC, RMC, IPC, P, D>
 where
-    D: Digest,
-    P: PairingEngine,
+    D: TranscriptDigest,
+    P: Pairing,
     IP: InnerProduct<
         LeftMessage = LMC::Message,
         RightMessage = RMC::Message,
@@ -49,23 +69,17 @@ where
     LMC: DoublyHomomorphicCommitment + TIPACompatibleSetup,
     RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar> + TIPACompatibleSetup,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    RMC::Message: MulAssign<LMC::Scalar>,
-    IPC::Message: MulAssign<LMC::Scalar>,
-    RMC::Key: MulAssign<LMC::Scalar>,
-    IPC::Key: MulAssign<LMC::Scalar>,
-    RMC::Output: MulAssign<LMC::Scalar>,
-    IPC::Output: MulAssign<LMC::Scalar>,
 {
-    gipa_proof: GIPAProof<IP, LMC, RMC, IPC, D>,
+    pub(crate) gipa_proof: GIPAProof<IP, LMC, RMC, IPC, D>,
     final_ck: (LMC::Key, RMC::Key),
-    final_ck_proof: (P::G2Projective, P::G1Projective),
+    final_ck_proof: (P::G2, P::G1),
     _pair: PhantomData<P>,
 }
 
 impl<IP, LMC, RMC, IPC, P, D> Clone for TIPAProof<IP, LMC, RMC, IPC, P, D>
 where
-    D: Digest,
-    P: PairingEngine,
+    D: TranscriptDigest,
+    P: Pairing,
     IP: InnerProduct<
         LeftMessage = LMC::Message,
         RightMessage = RMC::Message,
@@ -74,100 +88,391 @@ where
     LMC: DoublyHomomorphicCommitment + TIPACompatibleSetup,
     RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar> + TIPACompatibleSetup,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    RMC::Message: MulAssign<LMC::Scalar>,
-    IPC::Message: MulAssign<LMC::Scalar>,
-    RMC::Key: MulAssign<LMC::Scalar>,
-    IPC::Key: MulAssign<LMC::Scalar>,
-    RMC::Output: MulAssign<LMC::Scalar>,
-    IPC::Output: MulAssign<LMC::Scalar>,
 {
     fn clone(&self) -> Self {
         Self {
             gipa_proof: self.gipa_proof.clone(),
             final_ck: self.final_ck.clone(),
-            final_ck_proof: self.final_ck_proof.clone(),
+            final_ck_proof: self.final_ck_proof,
             _pair: PhantomData,
         }
     }
 }
 
+impl<IP, LMC, RMC, IPC, P, D> CanonicalSerialize for TIPAProof<IP, LMC, RMC, IPC, P, D>
+where
+    D: TranscriptDigest,
+    P: Pairing,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment + TIPACompatibleSetup,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar> + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    GIPAProof<IP, LMC, RMC, IPC, D>: CanonicalSerialize,
+    LMC::Key: CanonicalSerialize,
+    RMC::Key: CanonicalSerialize,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.gipa_proof.serialize_with_mode(&mut writer, compress)?;
+        self.final_ck.serialize_with_mode(&mut writer, compress)?;
+        self.final_ck_proof
+            .serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.gipa_proof.serialized_size(compress)
+            + self.final_ck.serialized_size(compress)
+            + self.final_ck_proof.serialized_size(compress)
+    }
+}
+
+impl<IP, LMC, RMC, IPC, P, D> Valid for TIPAProof<IP, LMC, RMC, IPC, P, D>
+where
+    D: TranscriptDigest,
+    P: Pairing,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment + TIPACompatibleSetup,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar> + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    GIPAProof<IP, LMC, RMC, IPC, D>: CanonicalDeserialize,
+    LMC::Key: CanonicalDeserialize,
+    RMC::Key: CanonicalDeserialize,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        self.gipa_proof.check()?;
+        self.final_ck.check()?;
+        self.final_ck_proof.check()
+    }
+}
+
+impl<IP, LMC, RMC, IPC, P, D> CanonicalDeserialize for TIPAProof<IP, LMC, RMC, IPC, P, D>
+where
+    D: TranscriptDigest,
+    P: Pairing,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment + TIPACompatibleSetup,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar> + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    GIPAProof<IP, LMC, RMC, IPC, D>: CanonicalDeserialize,
+    LMC::Key: CanonicalDeserialize,
+    RMC::Key: CanonicalDeserialize,
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(TIPAProof {
+            gipa_proof: CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+            final_ck: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            final_ck_proof: CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+            _pair: PhantomData,
+        })
+    }
+}
+
+impl<IP, LMC, RMC, IPC, P, D> Validate for TIPAProof<IP, LMC, RMC, IPC, P, D>
+where
+    D: TranscriptDigest,
+    P: Pairing,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment + TIPACompatibleSetup,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar> + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    GIPAProof<IP, LMC, RMC, IPC, D>: Validate,
+    LMC::Key: Validate,
+    RMC::Key: Validate,
+    P::G1: Validate,
+    P::G2: Validate,
+{
+    fn validate(&self) -> Result<(), Error> {
+        self.gipa_proof.validate()?;
+        self.final_ck.validate()?;
+        self.final_ck_proof.validate()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl_canonical_serde!([IP, LMC, RMC, IPC, P, D] TIPAProof<IP, LMC, RMC, IPC, P, D>
+where
+    D: TranscriptDigest,
+    P: Pairing,
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment + TIPACompatibleSetup,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar> + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+);
+
 #[derive(Clone)]
-pub struct SRS<P: PairingEngine> {
-    pub g_alpha_powers: Vec<P::G1Projective>,
-    pub h_beta_powers: Vec<P::G2Projective>,
-    pub g_beta: P::G1Projective,
-    pub h_alpha: P::G2Projective,
+pub struct SRS<P: Pairing> {
+    pub g_alpha_powers: Vec<P::G1>,
+    pub h_beta_powers: Vec<P::G2>,
+    pub g_beta: P::G1,
+    pub h_alpha: P::G2,
+}
+
+#[derive(Clone)]
+pub struct VerifierSRS<P: Pairing> {
+    pub g: P::G1,
+    pub h: P::G2,
+    pub g_beta: P::G1,
+    pub h_alpha: P::G2,
+}
+
+impl<P: Pairing> CanonicalSerialize for SRS<P> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.g_alpha_powers
+            .serialize_with_mode(&mut writer, compress)?;
+        self.h_beta_powers
+            .serialize_with_mode(&mut writer, compress)?;
+        self.g_beta.serialize_with_mode(&mut writer, compress)?;
+        self.h_alpha.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.g_alpha_powers.serialized_size(compress)
+            + self.h_beta_powers.serialized_size(compress)
+            + self.g_beta.serialized_size(compress)
+            + self.h_alpha.serialized_size(compress)
+    }
+}
+
+impl<P: Pairing> Valid for SRS<P> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.g_alpha_powers.check()?;
+        self.h_beta_powers.check()?;
+        self.g_beta.check()?;
+        self.h_alpha.check()
+    }
+}
+
+impl<P: Pairing> CanonicalDeserialize for SRS<P> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        let max_srs_size = limits().max_srs_size;
+        let srs = SRS {
+            g_alpha_powers: deserialize_vec(&mut reader, compress, validate, max_srs_size)?,
+            h_beta_powers: deserialize_vec(&mut reader, compress, validate, max_srs_size)?,
+            g_beta: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            h_alpha: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+        };
+        // Powers are generated for 2 * size - 1 exponents of a power of 2 size
+        if srs.g_alpha_powers.len() != srs.h_beta_powers.len()
+            || !(srs.g_alpha_powers.len() + 1).is_power_of_two()
+        {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(srs)
+    }
+}
+
+impl<P: Pairing> CanonicalSerialize for VerifierSRS<P> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.g.serialize_with_mode(&mut writer, compress)?;
+        self.h.serialize_with_mode(&mut writer, compress)?;
+        self.g_beta.serialize_with_mode(&mut writer, compress)?;
+        self.h_alpha.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.g.serialized_size(compress)
+            + self.h.serialized_size(compress)
+            + self.g_beta.serialized_size(compress)
+            + self.h_alpha.serialized_size(compress)
+    }
+}
+
+impl<P: Pairing> Valid for VerifierSRS<P> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.g.check()?;
+        self.h.check()?;
+        self.g_beta.check()?;
+        self.h_alpha.check()
+    }
+}
+
+impl<P: Pairing> CanonicalDeserialize for VerifierSRS<P> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(VerifierSRS {
+            g: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            h: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            g_beta: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            h_alpha: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+        })
+    }
+}
+
+impl<P: Pairing> Validate for SRS<P>
+where
+    P::G1: Validate,
+    P::G2: Validate,
+{
+    fn validate(&self) -> Result<(), Error> {
+        validate_generators(&self.g_alpha_powers)?;
+        validate_generators(&self.h_beta_powers)?;
+        validate_generators(&[self.g_beta])?;
+        validate_generators(&[self.h_alpha])
+    }
+}
+
+impl<P: Pairing> Validate for VerifierSRS<P>
+where
+    P::G1: Validate,
+    P::G2: Validate,
+{
+    fn validate(&self) -> Result<(), Error> {
+        validate_generators(&[self.g, self.g_beta])?;
+        validate_generators(&[self.h, self.h_alpha])
+    }
 }
 
+// Verifier SRS with its generators prepared for pairings, e.g. kept in a `KeyCache` by services
+// verifying many proofs so that the preparation is not repeated for each of them
 #[derive(Clone)]
-pub struct VerifierSRS<P: PairingEngine> {
-    pub g: P::G1Projective,
-    pub h: P::G2Projective,
-    pub g_beta: P::G1Projective,
-    pub h_alpha: P::G2Projective,
+pub struct PreparedVerifierSRS<P: Pairing> {
+    pub v_srs: VerifierSRS<P>,
+    pub g: P::G1Prepared,
+    pub h: P::G2Prepared,
+    pub g_beta: P::G1Prepared,
+    pub h_alpha: P::G2Prepared,
+}
+
+impl<P: Pairing> VerifierSRS<P> {
+    pub fn prepare(&self) -> PreparedVerifierSRS<P> {
+        PreparedVerifierSRS {
+            v_srs: self.clone(),
+            g: self.g.into_affine().into(),
+            h: self.h.into_affine().into(),
+            g_beta: self.g_beta.into_affine().into(),
+            h_alpha: self.h_alpha.into_affine().into(),
+        }
+    }
 }
 
+#[cfg(feature = "serde")]
+impl_canonical_serde!([P: Pairing] SRS<P>);
+#[cfg(feature = "serde")]
+impl_canonical_serde!([P: Pairing] VerifierSRS<P>);
+
 //TODO: Change SRS to return reference iterator - requires changes to TIPA and GIPA signatures
-impl<P: PairingEngine> SRS<P> {
-    pub fn get_commitment_keys(&self) -> (Vec<P::G2Projective>, Vec<P::G1Projective>) {
+impl<P: Pairing> SRS<P> {
+    pub fn get_commitment_keys(&self) -> (Vec<P::G2>, Vec<P::G1>) {
         let ck_1 = self.h_beta_powers.iter().step_by(2).cloned().collect();
         let ck_2 = self.g_alpha_powers.iter().step_by(2).cloned().collect();
         (ck_1, ck_2)
     }
 
+    // Commitment keys as handles to the SRS powers, which expose the structure of the final keys
+    pub fn get_structured_commitment_keys(&self) -> Result<StructuredCommitmentKeys<P>, Error> {
+        Ok((
+            StructuredCommitmentKey::new(Arc::new(self.h_beta_powers.clone()))?,
+            StructuredCommitmentKey::new(Arc::new(self.g_alpha_powers.clone()))?,
+        ))
+    }
+
     pub fn get_verifier_key(&self) -> VerifierSRS<P> {
         VerifierSRS {
-            g: self.g_alpha_powers[0].clone(),
-            h: self.h_beta_powers[0].clone(),
-            g_beta: self.g_beta.clone(),
-            h_alpha: self.h_alpha.clone(),
+            g: self.g_alpha_powers[0],
+            h: self.h_beta_powers[0],
+            g_beta: self.g_beta,
+            h_alpha: self.h_alpha,
         }
     }
 }
 
 impl<IP, LMC, RMC, IPC, P, D> TIPA<IP, LMC, RMC, IPC, P, D>
 where
-    D: Digest,
-    P: PairingEngine,
+    D: TranscriptDigest,
+    P: Pairing,
     IP: InnerProduct<
         LeftMessage = LMC::Message,
         RightMessage = RMC::Message,
         Output = IPC::Message,
     >,
-    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective> + TIPACompatibleSetup,
-    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1Projective>
-        + TIPACompatibleSetup,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::ScalarField, Key = P::G2> + TIPACompatibleSetup,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar, Key = P::G1> + TIPACompatibleSetup,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    LMC::Message: MulAssign<P::Fr>,
-    RMC::Message: MulAssign<P::Fr>,
-    IPC::Message: MulAssign<P::Fr>,
-    IPC::Key: MulAssign<P::Fr>,
-    LMC::Output: MulAssign<P::Fr>,
-    RMC::Output: MulAssign<P::Fr>,
-    IPC::Output: MulAssign<P::Fr>,
 {
-    pub fn setup<R: Rng>(rng: &mut R, size: usize) -> Result<(SRS<P>, IPC::Key), Error> {
-        let alpha = <P::Fr>::rand(rng);
-        let beta = <P::Fr>::rand(rng);
-        let g = <P::G1Projective>::prime_subgroup_generator();
-        let h = <P::G2Projective>::prime_subgroup_generator();
+    pub fn setup<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        size: usize,
+    ) -> Result<(SRS<P>, IPC::Key), Error> {
+        limits().check_srs_size(2 * size - 1)?;
+        let alpha = <P::ScalarField>::rand(rng);
+        let beta = <P::ScalarField>::rand(rng);
+        let g = <P::G1>::generator();
+        let h = <P::G2>::generator();
         Ok((
             SRS {
                 g_alpha_powers: structured_generators_scalar_power(2 * size - 1, &g, &alpha),
                 h_beta_powers: structured_generators_scalar_power(2 * size - 1, &h, &beta),
-                g_beta: g.mul(beta.into_repr()),
-                h_alpha: h.mul(alpha.into_repr()),
+                g_beta: g.mul_bigint(beta.into_bigint()),
+                h_alpha: h.mul_bigint(alpha.into_bigint()),
             },
             IPC::setup(rng, 1)?.pop().unwrap(),
         ))
     }
 
+    // Commitments to the messages and to their inner product, the statement of `verify`
+    pub fn commit(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+    ) -> Result<(LMC::Output, RMC::Output, IPC::Output), Error> {
+        <GIPA<IP, LMC, RMC, IPC, D>>::commit(values, ck)
+    }
+
     pub fn prove(
         srs: &SRS<P>,
         values: (&[IP::LeftMessage], &[IP::RightMessage]),
         ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
     ) -> Result<TIPAProof<IP, LMC, RMC, IPC, P, D>, Error> {
-        Self::prove_with_srs_shift(srs, values, ck, &<P::Fr>::one())
+        Self::prove_with_srs_shift(srs, values, ck, &<P::ScalarField>::one())
     }
 
     // Shifts KZG proof for left message by scalar r (used for efficient composition with aggregation protocols)
@@ -176,32 +481,131 @@ where
         srs: &SRS<P>,
         values: (&[IP::LeftMessage], &[IP::RightMessage]),
         ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
-        r_shift: &P::Fr,
+        r_shift: &P::ScalarField,
     ) -> Result<TIPAProof<IP, LMC, RMC, IPC, P, D>, Error> {
-        // Run GIPA
-        let (proof, aux) = <GIPA<IP, LMC, RMC, IPC, D>>::prove_with_aux(
+        let state = <GIPA<IP, LMC, RMC, IPC, D>>::prover_state(
             values,
-            (ck.0, ck.1, &vec![ck.2.clone()]),
+            (ck.0, ck.1, ark_std::slice::from_ref(ck.2)),
         )?;
+        Self::prove_with_srs_shift_from_state(srs, state, r_shift, |_| Ok::<_, Error>(()))
+    }
 
+    // Runs the prover from a GIPA prover state, e.g. one restored from a checkpoint, passing the
+    // state after each GIPA round to `checkpoint`
+    pub fn prove_with_srs_shift_from_state<E: From<Error>>(
+        srs: &SRS<P>,
+        state: GIPAProverState<IP, LMC, RMC, IPC, D>,
+        r_shift: &P::ScalarField,
+        checkpoint: impl FnMut(&GIPAProverState<IP, LMC, RMC, IPC, D>) -> Result<(), E>,
+    ) -> Result<TIPAProof<IP, LMC, RMC, IPC, P, D>, E> {
+        let _prove = phase_span!("tipa_prove", len = state.len());
+        // The KZG openings are for keys folded in every round
+        if state.m_a.len() != state.m_b.len() {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                state.m_a.len(),
+                state.m_b.len(),
+            )
+            .into());
+        }
+        // Run GIPA
+        let (proof, aux) = <GIPA<IP, LMC, RMC, IPC, D>>::prove_from_state(state, checkpoint)?;
+        Ok(Self::_prove_final_ck(srs, proof, aux, r_shift)?)
+    }
+
+    // Proves as a sub-protocol of a composite argument: the statement `com` is absorbed into
+    // `transcript` and seeds the first GIPA challenge, and the proof is absorbed after
+    pub fn prove_in_transcript(
+        srs: &SRS<P>,
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        transcript: &mut SharedTranscript<D>,
+    ) -> Result<TIPAProof<IP, LMC, RMC, IPC, P, D>, Error>
+    where
+        TIPAProof<IP, LMC, RMC, IPC, P, D>: CanonicalSerialize,
+    {
+        let seed = <GIPA<IP, LMC, RMC, IPC, D>>::seed_from_transcript(com, transcript)?;
+        let state = <GIPA<IP, LMC, RMC, IPC, D>>::prover_state(
+            values,
+            (ck.0, ck.1, ark_std::slice::from_ref(ck.2)),
+        )?
+        .with_seed(seed);
+        let proof =
+            Self::prove_with_srs_shift_from_state(srs, state, &<P::ScalarField>::one(), |_| {
+                Ok::<_, Error>(())
+            })?;
+        transcript.absorb(b"tipa-proof", &proof)?;
+        Ok(proof)
+    }
+
+    // Commitments to padded messages under the first generators of keys of the padded length
+    pub fn commit_padded(
+        values: (
+            &PaddedInstance<IP::LeftMessage>,
+            &PaddedInstance<IP::RightMessage>,
+        ),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+    ) -> Result<(LMC::Output, RMC::Output, IPC::Output), Error> {
+        <GIPA<IP, LMC, RMC, IPC, D>>::commit_padded(values, ck)
+    }
+
+    // Proves over the padded messages with the true length bound into the challenges
+    pub fn prove_padded(
+        srs: &SRS<P>,
+        values: (
+            &PaddedInstance<IP::LeftMessage>,
+            &PaddedInstance<IP::RightMessage>,
+        ),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+    ) -> Result<TIPAProof<IP, LMC, RMC, IPC, P, D>, Error> {
+        let len = values.0.unpadded_len();
+        if values.1.unpadded_len() != len {
+            return Err(InnerProductArgumentError::MessageLengthInvalid(
+                len,
+                values.1.unpadded_len(),
+            ));
+        }
+        let seed = padding_seed::<_, D>(len, ck.0.len())?;
+        let state = <GIPA<IP, LMC, RMC, IPC, D>>::prover_state(
+            (values.0.values(), values.1.values()),
+            (ck.0, ck.1, ark_std::slice::from_ref(ck.2)),
+        )?
+        .with_seed(seed);
+        Self::prove_with_srs_shift_from_state(srs, state, &<P::ScalarField>::one(), |_| {
+            Ok::<_, Error>(())
+        })
+    }
+
+    fn _prove_final_ck(
+        srs: &SRS<P>,
+        proof: GIPAProof<IP, LMC, RMC, IPC, D>,
+        aux: GIPAAux<IP, LMC, RMC, IPC, D>,
+        r_shift: &P::ScalarField,
+    ) -> Result<TIPAProof<IP, LMC, RMC, IPC, P, D>, Error> {
         // Prove final commitment keys are wellformed
         let (ck_a_final, ck_b_final) = aux.ck_base;
         let transcript = aux.r_transcript;
-        let transcript_inverse = transcript.iter().map(|x| x.inverse().unwrap()).collect();
-        let r_inverse = r_shift.inverse().unwrap();
+        let transcript_inverse = transcript
+            .iter()
+            .map(|x| x.inverse().ok_or(InnerProductArgumentError::ZeroChallenge))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let r_inverse = r_shift
+            .inverse()
+            .ok_or(InnerProductArgumentError::ZeroChallenge)?;
 
         // KZG challenge point
+        let mut input = D::input();
+        input.absorb_scalar(
+            transcript
+                .first()
+                .ok_or(InnerProductArgumentError::EmptyTranscript)?,
+        )?;
+        input.absorb(&ck_a_final)?;
+        input.absorb(&ck_b_final)?;
         let mut counter_nonce: usize = 0;
         let c = loop {
-            let mut hash_input = Vec::new();
-            hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-            //TODO: Should use CanonicalSerialize instead of ToBytes
-            hash_input.extend_from_slice(&to_bytes![
-                transcript.first().unwrap(),
-                ck_a_final,
-                ck_b_final
-            ]?);
-            if let Some(c) = LMC::Scalar::from_random_bytes(&D::digest(&hash_input)) {
+            if let Some(c) = input.challenge::<LMC::Scalar>(counter_nonce) {
+                transcript_log!("tipa", None, b"kzg-point", &c, nonce = counter_nonce);
                 break c;
             };
             counter_nonce += 1;
@@ -217,7 +621,7 @@ where
         let ck_b_kzg_opening = prove_commitment_key_kzg_opening(
             &srs.g_alpha_powers,
             &transcript,
-            &<P::Fr>::one(),
+            &<P::ScalarField>::one(),
             &c,
         )?;
 
@@ -235,7 +639,7 @@ where
         com: (&LMC::Output, &RMC::Output, &IPC::Output),
         proof: &TIPAProof<IP, LMC, RMC, IPC, P, D>,
     ) -> Result<bool, Error> {
-        Self::verify_with_srs_shift(v_srs, ck_t, com, proof, &<P::Fr>::one())
+        Self::verify_with_srs_shift(v_srs, ck_t, com, proof, &<P::ScalarField>::one())
     }
 
     pub fn verify_with_srs_shift(
@@ -243,66 +647,516 @@ where
         ck_t: &IPC::Key,
         com: (&LMC::Output, &RMC::Output, &IPC::Output),
         proof: &TIPAProof<IP, LMC, RMC, IPC, P, D>,
-        r_shift: &P::Fr,
+        r_shift: &P::ScalarField,
+    ) -> Result<bool, Error> {
+        let _verify = phase_span!("tipa_verify");
+        let (base_valid, pairing_checks) =
+            Self::verify_with_srs_shift_deferred(v_srs, ck_t, com, proof, r_shift)?;
+        Ok(base_valid && PairingCheck::verify_all(&pairing_checks))
+    }
+
+    // Verifies several proofs with the pairing checks of all of them in one batch
+    pub fn verify_batch<R: RngCore + CryptoRng>(
+        v_srs: &VerifierSRS<P>,
+        ck_t: &IPC::Key,
+        instances: &[(
+            (&LMC::Output, &RMC::Output, &IPC::Output),
+            &TIPAProof<IP, LMC, RMC, IPC, P, D>,
+        )],
+        rng: &mut R,
+    ) -> Result<bool, Error> {
+        let _verify = phase_span!("tipa_verify_batch", proofs = instances.len());
+        let mut batch = PairingCheckBatch::new();
+        for (com, proof) in instances {
+            let (base_valid, pairing_checks) = Self::verify_with_srs_shift_deferred(
+                v_srs,
+                ck_t,
+                *com,
+                proof,
+                &<P::ScalarField>::one(),
+            )?;
+            if !base_valid {
+                return Ok(false);
+            }
+            batch.extend(pairing_checks);
+        }
+        Ok(batch.verify(rng))
+    }
+
+    pub fn verify_in_transcript(
+        v_srs: &VerifierSRS<P>,
+        ck_t: &IPC::Key,
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &TIPAProof<IP, LMC, RMC, IPC, P, D>,
+        transcript: &mut SharedTranscript<D>,
+    ) -> Result<bool, Error>
+    where
+        TIPAProof<IP, LMC, RMC, IPC, P, D>: CanonicalSerialize,
+    {
+        let seed = <GIPA<IP, LMC, RMC, IPC, D>>::seed_from_transcript(com, transcript)?;
+        transcript.absorb(b"tipa-proof", proof)?;
+        let (base_valid, pairing_checks) =
+            Self::_verify_deferred(v_srs, ck_t, com, proof, &<P::ScalarField>::one(), &seed)?;
+        Ok(base_valid && PairingCheck::verify_all(&pairing_checks))
+    }
+
+    // Proves in the session `id` supplied by the application, see `session::session_transcript`
+    pub fn prove_with_session(
+        srs: &SRS<P>,
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &[RMC::Key], &IPC::Key),
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        id: &[u8],
+    ) -> Result<TIPAProof<IP, LMC, RMC, IPC, P, D>, Error>
+    where
+        TIPAProof<IP, LMC, RMC, IPC, P, D>: CanonicalSerialize,
+    {
+        Self::prove_in_transcript(srs, values, ck, com, &mut session_transcript(id)?)
+    }
+
+    pub fn verify_with_session(
+        v_srs: &VerifierSRS<P>,
+        ck_t: &IPC::Key,
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &TIPAProof<IP, LMC, RMC, IPC, P, D>,
+        id: &[u8],
+    ) -> Result<bool, Error>
+    where
+        TIPAProof<IP, LMC, RMC, IPC, P, D>: CanonicalSerialize,
+    {
+        Self::verify_in_transcript(v_srs, ck_t, com, proof, &mut session_transcript(id)?)
+    }
+
+    // Verifies a proof over messages of true length `len`, padded to a length of 2^rounds
+    pub fn verify_padded(
+        v_srs: &VerifierSRS<P>,
+        ck_t: &IPC::Key,
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        len: usize,
+        proof: &TIPAProof<IP, LMC, RMC, IPC, P, D>,
     ) -> Result<bool, Error> {
+        let rounds = proof.gipa_proof.r_commitment_steps.len();
+        limits().check_rounds(rounds)?;
+        let seed = match padding_seed::<_, D>(len, 1 << rounds) {
+            Ok(seed) => seed,
+            Err(_) => return Ok(false),
+        };
+        let (base_valid, pairing_checks) =
+            Self::_verify_deferred(v_srs, ck_t, com, proof, &<P::ScalarField>::one(), &seed)?;
+        Ok(base_valid && PairingCheck::verify_all(&pairing_checks))
+    }
+
+    // Runs all verifier checks except the commitment key openings, which are returned unevaluated.
+    // The base commitment is still checked, with pairings for pairing-based commitments
+    pub fn verify_with_srs_shift_deferred(
+        v_srs: &VerifierSRS<P>,
+        ck_t: &IPC::Key,
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &TIPAProof<IP, LMC, RMC, IPC, P, D>,
+        r_shift: &P::ScalarField,
+    ) -> Result<(bool, Vec<PairingCheck<P>>), Error> {
+        Self::_verify_deferred(
+            v_srs,
+            ck_t,
+            com,
+            proof,
+            r_shift,
+            &<P::ScalarField>::default(),
+        )
+    }
+
+    // As `verify_with_srs_shift_deferred`, also leaving the base commitment unchecked for the caller,
+    // for a proof whose first challenge was seeded with `seed` as with `GIPAProverState::with_seed`
+    pub fn verify_with_srs_shift_base_deferred(
+        v_srs: &VerifierSRS<P>,
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &TIPAProof<IP, LMC, RMC, IPC, P, D>,
+        r_shift: &P::ScalarField,
+        seed: &P::ScalarField,
+    ) -> Result<(TIPABase<IP, LMC, RMC, IPC>, Vec<PairingCheck<P>>), Error> {
+        Self::_verify_base_deferred(v_srs, com, proof, r_shift, seed)
+    }
+
+    fn _verify_deferred(
+        v_srs: &VerifierSRS<P>,
+        ck_t: &IPC::Key,
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &TIPAProof<IP, LMC, RMC, IPC, P, D>,
+        r_shift: &P::ScalarField,
+        seed: &P::ScalarField,
+    ) -> Result<(bool, Vec<PairingCheck<P>>), Error> {
+        let (base, pairing_checks) = Self::_verify_base_deferred(v_srs, com, proof, r_shift, seed)?;
+        Ok((base.verify(ck_t)?, pairing_checks))
+    }
+
+    fn _verify_base_deferred(
+        v_srs: &VerifierSRS<P>,
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &TIPAProof<IP, LMC, RMC, IPC, P, D>,
+        r_shift: &P::ScalarField,
+        seed: &P::ScalarField,
+    ) -> Result<(TIPABase<IP, LMC, RMC, IPC>, Vec<PairingCheck<P>>), Error> {
         let (base_com, transcript) =
-            GIPA::verify_recursive_challenge_transcript(com, &proof.gipa_proof)?;
-        let transcript_inverse = transcript.iter().map(|x| x.inverse().unwrap()).collect();
+            GIPA::verify_recursive_challenge_transcript_with_seed(com, &proof.gipa_proof, seed)?;
+        let transcript_inverse = transcript
+            .iter()
+            .map(|x| x.inverse().ok_or(InnerProductArgumentError::ZeroChallenge))
+            .collect::<Result<Vec<_>, Error>>()?;
 
         // Verify commitment keys wellformed
         let (ck_a_final, ck_b_final) = &proof.final_ck;
         let (ck_a_proof, ck_b_proof) = &proof.final_ck_proof;
 
         // KZG challenge point
+        let mut input = D::input();
+        input.absorb_scalar(
+            transcript
+                .first()
+                .ok_or(InnerProductArgumentError::EmptyTranscript)?,
+        )?;
+        input.absorb(ck_a_final)?;
+        input.absorb(ck_b_final)?;
         let mut counter_nonce: usize = 0;
         let c = loop {
-            let mut hash_input = Vec::new();
-            hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-            //TODO: Should use CanonicalSerialize instead of ToBytes
-            hash_input.extend_from_slice(&to_bytes![
-                transcript.first().unwrap(),
-                ck_a_final,
-                ck_b_final
-            ]?);
-            if let Some(c) = LMC::Scalar::from_random_bytes(&D::digest(&hash_input)) {
+            if let Some(c) = input.challenge::<LMC::Scalar>(counter_nonce) {
+                transcript_log!("tipa", None, b"kzg-point", &c, nonce = counter_nonce);
                 break c;
             };
             counter_nonce += 1;
         };
 
-        let ck_a_valid = verify_commitment_key_g2_kzg_opening(
+        let ck_a_check = commitment_key_g2_kzg_opening_check(
             v_srs,
-            &ck_a_final,
-            &ck_a_proof,
+            ck_a_final,
+            ck_a_proof,
             &transcript_inverse,
-            &r_shift.inverse().unwrap(),
+            &r_shift
+                .inverse()
+                .ok_or(InnerProductArgumentError::ZeroChallenge)?,
             &c,
-        )?;
-        let ck_b_valid = verify_commitment_key_g1_kzg_opening(
+        );
+        let ck_b_check = commitment_key_g1_kzg_opening_check(
             v_srs,
-            &ck_b_final,
-            &ck_b_proof,
+            ck_b_final,
+            ck_b_proof,
             &transcript,
-            &<P::Fr>::one(),
+            &<P::ScalarField>::one(),
             &c,
+        );
+
+        let base = TIPABase {
+            com: base_com,
+            ck: (*ck_a_final, *ck_b_final),
+            m: proof.gipa_proof.r_base.clone(),
+            _inner_product: PhantomData,
+        };
+        Ok((base, vec![ck_a_check, ck_b_check]))
+    }
+
+    // Evaluation claims checked by the commitment key pairing checks, for verifiers evaluating
+    // them outside of this crate
+    pub fn commitment_key_openings(
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &TIPAProof<IP, LMC, RMC, IPC, P, D>,
+        r_shift: &P::ScalarField,
+    ) -> Result<(CommitmentKeyOpening<P::G2>, CommitmentKeyOpening<P::G1>), Error> {
+        Self::commitment_key_openings_with_seed(com, proof, r_shift, &<P::ScalarField>::default())
+    }
+
+    // As `commitment_key_openings` for a proof whose first challenge was seeded with `seed`
+    pub fn commitment_key_openings_with_seed(
+        com: (&LMC::Output, &RMC::Output, &IPC::Output),
+        proof: &TIPAProof<IP, LMC, RMC, IPC, P, D>,
+        r_shift: &P::ScalarField,
+        seed: &P::ScalarField,
+    ) -> Result<(CommitmentKeyOpening<P::G2>, CommitmentKeyOpening<P::G1>), Error> {
+        let (_, transcript) =
+            GIPA::verify_recursive_challenge_transcript_with_seed(com, &proof.gipa_proof, seed)?;
+        let transcript_inverse = transcript
+            .iter()
+            .map(|x| x.inverse().ok_or(InnerProductArgumentError::ZeroChallenge))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let (ck_a_final, ck_b_final) = &proof.final_ck;
+        let (ck_a_proof, ck_b_proof) = &proof.final_ck_proof;
+
+        // KZG challenge point
+        let mut input = D::input();
+        input.absorb_scalar(
+            transcript
+                .first()
+                .ok_or(InnerProductArgumentError::EmptyTranscript)?,
         )?;
+        input.absorb(ck_a_final)?;
+        input.absorb(ck_b_final)?;
+        let mut counter_nonce: usize = 0;
+        let c = loop {
+            if let Some(c) = input.challenge::<LMC::Scalar>(counter_nonce) {
+                transcript_log!("tipa", None, b"kzg-point", &c, nonce = counter_nonce);
+                break c;
+            };
+            counter_nonce += 1;
+        };
 
-        // Verify base inner product commitment
-        let (com_a, com_b, com_t) = base_com;
-        let a_base = vec![proof.gipa_proof.r_base.0.clone()];
-        let b_base = vec![proof.gipa_proof.r_base.1.clone()];
+        let r_inverse = r_shift
+            .inverse()
+            .ok_or(InnerProductArgumentError::ZeroChallenge)?;
+        Ok((
+            CommitmentKeyOpening {
+                ck_final: *ck_a_final,
+                ck_opening: *ck_a_proof,
+                kzg_challenge: c,
+                eval: polynomial_evaluation_product_form_from_transcript(
+                    &transcript_inverse,
+                    &c,
+                    &r_inverse,
+                ),
+                transcript: transcript_inverse,
+                shift: r_inverse,
+            },
+            CommitmentKeyOpening {
+                ck_final: *ck_b_final,
+                ck_opening: *ck_b_proof,
+                kzg_challenge: c,
+                eval: polynomial_evaluation_product_form_from_transcript(
+                    &transcript,
+                    &c,
+                    &<P::ScalarField>::one(),
+                ),
+                transcript,
+                shift: <P::ScalarField>::one(),
+            },
+        ))
+    }
+}
+
+// Pairing product equation prod e(a_i, b_i) = target, kept unevaluated so that it can be batched
+// with other checks or handed to an outer circuit
+#[derive(Clone)]
+pub struct PairingCheck<P: Pairing> {
+    pub pairs: Vec<(P::G1, P::G2)>,
+    pub target: P::TargetField,
+}
+
+impl<P: Pairing> PairingCheck<P> {
+    pub fn verify(&self) -> bool {
+        phase_count!(pairings, self.pairs.len());
+        phase_count!(final_exponentiations, 1);
+        self._evaluate()
+    }
+
+    // Verifies the checks in parallel, counting their pairings on the calling thread
+    pub fn verify_all(checks: &[Self]) -> bool {
+        phase_count!(
+            pairings,
+            checks.iter().map(|check| check.pairs.len()).sum::<usize>()
+        );
+        phase_count!(final_exponentiations, checks.len());
+        cfg_iter!(checks).all(|check| check._evaluate())
+    }
+
+    fn _evaluate(&self) -> bool {
+        let _check = phase_span!("pairing_check", pairs = self.pairs.len());
+        let (a, b): (Vec<_>, Vec<_>) = self.pairs.iter().cloned().unzip();
+        P::multi_pairing(P::G1::normalize_batch(&a), P::G2::normalize_batch(&b)).0 == self.target
+    }
+}
+
+// Commitments left once the verifier has folded the recursion, with the final commitment keys and
+// the base messages the proof claims open them
+pub struct TIPABase<IP, LMC, RMC, IPC>
+where
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    pub com: (LMC::Output, RMC::Output, IPC::Output),
+    pub ck: (LMC::Key, RMC::Key),
+    pub m: (LMC::Message, RMC::Message),
+    _inner_product: PhantomData<IP>,
+}
+
+impl<IP, LMC, RMC, IPC> TIPABase<IP, LMC, RMC, IPC>
+where
+    IP: InnerProduct<
+        LeftMessage = LMC::Message,
+        RightMessage = RMC::Message,
+        Output = IPC::Message,
+    >,
+    LMC: DoublyHomomorphicCommitment,
+    RMC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    // Checks the base messages open the commitments and the inner product commitment
+    pub fn verify(&self, ck_t: &IPC::Key) -> Result<bool, Error> {
+        let a_base = vec![self.m.0.clone()];
+        let b_base = vec![self.m.1.clone()];
         let t_base = vec![IP::inner_product(&a_base, &b_base)?];
-        let base_valid = LMC::verify(&vec![ck_a_final.clone()], &a_base, &com_a)?
-            && RMC::verify(&vec![ck_b_final.clone()], &b_base, &com_b)?
-            && IPC::verify(&vec![ck_t.clone()], &t_base, &com_t)?;
+        Ok(
+            LMC::verify(ark_std::slice::from_ref(&self.ck.0), &a_base, &self.com.0)?
+                && RMC::verify(ark_std::slice::from_ref(&self.ck.1), &b_base, &self.com.1)?
+                && IPC::verify(ark_std::slice::from_ref(ck_t), &t_base, &self.com.2)?,
+        )
+    }
+}
+
+// Pairing checks collected across a verification batch, e.g. of several proofs, which are combined
+// with random 128-bit coefficients so that the batch takes a single final exponentiation. The
+// Miller loops run in parallel and the powers of the targets share their squarings, which are
+// cyclotomic squarings valid only in GT. Targets are checked to be elements of GT first, as a
+// target outside of it fails its own check but could pass a batch
+#[derive(Clone)]
+pub struct PairingCheckBatch<P: Pairing> {
+    checks: Vec<PairingCheck<P>>,
+}
+
+impl<P: Pairing> Default for PairingCheckBatch<P> {
+    fn default() -> Self {
+        PairingCheckBatch { checks: Vec::new() }
+    }
+}
+
+impl<P: Pairing> PairingCheckBatch<P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, check: PairingCheck<P>) {
+        self.checks.push(check);
+    }
+
+    pub fn extend(&mut self, checks: impl IntoIterator<Item = PairingCheck<P>>) {
+        self.checks.extend(checks);
+    }
+
+    pub fn len(&self) -> usize {
+        self.checks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    pub fn verify<R: RngCore + CryptoRng>(&self, rng: &mut R) -> bool {
+        if self.checks.is_empty() {
+            return true;
+        }
+        let _check = phase_span!("pairing_check_batch", checks = self.checks.len());
+        phase_count!(
+            pairings,
+            self.checks
+                .iter()
+                .map(|check| check.pairs.len())
+                .sum::<usize>()
+        );
+        phase_count!(final_exponentiations, 1);
+
+        // Pairings are in GT, so a check with a target outside of it fails on its own
+        let in_gt = cfg_iter!(self.checks).all(|check| {
+            check.target.is_one()
+                || check.target.pow(P::ScalarField::characteristic()) == P::TargetField::one()
+        });
+        if !in_gt {
+            return false;
+        }
+
+        let coeffs = self
+            .checks
+            .iter()
+            .map(|_| [rng.next_u64(), rng.next_u64()])
+            .collect::<Vec<_>>();
+        let miller_loop = cfg_iter!(self.checks)
+            .zip(&coeffs)
+            .map(|(check, coeff)| {
+                let (a, b): (Vec<_>, Vec<_>) = check
+                    .pairs
+                    .iter()
+                    .map(|(a, b)| (a.mul_bigint(coeff), *b))
+                    .unzip();
+                P::multi_miller_loop(P::G1::normalize_batch(&a), P::G2::normalize_batch(&b)).0
+            })
+            .product::<P::TargetField>();
+
+        let powers = self
+            .checks
+            .iter()
+            .zip(&coeffs)
+            .map(|(check, coeff)| (check.target, *coeff))
+            .collect::<Vec<_>>();
+        #[cfg(feature = "parallel")]
+        let chunk_size = powers.len().div_ceil(rayon::current_num_threads());
+        #[cfg(not(feature = "parallel"))]
+        let chunk_size = powers.len();
+        let target = cfg_chunks!(powers, chunk_size)
+            .map(multi_pow_128)
+            .product::<P::TargetField>();
 
-        Ok(ck_a_valid && ck_b_valid && base_valid)
+        P::final_exponentiation(MillerLoopOutput(miller_loop)).is_some_and(|gt| gt.0 == target)
     }
 }
 
-pub fn prove_commitment_key_kzg_opening<G: ProjectiveCurve>(
-    srs_powers: &Vec<G>,
-    transcript: &Vec<G::ScalarField>,
+// Product of the bases raised to 128-bit exponents, with one chain of squarings for all of them.
+// Bases must be in the cyclotomic subgroup, e.g. elements of GT, whose squarings are cheaper than
+// in the full field. Where inverses are cheap, as by conjugation in GT, the exponents are taken in
+// non-adjacent form, which has a third of its digits nonzero instead of half
+pub(crate) fn multi_pow_128<F: CyclotomicMultSubgroup>(powers: &[(F, [u64; 2])]) -> F {
+    let mut result = F::one();
+    if !F::INVERSE_IS_FAST {
+        for i in (0..128).rev() {
+            result.cyclotomic_square_in_place();
+            for (base, exp) in powers {
+                if (exp[i / 64] >> (i % 64)) & 1 == 1 {
+                    result *= base;
+                }
+            }
+        }
+        return result;
+    }
+    // A third limb leaves room for the carry of the top digit
+    let signed = powers
+        .iter()
+        .map(|(base, exp)| {
+            let digits = BigInt::new([exp[0], exp[1], 0])
+                .find_wnaf(2)
+                .unwrap_or_default();
+            let inverse = base.cyclotomic_inverse().unwrap_or_else(F::zero);
+            (base, inverse, digits)
+        })
+        .collect::<Vec<_>>();
+    for i in (0..129).rev() {
+        result.cyclotomic_square_in_place();
+        for (base, inverse, digits) in &signed {
+            match digits.get(i) {
+                Some(1) => result *= *base,
+                Some(-1) => result *= inverse,
+                _ => {}
+            }
+        }
+    }
+    result
+}
+
+// Claim that the final commitment key, the KZG commitment to the polynomial defined by the
+// transcript, evaluates to `eval` at the challenge point. The polynomial is the product of
+// 1 + transcript_i (shift X^2)^(2^i), i.e. `transcript` holds the GIPA challenges for keys in G1
+// and their inverses for keys in G2
+#[derive(Clone)]
+pub struct CommitmentKeyOpening<G: CurveGroup> {
+    pub ck_final: G,
+    pub ck_opening: G,
+    pub kzg_challenge: G::ScalarField,
+    pub eval: G::ScalarField,
+    pub transcript: Vec<G::ScalarField>,
+    pub shift: G::ScalarField,
+}
+
+pub fn prove_commitment_key_kzg_opening<G: CurveGroup>(
+    srs_powers: &[G],
+    transcript: &[G::ScalarField],
     r_shift: &G::ScalarField,
     kzg_challenge: &G::ScalarField,
 ) -> Result<G, Error> {
@@ -310,71 +1164,124 @@ pub fn prove_commitment_key_kzg_opening<G: ProjectiveCurve>(
         &polynomial_coefficients_from_transcript(transcript, r_shift),
     );
     assert_eq!(srs_powers.len(), ck_polynomial.coeffs.len());
+    let _opening = phase_span!("final_ck_proof", len = srs_powers.len());
 
     let eval = start_timer!(|| "polynomial eval");
     let ck_polynomial_c_eval =
-        polynomial_evaluation_product_form_from_transcript(&transcript, kzg_challenge, &r_shift);
+        polynomial_evaluation_product_form_from_transcript(transcript, kzg_challenge, r_shift);
     end_timer!(eval);
 
     let quotient = start_timer!(|| "polynomial quotient");
     let quotient_polynomial = &(&ck_polynomial
         - &DensePolynomial::from_coefficients_vec(vec![ck_polynomial_c_eval]))
-        / &(DensePolynomial::from_coefficients_vec(vec![
-            -kzg_challenge.clone(),
-            <G::ScalarField>::one(),
-        ]));
+        / &(DensePolynomial::from_coefficients_vec(vec![-*kzg_challenge, <G::ScalarField>::one()]));
     end_timer!(quotient);
 
     let mut quotient_polynomial_coeffs = quotient_polynomial.coeffs;
     quotient_polynomial_coeffs.resize(srs_powers.len(), <G::ScalarField>::zero());
 
     let multiexp = start_timer!(|| "opening multiexp");
+    // Reported to the metrics hook by the inner product
+    #[cfg(feature = "tracing")]
+    tracing::debug!(monotonic_counter.msm_terms = srs_powers.len() as u64);
     let opening =
         MultiexponentiationInnerProduct::inner_product(srs_powers, &quotient_polynomial_coeffs);
     end_timer!(multiexp);
-    opening
+    Ok(opening?)
 }
 
 //TODO: Figure out how to avoid needing two separate methods for verification of opposite groups
-pub fn verify_commitment_key_g2_kzg_opening<P: PairingEngine>(
+pub fn verify_commitment_key_g2_kzg_opening<P: Pairing>(
+    v_srs: &VerifierSRS<P>,
+    ck_final: &P::G2,
+    ck_opening: &P::G2,
+    transcript: &[P::ScalarField],
+    r_shift: &P::ScalarField,
+    kzg_challenge: &P::ScalarField,
+) -> Result<bool, Error> {
+    Ok(commitment_key_g2_kzg_opening_check(
+        v_srs,
+        ck_final,
+        ck_opening,
+        transcript,
+        r_shift,
+        kzg_challenge,
+    )
+    .verify())
+}
+
+pub fn verify_commitment_key_g1_kzg_opening<P: Pairing>(
     v_srs: &VerifierSRS<P>,
-    ck_final: &P::G2Projective,
-    ck_opening: &P::G2Projective,
-    transcript: &Vec<P::Fr>,
-    r_shift: &P::Fr,
-    kzg_challenge: &P::Fr,
+    ck_final: &P::G1,
+    ck_opening: &P::G1,
+    transcript: &[P::ScalarField],
+    r_shift: &P::ScalarField,
+    kzg_challenge: &P::ScalarField,
 ) -> Result<bool, Error> {
+    Ok(commitment_key_g1_kzg_opening_check(
+        v_srs,
+        ck_final,
+        ck_opening,
+        transcript,
+        r_shift,
+        kzg_challenge,
+    )
+    .verify())
+}
+
+// e(g, ck_final - h^eval) = e(g^beta - g^c, ck_opening)
+pub fn commitment_key_g2_kzg_opening_check<P: Pairing>(
+    v_srs: &VerifierSRS<P>,
+    ck_final: &P::G2,
+    ck_opening: &P::G2,
+    transcript: &[P::ScalarField],
+    r_shift: &P::ScalarField,
+    kzg_challenge: &P::ScalarField,
+) -> PairingCheck<P> {
     let ck_polynomial_c_eval =
         polynomial_evaluation_product_form_from_transcript(transcript, kzg_challenge, r_shift);
-    Ok(P::pairing(
-        v_srs.g,
-        *ck_final - &v_srs.h.mul(ck_polynomial_c_eval.into_repr()),
-    ) == P::pairing(
-        v_srs.g_beta - &v_srs.g.mul(kzg_challenge.into_repr()),
-        *ck_opening,
-    ))
+    PairingCheck {
+        pairs: vec![
+            (
+                v_srs.g,
+                *ck_final - v_srs.h.mul_bigint(ck_polynomial_c_eval.into_bigint()),
+            ),
+            (
+                -(v_srs.g_beta - v_srs.g.mul_bigint(kzg_challenge.into_bigint())),
+                *ck_opening,
+            ),
+        ],
+        target: <P::TargetField>::one(),
+    }
 }
 
-pub fn verify_commitment_key_g1_kzg_opening<P: PairingEngine>(
+// e(ck_final - g^eval, h) = e(ck_opening, h^alpha - h^c)
+pub fn commitment_key_g1_kzg_opening_check<P: Pairing>(
     v_srs: &VerifierSRS<P>,
-    ck_final: &P::G1Projective,
-    ck_opening: &P::G1Projective,
-    transcript: &Vec<P::Fr>,
-    r_shift: &P::Fr,
-    kzg_challenge: &P::Fr,
-) -> Result<bool, Error> {
+    ck_final: &P::G1,
+    ck_opening: &P::G1,
+    transcript: &[P::ScalarField],
+    r_shift: &P::ScalarField,
+    kzg_challenge: &P::ScalarField,
+) -> PairingCheck<P> {
     let ck_polynomial_c_eval =
         polynomial_evaluation_product_form_from_transcript(transcript, kzg_challenge, r_shift);
-    Ok(P::pairing(
-        *ck_final - &v_srs.g.mul(ck_polynomial_c_eval.into_repr()),
-        v_srs.h,
-    ) == P::pairing(
-        *ck_opening,
-        v_srs.h_alpha - &v_srs.h.mul(kzg_challenge.into_repr()),
-    ))
+    PairingCheck {
+        pairs: vec![
+            (
+                *ck_final - v_srs.g.mul_bigint(ck_polynomial_c_eval.into_bigint()),
+                v_srs.h,
+            ),
+            (
+                -*ck_opening,
+                v_srs.h_alpha - v_srs.h.mul_bigint(kzg_challenge.into_bigint()),
+            ),
+        ],
+        target: <P::TargetField>::one(),
+    }
 }
 
-pub fn structured_generators_scalar_power<G: ProjectiveCurve>(
+pub fn structured_generators_scalar_power<G: CurveGroup>(
     num: usize,
     g: &G,
     s: &G::ScalarField,
@@ -387,42 +1294,41 @@ pub fn structured_generators_scalar_power<G: ProjectiveCurve>(
         pow_s *= s;
     }
 
-    let window_size = FixedBaseMSM::get_mul_window_size(num);
+    let window_size = FixedBase::get_mul_window_size(num);
 
-    let scalar_bits = G::ScalarField::size_in_bits();
-    let g_table = FixedBaseMSM::get_window_table(scalar_bits, window_size, g.clone());
-    let powers_of_g =
-        FixedBaseMSM::multi_scalar_mul::<G>(scalar_bits, window_size, &g_table, &powers_of_scalar);
-    powers_of_g
+    let scalar_bits = G::ScalarField::MODULUS_BIT_SIZE as usize;
+    let g_table = FixedBase::get_window_table(scalar_bits, window_size, *g);
+
+    FixedBase::msm::<G>(scalar_bits, window_size, &g_table, &powers_of_scalar)
 }
 
 fn polynomial_evaluation_product_form_from_transcript<F: Field>(
-    transcript: &Vec<F>,
+    transcript: &[F],
     z: &F,
     r_shift: &F,
 ) -> F {
-    let mut power_2_zr = (z.clone() * z) * r_shift;
+    let mut power_2_zr = (*z * z) * r_shift;
     let mut product_form = Vec::new();
     for x in transcript.iter() {
-        product_form.push(F::one() + (x.clone() * &power_2_zr));
+        product_form.push(F::one() + (*x * power_2_zr));
         power_2_zr *= power_2_zr;
     }
     product_form.iter().product()
 }
 
-fn polynomial_coefficients_from_transcript<F: Field>(transcript: &Vec<F>, r_shift: &F) -> Vec<F> {
+fn polynomial_coefficients_from_transcript<F: Field>(transcript: &[F], r_shift: &F) -> Vec<F> {
     let mut coefficients = vec![F::one()];
-    let mut power_2_r = r_shift.clone();
+    let mut power_2_r = *r_shift;
     for (i, x) in transcript.iter().enumerate() {
         for j in 0..(2_usize).pow(i as u32) {
-            coefficients.push(coefficients[j] * &(x.clone() * &power_2_r));
+            coefficients.push(coefficients[j] * (*x * power_2_r));
         }
         power_2_r *= power_2_r;
     }
     // Interleave with 0 coefficients
     coefficients
         .iter()
-        .interleave(vec![F::zero()].iter().cycle().take(coefficients.len() - 1))
+        .interleave([F::zero()].iter().cycle().take(coefficients.len() - 1))
         .cloned()
         .collect()
 }
@@ -431,7 +1337,7 @@ fn polynomial_coefficients_from_transcript<F: Field>(transcript: &Vec<F>, r_shif
 mod tests {
     use super::*;
     use ark_bls12_381::Bls12_381;
-    use blake2::Blake2b;
+    use blake2::Blake2b512;
     use rand::{rngs::StdRng, SeedableRng};
 
     use crate::tipa::structured_scalar_message::structured_scalar_power;
@@ -448,17 +1354,19 @@ mod tests {
 
     type GC1 = AFGHOCommitmentG1<Bls12_381>;
     type GC2 = AFGHOCommitmentG2<Bls12_381>;
-    type SC1 = PedersenCommitment<<Bls12_381 as PairingEngine>::G1Projective>;
-    type SC2 = PedersenCommitment<<Bls12_381 as PairingEngine>::G2Projective>;
+    type SC1 = PedersenCommitment<<Bls12_381 as Pairing>::G1>;
+    type SC2 = PedersenCommitment<<Bls12_381 as Pairing>::G2>;
 
     const TEST_SIZE: usize = 8;
 
     #[test]
     fn pairing_inner_product_test() {
         type IP = PairingInnerProduct<Bls12_381>;
-        type IPC =
-            IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>;
-        type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b>;
+        type IPC = IdentityCommitment<
+            ExtensionFieldElement<Bls12_381>,
+            <Bls12_381 as Pairing>::ScalarField,
+        >;
+        type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b512>;
 
         let mut rng = StdRng::seed_from_u64(0u64);
         let (srs, ck_t) = PairingTIPA::setup(&mut rng, TEST_SIZE).unwrap();
@@ -469,7 +1377,7 @@ mod tests {
         let com_a = GC1::commit(&ck_a, &m_a).unwrap();
         let com_b = GC2::commit(&ck_b, &m_b).unwrap();
         let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
-        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+        let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
 
         let proof = PairingTIPA::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
 
@@ -477,13 +1385,93 @@ mod tests {
     }
 
     #[test]
-    fn multiexponentiation_inner_product_test() {
-        type IP = MultiexponentiationInnerProduct<<Bls12_381 as PairingEngine>::G1Projective>;
+    fn pairing_check_batch_test() {
+        type IP = PairingInnerProduct<Bls12_381>;
         type IPC = IdentityCommitment<
-            <Bls12_381 as PairingEngine>::G1Projective,
-            <Bls12_381 as PairingEngine>::Fr,
+            ExtensionFieldElement<Bls12_381>,
+            <Bls12_381 as Pairing>::ScalarField,
         >;
-        type MultiExpTIPA = TIPA<IP, GC1, SC1, IPC, Bls12_381, Blake2b>;
+        type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b512>;
+
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = PairingTIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let mut instances = Vec::new();
+        for _ in 0..3 {
+            let m_a = random_generators(&mut rng, TEST_SIZE);
+            let m_b = random_generators(&mut rng, TEST_SIZE);
+            let com_a = GC1::commit(&ck_a, &m_a).unwrap();
+            let com_b = GC2::commit(&ck_b, &m_b).unwrap();
+            let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
+            let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
+            let proof = PairingTIPA::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+            instances.push(((com_a, com_b, com_t), proof));
+        }
+        let batch = instances
+            .iter()
+            .map(|((com_a, com_b, com_t), proof)| ((com_a, com_b, com_t), proof))
+            .collect::<Vec<_>>();
+        assert!(PairingTIPA::verify_batch(&v_srs, &ck_t, &batch, &mut rng).unwrap());
+
+        // A single failing pairing check fails the batch
+        let mut pairing_checks = PairingCheckBatch::new();
+        for ((com_a, com_b, com_t), proof) in &batch {
+            let (base_valid, checks) = PairingTIPA::verify_with_srs_shift_deferred(
+                &v_srs,
+                &ck_t,
+                (com_a, com_b, com_t),
+                proof,
+                &<Bls12_381 as Pairing>::ScalarField::one(),
+            )
+            .unwrap();
+            assert!(base_valid);
+            pairing_checks.extend(checks);
+        }
+        assert!(pairing_checks.verify(&mut rng));
+        let mut wrong_check = pairing_checks.checks[0].clone();
+        wrong_check.target.double_in_place();
+        pairing_checks.add(wrong_check);
+        assert!(!pairing_checks.verify(&mut rng));
+    }
+
+    #[test]
+    fn multi_pow_128_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let exps = [
+            [0, 0],
+            [1, 0],
+            [u64::MAX, u64::MAX],
+            [0xaaaa_aaaa_aaaa_aaaa, 0x5555_5555_5555_5555],
+            [rng.next_u64(), rng.next_u64()],
+        ];
+        let powers = exps
+            .iter()
+            .map(|exp| {
+                let base = <Bls12_381 as Pairing>::pairing(
+                    <Bls12_381 as Pairing>::G1::rand(&mut rng),
+                    <Bls12_381 as Pairing>::G2::rand(&mut rng),
+                );
+                (base.0, *exp)
+            })
+            .collect::<Vec<_>>();
+        let expected = powers
+            .iter()
+            .map(|(base, exp)| base.pow(exp))
+            .product::<<Bls12_381 as Pairing>::TargetField>();
+        assert_eq!(multi_pow_128(&powers), expected);
+        assert_eq!(
+            multi_pow_128(&powers[..1]),
+            <Bls12_381 as Pairing>::TargetField::one()
+        );
+    }
+
+    #[test]
+    fn multiexponentiation_inner_product_test() {
+        type IP = MultiexponentiationInnerProduct<<Bls12_381 as Pairing>::G1>;
+        type IPC =
+            IdentityCommitment<<Bls12_381 as Pairing>::G1, <Bls12_381 as Pairing>::ScalarField>;
+        type MultiExpTIPA = TIPA<IP, GC1, SC1, IPC, Bls12_381, Blake2b512>;
 
         let mut rng = StdRng::seed_from_u64(0u64);
         let (srs, ck_t) = MultiExpTIPA::setup(&mut rng, TEST_SIZE).unwrap();
@@ -492,12 +1480,12 @@ mod tests {
         let m_a = random_generators(&mut rng, TEST_SIZE);
         let mut m_b = Vec::new();
         for _ in 0..TEST_SIZE {
-            m_b.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
+            m_b.push(<Bls12_381 as Pairing>::ScalarField::rand(&mut rng));
         }
         let com_a = GC1::commit(&ck_a, &m_a).unwrap();
         let com_b = SC1::commit(&ck_b, &m_b).unwrap();
         let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
-        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+        let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
 
         let proof = MultiExpTIPA::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
 
@@ -506,10 +1494,12 @@ mod tests {
 
     #[test]
     fn scalar_inner_product_test() {
-        type IP = ScalarInnerProduct<<Bls12_381 as PairingEngine>::Fr>;
-        type IPC =
-            IdentityCommitment<<Bls12_381 as PairingEngine>::Fr, <Bls12_381 as PairingEngine>::Fr>;
-        type ScalarTIPA = TIPA<IP, SC2, SC1, IPC, Bls12_381, Blake2b>;
+        type IP = ScalarInnerProduct<<Bls12_381 as Pairing>::ScalarField>;
+        type IPC = IdentityCommitment<
+            <Bls12_381 as Pairing>::ScalarField,
+            <Bls12_381 as Pairing>::ScalarField,
+        >;
+        type ScalarTIPA = TIPA<IP, SC2, SC1, IPC, Bls12_381, Blake2b512>;
 
         let mut rng = StdRng::seed_from_u64(0u64);
         let (srs, ck_t) = ScalarTIPA::setup(&mut rng, TEST_SIZE).unwrap();
@@ -518,13 +1508,13 @@ mod tests {
         let mut m_a = Vec::new();
         let mut m_b = Vec::new();
         for _ in 0..TEST_SIZE {
-            m_a.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
-            m_b.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
+            m_a.push(<Bls12_381 as Pairing>::ScalarField::rand(&mut rng));
+            m_b.push(<Bls12_381 as Pairing>::ScalarField::rand(&mut rng));
         }
         let com_a = SC2::commit(&ck_a, &m_a).unwrap();
         let com_b = SC1::commit(&ck_b, &m_b).unwrap();
         let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
-        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+        let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
 
         let proof = ScalarTIPA::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
 
@@ -534,9 +1524,11 @@ mod tests {
     #[test]
     fn pairing_inner_product_with_srs_shift_test() {
         type IP = PairingInnerProduct<Bls12_381>;
-        type IPC =
-            IdentityCommitment<ExtensionFieldElement<Bls12_381>, <Bls12_381 as PairingEngine>::Fr>;
-        type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b>;
+        type IPC = IdentityCommitment<
+            ExtensionFieldElement<Bls12_381>,
+            <Bls12_381 as Pairing>::ScalarField,
+        >;
+        type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b512>;
 
         let mut rng = StdRng::seed_from_u64(0u64);
         let (srs, ck_t) = PairingTIPA::setup(&mut rng, TEST_SIZE).unwrap();
@@ -548,21 +1540,21 @@ mod tests {
         let com_a = GC1::commit(&ck_a, &m_a).unwrap();
         let com_b = GC2::commit(&ck_b, &m_b).unwrap();
 
-        let r_scalar = <<Bls12_381 as PairingEngine>::Fr>::rand(&mut rng);
+        let r_scalar = <<Bls12_381 as Pairing>::ScalarField>::rand(&mut rng);
         let r_vec = structured_scalar_power(TEST_SIZE, &r_scalar);
         let m_a_r = m_a
             .iter()
             .zip(&r_vec)
-            .map(|(a, r)| a.mul(r.into_repr()))
-            .collect::<Vec<<Bls12_381 as PairingEngine>::G1Projective>>();
+            .map(|(a, r)| a.mul_bigint(r.into_bigint()))
+            .collect::<Vec<<Bls12_381 as Pairing>::G1>>();
         let ck_a_r = ck_a
             .iter()
             .zip(&r_vec)
-            .map(|(ck, r)| ck.mul(&r.inverse().unwrap().into_repr()))
-            .collect::<Vec<<Bls12_381 as PairingEngine>::G2Projective>>();
+            .map(|(ck, r)| ck.mul_bigint(r.inverse().unwrap().into_bigint()))
+            .collect::<Vec<<Bls12_381 as Pairing>::G2>>();
 
         let t = vec![IP::inner_product(&m_a_r, &m_b).unwrap()];
-        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+        let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
 
         assert_eq!(com_a, IP::inner_product(&m_a_r, &ck_a_r).unwrap());
 
@@ -0,0 +1,268 @@
+use alloc::sync::Arc;
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_poly::polynomial::{univariate::DensePolynomial, DenseUVPolynomial};
+use ark_std::{marker::PhantomData, vec::Vec};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    tipa::{
+        polynomial_coefficients_from_transcript, prove_commitment_key_kzg_opening,
+        TIPACompatibleSetup,
+    },
+    Error, InnerProductArgumentError,
+};
+use ark_dh_commitments::{CommitmentError, DoublyHomomorphicCommitment};
+use ark_inner_products::{InnerProduct, MultiexponentiationInnerProduct};
+
+// Commitment key (g, g^{a^2}, g^{a^4}, ...) represented by a handle to the SRS powers
+// (g, g^a, g^{a^2}, ...) it is taken from, as returned by SRS::get_commitment_keys
+#[derive(Clone)]
+pub struct StructuredCommitmentKey<G: CurveGroup> {
+    srs_powers: Arc<Vec<G>>,
+}
+
+impl<G: CurveGroup> StructuredCommitmentKey<G> {
+    pub fn new(srs_powers: Arc<Vec<G>>) -> Result<Self, Error> {
+        if !(srs_powers.len() + 1).is_power_of_two() || srs_powers.len() < 3 {
+            return Err(InnerProductArgumentError::SrsLength(srs_powers.len()));
+        }
+        Ok(StructuredCommitmentKey { srs_powers })
+    }
+
+    pub fn len(&self) -> usize {
+        self.srs_powers.len().div_ceil(2)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn srs_powers(&self) -> &Vec<G> {
+        &self.srs_powers
+    }
+
+    pub fn key(&self) -> Vec<G> {
+        self.srs_powers.iter().step_by(2).cloned().collect()
+    }
+
+    // Folding the key with the GIPA challenges x_i and shift r yields the commitment under the SRS
+    // powers to prod_i (1 + x_i r^{2^i} X^{2^{i+1}}), whose coefficients are the challenge products
+    pub fn folding_polynomial(
+        transcript: &[G::ScalarField],
+        r_shift: &G::ScalarField,
+    ) -> DensePolynomial<G::ScalarField> {
+        DensePolynomial::from_coefficients_vec(polynomial_coefficients_from_transcript(
+            transcript, r_shift,
+        ))
+    }
+
+    pub fn final_key(
+        &self,
+        transcript: &[G::ScalarField],
+        r_shift: &G::ScalarField,
+    ) -> Result<G, Error> {
+        let coeffs = Self::folding_polynomial(transcript, r_shift).coeffs;
+        if coeffs.len() != self.srs_powers.len() {
+            return Err(InnerProductArgumentError::TranscriptKeyMismatch {
+                transcript: transcript.len(),
+                key: self.len(),
+            });
+        }
+        Ok(MultiexponentiationInnerProduct::inner_product(
+            &self.srs_powers,
+            &coeffs,
+        )?)
+    }
+
+    pub fn prove_final_key_opening(
+        &self,
+        transcript: &[G::ScalarField],
+        r_shift: &G::ScalarField,
+        kzg_challenge: &G::ScalarField,
+    ) -> Result<G, Error> {
+        prove_commitment_key_kzg_opening(&self.srs_powers, transcript, r_shift, kzg_challenge)
+    }
+}
+
+// Keys of the left and right messages of TIPA, as taken from the same SRS
+pub type StructuredCommitmentKeys<P> = (
+    StructuredCommitmentKey<<P as Pairing>::G2>,
+    StructuredCommitmentKey<<P as Pairing>::G1>,
+);
+
+// Commitment scheme C under a structured key, for use with TIPA
+#[derive(Clone)]
+pub struct StructuredKeyCommitment<C: DoublyHomomorphicCommitment> {
+    _commitment: PhantomData<C>,
+}
+
+impl<C> StructuredKeyCommitment<C>
+where
+    C: DoublyHomomorphicCommitment,
+    C::Key: CurveGroup<ScalarField = C::Scalar>,
+{
+    pub fn commit_structured(
+        k: &StructuredCommitmentKey<C::Key>,
+        m: &[C::Message],
+    ) -> Result<C::Output, Error> {
+        Ok(C::commit(&k.key(), m)?)
+    }
+}
+
+impl<C> DoublyHomomorphicCommitment for StructuredKeyCommitment<C>
+where
+    C: DoublyHomomorphicCommitment,
+    C::Key: CurveGroup<ScalarField = C::Scalar>,
+{
+    type Scalar = C::Scalar;
+    type Message = C::Message;
+    type Key = C::Key;
+    type Output = C::Output;
+
+    // Structured keys are taken from an SRS, see SRS::get_structured_commitment_keys, rather than
+    // sampled here with a trapdoor no one can use
+    fn setup<R: RngCore + CryptoRng>(
+        _rng: &mut R,
+        _size: usize,
+    ) -> Result<Vec<Self::Key>, CommitmentError> {
+        Err(CommitmentError::TrustedSetupRequired)
+    }
+
+    // The trapdoor of a structured key can not be derived from a public seed
+    fn setup_from_seed(_seed: &[u8], _size: usize) -> Result<Vec<Self::Key>, CommitmentError> {
+        Err(CommitmentError::TrustedSetupRequired)
+    }
+
+    fn commit(k: &[Self::Key], m: &[Self::Message]) -> Result<Self::Output, CommitmentError> {
+        C::commit(k, m)
+    }
+
+    fn validate_key(k: &[Self::Key]) -> Result<(), CommitmentError> {
+        C::validate_key(k)
+    }
+
+    fn commit_split(
+        k: &[Self::Key],
+        m: &[Self::Message],
+        split: usize,
+    ) -> Result<(Self::Output, Self::Output), CommitmentError> {
+        C::commit_split(k, m, split)
+    }
+
+    fn verify_batch<R: RngCore + CryptoRng>(
+        k: &[Self::Key],
+        openings: &[(Vec<Self::Message>, Self::Output)],
+        rng: &mut R,
+    ) -> Result<bool, CommitmentError> {
+        C::verify_batch(k, openings, rng)
+    }
+
+    fn commit_batch(
+        k: &[Self::Key],
+        ms: &[Vec<Self::Message>],
+    ) -> Result<Vec<Self::Output>, CommitmentError> {
+        C::commit_batch(k, ms)
+    }
+
+    fn fold_keys(k: &[Self::Key], c: &Self::Scalar) -> Result<Vec<Self::Key>, CommitmentError> {
+        C::fold_keys(k, c)
+    }
+
+    fn fold_keys_msm(
+        k: &[Self::Key],
+        challenge_products: &[Self::Scalar],
+    ) -> Result<Self::Key, CommitmentError> {
+        C::fold_keys_msm(k, challenge_products)
+    }
+}
+
+impl<C> TIPACompatibleSetup for StructuredKeyCommitment<C>
+where
+    C: DoublyHomomorphicCommitment,
+    C::Key: CurveGroup<ScalarField = C::Scalar>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gipa::GIPA, tipa::TIPA};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_dh_commitments::{
+        afgho16::{AFGHOCommitmentG1, AFGHOCommitmentG2},
+        identity::{IdentityCommitment, IdentityOutput},
+    };
+    use ark_ff::{Field, One, UniformRand};
+    use ark_inner_products::{ExtensionFieldElement, PairingInnerProduct};
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type IP = PairingInnerProduct<Bls12_381>;
+    type GC1 = StructuredKeyCommitment<AFGHOCommitmentG1<Bls12_381>>;
+    type GC2 = StructuredKeyCommitment<AFGHOCommitmentG2<Bls12_381>>;
+    type IPC = IdentityCommitment<ExtensionFieldElement<Bls12_381>, Fr>;
+    type PairingTIPA = TIPA<IP, GC1, GC2, IPC, Bls12_381, Blake2b512>;
+
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn structured_key_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = PairingTIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let v_srs = srs.get_verifier_key();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let (structured_ck_a, structured_ck_b) = srs.get_structured_commitment_keys().unwrap();
+        assert_eq!(structured_ck_a.len(), TEST_SIZE);
+        assert!(structured_ck_a.key() == ck_a);
+        assert!(structured_ck_b.key() == ck_b);
+
+        let m_a = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G1::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| <Bls12_381 as Pairing>::G2::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com_a = GC1::commit_structured(&structured_ck_a, &m_a).unwrap();
+        let com_b = GC2::commit_structured(&structured_ck_b, &m_b).unwrap();
+        let com_t = IdentityOutput(vec![IP::inner_product(&m_a, &m_b).unwrap()]);
+        let proof = PairingTIPA::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+        assert!(PairingTIPA::verify(&v_srs, &ck_t, (&com_a, &com_b, &com_t), &proof).unwrap());
+
+        // Final keys of the proof are the folding polynomials committed under the SRS powers
+        let (_, transcript) =
+            GIPA::<IP, GC1, GC2, IPC, Blake2b512>::verify_recursive_challenge_transcript(
+                (&com_a, &com_b, &com_t),
+                &proof.gipa_proof,
+            )
+            .unwrap();
+        let transcript_inverse: Vec<_> = transcript.iter().map(|x| x.inverse().unwrap()).collect();
+        let ck_a_final = structured_ck_a
+            .final_key(&transcript_inverse, &Fr::one())
+            .unwrap();
+        let ck_b_final = structured_ck_b.final_key(&transcript, &Fr::one()).unwrap();
+        assert!(proof.final_ck == (ck_a_final, ck_b_final));
+        assert!(structured_ck_b
+            .final_key(&transcript[1..], &Fr::one())
+            .is_err());
+
+        let c = Fr::rand(&mut rng);
+        let ck_b_opening = structured_ck_b
+            .prove_final_key_opening(&transcript, &Fr::one(), &c)
+            .unwrap();
+        assert!(crate::tipa::verify_commitment_key_g1_kzg_opening(
+            &v_srs,
+            &ck_b_final,
+            &ck_b_opening,
+            &transcript,
+            &Fr::one(),
+            &c,
+        )
+        .unwrap());
+
+        assert!(StructuredCommitmentKey::new(Arc::new(ck_a)).is_err());
+        assert!(matches!(
+            GC1::setup(&mut rng, TEST_SIZE),
+            Err(CommitmentError::TrustedSetupRequired)
+        ));
+    }
+}
@@ -1,19 +1,29 @@
-use ark_ec::{group::Group, PairingEngine, ProjectiveCurve};
-use ark_ff::{to_bytes, Field, One, PrimeField, UniformRand, Zero};
-use ark_std::{end_timer, start_timer};
-use digest::Digest;
-use rand::Rng;
-use std::{marker::PhantomData, ops::MulAssign};
+use ark_ec::{pairing::Pairing, Group};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Write,
+};
+use ark_std::marker::PhantomData;
+use ark_std::{end_timer, start_timer, vec, vec::Vec};
+use rand::{CryptoRng, RngCore};
 
 use crate::{
-    gipa::{GIPAProof, GIPA},
+    gipa::{GIPAAux, GIPAProof, GIPAProverState, GIPA},
+    limits::limits,
     tipa::{
-        prove_commitment_key_kzg_opening, structured_generators_scalar_power,
-        verify_commitment_key_g2_kzg_opening, TIPACompatibleSetup, VerifierSRS, SRS,
+        commitment_key_g2_kzg_opening_check, polynomial_evaluation_product_form_from_transcript,
+        prove_commitment_key_kzg_opening, structured_generators_scalar_power, CommitmentKeyOpening,
+        PairingCheck, TIPACompatibleSetup, VerifierSRS, SRS,
     },
-    Error,
+    transcript::{ChallengeInput, SharedTranscript, TranscriptDigest},
+    validation::Validate,
+    Error, InnerProductArgumentError,
+};
+use ark_dh_commitments::{
+    identity::HomomorphicPlaceholderValue, CommitmentError, DoublyHomomorphicCommitment,
 };
-use ark_dh_commitments::{identity::HomomorphicPlaceholderValue, DoublyHomomorphicCommitment};
+#[cfg(feature = "serde")]
+use ark_inner_products::impl_canonical_serde;
 use ark_inner_products::InnerProduct;
 
 //TODO: Properly generalize the non-committed message approach of SIPP and MIPP to GIPA
@@ -32,49 +42,68 @@ impl<F: PrimeField> DoublyHomomorphicCommitment for SSMPlaceholderCommitment<F>
     type Key = HomomorphicPlaceholderValue;
     type Output = F;
 
-    fn setup<R: Rng>(_rng: &mut R, size: usize) -> Result<Vec<Self::Key>, Error> {
+    fn setup<R: RngCore + CryptoRng>(
+        _rng: &mut R,
+        size: usize,
+    ) -> Result<Vec<Self::Key>, CommitmentError> {
+        Ok(vec![HomomorphicPlaceholderValue {}; size])
+    }
+
+    fn setup_from_seed(_seed: &[u8], size: usize) -> Result<Vec<Self::Key>, CommitmentError> {
         Ok(vec![HomomorphicPlaceholderValue {}; size])
     }
 
     //TODO: Doesn't include message which means scalar b not included in generating challenges
-    fn commit(_k: &[Self::Key], _m: &[Self::Message]) -> Result<Self::Output, Error> {
+    fn commit(_k: &[Self::Key], _m: &[Self::Message]) -> Result<Self::Output, CommitmentError> {
         Ok(F::zero())
     }
 }
 
 pub struct GIPAWithSSM<IP, LMC, IPC, D> {
-    _inner_product: PhantomData<IP>,
-    _left_commitment: PhantomData<LMC>,
-    _inner_product_commitment: PhantomData<IPC>,
-    _digest: PhantomData<D>,
+    _inner_product: PhantomData<fn() -> IP>,
+    _left_commitment: PhantomData<fn() -> LMC>,
+    _inner_product_commitment: PhantomData<fn() -> IPC>,
+    _digest: PhantomData<fn() -> D>,
 }
 
 impl<IP, LMC, IPC, D> GIPAWithSSM<IP, LMC, IPC, D>
 where
-    D: Digest,
+    D: TranscriptDigest,
     IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = LMC::Scalar, Output = IPC::Message>,
     LMC: DoublyHomomorphicCommitment,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    IPC::Message: MulAssign<LMC::Scalar>,
-    IPC::Key: MulAssign<LMC::Scalar>,
-    IPC::Output: MulAssign<LMC::Scalar>,
 {
-    pub fn setup<R: Rng>(rng: &mut R, size: usize) -> Result<(Vec<LMC::Key>, IPC::Key), Error> {
+    pub fn setup<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        size: usize,
+    ) -> Result<(Vec<LMC::Key>, IPC::Key), Error> {
+        limits().check_vector_len(size)?;
         Ok((LMC::setup(rng, size)?, IPC::setup(rng, 1)?.pop().unwrap()))
     }
 
     pub fn prove_with_structured_scalar_message(
         values: (&[IP::LeftMessage], &[IP::RightMessage]),
         ck: (&[LMC::Key], &IPC::Key),
+    ) -> Result<GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>, Error> {
+        Self::prove_with_structured_scalar_message_and_seed(values, ck, &LMC::Scalar::default())
+    }
+
+    // Seeds the first challenge, e.g. with a hash of scalar b and the rest of the statement, as the
+    // placeholder commitment does not bind scalar b
+    pub fn prove_with_structured_scalar_message_and_seed(
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &IPC::Key),
+        seed: &LMC::Scalar,
     ) -> Result<GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>, Error> {
         let (proof, _) =
-            <GIPA<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>>::prove_with_aux(
+            <GIPA<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>>::prove_with_aux_and_seed(
                 values,
                 (
                     ck.0,
                     &vec![HomomorphicPlaceholderValue {}; values.1.len()],
-                    &vec![ck.1.clone()],
+                    ark_std::slice::from_ref(ck.1),
                 ),
+                seed,
             )?;
         Ok(proof)
     }
@@ -85,119 +114,306 @@ where
         scalar_b: &LMC::Scalar,
         proof: &GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>,
     ) -> Result<bool, Error> {
+        Self::verify_with_tensor_scalar_message(
+            ck,
+            com,
+            &Self::structured_factors(scalar_b, proof.r_commitment_steps.len()),
+            proof,
+        )
+    }
+
+    pub fn verify_with_structured_scalar_message_and_seed(
+        ck: (&[LMC::Key], &IPC::Key),
+        com: (&LMC::Output, &IPC::Output),
+        scalar_b: &LMC::Scalar,
+        proof: &GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>,
+        seed: &LMC::Scalar,
+    ) -> Result<bool, Error> {
+        Self::_verify_with_tensor_scalar_message(
+            ck,
+            com,
+            &Self::structured_factors(scalar_b, proof.r_commitment_steps.len()),
+            proof,
+            seed,
+        )
+    }
+
+    // Powers of b are the tensor product of (1, b^(2^i))
+    fn structured_factors(
+        scalar_b: &LMC::Scalar,
+        rounds: usize,
+    ) -> Vec<(LMC::Scalar, LMC::Scalar)> {
+        let mut power_2_b = *scalar_b;
+        let mut factors = Vec::new();
+        for _ in 0..rounds {
+            factors.push((<LMC::Scalar>::one(), power_2_b));
+            power_2_b *= &power_2_b.clone();
+        }
+        factors
+    }
+
+    // Scalar message is the tensor product of the factors: the entry at index j is the product of
+    // factors[i].0 if bit i of j is unset and factors[i].1 otherwise
+    pub fn verify_with_tensor_scalar_message(
+        ck: (&[LMC::Key], &IPC::Key),
+        com: (&LMC::Output, &IPC::Output),
+        factors: &[(LMC::Scalar, LMC::Scalar)],
+        proof: &GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>,
+    ) -> Result<bool, Error> {
+        //TODO: Scalar b not included in generating challenges outside of the seeded variant
+        Self::_verify_with_tensor_scalar_message(ck, com, factors, proof, &LMC::Scalar::default())
+    }
+
+    fn _verify_with_tensor_scalar_message(
+        ck: (&[LMC::Key], &IPC::Key),
+        com: (&LMC::Output, &IPC::Output),
+        factors: &[(LMC::Scalar, LMC::Scalar)],
+        proof: &GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>,
+        seed: &LMC::Scalar,
+    ) -> Result<bool, Error> {
+        if factors.len() != proof.r_commitment_steps.len() {
+            return Ok(false);
+        }
         // Calculate base commitments and recursive transcript
-        //TODO: Scalar b not included in generating challenges
-        let (base_com, transcript) = GIPA::verify_recursive_challenge_transcript(
+        let (base_com, transcript) = GIPA::verify_recursive_challenge_transcript_with_seed(
             (com.0, &LMC::Scalar::zero(), com.1),
             proof,
+            seed,
         )?;
         // Calculate base commitment keys
         let (ck_a_base, ck_b_base) = GIPA::<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>::_compute_final_commitment_keys(
-            (&ck.0, &vec![HomomorphicPlaceholderValue {}; ck.0.len()], &ck.1),
+            (ck.0, &vec![HomomorphicPlaceholderValue {}; ck.0.len()], ck.1),
             &transcript,
         )?;
         // Verify base commitment
-        let gipa_valid = GIPA::_verify_base_commitment(
+        let gipa_valid = GIPA::<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>::_verify_base_commitment(
             (&ck_a_base, &ck_b_base, &vec![ck.1.clone()]),
             base_com.clone(),
-            proof,
+            &proof.r_base,
         )?;
 
         // Compute final scalar
-        let mut power_2_b = scalar_b.clone();
-        let mut product_form = Vec::new();
-        for x in transcript.iter() {
-            product_form.push(<LMC::Scalar>::one() + &(x.inverse().unwrap() * &power_2_b));
-            power_2_b *= &power_2_b.clone();
-        }
-        let b_base = product_form.iter().product::<LMC::Scalar>();
+        let b_base = transcript
+            .iter()
+            .zip(factors)
+            .map(|(x, (b_0, b_1))| *b_0 + (x.inverse().unwrap() * b_1))
+            .product::<LMC::Scalar>();
 
         // Verify base inner product commitment
         let (com_a, _, com_t) = base_com;
         let a_base = vec![proof.r_base.0.clone()];
-        let t_base = vec![IP::inner_product(&a_base, &vec![b_base])?];
-        let base_valid = LMC::verify(&vec![ck_a_base.clone()], &a_base, &com_a)?
-            && IPC::verify(&vec![ck.1.clone()], &t_base, &com_t)?;
+        let t_base = vec![IP::inner_product(&a_base, &[b_base])?];
+        // The scalar sent by the prover is redundant, but must not be malleable
+        let base_valid = proof.r_base.1 == b_base
+            && LMC::verify(ark_std::slice::from_ref(&ck_a_base), &a_base, &com_a)?
+            && IPC::verify(ark_std::slice::from_ref(ck.1), &t_base, &com_t)?;
 
         Ok(gipa_valid && base_valid)
     }
 }
 
-pub struct TIPAWithSSM<IP, LMC, IPC, P, D> {
+// Commitments left once the verifier has folded the recursion, with the final commitment key, the
+// base message the proof claims opens it and the folded structured scalar
+pub struct TIPAWithSSMBase<IP, LMC, IPC>
+where
+    IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = LMC::Scalar, Output = IPC::Message>,
+    LMC: DoublyHomomorphicCommitment,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    pub com: (LMC::Output, IPC::Output),
+    pub ck: LMC::Key,
+    pub m: (LMC::Message, LMC::Scalar),
+    // Folded scalar sent in the proof, redundant with `m.1`
+    claimed_scalar: LMC::Scalar,
     _inner_product: PhantomData<IP>,
-    _left_commitment: PhantomData<LMC>,
-    _inner_product_commitment: PhantomData<IPC>,
-    _pair: PhantomData<P>,
-    _digest: PhantomData<D>,
+}
+
+impl<IP, LMC, IPC> TIPAWithSSMBase<IP, LMC, IPC>
+where
+    IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = LMC::Scalar, Output = IPC::Message>,
+    LMC: DoublyHomomorphicCommitment,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+{
+    // Checks the proof sent the folded scalar, so that it is not malleable
+    pub fn scalar_valid(&self) -> bool {
+        self.claimed_scalar == self.m.1
+    }
+
+    // Checks the base message opens the commitment and the inner product commitment
+    pub fn verify(&self, ck_t: &IPC::Key) -> Result<bool, Error> {
+        let a_base = vec![self.m.0.clone()];
+        let t_base = vec![IP::inner_product(&a_base, &[self.m.1])?];
+        Ok(self.scalar_valid()
+            && LMC::verify(ark_std::slice::from_ref(&self.ck), &a_base, &self.com.0)?
+            && IPC::verify(ark_std::slice::from_ref(ck_t), &t_base, &self.com.1)?)
+    }
+}
+
+pub struct TIPAWithSSM<IP, LMC, IPC, P, D> {
+    _inner_product: PhantomData<fn() -> IP>,
+    _left_commitment: PhantomData<fn() -> LMC>,
+    _inner_product_commitment: PhantomData<fn() -> IPC>,
+    _pair: PhantomData<fn() -> P>,
+    _digest: PhantomData<fn() -> D>,
 }
 
 pub struct TIPAWithSSMProof<IP, LMC, IPC, P, D>
 where
-    D: Digest,
-    P: PairingEngine,
+    D: TranscriptDigest,
+    P: Pairing,
     IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = LMC::Scalar, Output = IPC::Message>,
-    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective> + TIPACompatibleSetup,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::ScalarField, Key = P::G2> + TIPACompatibleSetup,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    IPC::Message: MulAssign<LMC::Scalar>,
-    IPC::Key: MulAssign<LMC::Scalar>,
-    IPC::Output: MulAssign<LMC::Scalar>,
-    LMC::Message: MulAssign<P::Fr>,
-    LMC::Output: MulAssign<P::Fr>,
 {
-    gipa_proof: GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>,
+    pub(crate) gipa_proof: GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>,
     final_ck: LMC::Key,
-    final_ck_proof: P::G2Projective,
+    final_ck_proof: P::G2,
     _pairing: PhantomData<P>,
 }
 
 impl<IP, LMC, IPC, P, D> Clone for TIPAWithSSMProof<IP, LMC, IPC, P, D>
 where
-    D: Digest,
-    P: PairingEngine,
+    D: TranscriptDigest,
+    P: Pairing,
     IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = LMC::Scalar, Output = IPC::Message>,
-    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective> + TIPACompatibleSetup,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::ScalarField, Key = P::G2> + TIPACompatibleSetup,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    IPC::Message: MulAssign<LMC::Scalar>,
-    IPC::Key: MulAssign<LMC::Scalar>,
-    IPC::Output: MulAssign<LMC::Scalar>,
-    LMC::Message: MulAssign<P::Fr>,
-    LMC::Output: MulAssign<P::Fr>,
 {
     fn clone(&self) -> Self {
         Self {
             gipa_proof: self.gipa_proof.clone(),
-            final_ck: self.final_ck.clone(),
-            final_ck_proof: self.final_ck_proof.clone(),
+            final_ck: self.final_ck,
+            final_ck_proof: self.final_ck_proof,
             _pairing: PhantomData,
         }
     }
 }
 
+impl<IP, LMC, IPC, P, D> CanonicalSerialize for TIPAWithSSMProof<IP, LMC, IPC, P, D>
+where
+    D: TranscriptDigest,
+    P: Pairing,
+    IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = LMC::Scalar, Output = IPC::Message>,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::ScalarField, Key = P::G2> + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>: CanonicalSerialize,
+{
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.gipa_proof.serialize_with_mode(&mut writer, compress)?;
+        self.final_ck.serialize_with_mode(&mut writer, compress)?;
+        self.final_ck_proof
+            .serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.gipa_proof.serialized_size(compress)
+            + self.final_ck.serialized_size(compress)
+            + self.final_ck_proof.serialized_size(compress)
+    }
+}
+
+impl<IP, LMC, IPC, P, D> Valid for TIPAWithSSMProof<IP, LMC, IPC, P, D>
+where
+    D: TranscriptDigest,
+    P: Pairing,
+    IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = LMC::Scalar, Output = IPC::Message>,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::ScalarField, Key = P::G2> + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>: CanonicalDeserialize,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        self.gipa_proof.check()?;
+        self.final_ck.check()?;
+        self.final_ck_proof.check()
+    }
+}
+
+impl<IP, LMC, IPC, P, D> CanonicalDeserialize for TIPAWithSSMProof<IP, LMC, IPC, P, D>
+where
+    D: TranscriptDigest,
+    P: Pairing,
+    IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = LMC::Scalar, Output = IPC::Message>,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::ScalarField, Key = P::G2> + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>: CanonicalDeserialize,
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(TIPAWithSSMProof {
+            gipa_proof: CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+            final_ck: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            final_ck_proof: CanonicalDeserialize::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+            _pairing: PhantomData,
+        })
+    }
+}
+
+impl<IP, LMC, IPC, P, D> Validate for TIPAWithSSMProof<IP, LMC, IPC, P, D>
+where
+    D: TranscriptDigest,
+    P: Pairing,
+    IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = LMC::Scalar, Output = IPC::Message>,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::ScalarField, Key = P::G2> + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+    GIPAProof<IP, LMC, SSMPlaceholderCommitment<LMC::Scalar>, IPC, D>: Validate,
+    P::G2: Validate,
+{
+    fn validate(&self) -> Result<(), Error> {
+        self.gipa_proof.validate()?;
+        self.final_ck.validate()?;
+        self.final_ck_proof.validate()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl_canonical_serde!([IP, LMC, IPC, P, D] TIPAWithSSMProof<IP, LMC, IPC, P, D>
+where
+    D: TranscriptDigest,
+    P: Pairing,
+    IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = LMC::Scalar, Output = IPC::Message>,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::ScalarField, Key = P::G2> + TIPACompatibleSetup,
+    IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
+);
+
 impl<IP, LMC, IPC, P, D> TIPAWithSSM<IP, LMC, IPC, P, D>
 where
-    D: Digest,
-    P: PairingEngine,
+    D: TranscriptDigest,
+    P: Pairing,
     IP: InnerProduct<LeftMessage = LMC::Message, RightMessage = LMC::Scalar, Output = IPC::Message>,
-    LMC: DoublyHomomorphicCommitment<Scalar = P::Fr, Key = P::G2Projective> + TIPACompatibleSetup,
+    LMC: DoublyHomomorphicCommitment<Scalar = P::ScalarField, Key = P::G2> + TIPACompatibleSetup,
     IPC: DoublyHomomorphicCommitment<Scalar = LMC::Scalar>,
-    IPC::Message: MulAssign<P::Fr>,
-    IPC::Key: MulAssign<P::Fr>,
-    IPC::Output: MulAssign<P::Fr>,
-    LMC::Message: MulAssign<P::Fr>,
-    LMC::Output: MulAssign<P::Fr>,
 {
     //TODO: Don't need full TIPA SRS since only using one set of powers
-    pub fn setup<R: Rng>(rng: &mut R, size: usize) -> Result<(SRS<P>, IPC::Key), Error> {
-        let alpha = <P::Fr>::rand(rng);
-        let beta = <P::Fr>::rand(rng);
-        let g = <P::G1Projective>::prime_subgroup_generator();
-        let h = <P::G2Projective>::prime_subgroup_generator();
+    pub fn setup<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        size: usize,
+    ) -> Result<(SRS<P>, IPC::Key), Error> {
+        limits().check_srs_size(2 * size - 1)?;
+        let alpha = <P::ScalarField>::rand(rng);
+        let beta = <P::ScalarField>::rand(rng);
+        let g = <P::G1>::generator();
+        let h = <P::G2>::generator();
         Ok((
             SRS {
                 g_alpha_powers: structured_generators_scalar_power(2 * size - 1, &g, &alpha),
                 h_beta_powers: structured_generators_scalar_power(2 * size - 1, &h, &beta),
-                g_beta: <P::G1Projective as Group>::mul(&g, &beta),
-                h_alpha: <P::G2Projective as Group>::mul(&h, &alpha),
+                g_beta: g * beta,
+                h_alpha: h * alpha,
             },
             IPC::setup(rng, 1)?.pop().unwrap(),
         ))
@@ -208,33 +424,98 @@ where
         values: (&[IP::LeftMessage], &[IP::RightMessage]),
         ck: (&[LMC::Key], &IPC::Key),
     ) -> Result<TIPAWithSSMProof<IP, LMC, IPC, P, D>, Error> {
+        let state =
+            <GIPA<IP, LMC, SSMPlaceholderCommitment<P::ScalarField>, IPC, D>>::prover_state(
+                values,
+                (
+                    ck.0,
+                    &vec![HomomorphicPlaceholderValue {}; values.1.len()],
+                    ark_std::slice::from_ref(ck.1),
+                ),
+            )?;
+        Self::prove_with_structured_scalar_message_from_state(srs, state, |_| Ok::<_, Error>(()))
+    }
+
+    // Runs the prover from a GIPA prover state, e.g. one restored from a checkpoint, passing the
+    // state after each GIPA round to `checkpoint`
+    pub fn prove_with_structured_scalar_message_from_state<E: From<Error>>(
+        srs: &SRS<P>,
+        state: GIPAProverState<IP, LMC, SSMPlaceholderCommitment<P::ScalarField>, IPC, D>,
+        checkpoint: impl FnMut(
+            &GIPAProverState<IP, LMC, SSMPlaceholderCommitment<P::ScalarField>, IPC, D>,
+        ) -> Result<(), E>,
+    ) -> Result<TIPAWithSSMProof<IP, LMC, IPC, P, D>, E> {
+        let _prove = phase_span!("tipa_ssm_prove", len = state.len());
         // Run GIPA
         let gipa = start_timer!(|| "GIPA");
         let (proof, aux) =
-            <GIPA<IP, LMC, SSMPlaceholderCommitment<P::Fr>, IPC, D>>::prove_with_aux(
+            <GIPA<IP, LMC, SSMPlaceholderCommitment<P::ScalarField>, IPC, D>>::prove_from_state(
+                state, checkpoint,
+            )?;
+        end_timer!(gipa);
+        Ok(Self::_prove_final_ck(srs, proof, aux)?)
+    }
+
+    // Proves as a sub-protocol of a composite argument: the statement `com` and `scalar_b` are
+    // absorbed into `transcript` and seed the first GIPA challenge, and the proof is absorbed after
+    pub fn prove_in_transcript(
+        srs: &SRS<P>,
+        values: (&[IP::LeftMessage], &[IP::RightMessage]),
+        ck: (&[LMC::Key], &IPC::Key),
+        com: (&LMC::Output, &IPC::Output),
+        scalar_b: &P::ScalarField,
+        transcript: &mut SharedTranscript<D>,
+    ) -> Result<TIPAWithSSMProof<IP, LMC, IPC, P, D>, Error>
+    where
+        TIPAWithSSMProof<IP, LMC, IPC, P, D>: CanonicalSerialize,
+    {
+        let seed = GIPA::<IP, LMC, SSMPlaceholderCommitment<P::ScalarField>, IPC, D>::seed_from_transcript(
+            (com.0, scalar_b, com.1),
+            transcript,
+        )?;
+        let state =
+            <GIPA<IP, LMC, SSMPlaceholderCommitment<P::ScalarField>, IPC, D>>::prover_state(
                 values,
                 (
                     ck.0,
                     &vec![HomomorphicPlaceholderValue {}; values.1.len()],
-                    &vec![ck.1.clone()],
+                    ark_std::slice::from_ref(ck.1),
                 ),
-            )?;
-        end_timer!(gipa);
+            )?
+            .with_seed(seed);
+        let proof = Self::prove_with_structured_scalar_message_from_state(srs, state, |_| {
+            Ok::<_, Error>(())
+        })?;
+        transcript.absorb(b"tipa-ssm-proof", &proof)?;
+        Ok(proof)
+    }
 
+    fn _prove_final_ck(
+        srs: &SRS<P>,
+        proof: GIPAProof<IP, LMC, SSMPlaceholderCommitment<P::ScalarField>, IPC, D>,
+        aux: GIPAAux<IP, LMC, SSMPlaceholderCommitment<P::ScalarField>, IPC, D>,
+    ) -> Result<TIPAWithSSMProof<IP, LMC, IPC, P, D>, Error> {
         // Prove final commitment key is wellformed
         let ck_kzg = start_timer!(|| "Prove commitment key");
         let (ck_a_final, _) = aux.ck_base;
         let transcript = aux.r_transcript;
-        let transcript_inverse = transcript.iter().map(|x| x.inverse().unwrap()).collect();
+        let transcript_inverse = transcript
+            .iter()
+            .map(|x| x.inverse().ok_or(InnerProductArgumentError::ZeroChallenge))
+            .collect::<Result<Vec<_>, Error>>()?;
 
         // KZG challenge point
+        let mut input = D::input();
+        input.absorb_scalar(
+            transcript
+                .first()
+                .ok_or(InnerProductArgumentError::EmptyTranscript)?,
+        )?;
+        input.absorb(&ck_a_final)?;
         let mut counter_nonce: usize = 0;
         let c = loop {
-            let mut hash_input = Vec::new();
-            hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-            //TODO: Should use CanonicalSerialize instead of ToBytes
-            hash_input.extend_from_slice(&to_bytes![transcript.first().unwrap(), ck_a_final]?);
-            if let Some(c) = LMC::Scalar::from_random_bytes(&D::digest(&hash_input)) {
+            if let Some(c) = input.challenge::<LMC::Scalar>(counter_nonce) {
+                transcript_log!("tipa-ssm", None, b"kzg-point", &c, nonce = counter_nonce);
                 break c;
             };
             counter_nonce += 1;
@@ -244,7 +525,7 @@ where
         let ck_a_kzg_opening = prove_commitment_key_kzg_opening(
             &srs.h_beta_powers,
             &transcript_inverse,
-            &<P::Fr>::one(),
+            &<P::ScalarField>::one(),
             &c,
         )?;
         end_timer!(ck_kzg);
@@ -261,58 +542,202 @@ where
         v_srs: &VerifierSRS<P>,
         ck_t: &IPC::Key,
         com: (&LMC::Output, &IPC::Output),
-        scalar_b: &P::Fr,
+        scalar_b: &P::ScalarField,
         proof: &TIPAWithSSMProof<IP, LMC, IPC, P, D>,
     ) -> Result<bool, Error> {
-        let (base_com, transcript) = GIPA::verify_recursive_challenge_transcript(
+        let _verify = phase_span!("tipa_ssm_verify");
+        let (base_valid, pairing_checks) = Self::verify_with_structured_scalar_message_deferred(
+            v_srs, ck_t, com, scalar_b, proof,
+        )?;
+        Ok(base_valid && PairingCheck::verify_all(&pairing_checks))
+    }
+
+    pub fn verify_in_transcript(
+        v_srs: &VerifierSRS<P>,
+        ck_t: &IPC::Key,
+        com: (&LMC::Output, &IPC::Output),
+        scalar_b: &P::ScalarField,
+        proof: &TIPAWithSSMProof<IP, LMC, IPC, P, D>,
+        transcript: &mut SharedTranscript<D>,
+    ) -> Result<bool, Error>
+    where
+        TIPAWithSSMProof<IP, LMC, IPC, P, D>: CanonicalSerialize,
+    {
+        let seed = GIPA::<IP, LMC, SSMPlaceholderCommitment<P::ScalarField>, IPC, D>::seed_from_transcript(
+            (com.0, scalar_b, com.1),
+            transcript,
+        )?;
+        transcript.absorb(b"tipa-ssm-proof", proof)?;
+        let (base_valid, pairing_checks) =
+            Self::_verify_deferred(v_srs, ck_t, com, scalar_b, proof, &seed)?;
+        Ok(base_valid && PairingCheck::verify_all(&pairing_checks))
+    }
+
+    // Runs all verifier checks except the commitment key opening, which is returned unevaluated.
+    // The base commitment is still checked, with pairings for pairing-based commitments
+    pub fn verify_with_structured_scalar_message_deferred(
+        v_srs: &VerifierSRS<P>,
+        ck_t: &IPC::Key,
+        com: (&LMC::Output, &IPC::Output),
+        scalar_b: &P::ScalarField,
+        proof: &TIPAWithSSMProof<IP, LMC, IPC, P, D>,
+    ) -> Result<(bool, Vec<PairingCheck<P>>), Error> {
+        Self::_verify_deferred(
+            v_srs,
+            ck_t,
+            com,
+            scalar_b,
+            proof,
+            &<P::ScalarField>::default(),
+        )
+    }
+
+    // As `verify_with_structured_scalar_message_deferred`, also leaving the base commitment
+    // unchecked for the caller, for a proof whose first challenge was seeded with `seed`
+    pub fn verify_with_structured_scalar_message_base_deferred(
+        v_srs: &VerifierSRS<P>,
+        com: (&LMC::Output, &IPC::Output),
+        scalar_b: &P::ScalarField,
+        proof: &TIPAWithSSMProof<IP, LMC, IPC, P, D>,
+        seed: &P::ScalarField,
+    ) -> Result<(TIPAWithSSMBase<IP, LMC, IPC>, Vec<PairingCheck<P>>), Error> {
+        Self::_verify_base_deferred(v_srs, com, scalar_b, proof, seed)
+    }
+
+    fn _verify_deferred(
+        v_srs: &VerifierSRS<P>,
+        ck_t: &IPC::Key,
+        com: (&LMC::Output, &IPC::Output),
+        scalar_b: &P::ScalarField,
+        proof: &TIPAWithSSMProof<IP, LMC, IPC, P, D>,
+        seed: &P::ScalarField,
+    ) -> Result<(bool, Vec<PairingCheck<P>>), Error> {
+        let (base, pairing_checks) =
+            Self::_verify_base_deferred(v_srs, com, scalar_b, proof, seed)?;
+        Ok((base.verify(ck_t)?, pairing_checks))
+    }
+
+    fn _verify_base_deferred(
+        v_srs: &VerifierSRS<P>,
+        com: (&LMC::Output, &IPC::Output),
+        scalar_b: &P::ScalarField,
+        proof: &TIPAWithSSMProof<IP, LMC, IPC, P, D>,
+        seed: &P::ScalarField,
+    ) -> Result<(TIPAWithSSMBase<IP, LMC, IPC>, Vec<PairingCheck<P>>), Error> {
+        let (base_com, transcript) = GIPA::verify_recursive_challenge_transcript_with_seed(
             (com.0, scalar_b, com.1),
             &proof.gipa_proof,
+            seed,
         )?;
-        let transcript_inverse = transcript.iter().map(|x| x.inverse().unwrap()).collect();
+        let transcript_inverse = transcript
+            .iter()
+            .map(|x| x.inverse().ok_or(InnerProductArgumentError::ZeroChallenge))
+            .collect::<Result<Vec<_>, Error>>()?;
 
         let ck_a_final = &proof.final_ck;
         let ck_a_proof = &proof.final_ck_proof;
 
         // KZG challenge point
+        let mut input = D::input();
+        input.absorb_scalar(
+            transcript
+                .first()
+                .ok_or(InnerProductArgumentError::EmptyTranscript)?,
+        )?;
+        input.absorb(ck_a_final)?;
         let mut counter_nonce: usize = 0;
         let c = loop {
-            let mut hash_input = Vec::new();
-            hash_input.extend_from_slice(&counter_nonce.to_be_bytes()[..]);
-            //TODO: Should use CanonicalSerialize instead of ToBytes
-            hash_input.extend_from_slice(&to_bytes![transcript.first().unwrap(), ck_a_final]?);
-            if let Some(c) = LMC::Scalar::from_random_bytes(&D::digest(&hash_input)) {
+            if let Some(c) = input.challenge::<LMC::Scalar>(counter_nonce) {
+                transcript_log!("tipa-ssm", None, b"kzg-point", &c, nonce = counter_nonce);
                 break c;
             };
             counter_nonce += 1;
         };
 
         // Check commitment key
-        let ck_a_valid = verify_commitment_key_g2_kzg_opening(
+        let ck_a_check = commitment_key_g2_kzg_opening_check(
             v_srs,
-            &ck_a_final,
-            &ck_a_proof,
+            ck_a_final,
+            ck_a_proof,
             &transcript_inverse,
-            &<P::Fr>::one(),
+            &<P::ScalarField>::one(),
             &c,
-        )?;
+        );
 
         // Compute final scalar
-        let mut power_2_b = scalar_b.clone();
+        let mut power_2_b = *scalar_b;
         let mut product_form = Vec::new();
         for x in transcript.iter() {
-            product_form.push(<P::Fr>::one() + &(x.inverse().unwrap() * &power_2_b));
+            product_form.push(<P::ScalarField>::one() + (x.inverse().unwrap() * power_2_b));
             power_2_b *= &power_2_b.clone();
         }
-        let b_base = product_form.iter().product::<P::Fr>();
+        let b_base = product_form.iter().product::<P::ScalarField>();
 
-        // Verify base inner product commitment
         let (com_a, _, com_t) = base_com;
-        let a_base = vec![proof.gipa_proof.r_base.0.clone()];
-        let t_base = vec![IP::inner_product(&a_base, &vec![b_base])?];
-        let base_valid = LMC::verify(&vec![ck_a_final.clone()], &a_base, &com_a)?
-            && IPC::verify(&vec![ck_t.clone()], &t_base, &com_t)?;
+        let base = TIPAWithSSMBase {
+            com: (com_a, com_t),
+            ck: *ck_a_final,
+            m: (proof.gipa_proof.r_base.0.clone(), b_base),
+            claimed_scalar: proof.gipa_proof.r_base.1,
+            _inner_product: PhantomData,
+        };
+        Ok((base, vec![ck_a_check]))
+    }
+
+    pub fn commitment_key_opening(
+        com: (&LMC::Output, &IPC::Output),
+        scalar_b: &P::ScalarField,
+        proof: &TIPAWithSSMProof<IP, LMC, IPC, P, D>,
+    ) -> Result<CommitmentKeyOpening<P::G2>, Error> {
+        Self::commitment_key_opening_with_seed(com, scalar_b, proof, &<P::ScalarField>::default())
+    }
+
+    // As `commitment_key_opening` for a proof whose first challenge was seeded with `seed`
+    pub fn commitment_key_opening_with_seed(
+        com: (&LMC::Output, &IPC::Output),
+        scalar_b: &P::ScalarField,
+        proof: &TIPAWithSSMProof<IP, LMC, IPC, P, D>,
+        seed: &P::ScalarField,
+    ) -> Result<CommitmentKeyOpening<P::G2>, Error> {
+        let (_, transcript) = GIPA::verify_recursive_challenge_transcript_with_seed(
+            (com.0, scalar_b, com.1),
+            &proof.gipa_proof,
+            seed,
+        )?;
+        let transcript_inverse = transcript
+            .iter()
+            .map(|x| x.inverse().ok_or(InnerProductArgumentError::ZeroChallenge))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // KZG challenge point
+        let mut input = D::input();
+        input.absorb_scalar(
+            transcript
+                .first()
+                .ok_or(InnerProductArgumentError::EmptyTranscript)?,
+        )?;
+        input.absorb(&proof.final_ck)?;
+        let mut counter_nonce: usize = 0;
+        let c = loop {
+            if let Some(c) = input.challenge::<LMC::Scalar>(counter_nonce) {
+                transcript_log!("tipa-ssm", None, b"kzg-point", &c, nonce = counter_nonce);
+                break c;
+            };
+            counter_nonce += 1;
+        };
 
-        Ok(ck_a_valid && base_valid)
+        Ok(CommitmentKeyOpening {
+            ck_final: proof.final_ck,
+            ck_opening: proof.final_ck_proof,
+            kzg_challenge: c,
+            eval: polynomial_evaluation_product_form_from_transcript(
+                &transcript_inverse,
+                &c,
+                &<P::ScalarField>::one(),
+            ),
+            transcript: transcript_inverse,
+            shift: <P::ScalarField>::one(),
+        })
     }
 }
 
@@ -324,11 +749,21 @@ pub fn structured_scalar_power<F: Field>(num: usize, s: &F) -> Vec<F> {
     powers
 }
 
+pub fn tensor_scalar_product<F: Field>(factors: &[(F, F)]) -> Vec<F> {
+    let mut product = vec![F::one()];
+    for (f_0, f_1) in factors.iter() {
+        let high = product.iter().map(|p| *p * f_1).collect::<Vec<F>>();
+        product.iter_mut().for_each(|p| *p *= f_0);
+        product.extend(high);
+    }
+    product
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use ark_bls12_381::Bls12_381;
-    use blake2::Blake2b;
+    use blake2::Blake2b512;
     use rand::{rngs::StdRng, SeedableRng};
 
     use ark_dh_commitments::{
@@ -338,29 +773,27 @@ mod tests {
     use ark_inner_products::{InnerProduct, MultiexponentiationInnerProduct, ScalarInnerProduct};
 
     type GC1 = AFGHOCommitmentG1<Bls12_381>;
-    type SC1 = PedersenCommitment<<Bls12_381 as PairingEngine>::G1Projective>;
+    type SC1 = PedersenCommitment<<Bls12_381 as Pairing>::G1>;
 
     const TEST_SIZE: usize = 8;
 
     #[test]
     fn tipa_ssm_multiexponentiation_inner_product_test() {
-        type IP = MultiexponentiationInnerProduct<<Bls12_381 as PairingEngine>::G1Projective>;
-        type IPC = IdentityCommitment<
-            <Bls12_381 as PairingEngine>::G1Projective,
-            <Bls12_381 as PairingEngine>::Fr,
-        >;
-        type MultiExpTIPA = TIPAWithSSM<IP, GC1, IPC, Bls12_381, Blake2b>;
+        type IP = MultiexponentiationInnerProduct<<Bls12_381 as Pairing>::G1>;
+        type IPC =
+            IdentityCommitment<<Bls12_381 as Pairing>::G1, <Bls12_381 as Pairing>::ScalarField>;
+        type MultiExpTIPA = TIPAWithSSM<IP, GC1, IPC, Bls12_381, Blake2b512>;
 
         let mut rng = StdRng::seed_from_u64(0u64);
         let (srs, ck_t) = MultiExpTIPA::setup(&mut rng, TEST_SIZE).unwrap();
         let (ck_a, _) = srs.get_commitment_keys();
         let v_srs = srs.get_verifier_key();
         let m_a = random_generators(&mut rng, TEST_SIZE);
-        let b = <<Bls12_381 as PairingEngine>::Fr>::rand(&mut rng);
+        let b = <<Bls12_381 as Pairing>::ScalarField>::rand(&mut rng);
         let m_b = structured_scalar_power(TEST_SIZE, &b);
         let com_a = GC1::commit(&ck_a, &m_a).unwrap();
         let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
-        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+        let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
 
         let proof =
             MultiExpTIPA::prove_with_structured_scalar_message(&srs, (&m_a, &m_b), (&ck_a, &ck_t))
@@ -378,22 +811,24 @@ mod tests {
 
     #[test]
     fn gipa_ssm_scalar_inner_product_test() {
-        type IP = ScalarInnerProduct<<Bls12_381 as PairingEngine>::Fr>;
-        type IPC =
-            IdentityCommitment<<Bls12_381 as PairingEngine>::Fr, <Bls12_381 as PairingEngine>::Fr>;
-        type ScalarGIPA = GIPAWithSSM<IP, SC1, IPC, Blake2b>;
+        type IP = ScalarInnerProduct<<Bls12_381 as Pairing>::ScalarField>;
+        type IPC = IdentityCommitment<
+            <Bls12_381 as Pairing>::ScalarField,
+            <Bls12_381 as Pairing>::ScalarField,
+        >;
+        type ScalarGIPA = GIPAWithSSM<IP, SC1, IPC, Blake2b512>;
 
         let mut rng = StdRng::seed_from_u64(0u64);
         let (ck_a, ck_t) = ScalarGIPA::setup(&mut rng, TEST_SIZE).unwrap();
         let mut m_a = Vec::new();
         for _ in 0..TEST_SIZE {
-            m_a.push(<Bls12_381 as PairingEngine>::Fr::rand(&mut rng));
+            m_a.push(<Bls12_381 as Pairing>::ScalarField::rand(&mut rng));
         }
-        let b = <<Bls12_381 as PairingEngine>::Fr>::rand(&mut rng);
+        let b = <<Bls12_381 as Pairing>::ScalarField>::rand(&mut rng);
         let m_b = structured_scalar_power(TEST_SIZE, &b);
         let com_a = SC1::commit(&ck_a, &m_a).unwrap();
         let t = vec![IP::inner_product(&m_a, &m_b).unwrap()];
-        let com_t = IPC::commit(&vec![ck_t.clone()], &t).unwrap();
+        let com_t = IPC::commit(ark_std::slice::from_ref(&ck_t), &t).unwrap();
 
         let proof =
             ScalarGIPA::prove_with_structured_scalar_message((&m_a, &m_b), (&ck_a, &ck_t)).unwrap();
@@ -0,0 +1,277 @@
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use ark_std::{convert::TryInto, marker::PhantomData, vec::Vec};
+use digest::Digest;
+
+use crate::Error;
+
+// Hash of the Fiat-Shamir transcripts. Every `Digest` is one, hashing the canonical encoding of the
+// values a challenge is derived from, while `SpongeDigest` absorbs scalars of its field as single
+// elements and squeezes challenges as field elements
+pub trait TranscriptDigest {
+    type Input: ChallengeInput;
+
+    // Input of a challenge with nothing absorbed yet
+    fn input() -> Self::Input;
+
+    // Digest of bytes outside of a transcript, e.g. of a verifying key
+    fn hash(bytes: &[u8]) -> Vec<u8>;
+
+    fn output_size() -> usize;
+}
+
+// Values a challenge is derived from. Challenges are derived with a nonce, which provers and
+// verifiers increment until the challenge is accepted, e.g. until it is invertible
+pub trait ChallengeInput {
+    fn absorb<T: CanonicalSerialize + ?Sized>(&mut self, value: &T) -> Result<(), Error>;
+
+    fn absorb_scalar<F: PrimeField>(&mut self, scalar: &F) -> Result<(), Error>;
+
+    // Challenge derived with `nonce`, or `None` if the output does not map into the field
+    fn challenge<F: PrimeField>(&self, nonce: usize) -> Option<F>;
+
+    // As `challenge` for the 128-bit GIPA challenges, which rescale group elements with half the
+    // doublings. Algebraic sponges squeeze a full field element instead
+    fn short_challenge<F: PrimeField>(&self, nonce: usize) -> F;
+}
+
+// Canonical encoding of the values absorbed, hashed after the nonce for each challenge
+pub struct DigestInput<D> {
+    bytes: Vec<u8>,
+    _digest: PhantomData<D>,
+}
+
+impl<D: Digest> DigestInput<D> {
+    fn digest(&self, nonce: usize) -> Vec<u8> {
+        let mut hash_input = Vec::with_capacity(self.bytes.len() + 8);
+        hash_input.extend_from_slice(&nonce.to_be_bytes()[..]);
+        hash_input.extend_from_slice(&self.bytes);
+        hash_count!(hash_input);
+        D::digest(&hash_input).to_vec()
+    }
+}
+
+impl<D: Digest> ChallengeInput for DigestInput<D> {
+    fn absorb<T: CanonicalSerialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.bytes.extend(canonical_bytes!(value)?);
+        Ok(())
+    }
+
+    fn absorb_scalar<F: PrimeField>(&mut self, scalar: &F) -> Result<(), Error> {
+        self.absorb(scalar)
+    }
+
+    fn challenge<F: PrimeField>(&self, nonce: usize) -> Option<F> {
+        F::from_random_bytes(&self.digest(nonce))
+    }
+
+    fn short_challenge<F: PrimeField>(&self, nonce: usize) -> F {
+        u128::from_be_bytes(self.digest(nonce)[0..16].try_into().unwrap()).into()
+    }
+}
+
+impl<D: Digest> TranscriptDigest for D {
+    type Input = DigestInput<D>;
+
+    fn input() -> Self::Input {
+        DigestInput {
+            bytes: Vec::new(),
+            _digest: PhantomData,
+        }
+    }
+
+    fn hash(bytes: &[u8]) -> Vec<u8> {
+        D::digest(bytes).to_vec()
+    }
+
+    fn output_size() -> usize {
+        <D as Digest>::output_size()
+    }
+}
+
+const SHARED_TRANSCRIPT_DOMAIN: &[u8] = b"ripp-shared-transcript";
+
+// Fiat-Shamir transcript shared by the sub-protocols of a composite argument, e.g. TIPP and MIPP
+// over the same statement. Each sub-protocol absorbs its statement before deriving its challenges
+// and its proof after, so the challenges of every sub-protocol bind all of the composite statement
+// absorbed before them. Provers and verifiers must run the sub-protocols in the same order
+#[derive(Clone)]
+pub struct SharedTranscript<D: TranscriptDigest> {
+    state: Vec<u8>,
+    _digest: PhantomData<D>,
+}
+
+impl<D: TranscriptDigest> SharedTranscript<D> {
+    // Transcript for the composite argument named by `label`
+    pub fn new(label: &[u8]) -> Self {
+        let mut transcript = SharedTranscript {
+            state: Vec::new(),
+            _digest: PhantomData,
+        };
+        transcript.absorb_bytes(SHARED_TRANSCRIPT_DOMAIN, label);
+        transcript
+    }
+
+    pub fn absorb<T: CanonicalSerialize + ?Sized>(
+        &mut self,
+        label: &[u8],
+        value: &T,
+    ) -> Result<(), Error> {
+        let bytes = canonical_bytes!(value)?;
+        transcript_log!("shared", None, label, value);
+        self.absorb_bytes(label, &bytes);
+        Ok(())
+    }
+
+    // Challenge from everything absorbed so far, which is itself absorbed
+    pub fn challenge<F: PrimeField>(&mut self, label: &[u8]) -> Result<F, Error> {
+        let mut input = D::input();
+        input.absorb(&self.state[..])?;
+        input.absorb(label)?;
+        let mut counter_nonce: usize = 0;
+        let c = loop {
+            if let Some(c) = input.challenge::<F>(counter_nonce) {
+                transcript_log!("shared", None, label, &c, nonce = counter_nonce);
+                break c;
+            };
+            counter_nonce += 1;
+        };
+        self.absorb(label, &c)?;
+        Ok(c)
+    }
+
+    // Labels and values are length prefixed so that distinct sequences are absorbed differently
+    fn absorb_bytes(&mut self, label: &[u8], bytes: &[u8]) {
+        let mut hash_input = self.state.clone();
+        hash_input.extend_from_slice(&(label.len() as u64).to_le_bytes());
+        hash_input.extend_from_slice(label);
+        hash_input.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        hash_input.extend_from_slice(bytes);
+        hash_count!(hash_input);
+        self.state = D::hash(&hash_input);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{
+        structured_scalar_power, InnerProduct, MultiExpIPC, MultiExpTIPA, PairingTIPA,
+    };
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+    use ark_dh_commitments::{afgho16::AFGHOCommitmentG1, DoublyHomomorphicCommitment};
+    use ark_ff::UniformRand;
+    use ark_inner_products::MultiexponentiationInnerProduct;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn shared_transcript_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = PairingTIPA::<Bls12_381, Blake2b512>::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+
+        // TIPP over (A, B) and MIPP over (C, r^i) of the same statement
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_c = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let r = Fr::rand(&mut rng);
+        let r_vec = structured_scalar_power(TEST_SIZE, &r);
+        let com_ab =
+            PairingTIPA::<Bls12_381, Blake2b512>::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t))
+                .unwrap();
+        let com_c = AFGHOCommitmentG1::<Bls12_381>::commit(&ck_a, &m_c).unwrap();
+        let agg_c = MultiExpIPC::<Bls12_381>::commit(
+            ark_std::slice::from_ref(&ck_t),
+            &[
+                MultiexponentiationInnerProduct::<G1Projective>::inner_product(&m_c, &r_vec)
+                    .unwrap(),
+            ],
+        )
+        .unwrap();
+
+        let mut transcript = SharedTranscript::<Blake2b512>::new(b"tipp-mipp");
+        let proof_ab = PairingTIPA::<Bls12_381, Blake2b512>::prove_in_transcript(
+            &srs,
+            (&m_a, &m_b),
+            (&ck_a, &ck_b, &ck_t),
+            (&com_ab.0, &com_ab.1, &com_ab.2),
+            &mut transcript,
+        )
+        .unwrap();
+        let proof_c = MultiExpTIPA::<Bls12_381, Blake2b512>::prove_in_transcript(
+            &srs,
+            (&m_c, &r_vec),
+            (&ck_a, &ck_t),
+            (&com_c, &agg_c),
+            &r,
+            &mut transcript,
+        )
+        .unwrap();
+
+        let verify = |transcript: &mut SharedTranscript<Blake2b512>| {
+            PairingTIPA::<Bls12_381, Blake2b512>::verify_in_transcript(
+                &v_srs,
+                &ck_t,
+                (&com_ab.0, &com_ab.1, &com_ab.2),
+                &proof_ab,
+                transcript,
+            )
+            .unwrap()
+                && MultiExpTIPA::<Bls12_381, Blake2b512>::verify_in_transcript(
+                    &v_srs,
+                    &ck_t,
+                    (&com_c, &agg_c),
+                    &r,
+                    &proof_c,
+                    transcript,
+                )
+                .unwrap()
+        };
+        assert!(verify(&mut SharedTranscript::new(b"tipp-mipp")));
+        assert!(!verify(&mut SharedTranscript::new(b"other")));
+
+        // Another TIPP statement changes the MIPP challenges after it
+        let other_com_ab =
+            PairingTIPA::<Bls12_381, Blake2b512>::commit((&m_c, &m_b), (&ck_a, &ck_b, &ck_t))
+                .unwrap();
+        let mut transcript = SharedTranscript::new(b"tipp-mipp");
+        assert!(!PairingTIPA::<Bls12_381, Blake2b512>::verify_in_transcript(
+            &v_srs,
+            &ck_t,
+            (&other_com_ab.0, &other_com_ab.1, &other_com_ab.2),
+            &proof_ab,
+            &mut transcript,
+        )
+        .unwrap());
+        assert!(
+            !MultiExpTIPA::<Bls12_381, Blake2b512>::verify_in_transcript(
+                &v_srs,
+                &ck_t,
+                (&com_c, &agg_c),
+                &r,
+                &proof_c,
+                &mut transcript,
+            )
+            .unwrap()
+        );
+
+        // Composed proofs do not verify on their own
+        assert!(!PairingTIPA::<Bls12_381, Blake2b512>::verify(
+            &v_srs,
+            &ck_t,
+            (&com_ab.0, &com_ab.1, &com_ab.2),
+            &proof_ab
+        )
+        .unwrap());
+    }
+}
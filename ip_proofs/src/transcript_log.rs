@@ -0,0 +1,184 @@
+use ark_serialize::CanonicalSerialize;
+use std::{
+    cell::RefCell,
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+
+// Message absorbed into or challenge derived from a Fiat-Shamir transcript, with the canonical
+// serialization of its value. Challenges carry the nonce of the digest they were taken from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptEvent {
+    pub protocol: &'static str,
+    pub round: Option<usize>,
+    pub label: String,
+    pub nonce: Option<u64>,
+    pub value: Vec<u8>,
+}
+
+impl TranscriptEvent {
+    // One line of the canonical format: protocol, round, label, nonce and the value in hex,
+    // separated by spaces with `-` for missing fields
+    pub fn to_line(&self) -> String {
+        let optional = |x: Option<u64>| x.map_or("-".into(), |x| x.to_string());
+        let mut line = format!(
+            "{} {} {} {} ",
+            self.protocol,
+            optional(self.round.map(|r| r as u64)),
+            self.label,
+            optional(self.nonce),
+        );
+        for b in &self.value {
+            write!(line, "{:02x}", b).unwrap();
+        }
+        line
+    }
+}
+
+// Transcript of the provers and verifiers run while it is installed with `with_transcript_log`,
+// in the order the messages are absorbed and the challenges derived. Logs of a prover and a
+// verifier, or of two implementations, diverge at the first message they disagree on, e.g. for
+// finding why a proof made by one system is rejected by another
+#[derive(Debug, Default)]
+pub struct TranscriptLog {
+    events: Mutex<Vec<TranscriptEvent>>,
+}
+
+impl TranscriptLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn events(&self) -> Vec<TranscriptEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    pub fn clear(&self) {
+        self.events.lock().unwrap().clear();
+    }
+
+    // Events in the canonical format, one line each
+    pub fn export(&self) -> String {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| event.to_line() + "\n")
+            .collect()
+    }
+
+    // Index of the first event differing between the logs, or of the end of the shorter one
+    pub fn first_divergence(&self, other: &Self) -> Option<usize> {
+        let (a, b) = (self.events(), other.events());
+        a.iter()
+            .zip(&b)
+            .position(|(x, y)| x != y)
+            .or_else(|| (a.len() != b.len()).then(|| a.len().min(b.len())))
+    }
+
+    fn push(&self, event: TranscriptEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+thread_local! {
+    static LOG: RefCell<Option<Arc<TranscriptLog>>> = const { RefCell::new(None) };
+}
+
+// Runs `f` with `log` recording the transcripts of the current thread, restoring the previous
+// log after
+pub fn with_transcript_log<T>(log: Arc<TranscriptLog>, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Arc<TranscriptLog>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            LOG.with(|l| *l.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = Restore(LOG.with(|l| l.borrow_mut().replace(log)));
+    f()
+}
+
+// Values are only serialized while a log is installed
+pub(crate) fn record<T: CanonicalSerialize + ?Sized>(
+    protocol: &'static str,
+    round: Option<usize>,
+    label: &[u8],
+    value: &T,
+    nonce: Option<u64>,
+) {
+    if let Some(log) = LOG.with(|l| l.borrow().clone()) {
+        let mut bytes = Vec::new();
+        if value.serialize_compressed(&mut bytes).is_ok() {
+            log.push(TranscriptEvent {
+                protocol,
+                round,
+                label: String::from_utf8_lossy(label).into_owned(),
+                nonce,
+                value: bytes,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{PairingTIPA, PairingTIPAProof};
+    use ark_bls12_381::{Bls12_381, G1Projective, G2Projective};
+    use ark_ff::UniformRand;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    type TestTIPA = PairingTIPA<Bls12_381, Blake2b512>;
+
+    #[test]
+    fn transcript_log_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = TestTIPA::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = TestTIPA::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap();
+
+        let prover = Arc::new(TranscriptLog::new());
+        let mut proof = with_transcript_log(prover.clone(), || {
+            TestTIPA::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t)).unwrap()
+        });
+        // Commitments and challenge of each round, then the KZG point
+        let rounds = TEST_SIZE.trailing_zeros() as usize;
+        assert_eq!(prover.events().len(), 3 * rounds + 1);
+        assert_eq!(prover.events()[2].round, Some(0));
+        assert!(prover.events()[2].nonce.is_some());
+        assert_eq!(prover.export().lines().count(), 3 * rounds + 1);
+
+        let verify = |proof: &PairingTIPAProof<Bls12_381, Blake2b512>| {
+            let verifier = Arc::new(TranscriptLog::new());
+            with_transcript_log(verifier.clone(), || {
+                TestTIPA::verify(&v_srs, &ck_t, (&com.0, &com.1, &com.2), proof).unwrap()
+            });
+            verifier
+        };
+        let verifier = verify(&proof);
+        assert_eq!(prover.export(), verifier.export());
+        assert_eq!(prover.first_divergence(&verifier), None);
+
+        // A proof with the commitments of the second round swapped diverges at that round
+        let steps = &mut proof.gipa_proof.r_commitment_steps;
+        let second = steps.len() - 2;
+        let (com_1, com_2) = steps[second].clone();
+        steps[second] = (com_2, com_1);
+        let divergence = prover.first_divergence(&verify(&proof)).unwrap();
+        assert_eq!(prover.events()[divergence].round, Some(1));
+        assert_eq!(prover.events()[divergence].label, "com-1");
+    }
+}
@@ -0,0 +1,271 @@
+use ark_dh_commitments::identity::{HomomorphicPlaceholderValue, IdentityOutput};
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{self, SWCurveConfig},
+    twisted_edwards::{self, TECurveConfig},
+    CurveGroup,
+};
+use ark_ff::{Field, Fp, FpConfig, One, PrimeField};
+use ark_groth16::{Proof, VerifyingKey};
+use ark_inner_products::ExtensionFieldElement;
+use ark_serialize::CanonicalDeserialize;
+use ark_std::{io::Read, ops::Deref, vec::Vec};
+
+use crate::{Error, InnerProductArgumentError};
+
+// Checks on values from untrusted sources, e.g. received over FFI or deserialized unchecked: group
+// elements are on the curve and in the prime order subgroup, field elements are reduced, and
+// proofs and SRSs are well formed. Checked deserialization already rejects invalid points, but
+// not values built in memory or structural problems such as an SRS of identity elements
+pub trait Validate {
+    fn validate(&self) -> Result<(), Error>;
+}
+
+// Value that was validated, or generated by this process and so valid by construction, which can
+// be used repeatedly without checking it again
+#[derive(Clone, Debug)]
+pub struct TrustedInput<T>(T);
+
+impl<T: Validate> TrustedInput<T> {
+    pub fn validate(value: T) -> Result<Self, Error> {
+        value.validate()?;
+        Ok(TrustedInput(value))
+    }
+}
+
+impl<T: Validate + CanonicalDeserialize> TrustedInput<T> {
+    pub fn deserialize<R: Read>(reader: R) -> Result<Self, Error> {
+        Self::validate(T::deserialize_compressed(reader)?)
+    }
+}
+
+impl<T> TrustedInput<T> {
+    // For values generated internally, e.g. by `setup` or `prove`
+    pub fn assume_valid(value: T) -> Self {
+        TrustedInput(value)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for TrustedInput<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<P: SWCurveConfig> Validate for short_weierstrass::Affine<P> {
+    fn validate(&self) -> Result<(), Error> {
+        if !self.is_on_curve() {
+            return Err(InnerProductArgumentError::NotOnCurve);
+        }
+        if !self.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(InnerProductArgumentError::NotInSubgroup);
+        }
+        Ok(())
+    }
+}
+
+impl<P: SWCurveConfig> Validate for short_weierstrass::Projective<P> {
+    fn validate(&self) -> Result<(), Error> {
+        self.into_affine().validate()
+    }
+}
+
+impl<P: TECurveConfig> Validate for twisted_edwards::Affine<P> {
+    fn validate(&self) -> Result<(), Error> {
+        if !self.is_on_curve() {
+            return Err(InnerProductArgumentError::NotOnCurve);
+        }
+        if !self.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(InnerProductArgumentError::NotInSubgroup);
+        }
+        Ok(())
+    }
+}
+
+impl<P: TECurveConfig> Validate for twisted_edwards::Projective<P> {
+    fn validate(&self) -> Result<(), Error> {
+        self.into_affine().validate()
+    }
+}
+
+impl<P: FpConfig<N>, const N: usize> Validate for Fp<P, N> {
+    fn validate(&self) -> Result<(), Error> {
+        if self.0 >= Self::MODULUS {
+            return Err(InnerProductArgumentError::NotReduced);
+        }
+        Ok(())
+    }
+}
+
+// Target group elements are of order r
+impl<P: Pairing> Validate for ExtensionFieldElement<P> {
+    fn validate(&self) -> Result<(), Error> {
+        if self.0.pow(P::ScalarField::characteristic()) != <P::TargetField>::one() {
+            return Err(InnerProductArgumentError::NotInSubgroup);
+        }
+        Ok(())
+    }
+}
+
+impl Validate for HomomorphicPlaceholderValue {
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<T: Validate + Clone + Default + Eq> Validate for IdentityOutput<T> {
+    fn validate(&self) -> Result<(), Error> {
+        self.0.validate()
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate(&self) -> Result<(), Error> {
+        self.iter().try_for_each(Validate::validate)
+    }
+}
+
+impl<A: Validate, B: Validate> Validate for (A, B) {
+    fn validate(&self) -> Result<(), Error> {
+        self.0.validate()?;
+        self.1.validate()
+    }
+}
+
+impl<A: Validate, B: Validate, C: Validate> Validate for (A, B, C) {
+    fn validate(&self) -> Result<(), Error> {
+        self.0.validate()?;
+        self.1.validate()?;
+        self.2.validate()
+    }
+}
+
+impl<P: Pairing> Validate for Proof<P>
+where
+    P::G1Affine: Validate,
+    P::G2Affine: Validate,
+{
+    fn validate(&self) -> Result<(), Error> {
+        self.a.validate()?;
+        self.b.validate()?;
+        self.c.validate()
+    }
+}
+
+impl<P: Pairing> Validate for VerifyingKey<P>
+where
+    P::G1Affine: Validate,
+    P::G2Affine: Validate,
+{
+    fn validate(&self) -> Result<(), Error> {
+        self.alpha_g1.validate()?;
+        self.beta_g2.validate()?;
+        self.gamma_g2.validate()?;
+        self.delta_g2.validate()?;
+        self.gamma_abc_g1.validate()
+    }
+}
+
+// Generators of an SRS, which must also not be the identity
+pub(crate) fn validate_generators<G: CurveGroup + Validate>(g: &[G]) -> Result<(), Error> {
+    for g in g {
+        if g.is_zero() {
+            return Err(InnerProductArgumentError::IdentityGenerator);
+        }
+        g.validate()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::PairingTIPA, tipa::VerifierSRS};
+    use ark_bls12_381::{Bls12_381, Fq, Fq12, Fr, G1Affine, G1Projective, G2Projective};
+    use ark_ec::AffineRepr;
+    use ark_ff::{UniformRand, Zero};
+    use ark_serialize::CanonicalSerialize;
+    use blake2::Blake2b512;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 8;
+
+    #[test]
+    fn validation_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let (srs, ck_t) = PairingTIPA::<Bls12_381, Blake2b512>::setup(&mut rng, TEST_SIZE).unwrap();
+        let (ck_a, ck_b) = srs.get_commitment_keys();
+        let v_srs = srs.get_verifier_key();
+        let m_a = (0..TEST_SIZE)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let m_b = (0..TEST_SIZE)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let com = PairingTIPA::<Bls12_381, Blake2b512>::commit((&m_a, &m_b), (&ck_a, &ck_b, &ck_t))
+            .unwrap();
+        let proof =
+            PairingTIPA::<Bls12_381, Blake2b512>::prove(&srs, (&m_a, &m_b), (&ck_a, &ck_b, &ck_t))
+                .unwrap();
+        assert!(proof.validate().is_ok());
+        assert!(com.validate().is_ok());
+        assert!(srs.validate().is_ok());
+
+        // Validated values are used through the marker without checking them again
+        let mut bytes = Vec::new();
+        v_srs.serialize_compressed(&mut bytes).unwrap();
+        let trusted_v_srs =
+            TrustedInput::<VerifierSRS<Bls12_381>>::deserialize(&bytes[..]).unwrap();
+        let trusted_proof = TrustedInput::validate(proof).unwrap();
+        assert!(PairingTIPA::<Bls12_381, Blake2b512>::verify(
+            &trusted_v_srs,
+            &ck_t,
+            (&com.0, &com.1, &com.2),
+            &trusted_proof
+        )
+        .unwrap());
+
+        // Coordinates off the curve, e.g. passed over FFI
+        let off_curve = G1Affine::new_unchecked(Fq::rand(&mut rng), Fq::rand(&mut rng));
+        assert!(matches!(
+            off_curve.validate(),
+            Err(InnerProductArgumentError::NotOnCurve)
+        ));
+        // Points of the curve outside the prime order subgroup
+        let outside_subgroup = loop {
+            if let Some(g) = G1Affine::get_point_from_x_unchecked(Fq::rand(&mut rng), true) {
+                if !g.is_in_correct_subgroup_assuming_on_curve() {
+                    break g;
+                }
+            }
+        };
+        assert!(matches!(
+            outside_subgroup.into_group().validate(),
+            Err(InnerProductArgumentError::NotInSubgroup)
+        ));
+        assert!(matches!(
+            ExtensionFieldElement::<Bls12_381>(Fq12::rand(&mut rng)).validate(),
+            Err(InnerProductArgumentError::NotInSubgroup)
+        ));
+        assert!(matches!(
+            Fr::new_unchecked(Fr::MODULUS).validate(),
+            Err(InnerProductArgumentError::NotReduced)
+        ));
+
+        let mut wrong_v_srs = v_srs.clone();
+        wrong_v_srs.h_alpha = G2Projective::zero();
+        assert!(matches!(
+            wrong_v_srs.validate(),
+            Err(InnerProductArgumentError::IdentityGenerator)
+        ));
+        let mut wrong_srs = srs.clone();
+        wrong_srs.g_alpha_powers[1] = outside_subgroup.into_group();
+        assert!(wrong_srs.validate().is_err());
+    }
+}
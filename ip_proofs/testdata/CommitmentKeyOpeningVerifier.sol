@@ -0,0 +1,263 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.0;
+
+// Checks the KZG openings of the final commitment keys of TIPA proofs over BN254 against a fixed
+// verifier SRS. Generated by ark-ip-proofs.
+//
+// The KZG challenge is derived here from the GIPA challenges and the final keys with keccak256,
+// matching proofs made with the Keccak256 transcript digest, and the evaluations are computed from
+// the GIPA challenges. The GIPA challenges and the SRS shift are taken as given: this contract does
+// not check the GIPA recursion of the TIPP and MIPP proofs, which must be verified off chain.
+contract CommitmentKeyOpeningVerifier {
+    uint256 constant Q = 0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47;
+    uint256 constant R = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001;
+    uint256 constant CHALLENGE_MASK = 0x3fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff;
+    uint256 constant G_X = 0x0000000000000000000000000000000000000000000000000000000000000001;
+    uint256 constant G_Y = 0x0000000000000000000000000000000000000000000000000000000000000002;
+    uint256 constant G_BETA_X = 0x2374c00cd43d3283a52a380a4ae5000ce7971d2df26d38166060ae97ee99958a;
+    uint256 constant G_BETA_Y = 0x0b76a93f0e2f95e7e7fca64255b56b6b89733651ac59f68a3f0a9e2cc4eb04a6;
+    uint256 constant H_X_1 = 0x198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2;
+    uint256 constant H_X_0 = 0x1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed;
+    uint256 constant H_Y_1 = 0x090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b;
+    uint256 constant H_Y_0 = 0x12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa;
+    uint256 constant H_ALPHA_X_1 = 0x1d52350c6e014bf3f1b90026ae0a75a9ba5fcfa50b5c5f52fece0ad24acf4e6a;
+    uint256 constant H_ALPHA_X_0 = 0x0141e0464c49c63c15dd475027f1a1cccc107ec224fad6c8ab4065da5d88f4d4;
+    uint256 constant H_ALPHA_Y_1 = 0x2683178135d539b42dd3447214eb761f45b11289f5eb5464404388eda9a0b6e8;
+    uint256 constant H_ALPHA_Y_0 = 0x19b02df8eb9ceaf16944205ceaee2ead460951e5dc9a2aca6f0e1c1f7fbc4c84;
+
+    // Flags of the compressed point encoding of the Rust verifier, in the last byte of x
+    uint256 constant Y_NEGATIVE = 1 << 255;
+    uint256 constant INFINITY = 1 << 254;
+
+    // Openings of the final keys of a TIPA proof: ckA in G2 at the inverses of the GIPA challenges
+    // with SRS shift shiftA, given as transcriptA, and ckB in G1 at the GIPA challenges transcriptB
+    function verifyTIPAOpenings(
+        uint256[4] calldata ckAFinal,
+        uint256[4] calldata ckAOpening,
+        uint256[2] calldata ckBFinal,
+        uint256[2] calldata ckBOpening,
+        uint256 shiftA,
+        uint256[] calldata transcriptA,
+        uint256[] calldata transcriptB
+    ) external view returns (bool) {
+        if (transcriptB.length == 0 || transcriptA.length != transcriptB.length) {
+            return false;
+        }
+        for (uint256 i = 0; i < transcriptB.length; i++) {
+            if (transcriptB[i] >= R || mulmod(transcriptA[i], transcriptB[i], R) != 1) {
+                return false;
+            }
+        }
+        uint256 c = kzgChallenge(
+            abi.encodePacked(scalarBytes(transcriptB[0]), g2Bytes(ckAFinal), g1Bytes(ckBFinal))
+        );
+        return
+            g2Opening(ckAFinal, ckAOpening, c, evaluate(transcriptA, c, shiftA)) &&
+            g1Opening(ckBFinal, ckBOpening, c, evaluate(transcriptB, c, 1));
+    }
+
+    // Opening of the final key in G2 of a TIPA proof with structured scalar message, at the
+    // inverses of the GIPA challenges, given as transcript
+    function verifySSMOpening(
+        uint256[4] calldata ckFinal,
+        uint256[4] calldata ckOpening,
+        uint256[] calldata transcript
+    ) external view returns (bool) {
+        if (transcript.length == 0 || transcript[0] == 0 || transcript[0] >= R) {
+            return false;
+        }
+        uint256 c = kzgChallenge(abi.encodePacked(scalarBytes(inverse(transcript[0])), g2Bytes(ckFinal)));
+        return g2Opening(ckFinal, ckOpening, c, evaluate(transcript, c, 1));
+    }
+
+    // e(ckFinal - eval g + challenge ckOpening, h) e(-ckOpening, h^alpha) = 1, for a key in G1
+    function g1Opening(
+        uint256[2] memory ckFinal,
+        uint256[2] memory ckOpening,
+        uint256 challenge,
+        uint256 eval
+    ) internal view returns (bool) {
+        uint256[2] memory lhs = ecAdd(
+            ecAdd(ckFinal, negate(ecMul([G_X, G_Y], eval))),
+            ecMul(ckOpening, challenge)
+        );
+        uint256[2] memory opening = negate(ckOpening);
+        uint256[12] memory input;
+        input[0] = lhs[0];
+        input[1] = lhs[1];
+        input[2] = H_X_1;
+        input[3] = H_X_0;
+        input[4] = H_Y_1;
+        input[5] = H_Y_0;
+        input[6] = opening[0];
+        input[7] = opening[1];
+        input[8] = H_ALPHA_X_1;
+        input[9] = H_ALPHA_X_0;
+        input[10] = H_ALPHA_Y_1;
+        input[11] = H_ALPHA_Y_0;
+        return pairing(input);
+    }
+
+    // e(g, ckFinal - eval h + challenge ckOpening) e(-g^beta, ckOpening) = 1, for a key in G2.
+    // There is no G2 precompile, so the scalars move to G1:
+    // e(g, ckFinal) e(-eval g, h) e(challenge g - g^beta, ckOpening) = 1
+    function g2Opening(
+        uint256[4] memory ckFinal,
+        uint256[4] memory ckOpening,
+        uint256 challenge,
+        uint256 eval
+    ) internal view returns (bool) {
+        uint256[2] memory evalG = negate(ecMul([G_X, G_Y], eval));
+        uint256[2] memory challengeG = ecAdd(
+            ecMul([G_X, G_Y], challenge),
+            negate([G_BETA_X, G_BETA_Y])
+        );
+        uint256[18] memory input;
+        input[0] = G_X;
+        input[1] = G_Y;
+        input[2] = ckFinal[0];
+        input[3] = ckFinal[1];
+        input[4] = ckFinal[2];
+        input[5] = ckFinal[3];
+        input[6] = evalG[0];
+        input[7] = evalG[1];
+        input[8] = H_X_1;
+        input[9] = H_X_0;
+        input[10] = H_Y_1;
+        input[11] = H_Y_0;
+        input[12] = challengeG[0];
+        input[13] = challengeG[1];
+        input[14] = ckOpening[0];
+        input[15] = ckOpening[1];
+        input[16] = ckOpening[2];
+        input[17] = ckOpening[3];
+        return pairing(input);
+    }
+
+    // Challenge from the digest of the big-endian 64-bit nonce and the input, read as a
+    // little-endian integer below the bit size of R, with the next nonce while it is not below R
+    function kzgChallenge(bytes memory input) internal pure returns (uint256 c) {
+        for (uint64 nonce = 0; ; nonce++) {
+            c = reverseBytes(uint256(keccak256(abi.encodePacked(nonce, input)))) & CHALLENGE_MASK;
+            if (c < R) {
+                return c;
+            }
+        }
+    }
+
+    // Product of 1 + transcript_i (shift z^2)^(2^i), the folded key polynomial at z
+    function evaluate(
+        uint256[] calldata transcript,
+        uint256 z,
+        uint256 shift
+    ) internal pure returns (uint256 eval) {
+        uint256 power = mulmod(mulmod(z, z, R), shift, R);
+        eval = 1;
+        for (uint256 i = 0; i < transcript.length; i++) {
+            eval = mulmod(eval, addmod(1, mulmod(transcript[i], power, R), R), R);
+            power = mulmod(power, power, R);
+        }
+    }
+
+    // Little-endian encoding of a scalar
+    function scalarBytes(uint256 s) internal pure returns (bytes32) {
+        return bytes32(reverseBytes(s));
+    }
+
+    // Compressed encoding of a point in G1: x with the sign of y, whose negation is larger for a
+    // positive y, or the infinity flag
+    function g1Bytes(uint256[2] memory p) internal pure returns (bytes32) {
+        if (p[0] == 0 && p[1] == 0) {
+            return bytes32(reverseBytes(INFINITY));
+        }
+        uint256 flag = p[1] <= (Q - p[1]) % Q ? uint256(0) : Y_NEGATIVE;
+        return bytes32(reverseBytes(p[0] | flag));
+    }
+
+    // Compressed encoding of a point in G2: the real and then the imaginary part of x, with the
+    // sign of y compared on the imaginary part first
+    function g2Bytes(uint256[4] memory p) internal pure returns (bytes memory) {
+        if (p[0] == 0 && p[1] == 0 && p[2] == 0 && p[3] == 0) {
+            return abi.encodePacked(bytes32(0), bytes32(reverseBytes(INFINITY)));
+        }
+        uint256 negY1 = (Q - p[2]) % Q;
+        bool positive = p[2] < negY1 || (p[2] == negY1 && p[3] <= (Q - p[3]) % Q);
+        return
+            abi.encodePacked(
+                bytes32(reverseBytes(p[1])),
+                bytes32(reverseBytes(p[0] | (positive ? uint256(0) : Y_NEGATIVE)))
+            );
+    }
+
+    function reverseBytes(uint256 v) internal pure returns (uint256) {
+        v =
+            ((v >> 8) & 0x00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF) |
+            ((v & 0x00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF00FF) << 8);
+        v =
+            ((v >> 16) & 0x0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF) |
+            ((v & 0x0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF0000FFFF) << 16);
+        v =
+            ((v >> 32) & 0x00000000FFFFFFFF00000000FFFFFFFF00000000FFFFFFFF00000000FFFFFFFF) |
+            ((v & 0x00000000FFFFFFFF00000000FFFFFFFF00000000FFFFFFFF00000000FFFFFFFF) << 32);
+        v =
+            ((v >> 64) & 0x0000000000000000FFFFFFFFFFFFFFFF0000000000000000FFFFFFFFFFFFFFFF) |
+            ((v & 0x0000000000000000FFFFFFFFFFFFFFFF0000000000000000FFFFFFFFFFFFFFFF) << 64);
+        return (v >> 128) | (v << 128);
+    }
+
+    function inverse(uint256 s) internal view returns (uint256) {
+        uint256[6] memory input = [uint256(32), 32, 32, s, R - 2, R];
+        uint256[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x05, input, 0xc0, out, 0x20)
+        }
+        require(success, "modexp failed");
+        return out[0];
+    }
+
+    function negate(uint256[2] memory p) internal pure returns (uint256[2] memory) {
+        return [p[0], (Q - p[1]) % Q];
+    }
+    function ecAdd(uint256[2] memory p, uint256[2] memory q)
+        internal
+        view
+        returns (uint256[2] memory r)
+    {
+        uint256[4] memory input = [p[0], p[1], q[0], q[1]];
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x06, input, 0x80, r, 0x40)
+        }
+        require(success, "ecAdd failed");
+    }
+
+    function ecMul(uint256[2] memory p, uint256 s) internal view returns (uint256[2] memory r) {
+        uint256[3] memory input = [p[0], p[1], s];
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x07, input, 0x60, r, 0x40)
+        }
+        require(success, "ecMul failed");
+    }
+
+    function pairing(uint256[12] memory input) internal view returns (bool) {
+        uint256[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x08, input, 0x180, out, 0x20)
+        }
+        require(success, "ecPairing failed");
+        return out[0] == 1;
+    }
+
+    function pairing(uint256[18] memory input) internal view returns (bool) {
+        uint256[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x08, input, 0x240, out, 0x20)
+        }
+        require(success, "ecPairing failed");
+        return out[0] == 1;
+    }
+}
@@ -0,0 +1,79 @@
+use ark_bls12_377::*;
+use ark_ec::CurveGroup;
+use ark_ff::UniformRand;
+use ark_sipp::{rng::FiatShamirRng, SIPP};
+use blake2::Blake2s256;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use csv::Writer;
+
+use std::{env, path::PathBuf};
+
+type ExampleSIPP = SIPP<Bls12_377, Blake2s256>;
+
+// Logarithms of the vector lengths to benchmark, from RIPP_BENCH_LOG_SIZES as e.g. "8,12,16"
+fn log_sizes() -> Vec<usize> {
+    match env::var("RIPP_BENCH_LOG_SIZES") {
+        Ok(sizes) => sizes
+            .split(',')
+            .map(|s| {
+                s.trim()
+                    .parse()
+                    .expect("RIPP_BENCH_LOG_SIZES should be a comma separated list of integers")
+            })
+            .collect(),
+        Err(_) => (8..=20).step_by(4).collect(),
+    }
+}
+
+fn bench_sipp(c: &mut Criterion) {
+    let mut rng = FiatShamirRng::<Blake2s256>::from_seed(b"falafel");
+    let mut group = c.benchmark_group("sipp");
+
+    // Proof sizes in bytes, written as CSV next to the JSON estimates criterion writes for timings
+    let dir = env::var("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../target"))
+        .join("criterion");
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut sizes = Writer::from_path(dir.join("sipp_proof_sizes.csv")).unwrap();
+    sizes
+        .write_record(["protocol", "log_size", "proof_bytes"])
+        .unwrap();
+
+    for log_size in log_sizes() {
+        let m = 1 << log_size;
+        let a_s = (0..m)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let b_s = (0..m)
+            .map(|_| G2Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let a_s = CurveGroup::normalize_batch(&a_s);
+        let b_s = CurveGroup::normalize_batch(&b_s);
+        let r_s = (0..m).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let z = ark_sipp::product_of_pairings_with_coeffs::<Bls12_377>(&a_s, &b_s, &r_s);
+
+        let prove = || ExampleSIPP::prove(&a_s, &b_s, &r_s, z).unwrap();
+        group.bench_function(BenchmarkId::new("prove", m), |b| b.iter(prove));
+        let proof = prove();
+        sizes
+            .write_record(&[
+                "sipp".to_string(),
+                log_size.to_string(),
+                proof.size_in_bytes().to_string(),
+            ])
+            .unwrap();
+        sizes.flush().unwrap();
+        group.bench_function(BenchmarkId::new("verify", m), |b| {
+            b.iter(|| assert!(ExampleSIPP::verify(&a_s, &b_s, &r_s, z, &proof).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = sipp;
+    config = Criterion::default().sample_size(10);
+    targets = bench_sipp
+}
+criterion_main!(sipp);
@@ -1,13 +1,13 @@
 // For benchmarking
 use ark_bls12_377::*;
-use ark_ec::ProjectiveCurve;
+use ark_ec::{CurveGroup, Group};
 use ark_ff::UniformRand;
 use ark_sipp::{rng::FiatShamirRng, SIPP};
-use blake2::Blake2s;
+use blake2::Blake2s256;
 use rand::seq::SliceRandom;
 use std::time::Instant;
 
-type ExampleSIPP = SIPP<Bls12_377, Blake2s>;
+type ExampleSIPP = SIPP<Bls12_377, Blake2s256>;
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
@@ -23,13 +23,15 @@ fn main() {
     if args.len() < 2 || args[1] == "-h" || args[1] == "--help" {
         println!("\nHelp: Invoke this as <program> <log_min_num_inputs> <log_max_num_inputs> <path_to_output_dir>\n");
     }
-    let min_num_inputs: usize = String::from(args[1].clone())
+    let min_num_inputs: usize = args[1]
+        .clone()
         .parse()
         .expect("<log_min_num_constraints> should be integer");
-    let max_num_inputs: usize = String::from(args[2].clone())
+    let max_num_inputs: usize = args[2]
+        .clone()
         .parse()
         .expect("<log_max_num_constraints> should be integer");
-    let output_directory = String::from(args[3].clone());
+    let output_directory = args[3].clone();
 
     let num_threads: usize =
         std::env::var("RAYON_NUM_THREADS").map_or(rayon::current_num_threads(), |n| {
@@ -37,7 +39,7 @@ fn main() {
                 .expect("The environment variable `RAYON_NUM_THREADS` must be an integer")
         });
 
-    let mut rng = FiatShamirRng::<Blake2s>::from_seed(b"falafel");
+    let mut rng = FiatShamirRng::<Blake2s256>::from_seed(b"falafel");
     let g = G1Projective::rand(&mut rng);
     let h = G2Projective::rand(&mut rng);
     let mut a_s = Vec::new();
@@ -46,8 +48,8 @@ fn main() {
         a_s.push(g.double());
         b_s.push(h.double());
     }
-    let mut a_s = ProjectiveCurve::batch_normalization_into_affine(&a_s);
-    let mut b_s = ProjectiveCurve::batch_normalization_into_affine(&b_s);
+    let mut a_s = CurveGroup::normalize_batch(&a_s);
+    let mut b_s = CurveGroup::normalize_batch(&b_s);
     let r_s = vec![Fr::rand(&mut rng); a_s.len()];
 
     let output_file = output_directory + &format!("/ipp-{}-threads.csv", num_threads);
@@ -74,11 +76,11 @@ fn main() {
             direct_time += (start.elapsed().as_millis() as f64) / 1_000.0;
 
             let start = Instant::now();
-            let proof = ExampleSIPP::prove(a_s, b_s, &r_s, z.clone()).unwrap();
+            let proof = ExampleSIPP::prove(a_s, b_s, &r_s, z).unwrap();
             prover_time += (start.elapsed().as_millis() as f64) / 1_000.0;
 
             let start = Instant::now();
-            assert!(ExampleSIPP::verify(a_s, b_s, &r_s, z.clone(), &proof).unwrap());
+            assert!(ExampleSIPP::verify(a_s, b_s, &r_s, z, &proof).unwrap());
             verifier_time += (start.elapsed().as_millis() as f64) / 1_000.0;
         }
         let num_iters = num_iters as f64;
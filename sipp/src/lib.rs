@@ -1,10 +1,17 @@
 //! A crate for inner pairing product arguments/proofs.
 #![deny(warnings, unused, missing_docs)]
 #![forbid(unsafe_code)]
+#![allow(clippy::result_unit_err, clippy::type_complexity)]
 
-use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
-use ark_ff::{to_bytes, Field, One, PrimeField, UniformRand};
+use ark_ec::{
+    pairing::{MillerLoopOutput, Pairing},
+    CurveGroup, VariableBaseMSM,
+};
+use ark_ff::{Field, One, PrimeField, UniformRand};
+use ark_ip_proofs::tipa::{PairingCheck, PairingCheckBatch};
+use ark_serialize::CanonicalSerialize;
 use digest::Digest;
+use rand_core::{CryptoRng, RngCore};
 use rayon::prelude::*;
 use std::marker::PhantomData;
 
@@ -15,24 +22,31 @@ use rng::FiatShamirRng;
 
 /// SIPP is a inner-pairing product proof that allows a verifier to check an
 /// inner-pairing product over `n` elements with only a single pairing.
-pub struct SIPP<E: PairingEngine, D: Digest> {
+pub struct SIPP<E: Pairing, D: Digest> {
     _engine: PhantomData<E>,
     _digest: PhantomData<D>,
 }
 
 /// `Proof` contains the GT elements produced by the prover.
 // TODO(psi): why not just make Proof an alias since there's only one field?
-pub struct Proof<E: PairingEngine> {
-    gt_elems: Vec<(E::Fqk, E::Fqk)>,
+pub struct Proof<E: Pairing> {
+    gt_elems: Vec<(E::TargetField, E::TargetField)>,
 }
 
-impl<E: PairingEngine, D: Digest> SIPP<E, D> {
+impl<E: Pairing> Proof<E> {
+    /// Size of the proof in bytes, as the byte encodings of its GT elements.
+    pub fn size_in_bytes(&self) -> usize {
+        2 * self.gt_elems.len() * E::TargetField::one().compressed_size()
+    }
+}
+
+impl<E: Pairing, D: Digest> SIPP<E, D> {
     /// Produce a proof of the inner pairing product.
     pub fn prove(
         a: &[E::G1Affine],
         b: &[E::G2Affine],
-        r: &[E::Fr],
-        value: E::Fqk,
+        r: &[E::ScalarField],
+        value: E::TargetField,
     ) -> Result<Proof<E>, ()> {
         assert_eq!(a.len(), b.len());
         // Ensure the order of the input vectors is a power of 2
@@ -42,13 +56,13 @@ impl<E: PairingEngine, D: Digest> SIPP<E, D> {
         assert_eq!(length.count_ones(), 1);
         let mut proof_vec = Vec::new();
         // TODO(psi): should we also input a succinct bilinear group description to the rng?
-        let mut rng = FiatShamirRng::<D>::from_seed(&to_bytes![a, b, r, value].unwrap());
+        let mut rng = FiatShamirRng::<D>::from_seed(&(a, b, r, value));
         let a = a
             .into_par_iter()
             .zip(r)
-            .map(|(a, r)| a.mul(*r))
+            .map(|(a, r)| *a * r)
             .collect::<Vec<_>>();
-        let mut a = E::G1Projective::batch_normalization_into_affine(&a);
+        let mut a = E::G1::normalize_batch(&a);
         let mut b = b.to_vec();
 
         while length != 1 {
@@ -62,31 +76,31 @@ impl<E: PairingEngine, D: Digest> SIPP<E, D> {
             let z_l = product_of_pairings::<E>(a_r, b_l);
             let z_r = product_of_pairings::<E>(a_l, b_r);
             proof_vec.push((z_l, z_r));
-            rng.absorb(&to_bytes![z_l, z_r].unwrap());
-            let x: E::Fr = u128::rand(&mut rng).into();
+            rng.absorb(&(z_l, z_r));
+            let x: E::ScalarField = u128::rand(&mut rng).into();
 
             let a_proj = a_l
                 .par_iter()
                 .zip(a_r)
                 .map(|(a_l, a_r)| {
-                    let mut temp = a_r.mul(x);
-                    temp.add_assign_mixed(a_l);
+                    let mut temp = *a_r * x;
+                    temp += a_l;
                     temp
                 })
                 .collect::<Vec<_>>();
-            a = E::G1Projective::batch_normalization_into_affine(&a_proj);
+            a = E::G1::normalize_batch(&a_proj);
 
             let x_inv = x.inverse().unwrap();
             let b_proj = b_l
                 .par_iter()
                 .zip(b_r)
                 .map(|(b_l, b_r)| {
-                    let mut temp = b_r.mul(x_inv);
-                    temp.add_assign_mixed(b_l);
+                    let mut temp = *b_r * x_inv;
+                    temp += b_l;
                     temp
                 })
                 .collect::<Vec<_>>();
-            b = E::G2Projective::batch_normalization_into_affine(&b_proj);
+            b = E::G2::normalize_batch(&b_proj);
         }
 
         Ok(Proof {
@@ -98,10 +112,40 @@ impl<E: PairingEngine, D: Digest> SIPP<E, D> {
     pub fn verify(
         a: &[E::G1Affine],
         b: &[E::G2Affine],
-        r: &[E::Fr],
-        claimed_value: E::Fqk,
+        r: &[E::ScalarField],
+        claimed_value: E::TargetField,
         proof: &Proof<E>,
     ) -> Result<bool, ()> {
+        Ok(Self::verify_deferred(a, b, r, claimed_value, proof)?.verify())
+    }
+
+    /// Verify several inner-pairing-product proofs, with their final pairings
+    /// checked in one batch.
+    pub fn verify_batch<R: RngCore + CryptoRng>(
+        instances: &[(
+            &[E::G1Affine],
+            &[E::G2Affine],
+            &[E::ScalarField],
+            E::TargetField,
+            &Proof<E>,
+        )],
+        rng: &mut R,
+    ) -> Result<bool, ()> {
+        let mut batch = PairingCheckBatch::new();
+        for (a, b, r, claimed_value, proof) in instances {
+            batch.add(Self::verify_deferred(a, b, r, *claimed_value, proof)?);
+        }
+        Ok(batch.verify(rng))
+    }
+
+    /// Run the verifier up to its final pairing, which is returned unevaluated.
+    pub fn verify_deferred(
+        a: &[E::G1Affine],
+        b: &[E::G2Affine],
+        r: &[E::ScalarField],
+        claimed_value: E::TargetField,
+        proof: &Proof<E>,
+    ) -> Result<PairingCheck<E>, ()> {
         // Ensure the order of the input vectors is a power of 2
         let length = a.len();
         assert_eq!(length.count_ones(), 1);
@@ -112,14 +156,14 @@ impl<E: PairingEngine, D: Digest> SIPP<E, D> {
         assert_eq!(proof_len as f32, f32::log2(length as f32));
 
         // TODO(psi): should we also input a succinct bilinear group description to the rng?
-        let mut rng = FiatShamirRng::<D>::from_seed(&to_bytes![a, b, r, claimed_value].unwrap());
+        let mut rng = FiatShamirRng::<D>::from_seed(&(a, b, r, claimed_value));
 
         let x_s = proof
             .gt_elems
             .iter()
             .map(|(z_l, z_r)| {
-                rng.absorb(&to_bytes![z_l, z_r].unwrap());
-                let x: E::Fr = u128::rand(&mut rng).into();
+                rng.absorb(&(*z_l, *z_r));
+                let x: E::ScalarField = u128::rand(&mut rng).into();
                 x
             })
             .collect::<Vec<_>>();
@@ -128,18 +172,18 @@ impl<E: PairingEngine, D: Digest> SIPP<E, D> {
         ark_ff::batch_inversion(&mut x_invs);
 
         let z_prime = claimed_value
-            * &proof
+            * proof
                 .gt_elems
                 .par_iter()
                 .zip(&x_s)
                 .zip(&x_invs)
                 .map(|(((z_l, z_r), x), x_inv)| {
-                    z_l.pow(x.into_repr()) * &z_r.pow(x_inv.into_repr())
+                    z_l.pow(x.into_bigint()) * z_r.pow(x_inv.into_bigint())
                 })
-                .reduce(|| E::Fqk::one(), |a, b| a * &b);
+                .reduce(E::TargetField::one, |a, b| a * b);
 
-        let mut s: Vec<E::Fr> = vec![E::Fr::one(); length];
-        let mut s_invs: Vec<E::Fr> = vec![E::Fr::one(); length];
+        let mut s: Vec<E::ScalarField> = vec![E::ScalarField::one(); length];
+        let mut s_invs: Vec<E::ScalarField> = vec![E::ScalarField::one(); length];
         // TODO(psi): batch verify
         for (j, (x, x_inv)) in x_s.into_iter().zip(x_invs).enumerate() {
             for i in 0..length {
@@ -153,68 +197,65 @@ impl<E: PairingEngine, D: Digest> SIPP<E, D> {
         let s = s
             .into_iter()
             .zip(r)
-            .map(|(x, r)| (x * r).into_repr())
+            .map(|(x, r)| (x * r).into_bigint())
             .collect::<Vec<_>>();
         let s_invs = s_invs
             .iter()
-            .map(|x_inv| x_inv.into_repr())
+            .map(|x_inv| x_inv.into_bigint())
             .collect::<Vec<_>>();
 
-        let a_prime = VariableBaseMSM::multi_scalar_mul(&a, &s);
-        let b_prime = VariableBaseMSM::multi_scalar_mul(&b, &s_invs);
-
-        let accept = E::pairing(a_prime, b_prime) == z_prime;
+        let a_prime = E::G1::msm_bigint(a, &s);
+        let b_prime = E::G2::msm_bigint(b, &s_invs);
 
-        Ok(accept)
+        Ok(PairingCheck {
+            pairs: vec![(a_prime, b_prime)],
+            target: z_prime,
+        })
     }
 }
 
 /// Compute the product of pairings of `r_i * a_i` and `b_i`.
-pub fn product_of_pairings_with_coeffs<E: PairingEngine>(
+pub fn product_of_pairings_with_coeffs<E: Pairing>(
     a: &[E::G1Affine],
     b: &[E::G2Affine],
-    r: &[E::Fr],
-) -> E::Fqk {
+    r: &[E::ScalarField],
+) -> E::TargetField {
     let a = a
         .into_par_iter()
         .zip(r)
-        .map(|(a, r)| a.mul(*r))
+        .map(|(a, r)| *a * r)
         .collect::<Vec<_>>();
-    let a = E::G1Projective::batch_normalization_into_affine(&a);
-    let elements = a
-        .par_iter()
-        .zip(b)
-        .map(|(a, b)| (E::G1Prepared::from(*a), E::G2Prepared::from(*b)))
-        .collect::<Vec<_>>();
-    let num_chunks = elements.len() / rayon::current_num_threads();
-    let num_chunks = if num_chunks == 0 {
-        elements.len()
-    } else {
-        num_chunks
-    };
-    let ml_result = elements
+    let a = E::G1::normalize_batch(&a);
+    let num_chunks = a.len() / rayon::current_num_threads();
+    let num_chunks = if num_chunks == 0 { a.len() } else { num_chunks };
+    let ml_result = a
         .par_chunks(num_chunks)
-        .map(E::miller_loop)
+        .zip(b.par_chunks(num_chunks))
+        .map(|(a, b)| E::multi_miller_loop(a.iter().copied(), b.iter().copied()).0)
         .product();
-    E::final_exponentiation(&ml_result).unwrap()
+    E::final_exponentiation(MillerLoopOutput(ml_result))
+        .unwrap()
+        .0
 }
 
 /// Compute the product of pairings of `a` and `b`.
 #[must_use]
-pub fn product_of_pairings<E: PairingEngine>(a: &[E::G1Affine], b: &[E::G2Affine]) -> E::Fqk {
-    let r = vec![E::Fr::one(); a.len()];
+pub fn product_of_pairings<E: Pairing>(a: &[E::G1Affine], b: &[E::G2Affine]) -> E::TargetField {
+    let r = vec![E::ScalarField::one(); a.len()];
     product_of_pairings_with_coeffs::<E>(a, b, &r)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ark_bls12_377::{Bls12_377, Fr, G1Projective, G2Projective};
-    use blake2::Blake2s;
+    use ark_bls12_377::{Bls12_377, Fq, Fr, G1Projective, G2Projective};
+    use blake2::Blake2s256;
+    use num_bigint::BigUint;
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn prove_and_verify_base_case() {
-        let mut rng = FiatShamirRng::<Blake2s>::from_seed(&to_bytes![b"falafel"].unwrap());
+        let mut rng = FiatShamirRng::<Blake2s256>::from_seed(b"falafel");
         let mut a = Vec::with_capacity(32);
         let mut b = Vec::with_capacity(32);
         let mut r = Vec::with_capacity(32);
@@ -226,12 +267,103 @@ mod tests {
 
         let z = product_of_pairings_with_coeffs::<Bls12_377>(&a, &b, &r);
 
-        let proof = SIPP::<Bls12_377, Blake2s>::prove(&a, &b, &r, z);
+        let proof = SIPP::<Bls12_377, Blake2s256>::prove(&a, &b, &r, z);
         assert!(proof.is_ok());
         let proof = proof.unwrap();
 
-        let accept = SIPP::<Bls12_377, Blake2s>::verify(&a, &b, &r, z, &proof);
+        let accept = SIPP::<Bls12_377, Blake2s256>::verify(&a, &b, &r, z, &proof);
         assert!(accept.is_ok());
         assert!(accept.unwrap());
     }
+
+    #[test]
+    fn prove_and_verify_batch() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let mut instances = Vec::new();
+        for _ in 0..3 {
+            let a = (0..32)
+                .map(|_| G1Projective::rand(&mut rng).into_affine())
+                .collect::<Vec<_>>();
+            let b = (0..32)
+                .map(|_| G2Projective::rand(&mut rng).into_affine())
+                .collect::<Vec<_>>();
+            let r = (0..32).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+            let z = product_of_pairings_with_coeffs::<Bls12_377>(&a, &b, &r);
+            let proof = SIPP::<Bls12_377, Blake2s256>::prove(&a, &b, &r, z).unwrap();
+            instances.push((a, b, r, z, proof));
+        }
+
+        let mut batch = instances
+            .iter()
+            .map(|(a, b, r, z, proof)| (&a[..], &b[..], &r[..], *z, proof))
+            .collect::<Vec<_>>();
+        assert!(SIPP::<Bls12_377, Blake2s256>::verify_batch(&batch, &mut rng).unwrap());
+
+        batch[1].3 = batch[0].3;
+        assert!(!SIPP::<Bls12_377, Blake2s256>::verify_batch(&batch, &mut rng).unwrap());
+    }
+
+    // Rng of constant output, giving the batch chosen coefficients
+    struct ConstantRng(u64);
+
+    impl RngCore for ConstantRng {
+        fn next_u32(&mut self) -> u32 {
+            self.0 as u32
+        }
+        fn next_u64(&mut self) -> u64 {
+            self.0
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dest)
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for ConstantRng {}
+
+    #[test]
+    fn verify_batch_rejects_claims_outside_gt() {
+        type Fq12 = <Bls12_377 as Pairing>::TargetField;
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let a = (0..8)
+            .map(|_| G1Projective::rand(&mut rng).into_affine())
+            .collect::<Vec<_>>();
+        let b = (0..8)
+            .map(|_| G2Projective::rand(&mut rng).into_affine())
+            .collect::<Vec<_>>();
+        let r = (0..8).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let z = product_of_pairings_with_coeffs::<Bls12_377>(&a, &b, &r);
+        let proof = SIPP::<Bls12_377, Blake2s256>::prove(&a, &b, &r, z).unwrap();
+
+        // Element of order 73, which divides the order of the cyclotomic subgroup but not r. A
+        // proof for z times it fails only by this factor, which vanishes from a batch whose
+        // coefficients are multiples of 73
+        let p = BigUint::from(Fq::MODULUS);
+        let exponent = (p.pow(12) - 1u32) / 73u32;
+        let torsion = loop {
+            let w = Fq12::rand(&mut rng).pow(exponent.to_u64_digits());
+            if !w.is_one() {
+                break w;
+            }
+        };
+        for claimed_value in [z * torsion, Fq12::rand(&mut rng)] {
+            let bad_proof =
+                SIPP::<Bls12_377, Blake2s256>::prove(&a, &b, &r, claimed_value).unwrap();
+            assert!(
+                !SIPP::<Bls12_377, Blake2s256>::verify(&a, &b, &r, claimed_value, &bad_proof)
+                    .unwrap()
+            );
+            let batch = [
+                (&a[..], &b[..], &r[..], z, &proof),
+                (&a[..], &b[..], &r[..], claimed_value, &bad_proof),
+            ];
+            assert!(
+                !SIPP::<Bls12_377, Blake2s256>::verify_batch(&batch, &mut ConstantRng(73)).unwrap()
+            );
+            assert!(!SIPP::<Bls12_377, Blake2s256>::verify_batch(&batch, &mut rng).unwrap());
+        }
+    }
 }
@@ -1,15 +1,15 @@
-use ark_ff::{FromBytes, ToBytes};
-use digest::{generic_array::GenericArray, Digest};
+use ark_serialize::CanonicalSerialize;
+use digest::{Digest, Output};
 use rand_chacha::ChaChaRng;
 use rand_core::{RngCore, SeedableRng};
-use std::marker::PhantomData;
+use std::{convert::TryInto, marker::PhantomData};
 
 /// A `SeedableRng` that refreshes its seed by hashing together the previous seed
 /// and the new seed material.
 // TODO: later: re-evaluate decision about ChaChaRng
 pub struct FiatShamirRng<D: Digest> {
     r: ChaChaRng,
-    seed: GenericArray<u8, D::OutputSize>,
+    seed: Output<D>,
     #[doc(hidden)]
     digest: PhantomData<D>,
 }
@@ -32,7 +32,8 @@ impl<D: Digest> RngCore for FiatShamirRng<D> {
 
     #[inline]
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
-        Ok(self.r.fill_bytes(dest))
+        self.r.fill_bytes(dest);
+        Ok(())
     }
 }
 
@@ -40,11 +41,12 @@ impl<D: Digest> FiatShamirRng<D> {
     /// Create a new `Self` by initialzing with a fresh seed.
     /// `self.seed = H(self.seed || new_seed)`.
     #[inline]
-    pub fn from_seed<'a, T: 'a + ToBytes>(seed: &'a T) -> Self {
+    pub fn from_seed<'a, T: 'a + CanonicalSerialize + ?Sized>(seed: &'a T) -> Self {
         let mut bytes = Vec::new();
-        seed.write(&mut bytes).expect("failed to convert to bytes");
+        seed.serialize_compressed(&mut bytes)
+            .expect("failed to convert to bytes");
         let seed = D::digest(&bytes);
-        let r_seed: [u8; 32] = FromBytes::read(seed.as_ref()).expect("failed to get [u32; 8]");
+        let r_seed: [u8; 32] = seed[..32].try_into().expect("failed to get [u32; 8]");
         let r = ChaChaRng::from_seed(r_seed);
         Self {
             r,
@@ -56,12 +58,13 @@ impl<D: Digest> FiatShamirRng<D> {
     /// Refresh `self.seed` with new material. Achieved by setting
     /// `self.seed = H(self.seed || new_seed)`.
     #[inline]
-    pub fn absorb<'a, T: 'a + ToBytes>(&mut self, seed: &'a T) {
+    pub fn absorb<'a, T: 'a + CanonicalSerialize + ?Sized>(&mut self, seed: &'a T) {
         let mut bytes = Vec::new();
-        seed.write(&mut bytes).expect("failed to convert to bytes");
+        seed.serialize_compressed(&mut bytes)
+            .expect("failed to convert to bytes");
         bytes.extend_from_slice(&self.seed);
         self.seed = D::digest(&bytes);
-        let seed: [u8; 32] = FromBytes::read(self.seed.as_ref()).expect("failed to get [u32; 8]");
+        let seed: [u8; 32] = self.seed[..32].try_into().expect("failed to get [u32; 8]");
         self.r = ChaChaRng::from_seed(seed);
     }
 }
@@ -0,0 +1,123 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::VerifyingKey;
+use ark_ip_proofs::{
+    applications::groth16_aggregation::{verify_aggregate_proof, AggregateProof},
+    tipa::VerifierSRS,
+    Error,
+};
+use ark_serialize::CanonicalDeserialize;
+use blake2::Blake2b512;
+use wasm_bindgen::prelude::*;
+
+// Verifies an aggregate of Groth16 proofs over BLS12-381, as produced by `ripp aggregate`. All
+// arguments use the canonical compressed serialization of the corresponding arkworks types, with
+// the public inputs of every aggregated proof given as a serialized vector of vectors in the order
+// the proofs were aggregated. Malformed arguments throw in JavaScript.
+#[wasm_bindgen]
+pub fn verify_aggregate(
+    proof: &[u8],
+    vk: &[u8],
+    srs: &[u8],
+    inputs: &[u8],
+) -> Result<bool, JsValue> {
+    verify_aggregate_bytes(proof, vk, srs, inputs).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+pub fn verify_aggregate_bytes(
+    proof: &[u8],
+    vk: &[u8],
+    srs: &[u8],
+    inputs: &[u8],
+) -> Result<bool, Error> {
+    let proof = AggregateProof::<Bls12_381, Blake2b512>::deserialize_compressed(proof)?;
+    let vk = VerifyingKey::<Bls12_381>::deserialize_compressed(vk)?;
+    let v_srs = VerifierSRS::<Bls12_381>::deserialize_compressed(srs)?;
+    let inputs = Vec::<Vec<Fr>>::deserialize_compressed(inputs)?;
+    verify_aggregate_proof(&v_srs, &vk, &inputs, &proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+    use ark_groth16::Groth16;
+    use ark_ip_proofs::applications::groth16_aggregation::{aggregate_proofs, setup_inner_product};
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+    };
+    use ark_serialize::CanonicalSerialize;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TEST_SIZE: usize = 4;
+
+    fn bytes<T: CanonicalSerialize>(t: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        t.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    // Proves knowledge of a witness equal to the public input
+    struct TestCircuit(Fr);
+
+    impl ConstraintSynthesizer<Fr> for TestCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let input = cs.new_input_variable(|| Ok(self.0))?;
+            let witness = cs.new_witness_variable(|| Ok(self.0))?;
+            cs.enforce_constraint(lc!() + witness, lc!() + Variable::One, lc!() + input)
+        }
+    }
+
+    #[test]
+    fn verify_aggregate_test() {
+        let mut rng = StdRng::seed_from_u64(0u64);
+        let srs = setup_inner_product::<Bls12_381, Blake2b512, _>(&mut rng, TEST_SIZE).unwrap();
+        let parameters = Groth16::<Bls12_381>::generate_random_parameters_with_reduction(
+            TestCircuit(Fr::default()),
+            &mut rng,
+        )
+        .unwrap();
+        let inputs = (0..TEST_SIZE)
+            .map(|_| vec![Fr::rand(&mut rng)])
+            .collect::<Vec<_>>();
+        let proofs = inputs
+            .iter()
+            .map(|x| {
+                Groth16::<Bls12_381>::create_random_proof_with_reduction(
+                    TestCircuit(x[0]),
+                    &parameters,
+                    &mut rng,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let aggregate_proof =
+            aggregate_proofs::<Bls12_381, Blake2b512>(&srs, &parameters.vk, &proofs).unwrap();
+
+        let proof = bytes(&aggregate_proof);
+        let (vk, v_srs) = (bytes(&parameters.vk), bytes(&srs.get_verifier_key()));
+        let inputs = bytes(&inputs);
+        // Errors become JavaScript exceptions, which only exist on wasm targets, so natively the
+        // bindings are only called on valid arguments
+        assert!(verify_aggregate(&proof, &vk, &v_srs, &inputs).unwrap());
+        assert!(verify_aggregate_bytes(&proof, &vk, &v_srs, &inputs).unwrap());
+
+        // Flipped bits fail to deserialize or to verify, and never panic
+        for i in (0..proof.len()).step_by(proof.len() / 64) {
+            let mut flipped = proof.clone();
+            flipped[i] ^= 1;
+            assert!(
+                !matches!(
+                    verify_aggregate_bytes(&flipped, &vk, &v_srs, &inputs),
+                    Ok(true)
+                ),
+                "bit flipped in byte {}",
+                i
+            );
+        }
+        for len in [0, 1, proof.len() / 2, proof.len() - 1] {
+            assert!(verify_aggregate_bytes(&proof[..len], &vk, &v_srs, &inputs).is_err());
+        }
+        assert!(verify_aggregate_bytes(&proof, &vk, &v_srs, &inputs[..inputs.len() - 1]).is_err());
+    }
+}